@@ -1,18 +1,175 @@
-pub struct Keyframe;
+use crate::{
+    animation::{self, Animation, Effect, EffectTiming, Source},
+    domitem::DOMItem,
+    AsElement, AsNode, DOMException, Document, Element,
+};
+
+/// A keyframe of an animation: the values that properties have at a point of its iterations.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Web_Animations_API/Keyframe_Formats)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Keyframe {
+    /// Where the keyframe is in an iteration, from zero to one. Keyframes without an offset are spaced evenly between the ones around them, with the first at zero and the last at one.
+    pub offset: Option<f64>,
+    /// The easing function from this keyframe to the next one, `linear` if `None`.
+    pub easing: Option<String>,
+    /// The CSS properties and their values, like `("margin-left", "10px")`. Shorthands are expanded, and properties that are not supported are left out.
+    pub properties: Vec<(String, String)>,
+}
+
+impl Keyframe {
+    /// Creates a keyframe with properties and their values, and no offset or easing.
+    pub fn new<N: Into<String>, V: Into<String>>(
+        properties: impl IntoIterator<Item = (N, V)>,
+    ) -> Self {
+        Keyframe {
+            properties: properties
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The options of [`Animatable::animate`]: either the duration of the animation in milliseconds, or its full options.
+#[derive(Debug, Clone, PartialEq)]
 pub enum AnimationOptions {
-    Number(usize),
-    KeyframeAnimationOptions {
-        composite: Option<()>,
-        id: Option<String>,
-        iteration_composite: Option<()>,
-        pseudo_element: Option<String>,
-    },
+    Number(f64),
+    KeyframeAnimationOptions(KeyframeAnimationOptions),
 }
 
-pub struct GetAnimationOptions;
-pub struct Animation;
+impl From<f64> for AnimationOptions {
+    fn from(duration: f64) -> Self {
+        AnimationOptions::Number(duration)
+    }
+}
 
-pub trait Animatable {
-    fn animate(&mut self, keyframes: Option<Vec<Keyframe>>, options: Option<AnimationOptions>);
-    fn get_animations(&self, options: Option<GetAnimationOptions>) -> Vec<Animation>;
+impl From<KeyframeAnimationOptions> for AnimationOptions {
+    fn from(options: KeyframeAnimationOptions) -> Self {
+        AnimationOptions::KeyframeAnimationOptions(options)
+    }
 }
+
+/// The identifier and the timing of an animation created with [`Animatable::animate`].
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Element/animate#options)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyframeAnimationOptions {
+    pub id: Option<String>,
+    pub timing: EffectTiming,
+}
+
+/// The options of [`Animatable::get_animations`].
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Element/getAnimations#options)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GetAnimationsOptions {
+    /// Whether the animations of the descendants of the element are included too.
+    pub subtree: bool,
+}
+
+pub trait Animatable: AsElement {
+    /// Creates an animation of the element's properties through keyframes, and plays it on the document timeline.
+    ///
+    /// Returns a `TypeError` if the offsets of the keyframes are out of order or not between zero and one, or if an easing or the timing is not valid.
+    ///
+    /// MDN Reference: [`Element.animate()`](https://developer.mozilla.org/docs/Web/API/Element/animate)
+    /// # Example
+    /// ```
+    /// use dom::{
+    ///     traits::*, Animatable, Document, EffectTiming, FillMode, Keyframe,
+    ///     KeyframeAnimationOptions, PlaybackDirection, Window,
+    /// };
+    ///
+    /// let document = Document::parse_html_unsafe("<div></div>");
+    /// let body = document.first_element_child().unwrap().last_element_child().unwrap();
+    /// let mut div = body.first_element_child().unwrap();
+    /// let mut window = Window::new(document);
+    ///
+    /// let keyframes = vec![
+    ///     Keyframe::new([("margin-left", "0px"), ("background-color", "black")]),
+    ///     Keyframe::new([("margin-left", "100px"), ("background-color", "white")]),
+    /// ];
+    /// let options = KeyframeAnimationOptions {
+    ///     id: Some(String::from("slide")),
+    ///     timing: EffectTiming {
+    ///         duration: 100.0,
+    ///         iterations: 2.0,
+    ///         direction: PlaybackDirection::Alternate,
+    ///         fill: FillMode::Forwards,
+    ///         ..Default::default()
+    ///     },
+    /// };
+    /// let animation = div.animate(keyframes, Some(options.into())).unwrap();
+    /// assert_eq!(animation.id(), "slide");
+    ///
+    /// window.advance_time(25.0);
+    /// let style = window.get_computed_style(&div, None);
+    /// assert_eq!(style.margin_left(), "25px");
+    /// assert_eq!(style.background_color(), "rgb(64, 64, 64)");
+    ///
+    /// // The second iteration plays backwards, and the effect stays at its end once it finishes.
+    /// window.advance_time(100.0);
+    /// assert_eq!(window.get_computed_style(&div, None).margin_left(), "75px");
+    /// window.advance_time(500.0);
+    /// assert_eq!(window.get_computed_style(&div, None).margin_left(), "0px");
+    /// assert_eq!(div.get_animations(None), [animation]);
+    /// ```
+    fn animate(
+        &mut self,
+        keyframes: Vec<Keyframe>,
+        options: Option<AnimationOptions>,
+    ) -> Result<Animation, DOMException> {
+        let element = AsElement::cast(self);
+        let (id, timing) = match options {
+            Some(AnimationOptions::Number(duration)) => (
+                None,
+                EffectTiming {
+                    duration,
+                    ..Default::default()
+                },
+            ),
+            Some(AnimationOptions::KeyframeAnimationOptions(options)) => {
+                (options.id, options.timing)
+            }
+            None => (None, EffectTiming::default()),
+        };
+        let effect = Effect::new(element.clone_ref(), keyframes, timing)?;
+        let document = element.owner_document().unwrap_or_else(Document::new);
+        let mut animation = Animation::new(document.weak_ref(), effect, Source::Script);
+        if let Some(id) = id {
+            animation.set_id(&id);
+        }
+        document.state_mut().animations.push(animation.clone());
+        animation.play()?;
+        Ok(animation)
+    }
+    /// Returns the animations of the element that are playing, are about to play, or still apply because they fill, in the order they apply.
+    ///
+    /// MDN Reference: [`Element.getAnimations()`](https://developer.mozilla.org/docs/Web/API/Element/getAnimations)
+    fn get_animations(&self, options: Option<GetAnimationsOptions>) -> Vec<Animation> {
+        let element = AsElement::cast(self);
+        let Some(document) = element.owner_document() else {
+            return vec![];
+        };
+        let subtree = options.unwrap_or_default().subtree;
+        animation::animations_of(&document, |target| {
+            let mut node = Some(AsNode::cast(target).clone_ref());
+            while let Some(current) = node {
+                if current == *element {
+                    return true;
+                }
+                if !subtree {
+                    return false;
+                }
+                node = current
+                    .parent_node()
+                    .map(|parent| AsNode::cast(&parent).clone_ref());
+            }
+            false
+        })
+    }
+}
+
+impl<T: AsElement> Animatable for T {}
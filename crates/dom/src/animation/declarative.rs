@@ -0,0 +1,608 @@
+//! CSS transitions and CSS animations: the animations that the `transition-*` and `animation-*` properties start when the styles of elements change.
+//!
+//! Browsers notice style changes when they render a frame. Here, style changes are noticed when styles are next used, by computed styles, layout and `get_animations`, and when the virtual clock moves. The events of the animations are fired when the clock moves.
+//!
+//! [Reference](https://drafts.csswg.org/css-transitions/)
+use std::{collections::HashMap, rc::Rc};
+
+use super::{
+    interpolation::{interpolate, is_interpolable},
+    timing::Phase,
+    Animation, AnimationPlayState, Effect, EffectTiming, FillMode, PlaybackDirection,
+};
+use crate::{
+    css::{
+        is_animatable, longhands, parse_time, split_commas, ComputedStyle, StyleResolver,
+        PROPERTIES,
+    },
+    domitem::DOMItem,
+    node::{ChildNode, NodeBase},
+    slot::{assigned_nodes, is_slot, shadow_root_of},
+    AsNode, AsParentNode, CSSKeyframesRule, Document, Element, Event, EventDetail, EventInit,
+    EventType, Keyframe,
+};
+
+/// What started an animation.
+#[derive(Debug)]
+pub(crate) enum Source {
+    /// A script, with [`Animatable::animate`](crate::Animatable::animate).
+    Script,
+    /// A change of the computed value of a property that `transition-property` names.
+    Transition(Transition),
+    /// A name in `animation-name`.
+    Animation(String),
+}
+
+/// A CSS transition of a property from one computed value to another.
+///
+/// [Reference](https://drafts.csswg.org/css-transitions/#starting)
+#[derive(Debug)]
+pub(crate) struct Transition {
+    pub(crate) property: &'static str,
+    start_value: String,
+    end_value: String,
+    /// The value that the transition is reversed back to, which is where the transition it interrupted started.
+    reversing_adjusted_start_value: String,
+    /// How much shorter the transition is than its duration, because it reverses a transition that was only partly done.
+    reversing_shortening_factor: f64,
+}
+
+/// The phase and iteration of the effect of a CSS animation or transition the last time its events were fired.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Reported {
+    phase: Phase,
+    iteration: Option<f64>,
+    /// The active time, which is the elapsed time of a cancel event.
+    active_time: f64,
+    /// The time on the timeline at which the animation was last cancelled.
+    cancel_time: Option<f64>,
+}
+
+impl Reported {
+    /// Records when the animation was cancelled, which is when its cancel event is scheduled.
+    pub(crate) fn cancel(&mut self, time: Option<f64>) {
+        self.cancel_time = time;
+    }
+}
+
+/// The state that a document keeps between style changes.
+#[derive(Default)]
+pub(crate) struct StyleChanges {
+    /// The styles of the rendered elements, without animations, as of the last style change.
+    before_change: HashMap<*mut NodeBase, (Element, Rc<ComputedStyle>)>,
+    /// Whether a style change is being processed, so that nothing starts another one meanwhile.
+    in_progress: bool,
+}
+
+/// Processes the changes of style since the last time: starts, updates and cancels the CSS animations and transitions of the elements of a document.
+///
+/// Elements that are not rendered, because they or their ancestors have `display: none`, have no CSS animations or transitions, and elements that were not rendered before do not transition.
+///
+/// Returns the resolver that found the styles, unless a style change was already being processed.
+///
+/// [Reference](https://drafts.csswg.org/css-transitions/#starting)
+pub(crate) fn update_style(document: &Document) -> Option<StyleResolver> {
+    if document.state().style_changes.in_progress {
+        return None;
+    }
+    document.state_mut().style_changes.in_progress = true;
+    let resolver = StyleResolver::without_animations(document.state().viewport.clone());
+    let mut rendered = vec![];
+    if let Some(root) = document.first_element_child() {
+        collect_rendered(document, &root, &resolver, &mut rendered);
+    }
+    let mut before_change = std::mem::take(&mut document.state_mut().style_changes.before_change);
+    let mut after_change = HashMap::new();
+    for (element, style) in rendered {
+        let key = AsNode::cast(&element).get_base_ptr();
+        update_animations(document, &resolver, &element, &style);
+        if let Some((_, before)) = before_change.remove(&key) {
+            update_transitions(document, &element, &before, &style);
+        }
+        after_change.insert(key, (element, style));
+    }
+    let animations = document.state().animations.clone();
+    for animation in animations {
+        let mut base = animation.inner.borrow_mut();
+        let key = AsNode::cast(&base.effect.target).get_base_ptr();
+        if !matches!(base.source, Source::Script) && !after_change.contains_key(&key) {
+            base.reset();
+        }
+    }
+    let changes = &mut document.state_mut().style_changes;
+    changes.before_change = after_change;
+    changes.in_progress = false;
+    Some(resolver)
+}
+
+/// Returns a resolver for the styles of a document, with their animations, once its style changes are processed.
+pub(crate) fn style_resolver(document: &Document) -> StyleResolver {
+    match update_style(document) {
+        // Without animations, the styles that the changes were found from are the styles.
+        Some(resolver) if document.state().animations.is_empty() => resolver,
+        _ => StyleResolver::new(document.state().viewport.clone()),
+    }
+}
+
+/// Adds the elements that are rendered, from an element down through the flat tree, along with their styles.
+fn collect_rendered(
+    document: &Document,
+    element: &Element,
+    resolver: &StyleResolver,
+    rendered: &mut Vec<(Element, Rc<ComputedStyle>)>,
+) {
+    let style = resolver.compute(element, None);
+    if style.get("display") == "none" {
+        return;
+    }
+    rendered.push((element.clone_ref(), style));
+    let children: Vec<ChildNode> = match shadow_root_of(element) {
+        Some(shadow_root) => shadow_root
+            .child_nodes()
+            .iter()
+            .map(ChildNode::from)
+            .collect(),
+        None => {
+            let assigned = match is_slot(element) {
+                true => assigned_nodes(element),
+                false => vec![],
+            };
+            match assigned.is_empty() {
+                true => element.child_nodes().iter().map(ChildNode::from).collect(),
+                false => assigned,
+            }
+        }
+    };
+    for child in children {
+        if let Some(child) = document.lookup_html_element(AsNode::cast(&child).get_base_ptr()) {
+            collect_rendered(document, &child, resolver, rendered);
+        }
+    }
+}
+
+/// Returns the value of a list property, like `animation-duration`, for the item at an index of another list. The list repeats when it is shorter.
+fn item<'a>(style: &'a ComputedStyle, name: &str, index: usize) -> &'a str {
+    let items = split_commas(style.get(name));
+    items[index % items.len()]
+}
+
+/// Starts the CSS animations of an element that `animation-name` names, updates the ones it still names, and cancels the others.
+///
+/// [Reference](https://drafts.csswg.org/css-animations/#animations)
+fn update_animations(
+    document: &Document,
+    resolver: &StyleResolver,
+    element: &Element,
+    style: &ComputedStyle,
+) {
+    let mut previous: Vec<Animation> = document
+        .state()
+        .animations
+        .iter()
+        .filter(|animation| {
+            let base = animation.inner.borrow();
+            matches!(base.source, Source::Animation(_))
+                && base.effect.target == *element
+                && base.play_state() != AnimationPlayState::Idle
+        })
+        .cloned()
+        .collect();
+    for (index, name) in split_commas(style.get("animation-name"))
+        .into_iter()
+        .enumerate()
+    {
+        let name = name.trim_matches(['"', '\'']);
+        if name == "none" {
+            continue;
+        }
+        let Some(rule) = resolver.keyframes(element, name) else {
+            continue;
+        };
+        let timing = animation_timing(style, index);
+        let keyframes = keyframes_of(&rule, item(style, "animation-timing-function", index));
+        let position = previous
+            .iter()
+            .position(|animation| animation.animation_name().as_deref() == Some(name));
+        let mut animation = match position {
+            Some(position) => {
+                let animation = previous.remove(position);
+                let mut effect = animation.effect();
+                if effect.get_timing() != timing && effect.update_timing(timing).is_err() {
+                    continue;
+                }
+                let _ = effect.set_keyframes(keyframes);
+                animation
+            }
+            None => {
+                let Ok(effect) = Effect::new(element.clone_ref(), keyframes, timing) else {
+                    continue;
+                };
+                let mut animation = Animation::new(
+                    document.weak_ref(),
+                    effect,
+                    Source::Animation(name.to_owned()),
+                );
+                document.state_mut().animations.push(animation.clone());
+                let _ = animation.play();
+                animation
+            }
+        };
+        let is_paused = item(style, "animation-play-state", index) == "paused";
+        let _ = match (is_paused, animation.play_state()) {
+            (true, AnimationPlayState::Running) => animation.pause(),
+            (false, AnimationPlayState::Paused) => animation.play(),
+            _ => Ok(()),
+        };
+    }
+    for animation in previous {
+        animation.inner.borrow_mut().reset();
+    }
+}
+
+/// Returns the timing of the CSS animation at an index of `animation-name`.
+fn animation_timing(style: &ComputedStyle, index: usize) -> EffectTiming {
+    let time = |name| parse_time(item(style, name, index)).unwrap_or_default();
+    EffectTiming {
+        delay: time("animation-delay"),
+        fill: match item(style, "animation-fill-mode", index) {
+            "forwards" => FillMode::Forwards,
+            "backwards" => FillMode::Backwards,
+            "both" => FillMode::Both,
+            _ => FillMode::None,
+        },
+        iterations: match item(style, "animation-iteration-count", index) {
+            "infinite" => f64::INFINITY,
+            count => count.parse().unwrap_or(1.0),
+        },
+        duration: time("animation-duration").max(0.0),
+        direction: match item(style, "animation-direction", index) {
+            "reverse" => PlaybackDirection::Reverse,
+            "alternate" => PlaybackDirection::Alternate,
+            "alternate-reverse" => PlaybackDirection::AlternateReverse,
+            _ => PlaybackDirection::Normal,
+        },
+        ..Default::default()
+    }
+}
+
+/// Returns the keyframes of an `@keyframes` rule, in order. Keyframes at the same offset with the same easing are merged, with the later values winning, and `animation-timing-function` gives the easing of the keyframes that do not have their own.
+///
+/// [Reference](https://drafts.csswg.org/css-animations/#keyframes)
+fn keyframes_of(rule: &CSSKeyframesRule, easing: &str) -> Vec<Keyframe> {
+    let mut keyframes: Vec<Keyframe> = vec![];
+    for keyframe_rule in &rule.inner.borrow().keyframes {
+        let keyframe_rule = keyframe_rule.inner.borrow();
+        let block = keyframe_rule.style.borrow();
+        let mut keyframe_easing = easing.to_owned();
+        let mut properties = vec![];
+        for declaration in &block.declarations {
+            match declaration.name.as_str() {
+                "animation-timing-function" => keyframe_easing = declaration.value.clone(),
+                name => properties.push((name.to_owned(), declaration.value.clone())),
+            }
+        }
+        for key in &keyframe_rule.keys {
+            let same = keyframes.iter_mut().find(|keyframe| {
+                keyframe.offset == Some(*key)
+                    && keyframe.easing.as_deref() == Some(keyframe_easing.as_str())
+            });
+            let Some(keyframe) = same else {
+                keyframes.push(Keyframe {
+                    offset: Some(*key),
+                    easing: Some(keyframe_easing.clone()),
+                    properties: properties.clone(),
+                });
+                continue;
+            };
+            for (name, value) in &properties {
+                keyframe.properties.retain(|(other, _)| other != name);
+                keyframe.properties.push((name.clone(), value.clone()));
+            }
+        }
+    }
+    keyframes.sort_by(|a, b| {
+        a.offset
+            .unwrap_or_default()
+            .total_cmp(&b.offset.unwrap_or_default())
+    });
+    keyframes
+}
+
+/// Returns the duration, delay and easing that `transition-*` gives to a property, from the last item of `transition-property` that names it.
+fn transition_timing(style: &ComputedStyle, property: &str) -> Option<(f64, f64, String)> {
+    let index = split_commas(style.get("transition-property"))
+        .into_iter()
+        .rposition(|name| {
+            name == "all"
+                || name == property
+                || longhands(name).is_some_and(|longhands| longhands.contains(&property))
+        })?;
+    let time = |name| parse_time(item(style, name, index)).unwrap_or_default();
+    Some((
+        time("transition-duration").max(0.0),
+        time("transition-delay"),
+        item(style, "transition-timing-function", index).to_owned(),
+    ))
+}
+
+/// Starts, reverses and cancels the transitions of an element, from how the values of its properties changed.
+///
+/// [Reference](https://drafts.csswg.org/css-transitions/#starting)
+fn update_transitions(
+    document: &Document,
+    element: &Element,
+    before: &ComputedStyle,
+    after: &ComputedStyle,
+) {
+    let has_transitions = document.state().animations.iter().any(|animation| {
+        let base = animation.inner.borrow();
+        matches!(base.source, Source::Transition(_)) && base.effect.target == *element
+    });
+    let durations = split_commas(after.get("transition-duration")).len();
+    let delays = split_commas(after.get("transition-delay")).len();
+    let has_timing = (0..durations.max(delays)).any(|index| {
+        let time = |name| parse_time(item(after, name, index)).unwrap_or_default();
+        time("transition-duration").max(0.0) + time("transition-delay") > 0.0
+    });
+    if !has_transitions && !has_timing {
+        return;
+    }
+    let properties = PROPERTIES
+        .iter()
+        .map(|property| property.name)
+        .filter(|name| is_animatable(name));
+    for property in properties {
+        let running = document
+            .state()
+            .animations
+            .iter()
+            .find(|animation| {
+                let base = animation.inner.borrow();
+                matches!(&base.source, Source::Transition(transition) if transition.property == property)
+                    && base.effect.target == *element
+                    && matches!(
+                        base.play_state(),
+                        AnimationPlayState::Running | AnimationPlayState::Paused
+                    )
+            })
+            .cloned();
+        let timing = transition_timing(after, property);
+        let after_value = after.get(property);
+        let Some(mut running) = running else {
+            let before_value = before.get(property);
+            if let Some(timing) = timing.filter(|(duration, delay, _)| duration + delay > 0.0) {
+                if before_value != after_value
+                    && is_interpolable(property, before_value, after_value)
+                {
+                    let transition = Transition {
+                        property,
+                        start_value: before_value.to_owned(),
+                        end_value: after_value.to_owned(),
+                        reversing_adjusted_start_value: before_value.to_owned(),
+                        reversing_shortening_factor: 1.0,
+                    };
+                    start_transition(document, element, transition, timing);
+                }
+            }
+            continue;
+        };
+        let (current_value, output_progress, end_value, adjusted_start, factor) = {
+            let base = running.inner.borrow();
+            let Source::Transition(transition) = &base.source else {
+                unreachable!()
+            };
+            let progress = base
+                .effect
+                .sample(base.current_time(), base.playback_rate)
+                .timing
+                .progress
+                .unwrap_or_default();
+            (
+                interpolate(
+                    property,
+                    &transition.start_value,
+                    &transition.end_value,
+                    progress,
+                ),
+                progress,
+                transition.end_value.clone(),
+                transition.reversing_adjusted_start_value.clone(),
+                transition.reversing_shortening_factor,
+            )
+        };
+        let Some(timing) = timing else {
+            running.inner.borrow_mut().reset();
+            continue;
+        };
+        if end_value == after_value {
+            continue;
+        }
+        running.inner.borrow_mut().reset();
+        let (duration, delay, easing) = timing;
+        if current_value == after_value
+            || duration + delay <= 0.0
+            || !is_interpolable(property, &current_value, after_value)
+        {
+            continue;
+        }
+        let transition = match adjusted_start == after_value {
+            // Going back to where the interrupted transition started takes as long as it took to get here.
+            true => {
+                let factor = (output_progress * factor + 1.0 - factor)
+                    .abs()
+                    .clamp(0.0, 1.0);
+                let delay = match delay < 0.0 {
+                    true => delay * factor,
+                    false => delay,
+                };
+                let transition = Transition {
+                    property,
+                    start_value: current_value,
+                    end_value: after_value.to_owned(),
+                    reversing_adjusted_start_value: end_value,
+                    reversing_shortening_factor: factor,
+                };
+                start_transition(
+                    document,
+                    element,
+                    transition,
+                    (duration * factor, delay, easing),
+                );
+                continue;
+            }
+            false => Transition {
+                property,
+                reversing_adjusted_start_value: current_value.clone(),
+                start_value: current_value,
+                end_value: after_value.to_owned(),
+                reversing_shortening_factor: 1.0,
+            },
+        };
+        start_transition(document, element, transition, (duration, delay, easing));
+    }
+}
+
+/// Creates the animation of a transition, and plays it from the current time.
+fn start_transition(
+    document: &Document,
+    element: &Element,
+    transition: Transition,
+    (duration, delay, easing): (f64, f64, String),
+) {
+    let keyframes = vec![
+        Keyframe::new([(transition.property, transition.start_value.as_str())]),
+        Keyframe::new([(transition.property, transition.end_value.as_str())]),
+    ];
+    // Transitions show their start value during their delay.
+    let timing = EffectTiming {
+        delay,
+        duration,
+        fill: FillMode::Backwards,
+        easing,
+        ..Default::default()
+    };
+    let Ok(effect) = Effect::new(element.clone_ref(), keyframes, timing) else {
+        return;
+    };
+    let mut animation = Animation::new(document.weak_ref(), effect, Source::Transition(transition));
+    document.state_mut().animations.push(animation.clone());
+    let _ = animation.play();
+}
+
+/// Returns the events of a CSS animation or transition since they were last fired, with the times on the document timeline they happened at and the element to fire them at.
+///
+/// [Reference](https://drafts.csswg.org/css-animations-2/#event-dispatch)
+pub(crate) fn take_events(animation: &Animation, time: f64) -> Vec<(f64, Element, Event)> {
+    let mut base = animation.inner.borrow_mut();
+    if matches!(base.source, Source::Script) {
+        return vec![];
+    }
+    let current_time = base.current_time();
+    let sample = base.effect.sample(current_time, base.playback_rate);
+    let timing = &base.effect.timing;
+    let active_duration = timing.active_duration();
+    let previous = base.reported;
+    let reported = Reported {
+        phase: sample.phase,
+        iteration: sample.timing.current_iteration,
+        active_time: current_time.map_or(previous.active_time, |current_time| {
+            (current_time - timing.delay).clamp(0.0, active_duration)
+        }),
+        cancel_time: None,
+    };
+    let interval_start = (0.0 - timing.delay).min(active_duration).max(0.0);
+    let interval_end = (timing.end_time() - timing.delay)
+        .min(active_duration)
+        .max(0.0);
+    use EventType::*;
+    use Phase::*;
+    let events: Vec<(EventType, f64)> = match &base.source {
+        Source::Script => vec![],
+        Source::Transition(_) => match (previous.phase, reported.phase) {
+            (Idle, Before) => vec![(TransitionRun, interval_start)],
+            (Idle, Active) => vec![
+                (TransitionRun, interval_start),
+                (TransitionStart, interval_start),
+            ],
+            (Idle, After) => vec![
+                (TransitionRun, interval_start),
+                (TransitionStart, interval_start),
+                (TransitionEnd, interval_end),
+            ],
+            (Before, Active) => vec![(TransitionStart, interval_start)],
+            (Before, After) => vec![
+                (TransitionStart, interval_start),
+                (TransitionEnd, interval_end),
+            ],
+            (Active, After) => vec![(TransitionEnd, interval_end)],
+            (Active, Before) => vec![(TransitionEnd, interval_start)],
+            (After, Active) => vec![(TransitionStart, interval_end)],
+            (After, Before) => vec![
+                (TransitionStart, interval_end),
+                (TransitionEnd, interval_start),
+            ],
+            (Before | Active, Idle) => vec![(TransitionCancel, previous.active_time)],
+            _ => vec![],
+        },
+        Source::Animation(_) => match (previous.phase, reported.phase) {
+            (Idle | Before, Active) => vec![(AnimationStart, interval_start)],
+            (Idle | Before, After) => vec![
+                (AnimationStart, interval_start),
+                (AnimationEnd, interval_end),
+            ],
+            (Active, Before) => vec![(AnimationEnd, interval_start)],
+            (Active, Active) if previous.iteration != reported.iteration => {
+                let iteration = reported.iteration.unwrap_or_default();
+                let elapsed = (iteration - timing.iteration_start) * timing.duration;
+                vec![(AnimationIteration, elapsed)]
+            }
+            (Active, After) => vec![(AnimationEnd, interval_end)],
+            (After, Active) => vec![(AnimationStart, interval_end)],
+            (After, Before) => vec![
+                (AnimationStart, interval_end),
+                (AnimationEnd, interval_start),
+            ],
+            (Before | Active, Idle) => vec![(AnimationCancel, previous.active_time)],
+            _ => vec![],
+        },
+    };
+    // The time on the timeline at which the effect reached an elapsed time.
+    let timeline_time = |event_type: &EventType, elapsed: f64| match base.start_time {
+        Some(start_time)
+            if base.playback_rate != 0.0
+                && !matches!(event_type, TransitionCancel | AnimationCancel) =>
+        {
+            start_time + (timing.delay + elapsed) / base.playback_rate
+        }
+        _ => previous.cancel_time.unwrap_or(time),
+    };
+    let events = events
+        .into_iter()
+        .map(|(event_type, elapsed)| {
+            let detail = match &base.source {
+                Source::Transition(transition) => EventDetail::Transition {
+                    property_name: transition.property.to_owned(),
+                    elapsed_time: elapsed / 1000.0,
+                    pseudo_element: String::new(),
+                },
+                Source::Animation(name) => EventDetail::Animation {
+                    animation_name: name.clone(),
+                    elapsed_time: elapsed / 1000.0,
+                    pseudo_element: String::new(),
+                },
+                Source::Script => EventDetail::None,
+            };
+            let event_init = EventInit {
+                bubbles: true,
+                ..Default::default()
+            };
+            (
+                timeline_time(&event_type, elapsed),
+                base.effect.target.clone_ref(),
+                Event::trusted(event_type, event_init).with_detail(detail),
+            )
+        })
+        .collect();
+    base.reported = reported;
+    events
+}
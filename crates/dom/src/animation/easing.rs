@@ -0,0 +1,151 @@
+//! Easing functions, which map the progress of an animation to the progress of the values it animates.
+//!
+//! [Reference](https://drafts.csswg.org/css-easing/)
+use crate::css::split_dimension;
+
+/// Where the jumps of a `steps()` easing function are: the `jump-start`, `jump-end`, `jump-none` and `jump-both` keywords.
+///
+/// [Reference](https://drafts.csswg.org/css-easing/#step-position)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StepPosition {
+    Start,
+    End,
+    None,
+    Both,
+}
+
+/// An easing function, like `ease-in-out`, `cubic-bezier(0.1, 0.7, 1, 0.1)` or `steps(4, jump-end)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TimingFunction {
+    Linear,
+    CubicBezier(f64, f64, f64, f64),
+    Steps(u32, StepPosition),
+}
+
+impl TimingFunction {
+    /// The default easing of CSS transitions and animations.
+    pub(crate) const EASE: TimingFunction = TimingFunction::CubicBezier(0.25, 0.1, 0.25, 1.0);
+
+    /// Parses an easing function, or returns `None` if the value is not one.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        let function = match value.as_str() {
+            "linear" => TimingFunction::Linear,
+            "ease" => TimingFunction::EASE,
+            "ease-in" => TimingFunction::CubicBezier(0.42, 0.0, 1.0, 1.0),
+            "ease-out" => TimingFunction::CubicBezier(0.0, 0.0, 0.58, 1.0),
+            "ease-in-out" => TimingFunction::CubicBezier(0.42, 0.0, 0.58, 1.0),
+            "step-start" => TimingFunction::Steps(1, StepPosition::Start),
+            "step-end" => TimingFunction::Steps(1, StepPosition::End),
+            _ => {
+                let (name, arguments) = value.strip_suffix(')')?.split_once('(')?;
+                let arguments: Vec<&str> = arguments.split(',').map(str::trim).collect();
+                match (name.trim(), arguments.as_slice()) {
+                    ("cubic-bezier", [x1, y1, x2, y2]) => {
+                        let [x1, y1, x2, y2] = [x1, y1, x2, y2].map(|number| number.parse().ok());
+                        let (x1, y1, x2, y2): (f64, f64, f64, f64) = (x1?, y1?, x2?, y2?);
+                        // The curve has to be a function of time, so its control points cannot go back or forward in time.
+                        if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) {
+                            return None;
+                        }
+                        TimingFunction::CubicBezier(x1, y1, x2, y2)
+                    }
+                    ("steps", [steps, position @ ..]) if position.len() <= 1 => {
+                        let steps: u32 = match split_dimension(steps)? {
+                            (steps, "") if steps.fract() == 0.0 && steps >= 1.0 => steps as u32,
+                            _ => return None,
+                        };
+                        let position = match position.first().copied().unwrap_or("end") {
+                            "jump-start" | "start" => StepPosition::Start,
+                            "jump-end" | "end" => StepPosition::End,
+                            "jump-none" if steps >= 2 => StepPosition::None,
+                            "jump-both" => StepPosition::Both,
+                            _ => return None,
+                        };
+                        TimingFunction::Steps(steps, position)
+                    }
+                    _ => return None,
+                }
+            }
+        };
+        Some(function)
+    }
+
+    /// Returns the output progress for an input progress. Progress outside of zero and one, which effects with a negative iteration start or a `cubic-bezier()` easing can have, is extrapolated.
+    ///
+    /// The before flag is set when the progress is that of an effect before its active interval, where steps that jump at the start have not jumped yet.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-easing/#easing-functions)
+    pub(crate) fn apply(&self, input: f64, before: bool) -> f64 {
+        match *self {
+            TimingFunction::Linear => input,
+            TimingFunction::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, input),
+            TimingFunction::Steps(steps, position) => {
+                let steps = steps as f64;
+                let mut step = (input * steps).floor();
+                if matches!(position, StepPosition::Start | StepPosition::Both) {
+                    step += 1.0;
+                }
+                if before && (input * steps).fract() == 0.0 {
+                    step -= 1.0;
+                }
+                if input >= 0.0 && step < 0.0 {
+                    step = 0.0;
+                }
+                let jumps = match position {
+                    StepPosition::Both => steps + 1.0,
+                    StepPosition::None => steps - 1.0,
+                    _ => steps,
+                };
+                if input <= 1.0 && step > jumps {
+                    step = jumps;
+                }
+                step / jumps
+            }
+        }
+    }
+}
+
+/// Evaluates a cubic Bézier curve from `(0, 0)` to `(1, 1)` at an input progress, extrapolating it along the tangents at its ends outside of zero and one.
+///
+/// [Reference](https://drafts.csswg.org/css-easing/#cubic-bezier-algo)
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, input: f64) -> f64 {
+    let bezier = |p1: f64, p2: f64, t: f64| {
+        3.0 * (1.0 - t) * (1.0 - t) * t * p1 + 3.0 * (1.0 - t) * t * t * p2 + t * t * t
+    };
+    if input < 0.0 {
+        let slope = if x1 > 0.0 {
+            y1 / x1
+        } else if y1 == 0.0 && x2 > 0.0 {
+            y2 / x2
+        } else {
+            0.0
+        };
+        return slope * input;
+    }
+    if input > 1.0 {
+        let slope = if x2 < 1.0 {
+            (y2 - 1.0) / (x2 - 1.0)
+        } else if y2 == 1.0 && x1 < 1.0 {
+            (y1 - 1.0) / (x1 - 1.0)
+        } else {
+            0.0
+        };
+        return 1.0 + slope * (input - 1.0);
+    }
+    // The x coordinate grows with t, so the t that gives the input is found by bisection.
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut t = input;
+    for _ in 0..64 {
+        let x = bezier(x1, x2, t);
+        if (x - input).abs() < 1e-9 {
+            break;
+        }
+        match x < input {
+            true => low = t,
+            false => high = t,
+        }
+        t = (low + high) / 2.0;
+    }
+    bezier(y1, y2, t)
+}
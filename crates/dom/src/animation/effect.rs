@@ -0,0 +1,205 @@
+//! Keyframe effects, which animate the properties of an element through a list of keyframes.
+//!
+//! [Reference](https://drafts.csswg.org/web-animations-1/#keyframe-effects)
+use super::{
+    easing::TimingFunction,
+    interpolation::Interpolation,
+    timing::{self, EffectTiming, Sample},
+};
+use crate::{
+    css::{is_animatable, DeclarationBlock},
+    DOMException, Element, Keyframe,
+};
+
+/// A keyframe with its offset computed, and its properties expanded into longhands.
+#[derive(Debug, Clone)]
+struct ComputedKeyframe {
+    /// The keyframe as it was given.
+    keyframe: Keyframe,
+    offset: f64,
+    easing: TimingFunction,
+    values: Vec<(String, String)>,
+}
+
+/// The keyframes and timing of an animation, and the element they animate.
+#[derive(Debug)]
+pub(crate) struct Effect {
+    pub(crate) target: Element,
+    keyframes: Vec<ComputedKeyframe>,
+    pub(crate) timing: EffectTiming,
+    pub(crate) easing: TimingFunction,
+}
+
+impl Effect {
+    pub(crate) fn new(
+        target: Element,
+        keyframes: Vec<Keyframe>,
+        timing: EffectTiming,
+    ) -> Result<Self, DOMException> {
+        let easing = timing.validate()?;
+        Ok(Effect {
+            target,
+            keyframes: compute_keyframes(keyframes)?,
+            timing,
+            easing,
+        })
+    }
+
+    /// Returns the keyframes, with their computed offsets.
+    pub(crate) fn keyframes(&self) -> Vec<Keyframe> {
+        self.keyframes
+            .iter()
+            .map(|computed| Keyframe {
+                offset: Some(computed.offset),
+                ..computed.keyframe.clone()
+            })
+            .collect()
+    }
+
+    pub(crate) fn set_keyframes(&mut self, keyframes: Vec<Keyframe>) -> Result<(), DOMException> {
+        self.keyframes = compute_keyframes(keyframes)?;
+        Ok(())
+    }
+
+    pub(crate) fn set_timing(&mut self, timing: EffectTiming) -> Result<(), DOMException> {
+        self.easing = timing.validate()?;
+        self.timing = timing;
+        Ok(())
+    }
+
+    pub(crate) fn sample(&self, local_time: Option<f64>, playback_rate: f64) -> Sample {
+        timing::sample(&self.timing, &self.easing, local_time, playback_rate)
+    }
+
+    /// Returns how each animated property is interpolated at a progress through an iteration.
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#the-effect-value-of-a-keyframe-animation-effect)
+    pub(crate) fn values(&self, progress: f64) -> Vec<(String, Interpolation)> {
+        let mut names: Vec<&str> = vec![];
+        for keyframe in &self.keyframes {
+            for (name, _) in &keyframe.values {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| (name.to_owned(), self.interpolation(name, progress)))
+            .collect()
+    }
+
+    fn interpolation(&self, name: &str, progress: f64) -> Interpolation {
+        // The keyframes that have the property, with keyframes at the start and end for the underlying value if there are none.
+        let mut frames: Vec<(f64, Option<&str>, TimingFunction)> = self
+            .keyframes
+            .iter()
+            .filter_map(|keyframe| {
+                let (_, value) = keyframe.values.iter().find(|(other, _)| other == name)?;
+                Some((keyframe.offset, Some(value.as_str()), keyframe.easing))
+            })
+            .collect();
+        if frames.first().is_none_or(|(offset, _, _)| *offset != 0.0) {
+            frames.insert(0, (0.0, None, TimingFunction::Linear));
+        }
+        if frames.last().is_none_or(|(offset, _, _)| *offset != 1.0) {
+            frames.push((1.0, None, TimingFunction::Linear));
+        }
+        let single = |(_, value, _): &(f64, Option<&str>, TimingFunction)| Interpolation {
+            from: value.map(str::to_owned),
+            to: value.map(str::to_owned),
+            progress: 0.0,
+        };
+        let count = |at: f64| frames.iter().filter(|(offset, _, _)| *offset == at).count();
+        if progress < 0.0 && count(0.0) > 1 {
+            return single(&frames[0]);
+        }
+        if progress >= 1.0 && count(1.0) > 1 {
+            return single(&frames[frames.len() - 1]);
+        }
+        let start = frames
+            .iter()
+            .rposition(|(offset, _, _)| *offset <= progress && *offset < 1.0)
+            .or_else(|| frames.iter().rposition(|(offset, _, _)| *offset == 0.0))
+            .unwrap_or_default();
+        let (from, to) = (&frames[start], &frames[start + 1]);
+        let local_progress = match to.0 == from.0 {
+            true => 1.0,
+            false => (progress - from.0) / (to.0 - from.0),
+        };
+        Interpolation {
+            from: from.1.map(str::to_owned),
+            to: to.1.map(str::to_owned),
+            progress: from.2.apply(local_progress, false),
+        }
+    }
+}
+
+/// Checks a list of keyframes, parses their easings and properties, and computes the offsets that are missing.
+///
+/// [Reference](https://drafts.csswg.org/web-animations-1/#processing-a-keyframes-argument)
+fn compute_keyframes(keyframes: Vec<Keyframe>) -> Result<Vec<ComputedKeyframe>, DOMException> {
+    let mut previous = 0.0;
+    for offset in keyframes.iter().filter_map(|keyframe| keyframe.offset) {
+        if !(0.0..=1.0).contains(&offset) {
+            return Err(DOMException::TypeError(format!(
+                "The keyframe offset {offset} is not between 0 and 1"
+            )));
+        }
+        if offset < previous {
+            return Err(DOMException::TypeError(String::from(
+                "Keyframe offsets must be in order",
+            )));
+        }
+        previous = offset;
+    }
+    let mut offsets: Vec<Option<f64>> = keyframes.iter().map(|keyframe| keyframe.offset).collect();
+    // The first keyframe is at the start and the last one at the end, and the others are spaced evenly between the keyframes with offsets.
+    if let Some(last) = offsets.last_mut() {
+        last.get_or_insert(1.0);
+    }
+    if let Some(first) = offsets.first_mut() {
+        first.get_or_insert(0.0);
+    }
+    let mut index = 0;
+    while index + 1 < offsets.len() {
+        let next = (index + 1..offsets.len())
+            .find(|next| offsets[*next].is_some())
+            .unwrap();
+        let (start, end) = (offsets[index].unwrap(), offsets[next].unwrap());
+        for (step, offset) in offsets[index + 1..next].iter_mut().enumerate() {
+            let fraction = (step + 1) as f64 / (next - index) as f64;
+            *offset = Some(start + (end - start) * fraction);
+        }
+        index = next;
+    }
+    keyframes
+        .into_iter()
+        .zip(offsets)
+        .map(|(keyframe, offset)| {
+            let easing = match &keyframe.easing {
+                Some(easing) => TimingFunction::parse(easing).ok_or_else(|| {
+                    DOMException::TypeError(format!("'{easing}' is not a valid easing"))
+                })?,
+                None => TimingFunction::Linear,
+            };
+            // Shorthands are expanded, and properties that cannot be animated are left out.
+            let mut block = DeclarationBlock::default();
+            for (name, value) in &keyframe.properties {
+                block.set(&name.to_ascii_lowercase(), value, false);
+            }
+            let values = block
+                .declarations
+                .into_iter()
+                .filter(|declaration| is_animatable(&declaration.name))
+                .map(|declaration| (declaration.name, declaration.value))
+                .collect();
+            Ok(ComputedKeyframe {
+                offset: offset.unwrap_or_default(),
+                easing,
+                values,
+                keyframe,
+            })
+        })
+        .collect()
+}
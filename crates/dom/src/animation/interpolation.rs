@@ -0,0 +1,220 @@
+//! The interpolation of computed values, which gives the values of animated properties between keyframes.
+//!
+//! [Reference](https://drafts.csswg.org/css-values/#interpolation)
+use crate::css::{parse_color, serialize_color};
+
+/// A property of an element that an animation moves from one value to another.
+///
+/// A value of `None` is the underlying value: the value the property would have without the animation, for keyframes that leave the property out.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Interpolation {
+    pub(crate) from: Option<String>,
+    pub(crate) to: Option<String>,
+    /// How far the value is from `from` to `to`, which can be outside of zero and one when an easing function overshoots.
+    pub(crate) progress: f64,
+}
+
+/// The properties whose computed values are integers.
+const INTEGER_PROPERTIES: &[&str] = &["z-index", "order"];
+
+/// Interpolates between two computed values of a property.
+///
+/// Colors are interpolated in premultiplied sRGB, and values that only differ in their numbers, like lengths, numbers and lists of transform functions, number by number. Anything else, like keywords, flips from one value to the other halfway through.
+///
+/// [Reference](https://drafts.csswg.org/web-animations-1/#animating-properties)
+pub(crate) fn interpolate(name: &str, from: &str, to: &str, progress: f64) -> String {
+    if from == to {
+        return from.to_owned();
+    }
+    if name == "visibility" && (from == "visible" || to == "visible") {
+        // Visibility is visible all the way between a visible and a hidden value.
+        return match progress {
+            progress if progress <= 0.0 => from.to_owned(),
+            progress if progress >= 1.0 => to.to_owned(),
+            _ => String::from("visible"),
+        };
+    }
+    if let (Some(from), Some(to)) = (parse_color(from), parse_color(to)) {
+        return interpolate_color(from, to, progress);
+    }
+    let (from, to) = with_identity_transforms(name, from, to);
+    let from_tokens = tokenize(&from);
+    let to_tokens = tokenize(&to);
+    if !are_compatible(&from_tokens, &to_tokens) {
+        return match progress < 0.5 {
+            true => from,
+            false => to,
+        };
+    }
+    let is_integer = INTEGER_PROPERTIES.contains(&name);
+    from_tokens
+        .iter()
+        .zip(&to_tokens)
+        .map(|pair| match pair {
+            (Token::Number(from), Token::Number(to)) => {
+                let mut number = from + (to - from) * progress;
+                if is_integer {
+                    number = number.round();
+                }
+                if name == "opacity" {
+                    number = number.clamp(0.0, 1.0);
+                }
+                format_number(number)
+            }
+            (Token::Text(text), _) => text.clone(),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Returns true if two computed values of a property interpolate smoothly, rather than flipping from one to the other halfway through. Only those values can be transitioned.
+///
+/// [Reference](https://drafts.csswg.org/css-transitions/#transitionable)
+pub(crate) fn is_interpolable(name: &str, from: &str, to: &str) -> bool {
+    if name == "visibility" {
+        return from == "visible" || to == "visible";
+    }
+    if parse_color(from).is_some() && parse_color(to).is_some() {
+        return true;
+    }
+    let (from, to) = with_identity_transforms(name, from, to);
+    are_compatible(&tokenize(&from), &tokenize(&to))
+}
+
+/// Replaces a transform of `none` with the identity transform that matches the other value.
+fn with_identity_transforms(name: &str, from: &str, to: &str) -> (String, String) {
+    match (from, to) {
+        ("none", to) if name == "transform" => (identity_transform(to), to.to_owned()),
+        (from, "none") if name == "transform" => (from.to_owned(), identity_transform(from)),
+        (from, to) => (from.to_owned(), to.to_owned()),
+    }
+}
+
+/// Returns true if two values have numbers in the same places, and the same text between them.
+fn are_compatible(from: &[Token], to: &[Token]) -> bool {
+    from.len() == to.len()
+        && from.iter().zip(to).all(|pair| match pair {
+            (Token::Number(_), Token::Number(_)) => true,
+            (Token::Text(from), Token::Text(to)) => from == to,
+            _ => false,
+        })
+}
+
+/// Interpolates two colors in premultiplied sRGB.
+///
+/// [Reference](https://drafts.csswg.org/css-color/#interpolation-alpha)
+fn interpolate_color(
+    (from, from_alpha): ([u8; 3], f64),
+    (to, to_alpha): ([u8; 3], f64),
+    progress: f64,
+) -> String {
+    let alpha = (from_alpha + (to_alpha - from_alpha) * progress).clamp(0.0, 1.0);
+    let mut rgb = [0; 3];
+    for (channel, (from, to)) in rgb.iter_mut().zip(from.iter().zip(to.iter())) {
+        let premultiplied = *from as f64 * from_alpha
+            + (*to as f64 * to_alpha - *from as f64 * from_alpha) * progress;
+        let value = match alpha == 0.0 {
+            true => 0.0,
+            false => premultiplied / alpha,
+        };
+        *channel = value.round().clamp(0.0, 255.0) as u8;
+    }
+    serialize_color((rgb, alpha))
+}
+
+/// Returns the identity transform with the same functions as a transform list, which `none` is interpolated as.
+///
+/// [Reference](https://drafts.csswg.org/css-transforms/#interpolation-of-transforms)
+fn identity_transform(transform: &str) -> String {
+    let mut function = String::new();
+    let mut argument = 0;
+    tokenize(transform)
+        .into_iter()
+        .map(|token| match token {
+            Token::Text(text) => {
+                if let Some((before, _)) = text.rsplit_once('(') {
+                    function = before
+                        .rsplit([')', ' ', ','])
+                        .next()
+                        .unwrap_or_default()
+                        .to_ascii_lowercase();
+                    argument = 0;
+                }
+                text
+            }
+            Token::Number(_) => {
+                let is_one = match function.as_str() {
+                    "matrix" => matches!(argument, 0 | 3),
+                    "matrix3d" => matches!(argument, 0 | 5 | 10 | 15),
+                    function => function.starts_with("scale"),
+                };
+                argument += 1;
+                String::from(if is_one { "1" } else { "0" })
+            }
+        })
+        .collect()
+}
+
+/// A part of a value: either a number, with the unit that follows it kept as text, or anything else.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+}
+
+/// Splits a value into numbers and the text between them, so that `translate(10px, -5%)` is `translate(`, `10`, `px, `, `-5` and `%)`.
+fn tokenize(value: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut text = String::new();
+    let chars: Vec<char> = value.chars().collect();
+    let mut index = 0;
+    while index < chars.len() {
+        let is_number_start = {
+            let char = chars[index];
+            let next_is_digit = |offset: usize| {
+                chars
+                    .get(index + offset)
+                    .is_some_and(|char| char.is_ascii_digit())
+            };
+            let starts = char.is_ascii_digit()
+                || (char == '.' && next_is_digit(1))
+                || (matches!(char, '-' | '+')
+                    && (next_is_digit(1)
+                        || (chars.get(index + 1) == Some(&'.') && next_is_digit(2))));
+            // Digits in identifiers, like the `3` of `rotate3d`, are not numbers.
+            let in_identifier = index > 0
+                && (chars[index - 1].is_alphanumeric() || matches!(chars[index - 1], '-' | '_'));
+            starts && !in_identifier
+        };
+        if !is_number_start {
+            text.push(chars[index]);
+            index += 1;
+            continue;
+        }
+        let start = index;
+        index += 1;
+        while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '.') {
+            index += 1;
+        }
+        let number: String = chars[start..index].iter().collect();
+        match number.parse::<f64>() {
+            Ok(number) => {
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                tokens.push(Token::Number(number));
+            }
+            Err(_) => text.push_str(&number),
+        }
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    tokens
+}
+
+/// Formats a number the way computed values are, with at most three decimals.
+fn format_number(number: f64) -> String {
+    let number = (number * 1000.0).round() / 1000.0;
+    format!("{}", number + 0.0)
+}
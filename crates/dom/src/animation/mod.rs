@@ -0,0 +1,648 @@
+//! Web Animations: animations that play keyframe effects on the document timeline, which follows the virtual clock of the document.
+//!
+//! The animated values of an element take part in the cascade above its normal declarations and below its `!important` ones, so that they show in its computed style and in layout. CSS transitions are the exception, and apply above everything.
+//!
+//! [Reference](https://drafts.csswg.org/web-animations-1/)
+mod declarative;
+mod easing;
+mod effect;
+mod interpolation;
+mod timing;
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+pub(crate) use declarative::{style_resolver, update_style, Source, StyleChanges};
+pub(crate) use easing::TimingFunction;
+pub(crate) use effect::Effect;
+pub(crate) use interpolation::{interpolate, Interpolation};
+pub use timing::{ComputedEffectTiming, EffectTiming, FillMode, PlaybackDirection};
+
+use crate::{
+    document::WeakDocumentRef, domitem::DOMItem, AsEventTarget, AsNode, DOMException, Document,
+    Element, Event, EventInit, EventTarget, EventType, Keyframe,
+};
+
+/// The state of an animation.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/playState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationPlayState {
+    /// The animation has no current time, like after it is cancelled.
+    Idle,
+    Running,
+    Paused,
+    /// The animation reached the end of its effect, or its start when it plays backwards.
+    Finished,
+}
+
+/// An animation that plays a keyframe effect on the timeline of a document, which is driven by the virtual clock of its window.
+///
+/// Animations are created with [`Animatable::animate`], and start playing right away. Unlike in browsers, there is no pending state: the animation starts at the current time of the clock, and [`Window::advance_time`] moves it forward. A `finish` event is fired when the animation finishes, and a `cancel` event when it is cancelled.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation)
+///
+/// [`Animatable::animate`]: crate::Animatable::animate
+/// [`Window::advance_time`]: crate::Window::advance_time
+#[derive(Debug, Clone)]
+pub struct Animation {
+    pub(crate) inner: Rc<RefCell<AnimationBase>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct AnimationBase {
+    id: String,
+    pub(crate) effect: Effect,
+    document: WeakDocumentRef,
+    start_time: Option<f64>,
+    hold_time: Option<f64>,
+    playback_rate: f64,
+    previous_current_time: Option<f64>,
+    /// Whether the animation was finished the last time its finished state was updated.
+    is_finished: bool,
+    event_target: EventTarget,
+    pub(crate) source: Source,
+    /// Where the effect was the last time the events of a CSS animation or transition were fired.
+    reported: declarative::Reported,
+}
+
+impl PartialEq for Animation {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl AnimationBase {
+    /// Returns the current time of the document timeline, or `None` if the document is gone.
+    fn timeline_time(&self) -> Option<f64> {
+        let document = self.document.inner.upgrade()?;
+        let time = document.borrow().time;
+        Some(time)
+    }
+
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#the-current-time-of-an-animation)
+    fn current_time(&self) -> Option<f64> {
+        self.hold_time.or_else(|| {
+            let start_time = self.start_time?;
+            Some((self.timeline_time()? - start_time) * self.playback_rate)
+        })
+    }
+
+    fn end_time(&self) -> f64 {
+        self.effect.timing.end_time()
+    }
+
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#play-states)
+    fn play_state(&self) -> AnimationPlayState {
+        let current_time = self.current_time();
+        match (current_time, self.start_time) {
+            (None, None) => AnimationPlayState::Idle,
+            (_, None) => AnimationPlayState::Paused,
+            (Some(time), Some(_))
+                if (self.playback_rate > 0.0 && time >= self.end_time())
+                    || (self.playback_rate < 0.0 && time <= 0.0) =>
+            {
+                AnimationPlayState::Finished
+            }
+            _ => AnimationPlayState::Running,
+        }
+    }
+
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#silently-set-the-current-time)
+    fn silently_set_current_time(&mut self, seek_time: f64) {
+        let timeline_time = self.timeline_time();
+        match timeline_time {
+            Some(timeline_time)
+                if self.hold_time.is_none()
+                    && self.start_time.is_some()
+                    && self.playback_rate != 0.0 =>
+            {
+                self.start_time = Some(timeline_time - seek_time / self.playback_rate);
+            }
+            _ => self.hold_time = Some(seek_time),
+        }
+        if timeline_time.is_none() {
+            self.start_time = None;
+        }
+        self.previous_current_time = None;
+    }
+
+    /// Updates the hold time of the animation when it reaches either end of its effect, and returns true if it just finished.
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#update-an-animations-finished-state)
+    fn update_finished_state(&mut self, did_seek: bool) -> bool {
+        let end_time = self.end_time();
+        let rate = self.playback_rate;
+        let timeline_time = self.timeline_time();
+        let unconstrained_time = match did_seek {
+            true => self.current_time(),
+            false => self
+                .start_time
+                .zip(timeline_time)
+                .map(|(start_time, timeline_time)| (timeline_time - start_time) * rate),
+        };
+        if let (Some(time), Some(_)) = (unconstrained_time, self.start_time) {
+            if rate > 0.0 && time >= end_time {
+                self.hold_time = Some(match (did_seek, self.previous_current_time) {
+                    (false, Some(previous)) => previous.max(end_time),
+                    (false, None) => end_time,
+                    (true, _) => time,
+                });
+            } else if rate < 0.0 && time <= 0.0 {
+                self.hold_time = Some(match (did_seek, self.previous_current_time) {
+                    (false, Some(previous)) => previous.min(0.0),
+                    (false, None) => 0.0,
+                    (true, _) => time,
+                });
+            } else if let (true, Some(timeline_time)) = (rate != 0.0, timeline_time) {
+                if let (true, Some(hold_time)) = (did_seek, self.hold_time) {
+                    self.start_time = Some(timeline_time - hold_time / rate);
+                }
+                self.hold_time = None;
+            }
+        }
+        self.previous_current_time = self.current_time();
+        let is_finished = self.play_state() == AnimationPlayState::Finished;
+        let just_finished = is_finished && !self.is_finished;
+        self.is_finished = is_finished;
+        just_finished
+    }
+
+    /// Makes the animation idle, and returns true if it was not idle already.
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#cancel-an-animation)
+    pub(crate) fn reset(&mut self) -> bool {
+        let was_idle = self.play_state() == AnimationPlayState::Idle;
+        if !was_idle {
+            self.reported.cancel(self.timeline_time());
+        }
+        self.hold_time = None;
+        self.start_time = None;
+        self.previous_current_time = None;
+        self.is_finished = false;
+        !was_idle
+    }
+}
+
+impl Animation {
+    /// Creates an animation of an effect on the timeline of a document, without playing it.
+    pub(crate) fn new(document: WeakDocumentRef, effect: Effect, source: Source) -> Self {
+        Animation {
+            inner: Rc::new(RefCell::new(AnimationBase {
+                id: String::new(),
+                effect,
+                document,
+                start_time: None,
+                hold_time: None,
+                playback_rate: 1.0,
+                previous_current_time: None,
+                is_finished: false,
+                event_target: EventTarget::new(),
+                source,
+                reported: Default::default(),
+            })),
+        }
+    }
+
+    /// Returns the identifier of the animation, which is empty unless one was given.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/id)
+    pub fn id(&self) -> String {
+        self.inner.borrow().id.clone()
+    }
+    pub fn set_id(&mut self, id: &str) {
+        self.inner.borrow_mut().id = id.to_owned();
+    }
+    /// Returns the name of the `@keyframes` rule that the animation plays, if `animation-name` created it.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSAnimation/animationName)
+    pub fn animation_name(&self) -> Option<String> {
+        match &self.inner.borrow().source {
+            Source::Animation(name) => Some(name.clone()),
+            _ => None,
+        }
+    }
+    /// Returns the property that the animation transitions, if it is a CSS transition.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSTransition/transitionProperty)
+    pub fn transition_property(&self) -> Option<String> {
+        match &self.inner.borrow().source {
+            Source::Transition(transition) => Some(transition.property.to_owned()),
+            _ => None,
+        }
+    }
+    /// Returns the keyframe effect that the animation plays.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/effect)
+    pub fn effect(&self) -> KeyframeEffect {
+        KeyframeEffect {
+            animation: self.clone(),
+        }
+    }
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/playState)
+    pub fn play_state(&self) -> AnimationPlayState {
+        self.inner.borrow().play_state()
+    }
+    /// Returns the time of the animation in milliseconds, from the start of its effect, or `None` if it is idle.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/currentTime)
+    pub fn current_time(&self) -> Option<f64> {
+        self.inner.borrow().current_time()
+    }
+    /// Seeks the animation to a time, in milliseconds.
+    ///
+    /// Returns a `TypeError` when the time is `None` while the animation has a current time.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/currentTime)
+    pub fn set_current_time(&mut self, time: Option<f64>) -> Result<(), DOMException> {
+        let mut base = self.inner.borrow_mut();
+        let Some(time) = time else {
+            return match base.current_time() {
+                Some(_) => Err(DOMException::TypeError(String::from(
+                    "The current time of an animation cannot be unset",
+                ))),
+                None => Ok(()),
+            };
+        };
+        base.silently_set_current_time(time);
+        let just_finished = base.update_finished_state(true);
+        drop(base);
+        self.notify(just_finished);
+        Ok(())
+    }
+    /// Returns the time of the document timeline at which the animation started, or `None` if it is not playing.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/startTime)
+    pub fn start_time(&self) -> Option<f64> {
+        self.inner.borrow().start_time
+    }
+    /// Sets the time of the document timeline at which the animation starts. `None` pauses it.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/startTime)
+    pub fn set_start_time(&mut self, start_time: Option<f64>) {
+        let mut base = self.inner.borrow_mut();
+        let previous_time = base.current_time();
+        base.start_time = start_time;
+        match start_time {
+            Some(_) if base.playback_rate != 0.0 => base.hold_time = None,
+            Some(_) => {}
+            None => base.hold_time = previous_time,
+        }
+        let just_finished = base.update_finished_state(true);
+        drop(base);
+        self.notify(just_finished);
+    }
+    /// Returns how fast the animation plays, relative to the timeline. It plays backwards when the rate is negative.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/playbackRate)
+    pub fn playback_rate(&self) -> f64 {
+        self.inner.borrow().playback_rate
+    }
+    /// Changes how fast the animation plays, keeping its current time.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/playbackRate)
+    pub fn set_playback_rate(&mut self, playback_rate: f64) {
+        let mut base = self.inner.borrow_mut();
+        let previous_time = base.current_time();
+        base.playback_rate = playback_rate;
+        let Some(previous_time) = previous_time else {
+            return;
+        };
+        base.silently_set_current_time(previous_time);
+        let just_finished = base.update_finished_state(true);
+        drop(base);
+        self.notify(just_finished);
+    }
+    /// Plays the animation. An animation that is finished, or that has not started, plays from the start of its effect, or from its end when it plays backwards.
+    ///
+    /// Returns an `InvalidStateError` when the animation plays backwards from the end of an effect that repeats forever.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/play)
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{
+    ///     traits::*, Animatable, AnimationOptions, AnimationPlayState, Document, Keyframe, Window,
+    /// };
+    ///
+    /// let document = Document::parse_html_unsafe("<p>Fading</p>");
+    /// let body = document.first_element_child().unwrap().last_element_child().unwrap();
+    /// let mut paragraph = body.first_element_child().unwrap();
+    /// let mut window = Window::new(document);
+    /// let keyframes = vec![Keyframe::new([("opacity", "1")]), Keyframe::new([("opacity", "0")])];
+    /// let mut animation = paragraph
+    ///     .animate(keyframes, Some(AnimationOptions::from(1000.0)))
+    ///     .unwrap();
+    /// let opacity = |window: &Window| window.get_computed_style(&paragraph, None).opacity();
+    ///
+    /// window.advance_time(250.0);
+    /// assert_eq!(opacity(&window), "0.75");
+    ///
+    /// animation.pause().unwrap();
+    /// window.advance_time(250.0);
+    /// assert_eq!(animation.play_state(), AnimationPlayState::Paused);
+    /// assert_eq!(opacity(&window), "0.75");
+    ///
+    /// animation.play().unwrap();
+    /// window.advance_time(750.0);
+    /// assert_eq!(animation.play_state(), AnimationPlayState::Finished);
+    /// assert_eq!(opacity(&window), "1");
+    /// ```
+    pub fn play(&mut self) -> Result<(), DOMException> {
+        let mut base = self.inner.borrow_mut();
+        let current_time = base.current_time();
+        let end_time = base.end_time();
+        let rate = base.playback_rate;
+        let seek_time = match current_time {
+            None if rate >= 0.0 => Some(0.0),
+            Some(time) if rate > 0.0 && (time < 0.0 || time >= end_time) => Some(0.0),
+            Some(time) if rate < 0.0 && time > 0.0 && time <= end_time => None,
+            _ if rate < 0.0 => {
+                if end_time.is_infinite() {
+                    return Err(DOMException::InvalidStateError(String::from(
+                        "An animation that repeats forever cannot play backwards from its end",
+                    )));
+                }
+                Some(end_time)
+            }
+            _ => None,
+        };
+        if let Some(seek_time) = seek_time {
+            base.start_time = None;
+            base.hold_time = Some(seek_time);
+        }
+        // The animation is ready to play right away.
+        if let (Some(hold_time), Some(timeline_time)) = (base.hold_time, base.timeline_time()) {
+            match rate == 0.0 {
+                true => base.start_time = Some(timeline_time),
+                false => {
+                    base.start_time = Some(timeline_time - hold_time / rate);
+                    base.hold_time = None;
+                }
+            }
+        }
+        let just_finished = base.update_finished_state(false);
+        drop(base);
+        self.notify(just_finished);
+        Ok(())
+    }
+    /// Pauses the animation at its current time.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/pause)
+    pub fn pause(&mut self) -> Result<(), DOMException> {
+        let mut base = self.inner.borrow_mut();
+        if base.play_state() == AnimationPlayState::Paused {
+            return Ok(());
+        }
+        if base.current_time().is_none() {
+            let seek_time = match base.playback_rate >= 0.0 {
+                true => 0.0,
+                false => {
+                    let end_time = base.end_time();
+                    if end_time.is_infinite() {
+                        return Err(DOMException::InvalidStateError(String::from(
+                            "An animation that repeats forever cannot be paused at its end",
+                        )));
+                    }
+                    end_time
+                }
+            };
+            base.hold_time = Some(seek_time);
+        }
+        if base.hold_time.is_none() {
+            base.hold_time = base.current_time();
+        }
+        base.start_time = None;
+        let just_finished = base.update_finished_state(false);
+        drop(base);
+        self.notify(just_finished);
+        Ok(())
+    }
+    /// Plays the animation in the other direction, by negating its playback rate.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/reverse)
+    pub fn reverse(&mut self) -> Result<(), DOMException> {
+        if self.inner.borrow().timeline_time().is_none() {
+            return Err(DOMException::InvalidStateError(String::from(
+                "An animation without a timeline cannot be reversed",
+            )));
+        }
+        let playback_rate = self.playback_rate();
+        self.set_playback_rate(-playback_rate);
+        let result = self.play();
+        if result.is_err() {
+            self.set_playback_rate(playback_rate);
+        }
+        result
+    }
+    /// Seeks the animation to the end of its effect, or to its start when it plays backwards.
+    ///
+    /// Returns an `InvalidStateError` when the playback rate is zero, or when the effect repeats forever and the animation plays forwards.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/finish)
+    pub fn finish(&mut self) -> Result<(), DOMException> {
+        let mut base = self.inner.borrow_mut();
+        let rate = base.playback_rate;
+        let end_time = base.end_time();
+        if rate == 0.0 || (rate > 0.0 && end_time.is_infinite()) {
+            return Err(DOMException::InvalidStateError(String::from(
+                "The animation has no end to finish at",
+            )));
+        }
+        let limit = match rate > 0.0 {
+            true => end_time,
+            false => 0.0,
+        };
+        base.silently_set_current_time(limit);
+        if let (None, Some(timeline_time)) = (base.start_time, base.timeline_time()) {
+            base.start_time = Some(timeline_time - limit / rate);
+        }
+        let just_finished = base.update_finished_state(true);
+        drop(base);
+        self.notify(just_finished);
+        Ok(())
+    }
+    /// Stops the animation and removes its effect, and fires a `cancel` event if it was not idle.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Animation/cancel)
+    pub fn cancel(&mut self) {
+        let was_running = self.inner.borrow_mut().reset();
+        crate::layout::invalidate(AsNode::cast(&self.inner.borrow().effect.target));
+        if was_running {
+            self.dispatch_event(&mut Event::trusted(EventType::Cancel, EventInit::default()));
+        }
+    }
+
+    /// Marks the layout of the target's document as out of date after a change to the timing of the animation, and fires a `finish` event if it just finished.
+    fn notify(&mut self, just_finished: bool) {
+        crate::layout::invalidate(AsNode::cast(&self.inner.borrow().effect.target));
+        if just_finished {
+            self.dispatch_event(&mut Event::trusted(EventType::Finish, EventInit::default()));
+        }
+    }
+
+    /// Returns true if the effect of the animation is current or in effect, which is when it shows in [`Animatable::get_animations`](crate::Animatable::get_animations).
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#relevant-animations-section)
+    pub(crate) fn is_relevant(&self) -> bool {
+        let base = self.inner.borrow();
+        let sample = base.effect.sample(base.current_time(), base.playback_rate);
+        sample.is_current(base.playback_rate) || sample.is_in_effect()
+    }
+}
+
+impl AsEventTarget for Animation {
+    fn cast(&self) -> &EventTarget {
+        unsafe { &(*self.inner.as_ptr()).event_target }
+    }
+
+    fn cast_mut(&mut self) -> &mut EventTarget {
+        unsafe { &mut (*self.inner.as_ptr()).event_target }
+    }
+}
+
+/// The keyframes and timing that an animation plays on an element.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/KeyframeEffect)
+#[derive(Debug, Clone)]
+pub struct KeyframeEffect {
+    animation: Animation,
+}
+
+impl KeyframeEffect {
+    /// Returns the element that the effect animates.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/KeyframeEffect/target)
+    pub fn target(&self) -> Element {
+        self.animation.inner.borrow().effect.target.clone_ref()
+    }
+    /// Returns the keyframes of the effect, with their computed offsets.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/KeyframeEffect/getKeyframes)
+    pub fn get_keyframes(&self) -> Vec<Keyframe> {
+        self.animation.inner.borrow().effect.keyframes()
+    }
+    /// Replaces the keyframes of the effect.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/KeyframeEffect/setKeyframes)
+    pub fn set_keyframes(&mut self, keyframes: Vec<Keyframe>) -> Result<(), DOMException> {
+        crate::layout::invalidate(AsNode::cast(&self.target()));
+        self.animation
+            .inner
+            .borrow_mut()
+            .effect
+            .set_keyframes(keyframes)
+    }
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/AnimationEffect/getTiming)
+    pub fn get_timing(&self) -> EffectTiming {
+        self.animation.inner.borrow().effect.timing.clone()
+    }
+    /// Changes the timing of the effect.
+    ///
+    /// Returns a `TypeError` if a duration, delay or iteration count is out of range, or if the easing is not valid.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/AnimationEffect/updateTiming)
+    pub fn update_timing(&mut self, timing: EffectTiming) -> Result<(), DOMException> {
+        let mut base = self.animation.inner.borrow_mut();
+        base.effect.set_timing(timing)?;
+        let just_finished = base.update_finished_state(false);
+        drop(base);
+        self.animation.notify(just_finished);
+        Ok(())
+    }
+    /// Returns the timing of the effect at the current time of its animation.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/AnimationEffect/getComputedTiming)
+    pub fn get_computed_timing(&self) -> ComputedEffectTiming {
+        let base = self.animation.inner.borrow();
+        base.effect
+            .sample(base.current_time(), base.playback_rate)
+            .timing
+    }
+}
+
+/// Updates the animations of a document to the time on its virtual clock, and fires the `finish` events of the ones that finish, then the events of CSS animations and transitions in the order they happened.
+///
+/// Animations that no longer apply are dropped once nothing else refers to them. CSS animations are kept until they are cancelled, so that they do not start again while their name is in `animation-name`.
+///
+/// [Reference](https://drafts.csswg.org/web-animations-1/#update-animations-and-send-events)
+pub(crate) fn update_animations(document: &Document) {
+    let time = document.state().time;
+    let animations = document.state().animations.clone();
+    let mut events = vec![];
+    for mut animation in animations {
+        let just_finished = animation.inner.borrow_mut().update_finished_state(false);
+        animation.notify(just_finished);
+        events.extend(declarative::take_events(&animation, time));
+    }
+    // The animations are taken out while they are checked, since checking them can read the document.
+    let mut animations = std::mem::take(&mut document.state_mut().animations);
+    animations.retain(|animation| {
+        let is_kept = match animation.inner.borrow().source {
+            Source::Animation(_) => animation.play_state() != AnimationPlayState::Idle,
+            _ => animation.is_relevant(),
+        };
+        Rc::strong_count(&animation.inner) > 1 || is_kept
+    });
+    let mut state = document.state_mut();
+    // Animations that were created meanwhile come after the ones that were there.
+    animations.append(&mut state.animations);
+    state.animations = animations;
+    events.sort_by(|(time, _, _), (other, _, _)| time.total_cmp(other));
+    for (_, target, mut event) in events {
+        target.clone_ref().dispatch_event(&mut event);
+    }
+}
+
+/// Returns how either the transitions or the other animations of a document interpolate the properties of an element, in the order they apply: CSS animations, then the ones created by scripts.
+///
+/// [Reference](https://drafts.csswg.org/css-animations-2/#animation-composite-order)
+pub(crate) fn animated_values(
+    element: &Element,
+    transitions: bool,
+) -> HashMap<String, Vec<Interpolation>> {
+    let mut values: HashMap<String, Vec<Interpolation>> = HashMap::new();
+    let Some(document) = element.owner_document() else {
+        return values;
+    };
+    let order = |animation: &&Animation| match animation.inner.borrow().source {
+        Source::Transition(_) | Source::Animation(_) => 0,
+        Source::Script => 1,
+    };
+    let all_animations = document.state().animations.clone();
+    let mut animations: Vec<&Animation> = all_animations
+        .iter()
+        .filter(|animation| {
+            let is_transition = matches!(animation.inner.borrow().source, Source::Transition(_));
+            is_transition == transitions
+        })
+        .collect();
+    animations.sort_by_key(order);
+    for animation in animations {
+        let base = animation.inner.borrow();
+        if base.effect.target != *element {
+            continue;
+        }
+        let sample = base.effect.sample(base.current_time(), base.playback_rate);
+        let Some(progress) = sample.timing.progress else {
+            continue;
+        };
+        for (name, interpolation) in base.effect.values(progress) {
+            values.entry(name).or_default().push(interpolation);
+        }
+    }
+    values
+}
+
+/// Returns the relevant animations of a document whose targets match a filter, in the order they apply, once the CSS animations and transitions are up to date with the styles.
+pub(crate) fn animations_of(
+    document: &Document,
+    filter: impl Fn(&Element) -> bool,
+) -> Vec<Animation> {
+    let _ = update_style(document);
+    let animations = document.state().animations.clone();
+    animations
+        .into_iter()
+        .filter(|animation| filter(&animation.inner.borrow().effect.target))
+        .filter(|animation| animation.is_relevant())
+        .collect()
+}
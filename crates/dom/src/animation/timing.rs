@@ -0,0 +1,278 @@
+//! The timing model of animation effects, which turns the time of an animation into the progress of its effect.
+//!
+//! [Reference](https://drafts.csswg.org/web-animations-1/#timing-model)
+use super::easing::TimingFunction;
+use crate::DOMException;
+
+/// Whether an effect applies before and after its active interval.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/KeyframeEffect/KeyframeEffect#fill)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillMode {
+    /// Only while the effect is active.
+    None,
+    /// Also after the effect ends, with its final values.
+    Forwards,
+    /// Also during the delay before it starts, with its initial values.
+    Backwards,
+    /// Both before it starts and after it ends.
+    Both,
+    /// The same as `None` for keyframe effects.
+    #[default]
+    Auto,
+}
+
+/// The direction in which the iterations of an effect play.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/KeyframeEffect/KeyframeEffect#direction)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaybackDirection {
+    #[default]
+    Normal,
+    Reverse,
+    /// Forwards on even iterations, and backwards on odd ones.
+    Alternate,
+    /// Backwards on even iterations, and forwards on odd ones.
+    AlternateReverse,
+}
+
+/// The timing of an animation effect. Times are in milliseconds.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/AnimationEffect/getTiming)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectTiming {
+    /// The time before the effect starts.
+    pub delay: f64,
+    /// The time after the effect ends before the animation finishes.
+    pub end_delay: f64,
+    pub fill: FillMode,
+    /// Where in its iterations the effect starts, like `0.5` to start halfway through the first one.
+    pub iteration_start: f64,
+    /// How many times the effect repeats, which can be fractional or `f64::INFINITY`.
+    pub iterations: f64,
+    /// The duration of a single iteration.
+    pub duration: f64,
+    pub direction: PlaybackDirection,
+    /// The easing function applied to every iteration, like `ease-in` or `steps(4)`.
+    pub easing: String,
+}
+
+impl Default for EffectTiming {
+    fn default() -> Self {
+        EffectTiming {
+            delay: 0.0,
+            end_delay: 0.0,
+            fill: FillMode::Auto,
+            iteration_start: 0.0,
+            iterations: 1.0,
+            duration: 0.0,
+            direction: PlaybackDirection::Normal,
+            easing: String::from("linear"),
+        }
+    }
+}
+
+impl EffectTiming {
+    /// Checks that the timing is valid, and returns its easing function.
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#update-the-timing-properties-of-an-animation-effect)
+    pub(crate) fn validate(&self) -> Result<TimingFunction, DOMException> {
+        if !self.delay.is_finite() || !self.end_delay.is_finite() {
+            return Err(DOMException::TypeError(String::from(
+                "Delays must be finite",
+            )));
+        }
+        if !self.iteration_start.is_finite() || self.iteration_start < 0.0 {
+            return Err(DOMException::TypeError(String::from(
+                "The iteration start must be a finite number that is not negative",
+            )));
+        }
+        if self.iterations.is_nan() || self.iterations < 0.0 {
+            return Err(DOMException::TypeError(String::from(
+                "The iteration count must not be negative",
+            )));
+        }
+        if !self.duration.is_finite() || self.duration < 0.0 {
+            return Err(DOMException::TypeError(String::from(
+                "The duration must be a finite number that is not negative",
+            )));
+        }
+        TimingFunction::parse(&self.easing).ok_or_else(|| {
+            DOMException::TypeError(format!("'{}' is not a valid easing", self.easing))
+        })
+    }
+
+    /// Returns the duration of all the iterations together.
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#active-duration)
+    pub(crate) fn active_duration(&self) -> f64 {
+        match self.duration == 0.0 || self.iterations == 0.0 {
+            true => 0.0,
+            false => self.duration * self.iterations,
+        }
+    }
+
+    /// Returns the time at which the effect ends, including its delays.
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#end-time)
+    pub(crate) fn end_time(&self) -> f64 {
+        (self.delay + self.active_duration() + self.end_delay).max(0.0)
+    }
+}
+
+/// The timing of an animation effect at the current time of its animation.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/AnimationEffect/getComputedTiming)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComputedEffectTiming {
+    /// The time at which the effect ends, including its delays.
+    pub end_time: f64,
+    /// The duration of all the iterations together.
+    pub active_duration: f64,
+    /// The current time of the animation, or `None` if it is idle.
+    pub local_time: Option<f64>,
+    /// The progress through the current iteration, from zero to one after easing, or `None` if the effect does not apply.
+    pub progress: Option<f64>,
+    /// The index of the current iteration, starting from zero, or `None` if the effect does not apply.
+    pub current_iteration: Option<f64>,
+}
+
+/// Where the local time of an effect is relative to its active interval.
+///
+/// [Reference](https://drafts.csswg.org/web-animations-1/#animation-effect-phases-and-states)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Phase {
+    /// The animation is idle.
+    #[default]
+    Idle,
+    Before,
+    Active,
+    After,
+}
+
+/// The timing of an effect at a point in time, along with the phase it is in.
+pub(crate) struct Sample {
+    pub(crate) phase: Phase,
+    pub(crate) timing: ComputedEffectTiming,
+}
+
+impl Sample {
+    /// Returns true if the effect is current: it is active or will become active as its animation plays.
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#current)
+    pub(crate) fn is_current(&self, playback_rate: f64) -> bool {
+        match self.phase {
+            Phase::Idle => false,
+            Phase::Before => playback_rate >= 0.0,
+            Phase::Active => true,
+            Phase::After => playback_rate < 0.0,
+        }
+    }
+
+    /// Returns true if the effect applies, either because it is active or because it fills.
+    ///
+    /// [Reference](https://drafts.csswg.org/web-animations-1/#in-effect)
+    pub(crate) fn is_in_effect(&self) -> bool {
+        self.timing.progress.is_some()
+    }
+}
+
+/// Computes the timing of an effect at a local time, the current time of its animation, which plays at a playback rate.
+///
+/// [Reference](https://drafts.csswg.org/web-animations-1/#calculating-the-active-time)
+pub(crate) fn sample(
+    timing: &EffectTiming,
+    easing: &TimingFunction,
+    local_time: Option<f64>,
+    playback_rate: f64,
+) -> Sample {
+    let active_duration = timing.active_duration();
+    let end_time = timing.end_time();
+    let mut computed = ComputedEffectTiming {
+        end_time,
+        active_duration,
+        local_time,
+        progress: None,
+        current_iteration: None,
+    };
+    let Some(local_time) = local_time else {
+        return Sample {
+            phase: Phase::Idle,
+            timing: computed,
+        };
+    };
+    let is_backwards = playback_rate < 0.0;
+    let before_active = timing.delay.min(end_time).max(0.0);
+    let active_after = (timing.delay + active_duration).min(end_time).max(0.0);
+    let phase = if local_time < before_active || (is_backwards && local_time == before_active) {
+        Phase::Before
+    } else if local_time > active_after || (!is_backwards && local_time == active_after) {
+        Phase::After
+    } else {
+        Phase::Active
+    };
+    let fills_backwards = matches!(timing.fill, FillMode::Backwards | FillMode::Both);
+    let fills_forwards = matches!(timing.fill, FillMode::Forwards | FillMode::Both);
+    let active_time = match phase {
+        Phase::Before if fills_backwards => Some((local_time - timing.delay).max(0.0)),
+        Phase::Active => Some(local_time - timing.delay),
+        Phase::After if fills_forwards => {
+            Some((local_time - timing.delay).min(active_duration).max(0.0))
+        }
+        _ => None,
+    };
+    let Some(active_time) = active_time else {
+        return Sample {
+            phase,
+            timing: computed,
+        };
+    };
+
+    // [Reference](https://drafts.csswg.org/web-animations-1/#calculating-the-overall-progress)
+    let overall_progress = match timing.duration == 0.0 {
+        true => match phase {
+            Phase::Before => timing.iteration_start,
+            _ => timing.iteration_start + timing.iterations,
+        },
+        false => active_time / timing.duration + timing.iteration_start,
+    };
+    // [Reference](https://drafts.csswg.org/web-animations-1/#calculating-the-simple-iteration-progress)
+    let mut simple_progress = match overall_progress.is_infinite() {
+        true => timing.iteration_start % 1.0,
+        false => overall_progress % 1.0,
+    };
+    if simple_progress == 0.0
+        && matches!(phase, Phase::Active | Phase::After)
+        && active_time == active_duration
+        && timing.iterations != 0.0
+    {
+        simple_progress = 1.0;
+    }
+    // [Reference](https://drafts.csswg.org/web-animations-1/#calculating-the-current-iteration)
+    let current_iteration = if phase == Phase::After && timing.iterations.is_infinite() {
+        f64::INFINITY
+    } else if simple_progress == 1.0 {
+        overall_progress.floor() - 1.0
+    } else {
+        overall_progress.floor()
+    };
+    // [Reference](https://drafts.csswg.org/web-animations-1/#calculating-the-directed-progress)
+    let is_forwards = match timing.direction {
+        PlaybackDirection::Normal => true,
+        PlaybackDirection::Reverse => false,
+        PlaybackDirection::Alternate => current_iteration % 2.0 == 0.0,
+        PlaybackDirection::AlternateReverse => current_iteration % 2.0 != 0.0,
+    };
+    let directed_progress = match is_forwards {
+        true => simple_progress,
+        false => 1.0 - simple_progress,
+    };
+    // [Reference](https://drafts.csswg.org/web-animations-1/#calculating-the-transformed-progress)
+    let before = (is_forwards && phase == Phase::Before) || (!is_forwards && phase == Phase::After);
+    computed.progress = Some(easing.apply(directed_progress, before));
+    computed.current_iteration = Some(current_iteration);
+    Sample {
+        phase,
+        timing: computed,
+    }
+}
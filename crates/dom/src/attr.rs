@@ -89,7 +89,14 @@ impl Attr {
         }
     }
     pub fn set_value(&mut self, value: String) {
-        self.__value = Some(value);
+        let old_value = self.__value.replace(value);
+        if let Some(base) = self.owner_element_ref.as_ref().and_then(Weak::upgrade) {
+            Element::with_base(base).handle_attribute_changes(
+                &self.__name,
+                old_value.as_deref(),
+                self.__value.as_deref(),
+            );
+        }
     }
     #[allow(deprecated)]
     #[deprecated]
@@ -0,0 +1,97 @@
+use crate::{domitem::DOMItem, AsChildNode, AsNode, DOMException};
+
+/// The methods shared by [`Text`] and [`Comment`] nodes, which contain character data.
+///
+/// [`Text`]: crate::Text
+/// [`Comment`]: crate::Comment
+///
+/// MDN Reference: [`CharacterData`](https://developer.mozilla.org/docs/Web/API/CharacterData)
+pub trait AsCharacterData: AsNode + AsChildNode {
+    /// Returns the textual data contained in the node.
+    ///
+    /// MDN Reference: [`CharacterData.data`](https://developer.mozilla.org/docs/Web/API/CharacterData/data)
+    fn data(&self) -> &str {
+        &AsNode::cast(self).base().data
+    }
+    /// Sets the textual data contained in the node.
+    ///
+    /// MDN Reference: [`CharacterData.data`](https://developer.mozilla.org/docs/Web/API/CharacterData/data)
+    fn set_data(&mut self, value: &str) {
+        AsNode::cast(self).base().data = value.to_owned();
+        crate::layout::invalidate(AsNode::cast(self));
+    }
+    /// Returns the number of UTF-16 code units in the data of the node.
+    ///
+    /// MDN Reference: [`CharacterData.length`](https://developer.mozilla.org/docs/Web/API/CharacterData/length)
+    fn length(&self) -> usize {
+        self.data().encode_utf16().count()
+    }
+    /// Appends a string to the data of the node.
+    ///
+    /// MDN Reference: [`CharacterData.appendData()`](https://developer.mozilla.org/docs/Web/API/CharacterData/appendData)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut text = document.create_text_node("Hello");
+    /// text.append_data(", world");
+    ///
+    /// assert_eq!(text.data(), "Hello, world");
+    /// ```
+    fn append_data(&mut self, data: &str) {
+        AsNode::cast(self).base().data.push_str(data);
+        crate::layout::invalidate(AsNode::cast(self));
+    }
+    /// Returns a part of the data of the node, starting at an offset and spanning a number of UTF-16 code units.
+    ///
+    /// Returns an `IndexSizeError` if the offset is greater than the length of the data.
+    ///
+    /// MDN Reference: [`CharacterData.substringData()`](https://developer.mozilla.org/docs/Web/API/CharacterData/substringData)
+    fn substring_data(&self, offset: usize, count: usize) -> Result<String, DOMException> {
+        let units: Vec<u16> = self.data().encode_utf16().collect();
+        if offset > units.len() {
+            return Err(DOMException::IndexSizeError(String::from(
+                "Offset is greater than the length of the data.",
+            )));
+        }
+        let end = (offset + count).min(units.len());
+        Ok(String::from_utf16_lossy(&units[offset..end]))
+    }
+    /// Replaces a number of UTF-16 code units, starting at an offset, with a string.
+    ///
+    /// Returns an `IndexSizeError` if the offset is greater than the length of the data.
+    ///
+    /// MDN Reference: [`CharacterData.replaceData()`](https://developer.mozilla.org/docs/Web/API/CharacterData/replaceData)
+    fn replace_data(
+        &mut self,
+        offset: usize,
+        count: usize,
+        data: &str,
+    ) -> Result<(), DOMException> {
+        let units: Vec<u16> = self.data().encode_utf16().collect();
+        if offset > units.len() {
+            return Err(DOMException::IndexSizeError(String::from(
+                "Offset is greater than the length of the data.",
+            )));
+        }
+        let end = (offset + count).min(units.len());
+        let mut replaced = String::from_utf16_lossy(&units[..offset]);
+        replaced.push_str(data);
+        replaced.push_str(&String::from_utf16_lossy(&units[end..]));
+        self.set_data(&replaced);
+        Ok(())
+    }
+    /// Inserts a string at an offset in the data of the node.
+    ///
+    /// MDN Reference: [`CharacterData.insertData()`](https://developer.mozilla.org/docs/Web/API/CharacterData/insertData)
+    fn insert_data(&mut self, offset: usize, data: &str) -> Result<(), DOMException> {
+        self.replace_data(offset, 0, data)
+    }
+    /// Removes a number of UTF-16 code units, starting at an offset, from the data of the node.
+    ///
+    /// MDN Reference: [`CharacterData.deleteData()`](https://developer.mozilla.org/docs/Web/API/CharacterData/deleteData)
+    fn delete_data(&mut self, offset: usize, count: usize) -> Result<(), DOMException> {
+        self.replace_data(offset, count, "")
+    }
+}
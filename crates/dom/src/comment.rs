@@ -0,0 +1,44 @@
+use crate::{
+    character_data::AsCharacterData, domitem::DOMItem, AsChildNode, AsEventTarget, AsNode,
+    EventTarget, Node,
+};
+
+/// A node that represents a comment in the markup.
+///
+/// MDN Reference: [`Comment`](https://developer.mozilla.org/docs/Web/API/Comment)
+#[derive(Debug)]
+pub struct Comment {
+    pub(crate) node: Node,
+}
+
+impl<T: AsNode> PartialEq<T> for Comment {
+    fn eq(&self, other: &T) -> bool {
+        &self.node == AsNode::cast(other)
+    }
+}
+impl AsEventTarget for Comment {
+    fn cast(&self) -> &EventTarget {
+        AsEventTarget::cast(&self.node)
+    }
+
+    fn cast_mut(&mut self) -> &mut EventTarget {
+        AsEventTarget::cast_mut(&mut self.node)
+    }
+}
+impl AsNode for Comment {
+    fn cast(&self) -> &Node {
+        &self.node
+    }
+
+    fn cast_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn clone_node(&self, deep: bool) -> Self {
+        Comment {
+            node: self.node.clone_node(deep),
+        }
+    }
+}
+impl AsChildNode for Comment {}
+impl AsCharacterData for Comment {}
@@ -0,0 +1,1236 @@
+//! The cascade, which finds the declarations that apply to an element, and the computation of property values from them.
+//!
+//! [Reference](https://drafts.csswg.org/css-cascade/)
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    animation::{self, interpolate, Interpolation},
+    document::WeakDocumentRef,
+    domitem::DOMItem,
+    html_element::{link_element_sheet, style_element_sheet},
+    node::{
+        trees::{descendant_nodes, root_of},
+        NodeBase,
+    },
+    AsElement, AsNode, Element, Node, Viewport,
+};
+
+use super::{
+    custom_properties::{contains_var, substitute, DataType, Syntax, SyntaxComponent},
+    declaration::{Declaration, DeclarationBlock},
+    parser::{is_custom_property, split_commas, split_components},
+    properties::{self, PROPERTIES},
+    selector::{MatchContext, PseudoElement, Specificity},
+    stylesheet::{
+        CSSKeyframesRule, CSSPropertyRule, CSSRule, CSSStyleRule, CSSStyleSheet, ParseContext,
+    },
+    values::{parse_color, serialize_color, split_dimension},
+};
+
+thread_local! {
+    /// The default styles of HTML elements.
+    static USER_AGENT_SHEET: CSSStyleSheet = CSSStyleSheet::parse(
+        include_str!("html.css"),
+        None,
+        String::new(),
+        ParseContext {
+            base_url: String::new(),
+            loader: None,
+            depth: 0,
+            document: WeakDocumentRef::default(),
+        },
+    );
+}
+
+/// The font size of the initial value `medium`, in pixels.
+const MEDIUM_FONT_SIZE: f64 = 16.0;
+
+/// The source of a style sheet.
+///
+/// [Reference](https://drafts.csswg.org/css-cascade/#cascading-origins)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Origin {
+    UserAgent,
+    Author,
+}
+
+/// A style rule in the order the cascade sees it, along with the cascade layer it is in.
+struct CollectedRule {
+    rule: CSSStyleRule,
+    origin: Origin,
+    /// The position of the rule's layer among its sibling layers, at each level of nesting, ending in `usize::MAX` for the rules directly in the layer. Unlayered rules are `[usize::MAX]`, so they come after every layer.
+    layer: Vec<usize>,
+}
+
+/// The cascade layers declared in a tree, in the order they are first declared.
+///
+/// [Reference](https://drafts.csswg.org/css-cascade-5/#layer-ordering)
+#[derive(Default)]
+struct Layers {
+    /// The position of each layer, by its full dotted name.
+    positions: HashMap<String, Vec<usize>>,
+    /// The number of sublayers in each layer, by its full dotted name.
+    sublayers: HashMap<String, usize>,
+    anonymous: usize,
+}
+
+/// A layer that rules are being collected in: its full name and its position.
+type Layer = (String, Vec<usize>);
+
+impl Layers {
+    /// Returns a layer nested in another, declaring it if it is new. An empty name declares a new anonymous layer.
+    fn enter(&mut self, (parent, position): &Layer, name: &str) -> Layer {
+        let mut layer = (parent.clone(), position.clone());
+        let anonymous;
+        let segments: Vec<&str> = if name.is_empty() {
+            self.anonymous += 1;
+            // Anonymous layers can never be named again, so they get a name no rule can write.
+            anonymous = format!("\0{}", self.anonymous);
+            vec![&anonymous]
+        } else {
+            name.split('.').map(str::trim).collect()
+        };
+        for segment in segments {
+            let full_name = match layer.0.is_empty() {
+                true => segment.to_owned(),
+                false => format!("{}.{segment}", layer.0),
+            };
+            let position = match self.positions.get(&full_name) {
+                Some(position) => position.clone(),
+                None => {
+                    let index = self.sublayers.entry(layer.0.clone()).or_default();
+                    let mut position = layer.1.clone();
+                    position.push(*index);
+                    *index += 1;
+                    self.positions.insert(full_name.clone(), position.clone());
+                    position
+                }
+            };
+            layer = (full_name, position);
+        }
+        layer
+    }
+}
+
+/// The rules collected from the style sheets of a tree, in the order the cascade sees them.
+#[derive(Default)]
+struct Collection {
+    rules: Vec<CollectedRule>,
+    layers: Layers,
+    /// The custom properties registered with `@property`, by name. Later registrations replace earlier ones.
+    registrations: HashMap<String, CSSPropertyRule>,
+    /// The `@keyframes` rules, by name. Later rules replace earlier ones.
+    keyframes: HashMap<String, CSSKeyframesRule>,
+    /// The viewport that the media queries of `@media` rules and sheets are evaluated against.
+    viewport: Viewport,
+}
+
+impl Collection {
+    /// Collects the style rules of a sheet that apply to the current environment.
+    fn add_sheet(&mut self, sheet: &CSSStyleSheet, origin: Origin, layer: &Layer) {
+        let inner = sheet.inner.borrow();
+        if inner.disabled || !inner.media.matches(&self.viewport) {
+            return;
+        }
+        self.add_rules(&inner.rules.borrow(), origin, layer);
+    }
+
+    fn add_rules(&mut self, rules: &[CSSRule], origin: Origin, layer: &Layer) {
+        for rule in rules {
+            match rule {
+                CSSRule::Style(rule) => self.rules.push(CollectedRule {
+                    rule: rule.clone(),
+                    origin,
+                    layer: [layer.1.as_slice(), &[usize::MAX]].concat(),
+                }),
+                CSSRule::Import(rule) => {
+                    let import = &rule.inner;
+                    let Some(sheet) = &import.style_sheet else {
+                        continue;
+                    };
+                    let supported = import
+                        .supports_condition
+                        .as_deref()
+                        .is_none_or(super::supports::evaluate);
+                    if !supported || !import.media.matches(&self.viewport) {
+                        continue;
+                    }
+                    match &import.layer {
+                        Some(name) => {
+                            let layer = self.layers.enter(layer, name);
+                            self.add_sheet(sheet, origin, &layer);
+                        }
+                        None => self.add_sheet(sheet, origin, layer),
+                    }
+                }
+                CSSRule::Media(rule) if rule.media.matches(&self.viewport) => {
+                    self.add_rules(&rule.rules.borrow(), origin, layer)
+                }
+                CSSRule::Supports(rule) if rule.matches() => {
+                    self.add_rules(&rule.rules.borrow(), origin, layer)
+                }
+                CSSRule::LayerBlock(rule) => {
+                    let layer = self.layers.enter(layer, &rule.name);
+                    self.add_rules(&rule.rules.borrow(), origin, &layer);
+                }
+                CSSRule::LayerStatement(rule) => {
+                    for name in &rule.names {
+                        self.layers.enter(layer, name);
+                    }
+                }
+                CSSRule::Property(rule) => {
+                    self.registrations
+                        .insert(rule.name().to_owned(), rule.clone());
+                }
+                CSSRule::Keyframes(rule) => {
+                    self.keyframes.insert(rule.name(), rule.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Returns the rules from the style sheets in a tree, which is either a document or a shadow root.
+fn collect_tree(root: &impl AsNode, viewport: &Viewport) -> Collection {
+    let mut collection = Collection {
+        viewport: viewport.clone(),
+        ..Default::default()
+    };
+    let unlayered = (String::new(), vec![]);
+    for node in descendant_nodes(root) {
+        if let Some(sheet) = style_element_sheet(node).or_else(|| link_element_sheet(node)) {
+            collection.add_sheet(&sheet, Origin::Author, &unlayered);
+        }
+    }
+    collection
+}
+
+/// The place a declaration has in the cascade. Declarations that sort later win.
+///
+/// [Reference](https://drafts.csswg.org/css-cascade-5/#cascade-sort)
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Priority {
+    /// The origin and importance: user agent normal, author normal, author important, then user agent important.
+    origin: u8,
+    /// How deeply nested the shadow tree of the declaration is. Outer trees win for normal declarations, so this is negated for them.
+    context: isize,
+    /// Whether the declaration comes from the element's `style` attribute.
+    element_attached: bool,
+    /// The position of the layer. Earlier layers win for important declarations, so each position is inverted for them.
+    layer: Vec<usize>,
+    specificity: Specificity,
+    order: usize,
+}
+
+impl Priority {
+    fn origin_of(&self) -> Origin {
+        match self.origin {
+            0 | 3 => Origin::UserAgent,
+            _ => Origin::Author,
+        }
+    }
+}
+
+/// The winning declarations for an element.
+#[derive(Default)]
+struct Cascaded {
+    values: HashMap<String, String>,
+    /// The winning declarations from the user agent origin, which `revert` rolls back to.
+    user_agent: HashMap<String, String>,
+    /// The shorthands that set longhands with values that are still pending `var()` substitution, by longhand.
+    shorthands: HashMap<String, String>,
+    /// The animations of the properties that no `!important` declaration wins over, in the order they apply.
+    animations: HashMap<String, Vec<Interpolation>>,
+    /// The transitions of the properties, which win over every declaration.
+    transitions: HashMap<String, Vec<Interpolation>>,
+}
+
+/// The computed values of all the properties of an element.
+///
+/// [Reference](https://drafts.csswg.org/css-cascade/#computed)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ComputedStyle {
+    values: HashMap<&'static str, String>,
+    /// The custom properties, except the ones with the guaranteed-invalid value.
+    custom: HashMap<String, String>,
+    /// The computed font size of the root element, which `rem` lengths are relative to.
+    root_font_size: f64,
+}
+
+impl ComputedStyle {
+    /// Returns the computed value of a longhand or custom property, or an empty string if it is not supported or has no value.
+    pub(crate) fn get(&self, name: &str) -> &str {
+        match is_custom_property(name) {
+            true => self.custom.get(name),
+            false => self.values.get(name),
+        }
+        .map_or("", String::as_str)
+    }
+
+    /// Returns the computed font size in pixels.
+    pub(crate) fn font_size(&self) -> f64 {
+        px_value(self.get("font-size")).unwrap_or(MEDIUM_FONT_SIZE)
+    }
+
+    /// Returns the declaration block of all the computed values, with the longhands followed by the custom properties in alphabetical order.
+    pub(crate) fn to_block(&self) -> DeclarationBlock {
+        let mut custom: Vec<_> = self.custom.iter().collect();
+        custom.sort();
+        let declarations = PROPERTIES
+            .iter()
+            .map(|property| (property.name, self.get(property.name)))
+            .chain(
+                custom
+                    .into_iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str())),
+            )
+            .map(|(name, value)| Declaration {
+                name: name.to_owned(),
+                value: value.to_owned(),
+                important: false,
+            })
+            .collect();
+        DeclarationBlock { declarations }
+    }
+}
+
+/// Resolves the styles of the elements in a document.
+///
+/// The resolver collects the style rules when it is created and remembers the styles it computes, so it should only live as long as the document does not change.
+#[derive(Default)]
+pub(crate) struct StyleResolver {
+    user_agent: Vec<CollectedRule>,
+    /// The rules of each tree, keyed by the tree's root.
+    trees: RefCell<HashMap<*mut NodeBase, Rc<Collection>>>,
+    computed: RefCell<HashMap<*mut NodeBase, Rc<ComputedStyle>>>,
+    viewport: Viewport,
+    /// Whether the styles include the values of animations and transitions.
+    animated: bool,
+}
+
+impl StyleResolver {
+    pub(crate) fn new(viewport: Viewport) -> Self {
+        let mut user_agent = Collection::default();
+        USER_AGENT_SHEET.with(|sheet| {
+            user_agent.add_sheet(sheet, Origin::UserAgent, &(String::new(), vec![]));
+        });
+        StyleResolver {
+            user_agent: user_agent.rules,
+            viewport,
+            animated: true,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a resolver whose styles leave out animations and transitions, which are the styles that transitions go from and to.
+    pub(crate) fn without_animations(viewport: Viewport) -> Self {
+        StyleResolver {
+            animated: false,
+            ..Self::new(viewport)
+        }
+    }
+
+    /// Returns the `@keyframes` rule with a name that applies to an element, from its own tree or else from the document.
+    pub(crate) fn keyframes(&self, element: &Element, name: &str) -> Option<CSSKeyframesRule> {
+        let root = root_of(element);
+        if let Some(rule) = self.tree(&root).keyframes.get(name) {
+            return Some(rule.clone());
+        }
+        let document = element.owner_document()?;
+        self.tree(&document).keyframes.get(name).cloned()
+    }
+
+    /// Returns the computed style of an element, or of one of its pseudo-elements.
+    pub(crate) fn compute(
+        &self,
+        element: &Element,
+        pseudo_element: Option<&PseudoElement>,
+    ) -> Rc<ComputedStyle> {
+        let key = AsNode::cast(element).get_base_ptr();
+        if pseudo_element.is_none() {
+            if let Some(style) = self.computed.borrow().get(&key) {
+                return style.clone();
+            }
+        }
+        let parent = match pseudo_element {
+            Some(_) => Some(self.compute(element, None)),
+            None => inheritance_parent(element).map(|parent| self.compute(&parent, None)),
+        };
+        let cascaded = self.cascade(element, pseudo_element);
+        let is_root = pseudo_element.is_none()
+            && element
+                .parent_node()
+                .is_some_and(|parent| parent.node_type() == Node::DOCUMENT_NODE);
+        // Custom properties are registered for the whole document.
+        let registrations = match element.owner_document() {
+            Some(document) => self.tree(&document),
+            None => Rc::default(),
+        };
+        let style = Rc::new(compute_values(
+            &cascaded,
+            parent.as_deref(),
+            is_root,
+            &registrations.registrations,
+            &self.viewport,
+        ));
+        if pseudo_element.is_none() {
+            self.computed.borrow_mut().insert(key, style.clone());
+        }
+        style
+    }
+
+    /// Returns the style of an anonymous box, which inherits from the box it is in and has initial values for everything else.
+    pub(crate) fn anonymous(&self, parent: &ComputedStyle) -> Rc<ComputedStyle> {
+        Rc::new(compute_values(
+            &Cascaded::default(),
+            Some(parent),
+            false,
+            &HashMap::new(),
+            &self.viewport,
+        ))
+    }
+
+    fn tree(&self, root: &impl AsNode) -> Rc<Collection> {
+        let key = AsNode::cast(root).get_base_ptr();
+        if let Some(collection) = self.trees.borrow().get(&key) {
+            return collection.clone();
+        }
+        let collection = Rc::new(collect_tree(root, &self.viewport));
+        self.trees.borrow_mut().insert(key, collection.clone());
+        collection
+    }
+
+    /// Finds the winning declaration of every property that is declared for an element.
+    fn cascade(&self, element: &Element, pseudo_element: Option<&PseudoElement>) -> Cascaded {
+        let mut matched = vec![];
+        let mut add_rules =
+            |rules: &[CollectedRule],
+             depth: usize,
+             matches: &dyn Fn(&CSSStyleRule) -> Option<Specificity>| {
+                for (order, collected) in rules.iter().enumerate() {
+                    if let Some(specificity) = matches(&collected.rule) {
+                        matched.push(MatchedBlock {
+                            block: collected.rule.inner.borrow().style.borrow().clone(),
+                            origin: collected.origin,
+                            depth,
+                            element_attached: false,
+                            layer: collected.layer.clone(),
+                            specificity,
+                            order,
+                        });
+                    }
+                }
+            };
+        add_rules(&self.user_agent, 0, &|rule| {
+            let selectors = &rule.inner.borrow().selectors;
+            selectors.match_element(element, pseudo_element, &MatchContext::default())
+        });
+
+        // The rules of the element's own tree.
+        let root = root_of(element);
+        let depth = tree_depth(&root);
+        let context = MatchContext {
+            shadow_host: crate::slot::host_of_shadow_root(&root),
+            ..Default::default()
+        };
+        add_rules(&self.tree(&root).rules, depth, &|rule| {
+            let selectors = &rule.inner.borrow().selectors;
+            selectors.match_element(element, pseudo_element, &context)
+        });
+
+        // The rules of the element's shadow tree, which can match it with `:host`.
+        if let Some(shadow_root) = crate::slot::shadow_root_of(element) {
+            let context = MatchContext {
+                shadow_host: Some(element.clone_ref()),
+                ..Default::default()
+            };
+            add_rules(&self.tree(&shadow_root).rules, depth + 1, &|rule| {
+                let selectors = &rule.inner.borrow().selectors;
+                selectors.match_element(element, pseudo_element, &context)
+            });
+        }
+
+        // The rules of the shadow tree that the element is slotted into, which can match it with `::slotted()`.
+        let slot = crate::slot::assigned_slot_of(element).and_then(|slot| {
+            element
+                .owner_document()?
+                .lookup_html_element(AsNode::cast(&slot).get_base_ptr())
+        });
+        if let (Some(slot), None) = (slot, pseudo_element) {
+            let root = root_of(&slot);
+            let context = MatchContext {
+                shadow_host: crate::slot::host_of_shadow_root(&root),
+                ..Default::default()
+            };
+            add_rules(&self.tree(&root).rules, tree_depth(&root), &|rule| {
+                let selectors = &rule.inner.borrow().selectors;
+                selectors.match_slotted(&slot, element, &context)
+            });
+        }
+
+        // The `style` attribute.
+        if let (Some(style), None) = (element.get_attribute("style"), pseudo_element) {
+            matched.push(MatchedBlock {
+                block: DeclarationBlock::parse(style),
+                origin: Origin::Author,
+                depth,
+                element_attached: true,
+                layer: vec![usize::MAX],
+                specificity: Specificity::default(),
+                order: 0,
+            });
+        }
+
+        let mut declarations: Vec<(Priority, &Declaration)> = matched
+            .iter()
+            .flat_map(|matched| {
+                matched
+                    .block
+                    .declarations
+                    .iter()
+                    // Declarations that are invalid for their property never take part in the cascade.
+                    .filter(|declaration| is_parsable(declaration))
+                    .map(|declaration| (matched.priority(declaration.important), declaration))
+            })
+            .collect();
+        declarations.sort_by(|a, b| a.0.cmp(&b.0));
+        let winners = |origin: Option<Origin>| {
+            let mut winners = Cascaded::default();
+            let declarations = declarations
+                .iter()
+                .filter(|(priority, _)| origin.is_none_or(|origin| priority.origin_of() == origin));
+            for (_, declaration) in declarations {
+                let name = &declaration.name;
+                let value = &declaration.value;
+                // A shorthand is only kept in a block when its value has `var()` functions, and it sets all its longhands once they are substituted.
+                match properties::longhands(name) {
+                    Some(longhands) => {
+                        for longhand in longhands {
+                            winners.values.insert(longhand.to_string(), value.clone());
+                            winners
+                                .shorthands
+                                .insert(longhand.to_string(), name.clone());
+                        }
+                    }
+                    None => {
+                        winners.values.insert(name.clone(), value.clone());
+                        winners.shorthands.remove(name);
+                    }
+                }
+            }
+            winners
+        };
+        let user_agent = winners(Some(Origin::UserAgent));
+        // Animations override normal declarations, but not important ones, and transitions override everything.
+        let animated = self.animated && pseudo_element.is_none();
+        let (mut animations, transitions) = match animated {
+            true => (
+                animation::animated_values(element, false),
+                animation::animated_values(element, true),
+            ),
+            false => Default::default(),
+        };
+        for (_, declaration) in declarations
+            .iter()
+            .filter(|(_, declaration)| declaration.important)
+        {
+            match properties::longhands(&declaration.name) {
+                Some(longhands) => longhands.iter().for_each(|longhand| {
+                    animations.remove(*longhand);
+                }),
+                None => {
+                    animations.remove(&declaration.name);
+                }
+            }
+        }
+        Cascaded {
+            user_agent: user_agent.values,
+            animations,
+            transitions,
+            ..winners(None)
+        }
+    }
+}
+
+/// Returns true if the value of a declaration parses for its property, or can only be parsed once its `var()` functions are substituted.
+fn is_parsable(declaration: &Declaration) -> bool {
+    is_custom_property(&declaration.name)
+        || contains_var(&declaration.value)
+        || properties::parse_longhand(&declaration.name, &declaration.value).is_some()
+}
+
+/// A declaration block whose rule matches an element, along with where the rule is in the cascade.
+struct MatchedBlock {
+    block: DeclarationBlock,
+    origin: Origin,
+    depth: usize,
+    element_attached: bool,
+    layer: Vec<usize>,
+    specificity: Specificity,
+    order: usize,
+}
+
+impl MatchedBlock {
+    fn priority(&self, important: bool) -> Priority {
+        let origin = match (self.origin, important) {
+            (Origin::UserAgent, false) => 0,
+            (Origin::Author, false) => 1,
+            (Origin::Author, true) => 2,
+            (Origin::UserAgent, true) => 3,
+        };
+        let depth = self.depth as isize;
+        Priority {
+            origin,
+            context: if important { depth } else { -depth },
+            element_attached: self.element_attached,
+            layer: match important {
+                true => self
+                    .layer
+                    .iter()
+                    .map(|position| usize::MAX - position)
+                    .collect(),
+                false => self.layer.clone(),
+            },
+            specificity: self.specificity,
+            order: self.order,
+        }
+    }
+}
+
+/// Returns the element that an element inherits from: its parent in the flat tree.
+fn inheritance_parent(element: &Element) -> Option<Element> {
+    let document = element.owner_document()?;
+    if let Some(slot) = crate::slot::assigned_slot_of(element) {
+        return document.lookup_html_element(AsNode::cast(&slot).get_base_ptr());
+    }
+    let parent = element.parent_node()?;
+    match crate::slot::host_of_shadow_root(&parent) {
+        Some(host) => Some(host),
+        None => document.lookup_html_element(AsNode::cast(&parent).get_base_ptr()),
+    }
+}
+
+/// Returns how many shadow trees deep a tree is, with the document tree at zero.
+fn tree_depth(root: &impl AsNode) -> usize {
+    match crate::slot::host_of_shadow_root(root) {
+        Some(host) => tree_depth(&root_of(&host)) + 1,
+        None => 0,
+    }
+}
+
+/// The values that relative lengths are resolved against.
+struct Units<'a> {
+    font_size: f64,
+    root_font_size: f64,
+    viewport: &'a Viewport,
+}
+
+/// The properties whose values are lengths, so that `0` computes to `0px`.
+const LENGTH_PROPERTIES: &[&str] = &[
+    "border-bottom-left-radius",
+    "border-bottom-right-radius",
+    "border-spacing",
+    "border-top-left-radius",
+    "border-top-right-radius",
+    "bottom",
+    "column-gap",
+    "flex-basis",
+    "height",
+    "left",
+    "letter-spacing",
+    "margin-bottom",
+    "margin-left",
+    "margin-right",
+    "margin-top",
+    "max-height",
+    "max-width",
+    "min-height",
+    "min-width",
+    "outline-offset",
+    "padding-bottom",
+    "padding-left",
+    "padding-right",
+    "padding-top",
+    "right",
+    "row-gap",
+    "scroll-margin-bottom",
+    "scroll-margin-left",
+    "scroll-margin-right",
+    "scroll-margin-top",
+    "scroll-padding-bottom",
+    "scroll-padding-left",
+    "scroll-padding-right",
+    "scroll-padding-top",
+    "text-indent",
+    "top",
+    "vertical-align",
+    "width",
+    "word-spacing",
+];
+
+const COLOR_PROPERTIES: &[&str] = &[
+    "background-color",
+    "border-bottom-color",
+    "border-left-color",
+    "border-right-color",
+    "border-top-color",
+    "color",
+    "outline-color",
+    "text-decoration-color",
+];
+
+/// Computes the value of every property from the cascaded values and the parent's computed style.
+///
+/// [Reference](https://drafts.csswg.org/css-cascade/#value-stages)
+fn compute_values(
+    cascaded: &Cascaded,
+    parent: Option<&ComputedStyle>,
+    is_root: bool,
+    registrations: &HashMap<String, CSSPropertyRule>,
+    viewport: &Viewport,
+) -> ComputedStyle {
+    let parent_font_size = parent.map_or(MEDIUM_FONT_SIZE, ComputedStyle::font_size);
+    let mut style = ComputedStyle {
+        values: HashMap::new(),
+        custom: CustomProperties::new(cascaded, parent, registrations).resolve_all(),
+        root_font_size: parent.map_or(MEDIUM_FONT_SIZE, |parent| parent.root_font_size),
+    };
+    // Font sizes are needed to resolve `em` lengths, and the color to resolve `currentcolor`, so they are computed first.
+    let mut names: Vec<&'static str> = vec!["font-size", "color"];
+    names.extend(
+        PROPERTIES
+            .iter()
+            .map(|property| property.name)
+            .filter(|name| !matches!(*name, "font-size" | "color")),
+    );
+    for name in names {
+        let units = Units {
+            font_size: match name {
+                "font-size" => parent_font_size,
+                _ => style.font_size(),
+            },
+            root_font_size: match is_root {
+                true => MEDIUM_FONT_SIZE,
+                false => style.root_font_size,
+            },
+            viewport,
+        };
+        let value = match specified_value(name, cascaded, parent, &style.custom) {
+            Specified::Inherited(value) => value,
+            Specified::Value(value) => compute_value(name, &value, &units, &style, parent),
+        };
+        let value = match cascaded.animations.get(name) {
+            Some(interpolations) => {
+                animate_value(name, value, interpolations, &units, &style, parent)
+            }
+            None => value,
+        };
+        let value = match cascaded.transitions.get(name) {
+            Some(interpolations) => {
+                animate_value(name, value, interpolations, &units, &style, parent)
+            }
+            None => value,
+        };
+        style.values.insert(name, value);
+        if name == "font-size" {
+            if is_root {
+                style.root_font_size = style.font_size();
+            }
+            // Registered custom properties compute like the types in their syntax, which can be relative to the font size.
+            let units = Units {
+                font_size: style.font_size(),
+                root_font_size: style.root_font_size,
+                viewport,
+            };
+            for (name, value) in style.custom.iter_mut() {
+                if let Some(registration) = registrations.get(name) {
+                    *value = compute_registered_value(value, &registration.inner.syntax, &units);
+                }
+            }
+        }
+    }
+    fix_up_display(&mut style, parent, is_root);
+    style
+}
+
+/// The resolution of the custom properties of an element.
+struct CustomProperties<'a> {
+    cascaded: &'a Cascaded,
+    parent: Option<&'a ComputedStyle>,
+    registrations: &'a HashMap<String, CSSPropertyRule>,
+    /// The resolved values, with `None` for the guaranteed-invalid value.
+    resolved: HashMap<String, Option<String>>,
+    /// The custom properties whose values are being substituted, innermost last.
+    stack: Vec<String>,
+    /// The custom properties that depend on themselves, which are invalid at computed-value time.
+    in_cycle: HashSet<String>,
+}
+
+impl<'a> CustomProperties<'a> {
+    fn new(
+        cascaded: &'a Cascaded,
+        parent: Option<&'a ComputedStyle>,
+        registrations: &'a HashMap<String, CSSPropertyRule>,
+    ) -> Self {
+        CustomProperties {
+            cascaded,
+            parent,
+            registrations,
+            resolved: HashMap::new(),
+            stack: vec![],
+            in_cycle: HashSet::new(),
+        }
+    }
+
+    /// Resolves every custom property that is declared, inherited or registered, leaving out the ones with the guaranteed-invalid value.
+    fn resolve_all(mut self) -> HashMap<String, String> {
+        let cascaded = self
+            .cascaded
+            .values
+            .keys()
+            .filter(|name| is_custom_property(name));
+        let inherited = self
+            .parent
+            .into_iter()
+            .flat_map(|parent| parent.custom.keys());
+        let names: Vec<String> = cascaded
+            .chain(inherited)
+            .chain(self.registrations.keys())
+            .cloned()
+            .collect();
+        names
+            .into_iter()
+            .filter_map(|name| Some((name.clone(), self.resolve(&name)?)))
+            .collect()
+    }
+
+    fn initial(&self, name: &str) -> Option<String> {
+        let registration = self.registrations.get(name)?;
+        registration.initial_value().map(str::to_owned)
+    }
+
+    fn inherit(&self, name: &str) -> Option<String> {
+        match self.parent {
+            Some(parent) => parent.custom.get(name).cloned(),
+            None => self.initial(name),
+        }
+    }
+
+    /// Custom properties inherit unless they are registered not to.
+    fn unset(&self, name: &str) -> Option<String> {
+        match self.registrations.get(name) {
+            Some(registration) if !registration.inherits() => self.initial(name),
+            _ => self.inherit(name),
+        }
+    }
+
+    /// Returns the computed value of a custom property, substituting the `var()` functions in its declared value.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-variables/#cycles)
+    fn resolve(&mut self, name: &str) -> Option<String> {
+        if let Some(value) = self.resolved.get(name) {
+            return value.clone();
+        }
+        if let Some(index) = self.stack.iter().position(|entry| entry == name) {
+            self.in_cycle.extend(self.stack[index..].iter().cloned());
+            return None;
+        }
+        let cascaded = self.cascaded;
+        let value = match cascaded.values.get(name) {
+            None => self.unset(name),
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "inherit" => self.inherit(name),
+                "initial" => self.initial(name),
+                // No custom property is set by the user agent, so `revert` acts like `unset`.
+                "unset" | "revert" => self.unset(name),
+                _ => {
+                    self.stack.push(name.to_owned());
+                    let substituted = substitute(value, &mut |reference| self.resolve(reference));
+                    self.stack.pop();
+                    let registration = self.registrations.get(name);
+                    let valid = substituted.filter(|value| {
+                        !self.in_cycle.contains(name)
+                            && registration
+                                .is_none_or(|registration| registration.inner.syntax.accepts(value))
+                    });
+                    match (valid, registration) {
+                        (Some(value), _) => Some(value),
+                        // A registered property that is invalid at computed-value time acts like `unset`.
+                        (None, Some(_)) => self.unset(name),
+                        (None, None) => None,
+                    }
+                }
+            },
+        };
+        self.resolved.insert(name.to_owned(), value.clone());
+        value
+    }
+}
+
+/// Computes the value of a registered custom property, which turns lengths into pixels and colors into `rgb()`.
+///
+/// [Reference](https://drafts.css-houdini.org/css-properties-values-api/#calculation-of-computed-values)
+fn compute_registered_value(value: &str, syntax: &Syntax, units: &Units) -> String {
+    let Some(SyntaxComponent::Type {
+        data_type,
+        multiplier,
+    }) = syntax.match_value(value)
+    else {
+        return value.to_owned();
+    };
+    let (items, separator) = match multiplier {
+        Some('+') => (split_components(value), " "),
+        Some(_) => (split_commas(value), ", "),
+        None => (vec![value.trim()], ""),
+    };
+    items
+        .into_iter()
+        .map(|item| match data_type {
+            DataType::Length | DataType::LengthPercentage => {
+                to_px(&item.to_ascii_lowercase(), units).map_or_else(|| item.to_owned(), format_px)
+            }
+            DataType::Color if !item.eq_ignore_ascii_case("currentcolor") => {
+                parse_color(item).map_or_else(|| item.to_owned(), serialize_color)
+            }
+            _ => item.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+enum Specified {
+    /// The computed value of the parent.
+    Inherited(String),
+    /// A value that still needs to be computed.
+    Value(String),
+}
+
+/// Substitutes the `var()` functions in the cascaded value of a property, and resolves the CSS-wide keywords and the properties with no cascaded value into either an inherited or a specified value.
+///
+/// [Reference](https://drafts.csswg.org/css-cascade/#defaulting-keywords)
+fn specified_value(
+    name: &'static str,
+    cascaded: &Cascaded,
+    parent: Option<&ComputedStyle>,
+    custom: &HashMap<String, String>,
+) -> Specified {
+    let property = properties::property(name).unwrap();
+    let inherit = || match parent {
+        Some(parent) => Specified::Inherited(parent.get(name).to_owned()),
+        None => Specified::Value(property.initial.to_owned()),
+    };
+    let unset = || match property.inherited {
+        true => inherit(),
+        false => Specified::Value(property.initial.to_owned()),
+    };
+    let mut value = cascaded.values.get(name).cloned();
+    // A value with `var()` functions that cannot be substituted is invalid at computed-value time, and acts like `unset`.
+    let mut lookup = |reference: &str| custom.get(reference).cloned();
+    if let Some(shorthand) = cascaded.shorthands.get(name) {
+        value = value
+            .and_then(|value| substitute(&value, &mut lookup))
+            .and_then(|value| properties::expand(shorthand, &value))
+            .and_then(|longhands| {
+                longhands
+                    .into_iter()
+                    .find(|(longhand, _)| *longhand == name)
+                    .map(|(_, value)| value)
+            });
+        if value.is_none() {
+            return unset();
+        }
+    } else if value.as_deref().is_some_and(contains_var) {
+        value = value
+            .and_then(|value| substitute(&value, &mut lookup))
+            .filter(|value| !value.is_empty());
+        if value.is_none() {
+            return unset();
+        }
+    }
+    // `revert` rolls back to the user agent origin, where it acts like `unset`.
+    if value
+        .as_deref()
+        .is_some_and(|value| value.eq_ignore_ascii_case("revert"))
+    {
+        value = cascaded.user_agent.get(name).cloned();
+    }
+    // A value that does not parse for the property, like one whose `var()` functions were substituted with something invalid, acts like `unset` too.
+    let Some(value) = value.and_then(|value| properties::parse_longhand(name, &value)) else {
+        return unset();
+    };
+    match value.as_str() {
+        "inherit" => inherit(),
+        "initial" => Specified::Value(property.initial.to_owned()),
+        "unset" | "revert" => unset(),
+        _ => Specified::Value(value),
+    }
+}
+
+/// Applies the animations of a property to its computed value, each one on top of the value that the ones before it give.
+///
+/// The values of keyframes are computed like declared values, and then interpolated.
+///
+/// [Reference](https://drafts.csswg.org/web-animations-1/#the-effect-stack)
+fn animate_value(
+    name: &'static str,
+    value: String,
+    interpolations: &[Interpolation],
+    units: &Units,
+    style: &ComputedStyle,
+    parent: Option<&ComputedStyle>,
+) -> String {
+    let compute = |keyframe_value: &str| {
+        let mut cascaded = Cascaded::default();
+        cascaded
+            .values
+            .insert(name.to_owned(), keyframe_value.to_owned());
+        match specified_value(name, &cascaded, parent, &style.custom) {
+            Specified::Inherited(value) => value,
+            Specified::Value(value) => compute_value(name, &value, units, style, parent),
+        }
+    };
+    interpolations
+        .iter()
+        .fold(value, |underlying, interpolation| {
+            let endpoint = |keyframe_value: &Option<String>| match keyframe_value {
+                Some(keyframe_value) => compute(keyframe_value),
+                None => underlying.clone(),
+            };
+            interpolate(
+                name,
+                &endpoint(&interpolation.from),
+                &endpoint(&interpolation.to),
+                interpolation.progress,
+            )
+        })
+}
+
+/// Computes a specified value, which has already been parsed for the property.
+fn compute_value(
+    name: &str,
+    value: &str,
+    units: &Units,
+    style: &ComputedStyle,
+    parent: Option<&ComputedStyle>,
+) -> String {
+    let lowercase = value.to_ascii_lowercase();
+    if COLOR_PROPERTIES.contains(&name) {
+        let current_color = match name {
+            "color" => parent.map_or("rgb(0, 0, 0)", |parent| parent.get("color")),
+            _ => style.get("color"),
+        };
+        // Every color that parses has sRGB components, except `currentcolor`.
+        return parse_color(value).map_or_else(|| current_color.to_owned(), serialize_color);
+    }
+    match name {
+        "font-size" => {
+            // A size that cannot be resolved, like a math function, keeps the size of the parent.
+            format_px(compute_font_size(&lowercase, units).unwrap_or(units.font_size))
+        }
+        "font-weight" => {
+            let parent_weight = parent
+                .and_then(|parent| parent.get("font-weight").parse::<f64>().ok())
+                .unwrap_or(400.0);
+            let weight = match lowercase.as_str() {
+                "normal" => 400.0,
+                "bold" => 700.0,
+                "bolder" if parent_weight < 350.0 => 400.0,
+                "bolder" if parent_weight < 550.0 => 700.0,
+                "bolder" => parent_weight.max(900.0),
+                "lighter" if parent_weight < 550.0 => parent_weight.min(100.0),
+                "lighter" if parent_weight < 750.0 => 400.0,
+                "lighter" => 700.0,
+                number => number.parse().unwrap_or(400.0),
+            };
+            weight.to_string()
+        }
+        "line-height" => match split_dimension(&lowercase) {
+            Some((number, "")) => number.to_string(),
+            Some((percentage, "%")) => format_px(percentage / 100.0 * units.font_size),
+            // `normal`, or a length that cannot be resolved, like a math function.
+            _ => to_px(&lowercase, units).map_or_else(|| String::from("normal"), format_px),
+        },
+        "border-top-width"
+        | "border-right-width"
+        | "border-bottom-width"
+        | "border-left-width"
+        | "outline-width" => {
+            let line_style = style.get(&name.replace("width", "style"));
+            if matches!(line_style, "none" | "hidden") {
+                return String::from("0px");
+            }
+            match lowercase.as_str() {
+                "thin" => String::from("1px"),
+                "medium" => String::from("3px"),
+                "thick" => String::from("5px"),
+                _ => format_px(to_px(&lowercase, units).unwrap_or(3.0)),
+            }
+        }
+        "grid-template-columns"
+        | "grid-template-rows"
+        | "grid-auto-columns"
+        | "grid-auto-rows"
+        | "transform"
+        | "transform-origin" => compute_lengths(value, units),
+        name if LENGTH_PROPERTIES.contains(&name) => value
+            .split(' ')
+            .map(|component| {
+                to_px(&component.to_ascii_lowercase(), units)
+                    .map_or_else(|| component.to_owned(), format_px)
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => value.to_owned(),
+    }
+}
+
+/// Computes the lengths in a value made of functions, like the track list `repeat(2, minmax(2em, 1fr))` or the transform `translate(1em, 50%)`, leaving everything else as it is.
+fn compute_lengths(value: &str, units: &Units) -> String {
+    let mut computed = String::new();
+    let mut token = String::new();
+    let mut flush = |token: &mut String, computed: &mut String| {
+        let lowercase = token.to_ascii_lowercase();
+        match to_px(&lowercase, units) {
+            Some(pixels) => computed.push_str(&format_px(pixels)),
+            None => computed.push_str(token),
+        }
+        token.clear();
+    };
+    for character in value.chars() {
+        match character {
+            ' ' | '(' | ')' | ',' | '[' | ']' => {
+                flush(&mut token, &mut computed);
+                computed.push(character);
+            }
+            _ => token.push(character),
+        }
+    }
+    flush(&mut token, &mut computed);
+    computed
+}
+
+/// Computes the font given by a value of the `font` shorthand on its own, like the font of a canvas context, where relative sizes are relative to 10px. Returns `None` if the value is not a font.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/canvas.html#dom-context-2d-font)
+pub(crate) fn compute_font(value: &str, viewport: &Viewport) -> Option<ComputedStyle> {
+    if contains_var(value) {
+        return None;
+    }
+    let longhands = properties::expand("font", value)?;
+    let cascaded = |values: Vec<(&str, String)>| Cascaded {
+        values: values
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect(),
+        ..Default::default()
+    };
+    let registrations = HashMap::new();
+    let parent = compute_values(
+        &cascaded(vec![("font-size", String::from("10px"))]),
+        None,
+        false,
+        &registrations,
+        viewport,
+    );
+    Some(compute_values(
+        &cascaded(longhands),
+        Some(&parent),
+        false,
+        &registrations,
+        viewport,
+    ))
+}
+
+/// Computes a font size, where `units.font_size` is the font size of the parent.
+///
+/// [Reference](https://drafts.csswg.org/css-fonts/#font-size-prop)
+fn compute_font_size(value: &str, units: &Units) -> Option<f64> {
+    let size = match value {
+        "xx-small" => 9.0,
+        "x-small" => 10.0,
+        "small" => 13.0,
+        "medium" => MEDIUM_FONT_SIZE,
+        "large" => 18.0,
+        "x-large" => 24.0,
+        "xx-large" => 32.0,
+        "xxx-large" => 48.0,
+        "larger" => units.font_size * 1.2,
+        "smaller" => units.font_size / 1.2,
+        value => match split_dimension(value)? {
+            (percentage, "%") => percentage / 100.0 * units.font_size,
+            _ => to_px(value, units)?,
+        },
+    };
+    Some(size)
+}
+
+/// Converts an absolute or font-relative length into pixels.
+///
+/// [Reference](https://drafts.csswg.org/css-values/#lengths)
+fn to_px(value: &str, units: &Units) -> Option<f64> {
+    let (number, unit) = split_dimension(value)?;
+    let pixels_per_unit = match unit {
+        "" if number == 0.0 => 0.0,
+        "px" => 1.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        "q" => 96.0 / 101.6,
+        "in" => 96.0,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        "em" => units.font_size,
+        "rem" => units.root_font_size,
+        // Without font metrics, an `ex` and a `ch` are taken to be half an `em`.
+        "ex" | "ch" => units.font_size / 2.0,
+        "vw" => units.viewport.width / 100.0,
+        "vh" => units.viewport.height / 100.0,
+        "vmin" => units.viewport.width.min(units.viewport.height) / 100.0,
+        "vmax" => units.viewport.width.max(units.viewport.height) / 100.0,
+        _ => return None,
+    };
+    Some(number * pixels_per_unit)
+}
+
+/// Parses a length in pixels, like `16px`.
+pub(crate) fn px_value(value: &str) -> Option<f64> {
+    value.strip_suffix("px")?.parse().ok()
+}
+
+fn format_px(pixels: f64) -> String {
+    let pixels = (pixels * 1000.0).round() / 1000.0;
+    format!("{}px", pixels + 0.0)
+}
+
+/// Adjusts `display` and `float` for the root element, and for elements that are floated, absolutely positioned or inside a flex or grid container.
+///
+/// [Reference](https://drafts.csswg.org/css2/#dis-pos-flo)
+fn fix_up_display(style: &mut ComputedStyle, parent: Option<&ComputedStyle>, is_root: bool) {
+    let is_absolutely_positioned = matches!(style.get("position"), "absolute" | "fixed");
+    if is_absolutely_positioned {
+        style.values.insert("float", String::from("none"));
+    }
+    let is_flex_or_grid_item = !is_root
+        && parent.is_some_and(|parent| {
+            matches!(
+                parent.get("display"),
+                "flex" | "inline-flex" | "grid" | "inline-grid"
+            )
+        });
+    if is_root || is_absolutely_positioned || style.get("float") != "none" || is_flex_or_grid_item {
+        let display = match style.get("display") {
+            "inline-block" | "run-in" | "inline" => "block",
+            "inline-flex" => "flex",
+            "inline-grid" => "grid",
+            "inline-table" => "table",
+            "contents" if is_root => "block",
+            display if display.starts_with("table-") || display.starts_with("ruby") => "block",
+            _ => return,
+        };
+        style.values.insert("display", display.to_owned());
+    }
+}
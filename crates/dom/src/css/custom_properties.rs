@@ -0,0 +1,285 @@
+//! Custom properties: the substitution of `var()` functions, and the syntax of properties registered with `@property`.
+//!
+//! [Reference](https://drafts.csswg.org/css-variables/)
+use super::{
+    parser::{find_top_level, is_custom_property, split_commas, split_components},
+    values::{
+        function_name, is_color, is_css_wide_keyword, is_image, is_integer, is_length,
+        is_length_percentage, is_number, is_percentage, is_string, is_transform_function,
+        split_dimension,
+    },
+};
+
+/// Returns true if a value contains a `var()` function.
+pub(crate) fn contains_var(value: &str) -> bool {
+    find_var(value).is_some()
+}
+
+/// Returns the index of the first `var(` in a value, outside strings.
+fn find_var(value: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut previous = ' ';
+    for (index, char) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if char == '\\' {
+            escaped = true;
+        } else if let Some(quote_char) = quote {
+            if char == quote_char {
+                quote = None;
+            }
+        } else if char == '"' || char == '\'' {
+            quote = Some(char);
+        } else if value[index..]
+            .get(..4)
+            .is_some_and(|start| start.eq_ignore_ascii_case("var("))
+            && !(previous.is_alphanumeric() || previous == '-' || previous == '_')
+        {
+            return Some(index);
+        }
+        previous = char;
+    }
+    None
+}
+
+/// Replaces the `var()` functions in a value with the values of the custom properties they refer to, or with their fallbacks.
+///
+/// `lookup` returns the value of a custom property, or `None` if it has the guaranteed-invalid value. Returns `None` if a `var()` has no value and no fallback, which makes the declaration invalid at computed-value time.
+///
+/// [Reference](https://drafts.csswg.org/css-variables/#substitute-a-var)
+pub(crate) fn substitute(
+    value: &str,
+    lookup: &mut dyn FnMut(&str) -> Option<String>,
+) -> Option<String> {
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = find_var(rest) {
+        output.push_str(&rest[..start]);
+        let arguments = &rest[start + 4..];
+        let end = find_top_level(arguments, |char| char == ')')?;
+        let (name, fallback) = match find_top_level(&arguments[..end], |char| char == ',') {
+            Some(comma) => (&arguments[..comma], Some(&arguments[comma + 1..end])),
+            None => (&arguments[..end], None),
+        };
+        let name = name.trim();
+        if !is_custom_property(name) {
+            return None;
+        }
+        let replacement = match lookup(name) {
+            Some(value) => value,
+            None => substitute(fallback?.trim(), lookup)?,
+        };
+        output.push_str(&replacement);
+        rest = &arguments[end + 1..];
+    }
+    output.push_str(rest);
+    Some(output.trim().to_owned())
+}
+
+/// The data types that the syntax of a registered custom property can name.
+///
+/// [Reference](https://drafts.css-houdini.org/css-properties-values-api/#supported-names)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DataType {
+    Angle,
+    Color,
+    CustomIdent,
+    Image,
+    Integer,
+    Length,
+    LengthPercentage,
+    Number,
+    Percentage,
+    Resolution,
+    String,
+    Time,
+    TransformFunction,
+    TransformList,
+    Url,
+}
+
+impl DataType {
+    fn parse(name: &str) -> Option<Self> {
+        let data_type = match name {
+            "angle" => DataType::Angle,
+            "color" => DataType::Color,
+            "custom-ident" => DataType::CustomIdent,
+            "image" => DataType::Image,
+            "integer" => DataType::Integer,
+            "length" => DataType::Length,
+            "length-percentage" => DataType::LengthPercentage,
+            "number" => DataType::Number,
+            "percentage" => DataType::Percentage,
+            "resolution" => DataType::Resolution,
+            "string" => DataType::String,
+            "time" => DataType::Time,
+            "transform-function" => DataType::TransformFunction,
+            "transform-list" => DataType::TransformList,
+            "url" => DataType::Url,
+            _ => return None,
+        };
+        Some(data_type)
+    }
+
+    fn matches(self, value: &str) -> bool {
+        let has_unit = |units: &[&str]| {
+            split_dimension(value).is_some_and(|(_, unit)| {
+                units
+                    .iter()
+                    .any(|expected| expected.eq_ignore_ascii_case(unit))
+            })
+        };
+        match self {
+            DataType::Angle => has_unit(&["deg", "grad", "rad", "turn"]),
+            DataType::Color => is_color(value),
+            DataType::CustomIdent => {
+                is_identifier(value)
+                    && !is_css_wide_keyword(value)
+                    && !value.eq_ignore_ascii_case("default")
+            }
+            DataType::Image => is_image(value) && !value.eq_ignore_ascii_case("none"),
+            DataType::Integer => is_integer(value),
+            DataType::Length => is_length(value),
+            DataType::LengthPercentage => is_length_percentage(value),
+            DataType::Number => is_number(value),
+            DataType::Percentage => is_percentage(value),
+            DataType::Resolution => has_unit(&["dpi", "dpcm", "dppx", "x"]),
+            DataType::String => is_string(value),
+            DataType::Time => has_unit(&["s", "ms"]),
+            DataType::TransformFunction => is_transform_function(value),
+            DataType::TransformList => split_components(value)
+                .iter()
+                .all(|component| is_transform_function(component)),
+            DataType::Url => {
+                function_name(value).is_some_and(|name| name.eq_ignore_ascii_case("url"))
+            }
+        }
+    }
+}
+
+fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_' || first == '-')
+        && chars.all(|char| char.is_alphanumeric() || char == '_' || char == '-')
+}
+
+/// A component of a syntax, like `<length>+` or `auto`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SyntaxComponent {
+    Keyword(String),
+    Type {
+        data_type: DataType,
+        /// `+` for a space-separated list, or `#` for a comma-separated list.
+        multiplier: Option<char>,
+    },
+}
+
+/// The syntax of a registered custom property, like `<length> | auto`.
+///
+/// [Reference](https://drafts.css-houdini.org/css-properties-values-api/#syntax-strings)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Syntax {
+    /// The alternatives of the syntax, or `None` for the universal syntax `*`.
+    components: Option<Vec<SyntaxComponent>>,
+}
+
+impl Syntax {
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text == "*" {
+            return Some(Syntax { components: None });
+        }
+        let components = text
+            .split('|')
+            .map(|component| {
+                let component = component.trim();
+                let (component, multiplier) = match component.strip_suffix(['+', '#']) {
+                    Some(rest) => (rest, component.chars().last()),
+                    None => (component, None),
+                };
+                match component
+                    .strip_prefix('<')
+                    .and_then(|name| name.strip_suffix('>'))
+                {
+                    Some(name) => {
+                        let data_type = DataType::parse(name)?;
+                        // A transform list is already a list, so it cannot be multiplied.
+                        if data_type == DataType::TransformList && multiplier.is_some() {
+                            return None;
+                        }
+                        Some(SyntaxComponent::Type {
+                            data_type,
+                            multiplier,
+                        })
+                    }
+                    None if multiplier.is_none()
+                        && is_identifier(component)
+                        && !is_css_wide_keyword(component)
+                        && component != "default" =>
+                    {
+                        Some(SyntaxComponent::Keyword(component.to_owned()))
+                    }
+                    None => None,
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Syntax {
+            components: Some(components),
+        })
+    }
+
+    /// Returns true for the universal syntax `*`, which accepts any value.
+    pub(crate) fn is_universal(&self) -> bool {
+        self.components.is_none()
+    }
+
+    /// Returns the first alternative of the syntax that a value matches.
+    pub(crate) fn match_value(&self, value: &str) -> Option<&SyntaxComponent> {
+        let value = value.trim();
+        self.components
+            .as_ref()?
+            .iter()
+            .find(|component| match component {
+                SyntaxComponent::Keyword(keyword) => value == keyword,
+                SyntaxComponent::Type {
+                    data_type,
+                    multiplier,
+                } => {
+                    let items = match multiplier {
+                        Some('+') => split_components(value),
+                        Some(_) => split_commas(value),
+                        None => vec![value],
+                    };
+                    !items.is_empty() && items.iter().all(|item| data_type.matches(item))
+                }
+            })
+    }
+
+    /// Returns true if a value is accepted by the syntax.
+    pub(crate) fn accepts(&self, value: &str) -> bool {
+        self.is_universal() || self.match_value(value).is_some()
+    }
+}
+
+/// Returns true if a value does not depend on the element it is used on, as the initial value of a registered custom property must be.
+///
+/// [Reference](https://drafts.css-houdini.org/css-properties-values-api/#computationally-independent)
+pub(crate) fn is_computationally_independent(value: &str) -> bool {
+    const RELATIVE_UNITS: &[&str] = &[
+        "em", "rem", "ex", "rex", "ch", "rch", "ic", "cap", "lh", "rlh", "vw", "vh", "vmin",
+        "vmax", "vi", "vb",
+    ];
+    !contains_var(value)
+        && split_components(value).iter().all(|component| {
+            split_commas(component).iter().all(|item| {
+                split_dimension(item).is_none_or(|(_, unit)| {
+                    !RELATIVE_UNITS
+                        .iter()
+                        .any(|relative| relative.eq_ignore_ascii_case(unit))
+                })
+            })
+        })
+}
@@ -0,0 +1,623 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    animation, document::WeakDocumentRef, domitem::DOMItem, AsElement, AsNode, DOMException,
+    Element, Viewport,
+};
+
+use super::{
+    cascade::StyleResolver,
+    custom_properties::contains_var,
+    parser::{is_custom_property, normalize_value, parse_declarations, strip_important},
+    properties,
+    selector::PseudoElement,
+};
+
+/// A single longhand or custom property declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Declaration {
+    pub name: String,
+    pub value: String,
+    pub important: bool,
+}
+
+/// An ordered list of longhand declarations, with shorthands expanded.
+///
+/// [Reference](https://drafts.csswg.org/cssom/#css-declaration-blocks)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DeclarationBlock {
+    pub declarations: Vec<Declaration>,
+}
+
+impl DeclarationBlock {
+    /// Parses a declaration block from a list of declarations, dropping the ones that are invalid or unsupported.
+    pub(crate) fn parse(input: &str) -> Self {
+        let mut block = DeclarationBlock::default();
+        for declaration in parse_declarations(input) {
+            block.set(&declaration.name, &declaration.value, declaration.important);
+        }
+        block
+    }
+
+    /// Parses the descriptors of an at-rule like `@font-face`, keeping every declaration as written.
+    pub(crate) fn parse_descriptors(input: &str) -> Self {
+        let mut block = DeclarationBlock::default();
+        for declaration in parse_declarations(input) {
+            block.set_longhand(&declaration.name, declaration.value, declaration.important);
+        }
+        block
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&Declaration> {
+        self.declarations
+            .iter()
+            .find(|declaration| declaration.name == name)
+    }
+
+    /// Returns the value of a property, serializing shorthands from their longhands.
+    ///
+    /// [Reference](https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-getpropertyvalue)
+    pub(crate) fn get_value(&self, name: &str) -> String {
+        match properties::longhands(name) {
+            Some(_) => match self.get(name) {
+                Some(pending) => pending.value.clone(),
+                None => self.serialize_shorthand(name).unwrap_or_default(),
+            },
+            None => self
+                .get(name)
+                .map(|declaration| declaration.value.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns `"important"` if the property is important. A shorthand is important only if all of its longhands are.
+    pub(crate) fn get_priority(&self, name: &str) -> &'static str {
+        let important = match (properties::longhands(name), self.get(name)) {
+            (_, Some(declaration)) => declaration.important,
+            (Some(longhands), None) => longhands
+                .iter()
+                .all(|longhand| self.get(longhand).is_some_and(|d| d.important)),
+            (None, None) => false,
+        };
+        if important {
+            "important"
+        } else {
+            ""
+        }
+    }
+
+    /// Sets a property, expanding shorthands. Returns false if the property is unsupported or the value is invalid.
+    ///
+    /// A shorthand whose value contains `var()` cannot be expanded until its value is computed, so it is kept as a single pending-substitution declaration in place of its longhands.
+    pub(crate) fn set(&mut self, name: &str, value: &str, important: bool) -> bool {
+        if let (Some(_), true) = (properties::longhands(name), contains_var(value)) {
+            self.remove(name);
+            self.set_longhand(name, normalize_value(value), important);
+            return true;
+        }
+        if let Some(values) = properties::expand(name, value) {
+            self.declarations
+                .retain(|declaration| declaration.name != name);
+            for (longhand, value) in values {
+                self.set_longhand(longhand, value, important);
+            }
+            return true;
+        }
+        // A longhand with `var()` functions can only be parsed once they are substituted.
+        if is_custom_property(name) || (properties::property(name).is_some() && contains_var(value))
+        {
+            self.set_longhand(name, normalize_value(value), important);
+            return true;
+        }
+        match properties::parse_longhand(name, value) {
+            Some(value) => {
+                self.set_longhand(name, value, important);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_longhand(&mut self, name: &str, value: String, important: bool) {
+        match self
+            .declarations
+            .iter_mut()
+            .find(|declaration| declaration.name == name)
+        {
+            Some(declaration) => {
+                declaration.value = value;
+                declaration.important = important;
+            }
+            None => self.declarations.push(Declaration {
+                name: name.to_owned(),
+                value,
+                important,
+            }),
+        }
+    }
+
+    /// Removes a property, or all the longhands of a shorthand. Returns true if anything was removed.
+    pub(crate) fn remove(&mut self, name: &str) -> bool {
+        let length = self.declarations.len();
+        match properties::longhands(name) {
+            Some(longhands) => self.declarations.retain(|declaration| {
+                declaration.name != name && !longhands.contains(&declaration.name.as_str())
+            }),
+            None => self
+                .declarations
+                .retain(|declaration| declaration.name != name),
+        }
+        self.declarations.len() != length
+    }
+
+    /// Serializes a shorthand if all of its longhands are set with the same priority.
+    fn serialize_shorthand(&self, shorthand: &str) -> Option<String> {
+        let longhands = properties::longhands(shorthand)?;
+        let declarations = longhands
+            .iter()
+            .map(|longhand| self.get(longhand))
+            .collect::<Option<Vec<_>>>()?;
+        if declarations
+            .iter()
+            .any(|declaration| declaration.important != declarations[0].important)
+        {
+            return None;
+        }
+        let values: Vec<_> = declarations
+            .iter()
+            .map(|declaration| declaration.value.as_str())
+            .collect();
+        properties::serialize(shorthand, &values)
+    }
+
+    /// Serializes the block, using shorthands where they can represent the longhands.
+    ///
+    /// [Reference](https://drafts.csswg.org/cssom/#serialize-a-css-declaration-block)
+    pub(crate) fn serialize(&self) -> String {
+        let mut parts = vec![];
+        let mut already_serialized: Vec<&str> = vec![];
+        for declaration in &self.declarations {
+            if already_serialized.contains(&declaration.name.as_str()) {
+                continue;
+            }
+            let shorthand = properties::shorthands_of(&declaration.name)
+                .into_iter()
+                .find_map(|shorthand| {
+                    // A shorthand can only be used if none of its longhands has been serialized yet.
+                    let longhands = properties::longhands(shorthand)?;
+                    if longhands
+                        .iter()
+                        .any(|longhand| already_serialized.contains(longhand))
+                    {
+                        return None;
+                    }
+                    let value = self.serialize_shorthand(shorthand)?;
+                    Some((shorthand, value))
+                });
+            let (name, value) = match shorthand {
+                Some((shorthand, value)) => {
+                    already_serialized.extend(properties::longhands(shorthand).unwrap());
+                    (shorthand, value)
+                }
+                None => (declaration.name.as_str(), declaration.value.clone()),
+            };
+            let important = if declaration.important {
+                " !important"
+            } else {
+                ""
+            };
+            parts.push(format!("{name}: {value}{important};"));
+        }
+        parts.join(" ")
+    }
+}
+
+/// A CSS declaration block, which exposes style information and methods to change it.
+///
+/// The block of an element's inline style is kept in sync with its `style` attribute: it is parsed from the attribute whenever it is read, and the attribute is rewritten whenever it changes.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration)
+pub struct CSSStyleDeclaration {
+    owner: DeclarationOwner,
+    readonly: bool,
+}
+
+enum DeclarationOwner {
+    /// The inline style of an element.
+    Element(Element),
+    /// The declarations of a rule in a style sheet of a document.
+    Block {
+        block: Rc<RefCell<DeclarationBlock>>,
+        document: WeakDocumentRef,
+    },
+    /// The computed style of an element, or of one of its pseudo-elements, which is resolved again whenever it is read.
+    Computed {
+        element: Element,
+        pseudo_element: Option<String>,
+    },
+}
+
+impl CSSStyleDeclaration {
+    /// Returns the inline style declaration of an element.
+    pub(crate) fn for_element(element: &Element, readonly: bool) -> Self {
+        CSSStyleDeclaration {
+            owner: DeclarationOwner::Element(element.clone_ref()),
+            readonly,
+        }
+    }
+
+    /// Returns a declaration that reads and writes a block shared with a style sheet rule.
+    pub(crate) fn for_block(
+        block: &Rc<RefCell<DeclarationBlock>>,
+        document: &WeakDocumentRef,
+    ) -> Self {
+        CSSStyleDeclaration {
+            owner: DeclarationOwner::Block {
+                block: block.clone(),
+                document: document.clone(),
+            },
+            readonly: false,
+        }
+    }
+
+    /// Returns the read-only computed style of an element. A pseudo-element that is not valid gives an empty declaration.
+    pub(crate) fn computed(element: &Element, pseudo_element: Option<&str>) -> Self {
+        CSSStyleDeclaration {
+            owner: DeclarationOwner::Computed {
+                element: element.clone_ref(),
+                pseudo_element: pseudo_element.map(str::to_owned),
+            },
+            readonly: true,
+        }
+    }
+
+    fn block(&self) -> DeclarationBlock {
+        match &self.owner {
+            DeclarationOwner::Element(element) => {
+                DeclarationBlock::parse(element.get_attribute("style").unwrap_or(""))
+            }
+            DeclarationOwner::Block { block, .. } => block.borrow().clone(),
+            DeclarationOwner::Computed {
+                element,
+                pseudo_element,
+            } => {
+                // Anything that is not a pseudo-element, like an empty string, means the element itself.
+                let pseudo_element = match pseudo_element.as_deref() {
+                    Some(pseudo_element) if pseudo_element.starts_with(':') => {
+                        match PseudoElement::parse(pseudo_element) {
+                            Some(pseudo_element) => Some(pseudo_element),
+                            None => return DeclarationBlock::default(),
+                        }
+                    }
+                    _ => None,
+                };
+                let resolver = match element.owner_document() {
+                    Some(document) => animation::style_resolver(&document),
+                    None => StyleResolver::new(Viewport::default()),
+                };
+                resolver
+                    .compute(element, pseudo_element.as_ref())
+                    .to_block()
+            }
+        }
+    }
+
+    /// Runs a change on the block, then writes it back to its owner.
+    fn update(
+        &mut self,
+        change: impl FnOnce(&mut DeclarationBlock) -> bool,
+    ) -> Result<bool, DOMException> {
+        if self.readonly {
+            return Err(DOMException::NoModificationAllowedError(String::from(
+                "The declaration block is read-only.",
+            )));
+        }
+        let mut block = self.block();
+        let changed = change(&mut block);
+        if changed {
+            match &mut self.owner {
+                DeclarationOwner::Element(element) => {
+                    element.set_attribute("style", &block.serialize())
+                }
+                DeclarationOwner::Block {
+                    block: shared,
+                    document,
+                } => {
+                    *shared.borrow_mut() = block;
+                    crate::layout::invalidate_document(document);
+                }
+                DeclarationOwner::Computed { .. } => {}
+            }
+        }
+        Ok(changed)
+    }
+}
+
+// Properties.
+impl CSSStyleDeclaration {
+    /// Returns the textual representation of the declaration block.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration/cssText)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("div");
+    /// element.set_attribute("style", "MARGIN-TOP: 1px; margin-right: 2px ; margin-bottom:1px;margin-left:2px; unknown: 3");
+    ///
+    /// // Unknown properties are dropped, and longhands are combined into shorthands.
+    /// assert_eq!(element.style().css_text(), "margin: 1px 2px;");
+    /// ```
+    pub fn css_text(&self) -> String {
+        // The text of a computed style is always empty.
+        if let DeclarationOwner::Computed { .. } = self.owner {
+            return String::new();
+        }
+        self.block().serialize()
+    }
+    /// Replaces all the declarations in the block with the ones parsed from a string.
+    ///
+    /// Returns a `NoModificationAllowedError` if the declaration block is read-only.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration/cssText)
+    pub fn set_css_text(&mut self, value: &str) -> Result<(), DOMException> {
+        self.update(|block| {
+            *block = DeclarationBlock::parse(value);
+            true
+        })?;
+        Ok(())
+    }
+    /// Returns the number of longhand properties in the block.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration/length)
+    pub fn len(&self) -> usize {
+        self.block().declarations.len()
+    }
+    /// Returns true if the block has no declarations.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Methods.
+impl CSSStyleDeclaration {
+    /// Returns the name of the longhand property at `index`.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration/item)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("div");
+    /// element.style_mut().set_property("overflow", "hidden", None).unwrap();
+    ///
+    /// let style = element.style();
+    /// assert_eq!(style.len(), 2);
+    /// assert_eq!(style.item(0).unwrap(), "overflow-x");
+    /// assert_eq!(style.item(1).unwrap(), "overflow-y");
+    /// assert!(style.item(2).is_none());
+    /// ```
+    pub fn item(&self, index: usize) -> Option<String> {
+        self.block()
+            .declarations
+            .get(index)
+            .map(|declaration| declaration.name.clone())
+    }
+    /// Returns the value of a property, or an empty string if it is not set. The value of a shorthand is serialized from its longhands.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration/getPropertyValue)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("div");
+    /// element.set_attribute("style", "border: 2px solid red; border-left-color: blue");
+    ///
+    /// let style = element.style();
+    /// assert_eq!(style.get_property_value("border-top-width"), "2px");
+    /// assert_eq!(style.get_property_value("border-left-color"), "blue");
+    /// assert_eq!(style.get_property_value("border-top"), "2px solid red");
+    /// // The sides differ, so `border` cannot represent them.
+    /// assert_eq!(style.get_property_value("border"), "");
+    /// assert_eq!(style.get_property_value("border-color"), "red red red blue");
+    /// ```
+    pub fn get_property_value(&self, property: &str) -> String {
+        self.block().get_value(&property_name(property))
+    }
+    /// Returns `"important"` if the property is set with `!important`, and an empty string otherwise.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration/getPropertyPriority)
+    pub fn get_property_priority(&self, property: &str) -> &str {
+        self.block().get_priority(&property_name(property))
+    }
+    /// Sets a property. Shorthands are expanded into their longhands, and an empty value removes the property. `priority` can be `"important"`; any other non-empty priority is ignored, as are unsupported properties and invalid values.
+    ///
+    /// Returns a `NoModificationAllowedError` if the declaration block is read-only.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration/setProperty)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("div");
+    ///
+    /// let mut style = element.style_mut();
+    /// style.set_property("color", "green", None).unwrap();
+    /// style.set_property("padding", "4px 8px", Some("important")).unwrap();
+    /// // Invalid values and priorities are ignored.
+    /// style.set_property("color", "not a color!", None).unwrap();
+    /// style.set_property("color", "red", Some("urgent")).unwrap();
+    ///
+    /// assert_eq!(style.get_property_value("color"), "green");
+    /// assert_eq!(style.get_property_value("padding-left"), "8px");
+    /// assert_eq!(style.get_property_priority("padding"), "important");
+    /// assert_eq!(
+    ///     element.get_attribute("style").unwrap(),
+    ///     "color: green; padding: 4px 8px !important;"
+    /// );
+    /// ```
+    pub fn set_property(
+        &mut self,
+        property: &str,
+        value: &str,
+        priority: Option<&str>,
+    ) -> Result<(), DOMException> {
+        let property = property_name(property);
+        let value = value.trim();
+        // 3. If value is the empty string, invoke removeProperty() with property as argument and return.
+        if value.is_empty() && !is_custom_property(&property) {
+            self.remove_property(&property)?;
+            return Ok(());
+        }
+        // 4. If priority is not the empty string and is not an ASCII case-insensitive match for the string "important", then return.
+        let important = match priority.unwrap_or("") {
+            "" => false,
+            priority if priority.eq_ignore_ascii_case("important") => true,
+            _ => return Ok(()),
+        };
+        // A value with its own `!important` is not valid here.
+        if strip_important(value).1 {
+            return Ok(());
+        }
+        // 5. Let component value list be the result of parsing value for property property.
+        // 6. If component value list is null, then return.
+        if !DeclarationBlock::default().set(&property, value, important) {
+            return Ok(());
+        }
+        self.update(|block| block.set(&property, value, important))?;
+        Ok(())
+    }
+    /// Removes a property, or all the longhands of a shorthand, and returns its previous value.
+    ///
+    /// Returns a `NoModificationAllowedError` if the declaration block is read-only.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleDeclaration/removeProperty)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("div");
+    /// element.set_attribute("style", "margin: 0 auto; color: red");
+    ///
+    /// let mut style = element.style_mut();
+    /// assert_eq!(style.remove_property("margin").unwrap(), "0 auto");
+    /// assert_eq!(style.css_text(), "color: red;");
+    /// ```
+    pub fn remove_property(&mut self, property: &str) -> Result<String, DOMException> {
+        let property = property_name(property);
+        let value = self.block().get_value(&property);
+        self.update(|block| block.remove(&property))?;
+        Ok(value)
+    }
+}
+
+/// Lowercases a property name, unless it is a custom property.
+fn property_name(property: &str) -> String {
+    if is_custom_property(property) {
+        property.to_owned()
+    } else {
+        property.to_ascii_lowercase()
+    }
+}
+
+macro_rules! typed_accessors {
+    ($($getter:ident, $setter:ident => $name:literal;)*) => {
+        // Typed accessors.
+        impl CSSStyleDeclaration {
+            $(
+                #[doc = concat!("Returns the value of the `", $name, "` property, or an empty string if it is not set.")]
+                ///
+                #[doc = concat!("[MDN Reference](https://developer.mozilla.org/docs/Web/CSS/", $name, ")")]
+                pub fn $getter(&self) -> String {
+                    self.get_property_value($name)
+                }
+                #[doc = concat!("Sets the value of the `", $name, "` property. An empty value removes it.")]
+                ///
+                #[doc = concat!("[MDN Reference](https://developer.mozilla.org/docs/Web/CSS/", $name, ")")]
+                pub fn $setter(&mut self, value: &str) -> Result<(), DOMException> {
+                    self.set_property($name, value, None)
+                }
+            )*
+        }
+    };
+}
+
+typed_accessors! {
+    align_items, set_align_items => "align-items";
+    align_self, set_align_self => "align-self";
+    background, set_background => "background";
+    background_color, set_background_color => "background-color";
+    background_image, set_background_image => "background-image";
+    border, set_border => "border";
+    border_color, set_border_color => "border-color";
+    border_radius, set_border_radius => "border-radius";
+    border_style, set_border_style => "border-style";
+    border_width, set_border_width => "border-width";
+    bottom, set_bottom => "bottom";
+    box_sizing, set_box_sizing => "box-sizing";
+    color, set_color => "color";
+    column_gap, set_column_gap => "column-gap";
+    content_visibility, set_content_visibility => "content-visibility";
+    cursor, set_cursor => "cursor";
+    display, set_display => "display";
+    flex, set_flex => "flex";
+    flex_basis, set_flex_basis => "flex-basis";
+    flex_direction, set_flex_direction => "flex-direction";
+    flex_grow, set_flex_grow => "flex-grow";
+    flex_shrink, set_flex_shrink => "flex-shrink";
+    flex_wrap, set_flex_wrap => "flex-wrap";
+    font, set_font => "font";
+    font_family, set_font_family => "font-family";
+    font_size, set_font_size => "font-size";
+    font_style, set_font_style => "font-style";
+    font_weight, set_font_weight => "font-weight";
+    gap, set_gap => "gap";
+    grid_area, set_grid_area => "grid-area";
+    grid_column, set_grid_column => "grid-column";
+    grid_row, set_grid_row => "grid-row";
+    grid_template_areas, set_grid_template_areas => "grid-template-areas";
+    grid_template_columns, set_grid_template_columns => "grid-template-columns";
+    grid_template_rows, set_grid_template_rows => "grid-template-rows";
+    height, set_height => "height";
+    justify_content, set_justify_content => "justify-content";
+    left, set_left => "left";
+    line_height, set_line_height => "line-height";
+    margin, set_margin => "margin";
+    margin_bottom, set_margin_bottom => "margin-bottom";
+    margin_left, set_margin_left => "margin-left";
+    margin_right, set_margin_right => "margin-right";
+    margin_top, set_margin_top => "margin-top";
+    max_height, set_max_height => "max-height";
+    max_width, set_max_width => "max-width";
+    min_height, set_min_height => "min-height";
+    min_width, set_min_width => "min-width";
+    opacity, set_opacity => "opacity";
+    order, set_order => "order";
+    outline, set_outline => "outline";
+    overflow, set_overflow => "overflow";
+    padding, set_padding => "padding";
+    padding_bottom, set_padding_bottom => "padding-bottom";
+    padding_left, set_padding_left => "padding-left";
+    padding_right, set_padding_right => "padding-right";
+    padding_top, set_padding_top => "padding-top";
+    position, set_position => "position";
+    right, set_right => "right";
+    row_gap, set_row_gap => "row-gap";
+    scroll_behavior, set_scroll_behavior => "scroll-behavior";
+    scroll_margin, set_scroll_margin => "scroll-margin";
+    scroll_padding, set_scroll_padding => "scroll-padding";
+    scroll_snap_align, set_scroll_snap_align => "scroll-snap-align";
+    scroll_snap_type, set_scroll_snap_type => "scroll-snap-type";
+    text_align, set_text_align => "text-align";
+    text_decoration, set_text_decoration => "text-decoration";
+    top, set_top => "top";
+    visibility, set_visibility => "visibility";
+    white_space, set_white_space => "white-space";
+    width, set_width => "width";
+    z_index, set_z_index => "z-index";
+}
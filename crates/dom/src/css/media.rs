@@ -0,0 +1,477 @@
+//! Evaluation of media queries against a viewport, and the live media query lists returned by `Window::match_media`.
+//!
+//! [Reference](https://drafts.csswg.org/mediaqueries-4/)
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    document::WeakDocumentRef, event::EventType, window::ColorScheme, AsEventTarget, Event,
+    EventInit, EventTarget, MediaType, Viewport,
+};
+
+use super::{
+    parser::{normalize_value, split_commas, split_components},
+    values::split_dimension,
+};
+
+/// Returns true if a media query list, like `screen and (min-width: 600px), print`, matches a viewport. An empty list always matches.
+///
+/// [Reference](https://drafts.csswg.org/mediaqueries-4/#evaluating)
+pub(crate) fn evaluate_list(list: &str, viewport: &Viewport) -> bool {
+    let queries: Vec<_> = split_commas(list)
+        .into_iter()
+        .filter(|query| !query.is_empty())
+        .collect();
+    queries.is_empty() || queries.iter().any(|query| evaluate(query, viewport))
+}
+
+/// Returns true if a single media query matches a viewport. Queries that are invalid, or whose result is unknown, do not match.
+pub(crate) fn evaluate(query: &str, viewport: &Viewport) -> bool {
+    let parts = split_components(query);
+    let is_keyword = |part: &str, keyword: &str| part.eq_ignore_ascii_case(keyword);
+    // A query that starts with a condition has no media type.
+    if parts.first().is_some_and(|part| part.starts_with('('))
+        || parts.len() > 1 && is_keyword(parts[0], "not") && parts[1].starts_with('(')
+    {
+        return evaluate_condition(&parts, viewport, true) == Some(true);
+    }
+    let mut parts = parts.as_slice();
+    let mut negated = false;
+    match parts.first() {
+        Some(first) if is_keyword(first, "not") => {
+            negated = true;
+            parts = &parts[1..];
+        }
+        Some(first) if is_keyword(first, "only") => parts = &parts[1..],
+        _ => {}
+    }
+    let Some((media_type, rest)) = parts.split_first() else {
+        return false;
+    };
+    let media_type = media_type.to_ascii_lowercase();
+    if !is_identifier(&media_type)
+        || matches!(media_type.as_str(), "not" | "and" | "or" | "only" | "layer")
+    {
+        return false;
+    }
+    let type_matches = match media_type.as_str() {
+        "all" => true,
+        "screen" => viewport.media_type == MediaType::Screen,
+        "print" => viewport.media_type == MediaType::Print,
+        // Deprecated media types, like `tv`, are valid but never match.
+        _ => false,
+    };
+    let result = match rest {
+        [] => Some(type_matches),
+        [and, condition @ ..] if is_keyword(and, "and") && !condition.is_empty() => {
+            match evaluate_condition(condition, viewport, false) {
+                Some(true) => Some(type_matches),
+                None if type_matches => None,
+                _ => Some(false),
+            }
+        }
+        _ => return false,
+    };
+    // An unknown result is false, even when it is negated.
+    result.is_some_and(|result| result != negated)
+}
+
+/// Evaluates a media condition with three-valued logic, where `None` means unknown.
+///
+/// `allow_or` is false for the condition after a media type, which cannot use `or` at its top level.
+fn evaluate_condition(parts: &[&str], viewport: &Viewport, allow_or: bool) -> Option<bool> {
+    match parts {
+        [not, operand] if not.eq_ignore_ascii_case("not") => {
+            evaluate_in_parens(operand, viewport).map(|result| !result)
+        }
+        [first, rest @ ..] if rest.len() % 2 == 0 => {
+            let operators: Vec<_> = rest.iter().step_by(2).collect();
+            let operands = std::iter::once(first).chain(rest.iter().skip(1).step_by(2));
+            let results: Vec<_> = operands
+                .map(|operand| evaluate_in_parens(operand, viewport))
+                .collect();
+            if operators
+                .iter()
+                .all(|operator| operator.eq_ignore_ascii_case("and"))
+            {
+                match results.contains(&Some(false)) {
+                    true => Some(false),
+                    false => results
+                        .into_iter()
+                        .collect::<Option<Vec<_>>>()
+                        .map(|_| true),
+                }
+            } else if allow_or
+                && operators
+                    .iter()
+                    .all(|operator| operator.eq_ignore_ascii_case("or"))
+            {
+                match results.contains(&Some(true)) {
+                    true => Some(true),
+                    false => results
+                        .into_iter()
+                        .collect::<Option<Vec<_>>>()
+                        .map(|_| false),
+                }
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn evaluate_in_parens(operand: &str, viewport: &Viewport) -> Option<bool> {
+    let inner = operand.strip_prefix('(')?.strip_suffix(')')?.trim();
+    let parts = split_components(inner);
+    let is_condition = parts.first().is_some_and(|part| {
+        part.starts_with('(') || part.eq_ignore_ascii_case("not") && parts.len() == 2
+    });
+    match is_condition {
+        true => evaluate_condition(&parts, viewport, true),
+        // Anything else that is not a media feature is unknown.
+        false => evaluate_feature(inner, viewport),
+    }
+}
+
+/// The comparisons in a range context, like `width >= 600px`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn compare(self, left: f64, right: f64) -> bool {
+        let equal = (left - right).abs() < 1e-6;
+        match self {
+            Comparison::Less => left < right && !equal,
+            Comparison::LessOrEqual => left < right || equal,
+            Comparison::Equal => equal,
+            Comparison::GreaterOrEqual => left > right || equal,
+            Comparison::Greater => left > right && !equal,
+        }
+    }
+
+    /// Returns the comparison with its operands swapped, so that `600px < width` becomes `width > 600px`.
+    fn flip(self) -> Self {
+        match self {
+            Comparison::Less => Comparison::Greater,
+            Comparison::LessOrEqual => Comparison::GreaterOrEqual,
+            Comparison::Equal => Comparison::Equal,
+            Comparison::GreaterOrEqual => Comparison::LessOrEqual,
+            Comparison::Greater => Comparison::Less,
+        }
+    }
+
+    fn is_less(self) -> bool {
+        matches!(self, Comparison::Less | Comparison::LessOrEqual)
+    }
+}
+
+/// Splits a range, like `400px <= width < 800px`, into its operands and comparisons.
+fn split_range(inner: &str) -> Option<(Vec<&str>, Vec<Comparison>)> {
+    let mut operands = vec![];
+    let mut comparisons = vec![];
+    let mut start = 0;
+    let mut chars = inner.char_indices().peekable();
+    while let Some((index, char)) = chars.next() {
+        let comparison = match char {
+            '<' | '>' | '=' => char,
+            _ => continue,
+        };
+        let or_equal = chars.next_if(|(_, next)| *next == '=').is_some();
+        let comparison = match (comparison, or_equal) {
+            ('<', false) => Comparison::Less,
+            ('<', true) => Comparison::LessOrEqual,
+            ('>', false) => Comparison::Greater,
+            ('>', true) => Comparison::GreaterOrEqual,
+            ('=', false) => Comparison::Equal,
+            _ => return None,
+        };
+        operands.push(inner[start..index].trim());
+        comparisons.push(comparison);
+        start = index + if or_equal { 2 } else { 1 };
+    }
+    operands.push(inner[start..].trim());
+    (!comparisons.is_empty()).then_some((operands, comparisons))
+}
+
+/// Evaluates a media feature, in its boolean, plain or range form.
+///
+/// [Reference](https://drafts.csswg.org/mediaqueries-4/#mq-features)
+fn evaluate_feature(inner: &str, viewport: &Viewport) -> Option<bool> {
+    if let Some((operands, comparisons)) = split_range(inner) {
+        return match (operands.as_slice(), comparisons.as_slice()) {
+            ([left, right], [comparison]) => {
+                if is_identifier(left) {
+                    let name = left.to_ascii_lowercase();
+                    compare_feature(&name, *comparison, right, viewport)
+                } else {
+                    let name = right.to_ascii_lowercase();
+                    compare_feature(&name, comparison.flip(), left, viewport)
+                }
+            }
+            // Both comparisons must point the same way, like `400px < width <= 800px`.
+            ([low, name, high], [first, second]) if first.is_less() == second.is_less() => {
+                let name = name.to_ascii_lowercase();
+                let low = compare_feature(&name, first.flip(), low, viewport)?;
+                let high = compare_feature(&name, *second, high, viewport)?;
+                Some(low && high)
+            }
+            _ => None,
+        };
+    }
+    if let Some((name, value)) = inner.split_once(':') {
+        let name = name.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if let Some(name) = name.strip_prefix("min-") {
+            return compare_feature(name, Comparison::GreaterOrEqual, value, viewport);
+        }
+        if let Some(name) = name.strip_prefix("max-") {
+            return compare_feature(name, Comparison::LessOrEqual, value, viewport);
+        }
+        if let Some(name) = name.strip_prefix("-webkit-min-") {
+            let name = format!("-webkit-{name}");
+            return compare_feature(&name, Comparison::GreaterOrEqual, value, viewport);
+        }
+        if let Some(name) = name.strip_prefix("-webkit-max-") {
+            let name = format!("-webkit-{name}");
+            return compare_feature(&name, Comparison::LessOrEqual, value, viewport);
+        }
+        return match feature_value(&name, viewport)? {
+            FeatureValue::Number(actual, kind) => {
+                let expected = parse_value(value, kind, viewport)?;
+                Some(Comparison::Equal.compare(actual, expected))
+            }
+            FeatureValue::Keyword(actual) => {
+                is_identifier(value).then(|| value.eq_ignore_ascii_case(actual))
+            }
+        };
+    }
+    // A boolean feature is true unless its value is zero or `none`.
+    let name = inner.to_ascii_lowercase();
+    match feature_value(&name, viewport)? {
+        FeatureValue::Number(value, _) => Some(value != 0.0),
+        FeatureValue::Keyword(value) => Some(!matches!(value, "none" | "no-preference")),
+    }
+}
+
+/// Compares a range feature with a value, like the `width` in `width >= 600px`.
+fn compare_feature(
+    name: &str,
+    comparison: Comparison,
+    value: &str,
+    viewport: &Viewport,
+) -> Option<bool> {
+    let FeatureValue::Number(actual, kind) = feature_value(name, viewport)? else {
+        return None;
+    };
+    let expected = parse_value(value, kind, viewport)?;
+    Some(comparison.compare(actual, expected))
+}
+
+/// The kinds of values that range features compare.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueKind {
+    Length,
+    Ratio,
+    Resolution,
+    Integer,
+    Number,
+}
+
+enum FeatureValue {
+    /// A value that can be compared, with lengths in pixels and resolutions in dots per pixel.
+    Number(f64, ValueKind),
+    Keyword(&'static str),
+}
+
+/// Returns the value of a media feature for a viewport, or `None` if the feature is not supported.
+fn feature_value(name: &str, viewport: &Viewport) -> Option<FeatureValue> {
+    let is_screen = viewport.media_type == MediaType::Screen;
+    let value = match name {
+        "width" | "device-width" => FeatureValue::Number(viewport.width, ValueKind::Length),
+        "height" | "device-height" => FeatureValue::Number(viewport.height, ValueKind::Length),
+        "aspect-ratio" | "device-aspect-ratio" => {
+            FeatureValue::Number(viewport.width / viewport.height, ValueKind::Ratio)
+        }
+        "resolution" => FeatureValue::Number(viewport.device_pixel_ratio, ValueKind::Resolution),
+        "-webkit-device-pixel-ratio" => {
+            FeatureValue::Number(viewport.device_pixel_ratio, ValueKind::Number)
+        }
+        "color" => FeatureValue::Number(8.0, ValueKind::Integer),
+        "color-index" | "monochrome" | "grid" => FeatureValue::Number(0.0, ValueKind::Integer),
+        "orientation" => FeatureValue::Keyword(match viewport.height >= viewport.width {
+            true => "portrait",
+            false => "landscape",
+        }),
+        "prefers-color-scheme" => FeatureValue::Keyword(match viewport.color_scheme {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+        }),
+        "prefers-reduced-motion" => FeatureValue::Keyword(match viewport.reduced_motion {
+            true => "reduce",
+            false => "no-preference",
+        }),
+        "hover" | "any-hover" => FeatureValue::Keyword(if is_screen { "hover" } else { "none" }),
+        "pointer" | "any-pointer" => FeatureValue::Keyword(if is_screen { "fine" } else { "none" }),
+        "update" => FeatureValue::Keyword(if is_screen { "fast" } else { "none" }),
+        "scan" => FeatureValue::Keyword("progressive"),
+        "display-mode" => FeatureValue::Keyword("browser"),
+        "dynamic-range" | "video-dynamic-range" => FeatureValue::Keyword("standard"),
+        "forced-colors" | "inverted-colors" => FeatureValue::Keyword("none"),
+        "prefers-contrast" | "prefers-reduced-transparency" | "prefers-reduced-data" => {
+            FeatureValue::Keyword("no-preference")
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Parses the value a range feature is compared with.
+fn parse_value(value: &str, kind: ValueKind, viewport: &Viewport) -> Option<f64> {
+    let value = value.to_ascii_lowercase();
+    match kind {
+        ValueKind::Length => {
+            let (number, unit) = split_dimension(&value)?;
+            // Relative lengths are resolved against the initial font size of 16px.
+            let pixels_per_unit = match unit {
+                "" if number == 0.0 => 0.0,
+                "px" => 1.0,
+                "cm" => 96.0 / 2.54,
+                "mm" => 96.0 / 25.4,
+                "q" => 96.0 / 101.6,
+                "in" => 96.0,
+                "pt" => 96.0 / 72.0,
+                "pc" => 16.0,
+                "em" | "rem" => 16.0,
+                "ex" | "ch" => 8.0,
+                "vw" => viewport.width / 100.0,
+                "vh" => viewport.height / 100.0,
+                "vmin" => viewport.width.min(viewport.height) / 100.0,
+                "vmax" => viewport.width.max(viewport.height) / 100.0,
+                _ => return None,
+            };
+            Some(number * pixels_per_unit)
+        }
+        ValueKind::Ratio => {
+            let (numerator, denominator) = match value.split_once('/') {
+                Some((numerator, denominator)) => (numerator, denominator),
+                None => (value.as_str(), "1"),
+            };
+            let numerator: f64 = numerator.trim().parse().ok()?;
+            let denominator: f64 = denominator.trim().parse().ok()?;
+            (numerator >= 0.0 && denominator > 0.0).then(|| numerator / denominator)
+        }
+        ValueKind::Resolution => {
+            let (number, unit) = split_dimension(&value)?;
+            let dots_per_pixel = match unit {
+                "dppx" | "x" => 1.0,
+                "dpi" => 1.0 / 96.0,
+                "dpcm" => 2.54 / 96.0,
+                _ => return None,
+            };
+            Some(number * dots_per_pixel)
+        }
+        ValueKind::Integer => value.parse::<i64>().ok().map(|value| value as f64),
+        ValueKind::Number => value.parse().ok(),
+    }
+}
+
+fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_' || first == '-')
+        && chars.all(|char| char.is_alphanumeric() || char == '_' || char == '-')
+}
+
+/// A media query list whose result is kept up to date with the viewport of its window, and which fires `change` events when the result changes.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/MediaQueryList)
+#[derive(Debug, Clone)]
+pub struct MediaQueryList {
+    pub(crate) inner: Rc<RefCell<MediaQueryListBase>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct MediaQueryListBase {
+    media: String,
+    document: WeakDocumentRef,
+    /// The result of the last evaluation, which `change` events are fired against.
+    matches: bool,
+    event_target: EventTarget,
+}
+
+impl MediaQueryList {
+    pub(crate) fn new(media: &str, document: WeakDocumentRef, viewport: &Viewport) -> Self {
+        let media = split_commas(&normalize_value(media))
+            .into_iter()
+            .filter(|query| !query.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let matches = evaluate_list(&media, viewport);
+        MediaQueryList {
+            inner: Rc::new(RefCell::new(MediaQueryListBase {
+                media,
+                document,
+                matches,
+                event_target: EventTarget::new(),
+            })),
+        }
+    }
+
+    /// Returns the serialized media query list.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/MediaQueryList/media)
+    pub fn media(&self) -> String {
+        self.inner.borrow().media.clone()
+    }
+
+    /// Returns true if the media query list matches the current viewport of the window.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/MediaQueryList/matches)
+    pub fn matches(&self) -> bool {
+        let base = self.inner.borrow();
+        match base.document.inner.upgrade() {
+            Some(document) => evaluate_list(&base.media, &document.borrow().viewport),
+            None => base.matches,
+        }
+    }
+
+    /// Evaluates the list again, and fires a `change` event if its result is different from the last evaluation.
+    ///
+    /// [Reference](https://drafts.csswg.org/cssom-view/#evaluate-media-queries-and-report-changes)
+    pub(crate) fn report_change(&mut self, viewport: &Viewport) {
+        {
+            let mut base = self.inner.borrow_mut();
+            let matches = evaluate_list(&base.media, viewport);
+            if matches == base.matches {
+                return;
+            }
+            base.matches = matches;
+        }
+        self.dispatch_event(&mut Event::trusted(EventType::Change, EventInit::default()));
+    }
+
+    pub(crate) fn downgrade(&self) -> Weak<RefCell<MediaQueryListBase>> {
+        Rc::downgrade(&self.inner)
+    }
+}
+
+impl AsEventTarget for MediaQueryList {
+    fn cast(&self) -> &EventTarget {
+        unsafe { &(*self.inner.as_ptr()).event_target }
+    }
+
+    fn cast_mut(&mut self) -> &mut EventTarget {
+        unsafe { &mut (*self.inner.as_ptr()).event_target }
+    }
+}
@@ -0,0 +1,28 @@
+//! CSS support: style sheets and their rules, the declaration blocks behind inline styles, and the parsing and serialization of selectors and properties.
+mod cascade;
+mod custom_properties;
+mod declaration;
+mod media;
+mod parser;
+mod properties;
+mod selector;
+mod stylesheet;
+mod supports;
+mod tokenizer;
+mod values;
+
+pub(crate) use cascade::{compute_font, px_value, ComputedStyle, StyleResolver};
+pub use declaration::CSSStyleDeclaration;
+pub(crate) use declaration::DeclarationBlock;
+pub use media::MediaQueryList;
+pub(crate) use media::{evaluate_list, MediaQueryListBase};
+pub(crate) use parser::split_commas;
+pub(crate) use properties::{is_animatable, longhands, PROPERTIES};
+pub(crate) use selector::{element_children, MatchContext, PseudoElement, SelectorList};
+pub(crate) use stylesheet::ParseContext;
+pub use stylesheet::{
+    CSSFontFaceRule, CSSImportRule, CSSKeyframeRule, CSSKeyframesRule, CSSLayerBlockRule,
+    CSSLayerStatementRule, CSSMediaRule, CSSPropertyRule, CSSRule, CSSRuleList, CSSStyleRule,
+    CSSStyleSheet, CSSSupportsRule, MediaList, StyleSheetList,
+};
+pub(crate) use values::{parse_color, parse_time, serialize_color, split_dimension};
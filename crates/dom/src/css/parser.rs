@@ -0,0 +1,321 @@
+//! The parsing of CSS text into rules and declarations, and helpers for splitting values into their components.
+use super::tokenizer::{component_value_end, serialize, source, tokenize, Token, TokenKind};
+
+/// A declaration parsed from a declaration list, before shorthands are expanded.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParsedDeclaration {
+    pub name: String,
+    pub value: String,
+    pub important: bool,
+}
+
+/// A rule parsed from a list of rules, before its prelude and block are interpreted.
+///
+/// [Reference](https://drafts.csswg.org/css-syntax/#parser-definitions)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RawRule {
+    /// A rule like `p { color: red }`, whose prelude is usually a selector list.
+    Qualified { prelude: String, block: String },
+    /// A rule starting with an at-keyword, like `@media screen { ... }` or `@import "a.css";`.
+    At {
+        name: String,
+        prelude: String,
+        block: Option<String>,
+    },
+}
+
+/// Parses a list of rules, such as the contents of a stylesheet. Qualified rules that are not terminated are dropped.
+///
+/// [Reference](https://drafts.csswg.org/css-syntax/#consume-list-of-rules)
+pub(crate) fn parse_rules(input: &str) -> Vec<RawRule> {
+    let tokens = tokenize(input);
+    let mut rules = vec![];
+    let mut index = 0;
+    while let Some(token) = tokens.get(index) {
+        match &token.kind {
+            // The CDO and CDC tokens are ignored at the top level.
+            kind if token.is_trivia() || matches!(kind, TokenKind::Cdo | TokenKind::Cdc) => {
+                index += 1;
+            }
+            TokenKind::AtKeyword(name) => {
+                let (rule, end) = consume_at_rule(&tokens, index, name);
+                rules.push(rule);
+                index = end;
+            }
+            _ => {
+                let (rule, end) = consume_qualified_rule(&tokens, index);
+                rules.extend(rule);
+                index = end;
+            }
+        }
+    }
+    rules
+}
+
+/// Consumes an at-rule, whose prelude runs until a semicolon or a block. Returns the rule and the index after it.
+///
+/// [Reference](https://drafts.csswg.org/css-syntax/#consume-at-rule)
+fn consume_at_rule(tokens: &[Token], start: usize, name: &str) -> (RawRule, usize) {
+    let prelude_start = start + 1;
+    let mut index = prelude_start;
+    let (prelude_end, block, end) = loop {
+        match tokens.get(index).map(|token| &token.kind) {
+            None => break (index, None, index),
+            Some(TokenKind::Semicolon) => break (index, None, index + 1),
+            Some(TokenKind::OpenCurly) => {
+                let end = component_value_end(tokens, index);
+                break (index, Some(block_source(tokens, index, end)), end);
+            }
+            Some(_) => index = component_value_end(tokens, index),
+        }
+    };
+    let rule = RawRule::At {
+        name: name.to_ascii_lowercase(),
+        prelude: serialize(&tokens[prelude_start..prelude_end]),
+        block,
+    };
+    (rule, end)
+}
+
+/// Consumes a qualified rule, whose prelude runs until a block. Returns the rule, or `None` if it has no block, and the index after it.
+///
+/// [Reference](https://drafts.csswg.org/css-syntax/#consume-qualified-rule)
+fn consume_qualified_rule(tokens: &[Token], start: usize) -> (Option<RawRule>, usize) {
+    let mut index = start;
+    while let Some(token) = tokens.get(index) {
+        if token.kind == TokenKind::OpenCurly {
+            let end = component_value_end(tokens, index);
+            let prelude = &tokens[start..index];
+            // A prelude with a bad string or URL cannot be a valid selector.
+            let rule = (!prelude.iter().any(is_bad_token)).then(|| RawRule::Qualified {
+                prelude: serialize(prelude),
+                block: block_source(tokens, index, end),
+            });
+            return (rule, end);
+        }
+        index = component_value_end(tokens, index);
+    }
+    (None, index)
+}
+
+/// Returns the text inside a `{}` block that runs from `start` to `end`, without its braces.
+fn block_source(tokens: &[Token], start: usize, end: usize) -> String {
+    let is_closed = tokens
+        .get(end - 1)
+        .is_some_and(|token| end - 1 > start && token.kind == TokenKind::CloseCurly);
+    let content_end = if is_closed { end - 1 } else { end };
+    source(&tokens[start + 1..content_end])
+}
+
+fn is_bad_token(token: &Token) -> bool {
+    matches!(token.kind, TokenKind::BadString | TokenKind::BadUrl)
+}
+
+/// Returns the index of the first character that matches `is_match` outside strings, functions and blocks.
+pub(crate) fn find_top_level(input: &str, is_match: impl Fn(char) -> bool) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, char) in input.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if let Some(quote_char) = quote {
+            if char == '\\' {
+                escaped = true;
+            } else if char == quote_char {
+                quote = None;
+            }
+            continue;
+        }
+        if depth == 0 && is_match(char) {
+            return Some(index);
+        }
+        match char {
+            '\\' => escaped = true,
+            '"' | '\'' => quote = Some(char),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits text at the characters that match `is_separator`, ignoring those inside strings, functions and blocks.
+pub(crate) fn split_top_level(input: &str, is_separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (index, char) in input.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match quote {
+            Some(_) if char == '\\' => escaped = true,
+            Some(quote_char) if char == quote_char => quote = None,
+            Some(_) => {}
+            None => match char {
+                '\\' => escaped = true,
+                '"' | '\'' => quote = Some(char),
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                _ if depth == 0 && is_separator(char) => {
+                    parts.push(&input[start..index]);
+                    start = index + char.len_utf8();
+                }
+                _ => {}
+            },
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+/// Splits a value into its space-separated components, keeping functions and strings intact.
+pub(crate) fn split_components(value: &str) -> Vec<&str> {
+    split_top_level(value, char::is_whitespace)
+        .into_iter()
+        .filter(|component| !component.is_empty())
+        .collect()
+}
+
+/// Splits a value into its comma-separated parts.
+pub(crate) fn split_commas(value: &str) -> Vec<&str> {
+    split_top_level(value, |char| char == ',')
+        .into_iter()
+        .map(str::trim)
+        .collect()
+}
+
+/// Collapses the whitespace in a value outside of strings, and trims it.
+pub(crate) fn normalize_value(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut quote = None;
+    let mut escaped = false;
+    let mut pending_space = false;
+    for char in value.trim().chars() {
+        if quote.is_none() && !escaped && char.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space {
+            output.push(' ');
+            pending_space = false;
+        }
+        output.push(char);
+        if escaped {
+            escaped = false;
+        } else if char == '\\' {
+            escaped = true;
+        } else if quote == Some(char) {
+            quote = None;
+        } else if quote.is_none() && (char == '"' || char == '\'') {
+            quote = Some(char);
+        }
+    }
+    output
+}
+
+/// Returns true if the name is a custom property name, like `--accent-color`.
+pub(crate) fn is_custom_property(name: &str) -> bool {
+    name.len() > 2 && name.starts_with("--")
+}
+
+/// Parses a list of declarations, such as the value of a `style` attribute. Malformed declarations are dropped, as are at-rules.
+///
+/// [Reference](https://drafts.csswg.org/css-syntax/#consume-list-of-declarations)
+pub(crate) fn parse_declarations(input: &str) -> Vec<ParsedDeclaration> {
+    let tokens = tokenize(input);
+    let mut declarations = vec![];
+    let mut index = 0;
+    while let Some(token) = tokens.get(index) {
+        match &token.kind {
+            _ if token.is_trivia() => index += 1,
+            TokenKind::Semicolon => index += 1,
+            TokenKind::AtKeyword(name) => index = consume_at_rule(&tokens, index, name).1,
+            kind => {
+                // Anything else runs until the next semicolon, and is only a declaration if it starts with a name.
+                let start = index;
+                while tokens
+                    .get(index)
+                    .is_some_and(|token| token.kind != TokenKind::Semicolon)
+                {
+                    index = component_value_end(&tokens, index);
+                }
+                if let TokenKind::Ident(name) = kind {
+                    declarations.extend(parse_declaration(name, &tokens[start + 1..index]));
+                }
+            }
+        }
+    }
+    declarations
+}
+
+/// Parses a declaration from its name and the tokens after it.
+///
+/// [Reference](https://drafts.csswg.org/css-syntax/#consume-declaration)
+fn parse_declaration(name: &str, tokens: &[Token]) -> Option<ParsedDeclaration> {
+    let colon = tokens.iter().position(|token| !token.is_trivia())?;
+    if tokens[colon].kind != TokenKind::Colon {
+        return None;
+    }
+    let mut value = &tokens[colon + 1..];
+    // The value is important if its last two tokens are `!` and `important`.
+    let significant: Vec<usize> = (0..value.len())
+        .filter(|index| !value[*index].is_trivia())
+        .collect();
+    let mut important = false;
+    if let [.., bang, last] = significant[..] {
+        let is_important = |kind: &TokenKind| matches!(kind, TokenKind::Ident(ident) if ident.eq_ignore_ascii_case("important"));
+        if value[bang].kind == TokenKind::Delim('!') && is_important(&value[last].kind) {
+            important = true;
+            value = &value[..bang];
+        }
+    }
+    // Bad strings and URLs make a value invalid, as do closing brackets that do not match an opening one and `!` outside of functions and blocks.
+    if value.iter().any(is_bad_token) {
+        return None;
+    }
+    let mut index = 0;
+    while let Some(token) = value.get(index) {
+        if matches!(
+            token.kind,
+            TokenKind::Delim('!')
+                | TokenKind::CloseParen
+                | TokenKind::CloseSquare
+                | TokenKind::CloseCurly
+        ) {
+            return None;
+        }
+        index = component_value_end(value, index);
+    }
+    let name = if is_custom_property(name) {
+        name.to_owned()
+    } else {
+        name.to_ascii_lowercase()
+    };
+    let value = serialize(value);
+    if value.is_empty() && !is_custom_property(&name) {
+        return None;
+    }
+    Some(ParsedDeclaration {
+        name,
+        value,
+        important,
+    })
+}
+
+/// Removes a trailing `!important` from a value, returning the rest of the value and whether it was present.
+pub(crate) fn strip_important(value: &str) -> (&str, bool) {
+    if let Some(index) = value.rfind('!') {
+        if value[index + 1..].trim().eq_ignore_ascii_case("important") {
+            return (value[..index].trim_end(), true);
+        }
+    }
+    (value, false)
+}
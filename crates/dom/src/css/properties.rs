@@ -0,0 +1,1695 @@
+//! The supported CSS properties, and the expansion and serialization of shorthand properties.
+use super::{
+    parser::{normalize_value, split_commas, split_components},
+    values::{
+        function_name, is_color, is_css_wide_keyword, is_easing_function, is_identifier, is_image,
+        is_integer, is_keyword, is_length, is_length_percentage, is_line_style, is_line_width,
+        is_number, is_percentage, is_string, is_time, is_transform_function, split_dimension,
+    },
+};
+
+/// A longhand property.
+#[derive(Debug)]
+pub(crate) struct Property {
+    pub name: &'static str,
+    /// The initial value of the property.
+    pub initial: &'static str,
+    /// Whether the property inherits its value from the parent element by default.
+    pub inherited: bool,
+}
+
+macro_rules! properties {
+    ($($name:literal: $initial:literal $(, $inherited:ident)?;)*) => {
+        /// The longhand properties that are supported.
+        pub(crate) const PROPERTIES: &[Property] = &[
+            $(Property {
+                name: $name,
+                initial: $initial,
+                inherited: properties!(@inherited $($inherited)?),
+            },)*
+        ];
+    };
+    (@inherited inherited) => { true };
+    (@inherited) => { false };
+}
+
+properties! {
+    "align-content": "normal";
+    "align-items": "normal";
+    "align-self": "auto";
+    "animation-delay": "0s";
+    "animation-direction": "normal";
+    "animation-duration": "0s";
+    "animation-fill-mode": "none";
+    "animation-iteration-count": "1";
+    "animation-name": "none";
+    "animation-play-state": "running";
+    "animation-timing-function": "ease";
+    "background-attachment": "scroll";
+    "background-color": "transparent";
+    "background-image": "none";
+    "background-position": "0% 0%";
+    "background-repeat": "repeat";
+    "background-size": "auto";
+    "border-bottom-color": "currentcolor";
+    "border-bottom-left-radius": "0";
+    "border-bottom-right-radius": "0";
+    "border-bottom-style": "none";
+    "border-bottom-width": "medium";
+    "border-collapse": "separate", inherited;
+    "border-left-color": "currentcolor";
+    "border-left-style": "none";
+    "border-left-width": "medium";
+    "border-right-color": "currentcolor";
+    "border-right-style": "none";
+    "border-right-width": "medium";
+    "border-spacing": "0", inherited;
+    "border-top-color": "currentcolor";
+    "border-top-left-radius": "0";
+    "border-top-right-radius": "0";
+    "border-top-style": "none";
+    "border-top-width": "medium";
+    "bottom": "auto";
+    "box-shadow": "none";
+    "box-sizing": "content-box";
+    "caption-side": "top", inherited;
+    "clear": "none";
+    "color": "canvastext", inherited;
+    "column-gap": "normal";
+    "content": "normal";
+    "content-visibility": "visible";
+    "cursor": "auto", inherited;
+    "direction": "ltr", inherited;
+    "display": "inline";
+    "empty-cells": "show", inherited;
+    "flex-basis": "auto";
+    "flex-direction": "row";
+    "flex-grow": "0";
+    "flex-shrink": "1";
+    "flex-wrap": "nowrap";
+    "float": "none";
+    "font-family": "serif", inherited;
+    "font-size": "medium", inherited;
+    "font-style": "normal", inherited;
+    "font-variant": "normal", inherited;
+    "font-weight": "normal", inherited;
+    "grid-auto-columns": "auto";
+    "grid-auto-flow": "row";
+    "grid-auto-rows": "auto";
+    "grid-column-end": "auto";
+    "grid-column-start": "auto";
+    "grid-row-end": "auto";
+    "grid-row-start": "auto";
+    "grid-template-areas": "none";
+    "grid-template-columns": "none";
+    "grid-template-rows": "none";
+    "height": "auto";
+    "justify-content": "normal";
+    "justify-items": "legacy";
+    "justify-self": "auto";
+    "left": "auto";
+    "letter-spacing": "normal", inherited;
+    "line-height": "normal", inherited;
+    "list-style-image": "none", inherited;
+    "list-style-position": "outside", inherited;
+    "list-style-type": "disc", inherited;
+    "margin-bottom": "0";
+    "margin-left": "0";
+    "margin-right": "0";
+    "margin-top": "0";
+    "max-height": "none";
+    "max-width": "none";
+    "min-height": "auto";
+    "min-width": "auto";
+    "object-fit": "fill";
+    "opacity": "1";
+    "order": "0";
+    "outline-color": "currentcolor";
+    "outline-offset": "0";
+    "outline-style": "none";
+    "outline-width": "medium";
+    "overflow-wrap": "normal", inherited;
+    "overflow-x": "visible";
+    "overflow-y": "visible";
+    "padding-bottom": "0";
+    "padding-left": "0";
+    "padding-right": "0";
+    "padding-top": "0";
+    "pointer-events": "auto", inherited;
+    "position": "static";
+    "quotes": "auto", inherited;
+    "right": "auto";
+    "row-gap": "normal";
+    "scroll-behavior": "auto";
+    "scroll-margin-bottom": "0";
+    "scroll-margin-left": "0";
+    "scroll-margin-right": "0";
+    "scroll-margin-top": "0";
+    "scroll-padding-bottom": "auto";
+    "scroll-padding-left": "auto";
+    "scroll-padding-right": "auto";
+    "scroll-padding-top": "auto";
+    "scroll-snap-align": "none";
+    "scroll-snap-stop": "normal";
+    "scroll-snap-type": "none";
+    "tab-size": "8", inherited;
+    "table-layout": "auto";
+    "text-align": "start", inherited;
+    "text-decoration-color": "currentcolor";
+    "text-decoration-line": "none";
+    "text-decoration-style": "solid";
+    "text-indent": "0", inherited;
+    "text-overflow": "clip";
+    "text-shadow": "none", inherited;
+    "text-transform": "none", inherited;
+    "top": "auto";
+    "transform": "none";
+    "transform-origin": "50% 50% 0";
+    "transition-delay": "0s";
+    "transition-duration": "0s";
+    "transition-property": "all";
+    "transition-timing-function": "ease";
+    "unicode-bidi": "normal";
+    "user-select": "auto";
+    "vertical-align": "baseline";
+    "visibility": "visible", inherited;
+    "white-space": "normal", inherited;
+    "width": "auto";
+    "word-break": "normal", inherited;
+    "word-spacing": "normal", inherited;
+    "writing-mode": "horizontal-tb", inherited;
+    "z-index": "auto";
+}
+
+/// Returns the longhand property with a name.
+pub(crate) fn property(name: &str) -> Option<&'static Property> {
+    PROPERTIES.iter().find(|property| property.name == name)
+}
+
+/// The keywords of the box alignment properties, like `align-items` and `justify-content`.
+///
+/// [Reference](https://drafts.csswg.org/css-align/)
+const ALIGNMENT_KEYWORDS: &[&str] = &[
+    "normal",
+    "auto",
+    "stretch",
+    "baseline",
+    "first",
+    "last",
+    "start",
+    "end",
+    "center",
+    "self-start",
+    "self-end",
+    "flex-start",
+    "flex-end",
+    "left",
+    "right",
+    "space-between",
+    "space-around",
+    "space-evenly",
+    "safe",
+    "unsafe",
+    "legacy",
+];
+
+const DISPLAY_KEYWORDS: &[&str] = &[
+    "block",
+    "inline",
+    "run-in",
+    "flow",
+    "flow-root",
+    "table",
+    "flex",
+    "grid",
+    "ruby",
+    "list-item",
+];
+
+const SIZE_KEYWORDS: &[&str] = &["min-content", "max-content", "fit-content"];
+
+/// Parses the value of a longhand property, returning `None` if it does not match the grammar of the property. Custom properties and values with `var()` functions are not longhand values, and are parsed elsewhere.
+///
+/// [Reference](https://drafts.csswg.org/css-values/#value-defs)
+pub(crate) fn parse_longhand(longhand: &str, value: &str) -> Option<String> {
+    let value = normalize_value(value);
+    if is_css_wide_keyword(&value) {
+        return Some(value.to_ascii_lowercase());
+    }
+    let components = split_components(&value);
+    // Helpers for the common shapes of grammars.
+    let single =
+        |is_valid: &dyn Fn(&str) -> bool| matches!(*components, [component] if is_valid(component));
+    let keywords = |keywords: &[&str]| single(&|component| is_keyword(component, keywords));
+    let list = |is_valid: &dyn Fn(&str) -> bool| {
+        split_commas(&value)
+            .iter()
+            .all(|layer| !layer.is_empty() && is_valid(layer))
+    };
+    let is_valid = match longhand {
+        "align-content" | "align-items" | "align-self" | "justify-content" | "justify-items"
+        | "justify-self" => {
+            (1..=2).contains(&components.len())
+                && components
+                    .iter()
+                    .all(|component| is_keyword(component, ALIGNMENT_KEYWORDS))
+        }
+        "animation-delay" | "animation-duration" | "transition-delay" | "transition-duration" => {
+            list(&is_time)
+        }
+        "animation-direction" => list(&|layer| {
+            is_keyword(
+                layer,
+                &["normal", "reverse", "alternate", "alternate-reverse"],
+            )
+        }),
+        "animation-fill-mode" => {
+            list(&|layer| is_keyword(layer, &["none", "forwards", "backwards", "both"]))
+        }
+        "animation-iteration-count" => {
+            list(&|layer| layer.eq_ignore_ascii_case("infinite") || is_non_negative_number(layer))
+        }
+        "animation-name" => list(&|layer| {
+            layer.eq_ignore_ascii_case("none") || is_identifier(layer) || is_string(layer)
+        }),
+        "animation-play-state" => list(&|layer| is_keyword(layer, &["running", "paused"])),
+        "animation-timing-function" | "transition-timing-function" => list(&is_easing_function),
+        "background-attachment" => list(&|layer| is_keyword(layer, &["scroll", "fixed", "local"])),
+        "background-color"
+        | "border-bottom-color"
+        | "border-left-color"
+        | "border-right-color"
+        | "border-top-color"
+        | "color"
+        | "outline-color"
+        | "text-decoration-color" => single(&is_color),
+        "background-image" | "list-style-image" => list(&is_image),
+        "background-position" => list(&|layer| {
+            let components = split_components(layer);
+            components.len() <= 4 && components.iter().all(|component| is_position(component))
+        }),
+        "background-repeat" => list(&|layer| {
+            let components = split_components(layer);
+            components.len() <= 2
+                && components.iter().all(|component| {
+                    is_keyword(
+                        component,
+                        &[
+                            "repeat",
+                            "repeat-x",
+                            "repeat-y",
+                            "no-repeat",
+                            "space",
+                            "round",
+                        ],
+                    )
+                })
+        }),
+        "background-size" => list(&|layer| match *split_components(layer) {
+            [keyword] if is_keyword(keyword, &["cover", "contain"]) => true,
+            ref sizes => {
+                sizes.len() <= 2
+                    && sizes.iter().all(|size| {
+                        size.eq_ignore_ascii_case("auto") || is_non_negative_length_percentage(size)
+                    })
+            }
+        }),
+        "border-bottom-left-radius"
+        | "border-bottom-right-radius"
+        | "border-top-left-radius"
+        | "border-top-right-radius" => {
+            components.len() <= 2
+                && components
+                    .iter()
+                    .all(|component| is_non_negative_length_percentage(component))
+        }
+        "border-bottom-style" | "border-left-style" | "border-right-style" | "border-top-style" => {
+            single(&is_line_style)
+        }
+        "outline-style" => {
+            single(&|component| is_line_style(component) || component.eq_ignore_ascii_case("auto"))
+        }
+        "border-bottom-width"
+        | "border-left-width"
+        | "border-right-width"
+        | "border-top-width"
+        | "outline-width" => single(&is_line_width),
+        "border-collapse" => keywords(&["separate", "collapse"]),
+        "border-spacing" => {
+            components.len() <= 2 && components.iter().all(|component| is_length(component))
+        }
+        "bottom" | "left" | "right" | "top" | "margin-bottom" | "margin-left" | "margin-right"
+        | "margin-top" => single(&|component| {
+            is_length_percentage(component) || component.eq_ignore_ascii_case("auto")
+        }),
+        "box-shadow" | "text-shadow" => {
+            value.eq_ignore_ascii_case("none")
+                || list(&|layer| {
+                    let components = split_components(layer);
+                    let lengths = components
+                        .iter()
+                        .filter(|component| is_length(component))
+                        .count();
+                    (2..=4).contains(&lengths)
+                        && components.iter().all(|component| {
+                            is_length(component)
+                                || is_color(component)
+                                || (longhand == "box-shadow"
+                                    && component.eq_ignore_ascii_case("inset"))
+                        })
+                })
+        }
+        "box-sizing" => keywords(&["content-box", "border-box"]),
+        "caption-side" => keywords(&["top", "bottom"]),
+        "clear" => keywords(&[
+            "none",
+            "left",
+            "right",
+            "both",
+            "inline-start",
+            "inline-end",
+        ]),
+        "column-gap" | "row-gap" => single(&|component| {
+            component.eq_ignore_ascii_case("normal") || is_non_negative_length_percentage(component)
+        }),
+        "content" => {
+            keywords(&["normal", "none"])
+                || components.iter().all(|component| {
+                    is_string(component)
+                        || *component == "/"
+                        || is_keyword(
+                            component,
+                            &[
+                                "open-quote",
+                                "close-quote",
+                                "no-open-quote",
+                                "no-close-quote",
+                            ],
+                        )
+                        || (is_image(component) && !component.eq_ignore_ascii_case("none"))
+                        || function_name(component)
+                            .is_some_and(|name| is_keyword(name, &["attr", "counter", "counters"]))
+                })
+        }
+        "content-visibility" => keywords(&["visible", "hidden", "auto"]),
+        "cursor" => {
+            let layers = split_commas(&value);
+            let (last, images) = layers.split_last()?;
+            is_identifier(last)
+                && images.iter().all(|image| match *split_components(image) {
+                    [url] => is_image(url),
+                    [url, x, y] => is_image(url) && is_number(x) && is_number(y),
+                    _ => false,
+                })
+        }
+        "direction" => keywords(&["ltr", "rtl"]),
+        "display" => match *components {
+            [keyword] => {
+                is_keyword(keyword, DISPLAY_KEYWORDS)
+                    || is_keyword(
+                        keyword,
+                        &[
+                            "contents",
+                            "none",
+                            "inline-block",
+                            "inline-table",
+                            "inline-flex",
+                            "inline-grid",
+                            "table-row-group",
+                            "table-header-group",
+                            "table-footer-group",
+                            "table-row",
+                            "table-cell",
+                            "table-column-group",
+                            "table-column",
+                            "table-caption",
+                            "ruby-base",
+                            "ruby-text",
+                            "ruby-base-container",
+                            "ruby-text-container",
+                        ],
+                    )
+            }
+            ref keywords => {
+                keywords.len() <= 3
+                    && keywords.iter().enumerate().all(|(index, keyword)| {
+                        is_keyword(keyword, DISPLAY_KEYWORDS)
+                            && !keywords[..index]
+                                .iter()
+                                .any(|other| other.eq_ignore_ascii_case(keyword))
+                    })
+            }
+        },
+        "empty-cells" => keywords(&["show", "hide"]),
+        "flex-basis" => single(&is_flex_basis),
+        "flex-direction" => keywords(&["row", "row-reverse", "column", "column-reverse"]),
+        "flex-grow" | "flex-shrink" => single(&is_non_negative_number),
+        "flex-wrap" => keywords(&["nowrap", "wrap", "wrap-reverse"]),
+        "float" => keywords(&["left", "right", "none", "inline-start", "inline-end"]),
+        "font-family" => list(&|family| {
+            is_string(family)
+                || split_components(family)
+                    .iter()
+                    .all(|component| is_identifier(component))
+        }),
+        "font-size" => single(&|component| {
+            is_non_negative_length_percentage(component)
+                || is_keyword(
+                    component,
+                    &[
+                        "xx-small",
+                        "x-small",
+                        "small",
+                        "medium",
+                        "large",
+                        "x-large",
+                        "xx-large",
+                        "xxx-large",
+                        "smaller",
+                        "larger",
+                    ],
+                )
+        }),
+        "font-style" => match *components {
+            [keyword] => is_keyword(keyword, &["normal", "italic", "oblique"]),
+            [oblique, angle] => oblique.eq_ignore_ascii_case("oblique") && is_angle(angle),
+            _ => false,
+        },
+        "font-variant" => {
+            keywords(&["normal", "none"])
+                || components.iter().all(|component| {
+                    is_keyword(
+                        component,
+                        &[
+                            "small-caps",
+                            "all-small-caps",
+                            "petite-caps",
+                            "all-petite-caps",
+                            "unicase",
+                            "titling-caps",
+                        ],
+                    )
+                })
+        }
+        "font-weight" => single(&|component| {
+            is_keyword(component, &["normal", "bold", "bolder", "lighter"])
+                || (is_number(component)
+                    && component
+                        .parse::<f64>()
+                        .is_ok_and(|n| (1.0..=1000.0).contains(&n)))
+        }),
+        "grid-auto-columns" | "grid-auto-rows" => {
+            !components.is_empty() && components.iter().all(|component| is_track_size(component))
+        }
+        "grid-auto-flow" => {
+            (1..=2).contains(&components.len())
+                && components
+                    .iter()
+                    .all(|component| is_keyword(component, &["row", "column", "dense"]))
+        }
+        "grid-column-end" | "grid-column-start" | "grid-row-end" | "grid-row-start" => {
+            keywords(&["auto"])
+                || ((1..=3).contains(&components.len())
+                    && components.iter().all(|component| {
+                        component.eq_ignore_ascii_case("span")
+                            || (is_integer(component) && *component != "0")
+                            || (is_identifier(component) && !component.eq_ignore_ascii_case("auto"))
+                    }))
+        }
+        "grid-template-areas" => {
+            keywords(&["none"]) || components.iter().all(|component| is_string(component))
+        }
+        "grid-template-columns" | "grid-template-rows" => {
+            keywords(&["none", "subgrid", "masonry"])
+                || components.iter().all(|component| {
+                    is_track_size(component)
+                        || (component.starts_with('[') && component.ends_with(']'))
+                        || function_name(component)
+                            .is_some_and(|name| name.eq_ignore_ascii_case("repeat"))
+                })
+        }
+        "height" | "width" | "min-height" | "min-width" | "max-height" | "max-width" => {
+            single(&|component| {
+                is_non_negative_length_percentage(component)
+                    || is_keyword(component, SIZE_KEYWORDS)
+                    || function_name(component)
+                        .is_some_and(|name| name.eq_ignore_ascii_case("fit-content"))
+                    || match longhand {
+                        "max-height" | "max-width" => component.eq_ignore_ascii_case("none"),
+                        _ => component.eq_ignore_ascii_case("auto"),
+                    }
+            })
+        }
+        "letter-spacing" => {
+            single(&|component| component.eq_ignore_ascii_case("normal") || is_length(component))
+        }
+        "word-spacing" => single(&|component| {
+            component.eq_ignore_ascii_case("normal") || is_length_percentage(component)
+        }),
+        "line-height" => single(&|component| {
+            component.eq_ignore_ascii_case("normal")
+                || is_non_negative_number(component)
+                || is_non_negative_length_percentage(component)
+        }),
+        "list-style-position" => keywords(&["inside", "outside"]),
+        "list-style-type" => single(&|component| is_identifier(component) || is_string(component)),
+        "object-fit" => keywords(&["fill", "contain", "cover", "none", "scale-down"]),
+        "opacity" => single(&|component| is_number(component) || is_percentage(component)),
+        "order" => single(&is_integer),
+        "z-index" => {
+            single(&|component| component.eq_ignore_ascii_case("auto") || is_integer(component))
+        }
+        "outline-offset" => single(&is_length),
+        "overflow-wrap" => keywords(&["normal", "break-word", "anywhere"]),
+        "overflow-x" | "overflow-y" => keywords(&["visible", "hidden", "clip", "scroll", "auto"]),
+        "padding-bottom" | "padding-left" | "padding-right" | "padding-top" => {
+            single(&is_non_negative_length_percentage)
+        }
+        "pointer-events" => keywords(&[
+            "auto",
+            "none",
+            "visiblepainted",
+            "visiblefill",
+            "visiblestroke",
+            "visible",
+            "painted",
+            "fill",
+            "stroke",
+            "all",
+        ]),
+        "position" => keywords(&["static", "relative", "absolute", "fixed", "sticky"]),
+        "quotes" => {
+            keywords(&["auto", "none"])
+                || (components.len().is_multiple_of(2)
+                    && components.iter().all(|component| is_string(component)))
+        }
+        "scroll-behavior" => keywords(&["auto", "smooth"]),
+        "scroll-margin-bottom"
+        | "scroll-margin-left"
+        | "scroll-margin-right"
+        | "scroll-margin-top" => single(&is_length),
+        "scroll-padding-bottom"
+        | "scroll-padding-left"
+        | "scroll-padding-right"
+        | "scroll-padding-top" => single(&|component| {
+            component.eq_ignore_ascii_case("auto") || is_non_negative_length_percentage(component)
+        }),
+        "scroll-snap-align" => {
+            (1..=2).contains(&components.len())
+                && components
+                    .iter()
+                    .all(|component| is_keyword(component, &["none", "start", "end", "center"]))
+        }
+        "scroll-snap-stop" => keywords(&["normal", "always"]),
+        "scroll-snap-type" => match *components {
+            [keyword] => is_keyword(keyword, &["none", "x", "y", "block", "inline", "both"]),
+            [axis, strictness] => {
+                is_keyword(axis, &["x", "y", "block", "inline", "both"])
+                    && is_keyword(strictness, &["mandatory", "proximity"])
+            }
+            _ => false,
+        },
+        "tab-size" => single(&|component| {
+            is_non_negative_number(component) || is_non_negative_length_percentage(component)
+        }),
+        "table-layout" => keywords(&["auto", "fixed"]),
+        "text-align" => keywords(&[
+            "start",
+            "end",
+            "left",
+            "right",
+            "center",
+            "justify",
+            "match-parent",
+            "justify-all",
+        ]),
+        "text-decoration-line" => {
+            keywords(&["none"])
+                || components.iter().all(|component| {
+                    is_keyword(
+                        component,
+                        &["underline", "overline", "line-through", "blink"],
+                    )
+                })
+        }
+        "text-decoration-style" => keywords(&["solid", "double", "dotted", "dashed", "wavy"]),
+        "text-indent" => {
+            components
+                .iter()
+                .filter(|component| is_length_percentage(component))
+                .count()
+                == 1
+                && components.iter().all(|component| {
+                    is_length_percentage(component)
+                        || is_keyword(component, &["hanging", "each-line"])
+                })
+        }
+        "text-overflow" => {
+            (1..=2).contains(&components.len())
+                && components.iter().all(|component| {
+                    is_keyword(component, &["clip", "ellipsis"]) || is_string(component)
+                })
+        }
+        "text-transform" => keywords(&[
+            "none",
+            "capitalize",
+            "uppercase",
+            "lowercase",
+            "full-width",
+            "full-size-kana",
+        ]),
+        "transform" => {
+            keywords(&["none"])
+                || components
+                    .iter()
+                    .all(|component| is_transform_function(component))
+        }
+        "transform-origin" => {
+            components.len() <= 3 && components.iter().all(|component| is_position(component))
+        }
+        "transition-property" => {
+            keywords(&["none"])
+                || list(&|layer| is_identifier(layer) && !layer.eq_ignore_ascii_case("none"))
+        }
+        "unicode-bidi" => keywords(&[
+            "normal",
+            "embed",
+            "isolate",
+            "bidi-override",
+            "isolate-override",
+            "plaintext",
+        ]),
+        "user-select" => keywords(&["auto", "text", "none", "contain", "all"]),
+        "vertical-align" => single(&|component| {
+            is_length_percentage(component)
+                || is_keyword(
+                    component,
+                    &[
+                        "baseline",
+                        "sub",
+                        "super",
+                        "text-top",
+                        "text-bottom",
+                        "middle",
+                        "top",
+                        "bottom",
+                    ],
+                )
+        }),
+        "visibility" => keywords(&["visible", "hidden", "collapse"]),
+        "white-space" => keywords(&[
+            "normal",
+            "pre",
+            "nowrap",
+            "pre-wrap",
+            "break-spaces",
+            "pre-line",
+        ]),
+        "word-break" => keywords(&["normal", "break-all", "keep-all", "break-word"]),
+        "writing-mode" => keywords(&[
+            "horizontal-tb",
+            "vertical-rl",
+            "vertical-lr",
+            "sideways-rl",
+            "sideways-lr",
+        ]),
+        _ => false,
+    };
+    is_valid.then_some(value)
+}
+
+fn is_non_negative_number(value: &str) -> bool {
+    is_number(value) && !value.starts_with('-')
+}
+
+fn is_non_negative_length_percentage(value: &str) -> bool {
+    is_length_percentage(value) && !value.starts_with('-')
+}
+
+fn is_angle(value: &str) -> bool {
+    matches!(split_dimension(value), Some((_, unit)) if is_keyword(unit, &["deg", "grad", "rad", "turn"]))
+}
+
+/// Returns true if the value is a component of a position, like `left` or `25%`.
+fn is_position(value: &str) -> bool {
+    is_length_percentage(value) || is_keyword(value, &["left", "right", "top", "bottom", "center"])
+}
+
+/// Returns true if the value is the size of a grid track, like `1fr` or `minmax(100px, auto)`.
+///
+/// [Reference](https://drafts.csswg.org/css-grid/#typedef-track-size)
+fn is_track_size(value: &str) -> bool {
+    is_length_percentage(value)
+        || matches!(split_dimension(value), Some((number, unit)) if number >= 0.0 && unit.eq_ignore_ascii_case("fr"))
+        || is_keyword(value, &["auto", "min-content", "max-content"])
+        || function_name(value).is_some_and(|name| is_keyword(name, &["minmax", "fit-content"]))
+}
+
+/// The shorthand properties, with the longhands they set in the order they are given in the shorthand.
+const SHORTHANDS: &[(&str, &[&str])] = &[
+    (
+        "animation",
+        &[
+            "animation-duration",
+            "animation-timing-function",
+            "animation-delay",
+            "animation-iteration-count",
+            "animation-direction",
+            "animation-fill-mode",
+            "animation-play-state",
+            "animation-name",
+        ],
+    ),
+    (
+        "background",
+        &[
+            "background-color",
+            "background-image",
+            "background-repeat",
+            "background-attachment",
+            "background-position",
+            "background-size",
+        ],
+    ),
+    (
+        "border",
+        &[
+            "border-top-width",
+            "border-right-width",
+            "border-bottom-width",
+            "border-left-width",
+            "border-top-style",
+            "border-right-style",
+            "border-bottom-style",
+            "border-left-style",
+            "border-top-color",
+            "border-right-color",
+            "border-bottom-color",
+            "border-left-color",
+        ],
+    ),
+    (
+        "border-bottom",
+        &[
+            "border-bottom-width",
+            "border-bottom-style",
+            "border-bottom-color",
+        ],
+    ),
+    (
+        "border-color",
+        &[
+            "border-top-color",
+            "border-right-color",
+            "border-bottom-color",
+            "border-left-color",
+        ],
+    ),
+    (
+        "border-left",
+        &[
+            "border-left-width",
+            "border-left-style",
+            "border-left-color",
+        ],
+    ),
+    (
+        "border-radius",
+        &[
+            "border-top-left-radius",
+            "border-top-right-radius",
+            "border-bottom-right-radius",
+            "border-bottom-left-radius",
+        ],
+    ),
+    (
+        "border-right",
+        &[
+            "border-right-width",
+            "border-right-style",
+            "border-right-color",
+        ],
+    ),
+    (
+        "border-style",
+        &[
+            "border-top-style",
+            "border-right-style",
+            "border-bottom-style",
+            "border-left-style",
+        ],
+    ),
+    (
+        "border-top",
+        &["border-top-width", "border-top-style", "border-top-color"],
+    ),
+    (
+        "border-width",
+        &[
+            "border-top-width",
+            "border-right-width",
+            "border-bottom-width",
+            "border-left-width",
+        ],
+    ),
+    ("flex", &["flex-grow", "flex-shrink", "flex-basis"]),
+    ("flex-flow", &["flex-direction", "flex-wrap"]),
+    (
+        "font",
+        &[
+            "font-style",
+            "font-variant",
+            "font-weight",
+            "font-size",
+            "line-height",
+            "font-family",
+        ],
+    ),
+    ("gap", &["row-gap", "column-gap"]),
+    (
+        "grid-area",
+        &[
+            "grid-row-start",
+            "grid-column-start",
+            "grid-row-end",
+            "grid-column-end",
+        ],
+    ),
+    ("grid-column", &["grid-column-start", "grid-column-end"]),
+    ("grid-row", &["grid-row-start", "grid-row-end"]),
+    ("inset", &["top", "right", "bottom", "left"]),
+    (
+        "list-style",
+        &["list-style-position", "list-style-image", "list-style-type"],
+    ),
+    (
+        "margin",
+        &["margin-top", "margin-right", "margin-bottom", "margin-left"],
+    ),
+    (
+        "outline",
+        &["outline-width", "outline-style", "outline-color"],
+    ),
+    ("overflow", &["overflow-x", "overflow-y"]),
+    (
+        "padding",
+        &[
+            "padding-top",
+            "padding-right",
+            "padding-bottom",
+            "padding-left",
+        ],
+    ),
+    (
+        "scroll-margin",
+        &[
+            "scroll-margin-top",
+            "scroll-margin-right",
+            "scroll-margin-bottom",
+            "scroll-margin-left",
+        ],
+    ),
+    (
+        "scroll-padding",
+        &[
+            "scroll-padding-top",
+            "scroll-padding-right",
+            "scroll-padding-bottom",
+            "scroll-padding-left",
+        ],
+    ),
+    (
+        "text-decoration",
+        &[
+            "text-decoration-line",
+            "text-decoration-style",
+            "text-decoration-color",
+        ],
+    ),
+    (
+        "transition",
+        &[
+            "transition-property",
+            "transition-duration",
+            "transition-timing-function",
+            "transition-delay",
+        ],
+    ),
+];
+
+/// Returns the longhands of a shorthand property.
+pub(crate) fn longhands(shorthand: &str) -> Option<&'static [&'static str]> {
+    SHORTHANDS
+        .iter()
+        .find(|(name, _)| *name == shorthand)
+        .map(|(_, longhands)| *longhands)
+}
+
+/// Returns the shorthands that set a longhand, with the shorthands that set the most longhands first.
+pub(crate) fn shorthands_of(longhand: &str) -> Vec<&'static str> {
+    let mut shorthands: Vec<_> = SHORTHANDS
+        .iter()
+        .filter(|(_, longhands)| longhands.contains(&longhand))
+        .collect();
+    shorthands.sort_by_key(|(_, longhands)| std::cmp::Reverse(longhands.len()));
+    shorthands.into_iter().map(|(name, _)| *name).collect()
+}
+
+/// Returns true if a property, either longhand or shorthand, is supported.
+pub(crate) fn is_supported(name: &str) -> bool {
+    property(name).is_some() || longhands(name).is_some()
+}
+
+/// Returns true if a longhand can be animated, at least discretely. Everything can, except the properties that set up animations and transitions.
+///
+/// [Reference](https://drafts.csswg.org/web-animations-1/#animating-properties)
+pub(crate) fn is_animatable(longhand: &str) -> bool {
+    property(longhand).is_some()
+        && !longhand.starts_with("animation-")
+        && !longhand.starts_with("transition-")
+}
+
+fn initial(longhand: &str) -> &'static str {
+    property(longhand).map_or("", |property| property.initial)
+}
+
+/// Expands the value of a shorthand into values for each of its longhands, or returns `None` if the value is invalid.
+///
+/// [Reference](https://drafts.csswg.org/css-cascade/#shorthand-property)
+pub(crate) fn expand(shorthand: &str, value: &str) -> Option<Vec<(&'static str, String)>> {
+    let longhands = longhands(shorthand)?;
+    if is_css_wide_keyword(value) {
+        let value = value.to_ascii_lowercase();
+        return Some(
+            longhands
+                .iter()
+                .map(|name| (*name, value.clone()))
+                .collect(),
+        );
+    }
+    let components = split_components(value);
+    let values = match shorthand {
+        "margin" | "padding" | "inset" | "border-width" | "border-style" | "border-color"
+        | "border-radius" | "scroll-margin" | "scroll-padding" => {
+            let valid = |component: &&str| match shorthand {
+                "border-width" => is_line_width(component),
+                "border-style" => is_line_style(component),
+                "border-color" => is_color(component),
+                "border-radius" => is_length_percentage(component),
+                "margin" | "inset" | "scroll-padding" => {
+                    is_length_percentage(component) || component.eq_ignore_ascii_case("auto")
+                }
+                _ => is_length_percentage(component),
+            };
+            if !components.iter().all(valid) {
+                return None;
+            }
+            expand_box(&components)?
+        }
+        "border" | "border-top" | "border-right" | "border-bottom" | "border-left" | "outline" => {
+            let [width, style, color] = expand_line(&components)?;
+            if shorthand == "border" {
+                [width, style, color]
+                    .into_iter()
+                    .flat_map(|value| vec![value; 4])
+                    .collect()
+            } else {
+                vec![width, style, color]
+            }
+        }
+        "overflow" | "gap" => {
+            let valid = |component: &&str| match shorthand {
+                "gap" => {
+                    is_length_percentage(component) || component.eq_ignore_ascii_case("normal")
+                }
+                _ => is_keyword(component, &["visible", "hidden", "clip", "scroll", "auto"]),
+            };
+            match components.as_slice() {
+                [both] if valid(both) => vec![both.to_string(), both.to_string()],
+                [first, second] if valid(first) && valid(second) => {
+                    vec![first.to_string(), second.to_string()]
+                }
+                _ => return None,
+            }
+        }
+        "flex" => expand_flex(&components)?,
+        "flex-flow" => {
+            let mut direction = None;
+            let mut wrap = None;
+            for component in components {
+                let slot = if is_keyword(
+                    component,
+                    &["row", "row-reverse", "column", "column-reverse"],
+                ) {
+                    &mut direction
+                } else if is_keyword(component, &["nowrap", "wrap", "wrap-reverse"]) {
+                    &mut wrap
+                } else {
+                    return None;
+                };
+                if slot.replace(component.to_string()).is_some() {
+                    return None;
+                }
+            }
+            vec![
+                direction.unwrap_or_else(|| initial("flex-direction").to_owned()),
+                wrap.unwrap_or_else(|| initial("flex-wrap").to_owned()),
+            ]
+        }
+        "grid-row" | "grid-column" => {
+            let parts: Vec<_> = value.split('/').map(str::trim).collect();
+            match parts.as_slice() {
+                [start] if !start.is_empty() => {
+                    // A custom identifier is used for both lines, anything else leaves the end line automatic.
+                    vec![start.to_string(), omitted_grid_line(start).to_string()]
+                }
+                [start, end] if !start.is_empty() && !end.is_empty() => {
+                    vec![start.to_string(), end.to_string()]
+                }
+                _ => return None,
+            }
+        }
+        "grid-area" => {
+            let parts: Vec<_> = value.split('/').map(str::trim).collect();
+            if parts.len() > 4 || parts.iter().any(|part| part.is_empty()) {
+                return None;
+            }
+            // An omitted line copies the line it faces if that is a custom identifier, and is automatic otherwise.
+            let row_start = parts[0];
+            let column_start = parts
+                .get(1)
+                .copied()
+                .unwrap_or(omitted_grid_line(row_start));
+            let row_end = parts
+                .get(2)
+                .copied()
+                .unwrap_or(omitted_grid_line(row_start));
+            let column_end = parts
+                .get(3)
+                .copied()
+                .unwrap_or(omitted_grid_line(column_start));
+            [row_start, column_start, row_end, column_end]
+                .map(str::to_owned)
+                .to_vec()
+        }
+        "background" => expand_background(value)?,
+        "list-style" => {
+            let mut values: [Option<String>; 3] = Default::default();
+            let mut nones = 0;
+            for component in components {
+                let index = if component.eq_ignore_ascii_case("none") {
+                    nones += 1;
+                    continue;
+                } else if is_keyword(component, &["inside", "outside"]) {
+                    0
+                } else if is_image(component) {
+                    1
+                } else {
+                    2
+                };
+                if values[index].replace(component.to_string()).is_some() {
+                    return None;
+                }
+            }
+            // `none` sets whichever of the image and the type are not otherwise given.
+            let missing = values[1..].iter().filter(|value| value.is_none()).count();
+            if nones > 0 {
+                if nones > missing {
+                    return None;
+                }
+                for value in &mut values[1..] {
+                    value.get_or_insert_with(|| String::from("none"));
+                }
+            }
+            fill_initial(longhands, values)
+        }
+        "text-decoration" => {
+            let mut values: [Option<String>; 3] = Default::default();
+            let mut lines = vec![];
+            for component in components {
+                if is_keyword(
+                    component,
+                    &["none", "underline", "overline", "line-through", "blink"],
+                ) {
+                    lines.push(component);
+                    continue;
+                }
+                let index =
+                    if is_keyword(component, &["solid", "double", "dotted", "dashed", "wavy"]) {
+                        1
+                    } else if is_color(component) {
+                        2
+                    } else {
+                        return None;
+                    };
+                if values[index].replace(component.to_string()).is_some() {
+                    return None;
+                }
+            }
+            if !lines.is_empty() {
+                values[0] = Some(lines.join(" "));
+            }
+            fill_initial(longhands, values)
+        }
+        "font" => expand_font(value)?,
+        "transition" | "animation" => expand_layers(shorthand, value)?,
+        _ => return None,
+    };
+    // Every longhand value has to be valid on its own too.
+    longhands
+        .iter()
+        .zip(values)
+        .map(|(longhand, value)| Some((*longhand, parse_longhand(longhand, &value)?)))
+        .collect()
+}
+
+/// Expands one to four values into the values for the top, right, bottom and left sides.
+fn expand_box(components: &[&str]) -> Option<Vec<String>> {
+    let [top, right, bottom, left] = match *components {
+        [all] => [all; 4],
+        [vertical, horizontal] => [vertical, horizontal, vertical, horizontal],
+        [top, horizontal, bottom] => [top, horizontal, bottom, horizontal],
+        [top, right, bottom, left] => [top, right, bottom, left],
+        _ => return None,
+    };
+    Some(vec![
+        top.to_string(),
+        right.to_string(),
+        bottom.to_string(),
+        left.to_string(),
+    ])
+}
+
+/// Expands a line in any order, like `1px solid red`, into its width, style and color.
+fn expand_line(components: &[&str]) -> Option<[String; 3]> {
+    let mut values: [Option<String>; 3] = Default::default();
+    if components.is_empty() {
+        return None;
+    }
+    for component in components {
+        let index = if is_line_width(component) {
+            0
+        } else if is_line_style(component) {
+            1
+        } else if is_color(component) {
+            2
+        } else {
+            return None;
+        };
+        if values[index].replace(component.to_string()).is_some() {
+            return None;
+        }
+    }
+    let [width, style, color] = values;
+    Some([
+        width.unwrap_or_else(|| String::from("medium")),
+        style.unwrap_or_else(|| String::from("none")),
+        color.unwrap_or_else(|| String::from("currentcolor")),
+    ])
+}
+
+/// Expands the `flex` shorthand.
+///
+/// [Reference](https://drafts.csswg.org/css-flexbox/#flex-property)
+/// Returns the value of a grid line left out of a shorthand, given the line it faces: the same custom identifier, or `auto`.
+fn omitted_grid_line(facing: &str) -> &str {
+    let is_identifier = !is_number(facing) && !facing.contains(' ') && facing != "auto";
+    if is_identifier {
+        facing
+    } else {
+        "auto"
+    }
+}
+
+fn expand_flex(components: &[&str]) -> Option<Vec<String>> {
+    let values = match *components {
+        [keyword] if keyword.eq_ignore_ascii_case("none") => ["0", "0", "auto"],
+        [keyword] if keyword.eq_ignore_ascii_case("auto") => ["1", "1", "auto"],
+        [grow] if is_number(grow) => [grow, "1", "0%"],
+        [basis] => ["1", "1", is_flex_basis(basis).then_some(basis)?],
+        [grow, shrink] if is_number(grow) && is_number(shrink) => [grow, shrink, "0%"],
+        [grow, basis] if is_number(grow) && is_flex_basis(basis) => [grow, "1", basis],
+        [basis, grow] if is_flex_basis(basis) && is_number(grow) => [grow, "1", basis],
+        [grow, shrink, basis] if is_number(grow) && is_number(shrink) && is_flex_basis(basis) => {
+            [grow, shrink, basis]
+        }
+        [basis, grow, shrink] if is_flex_basis(basis) && is_number(grow) && is_number(shrink) => {
+            [grow, shrink, basis]
+        }
+        _ => return None,
+    };
+    Some(values.iter().map(|value| value.to_string()).collect())
+}
+
+fn is_flex_basis(value: &str) -> bool {
+    is_length_percentage(value)
+        || is_keyword(
+            value,
+            &[
+                "auto",
+                "content",
+                "min-content",
+                "max-content",
+                "fit-content",
+            ],
+        )
+}
+
+/// Expands the `background` shorthand for a single layer.
+///
+/// [Reference](https://drafts.csswg.org/css-backgrounds/#background)
+fn expand_background(value: &str) -> Option<Vec<String>> {
+    let (value, size) = match value.split_once('/') {
+        Some((value, size)) => {
+            // The size directly follows the position, so split off the tokens after it.
+            let mut size_components = split_components(size);
+            let size_length = size_components
+                .iter()
+                .take_while(|component| {
+                    is_length_percentage(component)
+                        || is_keyword(component, &["auto", "cover", "contain"])
+                })
+                .count()
+                .min(2);
+            if size_length == 0 {
+                return None;
+            }
+            let rest = size_components.split_off(size_length);
+            (
+                format!("{value} {}", rest.join(" ")),
+                Some(size_components.join(" ")),
+            )
+        }
+        None => (value.to_owned(), None),
+    };
+    let mut color = None;
+    let mut image = None;
+    let mut repeat = vec![];
+    let mut attachment = None;
+    let mut position = vec![];
+    for component in split_components(&value) {
+        if is_image(component) && image.is_none() {
+            image = Some(component.to_string());
+        } else if is_keyword(
+            component,
+            &[
+                "repeat",
+                "repeat-x",
+                "repeat-y",
+                "no-repeat",
+                "space",
+                "round",
+            ],
+        ) {
+            repeat.push(component);
+        } else if is_keyword(component, &["scroll", "fixed", "local"]) {
+            if attachment.replace(component.to_string()).is_some() {
+                return None;
+            }
+        } else if is_length_percentage(component)
+            || is_keyword(component, &["left", "right", "top", "bottom", "center"])
+        {
+            position.push(component);
+        } else if is_color(component) && color.is_none() {
+            color = Some(component.to_string());
+        } else {
+            return None;
+        }
+    }
+    if repeat.len() > 2 || position.len() > 4 || (size.is_some() && position.is_empty()) {
+        return None;
+    }
+    let join = |parts: Vec<&str>, longhand| {
+        if parts.is_empty() {
+            initial(longhand).to_owned()
+        } else {
+            parts.join(" ")
+        }
+    };
+    Some(vec![
+        color.unwrap_or_else(|| initial("background-color").to_owned()),
+        image.unwrap_or_else(|| initial("background-image").to_owned()),
+        join(repeat, "background-repeat"),
+        attachment.unwrap_or_else(|| initial("background-attachment").to_owned()),
+        join(position, "background-position"),
+        size.unwrap_or_else(|| initial("background-size").to_owned()),
+    ])
+}
+
+/// Expands the `font` shorthand.
+///
+/// [Reference](https://drafts.csswg.org/css-fonts/#font-prop)
+fn expand_font(value: &str) -> Option<Vec<String>> {
+    let components = split_components(value);
+    let mut style = None;
+    let mut variant = None;
+    let mut weight = None;
+    let mut index = 0;
+    // The style, variant and weight come in any order before the size.
+    while let Some(component) = components.get(index) {
+        let slot = if is_keyword(component, &["italic", "oblique"]) {
+            &mut style
+        } else if component.eq_ignore_ascii_case("small-caps") {
+            &mut variant
+        } else if is_keyword(component, &["bold", "bolder", "lighter"])
+            || (is_number(component)
+                && component
+                    .parse::<f64>()
+                    .is_ok_and(|n| (1.0..=1000.0).contains(&n)))
+        {
+            &mut weight
+        } else if component.eq_ignore_ascii_case("normal") {
+            index += 1;
+            continue;
+        } else {
+            break;
+        };
+        if slot.replace(component.to_string()).is_some() {
+            return None;
+        }
+        index += 1;
+    }
+    // Spaces around the slash between the size and the line height are optional.
+    let rest = components[index..]
+        .join(" ")
+        .replacen(" /", "/", 1)
+        .replacen("/ ", "/", 1);
+    let rest = split_components(&rest);
+    let (size, line_height) = match rest.first()?.split_once('/') {
+        Some((size, line_height)) => (size, Some(line_height)),
+        None => (rest[0], None),
+    };
+    let is_size = is_length_percentage(size)
+        || is_keyword(
+            size,
+            &[
+                "xx-small",
+                "x-small",
+                "small",
+                "medium",
+                "large",
+                "x-large",
+                "xx-large",
+                "xxx-large",
+                "smaller",
+                "larger",
+            ],
+        );
+    let family = rest[1..].join(" ");
+    if !is_size || line_height == Some("") || family.is_empty() {
+        return None;
+    }
+    Some(vec![
+        style.unwrap_or_else(|| String::from("normal")),
+        variant.unwrap_or_else(|| String::from("normal")),
+        weight.unwrap_or_else(|| String::from("normal")),
+        size.to_owned(),
+        line_height.unwrap_or("normal").to_owned(),
+        family,
+    ])
+}
+
+/// Expands the comma-separated layers of the `transition` and `animation` shorthands, into a list for each longhand with a value for every layer.
+fn expand_layers(shorthand: &str, value: &str) -> Option<Vec<String>> {
+    let layers = split_commas(value);
+    let mut lists = vec![vec![]; longhands(shorthand)?.len()];
+    for layer in &layers {
+        let values = match shorthand {
+            "transition" => expand_transition(layer, layers.len())?,
+            _ => expand_animation(layer)?,
+        };
+        for (list, value) in lists.iter_mut().zip(values) {
+            list.push(value);
+        }
+    }
+    Some(lists.into_iter().map(|list| list.join(", ")).collect())
+}
+
+/// Expands a layer of the `transition` shorthand, like `opacity 1s ease-in`. The first time is the duration, and the second one the delay.
+///
+/// [Reference](https://drafts.csswg.org/css-transitions/#transition-shorthand-property)
+fn expand_transition(layer: &str, layer_count: usize) -> Option<Vec<String>> {
+    let mut property = None;
+    let mut times = vec![];
+    let mut easing = None;
+    let components = split_components(layer);
+    if components.is_empty() {
+        return None;
+    }
+    for component in components {
+        if is_time(component) && times.len() < 2 {
+            times.push(component.to_owned());
+        } else if is_easing_function(component) && easing.is_none() {
+            easing = Some(component.to_owned());
+        } else if is_identifier(component) && property.is_none() {
+            property = Some(component.to_owned());
+        } else {
+            return None;
+        }
+    }
+    // `none` can only be the only layer.
+    if layer_count > 1 && property.as_deref() == Some("none") {
+        return None;
+    }
+    let mut times = times.into_iter();
+    Some(vec![
+        property.unwrap_or_else(|| initial("transition-property").to_owned()),
+        times
+            .next()
+            .unwrap_or_else(|| initial("transition-duration").to_owned()),
+        easing.unwrap_or_else(|| initial("transition-timing-function").to_owned()),
+        times
+            .next()
+            .unwrap_or_else(|| initial("transition-delay").to_owned()),
+    ])
+}
+
+/// Expands a layer of the `animation` shorthand, like `slide 1s infinite alternate`. Keywords go to the properties other than the name while those are not set yet.
+///
+/// [Reference](https://drafts.csswg.org/css-animations/#animation)
+fn expand_animation(layer: &str) -> Option<Vec<String>> {
+    let longhands = longhands("animation")?;
+    let mut values: [Option<String>; 8] = Default::default();
+    let mut times = 0;
+    let components = split_components(layer);
+    if components.is_empty() {
+        return None;
+    }
+    for component in components {
+        let is_free = |values: &[Option<String>; 8], index: usize| values[index].is_none();
+        let index = if is_time(component) && times < 2 {
+            times += 1;
+            if times == 1 {
+                0
+            } else {
+                2
+            }
+        } else if is_easing_function(component) && is_free(&values, 1) {
+            1
+        } else if (component.eq_ignore_ascii_case("infinite")
+            || component.parse::<f64>().is_ok_and(|count| count >= 0.0))
+            && is_free(&values, 3)
+        {
+            3
+        } else if is_keyword(
+            component,
+            &["normal", "reverse", "alternate", "alternate-reverse"],
+        ) && is_free(&values, 4)
+        {
+            4
+        } else if is_keyword(component, &["none", "forwards", "backwards", "both"])
+            && is_free(&values, 5)
+        {
+            5
+        } else if is_keyword(component, &["running", "paused"]) && is_free(&values, 6) {
+            6
+        } else if is_identifier(component) && is_free(&values, 7) {
+            7
+        } else {
+            return None;
+        };
+        values[index] = Some(component.to_owned());
+    }
+    Some(
+        longhands
+            .iter()
+            .zip(values)
+            .map(|(longhand, value)| value.unwrap_or_else(|| initial(longhand).to_owned()))
+            .collect(),
+    )
+}
+
+/// Fills the values that are missing from a shorthand with the initial values of their longhands.
+fn fill_initial(longhands: &[&str], values: [Option<String>; 3]) -> Vec<String> {
+    longhands
+        .iter()
+        .zip(values)
+        .map(|(longhand, value)| value.unwrap_or_else(|| initial(longhand).to_owned()))
+        .collect()
+}
+
+/// Serializes a shorthand from the values of its longhands, or returns `None` if it cannot represent them.
+///
+/// [Reference](https://drafts.csswg.org/cssom/#serialize-a-css-value)
+pub(crate) fn serialize(shorthand: &str, values: &[&str]) -> Option<String> {
+    let longhands = longhands(shorthand)?;
+    if values.len() != longhands.len() {
+        return None;
+    }
+    // A CSS-wide keyword can only be represented by the shorthand if it is shared by all the longhands.
+    let keywords = values
+        .iter()
+        .filter(|value| is_css_wide_keyword(value))
+        .count();
+    if keywords > 0 {
+        return (keywords == values.len() && values.iter().all(|value| *value == values[0]))
+            .then(|| values[0].to_owned());
+    }
+    // Omits the values that are initial, falling back to the given one if all are.
+    let non_initial = |values: &[&str], longhands: &[&str], fallback: usize| {
+        let parts: Vec<_> = values
+            .iter()
+            .zip(longhands)
+            .filter(|(value, longhand)| **value != initial(longhand))
+            .map(|(value, _)| *value)
+            .collect();
+        if parts.is_empty() {
+            values[fallback].to_owned()
+        } else {
+            parts.join(" ")
+        }
+    };
+    let value = match shorthand {
+        "margin" | "padding" | "inset" | "border-width" | "border-style" | "border-color"
+        | "border-radius" | "scroll-margin" | "scroll-padding" => match *values {
+            [top, right, bottom, left] if right == left => {
+                if top == bottom {
+                    if top == right {
+                        top.to_owned()
+                    } else {
+                        format!("{top} {right}")
+                    }
+                } else {
+                    format!("{top} {right} {bottom}")
+                }
+            }
+            _ => values.join(" "),
+        },
+        "border" => {
+            let sides_match = values
+                .chunks(4)
+                .all(|side| side.iter().all(|value| *value == side[0]));
+            if !sides_match {
+                return None;
+            }
+            let top = &["border-top-width", "border-top-style", "border-top-color"];
+            non_initial(&[values[0], values[4], values[8]], top, 1)
+        }
+        "border-top" | "border-right" | "border-bottom" | "border-left" | "outline" => {
+            non_initial(values, longhands, 1)
+        }
+        "overflow" | "gap" => {
+            if values[0] == values[1] {
+                values[0].to_owned()
+            } else {
+                values.join(" ")
+            }
+        }
+        "flex" => values.join(" "),
+        "flex-flow" | "list-style" | "text-decoration" => {
+            let fallback = if shorthand == "list-style" { 2 } else { 0 };
+            non_initial(values, longhands, fallback)
+        }
+        "grid-row" | "grid-column" => {
+            if values[1] == "auto" || values[0] == values[1] {
+                values[0].to_owned()
+            } else {
+                format!("{} / {}", values[0], values[1])
+            }
+        }
+        "grid-area" => {
+            // Trailing lines are left out while they are what omitting them would give.
+            let mut count = 4;
+            while count > 1 {
+                let facing = match count {
+                    4 => values[1],
+                    _ => values[0],
+                };
+                if values[count - 1] != omitted_grid_line(facing) {
+                    break;
+                }
+                count -= 1;
+            }
+            values[..count].join(" / ")
+        }
+        "background" => {
+            let mut parts: Vec<String> = values[..5]
+                .iter()
+                .zip(longhands)
+                .filter(|(value, longhand)| **value != initial(longhand))
+                .map(|(value, _)| value.to_string())
+                .collect();
+            if values[5] != initial("background-size") {
+                parts.push(format!("{} / {}", values[4], values[5]));
+                if values[4] != initial("background-position") {
+                    parts.retain(|part| part != values[4]);
+                }
+            }
+            if parts.is_empty() {
+                String::from("none")
+            } else {
+                parts.join(" ")
+            }
+        }
+        "font" => {
+            let mut parts: Vec<String> = values[..3]
+                .iter()
+                .filter(|value| **value != "normal")
+                .map(|value| value.to_string())
+                .collect();
+            parts.push(match values[4] {
+                "normal" => values[3].to_owned(),
+                line_height => format!("{}/{line_height}", values[3]),
+            });
+            parts.push(values[5].to_owned());
+            parts.join(" ")
+        }
+        "transition" | "animation" => serialize_layers(shorthand, longhands, values)?,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Serializes the `transition` and `animation` shorthands layer by layer, which is only possible when the lists of all the longhands have the same length.
+fn serialize_layers(shorthand: &str, longhands: &[&str], values: &[&str]) -> Option<String> {
+    let lists: Vec<Vec<&str>> = values.iter().map(|value| split_commas(value)).collect();
+    let count = lists[0].len();
+    if lists.iter().any(|list| list.len() != count) {
+        return None;
+    }
+    // A delay can only be given after a duration, and a layer of initial values is written as its property or name.
+    let (duration, delay, fallback) = match shorthand {
+        "transition" => (1, 3, 0),
+        _ => (0, 2, 7),
+    };
+    let layers: Vec<String> = (0..count)
+        .map(|index| {
+            let layer: Vec<&str> = lists.iter().map(|list| list[index]).collect();
+            let has_delay = layer[delay] != initial(longhands[delay]);
+            let parts: Vec<&str> = layer
+                .iter()
+                .zip(longhands)
+                .enumerate()
+                .filter(|(position, (value, longhand))| {
+                    **value != initial(longhand) || (*position == duration && has_delay)
+                })
+                .map(|(_, (value, _))| *value)
+                .collect();
+            match parts.is_empty() {
+                true => layer[fallback].to_owned(),
+                false => parts.join(" "),
+            }
+        })
+        .collect();
+    Some(layers.join(", "))
+}
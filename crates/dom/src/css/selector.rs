@@ -0,0 +1,1382 @@
+//! Parsing and serialization of selectors.
+//!
+//! [Reference](https://drafts.csswg.org/selectors/)
+use std::fmt::{self, Display, Formatter, Write};
+
+use crate::{
+    domitem::DOMItem,
+    html_element::{
+        constraint_validation, directionality, form_associated::is_disabled, html_input_element,
+    },
+    tag::Tag,
+    AsElement, AsNode, Element, Node,
+};
+
+use super::parser::split_commas;
+
+/// A comma-separated list of complex selectors, like `h1, .title > b`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SelectorList(pub Vec<ComplexSelector>);
+
+/// Compound selectors joined by combinators, like `.title > b`, optionally ending in a pseudo-element.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ComplexSelector {
+    /// The compound selectors from left to right.
+    pub compounds: Vec<CompoundSelector>,
+    /// The combinators between the compound selectors, so `combinators[i]` joins `compounds[i]` and `compounds[i + 1]`.
+    pub combinators: Vec<Combinator>,
+    pub pseudo_element: Option<PseudoElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Combinator {
+    /// `a b`
+    Descendant,
+    /// `a > b`
+    Child,
+    /// `a + b`
+    NextSibling,
+    /// `a ~ b`
+    SubsequentSibling,
+}
+
+/// A sequence of simple selectors that all apply to one element, like `p.intro:hover`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CompoundSelector(pub Vec<SimpleSelector>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SimpleSelector {
+    Universal,
+    Type(String),
+    Id(String),
+    Class(String),
+    Attribute {
+        name: String,
+        matcher: Option<AttributeMatcher>,
+    },
+    PseudoClass(PseudoClass),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AttributeMatcher {
+    pub operator: AttributeOperator,
+    pub value: String,
+    pub case_insensitive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum AttributeOperator {
+    /// `[a=b]`
+    Equals,
+    /// `[a~=b]`
+    Includes,
+    /// `[a|=b]`
+    DashMatch,
+    /// `[a^=b]`
+    Prefix,
+    /// `[a$=b]`
+    Suffix,
+    /// `[a*=b]`
+    Substring,
+}
+
+impl AttributeOperator {
+    fn as_str(&self) -> &str {
+        match self {
+            AttributeOperator::Equals => "=",
+            AttributeOperator::Includes => "~=",
+            AttributeOperator::DashMatch => "|=",
+            AttributeOperator::Prefix => "^=",
+            AttributeOperator::Suffix => "$=",
+            AttributeOperator::Substring => "*=",
+        }
+    }
+}
+
+/// The `An+B` argument of the `:nth-*` pseudo-classes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Nth {
+    pub a: i32,
+    pub b: i32,
+}
+
+/// A relative selector in `:has()`, like the `> img` in `a:has(> img)`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RelativeSelector {
+    pub combinator: Combinator,
+    pub selector: ComplexSelector,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PseudoClass {
+    /// A pseudo-class without arguments, like `:hover` or `:first-child`.
+    Named(&'static str),
+    NthChild(Nth, Option<SelectorList>),
+    NthLastChild(Nth, Option<SelectorList>),
+    NthOfType(Nth),
+    NthLastOfType(Nth),
+    Not(SelectorList),
+    Is(SelectorList),
+    Where(SelectorList),
+    Has(Vec<RelativeSelector>),
+    Host(Option<CompoundSelector>),
+    Lang(Vec<String>),
+    Dir(String),
+}
+
+/// The pseudo-classes without arguments that are supported.
+const NAMED_PSEUDO_CLASSES: &[&str] = &[
+    "active",
+    "any-link",
+    "checked",
+    "default",
+    "defined",
+    "disabled",
+    "empty",
+    "enabled",
+    "first-child",
+    "first-of-type",
+    "focus",
+    "focus-visible",
+    "focus-within",
+    "host",
+    "hover",
+    "in-range",
+    "indeterminate",
+    "invalid",
+    "last-child",
+    "last-of-type",
+    "link",
+    "only-child",
+    "only-of-type",
+    "optional",
+    "out-of-range",
+    "placeholder-shown",
+    "read-only",
+    "read-write",
+    "required",
+    "root",
+    "scope",
+    "target",
+    "user-invalid",
+    "user-valid",
+    "valid",
+    "visited",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PseudoElement {
+    /// A pseudo-element without arguments, like `::before`.
+    Named(&'static str),
+    /// `::slotted()`, which selects the elements assigned to a slot.
+    Slotted(CompoundSelector),
+    /// `::part()`, which selects the elements in a shadow tree with a matching `part` attribute.
+    Part(Vec<String>),
+}
+
+const NAMED_PSEUDO_ELEMENTS: &[&str] = &[
+    "after",
+    "backdrop",
+    "before",
+    "file-selector-button",
+    "first-letter",
+    "first-line",
+    "marker",
+    "placeholder",
+    "selection",
+];
+
+impl SelectorList {
+    /// Parses a selector list, returning `None` if any of the selectors is invalid.
+    ///
+    /// [Reference](https://drafts.csswg.org/selectors/#parse-a-selector)
+    pub(crate) fn parse(input: &str) -> Option<Self> {
+        split_commas(input)
+            .into_iter()
+            .map(ComplexSelector::parse)
+            .collect::<Option<Vec<_>>>()
+            .map(SelectorList)
+    }
+
+    /// Parses a selector list, leaving out the selectors that are invalid, as done in `:is()` and `:where()`.
+    fn parse_forgiving(input: &str) -> Self {
+        SelectorList(
+            split_commas(input)
+                .into_iter()
+                .filter_map(ComplexSelector::parse)
+                .collect(),
+        )
+    }
+}
+
+impl ComplexSelector {
+    fn parse(input: &str) -> Option<Self> {
+        let mut parser = Parser::new(input);
+        let selector = parser.parse_complex(None)?;
+        parser.at_end().then_some(selector)
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Parser {
+            chars: input.trim().chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn eat(&mut self, char: char) -> bool {
+        let matches = self.peek() == Some(char);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.chars.len()
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.position;
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+        self.position != start
+    }
+
+    fn parse_combinator(&mut self) -> Option<Combinator> {
+        let combinator = match self.peek()? {
+            '>' => Combinator::Child,
+            '+' => Combinator::NextSibling,
+            '~' => Combinator::SubsequentSibling,
+            _ => return None,
+        };
+        self.position += 1;
+        Some(combinator)
+    }
+
+    /// Parses a complex selector. A relative selector starts with `leading`, the combinator before it.
+    fn parse_complex(&mut self, leading: Option<&mut Combinator>) -> Option<ComplexSelector> {
+        let mut selector = ComplexSelector {
+            compounds: vec![],
+            combinators: vec![],
+            pseudo_element: None,
+        };
+        self.skip_whitespace();
+        if let Some(leading) = leading {
+            if let Some(combinator) = self.parse_combinator() {
+                *leading = combinator;
+                self.skip_whitespace();
+            }
+        }
+        loop {
+            let (compound, pseudo_element) = self.parse_compound()?;
+            selector.compounds.push(compound);
+            if pseudo_element.is_some() {
+                selector.pseudo_element = pseudo_element;
+                self.skip_whitespace();
+                return Some(selector);
+            }
+            let whitespace = self.skip_whitespace();
+            let combinator = match self.parse_combinator() {
+                Some(combinator) => {
+                    self.skip_whitespace();
+                    combinator
+                }
+                None if whitespace && !self.at_end() && self.peek() != Some(')') => {
+                    Combinator::Descendant
+                }
+                None => return Some(selector),
+            };
+            selector.combinators.push(combinator);
+        }
+    }
+
+    /// Parses a compound selector, and the pseudo-element that ends it, if there is one.
+    fn parse_compound(&mut self) -> Option<(CompoundSelector, Option<PseudoElement>)> {
+        let mut compound = CompoundSelector::default();
+        if self.eat('*') {
+            compound.0.push(SimpleSelector::Universal);
+        } else if self.starts_identifier() {
+            compound.0.push(SimpleSelector::Type(
+                self.parse_identifier()?.to_ascii_lowercase(),
+            ));
+        }
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.position += 1;
+                    compound.0.push(SimpleSelector::Id(self.parse_name()?));
+                }
+                Some('.') => {
+                    self.position += 1;
+                    compound
+                        .0
+                        .push(SimpleSelector::Class(self.parse_identifier()?));
+                }
+                Some('[') => {
+                    self.position += 1;
+                    compound.0.push(self.parse_attribute()?);
+                }
+                Some(':') => {
+                    self.position += 1;
+                    let is_element = self.eat(':');
+                    let name = self.parse_identifier()?.to_ascii_lowercase();
+                    let arguments = self.parse_arguments()?;
+                    // The pseudo-elements from CSS 2 can also be written with a single colon.
+                    let legacy = matches!(
+                        name.as_str(),
+                        "before" | "after" | "first-line" | "first-letter"
+                    );
+                    if is_element || (legacy && arguments.is_none()) {
+                        let pseudo_element = parse_pseudo_element(&name, arguments.as_deref())?;
+                        if compound.0.is_empty() {
+                            compound.0.push(SimpleSelector::Universal);
+                        }
+                        return Some((compound, Some(pseudo_element)));
+                    }
+                    let pseudo_class = parse_pseudo_class(&name, arguments.as_deref())?;
+                    compound.0.push(SimpleSelector::PseudoClass(pseudo_class));
+                }
+                _ => break,
+            }
+        }
+        (!compound.0.is_empty()).then_some((compound, None))
+    }
+
+    /// Parses the arguments of a functional pseudo-class, if there are any.
+    fn parse_arguments(&mut self) -> Option<Option<String>> {
+        if !self.eat('(') {
+            return Some(None);
+        }
+        let start = self.position;
+        let mut depth = 1;
+        let mut quote = None;
+        while let Some(char) = self.peek() {
+            self.position += 1;
+            match quote {
+                Some(quote_char) if char == quote_char => quote = None,
+                Some(_) if char == '\\' => self.position += 1,
+                Some(_) => {}
+                None => match char {
+                    '"' | '\'' => quote = Some(char),
+                    '\\' => self.position += 1,
+                    '(' => depth += 1,
+                    ')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            let arguments: String =
+                                self.chars[start..self.position - 1].iter().collect();
+                            return Some(Some(arguments.trim().to_owned()));
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+        None
+    }
+
+    fn parse_attribute(&mut self) -> Option<SimpleSelector> {
+        self.skip_whitespace();
+        let name = self.parse_identifier()?.to_ascii_lowercase();
+        self.skip_whitespace();
+        if self.eat(']') {
+            return Some(SimpleSelector::Attribute {
+                name,
+                matcher: None,
+            });
+        }
+        let operator = match self.peek()? {
+            '=' => AttributeOperator::Equals,
+            '~' => AttributeOperator::Includes,
+            '|' => AttributeOperator::DashMatch,
+            '^' => AttributeOperator::Prefix,
+            '$' => AttributeOperator::Suffix,
+            '*' => AttributeOperator::Substring,
+            _ => return None,
+        };
+        self.position += 1;
+        if operator != AttributeOperator::Equals && !self.eat('=') {
+            return None;
+        }
+        self.skip_whitespace();
+        let value = match self.peek()? {
+            quote @ ('"' | '\'') => self.parse_string(quote)?,
+            _ => self.parse_identifier()?,
+        };
+        self.skip_whitespace();
+        let case_insensitive = match self.peek()? {
+            'i' | 'I' => {
+                self.position += 1;
+                true
+            }
+            's' | 'S' => {
+                self.position += 1;
+                false
+            }
+            _ => false,
+        };
+        self.skip_whitespace();
+        self.eat(']').then_some(SimpleSelector::Attribute {
+            name,
+            matcher: Some(AttributeMatcher {
+                operator,
+                value,
+                case_insensitive,
+            }),
+        })
+    }
+
+    fn parse_string(&mut self, quote: char) -> Option<String> {
+        self.position += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek()? {
+                char if char == quote => {
+                    self.position += 1;
+                    return Some(value);
+                }
+                '\\' => {
+                    self.position += 1;
+                    value.push(self.parse_escape()?);
+                }
+                char => {
+                    self.position += 1;
+                    value.push(char);
+                }
+            }
+        }
+    }
+
+    fn starts_identifier(&self) -> bool {
+        match self.peek() {
+            Some('-') => match self.peek_at(1) {
+                Some(char) => is_name_start(char) || char == '-' || char == '\\',
+                None => false,
+            },
+            Some('\\') => true,
+            Some(char) => is_name_start(char),
+            None => false,
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Option<String> {
+        if !self.starts_identifier() {
+            return None;
+        }
+        self.parse_name()
+    }
+
+    /// Parses a sequence of name characters, like the part of an id selector after `#`.
+    fn parse_name(&mut self) -> Option<String> {
+        let mut name = String::new();
+        while let Some(char) = self.peek() {
+            if char == '\\' {
+                self.position += 1;
+                name.push(self.parse_escape()?);
+            } else if is_name_start(char) || char.is_ascii_digit() || char == '-' {
+                self.position += 1;
+                name.push(char);
+            } else {
+                break;
+            }
+        }
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Parses an escape, after the backslash.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-escaped-code-point)
+    fn parse_escape(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        if !char.is_ascii_hexdigit() {
+            self.position += 1;
+            return Some(char);
+        }
+        let mut hex = String::new();
+        while hex.len() < 6 && self.peek().is_some_and(|char| char.is_ascii_hexdigit()) {
+            hex.push(self.peek()?);
+            self.position += 1;
+        }
+        if self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+        let code_point = u32::from_str_radix(&hex, 16).ok()?;
+        Some(
+            char::from_u32(code_point)
+                .filter(|_| code_point != 0)
+                .unwrap_or('\u{fffd}'),
+        )
+    }
+}
+
+fn is_name_start(char: char) -> bool {
+    char.is_alphabetic() || char == '_' || !char.is_ascii()
+}
+
+fn parse_pseudo_class(name: &str, arguments: Option<&str>) -> Option<PseudoClass> {
+    let Some(arguments) = arguments else {
+        return NAMED_PSEUDO_CLASSES
+            .iter()
+            .find(|named| **named == name)
+            .map(|named| PseudoClass::Named(named));
+    };
+    let pseudo_class = match name {
+        "not" => PseudoClass::Not(SelectorList::parse(arguments)?),
+        "is" | "matches" => PseudoClass::Is(SelectorList::parse_forgiving(arguments)),
+        "where" => PseudoClass::Where(SelectorList::parse_forgiving(arguments)),
+        "has" => PseudoClass::Has(
+            split_commas(arguments)
+                .into_iter()
+                .map(|argument| {
+                    let mut parser = Parser::new(argument);
+                    let mut combinator = Combinator::Descendant;
+                    let selector = parser.parse_complex(Some(&mut combinator))?;
+                    parser.at_end().then_some(RelativeSelector {
+                        combinator,
+                        selector,
+                    })
+                })
+                .collect::<Option<_>>()?,
+        ),
+        "nth-child" | "nth-last-child" => {
+            let (nth, of) = match split_of(arguments) {
+                Some((nth, of)) => (nth, Some(SelectorList::parse(of)?)),
+                None => (arguments, None),
+            };
+            let nth = parse_nth(nth)?;
+            match name {
+                "nth-child" => PseudoClass::NthChild(nth, of),
+                _ => PseudoClass::NthLastChild(nth, of),
+            }
+        }
+        "nth-of-type" => PseudoClass::NthOfType(parse_nth(arguments)?),
+        "nth-last-of-type" => PseudoClass::NthLastOfType(parse_nth(arguments)?),
+        "host" => {
+            let mut parser = Parser::new(arguments);
+            let (compound, None) = parser.parse_compound()? else {
+                return None;
+            };
+            parser.at_end().then_some(())?;
+            PseudoClass::Host(Some(compound))
+        }
+        "lang" => PseudoClass::Lang(
+            split_commas(arguments)
+                .into_iter()
+                .map(|language| language.trim_matches(|c| c == '"' || c == '\'').to_owned())
+                .collect(),
+        ),
+        "dir" => match arguments.to_ascii_lowercase().as_str() {
+            direction @ ("ltr" | "rtl") => PseudoClass::Dir(direction.to_owned()),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(pseudo_class)
+}
+
+impl PseudoElement {
+    /// Parses a pseudo-element on its own, like `::before`. The pseudo-elements from CSS 2 can also be written with one colon, like `:before`.
+    pub(crate) fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let name = match input.strip_prefix("::") {
+            Some(name) => name,
+            None => input.strip_prefix(':').filter(|name| {
+                ["before", "after", "first-line", "first-letter"]
+                    .iter()
+                    .any(|legacy| legacy.eq_ignore_ascii_case(name))
+            })?,
+        };
+        match name.split_once('(') {
+            Some((name, arguments)) => parse_pseudo_element(
+                &name.to_ascii_lowercase(),
+                Some(arguments.strip_suffix(')')?.trim()),
+            ),
+            None => parse_pseudo_element(&name.to_ascii_lowercase(), None),
+        }
+    }
+}
+
+fn parse_pseudo_element(name: &str, arguments: Option<&str>) -> Option<PseudoElement> {
+    match (name, arguments) {
+        ("slotted", Some(arguments)) => {
+            let mut parser = Parser::new(arguments);
+            let (compound, None) = parser.parse_compound()? else {
+                return None;
+            };
+            parser.at_end().then_some(PseudoElement::Slotted(compound))
+        }
+        ("part", Some(arguments)) => {
+            let names: Vec<_> = arguments.split_whitespace().map(str::to_owned).collect();
+            (!names.is_empty()).then_some(PseudoElement::Part(names))
+        }
+        (name, None) => NAMED_PSEUDO_ELEMENTS
+            .iter()
+            .find(|named| **named == name)
+            .map(|named| PseudoElement::Named(named)),
+        _ => None,
+    }
+}
+
+/// Splits `2n+1 of .item` into the `An+B` and the selector list.
+fn split_of(arguments: &str) -> Option<(&str, &str)> {
+    let lowercase = arguments.to_ascii_lowercase();
+    let index = lowercase.find(" of ")?;
+    Some((&arguments[..index], &arguments[index + 4..]))
+}
+
+/// Parses the `An+B` microsyntax.
+///
+/// [Reference](https://drafts.csswg.org/css-syntax/#anb-microsyntax)
+fn parse_nth(input: &str) -> Option<Nth> {
+    let input: String = input
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match input.as_str() {
+        "odd" => return Some(Nth { a: 2, b: 1 }),
+        "even" => return Some(Nth { a: 2, b: 0 }),
+        _ => {}
+    }
+    let Some((a, b)) = input.split_once('n') else {
+        return Some(Nth {
+            a: 0,
+            b: input.parse().ok()?,
+        });
+    };
+    let a = match a {
+        "" | "+" => 1,
+        "-" => -1,
+        a => a.parse().ok()?,
+    };
+    let b = match b {
+        "" => 0,
+        b if b.starts_with('+') || b.starts_with('-') => b.parse().ok()?,
+        _ => return None,
+    };
+    Some(Nth { a, b })
+}
+
+impl Display for SelectorList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, selector) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{selector}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ComplexSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, compound) in self.compounds.iter().enumerate() {
+            if index > 0 {
+                f.write_str(match self.combinators[index - 1] {
+                    Combinator::Descendant => " ",
+                    Combinator::Child => " > ",
+                    Combinator::NextSibling => " + ",
+                    Combinator::SubsequentSibling => " ~ ",
+                })?;
+            }
+            let is_last = index + 1 == self.compounds.len();
+            // The universal selector is implied before a pseudo-element.
+            if is_last && self.pseudo_element.is_some() && compound.0 == [SimpleSelector::Universal]
+            {
+                continue;
+            }
+            write!(f, "{compound}")?;
+        }
+        match &self.pseudo_element {
+            Some(PseudoElement::Named(name)) => write!(f, "::{name}"),
+            Some(PseudoElement::Slotted(compound)) => write!(f, "::slotted({compound})"),
+            Some(PseudoElement::Part(names)) => write!(f, "::part({})", names.join(" ")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for CompoundSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for simple in &self.0 {
+            match simple {
+                SimpleSelector::Universal => f.write_char('*')?,
+                SimpleSelector::Type(name) => write_identifier(f, name)?,
+                SimpleSelector::Id(id) => {
+                    f.write_char('#')?;
+                    write_identifier(f, id)?;
+                }
+                SimpleSelector::Class(class) => {
+                    f.write_char('.')?;
+                    write_identifier(f, class)?;
+                }
+                SimpleSelector::Attribute { name, matcher } => {
+                    f.write_char('[')?;
+                    write_identifier(f, name)?;
+                    if let Some(matcher) = matcher {
+                        f.write_str(matcher.operator.as_str())?;
+                        write_string(f, &matcher.value)?;
+                        if matcher.case_insensitive {
+                            f.write_str(" i")?;
+                        }
+                    }
+                    f.write_char(']')?;
+                }
+                SimpleSelector::PseudoClass(pseudo_class) => write!(f, "{pseudo_class}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for PseudoClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PseudoClass::Named(name) => write!(f, ":{name}"),
+            PseudoClass::NthChild(nth, of) | PseudoClass::NthLastChild(nth, of) => {
+                let name = match self {
+                    PseudoClass::NthChild(..) => "nth-child",
+                    _ => "nth-last-child",
+                };
+                write!(f, ":{name}({nth}")?;
+                if let Some(of) = of {
+                    write!(f, " of {of}")?;
+                }
+                f.write_char(')')
+            }
+            PseudoClass::NthOfType(nth) => write!(f, ":nth-of-type({nth})"),
+            PseudoClass::NthLastOfType(nth) => write!(f, ":nth-last-of-type({nth})"),
+            PseudoClass::Not(list) => write!(f, ":not({list})"),
+            PseudoClass::Is(list) => write!(f, ":is({list})"),
+            PseudoClass::Where(list) => write!(f, ":where({list})"),
+            PseudoClass::Has(selectors) => {
+                f.write_str(":has(")?;
+                for (index, relative) in selectors.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    match relative.combinator {
+                        Combinator::Descendant => {}
+                        Combinator::Child => f.write_str("> ")?,
+                        Combinator::NextSibling => f.write_str("+ ")?,
+                        Combinator::SubsequentSibling => f.write_str("~ ")?,
+                    }
+                    write!(f, "{}", relative.selector)?;
+                }
+                f.write_char(')')
+            }
+            PseudoClass::Host(Some(compound)) => write!(f, ":host({compound})"),
+            PseudoClass::Host(None) => f.write_str(":host"),
+            PseudoClass::Lang(languages) => write!(f, ":lang({})", languages.join(", ")),
+            PseudoClass::Dir(direction) => write!(f, ":dir({direction})"),
+        }
+    }
+}
+
+impl Display for Nth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.a, self.b) {
+            (0, b) => write!(f, "{b}"),
+            (a, b) => {
+                match a {
+                    1 => f.write_char('n')?,
+                    -1 => f.write_str("-n")?,
+                    a => write!(f, "{a}n")?,
+                }
+                match b {
+                    0 => Ok(()),
+                    b if b > 0 => write!(f, "+{b}"),
+                    b => write!(f, "{b}"),
+                }
+            }
+        }
+    }
+}
+
+/// Writes an identifier, escaping the characters that cannot appear in one.
+///
+/// [Reference](https://drafts.csswg.org/cssom/#serialize-an-identifier)
+fn write_identifier(f: &mut Formatter<'_>, identifier: &str) -> fmt::Result {
+    for (index, char) in identifier.chars().enumerate() {
+        let needs_escape = if char.is_ascii_digit() {
+            index == 0
+        } else {
+            !(is_name_start(char) || char == '-')
+        };
+        if needs_escape && char.is_ascii_digit() {
+            write!(f, "\\{:x} ", char as u32)?;
+        } else if needs_escape {
+            write!(f, "\\{char}")?;
+        } else {
+            f.write_char(char)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes a double-quoted string.
+///
+/// [Reference](https://drafts.csswg.org/cssom/#serialize-a-string)
+pub(crate) fn write_string(f: &mut impl Write, string: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for char in string.chars() {
+        match char {
+            '"' | '\\' => write!(f, "\\{char}")?,
+            char if char.is_control() => write!(f, "\\{:x} ", char as u32)?,
+            char => f.write_char(char)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// The specificity of a selector: its number of id selectors, of class-like selectors, and of type-like selectors.
+///
+/// [Reference](https://drafts.csswg.org/selectors/#specificity-rules)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Specificity(pub u32, pub u32, pub u32);
+
+impl std::ops::Add for Specificity {
+    type Output = Specificity;
+
+    fn add(self, other: Specificity) -> Specificity {
+        Specificity(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+    }
+}
+
+impl SelectorList {
+    /// Returns the specificity of the most specific selector in the list.
+    fn max_specificity(&self) -> Specificity {
+        self.0
+            .iter()
+            .map(ComplexSelector::specificity)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl ComplexSelector {
+    pub(crate) fn specificity(&self) -> Specificity {
+        let specificity = self
+            .compounds
+            .iter()
+            .map(CompoundSelector::specificity)
+            .fold(Specificity::default(), std::ops::Add::add);
+        match &self.pseudo_element {
+            Some(PseudoElement::Slotted(compound)) => {
+                specificity + Specificity(0, 0, 1) + compound.specificity()
+            }
+            Some(_) => specificity + Specificity(0, 0, 1),
+            None => specificity,
+        }
+    }
+}
+
+impl CompoundSelector {
+    fn specificity(&self) -> Specificity {
+        self.0
+            .iter()
+            .map(|simple| match simple {
+                SimpleSelector::Universal => Specificity::default(),
+                SimpleSelector::Type(_) => Specificity(0, 0, 1),
+                SimpleSelector::Id(_) => Specificity(1, 0, 0),
+                SimpleSelector::Class(_) | SimpleSelector::Attribute { .. } => Specificity(0, 1, 0),
+                SimpleSelector::PseudoClass(pseudo_class) => match pseudo_class {
+                    PseudoClass::Where(_) => Specificity::default(),
+                    PseudoClass::Not(list) | PseudoClass::Is(list) => list.max_specificity(),
+                    PseudoClass::Has(selectors) => selectors
+                        .iter()
+                        .map(|relative| relative.selector.specificity())
+                        .max()
+                        .unwrap_or_default(),
+                    PseudoClass::NthChild(_, Some(list))
+                    | PseudoClass::NthLastChild(_, Some(list)) => {
+                        Specificity(0, 1, 0) + list.max_specificity()
+                    }
+                    PseudoClass::Host(Some(compound)) => {
+                        Specificity(0, 1, 0) + compound.specificity()
+                    }
+                    _ => Specificity(0, 1, 0),
+                },
+            })
+            .fold(Specificity::default(), std::ops::Add::add)
+    }
+}
+
+/// The state that selectors are matched in.
+#[derive(Default)]
+pub(crate) struct MatchContext {
+    /// The element that `:scope` matches. Without one, `:scope` matches the root element.
+    pub scope: Option<Element>,
+    /// The host of the shadow tree that the selectors come from. Inside the shadow tree, the host can only be matched by `:host`.
+    pub shadow_host: Option<Element>,
+}
+
+impl SelectorList {
+    /// Returns true if any selector in the list matches the element.
+    pub(crate) fn matches(&self, element: &Element, context: &MatchContext) -> bool {
+        self.match_element(element, None, context).is_some()
+    }
+
+    /// Returns the specificity of the most specific selector that matches the element, or one of its pseudo-elements, or `None` if no selector matches.
+    pub(crate) fn match_element(
+        &self,
+        element: &Element,
+        pseudo_element: Option<&PseudoElement>,
+        context: &MatchContext,
+    ) -> Option<Specificity> {
+        self.0
+            .iter()
+            .filter(|selector| selector.pseudo_element.as_ref() == pseudo_element)
+            .filter(|selector| {
+                match_complex(
+                    selector,
+                    selector.compounds.len() - 1,
+                    element,
+                    context,
+                    None,
+                )
+            })
+            .map(ComplexSelector::specificity)
+            .max()
+    }
+
+    /// Returns the specificity of the most specific `::slotted()` selector that matches an element assigned to a slot.
+    pub(crate) fn match_slotted(
+        &self,
+        slot: &Element,
+        slotted: &Element,
+        context: &MatchContext,
+    ) -> Option<Specificity> {
+        self.0
+            .iter()
+            .filter(|selector| match &selector.pseudo_element {
+                Some(PseudoElement::Slotted(compound)) => {
+                    match_compound(compound, slotted, &MatchContext::default())
+                }
+                _ => false,
+            })
+            .filter(|selector| {
+                match_complex(selector, selector.compounds.len() - 1, slot, context, None)
+            })
+            .map(ComplexSelector::specificity)
+            .max()
+    }
+}
+
+/// Matches the compound selector at `index`, and the ones to the left of it, against an element. A relative selector from `:has()` also needs the leftmost compound to be related to its anchor.
+fn match_complex(
+    selector: &ComplexSelector,
+    index: usize,
+    element: &Element,
+    context: &MatchContext,
+    anchor: Option<(&Element, Combinator)>,
+) -> bool {
+    if !match_compound(&selector.compounds[index], element, context) {
+        return false;
+    }
+    let (combinator, next) = match index.checked_sub(1) {
+        Some(next) => (selector.combinators[next], Some(next)),
+        None => match anchor {
+            Some((_, combinator)) => (combinator, None),
+            None => return true,
+        },
+    };
+    let matches_next = |candidate: &Element| match next {
+        Some(next) => match_complex(selector, next, candidate, context, anchor),
+        None => anchor.is_some_and(|(anchor, _)| anchor == candidate),
+    };
+    match combinator {
+        Combinator::Child => {
+            parent_of(element, context).is_some_and(|parent| matches_next(&parent))
+        }
+        Combinator::Descendant => {
+            let mut ancestor = parent_of(element, context);
+            while let Some(candidate) = ancestor {
+                if matches_next(&candidate) {
+                    return true;
+                }
+                ancestor = parent_of(&candidate, context);
+            }
+            false
+        }
+        Combinator::NextSibling => previous_siblings(element).last().is_some_and(&matches_next),
+        Combinator::SubsequentSibling => previous_siblings(element).iter().any(matches_next),
+    }
+}
+
+fn match_compound(compound: &CompoundSelector, element: &Element, context: &MatchContext) -> bool {
+    // The shadow host is featureless inside its shadow tree.
+    if context
+        .shadow_host
+        .as_ref()
+        .is_some_and(|host| host == element)
+    {
+        return compound.0.iter().all(|simple| {
+            matches!(
+                simple,
+                SimpleSelector::Universal
+                    | SimpleSelector::PseudoClass(
+                        PseudoClass::Named("host") | PseudoClass::Host(_)
+                    )
+            ) && match_simple(simple, element, context)
+        }) && compound
+            .0
+            .iter()
+            .any(|simple| matches!(simple, SimpleSelector::PseudoClass(_)));
+    }
+    compound
+        .0
+        .iter()
+        .all(|simple| match_simple(simple, element, context))
+}
+
+fn match_simple(simple: &SimpleSelector, element: &Element, context: &MatchContext) -> bool {
+    match simple {
+        SimpleSelector::Universal => true,
+        SimpleSelector::Type(name) => element.base().tag.to_string().eq_ignore_ascii_case(name),
+        SimpleSelector::Id(id) => element.get_attribute("id") == Some(id.as_str()),
+        SimpleSelector::Class(class) => element
+            .get_attribute("class")
+            .is_some_and(|classes| classes.split_ascii_whitespace().any(|c| c == class)),
+        SimpleSelector::Attribute { name, matcher } => {
+            let Some(value) = element.get_attribute(name) else {
+                return false;
+            };
+            let Some(matcher) = matcher else {
+                return true;
+            };
+            let (value, expected) = if matcher.case_insensitive {
+                (
+                    value.to_ascii_lowercase(),
+                    matcher.value.to_ascii_lowercase(),
+                )
+            } else {
+                (value.to_owned(), matcher.value.clone())
+            };
+            match matcher.operator {
+                AttributeOperator::Equals => value == expected,
+                AttributeOperator::Includes => {
+                    !expected.is_empty() && value.split_ascii_whitespace().any(|v| v == expected)
+                }
+                AttributeOperator::DashMatch => {
+                    value == expected || value.starts_with(&format!("{expected}-"))
+                }
+                AttributeOperator::Prefix => !expected.is_empty() && value.starts_with(&expected),
+                AttributeOperator::Suffix => !expected.is_empty() && value.ends_with(&expected),
+                AttributeOperator::Substring => !expected.is_empty() && value.contains(&expected),
+            }
+        }
+        SimpleSelector::PseudoClass(pseudo_class) => {
+            match_pseudo_class(pseudo_class, element, context)
+        }
+    }
+}
+
+fn match_pseudo_class(
+    pseudo_class: &PseudoClass,
+    element: &Element,
+    context: &MatchContext,
+) -> bool {
+    let tag = element.base().tag.clone();
+    let is_form_control = matches!(
+        tag,
+        Tag::Button
+            | Tag::Input
+            | Tag::Select
+            | Tag::Textarea
+            | Tag::Optgroup
+            | Tag::Option
+            | Tag::Fieldset
+    );
+    let input_type = || {
+        element
+            .get_attribute("type")
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    let is_checkable =
+        || tag == Tag::Input && matches!(input_type().as_str(), "checkbox" | "radio");
+    let is_text_field = || match tag {
+        Tag::Textarea => true,
+        Tag::Input => !matches!(
+            input_type().as_str(),
+            "checkbox"
+                | "radio"
+                | "button"
+                | "submit"
+                | "reset"
+                | "image"
+                | "file"
+                | "hidden"
+                | "range"
+                | "color"
+        ),
+        _ => false,
+    };
+    match pseudo_class {
+        PseudoClass::Named(name) => match *name {
+            "root" => element
+                .parent_node()
+                .is_some_and(|parent| parent.node_type() == Node::DOCUMENT_NODE),
+            "empty" => element
+                .child_nodes()
+                .iter()
+                .all(|child| match child.node_type() {
+                    Node::ELEMENT_NODE => false,
+                    Node::TEXT_NODE => AsNode::cast(child).base().data.is_empty(),
+                    _ => true,
+                }),
+            "first-child" => previous_siblings(element).is_empty(),
+            "last-child" => next_siblings(element).is_empty(),
+            "only-child" => {
+                previous_siblings(element).is_empty() && next_siblings(element).is_empty()
+            }
+            "first-of-type" => !previous_siblings(element)
+                .iter()
+                .any(|sibling| sibling.base().tag == tag),
+            "last-of-type" => !next_siblings(element)
+                .iter()
+                .any(|sibling| sibling.base().tag == tag),
+            "only-of-type" => {
+                !previous_siblings(element)
+                    .iter()
+                    .any(|sibling| sibling.base().tag == tag)
+                    && !next_siblings(element)
+                        .iter()
+                        .any(|sibling| sibling.base().tag == tag)
+            }
+            "link" | "any-link" => {
+                matches!(tag, Tag::A | Tag::Area) && element.has_attribute("href")
+            }
+            "checked" => match tag {
+                Tag::Option => element.has_attribute("selected"),
+                _ => is_checkable() && constraint_validation::checkedness(element),
+            },
+            "default" => match tag {
+                Tag::Option => element.has_attribute("selected"),
+                _ => is_checkable() && element.has_attribute("checked"),
+            },
+            "indeterminate" => match tag {
+                Tag::Progress => !element.has_attribute("value"),
+                Tag::Input => match input_type().as_str() {
+                    "checkbox" => html_input_element::indeterminate(element),
+                    // A radio button whose group has no checked button.
+                    "radio" => !constraint_validation::radio_button_group(element)
+                        .iter()
+                        .any(constraint_validation::checkedness),
+                    _ => false,
+                },
+                _ => false,
+            },
+            "disabled" => is_form_control && is_disabled(element),
+            "enabled" => is_form_control && !is_disabled(element),
+            "required" => {
+                matches!(tag, Tag::Input | Tag::Select | Tag::Textarea)
+                    && element.has_attribute("required")
+            }
+            "optional" => {
+                matches!(tag, Tag::Input | Tag::Select | Tag::Textarea)
+                    && !element.has_attribute("required")
+            }
+            "read-write" => {
+                (is_text_field() && !element.has_attribute("readonly") && !is_disabled(element))
+                    || element
+                        .get_attribute("contenteditable")
+                        .is_some_and(|value| value.is_empty() || value.eq_ignore_ascii_case("true"))
+            }
+            "read-only" => !match_pseudo_class(&PseudoClass::Named("read-write"), element, context),
+            "placeholder-shown" => {
+                is_text_field()
+                    && element.has_attribute("placeholder")
+                    && constraint_validation::value(element).is_empty()
+            }
+            "valid" => constraint_validation::matches_validity(element, true),
+            "invalid" => constraint_validation::matches_validity(element, false),
+            "user-valid" => constraint_validation::matches_user_validity(element, true),
+            "user-invalid" => constraint_validation::matches_user_validity(element, false),
+            "in-range" => constraint_validation::matches_range(element, true),
+            "out-of-range" => constraint_validation::matches_range(element, false),
+            "defined" => {
+                element.base().custom_element_state
+                    != crate::custom_elements::CustomElementState::Undefined
+            }
+            "scope" => match &context.scope {
+                Some(scope) => scope == element,
+                None => match_pseudo_class(&PseudoClass::Named("root"), element, context),
+            },
+            "host" => context
+                .shadow_host
+                .as_ref()
+                .is_some_and(|host| host == element),
+            // There is no user interaction, so elements are never hovered, focused, visited or targeted.
+            _ => false,
+        },
+        PseudoClass::NthChild(nth, of) | PseudoClass::NthLastChild(nth, of) => {
+            let siblings = match pseudo_class {
+                PseudoClass::NthChild(..) => previous_siblings(element),
+                _ => next_siblings(element),
+            };
+            match of {
+                Some(list) => {
+                    list.matches(element, context)
+                        && nth.matches(
+                            siblings
+                                .iter()
+                                .filter(|sibling| list.matches(sibling, context))
+                                .count()
+                                + 1,
+                        )
+                }
+                None => nth.matches(siblings.len() + 1),
+            }
+        }
+        PseudoClass::NthOfType(nth) => nth.matches(
+            previous_siblings(element)
+                .iter()
+                .filter(|sibling| sibling.base().tag == tag)
+                .count()
+                + 1,
+        ),
+        PseudoClass::NthLastOfType(nth) => nth.matches(
+            next_siblings(element)
+                .iter()
+                .filter(|sibling| sibling.base().tag == tag)
+                .count()
+                + 1,
+        ),
+        PseudoClass::Not(list) => !list.matches(element, context),
+        PseudoClass::Is(list) | PseudoClass::Where(list) => list.matches(element, context),
+        PseudoClass::Has(selectors) => {
+            let candidates: Vec<Element> = descendants(element)
+                .into_iter()
+                .chain(next_siblings(element).into_iter().flat_map(|sibling| {
+                    let mut family = vec![sibling.clone_ref()];
+                    family.extend(descendants(&sibling));
+                    family
+                }))
+                .collect();
+            selectors.iter().any(|relative| {
+                let last = relative.selector.compounds.len() - 1;
+                candidates.iter().any(|candidate| {
+                    match_complex(
+                        &relative.selector,
+                        last,
+                        candidate,
+                        context,
+                        Some((element, relative.combinator)),
+                    )
+                })
+            })
+        }
+        PseudoClass::Host(compound) => {
+            context
+                .shadow_host
+                .as_ref()
+                .is_some_and(|host| host == element)
+                && compound.as_ref().is_none_or(|compound| {
+                    match_compound(compound, element, &MatchContext::default())
+                })
+        }
+        PseudoClass::Lang(languages) => {
+            let mut current = Some(element.clone_ref());
+            while let Some(candidate) = current {
+                if let Some(lang) = candidate.get_attribute("lang") {
+                    let lang = lang.to_ascii_lowercase();
+                    return languages.iter().any(|language| {
+                        let language = language.to_ascii_lowercase();
+                        lang == language || lang.starts_with(&format!("{language}-"))
+                    });
+                }
+                current = candidate.parent_element();
+            }
+            false
+        }
+        PseudoClass::Dir(direction) => directionality(element).as_str() == direction,
+    }
+}
+
+impl Nth {
+    /// Returns true if a one-based index is `An+B` for some non-negative `n`.
+    fn matches(&self, index: usize) -> bool {
+        let difference = index as i32 - self.b;
+        match self.a {
+            0 => difference == 0,
+            a => difference % a == 0 && difference / a >= 0,
+        }
+    }
+}
+
+/// Returns true if a form control is disabled, either itself or through a disabled fieldset.
+/// Returns the parent element, or the shadow host that the selectors are matched for, if the element is at the top of its shadow tree.
+fn parent_of(element: &Element, context: &MatchContext) -> Option<Element> {
+    if let Some(parent) = element.parent_element() {
+        return Some(parent);
+    }
+    let host = crate::slot::host_of_shadow_root(&element.parent_node()?)?;
+    context
+        .shadow_host
+        .as_ref()
+        .is_some_and(|shadow_host| *shadow_host == host)
+        .then_some(host)
+}
+
+/// Returns the element children of a node, in tree order.
+pub(crate) fn element_children(node: &impl AsNode) -> Vec<Element> {
+    let Some(document) = node.owner_document() else {
+        return vec![];
+    };
+    node.child_nodes()
+        .iter()
+        .filter(|child| child.node_type() == Node::ELEMENT_NODE)
+        .filter_map(|child| document.lookup_html_element(AsNode::cast(child).get_base_ptr()))
+        .collect()
+}
+
+fn siblings(element: &Element) -> (Vec<Element>, Vec<Element>) {
+    let Some(parent) = element.parent_node() else {
+        return (vec![], vec![]);
+    };
+    let mut children = element_children(&parent);
+    let index = children
+        .iter()
+        .position(|child| child == element)
+        .unwrap_or_default();
+    let next = children.split_off(index + 1);
+    children.pop();
+    (children, next)
+}
+
+fn previous_siblings(element: &Element) -> Vec<Element> {
+    siblings(element).0
+}
+
+fn next_siblings(element: &Element) -> Vec<Element> {
+    siblings(element).1
+}
+
+fn descendants(element: &Element) -> Vec<Element> {
+    let mut descendants = vec![];
+    for child in element_children(element) {
+        let mut nested = self::descendants(&child);
+        descendants.push(child);
+        descendants.append(&mut nested);
+    }
+    descendants
+}
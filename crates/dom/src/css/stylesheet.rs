@@ -0,0 +1,1289 @@
+//! The CSS Object Model: style sheets, their rules and the lists that hold them.
+//!
+//! [Reference](https://drafts.csswg.org/cssom/)
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
+use crate::{
+    document::WeakDocumentRef,
+    loader::{resolve_url, ResourceLoader},
+    DOMException, Viewport,
+};
+
+use super::{
+    custom_properties::{is_computationally_independent, Syntax},
+    declaration::DeclarationBlock,
+    parser::{
+        is_custom_property, normalize_value, parse_rules, split_commas, split_components, RawRule,
+    },
+    selector::{write_string, SelectorList},
+    supports,
+    values::function_name,
+    CSSStyleDeclaration,
+};
+
+/// How deeply `@import` rules may be nested, so that import cycles terminate.
+const MAX_IMPORT_DEPTH: usize = 16;
+
+/// The state needed to parse a style sheet and the sheets it imports.
+#[derive(Clone)]
+pub(crate) struct ParseContext {
+    /// The URL that the relative URLs in the sheet are resolved against.
+    pub base_url: String,
+    pub loader: Option<Rc<dyn ResourceLoader>>,
+    /// How many `@import` rules deep the sheet is.
+    pub depth: usize,
+    /// The document that the sheet belongs to, whose layout is out of date when the sheet or one of its rules changes.
+    pub document: WeakDocumentRef,
+}
+
+/// A list of rules, shared between a style sheet or grouping rule and the handles to it.
+type RuleList = Rc<RefCell<Vec<CSSRule>>>;
+
+/// A single CSS style sheet.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleSheet)
+#[derive(Clone)]
+pub struct CSSStyleSheet {
+    pub(crate) inner: Rc<RefCell<StyleSheetBase>>,
+}
+
+pub(crate) struct StyleSheetBase {
+    pub(crate) rules: RuleList,
+    pub(crate) href: Option<String>,
+    pub(crate) media: MediaList,
+    pub(crate) disabled: bool,
+    /// The text or URL that the sheet was created from, used to tell when its owner needs a new sheet.
+    pub(crate) source: String,
+    context: ParseContext,
+}
+
+impl Debug for CSSStyleSheet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CSSStyleSheet")
+            .field("href", &self.inner.borrow().href)
+            .finish()
+    }
+}
+
+impl CSSStyleSheet {
+    /// Parses a style sheet from its text.
+    pub(crate) fn parse(
+        text: &str,
+        href: Option<String>,
+        source: String,
+        context: ParseContext,
+    ) -> Self {
+        let rules = parse_rule_list(text, &context, true);
+        CSSStyleSheet {
+            inner: Rc::new(RefCell::new(StyleSheetBase {
+                rules: Rc::new(RefCell::new(rules)),
+                href,
+                media: MediaList::parse("", &context.document),
+                disabled: false,
+                source,
+                context,
+            })),
+        }
+    }
+}
+
+// Properties.
+impl CSSStyleSheet {
+    /// Returns the URL the style sheet was loaded from, or `None` for the sheets of style elements.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/StyleSheet/href)
+    pub fn href(&self) -> Option<String> {
+        self.inner.borrow().href.clone()
+    }
+    /// Returns the media that the style sheet applies to.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/StyleSheet/media)
+    pub fn media(&self) -> MediaList {
+        self.inner.borrow().media.clone()
+    }
+    /// Returns true if the style sheet is disabled, so it does not apply to the document.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/StyleSheet/disabled)
+    pub fn disabled(&self) -> bool {
+        self.inner.borrow().disabled
+    }
+    pub fn set_disabled(&mut self, value: bool) {
+        self.inner.borrow_mut().disabled = value;
+        crate::layout::invalidate_document(&self.inner.borrow().context.document);
+    }
+    /// Returns the rules in the style sheet.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleSheet/cssRules)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, CSSRule, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut head = document.create_element("head");
+    /// head.set_inner_html("<style>p { color: red } @media print { p { color: black } }</style>").unwrap();
+    /// let mut html = document.create_element("html");
+    /// html.append_child(&mut head).unwrap();
+    /// let mut document_ref = document.clone();
+    /// document_ref.append_child(&mut html).unwrap();
+    ///
+    /// let sheet = document.style_sheets().item(0).unwrap();
+    /// let rules = sheet.css_rules();
+    /// assert_eq!(rules.len(), 2);
+    /// assert_eq!(rules.item(0).unwrap().css_text(), "p { color: red; }");
+    /// match rules.item(1).unwrap() {
+    ///     CSSRule::Media(rule) => assert_eq!(rule.media().media_text(), "print"),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn css_rules(&self) -> CSSRuleList {
+        CSSRuleList::from(&self.inner.borrow().rules)
+    }
+}
+
+// Methods.
+impl CSSStyleSheet {
+    /// Inserts a rule into the style sheet at an index, and returns the index.
+    ///
+    /// Returns an `IndexSizeError` if the index is past the end of the rules, a `SyntaxError` if the text is not a single valid rule, and a `HierarchyRequestError` if the rule cannot be placed at the index, such as an `@import` after a style rule.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleSheet/insertRule)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut html = document.create_element("html");
+    /// html.set_inner_html("<head><style>p { color: red }</style></head>").unwrap();
+    /// let mut document_ref = document.clone();
+    /// document_ref.append_child(&mut html).unwrap();
+    ///
+    /// let mut sheet = document.style_sheets().item(0).unwrap();
+    /// assert_eq!(sheet.insert_rule("h1 { margin: 0 }", 0).unwrap(), 0);
+    /// assert_eq!(sheet.css_rules().len(), 2);
+    /// assert!(sheet.insert_rule("h1 {", 5).is_err());
+    /// assert!(sheet.insert_rule("@import url(a.css);", 1).is_err());
+    ///
+    /// sheet.delete_rule(1).unwrap();
+    /// assert_eq!(sheet.css_rules().item(0).unwrap().css_text(), "h1 { margin: 0; }");
+    /// ```
+    pub fn insert_rule(&mut self, rule: &str, index: usize) -> Result<usize, DOMException> {
+        let (rules, context) = {
+            let inner = self.inner.borrow();
+            (inner.rules.clone(), inner.context.clone())
+        };
+        insert_rule(&rules, rule, index, Some(&context), &context.document)
+    }
+    /// Removes the rule at an index from the style sheet.
+    ///
+    /// Returns an `IndexSizeError` if there is no rule at the index.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleSheet/deleteRule)
+    pub fn delete_rule(&mut self, index: usize) -> Result<(), DOMException> {
+        let inner = self.inner.borrow();
+        delete_rule(&inner.rules, index, &inner.context.document)
+    }
+}
+
+/// Parses a list of rules, dropping the ones that are invalid. `@import` rules are only allowed at the top level, before any other rule.
+pub(crate) fn parse_rule_list(
+    input: &str,
+    context: &ParseContext,
+    top_level: bool,
+) -> Vec<CSSRule> {
+    let mut rules = vec![];
+    let mut imports_allowed = top_level;
+    for raw in parse_rules(input) {
+        let Some(rule) = parse_rule(raw, context, imports_allowed) else {
+            continue;
+        };
+        if !matches!(rule, CSSRule::Import(_) | CSSRule::LayerStatement(_)) {
+            imports_allowed = false;
+        }
+        rules.push(rule);
+    }
+    rules
+}
+
+fn parse_rule(raw: RawRule, context: &ParseContext, imports_allowed: bool) -> Option<CSSRule> {
+    let (name, prelude, block) = match raw {
+        RawRule::Qualified { prelude, block } => {
+            let selectors = SelectorList::parse(&prelude)?;
+            return Some(CSSRule::Style(CSSStyleRule {
+                inner: Rc::new(RefCell::new(StyleRuleBase {
+                    selectors,
+                    style: Rc::new(RefCell::new(DeclarationBlock::parse(&block))),
+                    document: context.document.clone(),
+                })),
+            }));
+        }
+        RawRule::At {
+            name,
+            prelude,
+            block,
+        } => (name, prelude, block),
+    };
+    let rule = match (name.as_str(), block) {
+        ("import", None) if imports_allowed => CSSRule::Import(parse_import(&prelude, context)?),
+        ("media", Some(block)) => CSSRule::Media(CSSMediaRule {
+            media: MediaList::parse(&prelude, &context.document),
+            rules: Rc::new(RefCell::new(parse_rule_list(&block, context, false))),
+            document: context.document.clone(),
+        }),
+        ("supports", Some(block)) if !prelude.is_empty() => CSSRule::Supports(CSSSupportsRule {
+            condition: prelude,
+            rules: Rc::new(RefCell::new(parse_rule_list(&block, context, false))),
+            document: context.document.clone(),
+        }),
+        ("layer", Some(block)) => {
+            let names = split_commas(&prelude);
+            if names.len() > 1 || prelude.contains(char::is_whitespace) {
+                return None;
+            }
+            CSSRule::LayerBlock(CSSLayerBlockRule {
+                name: prelude,
+                rules: Rc::new(RefCell::new(parse_rule_list(&block, context, false))),
+                document: context.document.clone(),
+            })
+        }
+        ("layer", None) => {
+            let names: Vec<String> = split_commas(&prelude)
+                .into_iter()
+                .map(str::to_owned)
+                .collect();
+            if names
+                .iter()
+                .any(|name| name.is_empty() || name.contains(char::is_whitespace))
+            {
+                return None;
+            }
+            CSSRule::LayerStatement(CSSLayerStatementRule { names })
+        }
+        ("property", Some(block)) if is_custom_property(&prelude) => {
+            CSSRule::Property(CSSPropertyRule::parse(&prelude, &block)?)
+        }
+        ("font-face", Some(block)) if prelude.is_empty() => CSSRule::FontFace(CSSFontFaceRule {
+            style: Rc::new(RefCell::new(DeclarationBlock::parse_descriptors(&block))),
+            document: context.document.clone(),
+        }),
+        ("keyframes" | "-webkit-keyframes", Some(block)) => {
+            let name = unquote(&prelude).unwrap_or(prelude);
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return None;
+            }
+            let keyframes = parse_rules(&block)
+                .into_iter()
+                .filter_map(|raw| match raw {
+                    RawRule::Qualified { prelude, block } => {
+                        CSSKeyframeRule::parse(&prelude, &block, &context.document)
+                    }
+                    RawRule::At { .. } => None,
+                })
+                .collect();
+            CSSRule::Keyframes(CSSKeyframesRule {
+                inner: Rc::new(RefCell::new(KeyframesRuleBase {
+                    name,
+                    keyframes,
+                    document: context.document.clone(),
+                })),
+            })
+        }
+        _ => return None,
+    };
+    Some(rule)
+}
+
+/// Parses the prelude of an `@import` rule, like `url("theme.css") layer(theme) screen`, and loads the imported sheet.
+fn parse_import(prelude: &str, context: &ParseContext) -> Option<CSSImportRule> {
+    let components = split_components(prelude);
+    let (first, mut rest) = components.split_first()?;
+    let href = match function_name(first) {
+        Some(name) if name.eq_ignore_ascii_case("url") => {
+            let argument = first[4..first.len() - 1].trim();
+            unquote(argument).unwrap_or_else(|| argument.to_owned())
+        }
+        _ => unquote(first)?,
+    };
+    let mut layer = None;
+    if let Some(component) = rest.first() {
+        if component.eq_ignore_ascii_case("layer") {
+            layer = Some(String::new());
+        } else if function_name(component).is_some_and(|name| name.eq_ignore_ascii_case("layer")) {
+            layer = Some(component[6..component.len() - 1].trim().to_owned());
+        }
+        if layer.is_some() {
+            rest = &rest[1..];
+        }
+    }
+    let mut supports_condition = None;
+    if let Some(component) = rest.first() {
+        if function_name(component).is_some_and(|name| name.eq_ignore_ascii_case("supports")) {
+            supports_condition = Some(component[9..component.len() - 1].trim().to_owned());
+            rest = &rest[1..];
+        }
+    }
+    let media = MediaList::parse(&rest.join(" "), &context.document);
+    // The sheet is only fetched if its supports condition is met.
+    let style_sheet = match &context.loader {
+        Some(loader)
+            if context.depth < MAX_IMPORT_DEPTH
+                && supports_condition
+                    .as_deref()
+                    .is_none_or(|condition| supports::evaluate(&format!("({condition})"))) =>
+        {
+            let url = resolve_url(&context.base_url, &href);
+            loader.load(&url).map(|text| {
+                let context = ParseContext {
+                    base_url: url.clone(),
+                    loader: Some(loader.clone()),
+                    depth: context.depth + 1,
+                    document: context.document.clone(),
+                };
+                let sheet = CSSStyleSheet::parse(&text, Some(url.clone()), url, context);
+                sheet.inner.borrow_mut().media = media.clone();
+                sheet
+            })
+        }
+        _ => None,
+    };
+    Some(CSSImportRule {
+        inner: Rc::new(ImportRuleBase {
+            href,
+            media,
+            layer,
+            supports_condition,
+            style_sheet,
+        }),
+    })
+}
+
+/// Returns the value of a quoted string, like `a.css` for `"a.css"`.
+fn unquote(value: &str) -> Option<String> {
+    let quote = value
+        .chars()
+        .next()
+        .filter(|char| matches!(char, '"' | '\''))?;
+    let inner = value.strip_prefix(quote)?.strip_suffix(quote)?;
+    let mut output = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => output.extend(chars.next()),
+            char => output.push(char),
+        }
+    }
+    Some(output)
+}
+
+/// Inserts a rule into a list of a document's style sheet. Only the rule lists of style sheets are given a context, since `@import` rules are not allowed anywhere else.
+///
+/// [Reference](https://drafts.csswg.org/cssom/#insert-a-css-rule)
+fn insert_rule(
+    list: &RuleList,
+    rule: &str,
+    index: usize,
+    context: Option<&ParseContext>,
+    document: &WeakDocumentRef,
+) -> Result<usize, DOMException> {
+    // 1. Set new rule to the results of performing parse a CSS rule on argument rule.
+    // 2. If new rule is a syntax error, throw a SyntaxError exception.
+    let mut raw_rules = parse_rules(rule);
+    if raw_rules.len() != 1 {
+        return Err(DOMException::SyntaxError(format!(
+            "Failed to parse the rule '{rule}'."
+        )));
+    }
+    // 3. Let length be the number of items in list.
+    // 4. If index is greater than length, then throw an IndexSizeError exception.
+    let length = list.borrow().len();
+    if index > length {
+        return Err(DOMException::IndexSizeError(format!(
+            "The index provided ({index}) is larger than the maximum index ({length})."
+        )));
+    }
+    let is_import = matches!(&raw_rules[0], RawRule::At { name, .. } if name == "import");
+    let parent_context = ParseContext {
+        base_url: String::new(),
+        loader: None,
+        depth: 0,
+        document: document.clone(),
+    };
+    let new_rule = parse_rule(
+        raw_rules.remove(0),
+        context.unwrap_or(&parent_context),
+        context.is_some(),
+    );
+    // 5. If new rule cannot be inserted into list at the zero-index position index due to constraints specified by CSS, then throw a HierarchyRequestError exception.
+    let new_rule = match new_rule {
+        Some(new_rule) => new_rule,
+        None if is_import => {
+            return Err(DOMException::HierarchyRequestError(String::from(
+                "@import rules are only allowed at the top of a style sheet.",
+            )))
+        }
+        None => {
+            return Err(DOMException::SyntaxError(format!(
+                "Failed to parse the rule '{rule}'."
+            )))
+        }
+    };
+    let rules = list.borrow();
+    let is_prefix_rule =
+        |rule: &CSSRule| matches!(rule, CSSRule::Import(_) | CSSRule::LayerStatement(_));
+    let misplaced = match new_rule {
+        // An import must come before every rule that is not an import or a layer statement.
+        CSSRule::Import(_) => !rules[..index].iter().all(is_prefix_rule),
+        // Any other rule must come after every import.
+        CSSRule::LayerStatement(_) => false,
+        _ => rules[index..]
+            .iter()
+            .any(|rule| matches!(rule, CSSRule::Import(_))),
+    };
+    drop(rules);
+    if misplaced {
+        return Err(DOMException::HierarchyRequestError(String::from(
+            "The rule cannot be inserted at the specified index.",
+        )));
+    }
+    // 8. Insert new rule into list at the zero-indexed position index.
+    list.borrow_mut().insert(index, new_rule);
+    crate::layout::invalidate_document(document);
+    // 9. Return index.
+    Ok(index)
+}
+
+/// Removes a rule from a list of a document's style sheet.
+///
+/// [Reference](https://drafts.csswg.org/cssom/#remove-a-css-rule)
+fn delete_rule(
+    list: &RuleList,
+    index: usize,
+    document: &WeakDocumentRef,
+) -> Result<(), DOMException> {
+    let length = list.borrow().len();
+    if index >= length {
+        return Err(DOMException::IndexSizeError(format!(
+            "The index provided ({index}) is outside the range [0, {length})."
+        )));
+    }
+    list.borrow_mut().remove(index);
+    crate::layout::invalidate_document(document);
+    Ok(())
+}
+
+/// A rule in a style sheet.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSRule)
+#[derive(Debug, Clone)]
+pub enum CSSRule {
+    Style(CSSStyleRule),
+    Import(CSSImportRule),
+    Media(CSSMediaRule),
+    FontFace(CSSFontFaceRule),
+    Keyframes(CSSKeyframesRule),
+    Keyframe(CSSKeyframeRule),
+    Supports(CSSSupportsRule),
+    LayerBlock(CSSLayerBlockRule),
+    LayerStatement(CSSLayerStatementRule),
+    Property(CSSPropertyRule),
+}
+
+impl CSSRule {
+    /// Returns the textual representation of the rule.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSRule/cssText)
+    pub fn css_text(&self) -> String {
+        match self {
+            CSSRule::Style(rule) => rule.css_text(),
+            CSSRule::Import(rule) => rule.css_text(),
+            CSSRule::Media(rule) => {
+                serialize_group(&format!("@media {}", rule.media.media_text()), &rule.rules)
+            }
+            CSSRule::FontFace(rule) => {
+                serialize_block("@font-face", &rule.style.borrow().serialize())
+            }
+            CSSRule::Keyframes(rule) => rule.css_text(),
+            CSSRule::Keyframe(rule) => rule.css_text(),
+            CSSRule::Supports(rule) => {
+                serialize_group(&format!("@supports {}", rule.condition), &rule.rules)
+            }
+            CSSRule::LayerBlock(rule) => match rule.name.as_str() {
+                "" => serialize_group("@layer", &rule.rules),
+                name => serialize_group(&format!("@layer {name}"), &rule.rules),
+            },
+            CSSRule::LayerStatement(rule) => format!("@layer {};", rule.names.join(", ")),
+            CSSRule::Property(rule) => rule.css_text(),
+        }
+    }
+}
+
+/// Serializes a rule whose block holds declarations, like `p { color: red; }`.
+fn serialize_block(prelude: &str, declarations: &str) -> String {
+    match declarations {
+        "" => format!("{prelude} {{ }}"),
+        declarations => format!("{prelude} {{ {declarations} }}"),
+    }
+}
+
+/// Serializes a rule whose block holds other rules, placing each of them on its own indented lines.
+fn serialize_group(prelude: &str, rules: &RuleList) -> String {
+    let mut text = format!("{prelude} {{\n");
+    for rule in rules.borrow().iter() {
+        for line in rule.css_text().lines() {
+            text.push_str("  ");
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    text.push('}');
+    text
+}
+
+/// An ordered list of rules.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSRuleList)
+pub struct CSSRuleList {
+    items: Vec<CSSRule>,
+}
+
+impl From<&RuleList> for CSSRuleList {
+    fn from(rules: &RuleList) -> Self {
+        CSSRuleList {
+            items: rules.borrow().clone(),
+        }
+    }
+}
+
+impl CSSRuleList {
+    /// Returns the number of rules in the list.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+    /// Returns the rule at an index.
+    pub fn item(&self, index: usize) -> Option<CSSRule> {
+        self.items.get(index).cloned()
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, CSSRule> {
+        self.items.iter()
+    }
+}
+
+impl IntoIterator for CSSRuleList {
+    type Item = CSSRule;
+
+    type IntoIter = std::vec::IntoIter<CSSRule>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// A list of style sheets.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/StyleSheetList)
+pub struct StyleSheetList {
+    pub(crate) items: Vec<CSSStyleSheet>,
+}
+
+impl StyleSheetList {
+    /// Returns the number of style sheets in the list.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+    /// Returns the style sheet at an index.
+    pub fn item(&self, index: usize) -> Option<CSSStyleSheet> {
+        self.items.get(index).cloned()
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, CSSStyleSheet> {
+        self.items.iter()
+    }
+}
+
+impl IntoIterator for StyleSheetList {
+    type Item = CSSStyleSheet;
+
+    type IntoIter = std::vec::IntoIter<CSSStyleSheet>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// A list of media queries, like the `screen, print` in `@media screen, print`.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/MediaList)
+#[derive(Debug, Clone, Default)]
+pub struct MediaList {
+    pub(crate) queries: Rc<RefCell<Vec<String>>>,
+    document: WeakDocumentRef,
+}
+
+impl MediaList {
+    pub(crate) fn parse(text: &str, document: &WeakDocumentRef) -> Self {
+        let list = MediaList {
+            queries: Rc::default(),
+            document: document.clone(),
+        };
+        list.set(text);
+        list
+    }
+
+    pub(crate) fn set(&self, text: &str) {
+        let queries: Vec<String> = split_commas(text)
+            .into_iter()
+            .filter(|query| !query.is_empty())
+            .map(|query| normalize_value(&query.to_ascii_lowercase()))
+            .collect();
+        if *self.queries.borrow() != queries {
+            *self.queries.borrow_mut() = queries;
+            crate::layout::invalidate_document(&self.document);
+        }
+    }
+
+    /// Returns true if the list is empty or any of its queries match a viewport.
+    pub(crate) fn matches(&self, viewport: &Viewport) -> bool {
+        let queries = self.queries.borrow();
+        queries.is_empty()
+            || queries
+                .iter()
+                .any(|query| super::media::evaluate(query, viewport))
+    }
+
+    /// Returns the media queries as a comma-separated string.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/MediaList/mediaText)
+    pub fn media_text(&self) -> String {
+        self.queries.borrow().join(", ")
+    }
+    pub fn set_media_text(&mut self, value: &str) {
+        self.set(value);
+    }
+    /// Returns the number of media queries in the list.
+    pub fn len(&self) -> usize {
+        self.queries.borrow().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.queries.borrow().is_empty()
+    }
+    /// Returns the media query at an index.
+    pub fn item(&self, index: usize) -> Option<String> {
+        self.queries.borrow().get(index).cloned()
+    }
+    /// Adds a media query to the end of the list, if it is not already in it.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/MediaList/appendMedium)
+    pub fn append_medium(&mut self, medium: &str) {
+        let medium = normalize_value(&medium.to_ascii_lowercase());
+        let mut queries = self.queries.borrow_mut();
+        if !medium.is_empty() && !queries.contains(&medium) {
+            queries.push(medium);
+            crate::layout::invalidate_document(&self.document);
+        }
+    }
+    /// Removes a media query from the list.
+    ///
+    /// Returns a `NotFoundError` if the query is not in the list.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/MediaList/deleteMedium)
+    pub fn delete_medium(&mut self, medium: &str) -> Result<(), DOMException> {
+        let medium = normalize_value(&medium.to_ascii_lowercase());
+        let mut queries = self.queries.borrow_mut();
+        let length = queries.len();
+        queries.retain(|query| *query != medium);
+        if queries.len() == length {
+            return Err(DOMException::NotFoundError(format!(
+                "Failed to find the medium '{medium}'."
+            )));
+        }
+        crate::layout::invalidate_document(&self.document);
+        Ok(())
+    }
+}
+
+/// A style rule, like `p { color: red }`.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleRule)
+#[derive(Debug, Clone)]
+pub struct CSSStyleRule {
+    pub(crate) inner: Rc<RefCell<StyleRuleBase>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct StyleRuleBase {
+    pub(crate) selectors: SelectorList,
+    pub(crate) style: Rc<RefCell<DeclarationBlock>>,
+    document: WeakDocumentRef,
+}
+
+impl CSSStyleRule {
+    /// Returns the selector list of the rule.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleRule/selectorText)
+    pub fn selector_text(&self) -> String {
+        self.inner.borrow().selectors.to_string()
+    }
+    /// Replaces the selector list of the rule. Invalid selectors are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, CSSRule, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut html = document.create_element("html");
+    /// html.set_inner_html("<head><style>p{color:red}</style></head>").unwrap();
+    /// let mut document_ref = document.clone();
+    /// document_ref.append_child(&mut html).unwrap();
+    ///
+    /// let sheet = document.style_sheets().item(0).unwrap();
+    /// let CSSRule::Style(mut rule) = sheet.css_rules().item(0).unwrap() else {
+    ///     unreachable!()
+    /// };
+    /// rule.set_selector_text("main >  P.intro");
+    /// rule.set_selector_text("p:unknown");
+    /// rule.style().set_property("color", "blue", None).unwrap();
+    ///
+    /// assert_eq!(rule.selector_text(), "main > p.intro");
+    /// assert_eq!(sheet.css_rules().item(0).unwrap().css_text(), "main > p.intro { color: blue; }");
+    /// ```
+    pub fn set_selector_text(&mut self, value: &str) {
+        if let Some(selectors) = SelectorList::parse(value) {
+            self.inner.borrow_mut().selectors = selectors;
+            crate::layout::invalidate_document(&self.inner.borrow().document);
+        }
+    }
+    /// Returns the declarations of the rule. Changes to the declarations apply to the rule.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSStyleRule/style)
+    pub fn style(&self) -> CSSStyleDeclaration {
+        let inner = self.inner.borrow();
+        CSSStyleDeclaration::for_block(&inner.style, &inner.document)
+    }
+    fn css_text(&self) -> String {
+        let inner = self.inner.borrow();
+        let declarations = inner.style.borrow().serialize();
+        serialize_block(&inner.selectors.to_string(), &declarations)
+    }
+}
+
+/// An `@import` rule, which includes the rules of another style sheet.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSImportRule)
+#[derive(Debug, Clone)]
+pub struct CSSImportRule {
+    pub(crate) inner: Rc<ImportRuleBase>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ImportRuleBase {
+    href: String,
+    pub(crate) media: MediaList,
+    pub(crate) layer: Option<String>,
+    pub(crate) supports_condition: Option<String>,
+    pub(crate) style_sheet: Option<CSSStyleSheet>,
+}
+
+impl CSSImportRule {
+    /// Returns the URL of the imported style sheet, as written in the rule.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSImportRule/href)
+    pub fn href(&self) -> &str {
+        &self.inner.href
+    }
+    /// Returns the media that the imported style sheet applies to.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSImportRule/media)
+    pub fn media(&self) -> MediaList {
+        self.inner.media.clone()
+    }
+    /// Returns the name of the cascade layer the style sheet is imported into, which is empty for an anonymous layer, or `None` if it is not imported into a layer.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSImportRule/layerName)
+    pub fn layer_name(&self) -> Option<&str> {
+        self.inner.layer.as_deref()
+    }
+    /// Returns the supports condition of the rule, if it has one.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSImportRule/supportsText)
+    pub fn supports_text(&self) -> Option<&str> {
+        self.inner.supports_condition.as_deref()
+    }
+    /// Returns the imported style sheet, or `None` if it could not be loaded.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSImportRule/styleSheet)
+    pub fn style_sheet(&self) -> Option<CSSStyleSheet> {
+        self.inner.style_sheet.clone()
+    }
+    fn css_text(&self) -> String {
+        let mut text = String::from("@import url(");
+        write_string(&mut text, &self.inner.href).unwrap();
+        text.push(')');
+        match self.inner.layer.as_deref() {
+            Some("") => text.push_str(" layer"),
+            Some(layer) => text.push_str(&format!(" layer({layer})")),
+            None => {}
+        }
+        if let Some(condition) = &self.inner.supports_condition {
+            text.push_str(&format!(" supports({condition})"));
+        }
+        if !self.inner.media.is_empty() {
+            text.push(' ');
+            text.push_str(&self.inner.media.media_text());
+        }
+        text.push(';');
+        text
+    }
+}
+
+/// Generates the methods shared by the rules that contain other rules.
+macro_rules! grouping_rule_methods {
+    ($rule:ident) => {
+        impl $rule {
+            /// Returns the rules nested in this rule.
+            ///
+            /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSGroupingRule/cssRules)
+            pub fn css_rules(&self) -> CSSRuleList {
+                CSSRuleList::from(&self.rules)
+            }
+            /// Inserts a rule into this rule at an index, and returns the index.
+            ///
+            /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSGroupingRule/insertRule)
+            pub fn insert_rule(&mut self, rule: &str, index: usize) -> Result<usize, DOMException> {
+                insert_rule(&self.rules, rule, index, None, &self.document)
+            }
+            /// Removes the rule at an index from this rule.
+            ///
+            /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSGroupingRule/deleteRule)
+            pub fn delete_rule(&mut self, index: usize) -> Result<(), DOMException> {
+                delete_rule(&self.rules, index, &self.document)
+            }
+        }
+    };
+}
+
+/// An `@media` rule, whose rules apply when its media queries match.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSMediaRule)
+#[derive(Debug, Clone)]
+pub struct CSSMediaRule {
+    pub(crate) media: MediaList,
+    pub(crate) rules: RuleList,
+    document: WeakDocumentRef,
+}
+
+impl CSSMediaRule {
+    /// Returns the media queries of the rule.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSMediaRule/media)
+    pub fn media(&self) -> MediaList {
+        self.media.clone()
+    }
+    /// Returns the condition of the rule.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSConditionRule/conditionText)
+    pub fn condition_text(&self) -> String {
+        self.media.media_text()
+    }
+}
+grouping_rule_methods!(CSSMediaRule);
+
+/// An `@supports` rule, whose rules apply when the declarations in its condition are supported.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSSupportsRule)
+#[derive(Debug, Clone)]
+pub struct CSSSupportsRule {
+    pub(crate) condition: String,
+    pub(crate) rules: RuleList,
+    document: WeakDocumentRef,
+}
+
+impl CSSSupportsRule {
+    /// Returns the condition of the rule.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSConditionRule/conditionText)
+    pub fn condition_text(&self) -> &str {
+        &self.condition
+    }
+    /// Returns true if the condition of the rule is met.
+    pub(crate) fn matches(&self) -> bool {
+        supports::evaluate(&self.condition)
+    }
+}
+grouping_rule_methods!(CSSSupportsRule);
+
+/// An `@layer` rule with a block, which places its rules in a cascade layer.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSLayerBlockRule)
+#[derive(Debug, Clone)]
+pub struct CSSLayerBlockRule {
+    pub(crate) name: String,
+    pub(crate) rules: RuleList,
+    document: WeakDocumentRef,
+}
+
+impl CSSLayerBlockRule {
+    /// Returns the name of the layer, which is empty for an anonymous layer.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSLayerBlockRule/name)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+grouping_rule_methods!(CSSLayerBlockRule);
+
+/// An `@layer` rule without a block, like `@layer reset, base;`, which declares the order of cascade layers.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSLayerStatementRule)
+#[derive(Debug, Clone)]
+pub struct CSSLayerStatementRule {
+    pub(crate) names: Vec<String>,
+}
+
+impl CSSLayerStatementRule {
+    /// Returns the names of the layers, in order.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSLayerStatementRule/nameList)
+    pub fn name_list(&self) -> &[String] {
+        &self.names
+    }
+}
+
+/// An `@property` rule, which registers a custom property with a syntax, an initial value and whether it inherits.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSPropertyRule)
+#[derive(Debug, Clone)]
+pub struct CSSPropertyRule {
+    pub(crate) inner: Rc<PropertyRuleBase>,
+}
+
+#[derive(Debug)]
+pub(crate) struct PropertyRuleBase {
+    pub(crate) name: String,
+    syntax_text: String,
+    pub(crate) syntax: Syntax,
+    pub(crate) inherits: bool,
+    pub(crate) initial_value: Option<String>,
+}
+
+impl CSSPropertyRule {
+    /// Parses the descriptors of an `@property` rule. The rule is invalid without a valid `syntax` and `inherits`, and, unless its syntax is `*`, without an initial value that matches the syntax and is computationally independent.
+    ///
+    /// [Reference](https://drafts.css-houdini.org/css-properties-values-api/#the-css-property-rule-interface)
+    fn parse(name: &str, block: &str) -> Option<Self> {
+        let descriptors = DeclarationBlock::parse_descriptors(block);
+        let syntax_text = unquote(&descriptors.get("syntax")?.value)?;
+        let syntax = Syntax::parse(&syntax_text)?;
+        let inherits = match descriptors
+            .get("inherits")?
+            .value
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "true" => true,
+            "false" => false,
+            _ => return None,
+        };
+        let initial_value = descriptors
+            .get("initial-value")
+            .map(|declaration| normalize_value(&declaration.value));
+        if !syntax.is_universal() {
+            let initial_value = initial_value.as_deref()?;
+            if !syntax.accepts(initial_value) || !is_computationally_independent(initial_value) {
+                return None;
+            }
+        }
+        Some(CSSPropertyRule {
+            inner: Rc::new(PropertyRuleBase {
+                name: name.to_owned(),
+                syntax_text,
+                syntax,
+                inherits,
+                initial_value,
+            }),
+        })
+    }
+    /// Returns the name of the custom property.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSPropertyRule/name)
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+    /// Returns the syntax of the custom property, like `<length> | auto`.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSPropertyRule/syntax)
+    pub fn syntax(&self) -> &str {
+        &self.inner.syntax_text
+    }
+    /// Returns true if the custom property inherits its value by default.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSPropertyRule/inherits)
+    pub fn inherits(&self) -> bool {
+        self.inner.inherits
+    }
+    /// Returns the initial value of the custom property, if it has one.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSPropertyRule/initialValue)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, CSSRule, Document, InnerHtml, Window};
+    ///
+    /// let mut document = Document::new();
+    /// let mut html = document.create_element("html");
+    /// html.set_inner_html(
+    ///     r#"<style>
+    ///         @property --space { syntax: "<length>"; inherits: false; initial-value: 8px; }
+    ///         p { --space: 2em; font-size: 10px; margin-top: var(--space); }
+    ///     </style>
+    ///     <p><span></span></p>"#,
+    /// )
+    /// .unwrap();
+    /// document.append_child(&mut html).unwrap();
+    ///
+    /// match document.style_sheets().item(0).unwrap().css_rules().item(0).unwrap() {
+    ///     CSSRule::Property(rule) => {
+    ///         assert_eq!(rule.name(), "--space");
+    ///         assert_eq!(rule.initial_value(), Some("8px"));
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// let window = Window::new(document);
+    /// let paragraph = html.children().item(1).unwrap();
+    /// let style = window.get_computed_style(&paragraph, None);
+    /// assert_eq!(style.get_property_value("--space"), "20px");
+    /// assert_eq!(style.margin_top(), "20px");
+    ///
+    /// // The property does not inherit, so the span has the initial value.
+    /// let span = paragraph.children().item(0).unwrap();
+    /// let style = window.get_computed_style(&span, None);
+    /// assert_eq!(style.get_property_value("--space"), "8px");
+    /// ```
+    pub fn initial_value(&self) -> Option<&str> {
+        self.inner.initial_value.as_deref()
+    }
+    fn css_text(&self) -> String {
+        let mut syntax = String::new();
+        write_string(&mut syntax, &self.inner.syntax_text).unwrap();
+        let mut declarations = format!("syntax: {syntax}; inherits: {};", self.inner.inherits);
+        if let Some(initial_value) = &self.inner.initial_value {
+            declarations.push_str(&format!(" initial-value: {initial_value};"));
+        }
+        serialize_block(&format!("@property {}", self.inner.name), &declarations)
+    }
+}
+
+/// An `@font-face` rule, which describes a font to download.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSFontFaceRule)
+#[derive(Debug, Clone)]
+pub struct CSSFontFaceRule {
+    pub(crate) style: Rc<RefCell<DeclarationBlock>>,
+    document: WeakDocumentRef,
+}
+
+impl CSSFontFaceRule {
+    /// Returns the descriptors of the font face.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSFontFaceRule/style)
+    pub fn style(&self) -> CSSStyleDeclaration {
+        CSSStyleDeclaration::for_block(&self.style, &self.document)
+    }
+}
+
+/// An `@keyframes` rule, which describes the steps of an animation.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframesRule)
+#[derive(Debug, Clone)]
+pub struct CSSKeyframesRule {
+    pub(crate) inner: Rc<RefCell<KeyframesRuleBase>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct KeyframesRuleBase {
+    pub(crate) name: String,
+    pub(crate) keyframes: Vec<CSSKeyframeRule>,
+    document: WeakDocumentRef,
+}
+
+impl CSSKeyframesRule {
+    /// Returns the name of the animation.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframesRule/name)
+    pub fn name(&self) -> String {
+        self.inner.borrow().name.clone()
+    }
+    pub fn set_name(&mut self, value: &str) {
+        self.inner.borrow_mut().name = value.to_owned();
+        crate::layout::invalidate_document(&self.inner.borrow().document);
+    }
+    /// Returns the keyframes of the animation.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframesRule/cssRules)
+    pub fn css_rules(&self) -> CSSRuleList {
+        CSSRuleList {
+            items: (self.inner.borrow().keyframes.iter())
+                .map(|keyframe| CSSRule::Keyframe(keyframe.clone()))
+                .collect(),
+        }
+    }
+    /// Parses a keyframe, like `50% { opacity: 0.5 }`, and adds it to the end of the animation. Invalid keyframes are ignored.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframesRule/appendRule)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, CSSRule, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut html = document.create_element("html");
+    /// html.set_inner_html("<head><style>@keyframes fade { from { opacity: 0 } }</style></head>").unwrap();
+    /// let mut document_ref = document.clone();
+    /// document_ref.append_child(&mut html).unwrap();
+    ///
+    /// let sheet = document.style_sheets().item(0).unwrap();
+    /// let CSSRule::Keyframes(mut rule) = sheet.css_rules().item(0).unwrap() else {
+    ///     unreachable!()
+    /// };
+    /// rule.append_rule("to { opacity: 1 }");
+    /// assert_eq!(rule.find_rule("100%").unwrap().key_text(), "100%");
+    ///
+    /// rule.delete_rule("0%");
+    /// assert_eq!(
+    ///     sheet.css_rules().item(0).unwrap().css_text(),
+    ///     "@keyframes fade {\n  100% { opacity: 1; }\n}"
+    /// );
+    /// ```
+    pub fn append_rule(&mut self, rule: &str) {
+        let document = self.inner.borrow().document.clone();
+        let keyframe = match parse_rules(rule).as_slice() {
+            [RawRule::Qualified { prelude, block }] => {
+                CSSKeyframeRule::parse(prelude, block, &document)
+            }
+            _ => None,
+        };
+        if let Some(keyframe) = keyframe {
+            self.inner.borrow_mut().keyframes.push(keyframe);
+            crate::layout::invalidate_document(&document);
+        }
+    }
+    /// Removes the last keyframe whose keys match a list of keys.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframesRule/deleteRule)
+    pub fn delete_rule(&mut self, select: &str) {
+        let index = self.find_index(select);
+        if let Some(index) = index {
+            self.inner.borrow_mut().keyframes.remove(index);
+            crate::layout::invalidate_document(&self.inner.borrow().document);
+        }
+    }
+    /// Returns the last keyframe whose keys match a list of keys.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframesRule/findRule)
+    pub fn find_rule(&self, select: &str) -> Option<CSSKeyframeRule> {
+        let index = self.find_index(select)?;
+        Some(self.inner.borrow().keyframes[index].clone())
+    }
+    fn find_index(&self, select: &str) -> Option<usize> {
+        let keys = parse_keyframe_selector(select)?;
+        self.inner
+            .borrow()
+            .keyframes
+            .iter()
+            .rposition(|keyframe| keyframe.inner.borrow().keys == keys)
+    }
+    fn css_text(&self) -> String {
+        let inner = self.inner.borrow();
+        let mut text = format!("@keyframes {} {{\n", inner.name);
+        for keyframe in &inner.keyframes {
+            text.push_str("  ");
+            text.push_str(&keyframe.css_text());
+            text.push('\n');
+        }
+        text.push('}');
+        text
+    }
+}
+
+/// A single keyframe in an `@keyframes` rule, like `50% { opacity: 0.5 }`.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframeRule)
+#[derive(Debug, Clone)]
+pub struct CSSKeyframeRule {
+    pub(crate) inner: Rc<RefCell<KeyframeRuleBase>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct KeyframeRuleBase {
+    /// The offsets of the keyframe, as fractions of the animation.
+    pub(crate) keys: Vec<f64>,
+    pub(crate) style: Rc<RefCell<DeclarationBlock>>,
+    document: WeakDocumentRef,
+}
+
+impl CSSKeyframeRule {
+    fn parse(prelude: &str, block: &str, document: &WeakDocumentRef) -> Option<Self> {
+        let keys = parse_keyframe_selector(prelude)?;
+        let mut style = DeclarationBlock::parse(block);
+        // Important declarations are ignored in keyframes.
+        style
+            .declarations
+            .retain(|declaration| !declaration.important);
+        Some(CSSKeyframeRule {
+            inner: Rc::new(RefCell::new(KeyframeRuleBase {
+                keys,
+                style: Rc::new(RefCell::new(style)),
+                document: document.clone(),
+            })),
+        })
+    }
+    /// Returns the offsets of the keyframe, like `0%, 50%`.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframeRule/keyText)
+    pub fn key_text(&self) -> String {
+        let keys = &self.inner.borrow().keys;
+        let keys: Vec<_> = keys.iter().map(|key| format!("{}%", key * 100.0)).collect();
+        keys.join(", ")
+    }
+    /// Replaces the offsets of the keyframe.
+    ///
+    /// Returns a `SyntaxError` if the offsets are invalid.
+    pub fn set_key_text(&mut self, value: &str) -> Result<(), DOMException> {
+        let keys = parse_keyframe_selector(value).ok_or_else(|| {
+            DOMException::SyntaxError(format!("'{value}' is not a valid keyframe selector."))
+        })?;
+        self.inner.borrow_mut().keys = keys;
+        crate::layout::invalidate_document(&self.inner.borrow().document);
+        Ok(())
+    }
+    /// Returns the declarations of the keyframe.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CSSKeyframeRule/style)
+    pub fn style(&self) -> CSSStyleDeclaration {
+        let inner = self.inner.borrow();
+        CSSStyleDeclaration::for_block(&inner.style, &inner.document)
+    }
+    fn css_text(&self) -> String {
+        let declarations = self.inner.borrow().style.borrow().serialize();
+        serialize_block(&self.key_text(), &declarations)
+    }
+}
+
+/// Parses a keyframe selector, like `from, 50%`, into offsets between 0 and 1.
+fn parse_keyframe_selector(selector: &str) -> Option<Vec<f64>> {
+    split_commas(selector)
+        .into_iter()
+        .map(|key| match key.to_ascii_lowercase().as_str() {
+            "from" => Some(0.0),
+            "to" => Some(1.0),
+            key => {
+                let percentage: f64 = key.strip_suffix('%')?.parse().ok()?;
+                (0.0..=100.0)
+                    .contains(&percentage)
+                    .then_some(percentage / 100.0)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,67 @@
+//! Evaluation of the conditions of `@supports` rules.
+//!
+//! [Reference](https://drafts.csswg.org/css-conditional-3/#at-supports)
+use super::{
+    declaration::DeclarationBlock,
+    parser::{split_components, strip_important},
+    selector::SelectorList,
+    values::function_name,
+};
+
+/// Returns true if a supports condition, like `(display: grid) and (not (gap: 1px))`, is met.
+pub(crate) fn evaluate(condition: &str) -> bool {
+    let parts = split_components(condition);
+    match parts.as_slice() {
+        [not, operand] if not.eq_ignore_ascii_case("not") => !evaluate_in_parens(operand),
+        [first, rest @ ..] if rest.len() % 2 == 0 => {
+            let operators: Vec<_> = rest.iter().step_by(2).collect();
+            let operands = std::iter::once(first).chain(rest.iter().skip(1).step_by(2));
+            if operators
+                .iter()
+                .all(|operator| operator.eq_ignore_ascii_case("and"))
+            {
+                operands
+                    .into_iter()
+                    .all(|operand| evaluate_in_parens(operand))
+            } else if operators
+                .iter()
+                .all(|operator| operator.eq_ignore_ascii_case("or"))
+            {
+                operands
+                    .into_iter()
+                    .any(|operand| evaluate_in_parens(operand))
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+fn evaluate_in_parens(operand: &str) -> bool {
+    if function_name(operand).is_some_and(|name| name.eq_ignore_ascii_case("selector")) {
+        let selector = &operand["selector(".len()..operand.len() - 1];
+        return SelectorList::parse(selector).is_some();
+    }
+    let Some(inner) = operand
+        .strip_prefix('(')
+        .and_then(|operand| operand.strip_suffix(')'))
+    else {
+        return false;
+    };
+    let inner = inner.trim();
+    match inner.split_once(':') {
+        Some((name, value)) if is_property_name(name.trim()) => {
+            let (value, _) = strip_important(value.trim());
+            DeclarationBlock::default().set(&name.trim().to_ascii_lowercase(), value, false)
+        }
+        _ => evaluate(inner),
+    }
+}
+
+fn is_property_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|char| char.is_alphanumeric() || char == '-' || char == '_')
+}
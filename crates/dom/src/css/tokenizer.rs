@@ -0,0 +1,433 @@
+//! The tokenization of CSS text, which the parsing of rules and declarations works on.
+//!
+//! [Reference](https://drafts.csswg.org/css-syntax/#tokenization)
+
+/// The type of a token, with the value of the token for the types that have one.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind {
+    Ident(String),
+    /// A function name followed by `(`, like `rgb(`.
+    Function(String),
+    AtKeyword(String),
+    Hash(String),
+    String(String),
+    /// A string that is cut off by a newline.
+    BadString,
+    /// An unquoted `url()`, like `url(a.png)`.
+    Url(String),
+    /// An unquoted `url()` with characters that are not allowed in it, like `url(a b.png)`.
+    BadUrl,
+    Delim(char),
+    Number,
+    Percentage,
+    Dimension,
+    Whitespace,
+    Comment,
+    /// `<!--`
+    Cdo,
+    /// `-->`
+    Cdc,
+    Colon,
+    Semicolon,
+    Comma,
+    OpenSquare,
+    CloseSquare,
+    OpenParen,
+    CloseParen,
+    OpenCurly,
+    CloseCurly,
+}
+
+/// A token, along with the text it was consumed from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+}
+
+impl Token<'_> {
+    /// Returns true if the token does not mean anything between other tokens, like whitespace and comments.
+    pub(crate) fn is_trivia(&self) -> bool {
+        matches!(self.kind, TokenKind::Whitespace | TokenKind::Comment)
+    }
+
+    /// Returns the token that closes a block or function that this token opens.
+    fn mirror(&self) -> Option<TokenKind> {
+        match self.kind {
+            TokenKind::OpenParen | TokenKind::Function(_) => Some(TokenKind::CloseParen),
+            TokenKind::OpenSquare => Some(TokenKind::CloseSquare),
+            TokenKind::OpenCurly => Some(TokenKind::CloseCurly),
+            _ => None,
+        }
+    }
+}
+
+/// Splits CSS text into tokens.
+pub(crate) fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokenizer = Tokenizer { input, position: 0 };
+    let mut tokens = vec![];
+    while let Some(token) = tokenizer.consume_token() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Returns the index after the component value that starts at `index`: a block or function along with its contents, or a single token. A block that is not closed runs to the end of the tokens.
+///
+/// [Reference](https://drafts.csswg.org/css-syntax/#consume-component-value)
+pub(crate) fn component_value_end(tokens: &[Token], index: usize) -> usize {
+    let Some(mirror) = tokens[index].mirror() else {
+        return index + 1;
+    };
+    let mut index = index + 1;
+    while let Some(token) = tokens.get(index) {
+        if token.kind == mirror {
+            return index + 1;
+        }
+        index = component_value_end(tokens, index);
+    }
+    index
+}
+
+/// Returns the text of some tokens, as it was written.
+pub(crate) fn source(tokens: &[Token]) -> String {
+    tokens.iter().map(|token| token.text).collect()
+}
+
+/// Returns the text of some tokens with comments removed and whitespace collapsed, which is how values and preludes are kept.
+pub(crate) fn serialize(tokens: &[Token]) -> String {
+    let mut output = String::new();
+    for token in tokens {
+        if token.is_trivia() {
+            if !output.is_empty() && !output.ends_with(' ') {
+                output.push(' ');
+            }
+        } else {
+            output.push_str(token.text);
+        }
+    }
+    output.trim_end().to_owned()
+}
+
+struct Tokenizer<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+fn is_newline(char: char) -> bool {
+    matches!(char, '\n' | '\r' | '\x0C')
+}
+
+fn is_ident_start(char: char) -> bool {
+    char.is_ascii_alphabetic() || !char.is_ascii() || char == '_'
+}
+
+fn is_ident_char(char: char) -> bool {
+    is_ident_start(char) || char.is_ascii_digit() || char == '-'
+}
+
+fn is_non_printable(char: char) -> bool {
+    matches!(char, '\0'..='\x08' | '\x0B' | '\x0E'..='\x1F' | '\x7F')
+}
+
+/// [Reference](https://drafts.csswg.org/css-syntax/#starts-with-a-valid-escape)
+fn is_valid_escape(first: Option<char>, second: Option<char>) -> bool {
+    first == Some('\\') && second.is_some_and(|char| !is_newline(char))
+}
+
+/// [Reference](https://drafts.csswg.org/css-syntax/#would-start-an-identifier)
+fn starts_ident_sequence(first: Option<char>, second: Option<char>, third: Option<char>) -> bool {
+    match first {
+        Some('-') => {
+            second.is_some_and(|char| is_ident_start(char) || char == '-')
+                || is_valid_escape(second, third)
+        }
+        Some('\\') => is_valid_escape(first, second),
+        Some(char) => is_ident_start(char),
+        None => false,
+    }
+}
+
+/// [Reference](https://drafts.csswg.org/css-syntax/#starts-with-a-number)
+fn starts_number(first: Option<char>, second: Option<char>, third: Option<char>) -> bool {
+    let is_digit = |char: Option<char>| char.is_some_and(|char| char.is_ascii_digit());
+    match first {
+        Some('+' | '-') => is_digit(second) || (second == Some('.') && is_digit(third)),
+        Some('.') => is_digit(second),
+        first => is_digit(first),
+    }
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Returns the character `offset` characters after the current position.
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.input[self.position..].chars().nth(offset)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek(0)?;
+        self.position += char.len_utf8();
+        Some(char)
+    }
+
+    fn consume_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while self.peek(0).is_some_and(&predicate) {
+            self.next();
+        }
+    }
+
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-token)
+    fn consume_token(&mut self) -> Option<Token<'a>> {
+        let start = self.position;
+        let kind = self.consume_token_kind()?;
+        Some(Token {
+            kind,
+            text: &self.input[start..self.position],
+        })
+    }
+
+    fn consume_token_kind(&mut self) -> Option<TokenKind> {
+        let (second, third) = (self.peek(1), self.peek(2));
+        let char = self.next()?;
+        let kind = match char {
+            '/' if self.peek(0) == Some('*') => {
+                self.next();
+                match self.input[self.position..].find("*/") {
+                    Some(end) => self.position += end + 2,
+                    None => self.position = self.input.len(),
+                }
+                TokenKind::Comment
+            }
+            char if char.is_ascii_whitespace() => {
+                self.consume_while(|char: char| char.is_ascii_whitespace());
+                TokenKind::Whitespace
+            }
+            '"' | '\'' => self.consume_string(char),
+            '#' if self.peek(0).is_some_and(is_ident_char)
+                || is_valid_escape(self.peek(0), self.peek(1)) =>
+            {
+                TokenKind::Hash(self.consume_ident_sequence())
+            }
+            '(' => TokenKind::OpenParen,
+            ')' => TokenKind::CloseParen,
+            '[' => TokenKind::OpenSquare,
+            ']' => TokenKind::CloseSquare,
+            '{' => TokenKind::OpenCurly,
+            '}' => TokenKind::CloseCurly,
+            ',' => TokenKind::Comma,
+            ':' => TokenKind::Colon,
+            ';' => TokenKind::Semicolon,
+            '+' | '-' | '.' if starts_number(Some(char), second, third) => {
+                self.position -= 1;
+                self.consume_numeric()
+            }
+            '-' if second == Some('-') && third == Some('>') => {
+                self.position += 2;
+                TokenKind::Cdc
+            }
+            '-' | '\\' if starts_ident_sequence(Some(char), second, third) => {
+                self.position -= 1;
+                self.consume_ident_like()
+            }
+            '<' if self.input[self.position..].starts_with("!--") => {
+                self.position += 3;
+                TokenKind::Cdo
+            }
+            '@' if starts_ident_sequence(self.peek(0), self.peek(1), self.peek(2)) => {
+                TokenKind::AtKeyword(self.consume_ident_sequence())
+            }
+            char if char.is_ascii_digit() => {
+                self.position -= 1;
+                self.consume_numeric()
+            }
+            char if is_ident_start(char) => {
+                self.position -= char.len_utf8();
+                self.consume_ident_like()
+            }
+            char => TokenKind::Delim(char),
+        };
+        Some(kind)
+    }
+
+    /// Consumes a string up to the quote that ends it. A newline in the string makes it a bad string, and is left for the next token.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-string-token)
+    fn consume_string(&mut self, quote: char) -> TokenKind {
+        let mut value = String::new();
+        loop {
+            match self.peek(0) {
+                None => return TokenKind::String(value),
+                Some(char) if char == quote => {
+                    self.next();
+                    return TokenKind::String(value);
+                }
+                Some(char) if is_newline(char) => return TokenKind::BadString,
+                Some('\\') => {
+                    self.next();
+                    match self.peek(0) {
+                        None => {}
+                        Some(char) if is_newline(char) => {
+                            self.next();
+                        }
+                        Some(_) => value.push(self.consume_escape()),
+                    }
+                }
+                Some(char) => {
+                    self.next();
+                    value.push(char);
+                }
+            }
+        }
+    }
+
+    /// Consumes an escape, after its backslash.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-escaped-code-point)
+    fn consume_escape(&mut self) -> char {
+        let Some(char) = self.next() else {
+            return char::REPLACEMENT_CHARACTER;
+        };
+        if !char.is_ascii_hexdigit() {
+            return char;
+        }
+        let start = self.position - 1;
+        while self.position - start < 6 && self.peek(0).is_some_and(|char| char.is_ascii_hexdigit())
+        {
+            self.next();
+        }
+        let code_point = u32::from_str_radix(&self.input[start..self.position], 16).unwrap();
+        if self
+            .peek(0)
+            .is_some_and(|char: char| char.is_ascii_whitespace())
+        {
+            self.next();
+        }
+        match char::from_u32(code_point) {
+            Some('\0') | None => char::REPLACEMENT_CHARACTER,
+            Some(char) => char,
+        }
+    }
+
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-name)
+    fn consume_ident_sequence(&mut self) -> String {
+        let mut value = String::new();
+        loop {
+            match self.peek(0) {
+                Some(char) if is_ident_char(char) => {
+                    self.next();
+                    value.push(char);
+                }
+                char if is_valid_escape(char, self.peek(1)) => {
+                    self.next();
+                    value.push(self.consume_escape());
+                }
+                _ => return value,
+            }
+        }
+    }
+
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-numeric-token)
+    fn consume_numeric(&mut self) -> TokenKind {
+        if matches!(self.peek(0), Some('+' | '-')) {
+            self.next();
+        }
+        self.consume_while(|char| char.is_ascii_digit());
+        if self.peek(0) == Some('.') && self.peek(1).is_some_and(|char| char.is_ascii_digit()) {
+            self.next();
+            self.consume_while(|char| char.is_ascii_digit());
+        }
+        if matches!(self.peek(0), Some('e' | 'E')) {
+            let digit = match self.peek(1) {
+                Some('+' | '-') => self.peek(2),
+                next => next,
+            };
+            if digit.is_some_and(|char| char.is_ascii_digit()) {
+                self.next();
+                if matches!(self.peek(0), Some('+' | '-')) {
+                    self.next();
+                }
+                self.consume_while(|char| char.is_ascii_digit());
+            }
+        }
+        if starts_ident_sequence(self.peek(0), self.peek(1), self.peek(2)) {
+            self.consume_ident_sequence();
+            TokenKind::Dimension
+        } else if self.peek(0) == Some('%') {
+            self.next();
+            TokenKind::Percentage
+        } else {
+            TokenKind::Number
+        }
+    }
+
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-ident-like-token)
+    fn consume_ident_like(&mut self) -> TokenKind {
+        let name = self.consume_ident_sequence();
+        if self.peek(0) != Some('(') {
+            return TokenKind::Ident(name);
+        }
+        self.next();
+        if !name.eq_ignore_ascii_case("url") {
+            return TokenKind::Function(name);
+        }
+        // A quoted URL is a function with a string argument, and an unquoted one is a single token.
+        let rest =
+            self.input[self.position..].trim_start_matches(|char: char| char.is_ascii_whitespace());
+        if rest.starts_with(['"', '\'']) {
+            return TokenKind::Function(name);
+        }
+        self.consume_url()
+    }
+
+    /// Consumes an unquoted URL, after its `url(`.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-url-token)
+    fn consume_url(&mut self) -> TokenKind {
+        let mut value = String::new();
+        self.consume_while(|char: char| char.is_ascii_whitespace());
+        loop {
+            match self.next() {
+                None | Some(')') => return TokenKind::Url(value),
+                Some(char) if char.is_ascii_whitespace() => {
+                    self.consume_while(|char: char| char.is_ascii_whitespace());
+                    if matches!(self.peek(0), None | Some(')')) {
+                        self.next();
+                        return TokenKind::Url(value);
+                    }
+                    self.consume_bad_url_remnants();
+                    return TokenKind::BadUrl;
+                }
+                Some('"' | '\'' | '(') => {
+                    self.consume_bad_url_remnants();
+                    return TokenKind::BadUrl;
+                }
+                Some(char) if is_non_printable(char) => {
+                    self.consume_bad_url_remnants();
+                    return TokenKind::BadUrl;
+                }
+                Some('\\') => {
+                    if is_valid_escape(Some('\\'), self.peek(0)) {
+                        value.push(self.consume_escape());
+                    } else {
+                        self.consume_bad_url_remnants();
+                        return TokenKind::BadUrl;
+                    }
+                }
+                Some(char) => value.push(char),
+            }
+        }
+    }
+
+    /// [Reference](https://drafts.csswg.org/css-syntax/#consume-remnants-of-bad-url)
+    fn consume_bad_url_remnants(&mut self) {
+        loop {
+            match self.next() {
+                None | Some(')') => return,
+                Some('\\') if is_valid_escape(Some('\\'), self.peek(0)) => {
+                    self.consume_escape();
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Classification of component values, used to tell apart the parts of shorthand properties.
+
+/// The named colors, with their red, green and blue components.
+///
+/// [Reference](https://drafts.csswg.org/css-color/#named-colors)
+pub(crate) const NAMED_COLORS: &[(&str, [u8; 3])] = &[
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aqua", [0, 255, 255]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("fuchsia", [255, 0, 255]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
+
+const LENGTH_UNITS: &[&str] = &[
+    "px", "em", "rem", "ex", "ch", "vw", "vh", "vmin", "vmax", "cm", "mm", "q", "in", "pt", "pc",
+];
+
+const LINE_STYLES: &[&str] = &[
+    "none", "hidden", "dotted", "dashed", "solid", "double", "groove", "ridge", "inset", "outset",
+];
+
+/// The keywords that every property accepts.
+///
+/// [Reference](https://drafts.csswg.org/css-cascade/#defaulting-keywords)
+pub(crate) const CSS_WIDE_KEYWORDS: &[&str] = &["initial", "inherit", "unset", "revert"];
+
+/// Returns the name of a function value, like `rgb` for `rgb(0 0 0)`.
+pub(crate) fn function_name(value: &str) -> Option<&str> {
+    let (name, _) = value.split_once('(')?;
+    value.ends_with(')').then_some(name)
+}
+
+/// Returns true if the value is a math function whose type is only known once it is computed.
+fn is_math_function(value: &str) -> bool {
+    matches!(
+        function_name(value).map(str::to_ascii_lowercase).as_deref(),
+        Some("calc" | "min" | "max" | "clamp")
+    )
+}
+
+pub(crate) fn is_css_wide_keyword(value: &str) -> bool {
+    CSS_WIDE_KEYWORDS
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(value))
+}
+
+pub(crate) fn is_number(value: &str) -> bool {
+    !value.is_empty() && !value.ends_with('.') && value.parse::<f64>().is_ok()
+}
+
+/// Splits a dimension like `12.5px` into its number and unit.
+pub(crate) fn split_dimension(value: &str) -> Option<(f64, &str)> {
+    let index = value
+        .find(|char: char| !(char.is_ascii_digit() || matches!(char, '.' | '-' | '+')))
+        .unwrap_or(value.len());
+    let number = &value[..index];
+    if !is_number(number) {
+        return None;
+    }
+    Some((number.parse().ok()?, &value[index..]))
+}
+
+pub(crate) fn is_length(value: &str) -> bool {
+    match split_dimension(value) {
+        Some((number, "")) => number == 0.0,
+        Some((_, unit)) => LENGTH_UNITS
+            .iter()
+            .any(|length_unit| length_unit.eq_ignore_ascii_case(unit)),
+        None => is_math_function(value),
+    }
+}
+
+pub(crate) fn is_percentage(value: &str) -> bool {
+    matches!(split_dimension(value), Some((_, "%")))
+}
+
+pub(crate) fn is_length_percentage(value: &str) -> bool {
+    is_length(value) || is_percentage(value)
+}
+
+/// Returns true if the value is a color that can be computed: `currentcolor`, or a color that [`parse_color`] parses.
+pub(crate) fn is_color(value: &str) -> bool {
+    value.eq_ignore_ascii_case("currentcolor") || parse_color(value).is_some()
+}
+
+pub(crate) fn is_line_style(value: &str) -> bool {
+    LINE_STYLES
+        .iter()
+        .any(|style| style.eq_ignore_ascii_case(value))
+}
+
+pub(crate) fn is_line_width(value: &str) -> bool {
+    is_length(value)
+        || ["thin", "medium", "thick"]
+            .iter()
+            .any(|keyword| keyword.eq_ignore_ascii_case(value))
+}
+
+pub(crate) fn is_image(value: &str) -> bool {
+    value.eq_ignore_ascii_case("none")
+        || function_name(value).is_some_and(|name| {
+            let name = name.to_ascii_lowercase();
+            name == "url" || name == "image" || name.ends_with("-gradient")
+        })
+}
+
+/// Returns true if the value is a time, like `250ms` or `1.5s`.
+pub(crate) fn is_time(value: &str) -> bool {
+    matches!(split_dimension(value), Some((_, unit)) if is_keyword(unit, &["s", "ms"]))
+}
+
+/// Parses a time into milliseconds.
+pub(crate) fn parse_time(value: &str) -> Option<f64> {
+    let (number, unit) = split_dimension(value)?;
+    match unit.to_ascii_lowercase().as_str() {
+        "s" => Some(number * 1000.0),
+        "ms" => Some(number),
+        _ => None,
+    }
+}
+
+/// Returns true if the value is an easing function, like `ease-in` or `steps(4, end)`.
+///
+/// [Reference](https://drafts.csswg.org/css-easing/#easing-functions)
+pub(crate) fn is_easing_function(value: &str) -> bool {
+    is_keyword(
+        value,
+        &[
+            "linear",
+            "ease",
+            "ease-in",
+            "ease-out",
+            "ease-in-out",
+            "step-start",
+            "step-end",
+        ],
+    ) || function_name(value).is_some_and(|name| is_keyword(name, &["cubic-bezier", "steps"]))
+}
+
+/// Returns true if the value is an identifier, like an animation name.
+pub(crate) fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || matches!(first, '-' | '_'))
+        && chars.all(|char| char.is_alphanumeric() || matches!(char, '-' | '_'))
+        && !value.starts_with("--")
+        && !is_css_wide_keyword(value)
+}
+
+/// Returns true if the value is an integer, like `-2`.
+pub(crate) fn is_integer(value: &str) -> bool {
+    value.parse::<i64>().is_ok()
+}
+
+/// Returns true if the value is a quoted string.
+pub(crate) fn is_string(value: &str) -> bool {
+    value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')))
+}
+
+/// Returns true if the value is a transform function, like `rotate(45deg)`.
+///
+/// [Reference](https://drafts.csswg.org/css-transforms-2/#transform-functions)
+pub(crate) fn is_transform_function(value: &str) -> bool {
+    function_name(value).is_some_and(|name| {
+        matches!(
+            name.to_ascii_lowercase().as_str(),
+            "matrix"
+                | "matrix3d"
+                | "translate"
+                | "translatex"
+                | "translatey"
+                | "translatez"
+                | "translate3d"
+                | "scale"
+                | "scalex"
+                | "scaley"
+                | "scalez"
+                | "scale3d"
+                | "rotate"
+                | "rotatex"
+                | "rotatey"
+                | "rotatez"
+                | "rotate3d"
+                | "skew"
+                | "skewx"
+                | "skewy"
+                | "perspective"
+        )
+    })
+}
+
+pub(crate) fn is_keyword(value: &str, keywords: &[&str]) -> bool {
+    keywords
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(value))
+}
+
+/// Parses a color into its red, green and blue components and its alpha value. Returns `None` for `currentcolor`, and for colors in spaces other than sRGB.
+///
+/// [Reference](https://drafts.csswg.org/css-color/#color-syntax)
+pub(crate) fn parse_color(value: &str) -> Option<([u8; 3], f64)> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digits: Vec<u8> = match hex.len() {
+            3 | 4 => hex
+                .chars()
+                .map(|c| c.to_digit(16).unwrap() as u8 * 17)
+                .collect(),
+            6 | 8 => (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect(),
+            _ => return None,
+        };
+        let alpha = digits.get(3).map_or(1.0, |alpha| *alpha as f64 / 255.0);
+        return Some(([digits[0], digits[1], digits[2]], alpha));
+    }
+    if let Some(name) = function_name(value) {
+        let arguments = &value[name.len() + 1..value.len() - 1];
+        let (channels, alpha) = match arguments.split_once('/') {
+            Some((channels, alpha)) => (channels, Some(alpha.trim())),
+            None => (arguments, None),
+        };
+        let mut channels: Vec<&str> = channels
+            .split(|c: char| c == ',' || c.is_ascii_whitespace())
+            .filter(|channel| !channel.is_empty())
+            .collect();
+        let alpha = match (alpha, channels.len()) {
+            (Some(alpha), 3) => Some(alpha),
+            (None, 4) => channels.pop(),
+            (None, 3) => None,
+            _ => return None,
+        };
+        let alpha = match alpha {
+            Some(alpha) => parse_fraction(alpha)?.clamp(0.0, 1.0),
+            None => 1.0,
+        };
+        let rgb = match name.to_ascii_lowercase().as_str() {
+            "rgb" | "rgba" => {
+                let mut rgb = [0; 3];
+                for (component, channel) in rgb.iter_mut().zip(&channels) {
+                    let value = match channel.strip_suffix('%') {
+                        Some(percentage) => percentage.parse::<f64>().ok()? * 2.55,
+                        None => channel.parse::<f64>().ok()?,
+                    };
+                    *component = value.round().clamp(0.0, 255.0) as u8;
+                }
+                rgb
+            }
+            "hsl" | "hsla" => {
+                let hue = match split_dimension(channels[0])? {
+                    (hue, "" | "deg") => hue,
+                    (hue, "turn") => hue * 360.0,
+                    (hue, "rad") => hue.to_degrees(),
+                    _ => return None,
+                };
+                let saturation = parse_fraction(channels[1])?.clamp(0.0, 1.0);
+                let lightness = parse_fraction(channels[2])?.clamp(0.0, 1.0);
+                hsl_to_rgb(hue, saturation, lightness)
+            }
+            _ => return None,
+        };
+        return Some((rgb, alpha));
+    }
+    match value.to_ascii_lowercase().as_str() {
+        "transparent" => Some(([0, 0, 0], 0.0)),
+        "canvastext" => Some(([0, 0, 0], 1.0)),
+        "canvas" => Some(([255, 255, 255], 1.0)),
+        name => NAMED_COLORS
+            .iter()
+            .find(|(color, _)| *color == name)
+            .map(|(_, rgb)| (*rgb, 1.0)),
+    }
+}
+
+/// Serializes a color in the form that computed colors take, like `rgb(255, 0, 0)` or `rgba(0, 0, 0, 0.5)`.
+///
+/// [Reference](https://drafts.csswg.org/css-color/#serializing-sRGB-values)
+pub(crate) fn serialize_color(([red, green, blue], alpha): ([u8; 3], f64)) -> String {
+    if alpha == 1.0 {
+        format!("rgb({red}, {green}, {blue})")
+    } else {
+        let alpha = (alpha * 1000.0).round() / 1000.0;
+        format!("rgba({red}, {green}, {blue}, {alpha})")
+    }
+}
+
+/// Parses a number or a percentage into a fraction, so that both `0.5` and `50%` are `0.5`.
+fn parse_fraction(value: &str) -> Option<f64> {
+    match value.strip_suffix('%') {
+        Some(percentage) => Some(percentage.parse::<f64>().ok()? / 100.0),
+        None => value.parse().ok(),
+    }
+}
+
+/// [Reference](https://drafts.csswg.org/css-color/#hsl-to-rgb)
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> [u8; 3] {
+    let hue = hue.rem_euclid(360.0);
+    let channel = |n: f64| {
+        let k = (n + hue / 30.0) % 12.0;
+        let a = saturation * lightness.min(1.0 - lightness);
+        let value = lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0);
+        (value * 255.0).round() as u8
+    };
+    [channel(0.0), channel(8.0), channel(4.0)]
+}
@@ -0,0 +1,501 @@
+use std::{cell::RefMut, collections::HashMap, rc::Rc};
+
+use crate::{
+    domitem::DOMItem, node::trees::shadow_including_inclusive_descendants, tag::Tag, AsDocument,
+    AsElement, AsNode, DOMException, Document, Element, HTMLElement, Node,
+};
+
+/// The lifecycle callbacks of an autonomous custom element, registered with [`CustomElementRegistry::define`].
+///
+/// All callbacks have empty default implementations, so only the ones needed have to be provided.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Web_components/Using_custom_elements#custom_element_lifecycle_callbacks)
+pub trait CustomElement {
+    /// Returns the names of the attributes whose changes are reported to [`CustomElement::attribute_changed_callback`].
+    fn observed_attributes(&self) -> Vec<&str> {
+        vec![]
+    }
+    /// Called each time the element is added to a document.
+    fn connected_callback(&self, element: &mut HTMLElement) {}
+    /// Called each time the element is removed from a document.
+    fn disconnected_callback(&self, element: &mut HTMLElement) {}
+    /// Called each time the element is moved to a new document.
+    fn adopted_callback(
+        &self,
+        element: &mut HTMLElement,
+        old_document: &Document,
+        new_document: &Document,
+    ) {
+    }
+    /// Called when one of the observed attributes of the element is added, removed or changed.
+    fn attribute_changed_callback(
+        &self,
+        element: &mut HTMLElement,
+        name: &str,
+        old_value: Option<&str>,
+        new_value: Option<&str>,
+    ) {
+    }
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#custom-element-definition)
+pub(crate) struct CustomElementDefinition {
+    name: String,
+    observed_attributes: Vec<String>,
+    callbacks: Rc<dyn CustomElement>,
+}
+
+impl std::fmt::Debug for CustomElementDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomElementDefinition")
+            .field("name", &self.name)
+            .field("observed_attributes", &self.observed_attributes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// [Reference](https://dom.spec.whatwg.org/#concept-element-custom-element-state)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CustomElementState {
+    Undefined,
+    Failed,
+    Uncustomized,
+    Custom,
+}
+
+pub(crate) enum CustomElementCallback {
+    Connected,
+    Disconnected,
+    Adopted {
+        old_document: Document,
+        new_document: Document,
+    },
+    AttributeChanged {
+        name: String,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    },
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#custom-element-reactions)
+pub(crate) enum CustomElementReaction {
+    Upgrade(Rc<CustomElementDefinition>),
+    Callback(CustomElementCallback),
+}
+
+/// The custom element definitions of a document.
+#[derive(Default)]
+pub(crate) struct CustomElementRegistryBase {
+    definitions: Vec<Rc<CustomElementDefinition>>,
+    when_defined: HashMap<String, Vec<Box<dyn FnOnce()>>>,
+}
+
+/// Registers custom elements and queries registered custom elements.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CustomElementRegistry)
+pub struct CustomElementRegistry {
+    pub(crate) document: Document,
+}
+
+impl CustomElementRegistry {
+    fn base(&self) -> RefMut<'_, CustomElementRegistryBase> {
+        RefMut::map(self.document.state_mut(), |state| {
+            &mut state.custom_elements
+        })
+    }
+    /// Defines a new autonomous custom element. Elements with the name that already exist in the document are upgraded.
+    ///
+    /// Returns a `SyntaxError` if the name is not a valid custom element name, and a `NotSupportedError` if the name is already defined.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CustomElementRegistry/define)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, CustomElement, Document, HTMLElement};
+    ///
+    /// struct MyWidget;
+    ///
+    /// impl CustomElement for MyWidget {
+    ///     fn observed_attributes(&self) -> Vec<&str> {
+    ///         vec!["label"]
+    ///     }
+    ///     fn connected_callback(&self, element: &mut HTMLElement) {
+    ///         element.set_attribute("connected", "");
+    ///     }
+    ///     fn attribute_changed_callback(
+    ///         &self,
+    ///         element: &mut HTMLElement,
+    ///         name: &str,
+    ///         old_value: Option<&str>,
+    ///         new_value: Option<&str>,
+    ///     ) {
+    ///         element.set_attribute("aria-label", new_value.unwrap_or(""));
+    ///     }
+    /// }
+    ///
+    /// let mut document = Document::new();
+    /// let mut body = document.create_element("body");
+    /// document.append_child(&mut body).unwrap();
+    ///
+    /// document.custom_elements().define("my-widget", MyWidget).unwrap();
+    ///
+    /// let mut widget = document.create_element("my-widget");
+    /// widget.set_attribute("label", "Hello");
+    /// assert_eq!(widget.get_attribute("aria-label"), Some("Hello"));
+    ///
+    /// body.append_child(&mut widget).unwrap();
+    /// assert!(widget.has_attribute("connected"));
+    /// ```
+    pub fn define(
+        &self,
+        name: &str,
+        element: impl CustomElement + 'static,
+    ) -> Result<(), DOMException> {
+        // 2. If name is not a valid custom element name, then throw a "SyntaxError" DOMException.
+        if !Tag::from(name).is_valid_custom_element_name() {
+            return Err(DOMException::SyntaxError(format!(
+                "\"{name}\" is not a valid custom element name."
+            )));
+        }
+        // 3. If this's custom element definition set contains an item with name name, then throw a "NotSupportedError" DOMException.
+        if self.get(name).is_some() {
+            return Err(DOMException::NotSupportedError(format!(
+                "A custom element with the name \"{name}\" has already been defined."
+            )));
+        }
+        // 14.5. Let observedAttributes be the result of reading observedAttributes.
+        let observed_attributes = element
+            .observed_attributes()
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        // 15. Let definition be a new custom element definition with name name, local name localName, constructor constructor, observed attributes observedAttributes and lifecycle callbacks lifecycleCallbacks.
+        // 16. Add definition to this's custom element definition set.
+        let definition = Rc::new(CustomElementDefinition {
+            name: name.to_owned(),
+            observed_attributes,
+            callbacks: Rc::new(element),
+        });
+        self.base().definitions.push(Rc::clone(&definition));
+        // 18. Let upgradeCandidates be all elements that are shadow-including descendants of document, whose namespace is the HTML namespace and whose local name is localName, in shadow-including tree order.
+        // 19. For each element element of upgradeCandidates, enqueue a custom element upgrade reaction given element and definition.
+        for node in shadow_including_inclusive_descendants(&self.document) {
+            if let Some(element) = element_of(&node) {
+                if is_candidate(&element, &definition) {
+                    enqueue_reaction(
+                        &element,
+                        CustomElementReaction::Upgrade(Rc::clone(&definition)),
+                    );
+                }
+            }
+        }
+        self.document.invoke_custom_element_reactions();
+        // 20. If this's when-defined promise map[name] exists, resolve it and delete the entry.
+        let callbacks = self.base().when_defined.remove(name);
+        for callback in callbacks.into_iter().flatten() {
+            callback();
+        }
+        Ok(())
+    }
+    /// Returns the definition of the custom element with the given name, or [`None`] if no such element has been defined.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CustomElementRegistry/get)
+    pub fn get(&self, name: &str) -> Option<Rc<dyn CustomElement>> {
+        self.base()
+            .definitions
+            .iter()
+            .find(|definition| definition.name == name)
+            .map(|definition| Rc::clone(&definition.callbacks))
+    }
+    /// Upgrades all the custom elements in the shadow-including subtree of the root, even if they are not connected.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CustomElementRegistry/upgrade)
+    pub fn upgrade(&self, root: &impl AsNode) {
+        for node in shadow_including_inclusive_descendants(root) {
+            if let Some(element) = element_of(&node) {
+                try_upgrade(&element);
+            }
+        }
+        self.document.invoke_custom_element_reactions();
+    }
+    /// Runs the callback once a custom element is defined with the given name. If the element is already defined, the callback runs immediately.
+    ///
+    /// Returns a `SyntaxError` if the name is not a valid custom element name.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CustomElementRegistry/whenDefined)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, CustomElement, Document};
+    /// use std::{cell::Cell, rc::Rc};
+    ///
+    /// struct Counter;
+    /// impl CustomElement for Counter {}
+    ///
+    /// let document = Document::new();
+    /// let registry = document.custom_elements();
+    ///
+    /// let defined = Rc::new(Cell::new(false));
+    /// let flag = defined.clone();
+    /// registry.when_defined("x-counter", move || flag.set(true)).unwrap();
+    /// assert!(!defined.get());
+    ///
+    /// registry.define("x-counter", Counter).unwrap();
+    /// assert!(defined.get());
+    /// ```
+    pub fn when_defined(
+        &self,
+        name: &str,
+        callback: impl FnOnce() + 'static,
+    ) -> Result<(), DOMException> {
+        // 1. If name is not a valid custom element name, then return a promise rejected with a "SyntaxError" DOMException.
+        if !Tag::from(name).is_valid_custom_element_name() {
+            return Err(DOMException::SyntaxError(format!(
+                "\"{name}\" is not a valid custom element name."
+            )));
+        }
+        // 2. If this's custom element definition set contains an item with name name, then return a promise resolved.
+        if self.get(name).is_some() {
+            callback();
+            return Ok(());
+        }
+        self.base()
+            .when_defined
+            .entry(name.to_owned())
+            .or_default()
+            .push(Box::new(callback));
+        Ok(())
+    }
+}
+
+fn element_of(node: &Node) -> Option<Element> {
+    if node.node_type() != Node::ELEMENT_NODE {
+        return None;
+    }
+    node.owner_document()?
+        .lookup_html_element(node.get_base_ptr())
+}
+
+/// Returns true if an element can be upgraded with a definition.
+fn is_candidate(element: &Element, definition: &CustomElementDefinition) -> bool {
+    let base = element.base();
+    matches!(&base.tag, Tag::Unknown(name) if *name == definition.name)
+        && base.custom_element_state == CustomElementState::Undefined
+}
+
+/// Look up a custom element definition in the document of an element.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#look-up-a-custom-element-definition)
+fn look_up_definition(document: &Document, name: &str) -> Option<Rc<CustomElementDefinition>> {
+    document
+        .state()
+        .custom_elements
+        .definitions
+        .iter()
+        .find(|definition| definition.name == name)
+        .cloned()
+}
+
+/// Sets the initial custom element state of a newly created element, synchronously defining it if a definition exists.
+///
+/// [Reference](https://dom.spec.whatwg.org/#concept-create-element)
+pub(crate) fn run_creation_steps(document: &Document, element: &Element) {
+    let base = element.base();
+    if !base.tag.is_valid_custom_element_name() {
+        return;
+    }
+    let name = base.tag.to_string();
+    match look_up_definition(document, &name) {
+        Some(definition) => {
+            base.custom_element_state = CustomElementState::Custom;
+            base.custom_element_definition = Some(definition);
+        }
+        None => base.custom_element_state = CustomElementState::Undefined,
+    }
+}
+
+/// [Reference](https://dom.spec.whatwg.org/#concept-element-custom)
+pub(crate) fn is_custom(element: &Element) -> bool {
+    element.base().custom_element_state == CustomElementState::Custom
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#concept-try-upgrade)
+pub(crate) fn try_upgrade(element: &Element) {
+    // 1. Let definition be the result of looking up a custom element definition given element's node document, element's namespace, element's local name, and element's is value.
+    // 2. If definition is not null, then enqueue a custom element upgrade reaction given element and definition.
+    let definition = element
+        .owner_document()
+        .and_then(|document| look_up_definition(&document, &element.base().tag.to_string()));
+    if let Some(definition) = definition {
+        if is_candidate(element, &definition) {
+            enqueue_reaction(element, CustomElementReaction::Upgrade(definition));
+        }
+    }
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#enqueue-a-custom-element-callback-reaction)
+pub(crate) fn enqueue_callback_reaction(element: &Element, callback: CustomElementCallback) {
+    let definition = match &element.base().custom_element_definition {
+        Some(definition) => definition,
+        None => return,
+    };
+    // 5. If callbackName is "attributeChangedCallback", then:
+    //  1. Let attributeName be the first element of args.
+    //  2. If definition's observed attributes does not contain attributeName, then return.
+    if let CustomElementCallback::AttributeChanged { name, .. } = &callback {
+        if !definition.observed_attributes.contains(name) {
+            return;
+        }
+    }
+    enqueue_reaction(element, CustomElementReaction::Callback(callback));
+}
+
+fn enqueue_reaction(element: &Element, reaction: CustomElementReaction) {
+    if let Some(document) = element.owner_document() {
+        document
+            .state_mut()
+            .custom_element_reactions
+            .push((element.clone_ref(), reaction));
+    }
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#upgrades)
+fn upgrade(element: &Element, definition: Rc<CustomElementDefinition>) {
+    // 1. If element's custom element state is not "undefined" or "uncustomized", then return.
+    if !matches!(
+        element.base().custom_element_state,
+        CustomElementState::Undefined | CustomElementState::Uncustomized
+    ) {
+        return;
+    }
+    // 2. Set element's custom element definition to definition.
+    // 3. Set element's custom element state to "failed".
+    element.base().custom_element_definition = Some(definition);
+    element.base().custom_element_state = CustomElementState::Failed;
+    // 4. For each attribute in element's attribute list, in order, enqueue a custom element callback reaction with element, callback name "attributeChangedCallback", and « attribute's local name, null, attribute's value, attribute's namespace ».
+    let attributes: Vec<_> = element
+        .attributes()
+        .iter()
+        .map(|attr| (attr.__name.clone(), attr.__value.clone()))
+        .collect();
+    for (name, value) in attributes {
+        enqueue_callback_reaction(
+            element,
+            CustomElementCallback::AttributeChanged {
+                name,
+                old_value: None,
+                new_value: value,
+            },
+        );
+    }
+    // 5. If element is connected, then enqueue a custom element callback reaction with element, callback name "connectedCallback", and « ».
+    if element.is_connected() {
+        enqueue_callback_reaction(element, CustomElementCallback::Connected);
+    }
+    // 8. Set element's custom element state to "custom".
+    element.base().custom_element_state = CustomElementState::Custom;
+    crate::layout::invalidate(AsNode::cast(element));
+}
+
+/// Invokes a reaction for an element.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#invoke-custom-element-reactions)
+pub(crate) fn invoke_reaction(element: Element, reaction: CustomElementReaction) {
+    match reaction {
+        CustomElementReaction::Upgrade(definition) => upgrade(&element, definition),
+        CustomElementReaction::Callback(callback) => {
+            let callbacks = match &element.base().custom_element_definition {
+                Some(definition) => Rc::clone(&definition.callbacks),
+                None => return,
+            };
+            let html_element = element.owner_document().and_then(|document| {
+                document.lookup_html_element_base(AsNode::cast(&element).get_base_ptr())
+            });
+            let mut html_element = match html_element {
+                Some(base) => HTMLElement { base },
+                None => return,
+            };
+            match callback {
+                CustomElementCallback::Connected => callbacks.connected_callback(&mut html_element),
+                CustomElementCallback::Disconnected => {
+                    callbacks.disconnected_callback(&mut html_element)
+                }
+                CustomElementCallback::Adopted {
+                    old_document,
+                    new_document,
+                } => callbacks.adopted_callback(&mut html_element, &old_document, &new_document),
+                CustomElementCallback::AttributeChanged {
+                    name,
+                    old_value,
+                    new_value,
+                } => callbacks.attribute_changed_callback(
+                    &mut html_element,
+                    &name,
+                    old_value.as_deref(),
+                    new_value.as_deref(),
+                ),
+            }
+        }
+    }
+}
+
+/// The custom element parts of the insertion algorithm, run for a node after it is inserted.
+///
+/// [Reference](https://dom.spec.whatwg.org/#concept-node-insert)
+pub(crate) fn run_insertion_steps(node: &Node) {
+    // 7.7. For each shadow-including inclusive descendant inclusiveDescendant of node, in shadow-including tree order:
+    //  2. If inclusiveDescendant is connected, then:
+    //      1. If inclusiveDescendant is custom, then enqueue a custom element callback reaction with inclusiveDescendant, callback name "connectedCallback", and an empty argument list.
+    //      2. Otherwise, try to upgrade inclusiveDescendant.
+    if !node.is_connected() {
+        return;
+    }
+    for inclusive_descendant in shadow_including_inclusive_descendants(node) {
+        if let Some(element) = element_of(&inclusive_descendant) {
+            if is_custom(&element) {
+                enqueue_callback_reaction(&element, CustomElementCallback::Connected);
+            } else {
+                try_upgrade(&element);
+            }
+        }
+    }
+}
+
+/// The custom element parts of the removal algorithm, run for a node after it is removed from its parent.
+///
+/// [Reference](https://dom.spec.whatwg.org/#concept-node-remove)
+pub(crate) fn run_removing_steps(parent: &Node, node: &Node) {
+    // 15. Let isParentConnected be parent's connected.
+    // 16. If node is custom and isParentConnected is true, then enqueue a custom element callback reaction with node, callback name "disconnectedCallback", and an empty argument list.
+    // 17. For each shadow-including descendant descendant of node, in shadow-including tree order: if descendant is custom and isParentConnected is true, then enqueue a custom element callback reaction with descendant, callback name "disconnectedCallback", and an empty argument list.
+    if !parent.is_connected() {
+        return;
+    }
+    for inclusive_descendant in shadow_including_inclusive_descendants(node) {
+        if let Some(element) = element_of(&inclusive_descendant) {
+            if is_custom(&element) {
+                enqueue_callback_reaction(&element, CustomElementCallback::Disconnected);
+            }
+        }
+    }
+}
+
+/// The custom element parts of the attribute change steps.
+///
+/// [Reference](https://dom.spec.whatwg.org/#handle-attribute-changes)
+pub(crate) fn run_attribute_change_steps(
+    element: &Element,
+    local_name: &str,
+    old_value: Option<&str>,
+    value: Option<&str>,
+) {
+    // 2. If element is custom, then enqueue a custom element callback reaction with element, callback name "attributeChangedCallback", and « attribute's local name, oldValue, newValue, attribute's namespace ».
+    if is_custom(element) {
+        enqueue_callback_reaction(
+            element,
+            CustomElementCallback::AttributeChanged {
+                name: local_name.to_owned(),
+                old_value: old_value.map(str::to_owned),
+                new_value: value.map(str::to_owned),
+            },
+        );
+    }
+}
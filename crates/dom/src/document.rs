@@ -1,18 +1,32 @@
 use std::{
-    cell::RefCell,
+    cell::{Ref, RefCell, RefMut},
     collections::{btree_map::IterMut, HashMap},
     rc::{Rc, Weak},
 };
 
 use crate::{
+    animation::{self, Animation, StyleChanges},
+    css::MediaQueryListBase,
+    custom_elements::{CustomElementCallback, CustomElementReaction, CustomElementRegistryBase},
     domitem::DOMItem,
     element::ElementBase,
+    event::EventType,
+    font::{FixedFontProvider, FontProvider},
     html_collection::{LiveCollection, LiveCollectionType},
+    html_parser,
+    layout::{hit_test, Layout},
+    loader::ResourceLoader,
+    node::trees::{descendant_nodes, root_of, shadow_including_inclusive_descendants},
     node::{NodeBase, NodeType},
+    paint::{rasterize, Bitmap, DisplayList},
+    scroll::SmoothScroll,
+    slot::host_of_shadow_root,
     tag::Tag,
-    AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode, Attr, Element,
+    terminal::{render_text, TerminalOptions},
+    AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode, Attr, Comment,
+    CustomElementRegistry, DOMException, DocumentFragment, Element, Event, EventInit,
     HTMLAnchorElement, HTMLCollection, HTMLCollectionOf, HTMLElement, HTMLElementBase,
-    HTMLOrSVGScriptElement, Node, Range,
+    HTMLOrSVGScriptElement, Node, Range, StyleSheetList, Text, Viewport,
 };
 
 pub struct HTMLAllCollection;
@@ -20,24 +34,43 @@ pub struct HTMLAllCollection;
 pub(crate) struct DocumentBase {
     document_node: Option<Node>,
     pub url: String,
-    html_elements: HashMap<*mut NodeBase, Weak<RefCell<HTMLElementBase>>>,
+    html_elements: HashMap<*mut NodeBase, Rc<RefCell<HTMLElementBase>>>,
+    /// Maps the nodes of shadow roots to their hosts.
+    shadow_roots: HashMap<*mut NodeBase, Weak<RefCell<ElementBase>>>,
+    /// The slots that have been signalled for a `slotchange` event.
+    signal_slots: Vec<Node>,
+    pub(crate) custom_elements: CustomElementRegistryBase,
+    /// The custom element reactions waiting to be invoked.
+    pub(crate) custom_element_reactions: Vec<(Element, CustomElementReaction)>,
     ranges: Vec<Range>,
     live_collections: Vec<Weak<RefCell<LiveCollection<Element>>>>,
+    /// The inert document that owns the contents of this document's template elements.
+    template_contents_owner: Option<Document>,
+    /// Whether this document is itself the template contents owner of another document.
+    is_template_contents_owner: bool,
+    /// The loader for the document's external resources, like linked style sheets.
+    pub(crate) resource_loader: Option<Rc<dyn ResourceLoader>>,
+    /// The provider that text is measured with.
+    pub(crate) font_provider: Rc<dyn FontProvider>,
+    /// The viewport that media queries are evaluated against.
+    pub(crate) viewport: Viewport,
+    /// The media query lists created by `Window::match_media`, which are told when the viewport changes.
+    pub(crate) media_query_lists: Vec<Weak<RefCell<MediaQueryListBase>>>,
+    /// The time of the virtual clock, in milliseconds, which only moves forward when `Window::advance_time` is called.
+    pub(crate) time: f64,
+    /// The smooth scrolls that are in progress.
+    pub(crate) smooth_scrolls: Vec<SmoothScroll>,
+    /// The animations on the document timeline, in the order they were created, which is the order they apply in.
+    pub(crate) animations: Vec<Animation>,
+    /// The styles that CSS transitions start from when styles change.
+    pub(crate) style_changes: StyleChanges,
+    /// The number of changes so far that could affect the layout of the document, which the cached layout is compared against.
+    pub(crate) generation: u64,
+    /// The last layout of the document, along with the generation of changes it is current for.
+    pub(crate) layout: Option<(u64, Rc<Layout>)>,
 }
 
 impl DocumentBase {
-    /// Refresh the document.
-    pub(crate) fn refresh(&mut self, target: &Element) {
-        self.refresh_collections(target);
-    }
-
-    fn refresh_collections(&mut self, target: &Element) {
-        self.live_collections
-            .iter()
-            .filter_map(|live_collection| live_collection.upgrade())
-            .for_each(|collection| collection.borrow_mut().update());
-    }
-
     pub(crate) fn base_url(&self) -> &str {
         "Hello"
     }
@@ -51,9 +84,20 @@ impl std::fmt::Debug for DocumentBase {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Document {
     pub(crate) inner: Rc<RefCell<DocumentBase>>,
+    /// The node of the document, which is also kept in its state, held here so that it can be handed out without borrowing the state.
+    node: Node,
+}
+
+impl Clone for Document {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            node: self.node.clone_ref(),
+        }
+    }
 }
 
 impl AsDocument for Document {
@@ -66,7 +110,7 @@ impl AsDocument for Document {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct WeakDocumentRef {
     pub(crate) inner: Weak<RefCell<DocumentBase>>,
 }
@@ -79,18 +123,18 @@ impl PartialEq for WeakDocumentRef {
 
 impl<T: AsNode> PartialEq<T> for Document {
     fn eq(&self, other: &T) -> bool {
-        self.inner().document_node.as_ref().unwrap() == other
+        self.node == *other
     }
 }
 
 impl AsParentNode for Document {}
 impl AsNode for Document {
     fn cast(&self) -> &Node {
-        self.inner().document_node.as_ref().unwrap()
+        &self.node
     }
 
     fn cast_mut(&mut self) -> &mut Node {
-        self.inner().document_node.as_mut().unwrap()
+        &mut self.node
     }
 
     fn clone_node(&self, deep: bool) -> Self {
@@ -113,17 +157,162 @@ impl Document {
             document_node: None,
             url: String::new(),
             html_elements: HashMap::new(),
+            shadow_roots: HashMap::new(),
+            signal_slots: vec![],
+            custom_elements: CustomElementRegistryBase::default(),
+            custom_element_reactions: vec![],
             live_collections: vec![],
             ranges: vec![],
+            template_contents_owner: None,
+            is_template_contents_owner: false,
+            resource_loader: None,
+            font_provider: Rc::new(FixedFontProvider),
+            viewport: Viewport::default(),
+            media_query_lists: vec![],
+            time: 0.0,
+            smooth_scrolls: vec![],
+            animations: vec![],
+            style_changes: StyleChanges::default(),
+            generation: 0,
+            layout: None,
         };
-        let document = Self {
-            inner: Rc::new(RefCell::new(base)),
-        };
+        let inner = Rc::new(RefCell::new(base));
         let weak_ref = WeakDocumentRef {
-            inner: Rc::downgrade(&document.inner),
+            inner: Rc::downgrade(&inner),
         };
-        document.inner.borrow_mut().document_node =
+        inner.borrow_mut().document_node =
             Some(Node::in_document(NodeType::DocumentNode, weak_ref));
+        Self::with_base(inner)
+    }
+    /// Wraps the shared state of a document.
+    pub(crate) fn with_base(inner: Rc<RefCell<DocumentBase>>) -> Self {
+        let node = inner
+            .borrow()
+            .document_node
+            .as_ref()
+            .expect("A document is created with its node.")
+            .clone_ref();
+        Self { inner, node }
+    }
+    /// Creates a new document from an HTML string. Unlike setting [`InnerHtml::set_inner_html`], `<template shadowrootmode>` elements attach declarative shadow roots to their parents.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Document/parseHTMLUnsafe_static)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<!DOCTYPE html><title>Cards</title><x-card><template shadowrootmode=closed><p>Hidden</template></x-card>",
+    /// );
+    /// let html = document.first_element_child().unwrap();
+    /// assert_eq!(
+    ///     html.inner_html(),
+    ///     "<head><title>Cards</title></head><body><x-card></x-card></body>"
+    /// );
+    /// ```
+    ///
+    /// [`InnerHtml::set_inner_html`]: crate::InnerHtml::set_inner_html
+    pub fn parse_html_unsafe(html: &str) -> Self {
+        let document = Document::new();
+        html_parser::parse_document(&document, html, true);
+        document
+    }
+    /// Sets the URL of the document, which relative URLs in it are resolved against, like the `action` of its forms.
+    pub fn set_url(&mut self, url: &str) {
+        self.state_mut().url = url.to_owned();
+    }
+    /// Sets the loader used to fetch the document's external resources, such as the style sheets of `<link rel=stylesheet>` elements and `@import` rules. Without a loader, no external resources are loaded.
+    ///
+    /// See [`ResourceLoader`] for an example.
+    pub fn set_resource_loader(&mut self, loader: impl ResourceLoader + 'static) {
+        self.state_mut().resource_loader = Some(Rc::new(loader));
+        crate::layout::invalidate(&self.node);
+    }
+    /// Sets the provider that the document's text is measured with, in layout and by canvas contexts. The default is a [`FixedFontProvider`], which gives every font the same fixed metrics.
+    ///
+    /// See [`FontProvider`] for an example.
+    pub fn set_font_provider(&mut self, provider: impl FontProvider + 'static) {
+        self.state_mut().font_provider = Rc::new(provider);
+        crate::layout::invalidate(&self.node);
+    }
+    /// Paints the document as it is shown in its viewport, into a bitmap with the size of the viewport in device pixels.
+    ///
+    /// Backgrounds, borders, text, `<img>` elements with PNG images, opacity, transforms and clipping are painted. Text is painted with the outlines of the font provider's glyphs, or with a box for each character if it has none, like with the default [`FixedFontProvider`]. Images are loaded through the document's resource loader.
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{Document, Viewport, Window};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<body style='margin: 0; background: navy'><div style='width: 10px; height: 10px; background: red'></div>",
+    /// );
+    /// let mut window = Window::new(document.clone());
+    /// window.set_viewport(Viewport { width: 20.0, height: 20.0, ..Default::default() });
+    /// let bitmap = document.render();
+    /// assert_eq!((bitmap.width(), bitmap.height()), (20, 20));
+    /// assert_eq!(bitmap.pixel(5, 5), Some([255, 0, 0, 255]));
+    /// assert_eq!(bitmap.pixel(15, 15), Some([0, 0, 128, 255]));
+    /// assert!(bitmap.to_png().starts_with(b"\x89PNG"));
+    /// ```
+    pub fn render(&self) -> Bitmap {
+        rasterize(&DisplayList::of(self))
+    }
+    /// Lays out the document and returns what it paints in its viewport, for embedders that draw documents with a [`RenderBackend`](crate::RenderBackend) of their own.
+    ///
+    /// See [`DisplayList`] for an example, and [`Painter`](crate::Painter) to repaint only what changes.
+    pub fn display_list(&self) -> DisplayList {
+        DisplayList::of(self)
+    }
+    /// Renders the document as text for a terminal, the way a text-mode browser shows it, with lines wrapped at the width of the options.
+    ///
+    /// Blocks are on lines of their own, list items start with their markers, and tables are laid out in columns. Text is styled with ANSI escape codes for bold, italic, underlined and struck text. Links are numbered after their text, and listed with their URLs at the end, and images and form controls are shown as placeholders in brackets.
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{Document, TerminalOptions};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<h1>Title</h1><ul><li>One</li><li>Two</li></ul><p>A <a href='https://example.com'>link</a>.</p>",
+    /// );
+    /// let options = TerminalOptions { width: 40, ansi: false };
+    /// assert_eq!(
+    ///     document.render_text(&options),
+    ///     "Title\n\n  • One\n  • Two\n\nA link[1].\n\nReferences\n1. https://example.com"
+    /// );
+    /// ```
+    pub fn render_text(&self, options: &TerminalOptions) -> String {
+        render_text(self, options)
+    }
+    /// Returns the animations of the elements in the document that are playing, are about to play, or still apply because they fill, in the order they apply.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Document/getAnimations)
+    pub fn get_animations(&self) -> Vec<Animation> {
+        animation::animations_of(self, |target| target.is_connected())
+    }
+    pub(crate) fn weak_ref(&self) -> WeakDocumentRef {
+        WeakDocumentRef {
+            inner: Rc::downgrade(&self.inner),
+        }
+    }
+    /// Returns the inert document that owns the contents of template elements in this document, creating it if needed.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/scripting.html#appropriate-template-contents-owner-document)
+    pub(crate) fn template_contents_owner(&self) -> Document {
+        // 1. If doc is not a Document created by this algorithm, then:
+        //  1. If doc does not yet have an associated inert template document, then:
+        //      1. Let newDoc be a new Document.
+        //      2. Set doc's associated inert template document to newDoc.
+        //  2. Set doc to doc's associated inert template document.
+        // 2. Return doc.
+        if self.state().is_template_contents_owner {
+            return self.clone();
+        }
+        if let Some(document) = &self.state().template_contents_owner {
+            return document.clone();
+        }
+        let document = Document::new();
+        document.state_mut().is_template_contents_owner = true;
+        self.state_mut().template_contents_owner = Some(document.clone());
         document
     }
     pub(crate) fn is_html_document(&self) -> bool {
@@ -132,24 +321,137 @@ impl Document {
     pub(crate) fn associate_node_with_element(
         &self,
         node_base: *mut NodeBase,
-        html_element: Weak<RefCell<HTMLElementBase>>,
+        html_element: Rc<RefCell<HTMLElementBase>>,
     ) {
-        self.inner().html_elements.insert(node_base, html_element);
+        self.state_mut()
+            .html_elements
+            .insert(node_base, html_element);
     }
 
     /// Find an element with a node base.
     pub(crate) fn lookup_html_element(&self, node_base: *mut NodeBase) -> Option<Element> {
-        let node = self.inner().html_elements.get(&node_base)?.upgrade()?;
+        let node = self.lookup_html_element_base(node_base)?;
         let element = node.borrow().element().clone_ref();
         Some(element)
     }
+    /// Find the base of an HTML element with a node base.
+    pub(crate) fn lookup_html_element_base(
+        &self,
+        node_base: *mut NodeBase,
+    ) -> Option<Rc<RefCell<HTMLElementBase>>> {
+        self.state().html_elements.get(&node_base).cloned()
+    }
+    pub(crate) fn register_shadow_root(
+        &self,
+        node_base: *mut NodeBase,
+        host: Weak<RefCell<ElementBase>>,
+    ) {
+        self.state_mut().shadow_roots.insert(node_base, host);
+        crate::layout::invalidate(&self.node);
+    }
+    /// Find the host of a shadow root with a node base.
+    pub(crate) fn lookup_shadow_host(&self, node_base: *mut NodeBase) -> Option<Element> {
+        let host = self.state().shadow_roots.get(&node_base)?.upgrade()?;
+        Some(Element::with_base(host))
+    }
+    pub(crate) fn has_shadow_roots(&self) -> bool {
+        !self.state().shadow_roots.is_empty()
+    }
+    pub(crate) fn append_signal_slot(&self, slot: &Node) {
+        let signal_slots = &mut self.state_mut().signal_slots;
+        if !signal_slots.iter().any(|other| other.is_same_node(slot)) {
+            signal_slots.push(slot.clone_ref());
+        }
+    }
+    /// Moves a node that has no parent, and its shadow-including descendants, into the document.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#concept-node-adopt)
+    pub(crate) fn adopt(&self, node: &Node) {
+        // 1. Let oldDocument be node’s node document.
+        let old_document = match node.base().owner_document.inner.upgrade() {
+            Some(inner) => Document::with_base(inner),
+            None => return,
+        };
+        // 3. If document is not oldDocument, then:
+        if Rc::ptr_eq(&old_document.inner, &self.inner) {
+            return;
+        }
+        let weak_ref = WeakDocumentRef {
+            inner: Rc::downgrade(&self.inner),
+        };
+        let descendants = shadow_including_inclusive_descendants(node);
+        //  1. For each inclusiveDescendant in node’s shadow-including inclusive descendants:
+        //      1. Set inclusiveDescendant’s node document to document.
+        //      2. If inclusiveDescendant is an element, then set the node document of each attribute in inclusiveDescendant’s attribute list to document.
+        for descendant in descendants.iter() {
+            let base_ptr = descendant.get_base_ptr();
+            descendant.base().owner_document = weak_ref.clone();
+            let html_element = old_document.state_mut().html_elements.remove(&base_ptr);
+            if let Some(html_element) = html_element {
+                for attr in html_element.borrow().element().attributes().iter() {
+                    AsNode::cast(attr).base().owner_document = weak_ref.clone();
+                }
+                self.state_mut()
+                    .html_elements
+                    .insert(base_ptr, html_element);
+            }
+            let host = old_document.state_mut().shadow_roots.remove(&base_ptr);
+            if let Some(host) = host {
+                self.state_mut().shadow_roots.insert(base_ptr, host);
+            }
+        }
+        // The adopting steps for template elements move their contents into the new template contents owner.
+        for descendant in descendants.iter() {
+            if let Some(content) = crate::html_element::template_contents(descendant) {
+                self.template_contents_owner().adopt(&content);
+            }
+        }
+        //  2. For each inclusiveDescendant in node’s shadow-including inclusive descendants that is custom, enqueue a custom element callback reaction with inclusiveDescendant, callback name "adoptedCallback", and « oldDocument, document ».
+        for descendant in descendants.iter() {
+            if let Some(element) = self.lookup_html_element(descendant.get_base_ptr()) {
+                if crate::custom_elements::is_custom(&element) {
+                    crate::custom_elements::enqueue_callback_reaction(
+                        &element,
+                        CustomElementCallback::Adopted {
+                            old_document: old_document.clone(),
+                            new_document: self.clone(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+    /// Invokes the queued custom element reactions, including the ones queued while invoking.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#invoke-custom-element-reactions)
+    pub(crate) fn invoke_custom_element_reactions(&self) {
+        while !self.state().custom_element_reactions.is_empty() {
+            let reactions = std::mem::take(&mut self.state_mut().custom_element_reactions);
+            for (element, reaction) in reactions {
+                crate::custom_elements::invoke_reaction(element, reaction);
+            }
+        }
+    }
+    /// Fires the `slotchange` events of the signalled slots.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#notify-mutation-observers)
+    pub(crate) fn notify_mutation_observers(&self) {
+        let signal_set = std::mem::take(&mut self.state_mut().signal_slots);
+        for mut slot in signal_set {
+            let event_init = EventInit {
+                bubbles: true,
+                ..Default::default()
+            };
+            slot.dispatch_event(&mut Event::trusted(EventType::SlotChange, event_init));
+        }
+    }
     /// Find a live collection in the document with the parameters given.
     pub(crate) fn lookup_class_collection(
         &self,
         target: &Element,
         class_names: &str,
     ) -> Option<Rc<RefCell<LiveCollection<Element>>>> {
-        self.inner().live_collections.iter().find_map(|collection| {
+        self.state().live_collections.iter().find_map(|collection| {
             collection
                 .upgrade()
                 .map(|collection_ref| {
@@ -177,21 +479,39 @@ impl Document {
             items: target.class_search(class_names),
         };
         let new_collection_ref = Rc::new(RefCell::new(new_collection));
-        self.inner()
+        self.state_mut()
             .live_collections
             .push(Rc::downgrade(&new_collection_ref));
         new_collection_ref
     }
 
-    fn inner(&self) -> &mut DocumentBase {
-        unsafe { &mut *self.inner.as_ptr() }
+    /// Refresh the document.
+    pub(crate) fn refresh(&self) {
+        // Updating a collection reads the document, so it cannot stay borrowed.
+        let collections: Vec<_> = self
+            .state()
+            .live_collections
+            .iter()
+            .filter_map(|live_collection| live_collection.upgrade())
+            .collect();
+        for collection in collections {
+            collection.borrow_mut().update();
+        }
+    }
+    /// Borrows the state of the document.
+    pub(crate) fn state(&self) -> Ref<'_, DocumentBase> {
+        self.inner.borrow()
+    }
+    /// Mutably borrows the state of the document. The borrow must end before anything that could use the document again, like a callback, runs.
+    pub(crate) fn state_mut(&self) -> RefMut<'_, DocumentBase> {
+        self.inner.borrow_mut()
     }
     pub(crate) fn lookup_tag_collection(
         &self,
         target: &Element,
         tag: &Tag,
     ) -> Option<Rc<RefCell<LiveCollection<Element>>>> {
-        self.inner().live_collections.iter().find_map(|collection| {
+        self.state().live_collections.iter().find_map(|collection| {
             collection
                 .upgrade()
                 .map(|collection_ref| {
@@ -220,24 +540,26 @@ impl Document {
             items: target.tag_search(tag),
         };
         let new_collection_ref = Rc::new(RefCell::new(new_collection));
-        self.inner()
+        self.state_mut()
             .live_collections
             .push(Rc::downgrade(&new_collection_ref));
         new_collection_ref
     }
     pub(crate) fn drop_node(&mut self, base_ptr: *mut NodeBase) {
-        self.inner().html_elements.remove(&base_ptr);
+        self.state_mut().html_elements.remove(&base_ptr);
     }
 
     pub(crate) fn document_base_url(&self) -> &str {
         todo!()
     }
 
-    pub(crate) fn live_ranges(&self) -> impl Iterator<Item = &Range> {
-        self.inner().ranges.iter().filter(|range| range.is_live)
-    }
-    pub(crate) fn live_ranges_mut(&mut self) -> impl Iterator<Item = &mut Range> {
-        self.inner().ranges.iter_mut().filter(|range| range.is_live)
+    /// Runs a closure on each live range of the document.
+    pub(crate) fn update_live_ranges(&self, update: impl FnMut(&mut Range)) {
+        self.state_mut()
+            .ranges
+            .iter_mut()
+            .filter(|range| range.is_live)
+            .for_each(update);
     }
 }
 
@@ -247,8 +569,8 @@ pub trait AsDocument {
     /// Returns the URL for the document.
     ///
     /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Document/URL)
-    fn url(&self) -> &str {
-        &AsDocument::cast(self).inner().url
+    fn url(&self) -> String {
+        AsDocument::cast(self).state().url.clone()
     }
     /// Returns the color of active links in the document.
     ///
@@ -322,8 +644,188 @@ pub trait AsDocument {
         let html_element = HTMLElement::in_document(tagname, weak_ref);
         AsDocument::cast(self).associate_node_with_element(
             AsNode::cast(&html_element).get_base_ptr(),
-            Rc::downgrade(&html_element.base),
+            Rc::clone(&html_element.base),
+        );
+        crate::custom_elements::run_creation_steps(
+            AsDocument::cast(self),
+            AsElement::cast(&html_element),
         );
         html_element
     }
+    /// Transfers a node from another document into this document. The node is removed from its parent, if it has one.
+    ///
+    /// Returns a `NotSupportedError` if the node is a document, and a `HierarchyRequestError` if the node is a shadow root.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Document/adoptNode)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let other_document = Document::new();
+    ///
+    /// let mut element = other_document.create_element("p");
+    /// document.adopt_node(&mut element).unwrap();
+    ///
+    /// assert!(element.owner_document().unwrap() == document);
+    /// ```
+    fn adopt_node<'a, T: AsNode>(&self, node: &'a mut T) -> Result<&'a mut T, DOMException> {
+        // 1. If node is a document, then throw a "NotSupportedError" DOMException.
+        if node.node_type() == Node::DOCUMENT_NODE {
+            return Err(DOMException::NotSupportedError(String::from(
+                "Documents cannot be adopted.",
+            )));
+        }
+        // 2. If node is a shadow root, then throw a "HierarchyRequestError" DOMException.
+        if crate::slot::is_shadow_root(node) {
+            return Err(DOMException::HierarchyRequestError(String::from(
+                "Shadow roots cannot be adopted.",
+            )));
+        }
+        // 2. If node’s parent is non-null, then remove node.
+        if let Some(mut parent) = node.parent_node() {
+            parent.remove_child(node)?;
+        }
+        // 3. Adopt node into this.
+        let document = AsDocument::cast(self);
+        document.adopt(AsNode::cast(node));
+        document.invoke_custom_element_reactions();
+        Ok(node)
+    }
+    /// Create a [`Text`] node containing the specified data.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Document/createTextNode)
+    fn create_text_node(&self, data: &str) -> Text {
+        Text {
+            node: Node::character_data(NodeType::TextNode, data, AsDocument::cast(self).weak_ref()),
+        }
+    }
+    /// Create a [`Comment`] node containing the specified data.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Document/createComment)
+    fn create_comment(&self, data: &str) -> Comment {
+        Comment {
+            node: Node::character_data(
+                NodeType::CommentNode,
+                data,
+                AsDocument::cast(self).weak_ref(),
+            ),
+        }
+    }
+    /// Create an empty [`DocumentFragment`].
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Document/createDocumentFragment)
+    fn create_document_fragment(&self) -> DocumentFragment {
+        DocumentFragment {
+            node: Node::in_document(
+                NodeType::DocumentFragmentNode,
+                AsDocument::cast(self).weak_ref(),
+            ),
+        }
+    }
+    /// Returns the [`CustomElementRegistry`] of the document, which is used to define custom elements.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Window/customElements)
+    fn custom_elements(&self) -> CustomElementRegistry {
+        CustomElementRegistry {
+            document: AsDocument::cast(self).clone(),
+        }
+    }
+    /// Returns the style sheets of the style and link elements in the document, in tree order.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Document/styleSheets)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<style>h1 { color: red }</style><p>Text</p><style media=print>body { margin: 0 }</style>",
+    /// );
+    /// let sheets = document.style_sheets();
+    /// assert_eq!(sheets.len(), 2);
+    /// assert_eq!(sheets.item(1).unwrap().media().media_text(), "print");
+    /// ```
+    fn style_sheets(&self) -> StyleSheetList {
+        let document = AsDocument::cast(self);
+        StyleSheetList {
+            items: descendant_nodes(document)
+                .into_iter()
+                .filter_map(|node| {
+                    crate::html_element::style_element_sheet(node)
+                        .or_else(|| crate::html_element::link_element_sheet(node))
+                })
+                .collect(),
+        }
+    }
+    /// Returns the topmost element at a point in the viewport, or the root element if there is no element there. Returns `None` if the point is outside the viewport.
+    ///
+    /// Elements are hit in the order they are painted, by stacking context and `z-index`, and as they are transformed, scrolled and clipped. Elements with `pointer-events: none` or that are not visible are not hit, and elements in shadow trees are retargeted to their shadow hosts.
+    ///
+    /// MDN Reference: [`Document.elementFromPoint()`](https://developer.mozilla.org/docs/Web/API/Document/elementFromPoint)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<style>
+    ///         body { margin: 0 }
+    ///         div { position: absolute; top: 0; width: 100px; height: 100px }
+    ///     </style>
+    ///     <div class=above style='z-index: 2'></div>
+    ///     <div class=below style='z-index: 1; left: 50px'></div>",
+    /// );
+    /// let element = document.element_from_point(75.0, 50.0).unwrap();
+    /// assert_eq!(element.class_name(), "above");
+    /// let element = document.element_from_point(125.0, 50.0).unwrap();
+    /// assert_eq!(element.class_name(), "below");
+    /// assert!(document.element_from_point(-1.0, 50.0).is_none());
+    /// ```
+    fn element_from_point(&self, x: f64, y: f64) -> Option<Element> {
+        let document = AsDocument::cast(self);
+        let elements = document.elements_from_point(x, y);
+        elements.into_iter().next()
+    }
+    /// Returns all the elements at a point in the viewport, from the topmost down, followed by the root element. Returns an empty list if the point is outside the viewport.
+    ///
+    /// MDN Reference: [`Document.elementsFromPoint()`](https://developer.mozilla.org/docs/Web/API/Document/elementsFromPoint)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<p style='margin: 0'><span>Text</span></p>",
+    /// );
+    /// let names: Vec<_> = document
+    ///     .elements_from_point(10.0, 10.0)
+    ///     .iter()
+    ///     .map(|element| element.tag_name())
+    ///     .collect();
+    /// assert_eq!(names, ["SPAN", "P", "BODY", "HTML"]);
+    /// ```
+    fn elements_from_point(&self, x: f64, y: f64) -> Vec<Element> {
+        let document = AsDocument::cast(self);
+        let viewport = document.state().viewport.clone();
+        if x < 0.0 || y < 0.0 || x > viewport.width || y > viewport.height {
+            return vec![];
+        }
+        let layout = Layout::of(document);
+        let mut elements: Vec<Element> = vec![];
+        for element in hit_test(&layout, (x, y)) {
+            // Elements in shadow trees are hidden behind their hosts.
+            let mut element = element.clone_ref();
+            while let Some(host) = host_of_shadow_root(&root_of(&element)) {
+                element = host;
+            }
+            if !elements.contains(&element) {
+                elements.push(element);
+            }
+        }
+        if let Some(root) = document.first_element_child() {
+            if elements.last() != Some(&root) {
+                elements.retain(|element| *element != root);
+                elements.push(root);
+            }
+        }
+        elements
+    }
 }
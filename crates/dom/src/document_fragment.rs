@@ -0,0 +1,45 @@
+use crate::{AsDocument, AsEventTarget, AsNode, AsParentNode, EventTarget, Node};
+
+/// A minimal document object that has no parent. Its children can be inserted into a tree all at once.
+///
+/// MDN Reference: [`DocumentFragment`](https://developer.mozilla.org/docs/Web/API/DocumentFragment)
+#[derive(Debug)]
+pub struct DocumentFragment {
+    pub(crate) node: Node,
+}
+
+impl<T: AsNode> PartialEq<T> for DocumentFragment {
+    fn eq(&self, other: &T) -> bool {
+        &self.node == AsNode::cast(other)
+    }
+}
+impl AsEventTarget for DocumentFragment {
+    fn cast(&self) -> &EventTarget {
+        AsEventTarget::cast(&self.node)
+    }
+
+    fn cast_mut(&mut self) -> &mut EventTarget {
+        AsEventTarget::cast_mut(&mut self.node)
+    }
+}
+impl AsNode for DocumentFragment {
+    fn cast(&self) -> &Node {
+        &self.node
+    }
+
+    fn cast_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn clone_node(&self, deep: bool) -> Self {
+        let document = self
+            .owner_document()
+            .expect("Document fragments always have a node document.");
+        let mut copy = document.create_document_fragment();
+        if deep {
+            self.node.clone_children_into(&mut copy.node, &document);
+        }
+        copy
+    }
+}
+impl AsParentNode for DocumentFragment {}
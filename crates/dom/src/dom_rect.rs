@@ -0,0 +1,156 @@
+use crate::layout::Rect;
+
+/// A rectangle, with its position and size in CSS pixels. A negative width or height makes the rectangle extend left or up from its position.
+///
+/// MDN Reference: [`DOMRect`](https://developer.mozilla.org/docs/Web/API/DOMRect)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DOMRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl DOMRect {
+    /// Creates a rectangle from its position and size.
+    ///
+    /// MDN Reference: [`DOMRect()`](https://developer.mozilla.org/docs/Web/API/DOMRect/DOMRect)
+    /// # Example
+    /// ```
+    /// use dom::DOMRect;
+    ///
+    /// let rect = DOMRect::new(10.0, 20.0, -5.0, 30.0);
+    /// assert_eq!(rect.left(), 5.0);
+    /// assert_eq!(rect.right(), 10.0);
+    /// assert_eq!(rect.bottom(), 50.0);
+    /// ```
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        DOMRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+    /// Returns the x coordinate of the rectangle's origin.
+    ///
+    /// MDN Reference: [`DOMRect.x`](https://developer.mozilla.org/docs/Web/API/DOMRect/x)
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+    /// Sets the x coordinate of the rectangle's origin.
+    pub fn set_x(&mut self, value: f64) {
+        self.x = value;
+    }
+    /// Returns the y coordinate of the rectangle's origin.
+    ///
+    /// MDN Reference: [`DOMRect.y`](https://developer.mozilla.org/docs/Web/API/DOMRect/y)
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+    /// Sets the y coordinate of the rectangle's origin.
+    pub fn set_y(&mut self, value: f64) {
+        self.y = value;
+    }
+    /// Returns the width of the rectangle.
+    ///
+    /// MDN Reference: [`DOMRect.width`](https://developer.mozilla.org/docs/Web/API/DOMRect/width)
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+    /// Sets the width of the rectangle.
+    pub fn set_width(&mut self, value: f64) {
+        self.width = value;
+    }
+    /// Returns the height of the rectangle.
+    ///
+    /// MDN Reference: [`DOMRect.height`](https://developer.mozilla.org/docs/Web/API/DOMRect/height)
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+    /// Sets the height of the rectangle.
+    pub fn set_height(&mut self, value: f64) {
+        self.height = value;
+    }
+    /// Returns the top edge of the rectangle, whichever way its height goes.
+    ///
+    /// MDN Reference: [`DOMRect.top`](https://developer.mozilla.org/docs/Web/API/DOMRect/top)
+    pub fn top(&self) -> f64 {
+        self.y.min(self.y + self.height)
+    }
+    /// Returns the right edge of the rectangle, whichever way its width goes.
+    ///
+    /// MDN Reference: [`DOMRect.right`](https://developer.mozilla.org/docs/Web/API/DOMRect/right)
+    pub fn right(&self) -> f64 {
+        self.x.max(self.x + self.width)
+    }
+    /// Returns the bottom edge of the rectangle, whichever way its height goes.
+    ///
+    /// MDN Reference: [`DOMRect.bottom`](https://developer.mozilla.org/docs/Web/API/DOMRect/bottom)
+    pub fn bottom(&self) -> f64 {
+        self.y.max(self.y + self.height)
+    }
+    /// Returns the left edge of the rectangle, whichever way its width goes.
+    ///
+    /// MDN Reference: [`DOMRect.left`](https://developer.mozilla.org/docs/Web/API/DOMRect/left)
+    pub fn left(&self) -> f64 {
+        self.x.min(self.x + self.width)
+    }
+}
+
+impl From<Rect> for DOMRect {
+    fn from(rect: Rect) -> Self {
+        DOMRect::new(rect.x, rect.y, rect.width, rect.height)
+    }
+}
+
+impl From<DOMRect> for Rect {
+    fn from(rect: DOMRect) -> Self {
+        Rect::new(rect.left(), rect.top(), rect.width.abs(), rect.height.abs())
+    }
+}
+
+/// A collection of rectangles, like the boxes returned by [`AsElement::get_client_rects`].
+///
+/// MDN Reference: [`DOMRectList`](https://developer.mozilla.org/docs/Web/API/DOMRectList)
+///
+/// [`AsElement::get_client_rects`]: crate::AsElement::get_client_rects
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DOMRectList {
+    rects: Vec<DOMRect>,
+}
+
+impl DOMRectList {
+    pub(crate) fn new(rects: Vec<DOMRect>) -> Self {
+        DOMRectList { rects }
+    }
+    /// Returns the number of rectangles in the list.
+    ///
+    /// MDN Reference: [`DOMRectList.length`](https://developer.mozilla.org/docs/Web/API/DOMRectList/length)
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+    /// Returns true if the list has no rectangles.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+    /// Returns the rectangle at an index, if there is one.
+    ///
+    /// MDN Reference: [`DOMRectList.item()`](https://developer.mozilla.org/docs/Web/API/DOMRectList/item)
+    pub fn item(&self, index: usize) -> Option<&DOMRect> {
+        self.rects.get(index)
+    }
+    /// Returns an iterator over the rectangles in the list.
+    pub fn iter(&self) -> std::slice::Iter<'_, DOMRect> {
+        self.rects.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a DOMRectList {
+    type Item = &'a DOMRect;
+    type IntoIter = std::slice::Iter<'a, DOMRect>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rects.iter()
+    }
+}
@@ -6,6 +6,10 @@ pub enum DOMException {
     TypeError(String),
     IllegalConstructor(String),
     NotFoundError(String),
+    NotSupportedError(String),
+    IndexSizeError(String),
+    NoModificationAllowedError(String),
+    InvalidStateError(String),
 }
 
 impl DOMException {
@@ -24,7 +28,11 @@ impl DOMException {
             | DOMException::InvalidCharacterError(message)
             | DOMException::TypeError(message)
             | DOMException::IllegalConstructor(message)
-            | DOMException::NotFoundError(message) => message,
+            | DOMException::NotFoundError(message)
+            | DOMException::NotSupportedError(message)
+            | DOMException::IndexSizeError(message)
+            | DOMException::NoModificationAllowedError(message)
+            | DOMException::InvalidStateError(message) => message,
         }
     }
 
@@ -36,6 +44,10 @@ impl DOMException {
             DOMException::TypeError(_) => "TypeError",
             DOMException::IllegalConstructor(message) => "IllegalConstructor",
             DOMException::NotFoundError(_) => "NotFoundError",
+            DOMException::NotSupportedError(_) => "NotSupportedError",
+            DOMException::IndexSizeError(_) => "IndexSizeError",
+            DOMException::NoModificationAllowedError(_) => "NoModificationAllowedError",
+            DOMException::InvalidStateError(_) => "InvalidStateError",
         }
     }
 }
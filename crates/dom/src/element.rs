@@ -4,18 +4,49 @@ use std::{
 };
 
 use crate::{
-    document::DocumentBase, dom_token_list::ListType, domitem::DOMItem, node::NodeType, tag::Tag,
-    AsChildNode, AsDocument, AsEventTarget, AsNode, AsParentNode, Attr, DOMException, DOMTokenList,
-    HTMLCollection, HTMLCollectionOf, InnerHtml, MutDOMTokenList, NamedNodeMap, Node, ShadowRoot,
-    ShadowRootInit,
+    custom_elements::{CustomElementDefinition, CustomElementState},
+    document::DocumentBase,
+    dom_token_list::ListType,
+    domitem::DOMItem,
+    html_parser::{self, SerializeOptions},
+    layout,
+    node::NodeType,
+    scroll::{self, ScrollBehavior, ScrollIntoView, ScrollToOptions},
+    tag::Tag,
+    AsChildNode, AsDocument, AsEventTarget, AsNode, AsParentNode, Attr, CSSStyleDeclaration,
+    DOMException, DOMRect, DOMRectList, DOMTokenList, HTMLCollection, HTMLCollectionOf,
+    HTMLElement, HTMLSlotElement, InnerHtml, MutDOMTokenList, NamedNodeMap, Node, ShadowRoot,
+    ShadowRootInit, ShadowRootMode,
 };
 
-pub struct CheckVisibilityOptions;
-pub struct DOMRect;
-pub struct DOMRectList;
+/// The options used when checking whether an element is visible with [`AsElement::check_visibility`].
+///
+/// MDN Reference: [`Element.checkVisibility()`](https://developer.mozilla.org/docs/Web/API/Element/checkVisibility#options)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckVisibilityOptions {
+    /// Treat the element as invisible if it, or one of its ancestors, has an `opacity` of zero.
+    pub check_opacity: bool,
+    /// Treat the element as invisible if its `visibility` is `hidden` or `collapse`.
+    pub check_visibility_css: bool,
+    /// Treat the element as invisible if one of its ancestors has `content-visibility: auto` and is off the screen, which skips its contents.
+    pub content_visibility_auto: bool,
+    /// The same as `check_opacity`.
+    pub opacity_property: bool,
+    /// The same as `check_visibility_css`.
+    pub visibility_property: bool,
+}
+/// The options used when serializing an element with [`AsElement::get_html`].
+///
+/// MDN Reference: [`Element.getHTML()`](https://developer.mozilla.org/docs/Web/API/Element/getHTML#options)
+#[derive(Debug, Default)]
+pub struct GetHTMLOptions<'a> {
+    /// Include the shadow roots that are marked as serializable.
+    pub serializable_shadow_roots: bool,
+    /// Shadow roots to include, whether they are serializable or not.
+    pub shadow_roots: Vec<&'a ShadowRoot>,
+}
 pub struct InsertPosition;
 pub struct FullscreenOptions;
-pub struct ScrollToOptions;
 pub enum NameSpaceUri {
     SVG,
     XHTML,
@@ -27,6 +58,17 @@ pub(super) struct ElementBase {
     node: Node,
     pub tag: Tag,
     is_html: bool,
+    pub(crate) shadow_root: Option<ShadowRoot>,
+    pub(crate) custom_element_state: CustomElementState,
+    pub(crate) custom_element_definition: Option<Rc<CustomElementDefinition>>,
+    /// How far the element is scrolled to the right and down, if it is a scroll container.
+    pub(crate) scroll_position: (f64, f64),
+    /// The form that the element is associated with, if it is a form-associated element.
+    pub(crate) form_owner: Option<Weak<RefCell<ElementBase>>>,
+    /// The message set with `setCustomValidity()`, which makes the element invalid while it is not empty.
+    pub(crate) custom_validity_error: String,
+    /// Whether the validity of the element has been reported to the user, after which it matches `:user-valid` or `:user-invalid`.
+    pub(crate) user_validity: bool,
 }
 
 /// Element is the most general base class from which all objects in a Document inherit. It only has methods and properties common to all kinds of elements. More specific classes inherit from Element.
@@ -42,6 +84,24 @@ impl<T: AsNode> PartialEq<T> for Element {
 }
 
 impl Element {
+    /// Replaces the children of the element with the result of parsing an HTML string in its context.
+    pub(crate) fn replace_with_html(&mut self, html: &str, allow_declarative_shadow_roots: bool) {
+        let context_name = self.base().tag.to_string();
+        // The markup of a template replaces its contents rather than its children.
+        if let Some(mut content) = crate::html_element::template_contents(self) {
+            let mut fragment = html_parser::parse_fragment(
+                &content,
+                &context_name,
+                html,
+                allow_declarative_shadow_roots,
+            );
+            content.replace_all(&mut fragment);
+            return;
+        }
+        let mut fragment =
+            html_parser::parse_fragment(self, &context_name, html, allow_declarative_shadow_roots);
+        AsNode::cast_mut(self).replace_all(&mut fragment);
+    }
     /// Search the descendants of this element for elements that have a set of class names.
     pub(crate) fn class_search(&self, class_names: &str) -> Vec<Element> {
         let mut matches = vec![];
@@ -79,6 +139,13 @@ impl Element {
                 is_html,
                 node: Node::in_document(NodeType::ElementNode, weak_ref),
                 tag,
+                shadow_root: None,
+                custom_element_state: CustomElementState::Uncustomized,
+                custom_element_definition: None,
+                scroll_position: (0.0, 0.0),
+                form_owner: None,
+                custom_validity_error: String::new(),
+                user_validity: false,
             })),
         };
         element.base().attributes = Some(NamedNodeMap {
@@ -101,6 +168,30 @@ impl Element {
         Rc::downgrade(&self.base)
     }
 
+    /// Runs the steps for when an attribute of the element is changed, appended or removed.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#handle-attribute-changes)
+    pub(crate) fn handle_attribute_changes(
+        &self,
+        local_name: &str,
+        old_value: Option<&str>,
+        value: Option<&str>,
+    ) {
+        crate::layout::invalidate(AsNode::cast(self));
+        crate::custom_elements::run_attribute_change_steps(self, local_name, old_value, value);
+        crate::slot::run_attribute_change_steps(self, local_name, old_value, value);
+        crate::html_element::form_associated::run_attribute_change_steps(
+            self, local_name, old_value, value,
+        );
+        crate::html_element::html_input_element::run_attribute_change_steps(
+            self, local_name, old_value, value,
+        );
+        if let Some(document) = self.owner_document() {
+            document.invoke_custom_element_reactions();
+            document.notify_mutation_observers();
+        }
+    }
+
     // /// Unsafe shenanigans. Returns a mutable reference to the base of the document in which this element is defined.
     // unsafe fn document_mut_ref(&self) -> &mut DocumentBase {
     //     &mut *((*self.base().node.inner.as_ptr())
@@ -150,6 +241,13 @@ impl AsNode for Element {
                 is_html: self.base().is_html,
                 attributes: None,
                 tag: self.base().tag.clone(),
+                shadow_root: None,
+                custom_element_state: CustomElementState::Uncustomized,
+                custom_element_definition: None,
+                scroll_position: (0.0, 0.0),
+                form_owner: None,
+                custom_validity_error: String::new(),
+                user_validity: false,
             })),
         };
         element.base().attributes = Some(NamedNodeMap {
@@ -167,12 +265,33 @@ impl AsNode for Element {
 impl AsChildNode for Element {}
 impl AsParentNode for Element {}
 impl InnerHtml for Element {
+    /// Returns an HTML serialization of the element's descendants.
+    ///
+    /// MDN Reference: [`Element.innerHTML`](https://developer.mozilla.org/docs/Web/API/Element/innerHTML)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut list = document.create_element("ul");
+    /// list.set_inner_html("<li class=item>One<li>Two & <b>three</b>").unwrap();
+    ///
+    /// assert_eq!(list.child_element_count(), 2);
+    /// assert_eq!(
+    ///     list.inner_html(),
+    ///     r#"<li class="item">One</li><li>Two &amp; <b>three</b></li>"#
+    /// );
+    /// ```
     fn inner_html(&self) -> String {
-        todo!()
+        html_parser::serialize_children(self, &SerializeOptions::NONE)
     }
 
+    /// Replaces the descendants of the element with the nodes parsed from an HTML string. Declarative shadow roots in the markup are left as `<template>` elements.
+    ///
+    /// MDN Reference: [`Element.innerHTML`](https://developer.mozilla.org/docs/Web/API/Element/innerHTML)
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.replace_with_html(value, false);
+        Ok(())
     }
 }
 impl AsElement for Element {
@@ -211,6 +330,41 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     fn class_list(&self) -> DOMTokenList {
         DOMTokenList::from_element(AsElement::cast(self), ListType::ClassList)
     }
+    /// Returns the inline style of the element, as set in its `style` attribute. The returned declaration is read-only; use [`AsElement::style_mut`] to change it.
+    ///
+    /// MDN Reference: [`HTMLElement.style`](https://developer.mozilla.org/docs/Web/API/HTMLElement/style).
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("p");
+    /// element.set_attribute("style", "color: green; font-weight: bold");
+    ///
+    /// assert_eq!(element.style().color(), "green");
+    /// assert!(element.style().set_color("red").is_err());
+    /// ```
+    fn style(&self) -> CSSStyleDeclaration {
+        CSSStyleDeclaration::for_element(AsElement::cast(self), true)
+    }
+    /// Returns the inline style of the element for editing. Changes to it are written back to the `style` attribute.
+    ///
+    /// MDN Reference: [`HTMLElement.style`](https://developer.mozilla.org/docs/Web/API/HTMLElement/style).
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("div");
+    /// element.style_mut().set_property("color", "green", None).unwrap();
+    /// element.style_mut().set_margin("0 auto").unwrap();
+    ///
+    /// assert_eq!(element.get_attribute("style").unwrap(), "color: green; margin: 0 auto;");
+    /// assert_eq!(element.style().margin_left(), "auto");
+    /// ```
+    fn style_mut(&mut self) -> CSSStyleDeclaration {
+        CSSStyleDeclaration::for_element(AsElement::cast(self), false)
+    }
     /// Returns a [`MutDOMTokenList`] containing the list of class attributes.
     ///
     /// MDN Reference: [`Element.classList`](https://developer.mozilla.org/en-US/docs/Web/API/Element/classList).
@@ -264,26 +418,44 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     /// Returns a number representing the inner height of the element.
     ///
     /// MDN Reference: [`Element.clientHeight`](https://developer.mozilla.org/en-US/docs/Web/API/Element/clientHeight)
+    ///
+    /// This is the height of the padding box, or the height of the viewport for the root element. It is zero for inline boxes and for elements that are not rendered.
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document =
+    ///     Document::parse_html_unsafe("<div style='height: 50px; padding: 10px; border: 2px solid'></div>");
+    /// let html = document.first_element_child().unwrap();
+    /// let body = html.children().item(1).unwrap();
+    /// let div = body.children().item(0).unwrap();
+    /// assert_eq!(div.client_height(), 70);
+    /// assert_eq!(div.client_top(), 2);
+    /// ```
     fn client_height(&self) -> usize {
-        todo!()
+        layout::geometry_of(self).map_or(0, |geometry| geometry.client_height.round() as usize)
     }
     /// Returns a number representing the width of the left border of the element.
     ///
     /// MDN Reference: [`Element.clientLeft`](https://developer.mozilla.org/en-US/docs/Web/API/Element/clientLeft).
     fn client_left(&self) -> usize {
-        todo!()
+        layout::geometry_of(self)
+            .filter(|geometry| !geometry.is_inline)
+            .map_or(0, |geometry| geometry.border.left.round() as usize)
     }
     /// Returns a number representing the width of the top border of the element.
     ///
     /// MDN Reference: [`Element.clientTop`](https://developer.mozilla.org/en-US/docs/Web/API/Element/clientTop).
     fn client_top(&self) -> usize {
-        todo!()
+        layout::geometry_of(self)
+            .filter(|geometry| !geometry.is_inline)
+            .map_or(0, |geometry| geometry.border.top.round() as usize)
     }
     /// Returns a number representing the inner width of the element.
     ///
     /// MDN Reference: [`Element.clientWidth`](https://developer.mozilla.org/en-US/docs/Web/API/Element/clientWidth).
     fn client_width(&self) -> usize {
-        todo!()
+        layout::geometry_of(self).map_or(0, |geometry| geometry.client_width.round() as usize)
     }
     /// Returns a string slice representing the id of the element. It returns an empty slice if there is no id specified.
     ///
@@ -342,17 +514,65 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     fn namespace_uri(&self) -> Option<&str> {
         todo!()
     }
-    /// Returns a string slice containing an HTML serialization of the element and its descendants.
+    /// Returns a string containing an HTML serialization of the element and its descendants.
     ///
     /// MDN Reference: [`Element.outerHTML`](https://developer.mozilla.org/en-US/docs/Web/API/Element/outerHTML).
-    fn outer_html(&self) -> &str {
-        todo!()
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut image = document.create_element("img");
+    /// image.set_attribute("alt", "A \"quoted\" caption");
+    ///
+    /// assert_eq!(image.outer_html(), r#"<img alt="A &quot;quoted&quot; caption">"#);
+    /// ```
+    fn outer_html(&self) -> String {
+        html_parser::serialize_node(self, &SerializeOptions::NONE)
     }
-    /// Sets the outer html of the element.
+    /// Replaces the element with the nodes parsed from an HTML string, in the context of its parent.
+    ///
+    /// Nothing happens if the element has no parent, and a `NoModificationAllowedError` is returned if the parent is a document.
     ///
     /// MDN Reference: [`Element.outerHTML`](https://developer.mozilla.org/en-US/docs/Web/API/Element/outerHTML).
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut parent = document.create_element("div");
+    /// let mut child = document.create_element("span");
+    /// parent.append_child(&mut child).unwrap();
+    ///
+    /// child.set_outer_html("<b>Bold</b> text").unwrap();
+    ///
+    /// assert_eq!(parent.inner_html(), "<b>Bold</b> text");
+    /// assert!(child.parent_node().is_none());
+    /// ```
     fn set_outer_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        let mut parent = match self.parent_node() {
+            Some(parent) => parent,
+            None => return Ok(()),
+        };
+        let context_name = match parent.node_type() {
+            Node::DOCUMENT_NODE => {
+                return Err(DOMException::NoModificationAllowedError(String::from(
+                    "Cannot replace the document element with outer_html.",
+                )))
+            }
+            Node::ELEMENT_NODE => self
+                .owner_document()
+                .and_then(|document| {
+                    document.lookup_html_element(AsNode::cast(&parent).get_base_ptr())
+                })
+                .map(|element| element.base().tag.to_string())
+                .unwrap_or_default(),
+            // The children of fragments are parsed as if in a body element.
+            _ => String::from("body"),
+        };
+        let mut fragment = html_parser::parse_fragment(self, &context_name, value, false);
+        parent.replace_child(&mut fragment, self)?;
+        Ok(())
     }
     /// Represents the part identifier(s) of the element (i.e. set using the part attribute), returned as a DOMTokenList.
     ///
@@ -376,61 +596,119 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     ///
     /// MDN Reference: [`Element.scrollHeight`](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollHeight).
     fn scroll_height(&self) -> usize {
-        todo!()
+        layout::geometry_of(self).map_or(0, |geometry| geometry.scroll_height.round() as usize)
     }
     /// Returns a number representing the left scroll offset of the element.
     ///
+    /// Only scroll containers, whose `overflow` is `hidden`, `scroll` or `auto`, can be scrolled. The root element scrolls the viewport.
+    ///
     /// MDN Reference: [`Element.scrollLeft`](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollLeft).
     fn scroll_left(&self) -> usize {
-        todo!()
+        scroll::scroll_position(AsElement::cast(self)).0.round() as usize
     }
-    /// Sets the left scroll offset of the element.
+    /// Sets the left scroll offset of the element. The offset is clamped to the scroll range of the element.
     ///
     /// MDN Reference: [`Element.scrollLeft`](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollLeft).
     fn set_scroll_left(&mut self, value: usize) {
-        todo!()
+        let element = AsElement::cast(self);
+        scroll::scroll_to(element, Some(value as f64), None, ScrollBehavior::Auto);
     }
     /// Returns a number representing number of pixels the top of the element is scrolled vertically.
     ///
+    /// Only scroll containers, whose `overflow` is `hidden`, `scroll` or `auto`, can be scrolled. The root element scrolls the viewport.
+    ///
     /// MDN Reference: [`Element.scrollTop`](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollTop).
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<div style='height: 100px; overflow: auto'><div style='height: 250px'></div></div>",
+    /// );
+    /// let html = document.first_element_child().unwrap();
+    /// let body = html.children().item(1).unwrap();
+    /// let mut list = body.first_element_child().unwrap();
+    /// list.set_scroll_top(60);
+    /// assert_eq!(list.scroll_top(), 60);
+    /// // Offsets are clamped to the content that can be scrolled to.
+    /// list.set_scroll_top(500);
+    /// assert_eq!(list.scroll_top(), 150);
+    /// ```
     fn scroll_top(&self) -> usize {
-        todo!()
+        scroll::scroll_position(AsElement::cast(self)).1.round() as usize
     }
-    /// Sets the number of pixels the top of the element is scrolled vertically.
+    /// Sets the number of pixels the top of the element is scrolled vertically. The offset is clamped to the scroll range of the element.
     ///
     /// MDN Reference: [`Element.scrollTop`](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollTop).
     fn set_scroll_top(&mut self, value: usize) {
-        todo!()
+        let element = AsElement::cast(self);
+        scroll::scroll_to(element, None, Some(value as f64), ScrollBehavior::Auto);
     }
     /// Returns a number representing the scroll view width of an element.
     ///
     /// MDN Reference: [`Element.scrollWidth`](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollWidth).
     fn scroll_width(&self) -> usize {
-        todo!()
+        layout::geometry_of(self).map_or(0, |geometry| geometry.scroll_width.round() as usize)
     }
     /// Returns reference to the open shadow root that is hosted by the element, or [`None`] if no open shadow root is present.
     ///
     /// MDN Reference: [`Element.shadowRoot`](https://developer.mozilla.org/en-US/docs/Web/API/Element/shadowRoot).
     fn shadow_root(&self) -> Option<&ShadowRoot> {
-        todo!()
+        AsElement::cast(self)
+            .base()
+            .shadow_root
+            .as_ref()
+            .filter(|shadow_root| shadow_root.mode() == ShadowRootMode::Open)
     }
     /// Returns a mutable reference to the open shadow root that is hosted by the element, or [`None`] if no open shadow root is present.
     ///
     /// MDN Reference: [`Element.shadowRoot`](https://developer.mozilla.org/en-US/docs/Web/API/Element/shadowRoot).
     fn shadow_root_mut(&mut self) -> Option<&mut ShadowRoot> {
-        todo!()
+        AsElement::cast_mut(self)
+            .base()
+            .shadow_root
+            .as_mut()
+            .filter(|shadow_root| shadow_root.mode() == ShadowRootMode::Open)
     }
     /// Returns the name of the shadow DOM slot the element is inserted in, or an empty string.
     ///
     /// MDN Reference: [`Element.slot`](https://developer.mozilla.org/en-US/docs/Web/API/Element/slot).
     fn slot(&self) -> &str {
-        todo!()
+        self.get_attribute("slot").unwrap_or("")
     }
     /// Sets the name of the shadow DOM slot the element is inserted in.
     ///
     /// MDN Reference: [`Element.slot`](https://developer.mozilla.org/en-US/docs/Web/API/Element/slot).
-    fn set_slot(&self, value: &str) {
-        todo!()
+    fn set_slot(&mut self, value: &str) {
+        self.set_attribute("slot", value)
+    }
+    /// Returns the slot the element is assigned to, if the slot is in an open shadow root.
+    ///
+    /// MDN Reference: [`Element.assignedSlot`](https://developer.mozilla.org/en-US/docs/Web/API/Element/assignedSlot).
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, ShadowRootInit, ShadowRootMode};
+    ///
+    /// let document = Document::new();
+    /// let mut host = document.create_element("div");
+    /// let mut shadow_root = host.attach_shadow(ShadowRootInit::new(ShadowRootMode::Open)).unwrap();
+    ///
+    /// let mut slot = document.create_element("slot");
+    /// slot.set_attribute("name", "title");
+    /// shadow_root.append_child(&mut slot).unwrap();
+    ///
+    /// let mut heading = document.create_element("h1");
+    /// heading.set_slot("title");
+    /// host.append_child(&mut heading).unwrap();
+    ///
+    /// assert!(heading.assigned_slot().unwrap() == slot);
+    /// ```
+    fn assigned_slot(&self) -> Option<HTMLSlotElement> {
+        let slot = crate::slot::find_a_slot(AsNode::cast(self), true)?;
+        let html_element = self
+            .owner_document()?
+            .lookup_html_element_base(slot.get_base_ptr())?;
+        HTMLSlotElement::try_from(HTMLElement { base: html_element }).ok()
     }
     /// Returns a string with the name of the tag for the given element.
     ///
@@ -458,17 +736,138 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     /// Attaches a shadow DOM tree to the specified element and returns a mutable reference to its [`ShadowRoot`].
     ///
     /// MDN Reference: [`Element.attachShadow()`](https://developer.mozilla.org/en-US/docs/Web/API/Element/attachShadow).
-    fn attach_shadow(&mut self, init: ShadowRootInit) -> &mut ShadowRoot {
-        todo!()
+    ///
+    /// Only elements with a valid custom element name, and `article`, `aside`, `blockquote`, `body`, `div`, `footer`, `h1` to `h6`, `header`, `main`, `nav`, `p`, `section` and `span` elements can host a shadow root.
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, ShadowRootInit, ShadowRootMode};
+    ///
+    /// let document = Document::new();
+    /// let mut host = document.create_element("div");
+    /// host.attach_shadow(ShadowRootInit::new(ShadowRootMode::Open)).unwrap();
+    /// assert!(host.shadow_root().is_some());
+    ///
+    /// // Closed shadow roots are not exposed.
+    /// let mut closed_host = document.create_element("span");
+    /// closed_host.attach_shadow(ShadowRootInit::new(ShadowRootMode::Closed)).unwrap();
+    /// assert!(closed_host.shadow_root().is_none());
+    ///
+    /// // Some elements cannot host shadow roots.
+    /// let mut image = document.create_element("img");
+    /// assert!(image.attach_shadow(ShadowRootInit::new(ShadowRootMode::Open)).is_err());
+    /// ```
+    fn attach_shadow(&mut self, init: ShadowRootInit) -> Result<&mut ShadowRoot, DOMException> {
+        let element = AsElement::cast_mut(self);
+        // 1. If this’s namespace is not the HTML namespace, then throw a "NotSupportedError" DOMException.
+        // 2. If this’s local name is not a valid shadow host name, then throw a "NotSupportedError" DOMException.
+        if !element.is_html() || !element.base().tag.is_valid_shadow_host() {
+            return Err(DOMException::NotSupportedError(format!(
+                "Cannot attach a shadow root to a <{}> element.",
+                element.base().tag
+            )));
+        }
+        // 4. If this is a shadow host, then throw a "NotSupportedError" DOMException.
+        if element.base().shadow_root.is_some() {
+            return Err(DOMException::NotSupportedError(String::from(
+                "The element is already a shadow host.",
+            )));
+        }
+        // 5. Let shadow be a new shadow root whose node document is this’s node document, host is this, and mode is init["mode"].
+        let shadow_root = ShadowRoot::for_host(element, init);
+        // 10. Set this’s shadow root to shadow.
+        element.base().shadow_root = Some(shadow_root);
+        Ok(element.base().shadow_root.as_mut().unwrap())
     }
+    /// Returns true if the element is rendered: if it generates boxes, and is not inside an element with `content-visibility: hidden`. The options also check its opacity and visibility.
+    ///
+    /// MDN Reference: [`Element.checkVisibility()`](https://developer.mozilla.org/docs/Web/API/Element/checkVisibility)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, CheckVisibilityOptions, Document};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<p style='opacity: 0'>Faded</p><p style='display: none'>Hidden</p>",
+    /// );
+    /// let html = document.first_element_child().unwrap();
+    /// let body = html.children().item(1).unwrap();
+    /// let faded = body.first_element_child().unwrap();
+    /// let hidden = body.last_element_child().unwrap();
+    ///
+    /// assert!(faded.check_visibility(None));
+    /// assert!(!faded.check_visibility(Some(CheckVisibilityOptions {
+    ///     check_opacity: true,
+    ///     ..Default::default()
+    /// })));
+    /// assert!(!hidden.check_visibility(None));
+    /// ```
     fn check_visibility(&self, options: Option<CheckVisibilityOptions>) -> bool {
-        todo!()
+        let options = options.unwrap_or_default();
+        let element = AsElement::cast(self);
+        let Some(document) = element.owner_document().filter(|_| element.is_connected()) else {
+            return false;
+        };
+        let layout = layout::Layout::of(&document);
+        // Elements inside `content-visibility: hidden` do not generate boxes.
+        let Some(path) = layout.path_to(element) else {
+            return false;
+        };
+        let style = &path[path.len() - 1].style;
+        if (options.check_opacity || options.opacity_property)
+            && path
+                .iter()
+                .any(|layout_box| layout_box.style.get("opacity").parse() == Ok(0.0))
+        {
+            return false;
+        }
+        if (options.check_visibility_css || options.visibility_property)
+            && style.get("visibility") != "visible"
+        {
+            return false;
+        }
+        if options.content_visibility_auto {
+            let viewport =
+                layout::Rect::new(0.0, 0.0, layout.viewport.width, layout.viewport.height);
+            let skips_contents = path[..path.len() - 1].iter().any(|layout_box| {
+                layout_box.style.get("content-visibility") == "auto"
+                    && !layout_box.rect.intersects(&viewport)
+            });
+            if skips_contents {
+                return false;
+            }
+        }
+        true
     }
     /// Traverses the element and its parents (heading toward the document root) until it finds a node that matches the specified CSS selector.
     ///
     /// MDN Reference: [`Element.closest()`](https://developer.mozilla.org/en-US/docs/Web/API/Element/closest).
+    ///
+    /// Returns [`None`] if the selector is invalid.
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut div = document.create_element("div");
+    /// div.set_inner_html("<section class=card><p><b>Bold</b></p></section>").unwrap();
+    /// let section = div.children().item(0).unwrap();
+    /// let paragraph = section.children().item(0).unwrap();
+    /// let bold = paragraph.children().item(0).unwrap();
+    ///
+    /// assert_eq!(bold.closest(".card").unwrap().tag_name(), "SECTION");
+    /// assert!(bold.closest("b").is_some());
+    /// assert!(bold.closest("article").is_none());
+    /// ```
     fn closest(&self, selector: &str) -> Option<Element> {
-        todo!()
+        let selectors = crate::css::SelectorList::parse(selector)?;
+        let context = crate::css::MatchContext::default();
+        let mut current = Some(AsElement::cast(self).clone_ref());
+        while let Some(element) = current {
+            if selectors.matches(&element, &context) {
+                return Some(element);
+            }
+            current = element.parent_element();
+        }
+        None
     }
     /// Returns the value of a specified attribute on the element, or None if the attribute does not exist.
     ///
@@ -568,11 +967,82 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
         self.attributes_mut()
             .get_named_item_ns_mut(namespace, local_name)
     }
+    /// Returns an HTML serialization of the element's descendants. Unlike [`InnerHtml::inner_html`], shadow roots can be included as declarative `<template shadowrootmode>` elements.
+    ///
+    /// MDN Reference: [`Element.getHTML()`](https://developer.mozilla.org/docs/Web/API/Element/getHTML)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, GetHTMLOptions};
+    ///
+    /// let document = Document::new();
+    /// let mut host = document.create_element("div");
+    /// host.set_html_unsafe(
+    ///     "<p><template shadowrootmode=open shadowrootserializable><slot></slot></template>Light</p>",
+    /// );
+    ///
+    /// // Shadow roots are left out by default.
+    /// assert_eq!(host.get_html(None), "<p>Light</p>");
+    ///
+    /// let options = GetHTMLOptions {
+    ///     serializable_shadow_roots: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     host.get_html(Some(options)),
+    ///     r#"<p><template shadowrootmode="open" shadowrootserializable=""><slot></slot></template>Light</p>"#
+    /// );
+    /// ```
+    fn get_html(&self, options: Option<GetHTMLOptions>) -> String {
+        let options = options.unwrap_or_default();
+        html_parser::serialize_children(
+            self,
+            &SerializeOptions {
+                serializable_shadow_roots: options.serializable_shadow_roots,
+                shadow_roots: &options.shadow_roots,
+            },
+        )
+    }
+    /// Returns the smallest rectangle that contains all the border boxes of the element, relative to the viewport.
+    ///
+    /// The rectangle is empty and at the origin if the element is not rendered.
+    ///
+    /// MDN Reference: [`Element.getBoundingClientRect()`](https://developer.mozilla.org/docs/Web/API/Element/getBoundingClientRect)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document =
+    ///     Document::parse_html_unsafe("<div style='width: 100px; height: 40px; margin: 0 auto'></div>");
+    /// let html = document.first_element_child().unwrap();
+    /// let body = html.children().item(1).unwrap();
+    /// let div = body.children().item(0).unwrap();
+    /// let rect = div.get_bounding_client_rect();
+    /// assert_eq!(rect.x(), 350.0);
+    /// assert_eq!(rect.y(), 8.0);
+    /// assert_eq!(rect.width(), 100.0);
+    /// assert_eq!(rect.bottom(), 48.0);
+    /// ```
     fn get_bounding_client_rect(&self) -> DOMRect {
-        todo!()
+        layout::geometry_of(self)
+            .and_then(|geometry| {
+                geometry
+                    .fragments
+                    .into_iter()
+                    .reduce(|bounds, fragment| bounds.union(&fragment))
+            })
+            .map(DOMRect::from)
+            .unwrap_or_default()
     }
+    /// Returns the border boxes of the element, relative to the viewport: one for a block-level box, or one for each line of an inline box.
+    ///
+    /// The list is empty if the element is not rendered.
+    ///
+    /// MDN Reference: [`Element.getClientRects()`](https://developer.mozilla.org/docs/Web/API/Element/getClientRects)
     fn get_client_rects(&self) -> DOMRectList {
-        todo!()
+        let fragments = layout::geometry_of(self)
+            .map(|geometry| geometry.fragments)
+            .unwrap_or_default();
+        DOMRectList::new(fragments.into_iter().map(DOMRect::from).collect())
     }
     /// Returns the elements that have the given class names, from within this element.
     ///
@@ -668,14 +1138,54 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     fn insert_adjacent_text(&mut self, data: &str) {
         todo!()
     }
+    /// Tests whether the element would be selected by the specified CSS selector.
+    ///
+    /// Returns false if the selector is invalid.
+    ///
+    /// MDN Reference: [`Element.matches()`](https://developer.mozilla.org/en-US/docs/Web/API/Element/matches).
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut list = document.create_element("ul");
+    /// list.set_inner_html("<li>One</li><li class=done>Two</li>").unwrap();
+    /// let second = list.children().item(1).unwrap();
+    ///
+    /// assert!(second.matches("li.done"));
+    /// assert!(second.matches("ul > :nth-child(2)"));
+    /// assert!(!second.matches(":first-child"));
+    /// ```
     fn matches(&self, selectors: &str) -> bool {
-        todo!()
+        crate::css::SelectorList::parse(selectors).is_some_and(|selectors| {
+            selectors.matches(AsElement::cast(self), &crate::css::MatchContext::default())
+        })
     }
     fn release_pointer_capture(&mut self, pointer_id: usize) {
         todo!()
     }
+    /// Removes the attribute with the specified name from the element.
+    ///
+    /// MDN Reference: [`Element.removeAttribute()`](https://developer.mozilla.org/en-US/docs/Web/API/Element/removeAttribute).
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("div");
+    ///
+    /// element.set_attribute("title", "Greeting");
+    /// element.remove_attribute("title");
+    ///
+    /// assert!(!element.has_attribute("title"));
+    /// ```
     fn remove_attribute(&mut self, qualified_name: &str) {
-        todo!()
+        let qualified_name = if AsElement::cast(self).is_html() {
+            qualified_name.to_ascii_lowercase()
+        } else {
+            qualified_name.to_owned()
+        };
+        self.attributes_mut().remove_named_item(&qualified_name);
     }
     fn remove_attribute_ns(&mut self, namespace: Option<&str>, local_name: &str) {
         todo!()
@@ -689,26 +1199,93 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     fn request_pointer_lock(&self) {
         todo!()
     }
+    /// Scrolls the element to a position. The same as [`AsElement::scroll_to`].
+    ///
+    /// MDN Reference: [`Element.scroll()`](https://developer.mozilla.org/docs/Web/API/Element/scroll)
     fn scroll(&mut self, options: Option<ScrollToOptions>) {
-        todo!()
+        self.scroll_to(options)
     }
-    fn scroll_xy(&mut self, x: usize, y: usize) {
-        todo!()
+    /// Scrolls the element to a position. The same as [`AsElement::scroll_to_xy`].
+    ///
+    /// MDN Reference: [`Element.scroll()`](https://developer.mozilla.org/docs/Web/API/Element/scroll)
+    fn scroll_xy(&mut self, x: f64, y: f64) {
+        self.scroll_to_xy(x, y)
     }
+    /// Scrolls the element by an amount, from where it is scrolled to now.
+    ///
+    /// MDN Reference: [`Element.scrollBy()`](https://developer.mozilla.org/docs/Web/API/Element/scrollBy)
     fn scroll_by(&mut self, options: Option<ScrollToOptions>) {
-        todo!()
+        let options = options.unwrap_or_default();
+        scroll::scroll_by(
+            AsElement::cast(self),
+            options.left.unwrap_or(0.0),
+            options.top.unwrap_or(0.0),
+            options.behavior,
+        );
     }
-    fn scroll_by_xy(&mut self, x: usize, y: usize) {
-        todo!()
+    /// Scrolls the element by an amount, from where it is scrolled to now.
+    ///
+    /// MDN Reference: [`Element.scrollBy()`](https://developer.mozilla.org/docs/Web/API/Element/scrollBy)
+    fn scroll_by_xy(&mut self, x: f64, y: f64) {
+        scroll::scroll_by(AsElement::cast(self), x, y, ScrollBehavior::Auto);
     }
+    /// Scrolls the scroll containers that the element is in, and the viewport, so that the element is in view.
+    ///
+    /// Each container is scrolled to align the element as given by the options, within the container's `scroll-padding`, and with the element's `scroll-margin` around it.
+    ///
+    /// MDN Reference: [`Element.scrollIntoView()`](https://developer.mozilla.org/docs/Web/API/Element/scrollIntoView)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, ScrollIntoView, ScrollLogicalPosition};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<div style='height: 100px; overflow: auto'>
+    ///         <div style='height: 300px'></div>
+    ///         <div style='height: 20px'></div>
+    ///         <div style='height: 300px'></div>
+    ///     </div>",
+    /// );
+    /// let html = document.first_element_child().unwrap();
+    /// let body = html.children().item(1).unwrap();
+    /// let list = body.first_element_child().unwrap();
+    /// let mut item = list.children().item(1).unwrap();
+    ///
+    /// item.scroll_into_view(true.into());
+    /// assert_eq!(list.scroll_top(), 300);
+    ///
+    /// item.scroll_into_view(ScrollIntoView {
+    ///     block: ScrollLogicalPosition::Center,
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(list.scroll_top(), 260);
+    /// ```
     fn scroll_into_view(&mut self, arg: ScrollIntoView) {
-        todo!()
+        scroll::scroll_into_view(AsElement::cast(self), arg);
     }
+    /// Scrolls the element to a position. Positions that are left out stay as they are.
+    ///
+    /// The position is snapped to the snap positions given by the `scroll-snap-type` of the element and the `scroll-snap-align` of its descendants, and clamped to the scroll range of the element. Smooth scrolls move over time on the virtual clock, see [`Window::advance_time`](crate::Window::advance_time).
+    ///
+    /// MDN Reference: [`Element.scrollTo()`](https://developer.mozilla.org/docs/Web/API/Element/scrollTo)
     fn scroll_to(&mut self, options: Option<ScrollToOptions>) {
-        todo!()
-    }
-    fn scroll_to_xy(&mut self, x: usize, y: usize) {
-        todo!()
+        let options = options.unwrap_or_default();
+        scroll::scroll_to(
+            AsElement::cast(self),
+            options.left,
+            options.top,
+            options.behavior,
+        );
+    }
+    /// Scrolls the element to a position.
+    ///
+    /// MDN Reference: [`Element.scrollTo()`](https://developer.mozilla.org/docs/Web/API/Element/scrollTo)
+    fn scroll_to_xy(&mut self, x: f64, y: f64) {
+        scroll::scroll_to(
+            AsElement::cast(self),
+            Some(x),
+            Some(y),
+            ScrollBehavior::Auto,
+        );
     }
     fn set_attribute(&mut self, qualified_name: &str, value: &str) {
         let mut attr = self
@@ -721,6 +1298,29 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     fn set_attribute_ns(&mut self, namespace: Option<&str>, qualified_name: &str, value: &str) {
         todo!()
     }
+    /// Replaces the descendants of the element with the nodes parsed from an HTML string. Unlike [`InnerHtml::set_inner_html`], `<template shadowrootmode>` elements in the markup attach shadow roots to their parents.
+    ///
+    /// MDN Reference: [`Element.setHTMLUnsafe()`](https://developer.mozilla.org/docs/Web/API/Element/setHTMLUnsafe)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut container = document.create_element("div");
+    /// container.set_html_unsafe(
+    ///     "<my-card><template shadowrootmode=open shadowrootdelegatesfocus><b>Shadow</b></template></my-card>",
+    /// );
+    ///
+    /// let card = container.first_element_child().unwrap();
+    /// let shadow_root = card.shadow_root().unwrap();
+    /// assert!(shadow_root.delegates_focus());
+    /// assert_eq!(shadow_root.inner_html(), "<b>Shadow</b>");
+    /// // The template itself is not part of the tree.
+    /// assert_eq!(card.child_nodes().len(), 0);
+    /// ```
+    fn set_html_unsafe(&mut self, html: &str) {
+        AsElement::cast_mut(self).replace_with_html(html, true);
+    }
     fn set_attribute_node(&mut self, mut attr: Attr) -> Option<&Attr> {
         self.attributes_mut()
             .set_named_item(attr)
@@ -739,8 +1339,6 @@ pub trait AsElement: AsNode + AsChildNode + AsParentNode + InnerHtml {
     }
     #[deprecated]
     fn webkit_matches_selector(&mut self, selectors: &str) -> bool {
-        todo!()
+        self.matches(selectors)
     }
 }
-
-pub struct ScrollIntoView;
@@ -1,4 +1,6 @@
-use crate::event_target::EventTargetRef;
+use std::rc::Rc;
+
+use crate::{event_target::EventTargetRef, Element, FormData};
 
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub enum EventType {
@@ -7,12 +9,153 @@ pub enum EventType {
     MouseOut,
     FullScreenChange,
     FullScreenError,
+    SlotChange,
+    Change,
+    Scroll,
+    ScrollEnd,
+    Finish,
+    Cancel,
+    Invalid,
+    Submit,
+    FormData,
+    TransitionRun,
+    TransitionStart,
+    TransitionEnd,
+    TransitionCancel,
+    AnimationStart,
+    AnimationIteration,
+    AnimationEnd,
+    AnimationCancel,
 }
 
 /// An event which takes place in the DOM.
-pub struct Event;
+pub struct Event {
+    inner: internal::EventInner,
+}
 pub type DOMHighResTimeStamp = usize;
 
+/// The information that events of some types carry besides what every event has.
+///
+/// # Example
+/// ```
+/// use dom::{traits::*, Document, Event, EventDetail, EventType, Window};
+/// use std::{cell::RefCell, rc::Rc};
+///
+/// let document = Document::parse_html_unsafe(
+///     "<style>div { transition: opacity 1s } .hidden { opacity: 0 }</style><div></div>",
+/// );
+/// let body = document.first_element_child().unwrap().last_element_child().unwrap();
+/// let mut div = body.first_element_child().unwrap();
+/// let mut window = Window::new(document);
+///
+/// let ended = Rc::new(RefCell::new(vec![]));
+/// let record = ended.clone();
+/// let listener = move |event: &mut Event| record.borrow_mut().push(event.detail().clone());
+/// div.add_event_listener(EventType::TransitionEnd, Some(listener.into()), None);
+///
+/// div.class_list_mut().add("hidden").unwrap();
+/// window.advance_time(1000.0);
+/// assert_eq!(
+///     *ended.borrow(),
+///     [EventDetail::Transition {
+///         property_name: String::from("opacity"),
+///         elapsed_time: 1.0,
+///         pseudo_element: String::new(),
+///     }]
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum EventDetail {
+    #[default]
+    None,
+    /// The detail of the `animationstart`, `animationiteration`, `animationend` and `animationcancel` events of CSS animations. The elapsed time is in seconds, and does not include the delay.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/AnimationEvent)
+    Animation {
+        animation_name: String,
+        elapsed_time: f64,
+        pseudo_element: String,
+    },
+    /// The detail of the `transitionrun`, `transitionstart`, `transitionend` and `transitioncancel` events of CSS transitions. The elapsed time is in seconds, and does not include the delay.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TransitionEvent)
+    Transition {
+        property_name: String,
+        elapsed_time: f64,
+        pseudo_element: String,
+    },
+    /// The detail of the `submit` event of forms: the submit button that submitted the form, if a button did.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/SubmitEvent)
+    Submit { submitter: Option<Rc<Element>> },
+    /// The detail of the `formdata` event of forms: the entries that the form submits. Entries that listeners add to it are submitted too.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/FormDataEvent)
+    FormData { form_data: FormData },
+}
+
+/// The options used when constructing an [`Event`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EventInit {
+    pub bubbles: bool,
+    pub cancelable: bool,
+    pub composed: bool,
+}
+
+impl Event {
+    /// Creates a new event of the given type.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Event/Event)
+    #[allow(deprecated)]
+    pub fn new(type_: impl Into<EventType>, event_init: Option<EventInit>) -> Self {
+        let event_init = event_init.unwrap_or_default();
+        Self {
+            inner: internal::EventInner {
+                type_: type_.into(),
+                bubbles: event_init.bubbles,
+                cancel_bubble: false,
+                cancelable: event_init.cancelable,
+                composed: event_init.composed,
+                current_target: None,
+                default_prevented: false,
+                event_phase: EventPhase::None,
+                is_trusted: false,
+                return_value: true,
+                src_element: None,
+                target: None,
+                time_stamp: 0,
+                stop_propagation_flag: false,
+                stop_immediate_propagation_flag: false,
+                in_passive_listener_flag: false,
+                detail: EventDetail::None,
+                path: vec![],
+            },
+        }
+    }
+    /// Creates an event that is dispatched by the user agent, i.e. with [`AsEvent::is_trusted`] set.
+    pub(crate) fn trusted(type_: EventType, event_init: EventInit) -> Self {
+        let mut event = Self::new(type_, Some(event_init));
+        event.inner.is_trusted = true;
+        event
+    }
+    /// Sets the information that the event carries for its type.
+    pub(crate) fn with_detail(mut self, detail: EventDetail) -> Self {
+        self.inner.detail = detail;
+        self
+    }
+}
+
+impl internal::AsEventInner for Event {
+    fn z_as_event(&self) -> &internal::EventInner {
+        &self.inner
+    }
+
+    fn z_as_event_mut(&mut self) -> &mut internal::EventInner {
+        &mut self.inner
+    }
+}
+impl AsEvent for Event {}
+
 #[allow(deprecated)]
 pub trait AsEvent: internal::AsEventInner {
     /// Returns true or false depending on how event was initialized. True if event goes through its target's ancestors in reverse tree order, and false otherwise.
@@ -87,9 +230,69 @@ pub trait AsEvent: internal::AsEventInner {
     fn type_(&self) -> &EventType {
         &self.z_as_event().type_
     }
+    /// Returns the information that the event carries for its type, like the property of a `transitionend` event.
+    fn detail(&self) -> &EventDetail {
+        &self.z_as_event().detail
+    }
     /// Returns the invocation target objects of event's path (objects on which listeners will be invoked), except for any nodes in shadow trees of which the shadow root's mode is "closed" that are not reachable from event's currentTarget.
+    ///
+    /// The path is empty when the event is not being dispatched.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#dom-event-composedpath)
     fn composed_path(&self) -> Vec<&EventTargetRef> {
-        todo!()
+        let event = self.z_as_event();
+        let path = &event.path;
+        let Some(current_target) = event.current_target.as_ref() else {
+            return vec![];
+        };
+        if path.is_empty() {
+            return vec![];
+        }
+        // The closed trees that the current target is in are hidden levels below the other nodes, and nodes in closed trees deeper than it are left out.
+        let mut current_target_index = 0;
+        let mut current_target_hidden_subtree_level = 0;
+        for (index, entry) in path.iter().enumerate().rev() {
+            if entry.root_of_closed_tree {
+                current_target_hidden_subtree_level += 1;
+            }
+            if entry.invocation_target == *current_target {
+                current_target_index = index;
+                break;
+            }
+            if entry.slot_in_closed_tree {
+                current_target_hidden_subtree_level -= 1;
+            }
+        }
+        let mut composed_path = vec![current_target];
+        let mut current_hidden_level = current_target_hidden_subtree_level;
+        let mut max_hidden_level = current_target_hidden_subtree_level;
+        for entry in path[..current_target_index].iter().rev() {
+            if entry.root_of_closed_tree {
+                current_hidden_level += 1;
+            }
+            if current_hidden_level <= max_hidden_level {
+                composed_path.insert(0, &entry.invocation_target);
+            }
+            if entry.slot_in_closed_tree {
+                current_hidden_level -= 1;
+                max_hidden_level = max_hidden_level.min(current_hidden_level);
+            }
+        }
+        current_hidden_level = current_target_hidden_subtree_level;
+        max_hidden_level = current_target_hidden_subtree_level;
+        for entry in &path[current_target_index + 1..] {
+            if entry.slot_in_closed_tree {
+                current_hidden_level += 1;
+            }
+            if current_hidden_level <= max_hidden_level {
+                composed_path.push(&entry.invocation_target);
+            }
+            if entry.root_of_closed_tree {
+                current_hidden_level -= 1;
+                max_hidden_level = max_hidden_level.min(current_hidden_level);
+            }
+        }
+        composed_path
     }
     #[deprecated]
     fn init_event(&mut self, r#type: EventType, bubbles: bool, cancelable: bool) {
@@ -97,19 +300,24 @@ pub trait AsEvent: internal::AsEventInner {
     }
     /// If invoked when the cancelable attribute value is true, and while executing a listener for the event with passive set to false, signals to the operation that caused event to be dispatched that it needs to be canceled.
     fn prevent_default(&mut self) {
-        todo!()
+        let event = self.z_as_event_mut();
+        if event.cancelable && !event.in_passive_listener_flag {
+            event.default_prevented = true;
+        }
     }
     /// Invoking this method prevents event from reaching any registered event listeners after the current one finishes running and, when dispatched in a tree, also prevents event from reaching any other objects.
     fn stop_immediate_propagation(&mut self) {
-        todo!()
+        let event = self.z_as_event_mut();
+        event.stop_propagation_flag = true;
+        event.stop_immediate_propagation_flag = true;
     }
     /// When dispatched in a tree, invoking this method prevents event from reaching any objects other than the current object.
     fn stop_propagation(&mut self) {
-        todo!()
+        self.z_as_event_mut().stop_propagation_flag = true;
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq)]
 pub enum EventPhase {
     None = 0,
     CapturingPhase = 1,
@@ -119,7 +327,7 @@ pub enum EventPhase {
 
 #[doc(hidden)]
 pub(crate) mod internal {
-    use super::{DOMHighResTimeStamp, EventPhase, EventType};
+    use super::{DOMHighResTimeStamp, EventDetail, EventPhase, EventType};
     use crate::event::EventTargetRef;
     pub struct EventInner {
         pub type_: EventType,
@@ -137,6 +345,24 @@ pub(crate) mod internal {
         pub src_element: Option<EventTargetRef>,
         pub target: Option<EventTargetRef>,
         pub time_stamp: DOMHighResTimeStamp,
+        pub stop_propagation_flag: bool,
+        pub stop_immediate_propagation_flag: bool,
+        pub in_passive_listener_flag: bool,
+        pub detail: EventDetail,
+        /// The targets that the event is dispatched through, from the target outwards.
+        pub path: Vec<PathEntry>,
+    }
+    /// An entry in the path of an event.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#concept-event-path)
+    pub struct PathEntry {
+        pub invocation_target: EventTargetRef,
+        /// The target as seen from the invocation target, if the invocation target is the target or a shadow host it is retargeted to.
+        pub shadow_adjusted_target: Option<EventTargetRef>,
+        /// True if the invocation target is a closed shadow root.
+        pub root_of_closed_tree: bool,
+        /// True if the invocation target is a slot in a closed shadow tree, that the previous entry is assigned to.
+        pub slot_in_closed_tree: bool,
     }
     pub trait AsEventInner {
         /// Convert to a reference to event.
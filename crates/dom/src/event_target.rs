@@ -1,6 +1,9 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{collections::HashMap, rc::Rc};
 
-use crate::{event::EventType, AddEventListenerOptionsOrBoolean};
+use crate::{
+    event::EventType, node::WeakNodeRef, AddEventListenerOptionsOrBoolean, AsEvent, EventPhase,
+    Node,
+};
 
 use super::Event;
 
@@ -12,30 +15,72 @@ impl EventListenerObject {
     }
 }
 
+/// A callback that is invoked when an event of the type it listens for is dispatched.
+#[derive(Clone)]
+pub struct EventListener {
+    callback: Rc<dyn Fn(&mut Event)>,
+}
+
+impl EventListener {
+    /// Wraps a closure as an event listener.
+    pub fn new(callback: impl Fn(&mut Event) + 'static) -> Self {
+        Self {
+            callback: Rc::new(callback),
+        }
+    }
+}
+
+impl std::fmt::Debug for EventListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventListener").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for EventListener {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.callback, &other.callback)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum EventListenerOrEventListenerObject {
-    EventListener(()),
+    EventListener(EventListener),
     EventListenerObject(EventListenerObject),
 }
 
-pub type EventTargetRef = Rc<RefCell<EventTarget>>;
+impl<F: Fn(&mut Event) + 'static> From<F> for EventListenerOrEventListenerObject {
+    fn from(callback: F) -> Self {
+        Self::EventListener(EventListener::new(callback))
+    }
+}
+
+/// The object an event is dispatched to.
+pub type EventTargetRef = Node;
 
-#[derive(Debug, Clone, PartialEq)]
+type ListenerEntry = (
+    Option<EventListenerOrEventListenerObject>,
+    Option<AddEventListenerOptionsOrBoolean>,
+);
+
+#[derive(Debug, Clone)]
 /// EventTarget is a DOM interface implemented by objects that can receive events and may have listeners for them.
 pub struct EventTarget {
-    pub listeners: HashMap<
-        EventType,
-        Vec<(
-            Option<EventListenerOrEventListenerObject>,
-            Option<AddEventListenerOptionsOrBoolean>,
-        )>,
-    >,
+    pub listeners: HashMap<EventType, Vec<ListenerEntry>>,
+    /// The node this event target belongs to, if any.
+    pub(crate) owner: Option<WeakNodeRef>,
+}
+
+impl PartialEq for EventTarget {
+    fn eq(&self, other: &Self) -> bool {
+        self.listeners == other.listeners
+    }
 }
 
 impl EventTarget {
     pub fn new() -> Self {
         Self {
             listeners: HashMap::new(),
+            owner: None,
         }
     }
 }
@@ -61,13 +106,13 @@ pub trait AsEventTarget: Sized {
     /// The options argument sets listener-specific options. For compatibility this can be a boolean, in which case the method behaves exactly as if the value was specified as options's capture.
     ///
     /// When set to true, options's capture prevents callback from being invoked when the event's eventPhase attribute value is BUBBLING_PHASE. When false (or not present), callback will not be invoked when event's eventPhase attribute value is CAPTURING_PHASE. Either way, callback will be invoked if event's eventPhase attribute value is AT_TARGET.
-    ///  
+    ///
     /// When set to true, options's passive indicates that the callback will not cancel the event by invoking preventDefault(). This is used to enable performance optimizations described in § 2.8 Observing event listeners.
-    ///  
+    ///
     /// When set to true, options's once indicates that the callback will only be invoked once after which the event listener will be removed.
-    ///  
+    ///
     /// If an AbortSignal is passed for options's signal, then the event listener will be removed when signal is aborted.
-    ///  
+    ///
     /// The event listener is appended to target's event listener list and is not appended if it has the same type, callback, and capture.
     fn add_event_listener(
         &mut self,
@@ -80,6 +125,12 @@ pub trait AsEventTarget: Sized {
 
         match target.listeners.get_mut(&type_) {
             Some(listeners) => {
+                let capture = helpers::capture(&options);
+                if listeners.iter().any(|(other, other_options)| {
+                    *other == callback && helpers::capture(other_options) == capture
+                }) {
+                    return;
+                }
                 listeners.push((callback, options));
             }
             None => {
@@ -88,8 +139,45 @@ pub trait AsEventTarget: Sized {
         };
     }
     /// Dispatches a synthetic event event to target and returns true if either event's cancelable attribute value is false or its preventDefault() method was not invoked, and false otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, Event, EventInit, EventType};
+    /// use std::{cell::Cell, rc::Rc};
+    ///
+    /// let document = Document::new();
+    /// let mut parent = document.create_element("div");
+    /// let mut child = document.create_element("span");
+    /// parent.append_child(&mut child).unwrap();
+    ///
+    /// let calls = Rc::new(Cell::new(0));
+    /// let counter = calls.clone();
+    /// parent.add_event_listener(
+    ///     EventType::Click,
+    ///     Some((move |_: &mut Event| counter.set(counter.get() + 1)).into()),
+    ///     None,
+    /// );
+    ///
+    /// let init = EventInit { bubbles: true, ..Default::default() };
+    /// child.dispatch_event(&mut Event::new(EventType::Click, Some(init)));
+    /// assert_eq!(calls.get(), 1);
+    /// ```
     fn dispatch_event(&mut self, event: &mut Event) -> bool {
-        false
+        let owner = self
+            .cast()
+            .owner
+            .as_ref()
+            .and_then(|weak_ref| weak_ref.inner.upgrade());
+        match owner {
+            Some(base) => helpers::dispatch(&Node { base }, event),
+            None => {
+                helpers::invoke(self.cast_mut(), event, EventPhase::AtTarget, true);
+                helpers::invoke(self.cast_mut(), event, EventPhase::AtTarget, false);
+                internal_event(event).event_phase = EventPhase::None;
+                helpers::reset_flags(event);
+                !event.default_prevented()
+            }
+        }
     }
     /// Removes the event listener in target's event listener list with the same type, callback, and options.
     fn remove_event_listener(
@@ -98,6 +186,235 @@ pub trait AsEventTarget: Sized {
         callback: Option<EventListenerOrEventListenerObject>,
         options: Option<AddEventListenerOptionsOrBoolean>,
     ) {
-        todo!()
+        let capture = helpers::capture(&options);
+        if let Some(listeners) = self.cast_mut().listeners.get_mut(&type_.into()) {
+            listeners.retain(|(other, other_options)| {
+                !(*other == callback && helpers::capture(other_options) == capture)
+            });
+        }
+    }
+}
+
+fn internal_event(event: &mut Event) -> &mut crate::event::internal::EventInner {
+    crate::event::internal::AsEventInner::z_as_event_mut(event)
+}
+
+mod helpers {
+    use super::{internal_event, EventListenerOrEventListenerObject, EventTarget, ListenerEntry};
+    use crate::{
+        domitem::DOMItem,
+        event::internal::PathEntry,
+        node::trees::{host_of, is_host_including_inclusive_ancestor, root_of},
+        AddEventListenerOptions, AddEventListenerOptionsOrBoolean, AsEvent, AsEventTarget, AsNode,
+        Event, EventPhase, Node, ShadowRootMode,
+    };
+
+    pub fn capture(options: &Option<AddEventListenerOptionsOrBoolean>) -> bool {
+        match options {
+            Some(AddEventListenerOptionsOrBoolean::Bool(capture)) => *capture,
+            Some(AddEventListenerOptionsOrBoolean::EventListenerOptions(options)) => {
+                options.capture.unwrap_or(false)
+            }
+            None => false,
+        }
+    }
+
+    fn flag(
+        options: &Option<AddEventListenerOptionsOrBoolean>,
+        flag: fn(&AddEventListenerOptions) -> Option<bool>,
+    ) -> bool {
+        match options {
+            Some(AddEventListenerOptionsOrBoolean::EventListenerOptions(options)) => {
+                flag(options).unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the parent of a node in an event path. A shadow root only has its host as parent when the event is composed, or when the event was dispatched from outside of it.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#get-the-parent)
+    fn get_the_parent(node: &Node, event: &Event, target: &Node) -> Option<Node> {
+        if let Some(slot) = crate::slot::assigned_slot_of(node) {
+            return Some(slot);
+        }
+        if let Some(host) = crate::slot::host_of_shadow_root(node) {
+            let is_root_of_target = root_of(target).is_same_node(node);
+            return (event.composed() || !is_root_of_target)
+                .then(|| AsNode::cast(&host).clone_ref());
+        }
+        node.parent_node()
+            .map(|parent| AsNode::cast(&parent).clone_ref())
+    }
+
+    /// Returns the node that stands in for `a` as seen from `b`, which is the shadow host of the tree `a` is in for nodes outside of it.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#retarget)
+    fn retarget(a: &Node, b: &Node) -> Node {
+        let mut a = a.clone_ref();
+        loop {
+            let root = root_of(&a);
+            match host_of(&root) {
+                // Shadow roots are the only document fragments with a host, so a host-including ancestor is a shadow-including one.
+                Some(host) if !is_host_including_inclusive_ancestor(&root, b) => a = host,
+                _ => return a,
+            }
+        }
+    }
+
+    /// Returns true if a node is a shadow root whose mode is closed.
+    fn is_closed_shadow_root(node: &Node) -> bool {
+        crate::slot::host_of_shadow_root(node).is_some_and(|host| {
+            crate::slot::is_shadow_host_and(&host, |root| root.mode() == ShadowRootMode::Closed)
+        })
+    }
+
+    /// Creates the entry of a node in an event path.
+    fn entry(
+        invocation_target: &Node,
+        shadow_adjusted_target: Option<Node>,
+        slot_in_closed_tree: bool,
+    ) -> PathEntry {
+        PathEntry {
+            invocation_target: invocation_target.clone_ref(),
+            shadow_adjusted_target,
+            root_of_closed_tree: is_closed_shadow_root(invocation_target),
+            slot_in_closed_tree,
+        }
+    }
+
+    /// Dispatches an event through the event path of a node. Listeners outside of the shadow trees that the target is in see the event as targeted at the shadow hosts of those trees.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#concept-event-dispatch)
+    pub fn dispatch(target: &Node, event: &mut Event) -> bool {
+        let mut path = vec![entry(target, Some(target.clone_ref()), false)];
+        // The target as seen from the last node in the path that it was retargeted for.
+        let mut adjusted_target = target.clone_ref();
+        let mut slottable = crate::slot::assigned_slot_of(target).map(|_| target.clone_ref());
+        let mut parent = get_the_parent(target, event, target);
+        while let Some(node) = parent {
+            let mut slot_in_closed_tree = false;
+            if slottable.take().is_some() {
+                slot_in_closed_tree = is_closed_shadow_root(AsNode::cast(&root_of(&node)));
+            }
+            if crate::slot::assigned_slot_of(&node).is_some() {
+                slottable = Some(node.clone_ref());
+            }
+            let shadow_adjusted_target =
+                match is_host_including_inclusive_ancestor(&root_of(&adjusted_target), &node) {
+                    true => None,
+                    false => {
+                        adjusted_target = retarget(target, &node);
+                        Some(adjusted_target.clone_ref())
+                    }
+                };
+            path.push(entry(&node, shadow_adjusted_target, slot_in_closed_tree));
+            parent = get_the_parent(&node, event, target);
+        }
+        internal_event(event).path = path;
+
+        for index in (0..internal_event(event).path.len()).rev() {
+            let phase = match internal_event(event).path[index].shadow_adjusted_target {
+                Some(_) => EventPhase::AtTarget,
+                None => EventPhase::CapturingPhase,
+            };
+            invoke_on_entry(index, event, phase, true);
+        }
+        let bubbles = event.bubbles();
+        for index in 0..internal_event(event).path.len() {
+            let phase = match internal_event(event).path[index].shadow_adjusted_target {
+                Some(_) => EventPhase::AtTarget,
+                None if bubbles => EventPhase::BubblingPhase,
+                None => continue,
+            };
+            invoke_on_entry(index, event, phase, false);
+        }
+
+        let event_inner = internal_event(event);
+        // A target in a shadow tree is not exposed after the dispatch.
+        let clear_targets = event_inner
+            .path
+            .iter()
+            .rev()
+            .find_map(|entry| entry.shadow_adjusted_target.as_ref())
+            .is_some_and(|target| crate::slot::is_shadow_root(&root_of(target)));
+        event_inner.event_phase = EventPhase::None;
+        event_inner.current_target = None;
+        event_inner.path.clear();
+        if clear_targets {
+            event_inner.target = None;
+        }
+        reset_flags(event);
+        !event.default_prevented()
+    }
+
+    pub fn reset_flags(event: &mut Event) {
+        let event_inner = internal_event(event);
+        event_inner.stop_propagation_flag = false;
+        event_inner.stop_immediate_propagation_flag = false;
+    }
+
+    /// Invokes the listeners of an entry in the path of an event, with the event targeted at the shadow-adjusted target of the entry or of the closest one before it.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#concept-event-listener-invoke)
+    fn invoke_on_entry(index: usize, event: &mut Event, phase: EventPhase, capturing: bool) {
+        let event_inner = internal_event(event);
+        let target = event_inner.path[..=index]
+            .iter()
+            .rev()
+            .find_map(|entry| entry.shadow_adjusted_target.as_ref())
+            .map(Node::clone_ref);
+        event_inner.target = target;
+        if event_inner.stop_propagation_flag {
+            return;
+        }
+        let mut node = event_inner.path[index].invocation_target.clone_ref();
+        event_inner.current_target = Some(node.clone_ref());
+        invoke(AsEventTarget::cast_mut(&mut node), event, phase, capturing);
+    }
+
+    /// Invokes the capture listeners of a target when `capturing`, and the other listeners otherwise.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#concept-event-listener-inner-invoke)
+    pub fn invoke(target: &mut EventTarget, event: &mut Event, phase: EventPhase, capturing: bool) {
+        internal_event(event).event_phase = phase;
+        let listeners: Vec<ListenerEntry> = match target.listeners.get(event.type_()) {
+            Some(listeners) => listeners.clone(),
+            None => return,
+        };
+        for entry in listeners {
+            // A listener that an earlier listener removed is not called.
+            let is_removed = !target
+                .listeners
+                .get(event.type_())
+                .is_some_and(|listeners| listeners.contains(&entry));
+            if is_removed {
+                continue;
+            }
+            let (callback, options) = &entry;
+            if capture(options) != capturing {
+                continue;
+            }
+            if flag(options, |options| options.once) {
+                if let Some(listeners) = target.listeners.get_mut(event.type_()) {
+                    listeners.retain(|other| *other != entry);
+                }
+            }
+            internal_event(event).in_passive_listener_flag =
+                flag(options, |options| options.passive);
+            match callback {
+                Some(EventListenerOrEventListenerObject::EventListener(listener)) => {
+                    (listener.callback)(event)
+                }
+                Some(EventListenerOrEventListenerObject::EventListenerObject(object)) => {
+                    object.handle_event(event)
+                }
+                None => {}
+            }
+            internal_event(event).in_passive_listener_flag = false;
+            if internal_event(event).stop_immediate_propagation_flag {
+                break;
+            }
+        }
     }
 }
@@ -0,0 +1,150 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The options used when constructing a [`File`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FilePropertyBag {
+    /// The MIME type of the file's contents, like `text/plain`.
+    pub r#type: String,
+    /// When the file was last modified, in milliseconds since the start of 1970 in UTC. The default is the current time.
+    pub last_modified: Option<f64>,
+}
+
+/// A file's contents along with its name, like the files that are selected in `<input type=file>` elements and sent with forms.
+///
+/// MDN Reference: [`File`](https://developer.mozilla.org/docs/Web/API/File)
+#[derive(Debug, Clone, PartialEq)]
+pub struct File {
+    bits: Vec<u8>,
+    name: String,
+    r#type: String,
+    last_modified: f64,
+}
+
+impl File {
+    /// Creates a file from its contents and name.
+    ///
+    /// MDN Reference: [`File()`](https://developer.mozilla.org/docs/Web/API/File/File)
+    /// # Example
+    /// ```
+    /// use dom::{File, FilePropertyBag};
+    ///
+    /// let options = FilePropertyBag {
+    ///     r#type: String::from("Text/Plain"),
+    ///     last_modified: Some(0.0),
+    /// };
+    /// let file = File::new("Hello!", "hello.txt", Some(options));
+    /// assert_eq!(file.name(), "hello.txt");
+    /// assert_eq!(file.r#type(), "text/plain");
+    /// assert_eq!(file.size(), 6);
+    /// assert_eq!(file.text(), "Hello!");
+    /// ```
+    pub fn new(bits: impl Into<Vec<u8>>, name: &str, options: Option<FilePropertyBag>) -> Self {
+        let options = options.unwrap_or_default();
+        // Types with characters outside the printable ASCII range are ignored.
+        let is_printable = options
+            .r#type
+            .bytes()
+            .all(|byte| (0x20..=0x7e).contains(&byte));
+        let r#type = match is_printable {
+            true => options.r#type.to_ascii_lowercase(),
+            false => String::new(),
+        };
+        let last_modified = options.last_modified.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |duration| duration.as_millis() as f64)
+        });
+        File {
+            bits: bits.into(),
+            name: name.to_owned(),
+            r#type,
+            last_modified,
+        }
+    }
+    /// Returns the name of the file, without a path.
+    ///
+    /// MDN Reference: [`File.name`](https://developer.mozilla.org/docs/Web/API/File/name)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the MIME type of the file's contents, or an empty string if it is not known.
+    ///
+    /// MDN Reference: [`Blob.type`](https://developer.mozilla.org/docs/Web/API/Blob/type)
+    pub fn r#type(&self) -> &str {
+        &self.r#type
+    }
+    /// Returns the size of the file in bytes.
+    ///
+    /// MDN Reference: [`Blob.size`](https://developer.mozilla.org/docs/Web/API/Blob/size)
+    pub fn size(&self) -> usize {
+        self.bits.len()
+    }
+    /// Returns when the file was last modified, in milliseconds since the start of 1970 in UTC.
+    ///
+    /// MDN Reference: [`File.lastModified`](https://developer.mozilla.org/docs/Web/API/File/lastModified)
+    pub fn last_modified(&self) -> f64 {
+        self.last_modified
+    }
+    /// Returns the contents of the file.
+    ///
+    /// MDN Reference: [`Blob.bytes()`](https://developer.mozilla.org/docs/Web/API/Blob/bytes)
+    pub fn bytes(&self) -> &[u8] {
+        &self.bits
+    }
+    /// Returns the contents of the file decoded as UTF-8, with invalid sequences replaced.
+    ///
+    /// MDN Reference: [`Blob.text()`](https://developer.mozilla.org/docs/Web/API/Blob/text)
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.bits).into_owned()
+    }
+    /// Returns a copy of the file with another name.
+    pub(crate) fn renamed(&self, name: &str) -> Self {
+        File {
+            name: name.to_owned(),
+            ..self.clone()
+        }
+    }
+}
+
+/// A list of files, like the files that are selected in an `<input type=file>` element.
+///
+/// MDN Reference: [`FileList`](https://developer.mozilla.org/docs/Web/API/FileList)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileList {
+    files: Vec<File>,
+}
+
+impl FileList {
+    /// Returns the number of files in the list.
+    ///
+    /// MDN Reference: [`FileList.length`](https://developer.mozilla.org/docs/Web/API/FileList/length)
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+    /// Returns true if there are no files in the list.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+    /// Returns the file at an index in the list.
+    ///
+    /// MDN Reference: [`FileList.item()`](https://developer.mozilla.org/docs/Web/API/FileList/item)
+    pub fn item(&self, index: usize) -> Option<&File> {
+        self.files.get(index)
+    }
+    /// Returns an iterator over the files in the list.
+    pub fn iter(&self) -> std::slice::Iter<'_, File> {
+        self.files.iter()
+    }
+}
+
+impl From<Vec<File>> for FileList {
+    fn from(files: Vec<File>) -> Self {
+        FileList { files }
+    }
+}
+
+impl From<FileList> for Vec<File> {
+    fn from(list: FileList) -> Self {
+        list.files
+    }
+}
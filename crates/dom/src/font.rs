@@ -0,0 +1,807 @@
+//! Fonts: the metrics that text is measured with, from a [`FontProvider`].
+//!
+//! There are no system fonts to rely on, so the default provider gives every font the same fixed metrics, and fonts are only read from files that are loaded explicitly. Either way, text measures the same on every machine.
+use std::{collections::HashMap, path::Path};
+
+use crate::{css::ComputedStyle, layout::Transform, DOMException};
+
+/// The font that a run of text asks for: a list of families in order of preference, and the size, weight and style to use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontDescription {
+    /// Family names, like `Helvetica Neue`, or generic families, like `sans-serif`, without quotes.
+    pub families: Vec<String>,
+    /// The font size, in pixels.
+    pub size: f64,
+    /// The weight, from 1 to 1000, where 400 is normal and 700 is bold.
+    pub weight: u16,
+    /// Whether the font is italic or oblique.
+    pub italic: bool,
+}
+
+impl FontDescription {
+    /// Describes the font of a computed style.
+    pub(crate) fn of(style: &ComputedStyle) -> Self {
+        FontDescription {
+            families: parse_families(style.get("font-family")),
+            size: style.font_size(),
+            weight: style.get("font-weight").parse().unwrap_or(400),
+            italic: matches!(
+                style.get("font-style").split(' ').next(),
+                Some("italic" | "oblique")
+            ),
+        }
+    }
+}
+
+impl Default for FontDescription {
+    fn default() -> Self {
+        FontDescription {
+            families: vec![String::from("serif")],
+            size: 16.0,
+            weight: 400,
+            italic: false,
+        }
+    }
+}
+
+/// Splits a `font-family` value into its family names, without quotes.
+fn parse_families(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|family| family.trim().trim_matches(['"', '\'']).to_owned())
+        .filter(|family| !family.is_empty())
+        .collect()
+}
+
+/// The vertical metrics of a font at a size, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    /// How far glyphs reach above the baseline.
+    pub ascent: f64,
+    /// How far glyphs reach below the baseline, as a positive distance.
+    pub descent: f64,
+    /// The extra space the font asks for between lines.
+    pub line_gap: f64,
+}
+
+impl FontMetrics {
+    /// Returns the height of the content area of an inline box that uses the font.
+    pub fn height(&self) -> f64 {
+        self.ascent + self.descent
+    }
+    /// Returns the height of a line when `line-height` is `normal`.
+    pub fn normal_line_height(&self) -> f64 {
+        self.ascent + self.descent + self.line_gap
+    }
+}
+
+/// A source of font metrics, used by layout and by [`CanvasRenderingContext2D::measure_text`] to measure text.
+///
+/// A provider must be deterministic: the same description and text always measure the same.
+///
+/// [`CanvasRenderingContext2D::measure_text`]: crate::CanvasRenderingContext2D::measure_text
+///
+/// # Example
+/// ```
+/// use dom::{traits::*, Document, FontDescription, FontMetrics, FontProvider};
+///
+/// // Every character is as wide as the font is tall.
+/// struct Squares;
+///
+/// impl FontProvider for Squares {
+///     fn metrics(&self, font: &FontDescription) -> FontMetrics {
+///         FontMetrics { ascent: font.size, descent: 0.0, line_gap: 0.0 }
+///     }
+///     fn advance(&self, font: &FontDescription, _: char) -> f64 {
+///         font.size
+///     }
+/// }
+///
+/// let mut document = Document::parse_html_unsafe("<p style='margin: 0'><span class=word>abc</span>");
+/// document.set_font_provider(Squares);
+/// let html = document.first_element_child().unwrap();
+/// let word = html.get_elements_by_class_name("word");
+/// let span = word.item(0).unwrap().get_bounding_client_rect();
+/// assert_eq!((span.width(), span.height()), (48.0, 16.0));
+/// ```
+pub trait FontProvider {
+    /// Returns the vertical metrics of the font used for a description.
+    fn metrics(&self, font: &FontDescription) -> FontMetrics;
+    /// Returns how far a character moves the pen along the line, in pixels.
+    fn advance(&self, font: &FontDescription, character: char) -> f64;
+    /// Returns the width of a run of text, in pixels. By default, this is the sum of the advances of its characters.
+    fn text_width(&self, font: &FontDescription, text: &str) -> f64 {
+        text.chars()
+            .map(|character| self.advance(font, character))
+            .sum()
+    }
+    /// Returns the outline that a character is painted with, as closed contours of points in pixels, relative to the pen position on the baseline, with y pointing down.
+    ///
+    /// By default, characters have no outlines, and painting draws a box in their place.
+    fn outline(&self, font: &FontDescription, character: char) -> Option<Vec<Vec<(f64, f64)>>> {
+        None
+    }
+}
+
+/// The default provider, which gives every font the same fixed metrics.
+///
+/// Glyphs are half an em wide, or six tenths of an em in monospace fonts, with wide characters like CJK ideographs a full em wide. The ascent is eight tenths of an em and the descent two tenths, with a line gap of two tenths, so `normal` lines are 1.2 em tall.
+///
+/// # Example
+/// ```
+/// use dom::{FixedFontProvider, FontDescription, FontProvider};
+///
+/// let font = FontDescription { size: 10.0, ..Default::default() };
+/// assert_eq!(FixedFontProvider.text_width(&font, "abc"), 15.0);
+/// assert_eq!(FixedFontProvider.metrics(&font).normal_line_height(), 12.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedFontProvider;
+
+impl FontProvider for FixedFontProvider {
+    fn metrics(&self, font: &FontDescription) -> FontMetrics {
+        FontMetrics {
+            ascent: font.size * 0.8,
+            descent: font.size * 0.2,
+            line_gap: font.size * 0.2,
+        }
+    }
+
+    fn advance(&self, font: &FontDescription, character: char) -> f64 {
+        let is_monospace = font
+            .families
+            .first()
+            .is_some_and(|family| family == "monospace");
+        match (is_wide(character), is_monospace) {
+            (true, _) => font.size,
+            (false, true) => font.size * 0.6,
+            (false, false) => font.size * 0.5,
+        }
+    }
+}
+
+/// Returns true for characters that take up a full em, like CJK ideographs and fullwidth forms.
+pub(crate) fn is_wide(char: char) -> bool {
+    matches!(char as u32,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x20000..=0x3FFFD)
+}
+
+/// A TrueType or OpenType font, read from the tables of a font file.
+///
+/// Only what is needed to measure text is read: the family, weight and style, the vertical metrics, the advance of each glyph, and the mapping of characters to glyphs. Font collections are read from their first font.
+///
+/// [Reference](https://learn.microsoft.com/typography/opentype/spec/otff)
+#[derive(Debug, Clone)]
+pub struct TrueTypeFont {
+    data: Vec<u8>,
+    family: String,
+    weight: u16,
+    italic: bool,
+    units_per_em: f64,
+    ascent: f64,
+    descent: f64,
+    line_gap: f64,
+    advances: Vec<u16>,
+    cmap: Option<CharacterMap>,
+    outlines: Option<Outlines>,
+}
+
+/// Where the outlines of the glyphs of a TrueType font are: the offsets of its `loca` and `glyf` tables, and whether `loca` holds 32-bit offsets.
+#[derive(Debug, Clone, Copy)]
+struct Outlines {
+    loca: usize,
+    glyf: usize,
+    long_offsets: bool,
+}
+
+/// The subtable of a `cmap` table that maps characters to glyphs, by its format and its offset in the font file.
+#[derive(Debug, Clone, Copy)]
+enum CharacterMap {
+    SegmentToDelta(usize),
+    SegmentedCoverage(usize),
+}
+
+/// Reads big-endian numbers from the bytes of a font file.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.data.get(offset..offset + 2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn i16(&self, offset: usize) -> Option<i16> {
+        self.u16(offset).map(|value| value as i16)
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.data.get(offset..offset + 4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+impl TrueTypeFont {
+    /// Reads a font from the bytes of a TrueType (`.ttf`), OpenType (`.otf`) or font collection (`.ttc`) file.
+    ///
+    /// Returns a `NotSupportedError` if the data is not a font, or lacks a table needed to measure text.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, DOMException> {
+        let invalid =
+            || DOMException::NotSupportedError(String::from("The data is not a valid font"));
+        let reader = Reader { data: &data };
+        let start = match reader.u32(0).ok_or_else(invalid)? {
+            // A collection starts with the offsets of its fonts.
+            0x7474_6366 => reader.u32(12).ok_or_else(invalid)? as usize,
+            0x0001_0000 | 0x4F54_544F | 0x7472_7565 => 0,
+            _ => return Err(invalid()),
+        };
+        let count = reader.u16(start + 4).ok_or_else(invalid)? as usize;
+        let mut tables = HashMap::new();
+        for index in 0..count {
+            let record = start + 12 + index * 16;
+            let tag = data.get(record..record + 4).ok_or_else(invalid)?;
+            let offset = reader.u32(record + 8).ok_or_else(invalid)? as usize;
+            tables.insert(tag.to_vec(), offset);
+        }
+        let table = |tag: &[u8]| tables.get(tag).copied().ok_or_else(invalid);
+        let head = table(b"head")?;
+        let hhea = table(b"hhea")?;
+        let hmtx = table(b"hmtx")?;
+        let units_per_em = reader
+            .u16(head + 18)
+            .filter(|units| *units > 0)
+            .ok_or_else(invalid)? as f64;
+        let mac_style = reader.u16(head + 44).unwrap_or(0);
+        let metric = |offset| reader.i16(offset).map(f64::from).ok_or_else(invalid);
+        let (mut ascent, mut descent, mut line_gap) =
+            (metric(hhea + 4)?, -metric(hhea + 6)?, metric(hhea + 8)?);
+        let mut weight = if mac_style & 1 != 0 { 700 } else { 400 };
+        let mut italic = mac_style & 2 != 0;
+        if let Ok(os2) = table(b"OS/2") {
+            weight = reader
+                .u16(os2 + 4)
+                .filter(|weight| *weight > 0)
+                .unwrap_or(weight);
+            let selection = reader.u16(os2 + 62).unwrap_or(0);
+            italic |= selection & 1 != 0;
+            // Fonts can ask for their typographic metrics to be used instead.
+            if selection & (1 << 7) != 0 {
+                ascent = metric(os2 + 68)?;
+                descent = -metric(os2 + 70)?;
+                line_gap = metric(os2 + 72)?;
+            }
+        }
+        let metrics_count = reader.u16(hhea + 34).ok_or_else(invalid)? as usize;
+        let advances = (0..metrics_count)
+            .map(|glyph| reader.u16(hmtx + glyph * 4).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let cmap = table(b"cmap")
+            .ok()
+            .and_then(|cmap| find_character_map(&reader, cmap));
+        let family = table(b"name")
+            .ok()
+            .and_then(|name| read_family(&reader, name))
+            .unwrap_or_default();
+        // Fonts with PostScript outlines have a `CFF` table instead, which is not read.
+        let outlines = match (table(b"loca"), table(b"glyf")) {
+            (Ok(loca), Ok(glyf)) => Some(Outlines {
+                loca,
+                glyf,
+                long_offsets: reader.i16(head + 50) == Some(1),
+            }),
+            _ => None,
+        };
+        Ok(TrueTypeFont {
+            data,
+            family,
+            weight,
+            italic,
+            units_per_em,
+            ascent,
+            descent,
+            line_gap,
+            advances,
+            cmap,
+            outlines,
+        })
+    }
+    /// Reads a font from a file.
+    ///
+    /// Returns a `NotFoundError` if the file cannot be read, and a `NotSupportedError` if it is not a font.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DOMException> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|error| {
+            DOMException::NotFoundError(format!("Cannot read {}: {error}", path.display()))
+        })?;
+        TrueTypeFont::from_bytes(data)
+    }
+    /// Returns the family name of the font, like `DejaVu Sans`.
+    pub fn family(&self) -> &str {
+        &self.family
+    }
+    /// Returns the weight of the font, where 400 is normal and 700 is bold.
+    pub fn weight(&self) -> u16 {
+        self.weight
+    }
+    /// Returns true if the font is italic or oblique.
+    pub fn is_italic(&self) -> bool {
+        self.italic
+    }
+    /// Returns the vertical metrics of the font at a size, in pixels.
+    pub fn metrics(&self, size: f64) -> FontMetrics {
+        let scale = size / self.units_per_em;
+        FontMetrics {
+            ascent: self.ascent * scale,
+            descent: self.descent * scale,
+            line_gap: self.line_gap * scale,
+        }
+    }
+    /// Returns the glyph for a character, or `None` if the font does not have one.
+    pub fn glyph(&self, character: char) -> Option<u16> {
+        let reader = Reader { data: &self.data };
+        let code = character as u32;
+        let glyph = match self.cmap? {
+            CharacterMap::SegmentToDelta(offset) => {
+                let code = u16::try_from(code).ok()?;
+                let segments = reader.u16(offset + 6)? as usize / 2;
+                let ends = offset + 14;
+                let starts = ends + segments * 2 + 2;
+                let deltas = starts + segments * 2;
+                let range_offsets = deltas + segments * 2;
+                let segment = (0..segments).find(|segment| {
+                    reader
+                        .u16(ends + segment * 2)
+                        .is_some_and(|end| end >= code)
+                })?;
+                let start = reader.u16(starts + segment * 2)?;
+                if start > code {
+                    return None;
+                }
+                let delta = reader.u16(deltas + segment * 2)?;
+                let range_offset = reader.u16(range_offsets + segment * 2)? as usize;
+                match range_offset {
+                    0 => code.wrapping_add(delta),
+                    _ => {
+                        let address = range_offsets
+                            + segment * 2
+                            + range_offset
+                            + (code - start) as usize * 2;
+                        match reader.u16(address)? {
+                            0 => 0,
+                            glyph => glyph.wrapping_add(delta),
+                        }
+                    }
+                }
+            }
+            CharacterMap::SegmentedCoverage(offset) => {
+                // The number of groups comes from the font, so no more are read than fit in the subtable.
+                let length = (reader.u32(offset + 4)? as usize).min(self.data.len() - offset);
+                let groups =
+                    (reader.u32(offset + 12)? as usize).min(length.saturating_sub(16) / 12);
+                let (start, _, glyph) = (0..groups)
+                    .map_while(|group| {
+                        let group = offset + 16 + group * 12;
+                        Some((
+                            reader.u32(group)?,
+                            reader.u32(group + 4)?,
+                            reader.u32(group + 8)?,
+                        ))
+                    })
+                    .find(|(start, end, _)| (*start..=*end).contains(&code))?;
+                u16::try_from(glyph.checked_add(code - start)?).ok()?
+            }
+        };
+        (glyph != 0).then_some(glyph)
+    }
+    /// Returns how far a glyph moves the pen at a size, in pixels.
+    pub fn advance(&self, glyph: u16, size: f64) -> f64 {
+        // Glyphs after the last metric share its advance.
+        let advance = self
+            .advances
+            .get(glyph as usize)
+            .or(self.advances.last())
+            .copied()
+            .unwrap_or(0);
+        advance as f64 * size / self.units_per_em
+    }
+    /// Returns the outline of a glyph at a size, as closed contours of points in pixels, relative to the origin of the glyph on the baseline, with y pointing down. Curves are flattened into straight segments.
+    ///
+    /// Returns `None` if the font does not have TrueType outlines, like fonts with PostScript outlines, or if the glyph cannot be read.
+    ///
+    /// [Reference](https://learn.microsoft.com/typography/opentype/spec/glyf)
+    pub fn outline(&self, glyph: u16, size: f64) -> Option<Vec<Vec<(f64, f64)>>> {
+        let mut contours = vec![];
+        self.read_outline(glyph, &Transform::IDENTITY, &mut contours, 0)?;
+        let scale = size / self.units_per_em;
+        Some(
+            contours
+                .into_iter()
+                .map(|contour| {
+                    contour
+                        .into_iter()
+                        .map(|(x, y)| (x * scale, -y * scale))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Reads the contours of a glyph in font units, transformed for a component of a composite glyph.
+    fn read_outline(
+        &self,
+        glyph: u16,
+        transform: &Transform,
+        contours: &mut Vec<Vec<(f64, f64)>>,
+        depth: usize,
+    ) -> Option<()> {
+        // Composite glyphs can refer to each other, so their depth is limited.
+        if depth > 8 {
+            return None;
+        }
+        let outlines = self.outlines?;
+        let reader = Reader { data: &self.data };
+        let glyph = glyph as usize;
+        let (start, end) = match outlines.long_offsets {
+            true => (
+                reader.u32(outlines.loca + glyph * 4)? as usize,
+                reader.u32(outlines.loca + glyph * 4 + 4)? as usize,
+            ),
+            false => (
+                reader.u16(outlines.loca + glyph * 2)? as usize * 2,
+                reader.u16(outlines.loca + glyph * 2 + 2)? as usize * 2,
+            ),
+        };
+        // Glyphs without contours, like the space, have no data.
+        if end <= start {
+            return Some(());
+        }
+        let offset = outlines.glyf + start;
+        let contour_count = reader.i16(offset)?;
+        if contour_count < 0 {
+            return self.read_composite(offset + 10, transform, contours, depth);
+        }
+        let ends = (0..contour_count as usize)
+            .map(|index| reader.u16(offset + 10 + index * 2).map(usize::from))
+            .collect::<Option<Vec<_>>>()?;
+        let point_count = ends.last().map_or(0, |end| end + 1);
+        let instructions = offset + 10 + ends.len() * 2;
+        let mut position = instructions + 2 + reader.u16(instructions)? as usize;
+        let mut flags = Vec::with_capacity(point_count);
+        while flags.len() < point_count {
+            let flag = *self.data.get(position)?;
+            position += 1;
+            let mut repeat = 1;
+            if flag & 8 != 0 {
+                repeat += *self.data.get(position)? as usize;
+                position += 1;
+            }
+            flags.extend(std::iter::repeat_n(flag, repeat));
+        }
+        flags.truncate(point_count);
+        // Coordinates are deltas from the previous point, as bytes with a sign flag or as 16-bit numbers.
+        let mut read_coordinates = |short: u8, same_or_positive: u8| -> Option<Vec<f64>> {
+            let mut value = 0i32;
+            let mut coordinates = Vec::with_capacity(point_count);
+            for flag in &flags {
+                if flag & short != 0 {
+                    let delta = *self.data.get(position)? as i32;
+                    position += 1;
+                    value += if flag & same_or_positive != 0 {
+                        delta
+                    } else {
+                        -delta
+                    };
+                } else if flag & same_or_positive == 0 {
+                    value += reader.i16(position)? as i32;
+                    position += 2;
+                }
+                coordinates.push(value as f64);
+            }
+            Some(coordinates)
+        };
+        let xs = read_coordinates(2, 16)?;
+        let ys = read_coordinates(4, 32)?;
+        let mut first = 0;
+        for end in ends {
+            let points: Vec<((f64, f64), bool)> = (first..=end.min(point_count - 1))
+                .map(|index| {
+                    (
+                        transform.apply((xs[index], ys[index])),
+                        flags[index] & 1 != 0,
+                    )
+                })
+                .collect();
+            first = end + 1;
+            if !points.is_empty() {
+                contours.push(flatten_contour(&points));
+            }
+        }
+        Some(())
+    }
+
+    /// Reads the components of a composite glyph, each of which is another glyph, moved and scaled.
+    fn read_composite(
+        &self,
+        mut position: usize,
+        transform: &Transform,
+        contours: &mut Vec<Vec<(f64, f64)>>,
+        depth: usize,
+    ) -> Option<()> {
+        const ARGS_ARE_WORDS: u16 = 1;
+        const ARGS_ARE_OFFSETS: u16 = 2;
+        const HAS_SCALE: u16 = 8;
+        const MORE_COMPONENTS: u16 = 32;
+        const HAS_X_AND_Y_SCALE: u16 = 64;
+        const HAS_TWO_BY_TWO: u16 = 128;
+        let reader = Reader { data: &self.data };
+        let fixed = |position: usize| reader.i16(position).map(|value| value as f64 / 16384.0);
+        loop {
+            let flags = reader.u16(position)?;
+            let glyph = reader.u16(position + 2)?;
+            position += 4;
+            let (x, y) = match flags & ARGS_ARE_WORDS != 0 {
+                true => {
+                    position += 4;
+                    (reader.i16(position - 4)?, reader.i16(position - 2)?)
+                }
+                false => {
+                    position += 2;
+                    (
+                        *self.data.get(position - 2)? as i8 as i16,
+                        *self.data.get(position - 1)? as i8 as i16,
+                    )
+                }
+            };
+            // Components can also be placed by matching points, which is not supported, so they are not moved.
+            let (x, y) = match flags & ARGS_ARE_OFFSETS != 0 {
+                true => (x as f64, y as f64),
+                false => (0.0, 0.0),
+            };
+            let (mut a, mut b, mut c, mut d) = (1.0, 0.0, 0.0, 1.0);
+            if flags & HAS_SCALE != 0 {
+                a = fixed(position)?;
+                d = a;
+                position += 2;
+            } else if flags & HAS_X_AND_Y_SCALE != 0 {
+                a = fixed(position)?;
+                d = fixed(position + 2)?;
+                position += 4;
+            } else if flags & HAS_TWO_BY_TWO != 0 {
+                a = fixed(position)?;
+                b = fixed(position + 2)?;
+                c = fixed(position + 4)?;
+                d = fixed(position + 6)?;
+                position += 8;
+            }
+            let component = Transform {
+                a,
+                b,
+                c,
+                d,
+                e: x,
+                f: y,
+            };
+            self.read_outline(glyph, &transform.multiply(&component), contours, depth + 1)?;
+            if flags & MORE_COMPONENTS == 0 {
+                return Some(());
+            }
+        }
+    }
+}
+
+/// Turns the points of a contour, which are on or off the curve, into a polygon, with the quadratic curves between them flattened.
+///
+/// Two points off the curve in a row have an implied point on the curve halfway between them.
+fn flatten_contour(points: &[((f64, f64), bool)]) -> Vec<(f64, f64)> {
+    const STEPS: usize = 8;
+    let midpoint = |(x1, y1): (f64, f64), (x2, y2): (f64, f64)| ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+    let count = points.len();
+    // The contour starts at a point on the curve, which may be implied.
+    let start_index = points.iter().position(|(_, on_curve)| *on_curve);
+    let start = match start_index {
+        Some(index) => points[index].0,
+        None => midpoint(points[count - 1].0, points[0].0),
+    };
+    let first = start_index.map_or(0, |index| index + 1);
+    let mut polygon = vec![start];
+    let mut control: Option<(f64, f64)> = None;
+    let mut current = start;
+    let mut curve_to = |polygon: &mut Vec<(f64, f64)>,
+                        current: &mut (f64, f64),
+                        control: (f64, f64),
+                        end: (f64, f64)| {
+        for step in 1..=STEPS {
+            let t = step as f64 / STEPS as f64;
+            let u = 1.0 - t;
+            polygon.push((
+                u * u * current.0 + 2.0 * u * t * control.0 + t * t * end.0,
+                u * u * current.1 + 2.0 * u * t * control.1 + t * t * end.1,
+            ));
+        }
+        *current = end;
+    };
+    for offset in 0..count {
+        let (point, on_curve) = points[(first + offset) % count];
+        match (on_curve, control) {
+            (true, None) => {
+                polygon.push(point);
+                current = point;
+            }
+            (true, Some(previous)) => {
+                curve_to(&mut polygon, &mut current, previous, point);
+                control = None;
+            }
+            (false, None) => control = Some(point),
+            (false, Some(previous)) => {
+                curve_to(
+                    &mut polygon,
+                    &mut current,
+                    previous,
+                    midpoint(previous, point),
+                );
+                control = Some(point);
+            }
+        }
+    }
+    if let Some(previous) = control {
+        curve_to(&mut polygon, &mut current, previous, start);
+    }
+    polygon
+}
+
+/// Finds the best subtable of a `cmap` table for Unicode characters, preferring one that covers characters beyond the Basic Multilingual Plane.
+///
+/// [Reference](https://learn.microsoft.com/typography/opentype/spec/cmap)
+fn find_character_map(reader: &Reader, cmap: usize) -> Option<CharacterMap> {
+    let count = reader.u16(cmap + 2)? as usize;
+    let mut best = None;
+    for index in 0..count {
+        let record = cmap + 4 + index * 8;
+        let platform = reader.u16(record)?;
+        let encoding = reader.u16(record + 2)?;
+        let offset = cmap + reader.u32(record + 4)? as usize;
+        let is_unicode = platform == 0 || (platform == 3 && matches!(encoding, 1 | 10));
+        if !is_unicode {
+            continue;
+        }
+        match reader.u16(offset)? {
+            12 => return Some(CharacterMap::SegmentedCoverage(offset)),
+            4 => best = best.or(Some(CharacterMap::SegmentToDelta(offset))),
+            _ => {}
+        }
+    }
+    best
+}
+
+/// Reads the family name from a `name` table, preferring the typographic family over the legacy one, and English names in Unicode.
+///
+/// [Reference](https://learn.microsoft.com/typography/opentype/spec/name)
+fn read_family(reader: &Reader, name: usize) -> Option<String> {
+    let count = reader.u16(name + 2)? as usize;
+    let storage = name + reader.u16(name + 4)? as usize;
+    let mut best: Option<(u16, String)> = None;
+    for index in 0..count {
+        let record = name + 6 + index * 12;
+        let platform = reader.u16(record)?;
+        let language = reader.u16(record + 4)?;
+        let name_id = reader.u16(record + 6)?;
+        let length = reader.u16(record + 8)? as usize;
+        let offset = storage + reader.u16(record + 10)? as usize;
+        let bytes = reader.data.get(offset..offset + length)?;
+        let text = match platform {
+            0 | 3 => {
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            1 => bytes.iter().map(|byte| *byte as char).collect(),
+            _ => continue,
+        };
+        let rank = match (name_id, platform == 1 || language == 0x409 || platform == 0) {
+            (16, true) => 4,
+            (16, false) => 3,
+            (1, true) => 2,
+            (1, false) => 1,
+            _ => continue,
+        };
+        if best.as_ref().is_none_or(|(best, _)| rank > *best) {
+            best = Some((rank, text));
+        }
+    }
+    best.map(|(_, family)| family)
+}
+
+/// A provider that measures text with fonts loaded from files, and falls back to the fixed metrics of [`FixedFontProvider`] for families and characters that none of them cover.
+///
+/// The font for a family is the one with the closest style and weight. Each character is measured with the first family in the list that has a glyph for it.
+///
+/// # Example
+/// ```no_run
+/// use dom::{traits::*, Document, FontCollection};
+///
+/// let mut fonts = FontCollection::new();
+/// fonts.load("fonts/DejaVuSans.ttf").unwrap();
+/// fonts.set_generic_family("sans-serif", "DejaVu Sans");
+///
+/// let mut document = Document::parse_html_unsafe("<p style='font-family: sans-serif'>Hello</p>");
+/// document.set_font_provider(fonts);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FontCollection {
+    fonts: Vec<TrueTypeFont>,
+    /// The families used for generic families like `sans-serif`.
+    generics: HashMap<String, String>,
+}
+
+impl FontCollection {
+    /// Creates a collection without any fonts.
+    pub fn new() -> Self {
+        FontCollection::default()
+    }
+    /// Adds a font to the collection.
+    pub fn add(&mut self, font: TrueTypeFont) {
+        self.fonts.push(font);
+    }
+    /// Reads a font from a file and adds it to the collection.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), DOMException> {
+        self.add(TrueTypeFont::open(path)?);
+        Ok(())
+    }
+    /// Uses a family for a generic family, like `DejaVu Sans Mono` for `monospace`.
+    pub fn set_generic_family(&mut self, generic: &str, family: &str) {
+        self.generics
+            .insert(generic.to_ascii_lowercase(), family.to_owned());
+    }
+    /// Returns the fonts that match the families of a description, in order.
+    fn matches<'a>(&'a self, font: &'a FontDescription) -> impl Iterator<Item = &'a TrueTypeFont> {
+        font.families.iter().filter_map(move |family| {
+            let family = self
+                .generics
+                .get(&family.to_ascii_lowercase())
+                .unwrap_or(family);
+            self.fonts
+                .iter()
+                .filter(|candidate| candidate.family.eq_ignore_ascii_case(family))
+                .min_by_key(|candidate| {
+                    let style = (candidate.italic != font.italic) as u16;
+                    (style, candidate.weight.abs_diff(font.weight))
+                })
+        })
+    }
+}
+
+impl FontProvider for FontCollection {
+    fn metrics(&self, font: &FontDescription) -> FontMetrics {
+        match self.matches(font).next() {
+            Some(matched) => matched.metrics(font.size),
+            None => FixedFontProvider.metrics(font),
+        }
+    }
+
+    fn advance(&self, font: &FontDescription, character: char) -> f64 {
+        self.matches(font)
+            .find_map(|matched| Some(matched.advance(matched.glyph(character)?, font.size)))
+            .unwrap_or_else(|| FixedFontProvider.advance(font, character))
+    }
+
+    fn outline(&self, font: &FontDescription, character: char) -> Option<Vec<Vec<(f64, f64)>>> {
+        self.matches(font)
+            .find_map(|matched| Some((matched, matched.glyph(character)?)))
+            .and_then(|(matched, glyph)| matched.outline(glyph, font.size))
+    }
+}
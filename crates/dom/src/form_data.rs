@@ -0,0 +1,203 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    html_element::form_submission, AsElement, DOMException, File, HTMLElement, HTMLFormElement,
+};
+
+/// The value of an entry in a [`FormData`].
+///
+/// MDN Reference: [`FormData.get()`](https://developer.mozilla.org/docs/Web/API/FormData/get)
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormDataEntryValue {
+    File(File),
+    String(String),
+}
+
+/// A list of name and value pairs, like the ones that a form sends when it is submitted.
+///
+/// Clones of a `FormData` share their entries, so the entries that the listeners of a `formdata` event add to the [`EventDetail::FormData`](crate::EventDetail::FormData) of the event are submitted with the form.
+///
+/// MDN Reference: [`FormData`](https://developer.mozilla.org/docs/Web/API/FormData)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FormData {
+    entries: Rc<RefCell<Vec<(String, FormDataEntryValue)>>>,
+}
+
+impl FormData {
+    /// Creates an empty list of entries.
+    ///
+    /// MDN Reference: [`FormData()`](https://developer.mozilla.org/docs/Web/API/FormData/FormData)
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Creates a list of the entries that a form would submit. If a submit button of the form is given, its name and value are included too.
+    ///
+    /// MDN Reference: [`FormData()`](https://developer.mozilla.org/docs/Web/API/FormData/FormData)
+    /// # Errors
+    /// - Returns a `TypeError` DOMException if the submitter is not a submit button.
+    /// - Returns a `NotFoundError` DOMException if the submitter does not belong to the form.
+    /// - Returns an `InvalidStateError` DOMException if the form is already constructing its entries, which happens when this is called by a `formdata` event listener.
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, FormData, FormDataEntryValue, HTMLFormElement, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    /// form.set_inner_html(r#"
+    ///     <input name="query" value="rust">
+    ///     <input name="safe" type="checkbox" checked>
+    ///     <input name="unchecked" type="checkbox">
+    ///     <button name="action" value="search">Search</button>
+    /// "#).unwrap();
+    ///
+    /// let form_data = FormData::from_form(&form, None).unwrap();
+    /// assert_eq!(form_data.keys(), ["query", "safe"]);
+    /// assert_eq!(
+    ///     form_data.get("safe"),
+    ///     Some(FormDataEntryValue::String(String::from("on")))
+    /// );
+    /// ```
+    pub fn from_form(
+        form: &HTMLFormElement,
+        submitter: Option<&HTMLElement>,
+    ) -> Result<Self, DOMException> {
+        let form = AsElement::cast(form);
+        let submitter = match submitter {
+            Some(submitter) => {
+                let submitter = AsElement::cast(submitter);
+                form_submission::check_submitter(form, submitter)?;
+                submitter
+            }
+            None => form,
+        };
+        form_submission::construct_entry_list(form, submitter).ok_or_else(|| {
+            DOMException::InvalidStateError(String::from(
+                "The form is already constructing its entry list.",
+            ))
+        })
+    }
+    /// Creates a list from entries.
+    pub(crate) fn from_entries(entries: Vec<(String, FormDataEntryValue)>) -> Self {
+        FormData {
+            entries: Rc::new(RefCell::new(entries)),
+        }
+    }
+    /// Adds an entry with a string value, after the entries that are already in the list.
+    ///
+    /// MDN Reference: [`FormData.append()`](https://developer.mozilla.org/docs/Web/API/FormData/append)
+    pub fn append(&mut self, name: &str, value: &str) {
+        self.push(name, FormDataEntryValue::String(value.to_owned()));
+    }
+    /// Adds an entry with a file, after the entries that are already in the list. The file is renamed to the filename if one is given.
+    ///
+    /// MDN Reference: [`FormData.append()`](https://developer.mozilla.org/docs/Web/API/FormData/append)
+    pub fn append_file(&mut self, name: &str, file: File, filename: Option<&str>) {
+        self.push(name, file_value(file, filename));
+    }
+    /// Removes all the entries with a name.
+    ///
+    /// MDN Reference: [`FormData.delete()`](https://developer.mozilla.org/docs/Web/API/FormData/delete)
+    pub fn delete(&mut self, name: &str) {
+        self.entries
+            .borrow_mut()
+            .retain(|(entry_name, _)| entry_name != name);
+    }
+    /// Returns the value of the first entry with a name.
+    ///
+    /// MDN Reference: [`FormData.get()`](https://developer.mozilla.org/docs/Web/API/FormData/get)
+    pub fn get(&self, name: &str) -> Option<FormDataEntryValue> {
+        self.get_all(name).into_iter().next()
+    }
+    /// Returns the values of all the entries with a name, in order.
+    ///
+    /// MDN Reference: [`FormData.getAll()`](https://developer.mozilla.org/docs/Web/API/FormData/getAll)
+    pub fn get_all(&self, name: &str) -> Vec<FormDataEntryValue> {
+        self.entries
+            .borrow()
+            .iter()
+            .filter(|(entry_name, _)| entry_name == name)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+    /// Returns true if there is an entry with a name.
+    ///
+    /// MDN Reference: [`FormData.has()`](https://developer.mozilla.org/docs/Web/API/FormData/has)
+    pub fn has(&self, name: &str) -> bool {
+        self.entries
+            .borrow()
+            .iter()
+            .any(|(entry_name, _)| entry_name == name)
+    }
+    /// Replaces the first entry with a name by an entry with a string value, and removes the others. The entry is added at the end if there is none with the name.
+    ///
+    /// MDN Reference: [`FormData.set()`](https://developer.mozilla.org/docs/Web/API/FormData/set)
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.replace(name, FormDataEntryValue::String(value.to_owned()));
+    }
+    /// Replaces the first entry with a name by an entry with a file, and removes the others. The file is renamed to the filename if one is given.
+    ///
+    /// MDN Reference: [`FormData.set()`](https://developer.mozilla.org/docs/Web/API/FormData/set)
+    pub fn set_file(&mut self, name: &str, file: File, filename: Option<&str>) {
+        self.replace(name, file_value(file, filename));
+    }
+    /// Returns the names and values of the entries, in order.
+    ///
+    /// MDN Reference: [`FormData.entries()`](https://developer.mozilla.org/docs/Web/API/FormData/entries)
+    pub fn entries(&self) -> Vec<(String, FormDataEntryValue)> {
+        self.entries.borrow().clone()
+    }
+    /// Returns the names of the entries, in order.
+    ///
+    /// MDN Reference: [`FormData.keys()`](https://developer.mozilla.org/docs/Web/API/FormData/keys)
+    pub fn keys(&self) -> Vec<String> {
+        self.entries
+            .borrow()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+    /// Returns the values of the entries, in order.
+    ///
+    /// MDN Reference: [`FormData.values()`](https://developer.mozilla.org/docs/Web/API/FormData/values)
+    pub fn values(&self) -> Vec<FormDataEntryValue> {
+        self.entries
+            .borrow()
+            .iter()
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    fn push(&mut self, name: &str, value: FormDataEntryValue) {
+        self.entries.borrow_mut().push((name.to_owned(), value));
+    }
+
+    fn replace(&mut self, name: &str, value: FormDataEntryValue) {
+        let mut entries = self.entries.borrow_mut();
+        // The first entry with the name takes the value, and the rest are removed.
+        let mut value = Some(value);
+        entries.retain_mut(|(entry_name, entry_value)| {
+            if entry_name != name {
+                return true;
+            }
+            match value.take() {
+                Some(value) => {
+                    *entry_value = value;
+                    true
+                }
+                None => false,
+            }
+        });
+        if let Some(value) = value {
+            entries.push((name.to_owned(), value));
+        }
+    }
+}
+
+/// [Reference](https://xhr.spec.whatwg.org/#create-an-entry)
+fn file_value(file: File, filename: Option<&str>) -> FormDataEntryValue {
+    FormDataEntryValue::File(match filename {
+        Some(filename) => file.renamed(filename),
+        None => file,
+    })
+}
@@ -35,30 +35,31 @@ pub struct HTMLCollection<'a> {
 impl<'a> HTMLCollection<'a> {
     /// Retrieves the number of objects in a collection.
     pub fn len(&self) -> usize {
-        self.items.len()
+        self.elements().count()
     }
     /// Retrieves an object from various collections.
     pub fn item(&self, index: usize) -> Option<Element> {
-        let node = self.items.get(index)?;
-        node.owner_document()?
-            .lookup_html_element(AsNode::cast(node).get_base_ptr())
+        self.elements().nth(index)
     }
     /// Retrieves a select object or an object from an options collection.
     pub fn named_item(&self, name: &str) -> Option<Element> {
         todo!()
     }
+    fn elements(&self) -> <Self as IntoIterator>::IntoIter {
+        HTMLCollection { items: self.items }.into_iter()
+    }
 }
 impl<'a> IntoIterator for HTMLCollection<'a> {
     type Item = Element;
 
-    type IntoIter = std::iter::Map<std::slice::Iter<'a, ChildNode>, fn(&'a ChildNode) -> Element>;
+    type IntoIter =
+        std::iter::FilterMap<std::slice::Iter<'a, ChildNode>, fn(&'a ChildNode) -> Option<Element>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.items.iter().map(|node| {
-            node.owner_document()
-                .unwrap()
+        // The items may include text and comment nodes, which are not elements.
+        self.items.iter().filter_map(|node| {
+            node.owner_document()?
                 .lookup_html_element(AsNode::cast(node).get_base_ptr())
-                .unwrap()
         })
     }
 }
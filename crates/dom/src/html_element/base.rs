@@ -1,4 +1,8 @@
-use crate::{document::WeakDocumentRef, tag::Tag, Element};
+use super::html_input_element::InputState;
+use crate::{
+    document::WeakDocumentRef, tag::Tag, AsDocument, CSSStyleSheet, ChildNode, Document,
+    DocumentFragment, Element,
+};
 
 #[derive(Debug)]
 pub enum HTMLElementBase {
@@ -49,7 +53,13 @@ pub enum HTMLElementBase {
     Figure(Element),
     Font(Element),
     Footer(Element),
-    Form(Element),
+    Form {
+        element: Element,
+        /// Whether the form is constructing its entry list, which keeps it from being submitted meanwhile.
+        constructing_entry_list: bool,
+        /// Whether the form is firing its `submit` event, which keeps it from being submitted again meanwhile.
+        firing_submission_events: bool,
+    },
     Frame(Element),
     Frameset(Element),
     H1(Element),
@@ -66,7 +76,10 @@ pub enum HTMLElementBase {
     Iframe(Element),
     Image(Element),
     Img(Element),
-    Input { value: String, element: Element },
+    Input {
+        element: Element,
+        state: InputState,
+    },
     Ins(Element),
     Isindex(Element),
     Kbd(Element),
@@ -74,7 +87,11 @@ pub enum HTMLElementBase {
     Label(Element),
     Legend(Element),
     Li(Element),
-    Link(Element),
+    Link {
+        element: Element,
+        /// The style sheet of the link, if it is a style sheet link that has been loaded.
+        sheet: Option<CSSStyleSheet>,
+    },
     Main(Element),
     Map(Element),
     Mark(Element),
@@ -109,13 +126,25 @@ pub enum HTMLElementBase {
     Section(Element),
     Select(Element),
     Small(Element),
-    Slot(Element),
+    Slot {
+        element: Element,
+        /// The slottables assigned to the slot.
+        assigned_nodes: Vec<ChildNode>,
+        /// The slottables manually assigned with [`HTMLSlotElement::assign`].
+        ///
+        /// [`HTMLSlotElement::assign`]: crate::HTMLSlotElement::assign
+        manually_assigned_nodes: Vec<ChildNode>,
+    },
     Source(Element),
     Spacer(Element),
     Span(Element),
     Strike(Element),
     Strong(Element),
-    Style(Element),
+    Style {
+        element: Element,
+        /// The style sheet parsed from the element's text.
+        sheet: Option<CSSStyleSheet>,
+    },
     Sub(Element),
     Summary(Element),
     Sup(Element),
@@ -123,7 +152,11 @@ pub enum HTMLElementBase {
     Table(Element),
     Tbody(Element),
     Td(Element),
-    Template(Element),
+    Template {
+        element: Element,
+        /// The fragment holding the template's contents, owned by the inert template contents owner document.
+        content: DocumentFragment,
+    },
     Textarea(Element),
     Tfoot(Element),
     Th(Element),
@@ -193,7 +226,11 @@ impl HTMLElementBase {
             "figure" => Self::Figure(elem_create(Tag::Figure, is_html, weak_ref)),
             "font" => Self::Font(elem_create(Tag::Font, is_html, weak_ref)),
             "footer" => Self::Footer(elem_create(Tag::Footer, is_html, weak_ref)),
-            "form" => Self::Form(elem_create(Tag::Form, is_html, weak_ref)),
+            "form" => Self::Form {
+                element: elem_create(Tag::Form, is_html, weak_ref),
+                constructing_entry_list: false,
+                firing_submission_events: false,
+            },
             "frame" => Self::Frame(elem_create(Tag::Frame, is_html, weak_ref)),
             "frameset" => Self::Frameset(elem_create(Tag::Frameset, is_html, weak_ref)),
             "h1" => Self::H1(elem_create(Tag::H1, is_html, weak_ref)),
@@ -212,7 +249,7 @@ impl HTMLElementBase {
             "img" => Self::Img(elem_create(Tag::Img, is_html, weak_ref)),
             "input" => Self::Input {
                 element: elem_create(Tag::Input, is_html, weak_ref),
-                value: String::new(),
+                state: InputState::default(),
             },
             "ins" => Self::Ins(elem_create(Tag::Ins, is_html, weak_ref)),
             "isindex" => Self::Isindex(elem_create(Tag::Isindex, is_html, weak_ref)),
@@ -221,7 +258,10 @@ impl HTMLElementBase {
             "label" => Self::Label(elem_create(Tag::Label, is_html, weak_ref)),
             "legend" => Self::Legend(elem_create(Tag::Legend, is_html, weak_ref)),
             "li" => Self::Li(elem_create(Tag::Li, is_html, weak_ref)),
-            "link" => Self::Link(elem_create(Tag::Link, is_html, weak_ref)),
+            "link" => Self::Link {
+                element: elem_create(Tag::Link, is_html, weak_ref),
+                sheet: None,
+            },
             "main" => Self::Main(elem_create(Tag::Main, is_html, weak_ref)),
             "map" => Self::Map(elem_create(Tag::Map, is_html, weak_ref)),
             "mark" => Self::Mark(elem_create(Tag::Mark, is_html, weak_ref)),
@@ -256,13 +296,20 @@ impl HTMLElementBase {
             "section" => Self::Section(elem_create(Tag::Section, is_html, weak_ref)),
             "select" => Self::Select(elem_create(Tag::Select, is_html, weak_ref)),
             "small" => Self::Small(elem_create(Tag::Small, is_html, weak_ref)),
-            "slot" => Self::Slot(elem_create(Tag::Slot, is_html, weak_ref)),
+            "slot" => Self::Slot {
+                element: elem_create(Tag::Slot, is_html, weak_ref),
+                assigned_nodes: vec![],
+                manually_assigned_nodes: vec![],
+            },
             "source" => Self::Source(elem_create(Tag::Source, is_html, weak_ref)),
             "spacer" => Self::Spacer(elem_create(Tag::Spacer, is_html, weak_ref)),
             "span" => Self::Span(elem_create(Tag::Span, is_html, weak_ref)),
             "strike" => Self::Strike(elem_create(Tag::Strike, is_html, weak_ref)),
             "strong" => Self::Strong(elem_create(Tag::Strong, is_html, weak_ref)),
-            "style" => Self::Style(elem_create(Tag::Style, is_html, weak_ref)),
+            "style" => Self::Style {
+                element: elem_create(Tag::Style, is_html, weak_ref),
+                sheet: None,
+            },
             "sub" => Self::Sub(elem_create(Tag::Sub, is_html, weak_ref)),
             "summary" => Self::Summary(elem_create(Tag::Summary, is_html, weak_ref)),
             "sup" => Self::Sup(elem_create(Tag::Sup, is_html, weak_ref)),
@@ -270,7 +317,12 @@ impl HTMLElementBase {
             "table" => Self::Table(elem_create(Tag::Table, is_html, weak_ref)),
             "tbody" => Self::Tbody(elem_create(Tag::Tbody, is_html, weak_ref)),
             "td" => Self::Td(elem_create(Tag::Td, is_html, weak_ref)),
-            "template" => Self::Template(elem_create(Tag::Template, is_html, weak_ref)),
+            "template" => Self::Template {
+                content: Document::with_base(weak_ref.inner.upgrade().unwrap())
+                    .template_contents_owner()
+                    .create_document_fragment(),
+                element: elem_create(Tag::Template, is_html, weak_ref),
+            },
             "textarea" => Self::Textarea(elem_create(Tag::Textarea, is_html, weak_ref)),
             "tfoot" => Self::Tfoot(elem_create(Tag::Tfoot, is_html, weak_ref)),
             "th" => Self::Th(elem_create(Tag::Th, is_html, weak_ref)),
@@ -343,7 +395,7 @@ impl HTMLElementBase {
             | Self::Figure(element)
             | Self::Font(element)
             | Self::Footer(element)
-            | Self::Form(element)
+            | Self::Form { element, .. }
             | Self::Frame(element)
             | Self::Frameset(element)
             | Self::H1(element)
@@ -368,7 +420,7 @@ impl HTMLElementBase {
             | Self::Label(element)
             | Self::Legend(element)
             | Self::Li(element)
-            | Self::Link(element)
+            | Self::Link { element, .. }
             | Self::Main(element)
             | Self::Map(element)
             | Self::Mark(element)
@@ -403,13 +455,13 @@ impl HTMLElementBase {
             | Self::Section(element)
             | Self::Select(element)
             | Self::Small(element)
-            | Self::Slot(element)
+            | Self::Slot { element, .. }
             | Self::Source(element)
             | Self::Spacer(element)
             | Self::Span(element)
             | Self::Strike(element)
             | Self::Strong(element)
-            | Self::Style(element)
+            | Self::Style { element, .. }
             | Self::Sub(element)
             | Self::Summary(element)
             | Self::Sup(element)
@@ -417,7 +469,7 @@ impl HTMLElementBase {
             | Self::Table(element)
             | Self::Tbody(element)
             | Self::Td(element)
-            | Self::Template(element)
+            | Self::Template { element, .. }
             | Self::Textarea(element)
             | Self::Tfoot(element)
             | Self::Th(element)
@@ -485,7 +537,7 @@ impl HTMLElementBase {
             | Self::Figure(element)
             | Self::Font(element)
             | Self::Footer(element)
-            | Self::Form(element)
+            | Self::Form { element, .. }
             | Self::Frame(element)
             | Self::Frameset(element)
             | Self::H1(element)
@@ -510,7 +562,7 @@ impl HTMLElementBase {
             | Self::Label(element)
             | Self::Legend(element)
             | Self::Li(element)
-            | Self::Link(element)
+            | Self::Link { element, .. }
             | Self::Main(element)
             | Self::Map(element)
             | Self::Mark(element)
@@ -545,13 +597,13 @@ impl HTMLElementBase {
             | Self::Section(element)
             | Self::Select(element)
             | Self::Small(element)
-            | Self::Slot(element)
+            | Self::Slot { element, .. }
             | Self::Source(element)
             | Self::Spacer(element)
             | Self::Span(element)
             | Self::Strike(element)
             | Self::Strong(element)
-            | Self::Style(element)
+            | Self::Style { element, .. }
             | Self::Sub(element)
             | Self::Summary(element)
             | Self::Sup(element)
@@ -559,7 +611,7 @@ impl HTMLElementBase {
             | Self::Table(element)
             | Self::Tbody(element)
             | Self::Td(element)
-            | Self::Template(element)
+            | Self::Template { element, .. }
             | Self::Textarea(element)
             | Self::Tfoot(element)
             | Self::Th(element)
@@ -0,0 +1,838 @@
+//! Constraint validation, which checks the values of form controls against the constraints that their attributes set.
+//!
+//! Until controls can be edited, the value of a `textarea` is its text.
+//!
+//! [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#constraints)
+mod pattern;
+
+use pattern::Pattern;
+
+use super::{
+    form_associated::{form_controls, form_owner, is_disabled},
+    html_input_element,
+    input_type::{parse_floating_point_number, InputType},
+};
+use crate::{
+    domitem::DOMItem,
+    node::trees::{descendant_nodes, root_of},
+    tag::Tag,
+    AsElement, AsEventTarget, AsNode, AsParentNode, Element, Event, EventInit, EventType, Node,
+};
+
+/// The states of validity that a form control can be in with respect to constraint validation. The states are read from the control when they are asked for, so they always reflect its current value.
+///
+/// MDN Reference: [`ValidityState`](https://developer.mozilla.org/docs/Web/API/ValidityState)
+pub struct ValidityState {
+    element: Element,
+}
+
+impl ValidityState {
+    /// Returns true if the control is required but has no value, or is a required checkbox or radio button group with nothing checked.
+    ///
+    /// MDN Reference: [`ValidityState.valueMissing`](https://developer.mozilla.org/docs/Web/API/ValidityState/valueMissing)
+    pub fn value_missing(&self) -> bool {
+        value_missing(&self.element)
+    }
+    /// Returns true if the value of an `email` or `url` input is not a valid email address or absolute URL.
+    ///
+    /// MDN Reference: [`ValidityState.typeMismatch`](https://developer.mozilla.org/docs/Web/API/ValidityState/typeMismatch)
+    pub fn type_mismatch(&self) -> bool {
+        type_mismatch(&self.element)
+    }
+    /// Returns true if the value does not match the regular expression in the `pattern` attribute.
+    ///
+    /// MDN Reference: [`ValidityState.patternMismatch`](https://developer.mozilla.org/docs/Web/API/ValidityState/patternMismatch)
+    pub fn pattern_mismatch(&self) -> bool {
+        pattern_mismatch(&self.element)
+    }
+    /// Returns true if the value is longer than the `maxlength` attribute allows.
+    ///
+    /// MDN Reference: [`ValidityState.tooLong`](https://developer.mozilla.org/docs/Web/API/ValidityState/tooLong)
+    pub fn too_long(&self) -> bool {
+        too_long(&self.element)
+    }
+    /// Returns true if the value is shorter than the `minlength` attribute allows.
+    ///
+    /// MDN Reference: [`ValidityState.tooShort`](https://developer.mozilla.org/docs/Web/API/ValidityState/tooShort)
+    pub fn too_short(&self) -> bool {
+        too_short(&self.element)
+    }
+    /// Returns true if the value is less than the `min` attribute allows.
+    ///
+    /// MDN Reference: [`ValidityState.rangeUnderflow`](https://developer.mozilla.org/docs/Web/API/ValidityState/rangeUnderflow)
+    pub fn range_underflow(&self) -> bool {
+        range_underflow(&self.element)
+    }
+    /// Returns true if the value is greater than the `max` attribute allows.
+    ///
+    /// MDN Reference: [`ValidityState.rangeOverflow`](https://developer.mozilla.org/docs/Web/API/ValidityState/rangeOverflow)
+    pub fn range_overflow(&self) -> bool {
+        range_overflow(&self.element)
+    }
+    /// Returns true if the value is not one of the values that the `step` attribute allows.
+    ///
+    /// MDN Reference: [`ValidityState.stepMismatch`](https://developer.mozilla.org/docs/Web/API/ValidityState/stepMismatch)
+    pub fn step_mismatch(&self) -> bool {
+        step_mismatch(&self.element)
+    }
+    /// Returns true if the user has entered input that can not be converted to a value.
+    ///
+    /// There is no user input to convert, so this is always false.
+    ///
+    /// MDN Reference: [`ValidityState.badInput`](https://developer.mozilla.org/docs/Web/API/ValidityState/badInput)
+    pub fn bad_input(&self) -> bool {
+        false
+    }
+    /// Returns true if a custom validity message has been set with [`ConstraintValidation::set_custom_validity`].
+    ///
+    /// MDN Reference: [`ValidityState.customError`](https://developer.mozilla.org/docs/Web/API/ValidityState/customError)
+    pub fn custom_error(&self) -> bool {
+        custom_error(&self.element)
+    }
+    /// Returns true if the control meets all of its constraints.
+    ///
+    /// MDN Reference: [`ValidityState.valid`](https://developer.mozilla.org/docs/Web/API/ValidityState/valid)
+    pub fn valid(&self) -> bool {
+        satisfies_constraints(&self.element)
+    }
+}
+
+/// The constraint validation API that form controls share.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#the-constraint-validation-api)
+pub trait ConstraintValidation: AsElement {
+    /// Returns true if the element will be validated when the form is submitted. Elements that are disabled, read-only, in a `datalist`, or are hidden inputs or reset or plain buttons are not validated.
+    ///
+    /// MDN Reference: [`HTMLInputElement.willValidate`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/willValidate)
+    fn will_validate(&self) -> bool {
+        is_candidate(AsElement::cast(self))
+    }
+    /// Returns the validity states of the element.
+    ///
+    /// MDN Reference: [`HTMLInputElement.validity`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/validity)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLInputElement};
+    ///
+    /// let document = Document::new();
+    /// let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    /// input.set_attribute("type", "number");
+    /// input.set_attribute("min", "1");
+    /// input.set_attribute("step", "2");
+    /// input.set_attribute("value", "4");
+    ///
+    /// let validity = input.validity();
+    /// assert!(validity.step_mismatch());
+    /// assert!(!validity.range_underflow());
+    ///
+    /// // The states are live.
+    /// input.set_attribute("value", "-1");
+    /// assert!(validity.range_underflow());
+    /// assert!(!validity.step_mismatch());
+    /// ```
+    fn validity(&self) -> ValidityState {
+        ValidityState {
+            element: AsElement::cast(self).clone_ref(),
+        }
+    }
+    /// Returns the message that describes how the element fails its constraints, or an empty string if it will not be validated or meets them.
+    ///
+    /// MDN Reference: [`HTMLInputElement.validationMessage`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/validationMessage)
+    fn validation_message(&self) -> String {
+        validation_message(AsElement::cast(self))
+    }
+    /// Returns true if the element meets its constraints. Otherwise, an `invalid` event is fired at it and false is returned.
+    ///
+    /// MDN Reference: [`HTMLInputElement.checkValidity()`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/checkValidity)
+    fn check_validity(&self) -> bool {
+        check_validity(AsElement::cast(self))
+    }
+    /// Works like [`ConstraintValidation::check_validity`], but also reports the problem if the `invalid` event is not canceled, after which the element matches `:user-invalid`.
+    ///
+    /// MDN Reference: [`HTMLInputElement.reportValidity()`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/reportValidity)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLInputElement};
+    ///
+    /// let document = Document::new();
+    /// let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    /// input.set_attribute("required", "");
+    /// assert!(!input.matches(":user-invalid"));
+    ///
+    /// assert!(!input.report_validity());
+    /// assert_eq!(input.validation_message(), "Please fill out this field.");
+    /// assert!(input.matches(":user-invalid"));
+    /// ```
+    fn report_validity(&self) -> bool {
+        report_validity(AsElement::cast(self))
+    }
+    /// Sets a custom message for why the element is invalid. The element fails its constraints until the message is set to an empty string.
+    ///
+    /// MDN Reference: [`HTMLInputElement.setCustomValidity()`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/setCustomValidity)
+    fn set_custom_validity(&mut self, error: &str) {
+        AsElement::cast(self).base().custom_validity_error = error.to_owned();
+    }
+}
+
+/// Returns true if an element is a candidate for constraint validation.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#candidate-for-constraint-validation)
+pub(crate) fn is_candidate(element: &Element) -> bool {
+    let barred = match element.base().tag {
+        Tag::Input => {
+            let input_type = InputType::of(element);
+            matches!(
+                input_type,
+                InputType::Hidden | InputType::Reset | InputType::Button
+            ) || (is_read_only(element, input_type))
+        }
+        Tag::Textarea => element.has_attribute("readonly"),
+        Tag::Button => matches!(
+            element
+                .get_attribute("type")
+                .unwrap_or_default()
+                .to_ascii_lowercase()
+                .as_str(),
+            "reset" | "button"
+        ),
+        Tag::Select => false,
+        // Only submittable elements are validated.
+        _ => return false,
+    };
+    !barred && !is_disabled(element) && !has_datalist_ancestor(element)
+}
+
+/// Returns true if an element meets all of its constraints.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-fv-valid)
+pub(crate) fn satisfies_constraints(element: &Element) -> bool {
+    !(value_missing(element)
+        || type_mismatch(element)
+        || pattern_mismatch(element)
+        || too_long(element)
+        || too_short(element)
+        || range_underflow(element)
+        || range_overflow(element)
+        || step_mismatch(element)
+        || custom_error(element))
+}
+
+/// Returns true if an element is a valid candidate, or an invalid one if `valid` is false. Forms and fieldsets are invalid when a control of theirs is.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/semantics-other.html#selector-valid)
+pub(crate) fn matches_validity(element: &Element, valid: bool) -> bool {
+    let is_invalid = |control: &Element| is_candidate(control) && !satisfies_constraints(control);
+    match element.base().tag {
+        Tag::Form => form_controls(element).iter().any(is_invalid) != valid,
+        Tag::Fieldset => {
+            descendant_nodes(element)
+                .into_iter()
+                .filter_map(element_of)
+                .any(|control| is_invalid(&control))
+                != valid
+        }
+        _ => is_candidate(element) && satisfies_constraints(element) == valid,
+    }
+}
+
+/// Returns true if the user has been told about the validity of a candidate, and it is valid, or invalid if `valid` is false.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/semantics-other.html#selector-user-invalid)
+pub(crate) fn matches_user_validity(element: &Element, valid: bool) -> bool {
+    element.base().user_validity && is_candidate(element) && satisfies_constraints(element) == valid
+}
+
+/// Returns true if a candidate has range limitations and its value is in range, or out of range if `in_range` is false.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/semantics-other.html#selector-in-range)
+pub(crate) fn matches_range(element: &Element, in_range: bool) -> bool {
+    let has_range_limitations =
+        range(element).is_some_and(|(min, max)| min.is_some() || max.is_some());
+    has_range_limitations
+        && is_candidate(element)
+        && (range_underflow(element) || range_overflow(element)) != in_range
+}
+
+pub(crate) fn validation_message(element: &Element) -> String {
+    if !is_candidate(element) || satisfies_constraints(element) {
+        return String::new();
+    }
+    if custom_error(element) {
+        return element.base().custom_validity_error.clone();
+    }
+    let attribute = |name: &str| element.get_attribute(name).unwrap_or_default().to_owned();
+    let input_type = (element.base().tag == Tag::Input).then(|| InputType::of(element));
+    if value_missing(element) {
+        return String::from(match (element.base().tag.clone(), input_type) {
+            (_, Some(InputType::Checkbox)) => "Please check this box if you want to proceed.",
+            (_, Some(InputType::Radio)) => "Please select one of these options.",
+            (_, Some(InputType::File)) => "Please select a file.",
+            (Tag::Select, _) => "Please select an item in the list.",
+            _ => "Please fill out this field.",
+        });
+    }
+    if type_mismatch(element) {
+        return String::from(match input_type {
+            Some(InputType::Email) => "Please enter an email address.",
+            _ => "Please enter a URL.",
+        });
+    }
+    if pattern_mismatch(element) {
+        return match element.get_attribute("title") {
+            Some(title) if !title.is_empty() => {
+                format!("Please match the requested format: {title}.")
+            }
+            _ => String::from("Please match the requested format."),
+        };
+    }
+    let length = utf16_length(&value(element));
+    if too_long(element) {
+        return format!(
+            "Please shorten this text to {} characters or less (you are currently using {length} characters).",
+            attribute("maxlength").trim()
+        );
+    }
+    if too_short(element) {
+        return format!(
+            "Please lengthen this text to {} characters or more (you are currently using {length} characters).",
+            attribute("minlength").trim()
+        );
+    }
+    if range_underflow(element) {
+        return format!(
+            "Value must be greater than or equal to {}.",
+            attribute("min")
+        );
+    }
+    if range_overflow(element) {
+        return format!("Value must be less than or equal to {}.", attribute("max"));
+    }
+    String::from("Please enter a valid value.")
+}
+
+/// Returns true if an element meets its constraints, and fires an `invalid` event at it if it does not.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#check-validity-steps)
+pub(crate) fn check_validity(element: &Element) -> bool {
+    if !is_candidate(element) || satisfies_constraints(element) {
+        return true;
+    }
+    fire_invalid(element);
+    false
+}
+
+/// Works like [`check_validity`], but marks the element as having been reported to the user if the `invalid` event is not canceled.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#report-validity-steps)
+pub(crate) fn report_validity(element: &Element) -> bool {
+    if !is_candidate(element) || satisfies_constraints(element) {
+        return true;
+    }
+    if fire_invalid(element) {
+        element.base().user_validity = true;
+        crate::layout::invalidate(AsNode::cast(element));
+    }
+    false
+}
+
+/// Fires `invalid` events at the invalid controls of a form, and returns the ones whose event was not canceled, or `Ok` if every control is valid.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#statically-validate-the-constraints)
+pub(crate) fn statically_validate(form: &Element) -> Result<(), Vec<Element>> {
+    let invalid_controls: Vec<Element> = form_controls(form)
+        .into_iter()
+        .filter(|control| is_candidate(control) && !satisfies_constraints(control))
+        .collect();
+    if invalid_controls.is_empty() {
+        return Ok(());
+    }
+    Err(invalid_controls.into_iter().filter(fire_invalid).collect())
+}
+
+/// Validates the controls of a form like [`statically_validate`], after which the user has been told about the validity of all of them.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#interactively-validate-the-constraints)
+pub(crate) fn interactively_validate(form: &Element) -> bool {
+    for control in form_controls(form)
+        .iter()
+        .filter(|control| is_candidate(control))
+    {
+        control.base().user_validity = true;
+    }
+    crate::layout::invalidate(AsNode::cast(form));
+    statically_validate(form).is_ok()
+}
+
+/// Fires a cancelable `invalid` event at an element, and returns false if it was canceled.
+fn fire_invalid(element: &Element) -> bool {
+    let event_init = EventInit {
+        bubbles: false,
+        cancelable: true,
+        composed: false,
+    };
+    element
+        .clone_ref()
+        .dispatch_event(&mut Event::trusted(EventType::Invalid, event_init))
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#suffering-from-being-missing)
+fn value_missing(element: &Element) -> bool {
+    match element.base().tag {
+        // A radio button group is missing a value if any of its buttons is required and none is checked.
+        Tag::Input if InputType::of(element) == InputType::Radio => {
+            let group = radio_button_group(element);
+            group.iter().any(|radio| radio.has_attribute("required"))
+                && !group.iter().any(checkedness)
+        }
+        _ if !element.has_attribute("required") => false,
+        Tag::Input => match InputType::of(element) {
+            InputType::Checkbox => !checkedness(element),
+            InputType::File => html_input_element::files(element).is_empty(),
+            InputType::Range | InputType::Color => false,
+            input_type if input_type.is_textual() || input_type.is_numeric() => {
+                is_mutable(element) && value(element).is_empty()
+            }
+            _ => false,
+        },
+        Tag::Textarea => is_mutable(element) && value(element).is_empty(),
+        Tag::Select => {
+            let options = list_of_options(element);
+            match selected_options(element, &options).as_slice() {
+                [] => true,
+                [option] => is_placeholder_label_option(element, &options, option),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#suffering-from-a-type-mismatch)
+fn type_mismatch(element: &Element) -> bool {
+    if element.base().tag != Tag::Input {
+        return false;
+    }
+    let value = value(element);
+    if value.is_empty() {
+        return false;
+    }
+    match InputType::of(element) {
+        InputType::Email if element.has_attribute("multiple") => !value.split(',').all(|address| {
+            is_valid_email(address.trim_matches(|char: char| char.is_ascii_whitespace()))
+        }),
+        InputType::Email => !is_valid_email(&value),
+        InputType::Url => !is_absolute_url(&value),
+        _ => false,
+    }
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#the-pattern-attribute)
+fn pattern_mismatch(element: &Element) -> bool {
+    if element.base().tag != Tag::Input {
+        return false;
+    }
+    let input_type = InputType::of(element);
+    let value = value(element);
+    if !input_type.is_textual() || value.is_empty() {
+        return false;
+    }
+    // Invalid patterns are ignored.
+    let Some(pattern) = element.get_attribute("pattern").and_then(Pattern::parse) else {
+        return false;
+    };
+    match input_type == InputType::Email && element.has_attribute("multiple") {
+        true => value.split(',').any(|address| {
+            !pattern.matches(address.trim_matches(|char: char| char.is_ascii_whitespace()))
+        }),
+        false => !pattern.matches(&value),
+    }
+}
+
+/// Returns the length limits of the `maxlength` and `minlength` attributes, if they apply to the element.
+///
+/// There is no user editing, so every value counts as if the user typed it.
+fn length_limit(element: &Element, name: &str) -> Option<usize> {
+    let applies = match element.base().tag {
+        Tag::Input => InputType::of(element).is_textual(),
+        Tag::Textarea => true,
+        _ => false,
+    };
+    applies
+        .then(|| parse_non_negative_integer(element.get_attribute(name)?))
+        .flatten()
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#suffering-from-being-too-long)
+fn too_long(element: &Element) -> bool {
+    length_limit(element, "maxlength").is_some_and(|max| utf16_length(&value(element)) > max)
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#suffering-from-being-too-short)
+fn too_short(element: &Element) -> bool {
+    length_limit(element, "minlength").is_some_and(|min| {
+        let length = utf16_length(&value(element));
+        length > 0 && length < min
+    })
+}
+
+/// Returns the minimum and maximum of an input whose type converts values to numbers.
+pub(crate) fn range(element: &Element) -> Option<(Option<f64>, Option<f64>)> {
+    if element.base().tag != Tag::Input {
+        return None;
+    }
+    let input_type = InputType::of(element);
+    if !input_type.is_numeric() {
+        return None;
+    }
+    let (default_min, default_max) = input_type.default_range();
+    let limit = |name: &str| {
+        element
+            .get_attribute(name)
+            .and_then(|value| input_type.to_number(value))
+    };
+    Some((limit("min").or(default_min), limit("max").or(default_max)))
+}
+
+/// Returns the value of an input as a number, with its minimum and maximum, and whether the range is reversed, which only `time` inputs can be.
+fn number_in_range(element: &Element) -> Option<(f64, Option<f64>, Option<f64>, bool)> {
+    let (min, max) = range(element)?;
+    let input_type = InputType::of(element);
+    let number = input_type.to_number(&value(element))?;
+    let reversed =
+        input_type == InputType::Time && min.zip(max).is_some_and(|(min, max)| min > max);
+    Some((number, min, max, reversed))
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#suffering-from-an-underflow)
+fn range_underflow(element: &Element) -> bool {
+    match number_in_range(element) {
+        Some((number, Some(min), Some(max), true)) => number < min && number > max,
+        Some((number, Some(min), _, false)) => number < min,
+        _ => false,
+    }
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#suffering-from-an-overflow)
+fn range_overflow(element: &Element) -> bool {
+    match number_in_range(element) {
+        Some((number, Some(min), Some(max), true)) => number < min && number > max,
+        Some((number, _, Some(max), false)) => number > max,
+        _ => false,
+    }
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#attr-input-step)
+fn step_mismatch(element: &Element) -> bool {
+    if range(element).is_none() {
+        return false;
+    }
+    let input_type = InputType::of(element);
+    let Some(number) = input_type.to_number(&value(element)) else {
+        return false;
+    };
+    let Some(step) = allowed_step(element) else {
+        return false;
+    };
+    let steps = (number - step_base(element)) / step;
+    (steps - steps.round()).abs() > 1e-9 * steps.abs().max(1.0)
+}
+
+/// Returns the allowed value step of an input, in the units that its values convert to, or `None` if any value is allowed.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-step)
+pub(crate) fn allowed_step(element: &Element) -> Option<f64> {
+    let input_type = InputType::of(element);
+    let step = match element.get_attribute("step") {
+        Some(step) if step.eq_ignore_ascii_case("any") => return None,
+        Some(step) => parse_floating_point_number(step)
+            .filter(|step| *step > 0.0)
+            .unwrap_or(input_type.default_step()),
+        None => input_type.default_step(),
+    };
+    Some(step * input_type.step_scale_factor())
+}
+
+/// Returns the number that the allowed values of an input count from.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-min-zero)
+pub(crate) fn step_base(element: &Element) -> f64 {
+    let input_type = InputType::of(element);
+    ["min", "value"]
+        .iter()
+        .find_map(|name| {
+            element
+                .get_attribute(name)
+                .and_then(|value| input_type.to_number(value))
+        })
+        .unwrap_or(input_type.default_step_base())
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#suffering-from-a-custom-error)
+fn custom_error(element: &Element) -> bool {
+    !element.base().custom_validity_error.is_empty()
+}
+
+/// Returns the value of an `input`, `button` or `textarea` control.
+pub(crate) fn value(element: &Element) -> String {
+    match element.base().tag {
+        Tag::Input => html_input_element::value(element),
+        Tag::Button => element
+            .get_attribute("value")
+            .unwrap_or_default()
+            .to_owned(),
+        Tag::Textarea => element
+            .text_content()
+            .unwrap_or_default()
+            .replace("\r\n", "\n")
+            .replace('\r', "\n"),
+        _ => String::new(),
+    }
+}
+
+pub(crate) fn checkedness(element: &Element) -> bool {
+    html_input_element::checkedness(element)
+}
+
+/// Returns true if the element is neither disabled nor read-only, so its value could be changed.
+fn is_mutable(element: &Element) -> bool {
+    !is_disabled(element) && !element.has_attribute("readonly")
+}
+
+/// Returns true if an input has the `readonly` attribute and its type is one that the attribute applies to.
+fn is_read_only(element: &Element, input_type: InputType) -> bool {
+    (input_type.is_textual() || (input_type.is_numeric() && input_type != InputType::Range))
+        && element.has_attribute("readonly")
+}
+
+pub(crate) fn has_datalist_ancestor(element: &Element) -> bool {
+    let mut ancestor = element.parent_element();
+    while let Some(element) = ancestor {
+        if element.base().tag == Tag::Datalist {
+            return true;
+        }
+        ancestor = element.parent_element();
+    }
+    false
+}
+
+/// Returns the radio buttons in the same tree as a radio button, with the same name and form owner.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#radio-button-group)
+pub(crate) fn radio_button_group(radio: &Element) -> Vec<Element> {
+    let name = radio.get_attribute("name").unwrap_or_default();
+    if name.is_empty() {
+        return vec![radio.clone_ref()];
+    }
+    let owner = form_owner(radio);
+    descendant_nodes(&root_of(radio))
+        .into_iter()
+        .filter_map(element_of)
+        .filter(|element| {
+            element.base().tag == Tag::Input
+                && InputType::of(element) == InputType::Radio
+                && element.get_attribute("name") == Some(name)
+                && match (form_owner(element), &owner) {
+                    (Some(form), Some(owner)) => form.is_same_node(owner),
+                    (None, None) => true,
+                    _ => false,
+                }
+        })
+        .collect()
+}
+
+/// Returns the `option` elements of a `select`, including the ones in its `optgroup` elements.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-elements.html#concept-select-option-list)
+pub(crate) fn list_of_options(select: &Element) -> Vec<Element> {
+    let mut options = vec![];
+    for child in select.children() {
+        match child.base().tag {
+            Tag::Option => options.push(child),
+            Tag::Optgroup => options.extend(
+                child
+                    .children()
+                    .into_iter()
+                    .filter(|option| option.base().tag == Tag::Option),
+            ),
+            _ => {}
+        }
+    }
+    options
+}
+
+/// Returns the options whose selectedness is true. A `select` that shows a single option without `multiple` always selects one: the last with the `selected` attribute, or else the first that is not disabled.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-elements.html#selectedness-setting-algorithm)
+pub(crate) fn selected_options(select: &Element, options: &[Element]) -> Vec<Element> {
+    let mut selected = options
+        .iter()
+        .filter(|option| option.has_attribute("selected"));
+    if select.has_attribute("multiple") {
+        return selected.map(Element::clone_ref).collect();
+    }
+    if let Some(last) = selected.next_back() {
+        return vec![last.clone_ref()];
+    }
+    if display_size(select) > 1 {
+        return vec![];
+    }
+    options
+        .iter()
+        .find(|option| !is_option_disabled(option))
+        .map(Element::clone_ref)
+        .into_iter()
+        .collect()
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-elements.html#concept-select-size)
+fn display_size(select: &Element) -> usize {
+    select
+        .get_attribute("size")
+        .and_then(parse_non_negative_integer)
+        .filter(|size| *size > 0)
+        .unwrap_or(if select.has_attribute("multiple") {
+            4
+        } else {
+            1
+        })
+}
+
+pub(crate) fn is_option_disabled(option: &Element) -> bool {
+    option.has_attribute("disabled")
+        || option.parent_element().is_some_and(|parent| {
+            parent.base().tag == Tag::Optgroup && parent.has_attribute("disabled")
+        })
+}
+
+/// Returns true if an option is the placeholder of a required `select`, which is not a choice.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-elements.html#placeholder-label-option)
+fn is_placeholder_label_option(select: &Element, options: &[Element], option: &Element) -> bool {
+    !select.has_attribute("multiple")
+        && display_size(select) == 1
+        && options
+            .first()
+            .is_some_and(|first| first.is_same_node(option))
+        && option
+            .parent_element()
+            .is_some_and(|parent| parent.is_same_node(select))
+        && option_value(option).is_empty()
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-elements.html#concept-option-value)
+pub(crate) fn option_value(option: &Element) -> String {
+    match option.get_attribute("value") {
+        Some(value) => value.to_owned(),
+        None => option
+            .text_content()
+            .unwrap_or_default()
+            .split(|char: char| char.is_ascii_whitespace())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// Returns true if a string is a valid email address.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#valid-e-mail-address)
+fn is_valid_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    let valid_label = |label: &str| {
+        (1..=63).contains(&label.len())
+            && label
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+    };
+    !local.is_empty()
+        && local
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b".!#$%&'*+/=?^_`{|}~-".contains(&byte))
+        && domain.split('.').all(valid_label)
+}
+
+/// Returns true if a string parses as an absolute URL: a scheme followed by a colon, and a host for the schemes that need one.
+///
+/// [Reference](https://url.spec.whatwg.org/#absolute-url-with-fragment-string)
+fn is_absolute_url(value: &str) -> bool {
+    let value = value.trim_matches(|char: char| char <= ' ');
+    let Some((scheme, rest)) = value.split_once(':') else {
+        return false;
+    };
+    let valid_scheme = scheme
+        .chars()
+        .next()
+        .is_some_and(|char| char.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || matches!(char, '+' | '-' | '.'));
+    if !valid_scheme {
+        return false;
+    }
+    if !matches!(
+        scheme.to_ascii_lowercase().as_str(),
+        "http" | "https" | "ws" | "wss" | "ftp"
+    ) {
+        return true;
+    }
+    let authority = rest.trim_start_matches(['/', '\\']);
+    let authority = &authority[..authority
+        .find(['/', '\\', '?', '#'])
+        .unwrap_or(authority.len())];
+    let host_and_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let (host, port) = match host_and_port.rfind(':') {
+        Some(index) if !host_and_port[index..].contains(']') => {
+            (&host_and_port[..index], &host_and_port[index + 1..])
+        }
+        _ => (host_and_port, ""),
+    };
+    let valid_port = port.is_empty()
+        || (port.bytes().all(|byte| byte.is_ascii_digit())
+            && port.parse::<u32>().is_ok_and(|port| port <= 65535));
+    let valid_host = match host.strip_prefix('[') {
+        Some(address) => address
+            .strip_suffix(']')
+            .is_some_and(|address| address.contains(':')),
+        None => {
+            !host.is_empty()
+                && !host.chars().any(|char| {
+                    char <= ' '
+                        || matches!(
+                            char,
+                            '#' | '/' | ':' | '<' | '>' | '?' | '@' | '[' | '\\' | ']' | '^' | '|'
+                        )
+                })
+        }
+    };
+    valid_port && valid_host
+}
+
+/// Parses a non-negative integer, which may have leading whitespace and a `+`.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-non-negative-integers)
+fn parse_non_negative_integer(value: &str) -> Option<usize> {
+    let value = value.trim_start_matches(|char: char| char.is_ascii_whitespace());
+    let value = value.strip_prefix('+').unwrap_or(value);
+    let end = value
+        .find(|char: char| !char.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+fn utf16_length(value: &str) -> usize {
+    value.encode_utf16().count()
+}
+
+fn element_of(node: &Node) -> Option<Element> {
+    if node.node_type() != Node::ELEMENT_NODE {
+        return None;
+    }
+    node.owner_document()?
+        .lookup_html_element(node.get_base_ptr())
+}
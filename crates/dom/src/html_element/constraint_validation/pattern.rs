@@ -0,0 +1,526 @@
+//! The regular expressions of `pattern` attributes, which are compiled like JavaScript regular expressions with the `v` flag and have to match whole values.
+//!
+//! Backreferences, lookbehinds, string disjunctions and most Unicode property escapes are not supported, and patterns that use them are ignored like invalid ones.
+//!
+//! [Reference](https://html.spec.whatwg.org/multipage/input.html#compiled-pattern-regular-expression)
+
+/// A compiled `pattern` attribute.
+#[derive(Debug, Clone)]
+pub(crate) struct Pattern {
+    node: Node,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Empty,
+    Char(char),
+    /// `.`, which is any character other than a line terminator.
+    Any,
+    Class(ClassSet),
+    Start,
+    End,
+    /// `\b`, or `\B` when the boolean is false.
+    WordBoundary(bool),
+    Lookahead {
+        node: Box<Node>,
+        negated: bool,
+    },
+    Sequence(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum ClassSet {
+    Char(char),
+    Range(char, char),
+    /// A character class escape like `\d`, or its negation like `\D`.
+    Escape(fn(char) -> bool, bool),
+    Union(Vec<ClassSet>),
+    Intersection(Vec<ClassSet>),
+    Subtraction(Vec<ClassSet>),
+    Negated(Box<ClassSet>),
+}
+
+impl ClassSet {
+    fn matches(&self, char: char) -> bool {
+        match self {
+            ClassSet::Char(expected) => char == *expected,
+            ClassSet::Range(start, end) => (*start..=*end).contains(&char),
+            ClassSet::Escape(predicate, negated) => predicate(char) != *negated,
+            ClassSet::Union(sets) => sets.iter().any(|set| set.matches(char)),
+            ClassSet::Intersection(sets) => sets.iter().all(|set| set.matches(char)),
+            ClassSet::Subtraction(sets) => {
+                sets[0].matches(char) && !sets[1..].iter().any(|set| set.matches(char))
+            }
+            ClassSet::Negated(set) => !set.matches(char),
+        }
+    }
+}
+
+impl Pattern {
+    /// Compiles the value of a `pattern` attribute, returning `None` if it is not a valid regular expression.
+    pub(crate) fn parse(pattern: &str) -> Option<Pattern> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            position: 0,
+        };
+        let node = parser.parse_disjunction()?;
+        (parser.position == parser.chars.len()).then_some(Pattern { node })
+    }
+
+    /// Returns true if the pattern matches the whole of a value.
+    pub(crate) fn matches(&self, value: &str) -> bool {
+        let input: Vec<char> = value.chars().collect();
+        match_node(&self.node, &input, 0, &mut |position| {
+            position == input.len()
+        })
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn eat(&mut self, char: char) -> bool {
+        let eaten = self.peek() == Some(char);
+        if eaten {
+            self.position += 1;
+        }
+        eaten
+    }
+
+    fn eat_str(&mut self, text: &str) -> bool {
+        let matches = text
+            .chars()
+            .enumerate()
+            .all(|(offset, char)| self.peek_at(offset) == Some(char));
+        if matches {
+            self.position += text.chars().count();
+        }
+        matches
+    }
+
+    fn parse_disjunction(&mut self) -> Option<Node> {
+        let mut alternatives = vec![self.parse_alternative()?];
+        while self.eat('|') {
+            alternatives.push(self.parse_alternative()?);
+        }
+        Some(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => Node::Alternation(alternatives),
+        })
+    }
+
+    fn parse_alternative(&mut self) -> Option<Node> {
+        let mut terms = vec![];
+        while let Some(char) = self.peek() {
+            if char == '|' || char == ')' {
+                break;
+            }
+            terms.push(self.parse_term()?);
+        }
+        Some(match terms.len() {
+            0 => Node::Empty,
+            1 => terms.remove(0),
+            _ => Node::Sequence(terms),
+        })
+    }
+
+    fn parse_term(&mut self) -> Option<Node> {
+        let char = self.peek()?;
+        self.position += 1;
+        // Assertions can not be quantified.
+        let atom = match char {
+            '^' => return Some(Node::Start),
+            '$' => return Some(Node::End),
+            '\\' if self.eat('b') => return Some(Node::WordBoundary(true)),
+            '\\' if self.eat('B') => return Some(Node::WordBoundary(false)),
+            '(' if self.eat_str("?=") || self.eat_str("?!") => {
+                let negated = self.chars[self.position - 1] == '!';
+                let node = self.parse_disjunction()?;
+                self.eat(')').then_some(())?;
+                return Some(Node::Lookahead {
+                    node: Box::new(node),
+                    negated,
+                });
+            }
+            '(' => {
+                if self.eat('?') {
+                    // Only non-capturing and named groups are left, and captures are not needed without backreferences.
+                    if self.eat('<') {
+                        while self.peek().is_some_and(|char| {
+                            char.is_alphanumeric() || char == '_' || char == '$'
+                        }) {
+                            self.position += 1;
+                        }
+                        self.eat('>').then_some(())?;
+                    } else {
+                        self.eat(':').then_some(())?;
+                    }
+                }
+                let node = self.parse_disjunction()?;
+                self.eat(')').then_some(())?;
+                node
+            }
+            '.' => Node::Any,
+            '[' => Node::Class(self.parse_class()?),
+            '\\' => self.parse_atom_escape()?,
+            // Syntax characters without an atom are errors in Unicode mode.
+            '*' | '+' | '?' | '{' | '}' | ']' | ')' => return None,
+            char => Node::Char(char),
+        };
+        self.parse_quantifier(atom)
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Option<Node> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.position += 1;
+                let min = self.parse_integer()?;
+                let max = match self.eat(',') {
+                    true if self.peek() == Some('}') => None,
+                    true => Some(self.parse_integer()?),
+                    false => Some(min),
+                };
+                if self.peek() != Some('}') || max.is_some_and(|max| max < min) {
+                    return None;
+                }
+                (min, max)
+            }
+            _ => return Some(atom),
+        };
+        self.position += 1;
+        let greedy = !self.eat('?');
+        Some(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+        })
+    }
+
+    fn parse_integer(&mut self) -> Option<usize> {
+        let start = self.position;
+        while self.peek().is_some_and(|char| char.is_ascii_digit()) {
+            self.position += 1;
+        }
+        self.chars[start..self.position]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+
+    /// Parses what follows a backslash outside of a class.
+    fn parse_atom_escape(&mut self) -> Option<Node> {
+        if let Some(set) = self.parse_class_escape() {
+            return Some(Node::Class(set));
+        }
+        match self.peek()? {
+            // Identity escapes in Unicode mode are only for syntax characters and `/`.
+            char @ ('^' | '$' | '\\' | '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{'
+            | '}' | '|' | '/') => {
+                self.position += 1;
+                Some(Node::Char(char))
+            }
+            _ => self.parse_character_escape().map(Node::Char),
+        }
+    }
+
+    /// Parses a character class escape like `\d` or `\p{L}`, after the backslash.
+    fn parse_class_escape(&mut self) -> Option<ClassSet> {
+        let (predicate, negated): (fn(char) -> bool, bool) = match self.peek()? {
+            'd' => (|char: char| char.is_ascii_digit(), false),
+            'D' => (|char: char| char.is_ascii_digit(), true),
+            's' => (is_white_space, false),
+            'S' => (is_white_space, true),
+            'w' => (is_word_character, false),
+            'W' => (is_word_character, true),
+            'p' | 'P' => {
+                let negated = self.peek() == Some('P');
+                self.position += 1;
+                self.eat('{').then_some(())?;
+                let start = self.position;
+                while self.peek().is_some_and(|char| char != '}') {
+                    self.position += 1;
+                }
+                let name: String = self.chars[start..self.position].iter().collect();
+                self.eat('}').then_some(())?;
+                return Some(ClassSet::Escape(unicode_property(&name)?, negated));
+            }
+            _ => return None,
+        };
+        self.position += 1;
+        Some(ClassSet::Escape(predicate, negated))
+    }
+
+    /// Parses an escape for a single character, after the backslash.
+    fn parse_character_escape(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.position += 1;
+        match char {
+            't' => Some('\t'),
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            'f' => Some('\u{c}'),
+            'v' => Some('\u{b}'),
+            '0' if !self.peek().is_some_and(|char| char.is_ascii_digit()) => Some('\0'),
+            'c' => {
+                let letter = self.peek().filter(char::is_ascii_alphabetic)?;
+                self.position += 1;
+                char::from_u32(letter as u32 % 32)
+            }
+            'x' => self.parse_hex(2),
+            'u' if self.eat('{') => {
+                let start = self.position;
+                while self.peek().is_some_and(|char| char.is_ascii_hexdigit()) {
+                    self.position += 1;
+                }
+                let hex: String = self.chars[start..self.position].iter().collect();
+                self.eat('}').then_some(())?;
+                char::from_u32(u32::from_str_radix(&hex, 16).ok()?)
+            }
+            'u' => self.parse_hex(4),
+            // Backreferences and every other escape are not supported or are errors in Unicode mode.
+            _ => None,
+        }
+    }
+
+    fn parse_hex(&mut self, length: usize) -> Option<char> {
+        let hex: String = self
+            .chars
+            .get(self.position..self.position + length)?
+            .iter()
+            .collect();
+        let code_point = u32::from_str_radix(&hex, 16).ok()?;
+        self.position += length;
+        char::from_u32(code_point)
+    }
+
+    /// Parses a class in `v` mode, which may contain nested classes, intersections with `&&` and subtractions with `--`, after the opening bracket.
+    fn parse_class(&mut self) -> Option<ClassSet> {
+        let negated = self.eat('^');
+        let mut operands = vec![];
+        let mut operator = None;
+        loop {
+            if self.eat(']') {
+                break;
+            }
+            let current = match (self.peek_at(0), self.peek_at(1)) {
+                (Some('&'), Some('&')) => Some("&&"),
+                (Some('-'), Some('-')) => Some("--"),
+                _ => None,
+            };
+            if let Some(current) = current {
+                // Operators need an operand on each side, and can not be mixed with each other or with unions.
+                if operands.len() != 1 && operator != Some(current) {
+                    return None;
+                }
+                operator = Some(current);
+                self.position += 2;
+                operands.push(self.parse_class_operand()?);
+                continue;
+            }
+            if operator.is_some() {
+                return None;
+            }
+            let operand = self.parse_class_operand()?;
+            // A range is only possible between two single characters.
+            match operand {
+                ClassSet::Char(start)
+                    if self.peek() == Some('-') && self.peek_at(1) != Some('-') =>
+                {
+                    self.position += 1;
+                    let ClassSet::Char(end) = self.parse_class_operand()? else {
+                        return None;
+                    };
+                    if end < start {
+                        return None;
+                    }
+                    operands.push(ClassSet::Range(start, end));
+                }
+                operand => operands.push(operand),
+            }
+        }
+        let set = match operator {
+            Some("&&") => ClassSet::Intersection(operands),
+            Some(_) => ClassSet::Subtraction(operands),
+            None => ClassSet::Union(operands),
+        };
+        Some(match negated {
+            true => ClassSet::Negated(Box::new(set)),
+            false => set,
+        })
+    }
+
+    /// Parses a nested class, a class escape or a single character in a class.
+    fn parse_class_operand(&mut self) -> Option<ClassSet> {
+        let char = self.peek()?;
+        self.position += 1;
+        match char {
+            '[' => self.parse_class(),
+            '\\' => {
+                if let Some(set) = self.parse_class_escape() {
+                    return Some(set);
+                }
+                match self.peek()? {
+                    // Syntax characters and the punctuators that are reserved in classes can be escaped.
+                    char @ ('^' | '$' | '\\' | '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']'
+                    | '{' | '}' | '|' | '/' | '-' | '&' | '!' | '#' | '%' | ',' | ':'
+                    | ';' | '<' | '=' | '>' | '@' | '`' | '~') => {
+                        self.position += 1;
+                        Some(ClassSet::Char(char))
+                    }
+                    'b' => {
+                        self.position += 1;
+                        Some(ClassSet::Char('\u{8}'))
+                    }
+                    _ => self.parse_character_escape().map(ClassSet::Char),
+                }
+            }
+            // These have to be escaped in `v` mode.
+            '(' | ')' | ']' | '{' | '}' | '/' | '-' | '|' => None,
+            char if self.peek() == Some(char) && "&!#$%*+,.:;<=>?@^`~".contains(char) => None,
+            char => Some(ClassSet::Char(char)),
+        }
+    }
+}
+
+/// Matches a node at a position, calling `next` with the positions after each way the node matches until it returns true.
+fn match_node(
+    node: &Node,
+    input: &[char],
+    position: usize,
+    next: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    match node {
+        Node::Empty => next(position),
+        Node::Char(expected) => input.get(position) == Some(expected) && next(position + 1),
+        Node::Any => {
+            input
+                .get(position)
+                .is_some_and(|char| !matches!(char, '\n' | '\r' | '\u{2028}' | '\u{2029}'))
+                && next(position + 1)
+        }
+        Node::Class(set) => {
+            input.get(position).is_some_and(|char| set.matches(*char)) && next(position + 1)
+        }
+        Node::Start => position == 0 && next(position),
+        Node::End => position == input.len() && next(position),
+        Node::WordBoundary(expected) => {
+            let before = position > 0 && is_word_character(input[position - 1]);
+            let after = input
+                .get(position)
+                .is_some_and(|char| is_word_character(*char));
+            (before != after) == *expected && next(position)
+        }
+        Node::Lookahead { node, negated } => {
+            match_node(node, input, position, &mut |_| true) != *negated && next(position)
+        }
+        Node::Sequence(nodes) => match_sequence(nodes, input, position, next),
+        Node::Alternation(nodes) => nodes
+            .iter()
+            .any(|node| match_node(node, input, position, next)),
+        Node::Repeat {
+            node,
+            min,
+            max,
+            greedy,
+        } => {
+            let repeat = Repeat {
+                node,
+                min: *min,
+                max: *max,
+                greedy: *greedy,
+            };
+            match_repeat(&repeat, input, position, 0, next)
+        }
+    }
+}
+
+fn match_sequence(
+    nodes: &[Node],
+    input: &[char],
+    position: usize,
+    next: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    match nodes.split_first() {
+        Some((first, rest)) => match_node(first, input, position, &mut |position| {
+            match_sequence(rest, input, position, next)
+        }),
+        None => next(position),
+    }
+}
+
+struct Repeat<'a> {
+    node: &'a Node,
+    min: usize,
+    max: Option<usize>,
+    greedy: bool,
+}
+
+fn match_repeat(
+    repeat: &Repeat,
+    input: &[char],
+    position: usize,
+    count: usize,
+    next: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    let mut match_again = |next: &mut dyn FnMut(usize) -> bool| {
+        repeat.max.is_none_or(|max| count < max)
+            && match_node(repeat.node, input, position, &mut |after| {
+                // Once the minimum is reached, an iteration that matches nothing ends the repetition.
+                (after != position || count < repeat.min)
+                    && match_repeat(repeat, input, after, count + 1, next)
+            })
+    };
+    if count < repeat.min {
+        return match_again(next);
+    }
+    // Greedy repetitions try another iteration first, and lazy ones try to stop first.
+    match repeat.greedy {
+        true => match_again(next) || next(position),
+        false => next(position) || match_again(next),
+    }
+}
+
+fn is_word_character(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+fn is_white_space(char: char) -> bool {
+    char.is_whitespace() || char == '\u{feff}'
+}
+
+/// Returns the test for a Unicode property in a `\p{…}` escape, for the properties that are supported.
+fn unicode_property(name: &str) -> Option<fn(char) -> bool> {
+    Some(match name {
+        "L" | "Letter" | "Alphabetic" | "Alpha" => char::is_alphabetic,
+        "Lu" | "Uppercase_Letter" | "Uppercase" | "Upper" => char::is_uppercase,
+        "Ll" | "Lowercase_Letter" | "Lowercase" | "Lower" => char::is_lowercase,
+        "N" | "Number" => char::is_numeric,
+        "White_Space" | "space" => char::is_whitespace,
+        "ASCII" => |char: char| char.is_ascii(),
+        "Any" => |_| true,
+        _ => return None,
+    })
+}
@@ -0,0 +1,125 @@
+//! The directionality of elements, which the `dir` attribute sets and elements without one inherit.
+//!
+//! [Reference](https://html.spec.whatwg.org/multipage/dom.html#the-dir-attribute)
+use unicode_bidi::{bidi_class, BidiClass};
+
+use crate::{
+    domitem::DOMItem,
+    node::{trees::root_of, ChildNode},
+    slot::{host_of_shadow_root, is_slot},
+    tag::Tag,
+    AsElement, AsNode, Element, Node,
+};
+
+/// The direction of the text of an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Ltr,
+    Rtl,
+}
+
+impl Direction {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl",
+        }
+    }
+}
+
+/// Returns the state of the `dir` attribute of an element: `ltr`, `rtl` or `auto`, or `None` when it is missing or invalid.
+pub(crate) fn dir_state(element: &Element) -> Option<&'static str> {
+    match element.get_attribute("dir")?.to_ascii_lowercase().as_str() {
+        "ltr" => Some("ltr"),
+        "rtl" => Some("rtl"),
+        "auto" => Some("auto"),
+        _ => None,
+    }
+}
+
+/// Returns the directionality of an element.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/dom.html#the-directionality)
+pub(crate) fn directionality(element: &Element) -> Direction {
+    let state = dir_state(element);
+    let tag = element.base().tag.clone();
+    let input_type = || {
+        element
+            .get_attribute("type")
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    match state {
+        Some("ltr") => return Direction::Ltr,
+        Some("rtl") => return Direction::Rtl,
+        Some("auto") if tag == Tag::Input => {
+            return match input_type().as_str() {
+                "" | "text" | "search" | "tel" | "url" | "email" => {
+                    text_direction(element.get_attribute("value").unwrap_or_default())
+                        .unwrap_or(Direction::Ltr)
+                }
+                _ => Direction::Ltr,
+            };
+        }
+        Some("auto") if tag == Tag::Textarea => {
+            return text_direction(&element.text_content().unwrap_or_default())
+                .unwrap_or(Direction::Ltr);
+        }
+        Some("auto") => return contained_text_direction(element).unwrap_or(Direction::Ltr),
+        _ if tag == Tag::Bdi => return contained_text_direction(element).unwrap_or(Direction::Ltr),
+        _ if tag == Tag::Input && input_type() == "tel" => return Direction::Ltr,
+        _ => {}
+    }
+    // Elements without a valid `dir` attribute have the directionality of their parent, or of the host of their shadow root.
+    let Some(parent) = AsNode::cast(element).parent_node() else {
+        return Direction::Ltr;
+    };
+    if let Some(host) = host_of_shadow_root(&parent) {
+        return directionality(&host);
+    }
+    match element.parent_element() {
+        Some(parent) => directionality(&parent),
+        None => Direction::Ltr,
+    }
+}
+
+/// Returns the direction of the first character of text with a strong direction, if any.
+fn text_direction(text: &str) -> Option<Direction> {
+    text.chars().find_map(|char| match bidi_class(char) {
+        BidiClass::L => Some(Direction::Ltr),
+        BidiClass::R | BidiClass::AL => Some(Direction::Rtl),
+        _ => None,
+    })
+}
+
+/// Returns the direction of the first text with a strong direction that an element contains, skipping the elements that set their own direction and the ones whose text is not content.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/dom.html#contained-text-auto-directionality)
+fn contained_text_direction(element: &Element) -> Option<Direction> {
+    let document = element.owner_document()?;
+    let children: Vec<ChildNode> = element.child_nodes().iter().map(ChildNode::from).collect();
+    children.iter().find_map(|child| {
+        let node = AsNode::cast(child);
+        match node.node_type() {
+            Node::TEXT_NODE => text_direction(&node.base().data),
+            Node::ELEMENT_NODE => {
+                let child = document.lookup_html_element(node.get_base_ptr())?;
+                // Slots in shadow trees stand for the text of their host.
+                if is_slot(&child) {
+                    if let Some(host) = host_of_shadow_root(&root_of(&child)) {
+                        return Some(directionality(&host));
+                    }
+                }
+                let skipped = matches!(
+                    child.base().tag,
+                    Tag::Bdi | Tag::Script | Tag::Style | Tag::Textarea
+                ) || dir_state(&child).is_some();
+                match skipped {
+                    true => None,
+                    false => contained_text_direction(&child),
+                }
+            }
+            _ => None,
+        }
+    })
+}
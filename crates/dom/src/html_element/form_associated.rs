@@ -0,0 +1,239 @@
+//! The association of form controls with their form owners.
+//!
+//! Only the listed elements are tracked, which are also all the reassociateable elements: `button`, `fieldset`, `input`, `object`, `output`, `select` and `textarea`.
+//!
+//! [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#association-of-controls-and-forms)
+use crate::{
+    domitem::DOMItem,
+    node::trees::{descendant_nodes, root_of},
+    tag::Tag,
+    AsElement, AsNode, AsParentNode, Element, HTMLElement, HTMLFormElement, Node,
+};
+
+/// Returns true if the element is a listed form-associated element.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/forms.html#category-listed)
+pub(crate) fn is_listed(element: &Element) -> bool {
+    matches!(
+        element.base().tag,
+        Tag::Button
+            | Tag::Fieldset
+            | Tag::Input
+            | Tag::Object
+            | Tag::Output
+            | Tag::Select
+            | Tag::Textarea
+    )
+}
+
+/// Returns true if the element can be associated with a `label` element.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/forms.html#category-label)
+pub(crate) fn is_labelable(element: &Element) -> bool {
+    match element.base().tag {
+        Tag::Input => !element
+            .get_attribute("type")
+            .is_some_and(|value| value.eq_ignore_ascii_case("hidden")),
+        Tag::Button | Tag::Meter | Tag::Output | Tag::Progress | Tag::Select | Tag::Textarea => {
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Returns the form owner of an element, if it has one.
+pub(crate) fn form_owner(element: &Element) -> Option<Element> {
+    let owner = element.base().form_owner.as_ref()?.upgrade()?;
+    Some(Element::with_base(owner))
+}
+
+/// Returns the form owner of an element as an `HTMLFormElement`, if it has one.
+pub(crate) fn form(element: &Element) -> Option<HTMLFormElement> {
+    let form = form_owner(element)?;
+    let base = form
+        .owner_document()?
+        .lookup_html_element_base(AsNode::cast(&form).get_base_ptr())?;
+    HTMLFormElement::try_from(HTMLElement { base }).ok()
+}
+
+/// Returns the listed elements whose form owner is a form, in tree order, leaving out image buttons.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/forms.html#dom-form-elements)
+pub(crate) fn form_controls(form: &Element) -> Vec<Element> {
+    associated_elements(form)
+        .into_iter()
+        .filter(|element| {
+            !(element.base().tag == Tag::Input
+                && element
+                    .get_attribute("type")
+                    .is_some_and(|value| value.eq_ignore_ascii_case("image")))
+        })
+        .collect()
+}
+
+/// Returns the elements whose form owner is a form, in tree order.
+pub(crate) fn associated_elements(form: &Element) -> Vec<Element> {
+    descendant_elements(&root_of(form))
+        .into_iter()
+        .filter(|element| form_owner(element).is_some_and(|owner| owner.is_same_node(form)))
+        .collect()
+}
+
+/// Returns true if a form control is disabled by its `disabled` attribute, or by a disabled `fieldset` ancestor that the element is not in the first `legend` child of. Options are also disabled by a disabled `optgroup` parent.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-fe-disabled)
+pub(crate) fn is_disabled(element: &Element) -> bool {
+    if element.has_attribute("disabled") {
+        return true;
+    }
+    match element.base().tag {
+        Tag::Option => element.parent_element().is_some_and(|parent| {
+            parent.base().tag == Tag::Optgroup && parent.has_attribute("disabled")
+        }),
+        Tag::Optgroup => false,
+        _ => {
+            let mut child = element.clone_ref();
+            while let Some(ancestor) = child.parent_element() {
+                if ancestor.base().tag == Tag::Fieldset && ancestor.has_attribute("disabled") {
+                    let first_legend = ancestor
+                        .children()
+                        .into_iter()
+                        .find(|child| child.base().tag == Tag::Legend);
+                    if !first_legend.is_some_and(|legend| legend.is_same_node(&child)) {
+                        return true;
+                    }
+                }
+                child = ancestor;
+            }
+            false
+        }
+    }
+}
+
+/// Returns the control that a `label` element labels.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/forms.html#labeled-control)
+pub(crate) fn labeled_control(label: &Element) -> Option<Element> {
+    match label.get_attribute("for") {
+        // The first element in the label's tree with the ID, if it is labelable.
+        Some(id) => element_with_id(&root_of(label), id).filter(is_labelable),
+        None => descendant_elements(label).into_iter().find(is_labelable),
+    }
+}
+
+/// Resets the form owner of a listed element.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#reset-the-form-owner)
+pub(crate) fn reset_form_owner(element: &Element) {
+    let form_attribute = element.get_attribute("form").map(str::to_owned);
+    let ancestor = nearest_form_ancestor(element);
+    // 2. If element's form owner is not null, and element's form content attribute is not present, and element's form owner is its nearest form element ancestor after the change to the ancestor chain, then return.
+    if form_attribute.is_none() {
+        if let (Some(owner), Some(ancestor)) = (form_owner(element), &ancestor) {
+            if owner.is_same_node(ancestor) {
+                return;
+            }
+        }
+    }
+    // 3. Set element's form owner to null.
+    // 4. If element has a form content attribute and is connected, then associate it with the first element in its tree to have the ID, if that is a form element.
+    // 5. Otherwise, if element has an ancestor form element, then associate element with the nearest such ancestor form element.
+    let owner = match form_attribute {
+        Some(id) if element.is_connected() => element_with_id(&root_of(element), &id)
+            .filter(|element| element.base().tag == Tag::Form),
+        _ => ancestor,
+    };
+    element.base().form_owner = owner.map(|owner| owner.as_weak_ref());
+}
+
+/// The form owner parts of the insertion steps: the form owners of the inserted listed elements are reset, and so are the ones that refer to a form by an ID the insertion may have added.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#association-of-controls-and-forms)
+pub(crate) fn run_insertion_steps(parent: &Node, node: &Node) {
+    reset_inserted_or_removed(parent, node);
+}
+
+/// The form owner parts of the removing steps, run after a node is removed from its parent.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#association-of-controls-and-forms)
+pub(crate) fn run_removing_steps(parent: &Node, node: &Node) {
+    reset_inserted_or_removed(parent, node);
+}
+
+/// The form owner parts of the attribute change steps.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#association-of-controls-and-forms)
+pub(crate) fn run_attribute_change_steps(
+    element: &Element,
+    local_name: &str,
+    old_value: Option<&str>,
+    value: Option<&str>,
+) {
+    if old_value == value {
+        return;
+    }
+    match local_name {
+        // When element's form content attribute is set, changed, or removed, then reset the form owner of element.
+        "form" if is_listed(element) => reset_form_owner(element),
+        // When an element in the tree changes its ID, reset the form owners of the elements with a form content attribute.
+        "id" if element.is_connected() => reset_form_attribute_owners(&root_of(element)),
+        _ => {}
+    }
+}
+
+/// Resets the form owners of the listed elements in a subtree that was inserted into or removed from a parent.
+fn reset_inserted_or_removed(parent: &Node, node: &Node) {
+    let elements: Vec<Element> = element_of(node)
+        .into_iter()
+        .chain(descendant_elements(node))
+        .collect();
+    // When a listed element or one of its ancestors is inserted or removed, then reset the form owner of that element.
+    for element in elements.iter().filter(|element| is_listed(element)) {
+        reset_form_owner(element);
+    }
+    // When an element with an ID is inserted into or removed from a connected tree, the form content attributes may refer to a different form.
+    if parent.is_connected() && elements.iter().any(|element| element.has_attribute("id")) {
+        reset_form_attribute_owners(&root_of(parent));
+    }
+}
+
+/// Resets the form owners of the listed elements in a tree that have a form content attribute.
+fn reset_form_attribute_owners(root: &impl AsNode) {
+    descendant_elements(root)
+        .iter()
+        .filter(|element| is_listed(element) && element.has_attribute("form"))
+        .for_each(reset_form_owner);
+}
+
+fn nearest_form_ancestor(element: &Element) -> Option<Element> {
+    let mut ancestor = element.parent_element();
+    while let Some(element) = ancestor {
+        if element.base().tag == Tag::Form {
+            return Some(element);
+        }
+        ancestor = element.parent_element();
+    }
+    None
+}
+
+/// Returns the first element in tree order among the descendants of a root that has an ID.
+fn element_with_id(root: &impl AsNode, id: &str) -> Option<Element> {
+    descendant_elements(root)
+        .into_iter()
+        .find(|element| element.get_attribute("id") == Some(id))
+}
+
+pub(crate) fn descendant_elements(root: &impl AsNode) -> Vec<Element> {
+    descendant_nodes(root)
+        .into_iter()
+        .filter_map(element_of)
+        .collect()
+}
+
+pub(crate) fn element_of(node: &Node) -> Option<Element> {
+    if node.node_type() != Node::ELEMENT_NODE {
+        return None;
+    }
+    node.owner_document()?
+        .lookup_html_element(node.get_base_ptr())
+}
@@ -0,0 +1,558 @@
+//! Form submission: constructing the entry list of a form, encoding it, and the navigation that submitting the form makes.
+//!
+//! Only UTF-8 is used to encode entries, whatever the `accept-charset` attribute says.
+//!
+//! [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#form-submission-2)
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    rc::Rc,
+};
+
+use super::{
+    base::HTMLElementBase,
+    constraint_validation::{
+        checkedness, has_datalist_ancestor, interactively_validate, is_option_disabled,
+        list_of_options, option_value, selected_options, value,
+    },
+    directionality::directionality,
+    form_associated::{associated_elements, form_owner, is_disabled},
+    html_input_element,
+    input_type::InputType,
+};
+use crate::{
+    domitem::DOMItem,
+    loader::{resolve_url, scheme_of},
+    tag::Tag,
+    AsElement, AsEventTarget, AsNode, DOMException, Element, Event, EventDetail, EventInit,
+    EventType, File, FilePropertyBag, FormData, FormDataEntryValue,
+};
+
+/// The request to navigate that submitting a form makes, which is where a browser would go next.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#plan-to-navigate)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavigationRequest {
+    /// The URL to navigate to, which has the entries of the form in its query for `GET` requests.
+    pub url: String,
+    /// The HTTP method, which is `GET` or `POST`.
+    pub method: String,
+    /// The encoded entries of the form, for `POST` requests.
+    pub body: Option<Vec<u8>>,
+    /// The `Content-Type` header of the body, like `multipart/form-data; boundary=...`.
+    pub content_type: Option<String>,
+    /// The name of the navigable to navigate, which is `_self` unless a `target` or `formtarget` attribute says otherwise.
+    pub target: String,
+}
+
+/// Returns true if an element is a submit button: a `button` in the submit state, or an `input` of type `submit` or `image`.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/forms.html#concept-submit-button)
+pub(crate) fn is_submit_button(element: &Element) -> bool {
+    match element.base().tag {
+        Tag::Button => !element.get_attribute("type").is_some_and(|value| {
+            value.eq_ignore_ascii_case("reset") || value.eq_ignore_ascii_case("button")
+        }),
+        Tag::Input => matches!(InputType::of(element), InputType::Submit | InputType::Image),
+        _ => false,
+    }
+}
+
+/// Checks that a submitter that is given for a form is one of its submit buttons.
+pub(crate) fn check_submitter(form: &Element, submitter: &Element) -> Result<(), DOMException> {
+    if !is_submit_button(submitter) {
+        return Err(DOMException::TypeError(String::from(
+            "The submitter is not a submit button.",
+        )));
+    }
+    if !form_owner(submitter).is_some_and(|owner| owner.is_same_node(form)) {
+        return Err(DOMException::NotFoundError(String::from(
+            "The submitter is not owned by the form.",
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the state of a `method` or `formmethod` attribute: `get`, `post` or `dialog`.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#attr-fs-method)
+pub(crate) fn method_state(value: Option<&str>) -> &'static str {
+    match value.unwrap_or_default().to_ascii_lowercase().as_str() {
+        "post" => "post",
+        "dialog" => "dialog",
+        _ => "get",
+    }
+}
+
+/// Returns the state of an `enctype` or `formenctype` attribute.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#attr-fs-enctype)
+pub(crate) fn enctype_state(value: Option<&str>) -> &'static str {
+    match value.unwrap_or_default().to_ascii_lowercase().as_str() {
+        "multipart/form-data" => "multipart/form-data",
+        "text/plain" => "text/plain",
+        _ => "application/x-www-form-urlencoded",
+    }
+}
+
+/// Returns the URL that a form is submitted to: its `action` attribute resolved against the document's URL, or the document's URL itself if the attribute is missing or empty.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#dom-fs-action)
+pub(crate) fn action_url(element: &Element, action: Option<&str>) -> String {
+    let document_url = element
+        .owner_document()
+        .map(|document| document.state().url.clone())
+        .unwrap_or_default();
+    match action {
+        Some(action) if !action.is_empty() => resolve_url(&document_url, action),
+        _ => document_url,
+    }
+}
+
+/// Constructs the entries that a form submits, and fires a `formdata` event at the form with them. The submitter is the form itself if no submit button submits it. Returns `None` if the form is already constructing its entries.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#constructing-the-form-data-set)
+pub(crate) fn construct_entry_list(form: &Element, submitter: &Element) -> Option<FormData> {
+    // 1. If form's constructing entry list is true, then return null.
+    // 2. Set form's constructing entry list to true.
+    if with_flags(form, |constructing_entry_list, _| {
+        std::mem::replace(constructing_entry_list, true)
+    })? {
+        return None;
+    }
+    let mut entries = vec![];
+    let mut append = |name: &str, value: FormDataEntryValue| entries.push((name.to_owned(), value));
+    let string = |value: &str| FormDataEntryValue::String(value.to_owned());
+    // 3. Let controls be a list of all the submittable elements whose form owner is form, in tree order.
+    for field in associated_elements(form).iter().filter(|field| {
+        matches!(
+            field.base().tag,
+            Tag::Button | Tag::Input | Tag::Select | Tag::Textarea
+        )
+    }) {
+        let tag = field.base().tag.clone();
+        let input_type = (tag == Tag::Input).then(|| InputType::of(field));
+        let is_button = tag == Tag::Button
+            || matches!(
+                input_type,
+                Some(InputType::Submit | InputType::Reset | InputType::Button | InputType::Image)
+            );
+        // 5.1. Skip fields in a datalist, disabled fields, buttons other than the submitter, and unchecked checkboxes and radio buttons.
+        if has_datalist_ancestor(field)
+            || is_disabled(field)
+            || (is_button && !field.is_same_node(submitter))
+            || (matches!(input_type, Some(InputType::Checkbox | InputType::Radio))
+                && !checkedness(field))
+        {
+            continue;
+        }
+        let name = field.get_attribute("name").unwrap_or_default().to_owned();
+        // 5.2. An image button submits the coordinate that was selected, which is the origin without a pointing device.
+        if input_type == Some(InputType::Image) {
+            let prefix = match name.is_empty() {
+                true => String::new(),
+                false => format!("{name}."),
+            };
+            append(&format!("{prefix}x"), string("0"));
+            append(&format!("{prefix}y"), string("0"));
+            continue;
+        }
+        // 5.4. If either the field element does not have a name attribute specified, or its name attribute's value is the empty string, skip to the next field element.
+        if name.is_empty() {
+            continue;
+        }
+        match (&tag, input_type) {
+            (Tag::Select, _) => {
+                for option in selected_options(field, &list_of_options(field)) {
+                    if !is_option_disabled(&option) {
+                        append(&name, string(&option_value(&option)));
+                    }
+                }
+            }
+            (_, Some(InputType::Checkbox | InputType::Radio)) => {
+                append(&name, string(field.get_attribute("value").unwrap_or("on")));
+            }
+            // A file input without selected files sends an empty file.
+            (_, Some(InputType::File)) => {
+                let files = html_input_element::files(field);
+                if files.is_empty() {
+                    let options = FilePropertyBag {
+                        r#type: String::from("application/octet-stream"),
+                        last_modified: None,
+                    };
+                    append(
+                        &name,
+                        FormDataEntryValue::File(File::new([], "", Some(options))),
+                    );
+                }
+                for file in files {
+                    append(&name, FormDataEntryValue::File(file));
+                }
+            }
+            (_, Some(InputType::Hidden)) if name.eq_ignore_ascii_case("_charset_") => {
+                append(&name, string("UTF-8"));
+            }
+            _ => append(&name, string(&value(field))),
+        }
+        // 5.12. If the element has a dirname attribute, append its directionality.
+        let dirname = field.get_attribute("dirname").unwrap_or_default();
+        let is_auto_directionality = tag == Tag::Textarea
+            || matches!(
+                input_type,
+                Some(
+                    InputType::Hidden
+                        | InputType::Text
+                        | InputType::Search
+                        | InputType::Tel
+                        | InputType::Url
+                        | InputType::Email
+                        | InputType::Password
+                        | InputType::Submit
+                        | InputType::Reset
+                        | InputType::Button
+                )
+            );
+        if !dirname.is_empty() && is_auto_directionality {
+            let dirname = dirname.to_owned();
+            append(&dirname, string(directionality(field).as_str()));
+        }
+    }
+    // 6. Let form data be a new FormData object associated with entry list.
+    // 7. Fire an event named formdata at form using FormDataEvent, with the formData attribute initialized to form data and the bubbles attribute initialized to true.
+    let form_data = FormData::from_entries(entries);
+    let event_init = EventInit {
+        bubbles: true,
+        cancelable: false,
+        composed: false,
+    };
+    let detail = EventDetail::FormData {
+        form_data: form_data.clone(),
+    };
+    form.clone_ref()
+        .dispatch_event(&mut Event::trusted(EventType::FormData, event_init).with_detail(detail));
+    // 8. Set form's constructing entry list to false.
+    with_flags(form, |constructing_entry_list, _| {
+        *constructing_entry_list = false
+    });
+    // 9. Return a clone of entry list.
+    Some(FormData::from_entries(form_data.entries()))
+}
+
+/// Submits a form, and returns the request to navigate that it makes. The submitter is the form itself if no submit button submits it.
+///
+/// Forms that are not connected, fail validation, have their `submit` event canceled or use the `dialog` method make no request.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-form-submit)
+pub(crate) fn submit(
+    form: &Element,
+    submitter: &Element,
+    submitted_from_submit_method: bool,
+) -> Option<NavigationRequest> {
+    let submitter_attribute = |name: &str| {
+        match submitter.is_same_node(form) {
+            true => None,
+            false => submitter.get_attribute(name),
+        }
+        .map(str::to_owned)
+    };
+    // 1. If form cannot navigate, then return.
+    // 2. If form's constructing entry list is true, then return.
+    if !form.is_connected()
+        || with_flags(form, |constructing_entry_list, _| *constructing_entry_list)?
+    {
+        return None;
+    }
+    // 6. If submitted from submit() method is false, then:
+    if !submitted_from_submit_method {
+        // 6.1. If form's firing submission events is true, then return.
+        // 6.2. Set form's firing submission events to true.
+        if with_flags(form, |_, firing_submission_events| {
+            std::mem::replace(firing_submission_events, true)
+        })? {
+            return None;
+        }
+        // 6.3. If the submitter element's no-validate state is false, then interactively validate the constraints of form and examine the result. If the result is negative, then set form's firing submission events to false and return.
+        let no_validate =
+            submitter_attribute("formnovalidate").is_some() || form.has_attribute("novalidate");
+        let should_continue = (no_validate || interactively_validate(form)) && {
+            // 6.4. Let submitterButton be null if submitter is form. Otherwise, let submitterButton be submitter.
+            // 6.5. Let shouldContinue be the result of firing an event named submit at form using SubmitEvent, with the submitter attribute initialized to submitterButton, the bubbles attribute initialized to true, and the cancelable attribute initialized to true.
+            let submitter_button =
+                (!submitter.is_same_node(form)).then(|| Rc::new(submitter.clone_ref()));
+            let event_init = EventInit {
+                bubbles: true,
+                cancelable: true,
+                composed: false,
+            };
+            let detail = EventDetail::Submit {
+                submitter: submitter_button,
+            };
+            form.clone_ref().dispatch_event(
+                &mut Event::trusted(EventType::Submit, event_init).with_detail(detail),
+            )
+        };
+        // 6.6. Set form's firing submission events to false.
+        with_flags(form, |_, firing_submission_events| {
+            *firing_submission_events = false
+        });
+        // 6.7. If shouldContinue is false, then return.
+        // 6.8. If form cannot navigate, then return.
+        if !should_continue || !form.is_connected() {
+            return None;
+        }
+    }
+    // 8. Let entry list be the result of constructing the entry list with form, submitter, and encoding.
+    // 9. If entry list is null, then return.
+    let entries = construct_entry_list(form, submitter)?.entries();
+    // 10. If form cannot navigate, then return.
+    if !form.is_connected() {
+        return None;
+    }
+    // 11. Let method be the submitter element's method.
+    let method = match submitter_attribute("formmethod") {
+        Some(method) => method_state(Some(&method)),
+        None => method_state(form.get_attribute("method")),
+    };
+    // 12. If method is dialog, then close the dialog that the form is in. Dialogs are not closed here, so there is nothing to do.
+    if method == "dialog" {
+        return None;
+    }
+    // 13. Let action be the submitter element's action.
+    // 14. If action is the empty string, let action be the URL of the form document.
+    // 15. Let parsed action be the result of encoding-parsing a URL given action, relative to submitter's node document.
+    let action = match submitter_attribute("formaction") {
+        Some(action) => action_url(submitter, Some(&action)),
+        None => action_url(form, form.get_attribute("action")),
+    };
+    // 17. Let scheme be the scheme of parsed action.
+    let scheme = scheme_of(&action).unwrap_or_default().to_ascii_lowercase();
+    // 18. Let enctype be the submitter element's enctype.
+    let enctype = match submitter_attribute("formenctype") {
+        Some(enctype) => enctype_state(Some(&enctype)),
+        None => enctype_state(form.get_attribute("enctype")),
+    };
+    // 19. Let formTarget be the submitter's formtarget attribute value if it has one, or else the form's target attribute value.
+    let target = submitter_attribute("formtarget")
+        .or_else(|| form.get_attribute("target").map(str::to_owned))
+        .filter(|target| !target.is_empty())
+        .unwrap_or_else(|| String::from("_self"));
+    let request = |url: String, method: &str, body: Option<(Vec<u8>, String)>| {
+        let (body, content_type) = body.unzip();
+        Some(NavigationRequest {
+            url,
+            method: method.to_owned(),
+            body,
+            content_type,
+            target: target.clone(),
+        })
+    };
+    // 25. Switch on scheme and method.
+    match (scheme.as_str(), method) {
+        // Mutate action URL.
+        ("http" | "https" | "data", "get") => {
+            let query = urlencode(&name_value_pairs(&entries));
+            request(with_query(&action, &query), "GET", None)
+        }
+        // Submit as entity body.
+        ("http" | "https", _) => {
+            let body = match enctype {
+                "multipart/form-data" => {
+                    let boundary = generate_boundary();
+                    let body = encode_multipart(&entries, &boundary);
+                    (body, format!("multipart/form-data; boundary={boundary}"))
+                }
+                "text/plain" => (
+                    encode_text_plain(&name_value_pairs(&entries)).into_bytes(),
+                    String::from("text/plain"),
+                ),
+                _ => (
+                    urlencode(&name_value_pairs(&entries)).into_bytes(),
+                    String::from(enctype),
+                ),
+            };
+            request(action, "POST", Some(body))
+        }
+        // Mail with headers.
+        ("mailto", "get") => {
+            let headers = urlencode(&name_value_pairs(&entries)).replace('+', "%20");
+            request(with_query(&action, &headers), "GET", None)
+        }
+        // Mail as body.
+        ("mailto", _) => {
+            let pairs = name_value_pairs(&entries);
+            let body = match enctype {
+                "text/plain" => percent_encode(&encode_text_plain(&pairs), is_default_encoded),
+                _ => urlencode(&pairs).replace('+', "%20"),
+            };
+            let (url, fragment) = split_fragment(&action);
+            let separator = match url.split_once('?') {
+                Some((_, query)) if !query.is_empty() => "&",
+                Some(_) => "",
+                None => "?",
+            };
+            request(
+                format!("{url}{separator}body={body}{fragment}"),
+                "GET",
+                None,
+            )
+        }
+        // Get action URL.
+        _ => request(action, "GET", None),
+    }
+}
+
+/// Runs a closure with the flags of a form that keep it from being submitted again while it is being submitted: whether it is constructing its entry list, and whether it is firing submission events. Returns `None` if the element is not a form.
+fn with_flags<T>(form: &Element, closure: impl FnOnce(&mut bool, &mut bool) -> T) -> Option<T> {
+    let base = form
+        .owner_document()?
+        .lookup_html_element_base(AsNode::cast(form).get_base_ptr())?;
+    let mut base = base.borrow_mut();
+    match &mut *base {
+        HTMLElementBase::Form {
+            constructing_entry_list,
+            firing_submission_events,
+            ..
+        } => Some(closure(constructing_entry_list, firing_submission_events)),
+        _ => None,
+    }
+}
+
+/// Converts entries to names and string values, with the names of files for values, and with every line break as a CR LF pair.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#convert-to-a-list-of-name-value-pairs)
+fn name_value_pairs(entries: &[(String, FormDataEntryValue)]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                FormDataEntryValue::String(value) => normalize_line_breaks(value),
+                FormDataEntryValue::File(file) => file.name().to_owned(),
+            };
+            (normalize_line_breaks(name), value)
+        })
+        .collect()
+}
+
+fn normalize_line_breaks(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', "\r\n")
+}
+
+/// Encodes pairs as `application/x-www-form-urlencoded`, like `name=value&other=a+b`.
+///
+/// [Reference](https://url.spec.whatwg.org/#concept-urlencoded-serializer)
+pub(crate) fn urlencode(pairs: &[(String, String)]) -> String {
+    let encode = |text: &str| {
+        percent_encode(text, |byte| {
+            !(byte.is_ascii_alphanumeric() || matches!(byte, b'*' | b'-' | b'.' | b'_' | b' '))
+        })
+        .replace(' ', "+")
+    };
+    pairs
+        .iter()
+        .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Encodes pairs as `text/plain`, with a `name=value` line for each.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#text/plain-encoding-algorithm)
+fn encode_text_plain(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(name, value)| format!("{name}={value}\r\n"))
+        .collect()
+}
+
+/// Encodes entries as `multipart/form-data`, with a part for each entry between boundaries.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#multipart-form-data-encoding-algorithm)
+fn encode_multipart(entries: &[(String, FormDataEntryValue)], boundary: &str) -> Vec<u8> {
+    // Quotes and line breaks in names and filenames are percent-encoded.
+    let escape = |text: &str| {
+        text.replace('\n', "%0A")
+            .replace('\r', "%0D")
+            .replace('"', "%22")
+    };
+    let mut body = vec![];
+    for (name, value) in entries {
+        let name = escape(&normalize_line_breaks(name));
+        body.extend_from_slice(
+            format!("--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"").as_bytes(),
+        );
+        match value {
+            FormDataEntryValue::String(value) => {
+                body.extend_from_slice(b"\r\n\r\n");
+                body.extend_from_slice(normalize_line_breaks(value).as_bytes());
+            }
+            FormDataEntryValue::File(file) => {
+                let content_type = match file.r#type() {
+                    "" => "application/octet-stream",
+                    content_type => content_type,
+                };
+                body.extend_from_slice(
+                    format!(
+                        "; filename=\"{}\"\r\nContent-Type: {content_type}\r\n\r\n",
+                        escape(file.name())
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(file.bytes());
+            }
+        }
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    body
+}
+
+/// Returns a boundary for `multipart/form-data` bodies, which is random so that it is unlikely to be in any of the entries.
+fn generate_boundary() -> String {
+    const CHARACTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    // Every RandomState is seeded differently.
+    let mut bits = RandomState::new().build_hasher().finish();
+    let suffix: String = (0..16)
+        .map(|_| {
+            let character = CHARACTERS[(bits % CHARACTERS.len() as u64) as usize];
+            bits /= CHARACTERS.len() as u64;
+            if bits == 0 {
+                bits = RandomState::new().build_hasher().finish();
+            }
+            character as char
+        })
+        .collect();
+    format!("----FormBoundary{suffix}")
+}
+
+/// UTF-8 percent-encodes the bytes of a text that are in an encode set.
+///
+/// [Reference](https://url.spec.whatwg.org/#string-utf-8-percent-encode)
+fn percent_encode(text: &str, is_encoded: impl Fn(u8) -> bool) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match is_encoded(byte) {
+            true => encoded.push_str(&format!("%{byte:02X}")),
+            false => encoded.push(byte as char),
+        }
+    }
+    encoded
+}
+
+/// [Reference](https://url.spec.whatwg.org/#default-encode-set)
+fn is_default_encoded(byte: u8) -> bool {
+    !(0x20..=0x7e).contains(&byte) || b" \"#<>?`{}".contains(&byte)
+}
+
+/// Splits a URL before its fragment, if it has one.
+fn split_fragment(url: &str) -> (&str, &str) {
+    url.split_at(url.find('#').unwrap_or(url.len()))
+}
+
+/// Replaces the query of a URL, keeping its fragment.
+fn with_query(url: &str, query: &str) -> String {
+    let (url, fragment) = split_fragment(url);
+    let url = url.split('?').next().unwrap_or_default();
+    format!("{url}?{query}{fragment}")
+}
@@ -31,11 +31,11 @@ impl AsElement for HTMLAudioElement {
 }
 impl InnerHtml for HTMLAudioElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLAudioElement {}
@@ -41,11 +41,11 @@ impl AsElement for HTMLBaseElement {
 }
 impl InnerHtml for HTMLBaseElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLBaseElement {}
@@ -1,10 +1,8 @@
 use crate::{
     tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, HTMLFormElement, InnerHtml, NodeListOf,
+    ConstraintValidation, DOMException, HTMLElement, HTMLFormElement, InnerHtml, NodeListOf,
 };
 
-pub struct ValidityState {}
-
 use super::HTMLLabelElement;
 
 pub struct HTMLButtonElement {
@@ -19,8 +17,11 @@ impl HTMLButtonElement {
     pub fn set_disabled(&mut self, value: bool) {
         todo!()
     }
+    /// Returns the form that the button is associated with, which is set by its `form` attribute or else is its nearest ancestor form.
+    ///
+    /// MDN Reference: [`HTMLButtonElement.form`](https://developer.mozilla.org/docs/Web/API/HTMLButtonElement/form)
     pub fn form(&self) -> Option<HTMLFormElement> {
-        todo!()
+        super::form_associated::form(AsElement::cast(self))
     }
     pub fn form_action(&self) -> &str {
         todo!()
@@ -67,34 +68,12 @@ impl HTMLButtonElement {
     pub fn set_type(&mut self, value: &str) -> &str {
         todo!()
     }
-    pub fn validation_message(&self) -> &str {
-        todo!()
-    }
-    pub fn validity(&self) -> ValidityState {
-        todo!()
-    }
     pub fn value(&self) -> &str {
         todo!()
     }
     pub fn set_value(&mut self, value: &str) {
         todo!()
     }
-    pub fn will_validate(&self) -> bool {
-        todo!()
-    }
-}
-
-// Methods.
-impl HTMLButtonElement {
-    pub fn check_validity(&self) -> bool {
-        todo!()
-    }
-    pub fn report_validity(&self) -> bool {
-        todo!()
-    }
-    pub fn set_custom_validity(&mut self, error: &str) {
-        todo!()
-    }
 }
 
 impl AsHTMLElement for HTMLButtonElement {
@@ -117,15 +96,16 @@ impl AsElement for HTMLButtonElement {
 }
 impl InnerHtml for HTMLButtonElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLButtonElement {}
 impl AsChildNode for HTMLButtonElement {}
+impl ConstraintValidation for HTMLButtonElement {}
 impl AsNode for HTMLButtonElement {
     fn cast(&self) -> &crate::Node {
         AsNode::cast(&self.html_element)
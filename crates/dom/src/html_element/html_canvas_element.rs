@@ -1,14 +1,37 @@
-use std::any::Any;
+use std::{any::Any, rc::Rc};
 
 use crate::{
-    tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml, MediaStream,
+    css::compute_font, layout::Font, tag::Tag, AsChildNode, AsElement, AsEventTarget,
+    AsHTMLElement, AsNode, AsParentNode, DOMException, FontProvider, HTMLElement, InnerHtml,
+    MediaStream, Viewport,
 };
 
 pub struct CanvasRenderingContext2DSettings;
 pub struct ImageBitmapRenderingContextSettings;
 pub struct ImageBitmapRenderingContext;
-pub struct CanvasRenderingContext2D;
+
+/// A context for drawing on a canvas in two dimensions. Only text measurement is supported.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CanvasRenderingContext2D)
+pub struct CanvasRenderingContext2D {
+    fonts: Rc<dyn FontProvider>,
+    /// The viewport of the canvas's document, which viewport-relative font sizes are relative to.
+    viewport: Viewport,
+    font: String,
+    computed_font: Font,
+}
+
+/// The dimensions of a piece of text, as measured by [`CanvasRenderingContext2D::measure_text`].
+///
+/// No glyph outlines are read, so the actual bounding box of the text is taken to be its advance, and the ascent and descent of its font.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TextMetrics)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextMetrics {
+    width: f64,
+    ascent: f64,
+    descent: f64,
+}
 pub struct WebGLContextAttributes;
 pub struct WebGLRenderingContext;
 pub struct RenderingContext;
@@ -23,17 +46,28 @@ pub struct HTMLCanvasElement {
 
 // Properties.
 impl HTMLCanvasElement {
+    /// Returns the height of the canvas in pixels, as written in the `height` attribute, or 150 if it is missing or invalid.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLCanvasElement/height)
     pub fn height(&self) -> usize {
-        todo!()
+        self.dimension("height", 150)
     }
     pub fn set_height(&mut self, value: usize) {
-        todo!()
+        self.set_attribute("height", &value.to_string())
     }
+    /// Returns the width of the canvas in pixels, as written in the `width` attribute, or 300 if it is missing or invalid.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLCanvasElement/width)
     pub fn width(&self) -> usize {
-        todo!()
+        self.dimension("width", 300)
     }
     pub fn set_width(&mut self, value: usize) {
-        todo!()
+        self.set_attribute("width", &value.to_string())
+    }
+    fn dimension(&self, name: &str, default: usize) -> usize {
+        self.get_attribute(name)
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(default)
     }
 }
 
@@ -45,11 +79,26 @@ impl HTMLCanvasElement {
     pub fn get_context(&self, context_id: &str, options: impl Any) -> Option<RenderingContext> {
         todo!()
     }
+    /// Returns a context for drawing on the canvas in two dimensions, which measures text with the fonts of the canvas's document. Returns `None` if the canvas has no owner document.
+    ///
+    /// Every call returns a new context, with the default state.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLCanvasElement/getContext)
     pub fn get_2d_context(
         &self,
         options: Option<CanvasRenderingContext2DSettings>,
     ) -> Option<CanvasRenderingContext2D> {
-        todo!()
+        let document = self.owner_document()?;
+        let fonts = document.state().font_provider.clone();
+        let viewport = document.state().viewport.clone();
+        let font = String::from("10px sans-serif");
+        let style = compute_font(&font, &viewport)?;
+        Some(CanvasRenderingContext2D {
+            computed_font: Font::new(&fonts, &style),
+            fonts,
+            viewport,
+            font,
+        })
     }
     pub fn get_bitmaprenderer_context(
         &self,
@@ -85,6 +134,93 @@ impl HTMLCanvasElement {
     }
 }
 
+impl CanvasRenderingContext2D {
+    /// Returns the font that text is measured with, as last set with [`CanvasRenderingContext2D::set_font`]. The default is `10px sans-serif`.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CanvasRenderingContext2D/font)
+    pub fn font(&self) -> &str {
+        &self.font
+    }
+    /// Sets the font that text is measured with, as a value of the CSS `font` shorthand. Relative font sizes are relative to 10px. Values that are not fonts are ignored.
+    pub fn set_font(&mut self, value: &str) {
+        let Some(style) = compute_font(value, &self.viewport) else {
+            return;
+        };
+        self.font = value.trim().to_owned();
+        self.computed_font = Font::new(&self.fonts, &style);
+    }
+    /// Measures a piece of text in the current font.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/CanvasRenderingContext2D/measureText)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLCanvasElement};
+    ///
+    /// let mut document = Document::new();
+    /// let canvas = HTMLCanvasElement::try_from(document.create_element("canvas")).unwrap();
+    /// let mut context = canvas.get_2d_context(None).unwrap();
+    /// assert_eq!(context.measure_text("Hello").width(), 25.0);
+    ///
+    /// context.set_font("bold 20px monospace");
+    /// let metrics = context.measure_text("Hello");
+    /// assert_eq!(metrics.width(), 60.0);
+    /// assert_eq!(metrics.font_bounding_box_ascent(), 16.0);
+    /// ```
+    pub fn measure_text(&self, text: &str) -> TextMetrics {
+        let metrics = self.computed_font.metrics();
+        TextMetrics {
+            width: self.computed_font.text_width(text),
+            ascent: metrics.ascent,
+            descent: metrics.descent,
+        }
+    }
+}
+
+impl TextMetrics {
+    /// Returns the advance width of the text.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TextMetrics/width)
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+    /// Returns the distance from the start of the text to the left edge of its bounding box.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TextMetrics/actualBoundingBoxLeft)
+    pub fn actual_bounding_box_left(&self) -> f64 {
+        0.0
+    }
+    /// Returns the distance from the start of the text to the right edge of its bounding box.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TextMetrics/actualBoundingBoxRight)
+    pub fn actual_bounding_box_right(&self) -> f64 {
+        self.width
+    }
+    /// Returns the distance from the baseline to the top of the bounding box of the text.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TextMetrics/actualBoundingBoxAscent)
+    pub fn actual_bounding_box_ascent(&self) -> f64 {
+        self.ascent
+    }
+    /// Returns the distance from the baseline to the bottom of the bounding box of the text.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TextMetrics/actualBoundingBoxDescent)
+    pub fn actual_bounding_box_descent(&self) -> f64 {
+        self.descent
+    }
+    /// Returns the ascent of the font.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TextMetrics/fontBoundingBoxAscent)
+    pub fn font_bounding_box_ascent(&self) -> f64 {
+        self.ascent
+    }
+    /// Returns the descent of the font.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/TextMetrics/fontBoundingBoxDescent)
+    pub fn font_bounding_box_descent(&self) -> f64 {
+        self.descent
+    }
+}
+
 impl AsHTMLElement for HTMLCanvasElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -105,11 +241,11 @@ impl AsElement for HTMLCanvasElement {
 }
 impl InnerHtml for HTMLCanvasElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLCanvasElement {}
@@ -149,7 +285,7 @@ impl TryFrom<HTMLElement> for HTMLCanvasElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Canvas) {
             Ok(HTMLCanvasElement {
                 html_element: value,
             })
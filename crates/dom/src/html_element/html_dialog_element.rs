@@ -55,11 +55,11 @@ impl AsElement for HTMLDialogElement {
 }
 impl InnerHtml for HTMLDialogElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLDialogElement {}
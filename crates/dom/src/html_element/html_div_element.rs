@@ -43,11 +43,11 @@ impl AsElement for HTMLDivElement {
 }
 impl InnerHtml for HTMLDivElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLDivElement {}
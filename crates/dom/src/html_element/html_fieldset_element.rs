@@ -1,6 +1,6 @@
 use crate::{
     tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLCollection, HTMLElement, HTMLFormElement, InnerHtml, ValidityState,
+    ConstraintValidation, DOMException, HTMLCollection, HTMLElement, HTMLFormElement, InnerHtml,
 };
 pub struct HTMLFieldsetElement {
     html_element: HTMLElement,
@@ -16,8 +16,11 @@ impl HTMLFieldsetElement {
     pub fn elements(&self) -> HTMLCollection {
         todo!()
     }
+    /// Returns the form that the fieldset is associated with, which is set by its `form` attribute or else is its nearest ancestor form.
+    ///
+    /// MDN Reference: [`HTMLFieldSetElement.form`](https://developer.mozilla.org/docs/Web/API/HTMLFieldSetElement/form)
     pub fn form(&self) -> Option<HTMLFormElement> {
-        todo!()
+        super::form_associated::form(AsElement::cast(self))
     }
     pub fn name(&self) -> &str {
         todo!()
@@ -28,27 +31,6 @@ impl HTMLFieldsetElement {
     pub fn r#type(&self) -> &str {
         todo!()
     }
-    pub fn validation_message(&self) -> &str {
-        todo!()
-    }
-    pub fn validity(&self) -> ValidityState {
-        todo!()
-    }
-    pub fn will_validate(&self) -> bool {
-        todo!()
-    }
-}
-
-impl HTMLFieldsetElement {
-    pub fn check_validity(&self) -> bool {
-        todo!()
-    }
-    pub fn report_validity(&self) -> bool {
-        todo!()
-    }
-    pub fn set_custom_validity(&mut self, error: &str) {
-        todo!()
-    }
 }
 
 impl AsHTMLElement for HTMLFieldsetElement {
@@ -71,15 +53,16 @@ impl AsElement for HTMLFieldsetElement {
 }
 impl InnerHtml for HTMLFieldsetElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLFieldsetElement {}
 impl AsChildNode for HTMLFieldsetElement {}
+impl ConstraintValidation for HTMLFieldsetElement {}
 impl AsNode for HTMLFieldsetElement {
     fn cast(&self) -> &crate::Node {
         AsNode::cast(&self.html_element)
@@ -115,7 +98,7 @@ impl TryFrom<HTMLElement> for HTMLFieldsetElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Fieldset) {
             Ok(HTMLFieldsetElement {
                 html_element: value,
             })
@@ -1,10 +1,172 @@
 use std::ops::Index;
 
 use crate::{
-    tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, DOMTokenList, Element, HTMLElement, InnerHtml,
+    domitem::DOMItem, tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode,
+    AsParentNode, DOMException, DOMTokenList, Element, HTMLElement, InnerHtml,
 };
-pub struct HTMLFormControlsCollection;
+
+use super::{
+    form_associated::form_controls,
+    form_submission::{self, NavigationRequest},
+    html_input_element,
+    input_type::InputType,
+};
+
+/// A collection of the controls of a form, in tree order. The collection is live, so it always reflects the controls that currently belong to the form.
+///
+/// MDN Reference: [`HTMLFormControlsCollection`](https://developer.mozilla.org/docs/Web/API/HTMLFormControlsCollection)
+pub struct HTMLFormControlsCollection {
+    form: Element,
+}
+
+impl HTMLFormControlsCollection {
+    /// Returns the number of controls in the collection.
+    pub fn len(&self) -> usize {
+        form_controls(&self.form).len()
+    }
+    /// Returns true if the form has no controls.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the control at an index in the collection.
+    pub fn item(&self, index: usize) -> Option<Element> {
+        form_controls(&self.form).into_iter().nth(index)
+    }
+    /// Returns the control with an ID or name. If several controls have it, a [`RadioNodeList`] of all of them is returned instead.
+    ///
+    /// MDN Reference: [`HTMLFormControlsCollection.namedItem()`](https://developer.mozilla.org/docs/Web/API/HTMLFormControlsCollection/namedItem)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLFormElement, InnerHtml, RadioNodeListOrElement};
+    ///
+    /// let document = Document::new();
+    /// let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    /// form.set_inner_html(r#"
+    ///     <input name="size" type="radio" value="small">
+    ///     <input name="size" type="radio" value="large">
+    ///     <textarea id="comment"></textarea>
+    /// "#).unwrap();
+    ///
+    /// let elements = form.elements();
+    /// assert_eq!(elements.len(), 3);
+    /// match elements.named_item("size") {
+    ///     Some(RadioNodeListOrElement::RadioNodeList(list)) => assert_eq!(list.len(), 2),
+    ///     _ => panic!("Expected a list of radio buttons."),
+    /// }
+    /// assert!(matches!(
+    ///     elements.named_item("comment"),
+    ///     Some(RadioNodeListOrElement::Element(_))
+    /// ));
+    /// ```
+    pub fn named_item(&self, name: &str) -> Option<RadioNodeListOrElement> {
+        let list = RadioNodeList {
+            form: self.form.clone_ref(),
+            name: name.to_owned(),
+        };
+        let mut items = list.items();
+        match items.len() {
+            0 => None,
+            1 => items.pop().map(RadioNodeListOrElement::Element),
+            _ => Some(RadioNodeListOrElement::RadioNodeList(list)),
+        }
+    }
+    /// Returns an iterator over the controls in the collection.
+    pub fn iter(&self) -> std::vec::IntoIter<Element> {
+        form_controls(&self.form).into_iter()
+    }
+}
+
+/// A live list of the controls of a form that share an ID or name.
+///
+/// MDN Reference: [`RadioNodeList`](https://developer.mozilla.org/docs/Web/API/RadioNodeList)
+pub struct RadioNodeList {
+    form: Element,
+    name: String,
+}
+
+impl RadioNodeList {
+    fn items(&self) -> Vec<Element> {
+        if self.name.is_empty() {
+            return vec![];
+        }
+        let name = Some(self.name.as_str());
+        form_controls(&self.form)
+            .into_iter()
+            .filter(|element| {
+                element.get_attribute("id") == name || element.get_attribute("name") == name
+            })
+            .collect()
+    }
+    /// Returns the number of controls in the list.
+    pub fn len(&self) -> usize {
+        self.items().len()
+    }
+    /// Returns true if no control has the ID or name anymore.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the control at an index in the list.
+    pub fn item(&self, index: usize) -> Option<Element> {
+        self.items().into_iter().nth(index)
+    }
+    /// Returns an iterator over the controls in the list.
+    pub fn iter(&self) -> std::vec::IntoIter<Element> {
+        self.items().into_iter()
+    }
+    /// Returns the value of the first checked radio button in the list, or an empty string if none is checked.
+    ///
+    /// MDN Reference: [`RadioNodeList.value`](https://developer.mozilla.org/docs/Web/API/RadioNodeList/value)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLFormElement, InnerHtml, RadioNodeListOrElement};
+    ///
+    /// let document = Document::new();
+    /// let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    /// form.set_inner_html(r#"
+    ///     <input name="size" type="radio" value="small" checked>
+    ///     <input name="size" type="radio" value="large">
+    /// "#).unwrap();
+    ///
+    /// let Some(RadioNodeListOrElement::RadioNodeList(sizes)) = form.elements().named_item("size") else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(sizes.value(), "small");
+    /// sizes.set_value("large");
+    /// assert_eq!(sizes.value(), "large");
+    /// ```
+    pub fn value(&self) -> String {
+        self.radio_buttons()
+            .find(html_input_element::checkedness)
+            .map(|radio| radio.get_attribute("value").unwrap_or("on").to_owned())
+            .unwrap_or_default()
+    }
+    /// Checks the first radio button in the list with a value. Radio buttons without a `value` attribute have the value `on`.
+    ///
+    /// MDN Reference: [`RadioNodeList.value`](https://developer.mozilla.org/docs/Web/API/RadioNodeList/value)
+    pub fn set_value(&self, value: &str) {
+        let radio = self
+            .radio_buttons()
+            .find(|radio| match radio.get_attribute("value") {
+                Some(radio_value) => radio_value == value,
+                None => value == "on",
+            });
+        if let Some(radio) = radio {
+            html_input_element::set_checked(&radio, true);
+        }
+    }
+    fn radio_buttons(&self) -> impl Iterator<Item = Element> {
+        self.items()
+            .into_iter()
+            .filter(|element| element.base().tag == Tag::Input)
+            .filter(|element| InputType::of(element) == InputType::Radio)
+    }
+}
+
+/// The result of [`HTMLFormControlsCollection::named_item`], which is a single control, or a list when several controls share the name.
+pub enum RadioNodeListOrElement {
+    RadioNodeList(RadioNodeList),
+    Element(Element),
+}
 
 pub struct HTMLFormElement {
     html_element: HTMLElement,
@@ -18,11 +180,17 @@ impl HTMLFormElement {
     pub fn set_accept_charset(&mut self, value: &str) {
         todo!()
     }
-    pub fn action(&self) -> &str {
-        todo!()
+    /// Returns the URL that the form is submitted to, which is its `action` attribute resolved against the document's URL, or the document's URL if the attribute is missing or empty.
+    ///
+    /// MDN Reference: [`HTMLFormElement.action`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/action)
+    pub fn action(&self) -> String {
+        form_submission::action_url(AsElement::cast(self), self.get_attribute("action"))
     }
+    /// Sets the URL that the form is submitted to.
+    ///
+    /// MDN Reference: [`HTMLFormElement.action`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/action)
     pub fn set_action(&mut self, value: &str) {
-        todo!()
+        self.set_attribute("action", value)
     }
     pub fn auto_complete(&self) -> &str {
         todo!()
@@ -30,29 +198,72 @@ impl HTMLFormElement {
     pub fn set_auto_complete(&mut self, value: &str) {
         todo!()
     }
+    /// Returns the controls of the form, which are its listed elements other than image buttons.
+    ///
+    /// MDN Reference: [`HTMLFormElement.elements`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/elements)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLFormElement};
+    ///
+    /// let document = Document::new();
+    /// let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    /// let mut input = document.create_element("input");
+    /// form.append_child(&mut input).unwrap();
+    ///
+    /// let elements = form.elements();
+    /// assert_eq!(elements.len(), 1);
+    ///
+    /// // The collection is live.
+    /// form.append_child(&mut document.create_element("select")).unwrap();
+    /// assert_eq!(elements.len(), 2);
+    /// assert!(elements.item(0).unwrap() == input);
+    /// ```
     pub fn elements(&self) -> HTMLFormControlsCollection {
-        todo!()
+        HTMLFormControlsCollection {
+            form: AsElement::cast(self).clone_ref(),
+        }
     }
+    /// The same as [`HTMLFormElement::enctype`].
+    ///
+    /// MDN Reference: [`HTMLFormElement.encoding`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/encoding)
     pub fn encoding(&self) -> &str {
-        todo!()
+        self.enctype()
     }
+    /// The same as [`HTMLFormElement::set_enctype`].
+    ///
+    /// MDN Reference: [`HTMLFormElement.encoding`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/encoding)
     pub fn set_encoding(&mut self, value: &str) {
-        todo!()
+        self.set_enctype(value)
     }
+    /// Returns the MIME type that the form's entries are encoded with when it is submitted with the `post` method: `application/x-www-form-urlencoded`, `multipart/form-data` or `text/plain`.
+    ///
+    /// MDN Reference: [`HTMLFormElement.enctype`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/enctype)
     pub fn enctype(&self) -> &str {
-        todo!()
+        form_submission::enctype_state(self.get_attribute("enctype"))
     }
+    /// Sets the MIME type that the form's entries are encoded with.
+    ///
+    /// MDN Reference: [`HTMLFormElement.enctype`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/enctype)
     pub fn set_enctype(&mut self, value: &str) {
-        todo!()
+        self.set_attribute("enctype", value)
     }
+    /// Returns the number of controls in the form.
+    ///
+    /// MDN Reference: [`HTMLFormElement.length`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/length)
     pub fn len(&self) -> usize {
-        todo!()
+        self.elements().len()
     }
+    /// Returns the method that the form is submitted with: `get`, `post` or `dialog`.
+    ///
+    /// MDN Reference: [`HTMLFormElement.method`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/method)
     pub fn method(&self) -> &str {
-        todo!()
+        form_submission::method_state(self.get_attribute("method"))
     }
+    /// Sets the method that the form is submitted with.
+    ///
+    /// MDN Reference: [`HTMLFormElement.method`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/method)
     pub fn set_method(&mut self, value: &str) {
-        todo!()
+        self.set_attribute("method", value)
     }
     pub fn name(&self) -> &str {
         todo!()
@@ -60,11 +271,20 @@ impl HTMLFormElement {
     pub fn set_name(&mut self, value: &str) {
         todo!()
     }
+    /// Returns true if the form's controls are not validated when it is submitted, which its `novalidate` attribute says.
+    ///
+    /// MDN Reference: [`HTMLFormElement.noValidate`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/noValidate)
     pub fn no_validate(&self) -> bool {
-        todo!()
+        self.has_attribute("novalidate")
     }
+    /// Sets whether the form's controls are validated when it is submitted.
+    ///
+    /// MDN Reference: [`HTMLFormElement.noValidate`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/noValidate)
     pub fn set_no_validate(&mut self, value: bool) {
-        todo!()
+        match value {
+            true => self.set_attribute("novalidate", ""),
+            false => self.remove_attribute("novalidate"),
+        }
     }
     pub fn rel(&self) -> &str {
         todo!()
@@ -75,30 +295,106 @@ impl HTMLFormElement {
     pub fn rel_list(&self) -> DOMTokenList {
         todo!()
     }
+    /// Returns the name of the navigable that the form is submitted in, from its `target` attribute.
+    ///
+    /// MDN Reference: [`HTMLFormElement.target`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/target)
     pub fn target(&self) -> &str {
-        todo!()
+        self.get_attribute("target").unwrap_or("")
     }
+    /// Sets the name of the navigable that the form is submitted in.
+    ///
+    /// MDN Reference: [`HTMLFormElement.target`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/target)
     pub fn set_target(&mut self, value: &str) {
-        todo!()
+        self.set_attribute("target", value)
     }
 }
 
 // "Methods"
 impl HTMLFormElement {
+    /// Returns true if all the controls of the form meet their constraints. Otherwise, an `invalid` event is fired at each control that does not, and false is returned.
+    ///
+    /// MDN Reference: [`HTMLFormElement.checkValidity()`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/checkValidity)
     pub fn check_validity(&self) -> bool {
-        todo!()
+        super::constraint_validation::statically_validate(AsElement::cast(self)).is_ok()
     }
+    /// Works like [`HTMLFormElement::check_validity`], but also reports the validity of every control, after which they match `:user-valid` or `:user-invalid`.
+    ///
+    /// MDN Reference: [`HTMLFormElement.reportValidity()`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/reportValidity)
     pub fn report_validity(&self) -> bool {
-        todo!()
-    }
-    pub fn request_submit(&mut self, submitter: Option<HTMLElement>) {
-        todo!()
+        super::constraint_validation::interactively_validate(AsElement::cast(self))
+    }
+    /// Submits the form like a submit button would: the controls are validated, and a `submit` event that can be canceled is fired first. Returns the request to navigate that the submission makes, if it makes one.
+    ///
+    /// MDN Reference: [`HTMLFormElement.requestSubmit()`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/requestSubmit)
+    /// # Errors
+    /// - Returns a `TypeError` DOMException if the submitter is not a submit button.
+    /// - Returns a `NotFoundError` DOMException if the submitter does not belong to the form.
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLButtonElement, HTMLFormElement, InnerHtml};
+    ///
+    /// let mut document = Document::parse_html_unsafe("<body></body>");
+    /// document.set_url("https://example.com/search/");
+    /// let mut body = document.first_element_child().unwrap().last_element_child().unwrap();
+    /// let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    /// form.set_inner_html("<input name=q value='a b'><input name=lang value=en>").unwrap();
+    /// body.append_child(&mut form).unwrap();
+    ///
+    /// let mut button = document.create_element("button");
+    /// button.set_attribute("formaction", "results?page=2#top");
+    /// form.append_child(&mut button).unwrap();
+    ///
+    /// let request = form.request_submit(Some(button)).unwrap().unwrap();
+    /// assert_eq!(request.method, "GET");
+    /// assert_eq!(request.url, "https://example.com/search/results?q=a+b&lang=en#top");
+    /// ```
+    pub fn request_submit(
+        &mut self,
+        submitter: Option<HTMLElement>,
+    ) -> Result<Option<NavigationRequest>, DOMException> {
+        let form = AsElement::cast(self);
+        let submitter = match &submitter {
+            Some(submitter) => {
+                let submitter = AsElement::cast(submitter);
+                form_submission::check_submitter(form, submitter)?;
+                submitter
+            }
+            None => form,
+        };
+        Ok(form_submission::submit(form, submitter, false))
     }
     pub fn reset(&mut self) {
         todo!()
     }
-    pub fn submit(&mut self) {
-        todo!()
+    /// Submits the form without validating its controls or firing a `submit` event, and returns the request to navigate that the submission makes, if it makes one. Forms that are not in a document are not submitted.
+    ///
+    /// MDN Reference: [`HTMLFormElement.submit()`](https://developer.mozilla.org/docs/Web/API/HTMLFormElement/submit)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLFormElement, InnerHtml};
+    ///
+    /// let mut document = Document::parse_html_unsafe("<body></body>");
+    /// document.set_url("https://example.com/");
+    /// let mut body = document.first_element_child().unwrap().last_element_child().unwrap();
+    /// let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    /// form.set_inner_html("<input name=name value=Ferris><input name=required required>").unwrap();
+    /// form.set_action("/signup");
+    /// form.set_method("post");
+    /// body.append_child(&mut form).unwrap();
+    ///
+    /// let request = form.submit().unwrap();
+    /// assert_eq!(request.url, "https://example.com/signup");
+    /// assert_eq!(request.method, "POST");
+    /// assert_eq!(request.body.unwrap(), b"name=Ferris&required=");
+    /// assert_eq!(
+    ///     request.content_type.as_deref(),
+    ///     Some("application/x-www-form-urlencoded")
+    /// );
+    /// ```
+    pub fn submit(&mut self) -> Option<NavigationRequest> {
+        let form = AsElement::cast(self);
+        form_submission::submit(form, form, true)
     }
 }
 
@@ -138,11 +434,11 @@ impl AsElement for HTMLFormElement {
 }
 impl InnerHtml for HTMLFormElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLFormElement {}
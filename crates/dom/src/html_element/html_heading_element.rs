@@ -36,11 +36,11 @@ impl AsElement for HTMLHeadingElement {
 }
 impl InnerHtml for HTMLHeadingElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLHeadingElement {}
@@ -1,11 +1,229 @@
 use crate::{
-    tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    css::{element_children, evaluate_list},
+    domitem::DOMItem,
+    loader::resolve_url,
+    tag::Tag,
+    AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode, DOMException,
+    Element, HTMLElement, InnerHtml, Viewport,
 };
 pub struct HTMLImageElement {
     html_element: HTMLElement,
 }
 
+// Properties.
+impl HTMLImageElement {
+    /// Returns the URL of the image, as written in the `src` attribute.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLImageElement/src)
+    pub fn src(&self) -> &str {
+        self.get_attribute("src").unwrap_or_default()
+    }
+    pub fn set_src(&mut self, value: &str) {
+        self.set_attribute("src", value)
+    }
+    /// Returns the candidate images for different pixel densities or widths, as written in the `srcset` attribute.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLImageElement/srcset)
+    pub fn srcset(&self) -> &str {
+        self.get_attribute("srcset").unwrap_or_default()
+    }
+    pub fn set_srcset(&mut self, value: &str) {
+        self.set_attribute("srcset", value)
+    }
+    /// Returns the URL of the image that is selected for the current viewport, or an empty string if there is none.
+    ///
+    /// In a `<picture>`, the first `<source>` before the image whose `media` matches the viewport and whose `type` is supported is selected. Otherwise the image's own `srcset` and `src` are used. From a `srcset`, the candidate with the smallest pixel density that is at least the device pixel ratio is chosen.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLImageElement/currentSrc)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLImageElement, InnerHtml, Viewport, Window};
+    ///
+    /// let mut document = Document::new();
+    /// let mut picture = document.create_element("picture");
+    /// picture
+    ///     .set_inner_html(
+    ///         "<source media='(max-width: 600px)' srcset='small.png'>
+    ///         <source type='image/x-unknown' srcset='unknown.img'>",
+    ///     )
+    ///     .unwrap();
+    /// let mut image = HTMLImageElement::try_from(document.create_element("img")).unwrap();
+    /// image.set_src("large.png");
+    /// image.set_srcset("large@2x.png 2x");
+    /// picture.append_child(&mut image).unwrap();
+    /// document.append_child(&mut picture).unwrap();
+    /// assert_eq!(image.current_src(), "large.png");
+    ///
+    /// let mut window = Window::new(document);
+    /// window.set_viewport(Viewport { device_pixel_ratio: 2.0, ..Default::default() });
+    /// assert_eq!(image.current_src(), "large@2x.png");
+    ///
+    /// window.set_viewport(Viewport { width: 400.0, ..Default::default() });
+    /// assert_eq!(image.current_src(), "small.png");
+    /// ```
+    pub fn current_src(&self) -> String {
+        let Some(document) = self.owner_document() else {
+            return String::new();
+        };
+        let viewport = document.state().viewport.clone();
+        let url = self
+            .selected_source(&viewport)
+            .or_else(|| select_candidate(self.srcset(), Some(self.src()), &viewport))
+            .unwrap_or_default();
+        match url.is_empty() {
+            true => url,
+            false => resolve_url(&document.state().url, &url),
+        }
+    }
+
+    /// Returns the image candidate from the first matching `<source>` in the image's `<picture>`.
+    fn selected_source(&self, viewport: &Viewport) -> Option<String> {
+        let parent = self.parent_element()?;
+        if parent.base().tag != Tag::Picture {
+            return None;
+        }
+        let image: &Element = AsElement::cast(self);
+        for child in element_children(&parent) {
+            if child == *image {
+                break;
+            }
+            if child.base().tag != Tag::Source {
+                continue;
+            }
+            let srcset = child.get_attribute("srcset").unwrap_or_default();
+            let media_matches = child
+                .get_attribute("media")
+                .is_none_or(|media| evaluate_list(media, viewport));
+            let type_supported = child
+                .get_attribute("type")
+                .is_none_or(|kind| kind.is_empty() || is_supported_image_type(kind));
+            if srcset.trim().is_empty() || !media_matches || !type_supported {
+                continue;
+            }
+            return select_candidate(srcset, None, viewport);
+        }
+        None
+    }
+}
+
+fn is_supported_image_type(kind: &str) -> bool {
+    let kind = kind.split(';').next().unwrap_or_default().trim();
+    [
+        "image/apng",
+        "image/avif",
+        "image/bmp",
+        "image/gif",
+        "image/jpeg",
+        "image/png",
+        "image/svg+xml",
+        "image/webp",
+        "image/x-icon",
+    ]
+    .iter()
+    .any(|supported| supported.eq_ignore_ascii_case(kind))
+}
+
+/// Selects the image candidate from a `srcset`, with a `src` as a fallback candidate of density 1.
+///
+/// Width descriptors, like `640w`, are taken relative to the width of the viewport.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/images.html#select-an-image-source)
+fn select_candidate(srcset: &str, src: Option<&str>, viewport: &Viewport) -> Option<String> {
+    let positive_integer = |value: &str| value.parse::<u32>().ok().filter(|value| *value > 0);
+    let mut candidates: Vec<(String, f64)> = parse_srcset(srcset)
+        .into_iter()
+        .filter_map(|(url, descriptors)| {
+            let density = match descriptors.as_slice() {
+                [] => 1.0,
+                [descriptor] => match descriptor.strip_suffix('x') {
+                    Some(density) => density
+                        .parse()
+                        .ok()
+                        .filter(|density: &f64| *density >= 0.0)?,
+                    None => {
+                        positive_integer(descriptor.strip_suffix('w')?)? as f64 / viewport.width
+                    }
+                },
+                // A height descriptor is only allowed after a width descriptor, and does not change the density.
+                [width, height] => {
+                    positive_integer(height.strip_suffix('h')?)?;
+                    positive_integer(width.strip_suffix('w')?)? as f64 / viewport.width
+                }
+                _ => return None,
+            };
+            Some((url, density))
+        })
+        .collect();
+    if let Some(src) = src.filter(|src| !src.is_empty()) {
+        if !candidates.iter().any(|(_, density)| *density == 1.0) {
+            candidates.push((src.to_owned(), 1.0));
+        }
+    }
+    let ratio = viewport.device_pixel_ratio;
+    let sufficient = candidates
+        .iter()
+        .filter(|(_, density)| *density >= ratio)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    sufficient
+        .or_else(|| candidates.iter().max_by(|a, b| a.1.total_cmp(&b.1)))
+        .map(|(url, _)| url.clone())
+}
+
+/// Splits a `srcset` into its image candidates, each a URL and its descriptors. URLs may contain commas, like `data:` URLs do, except at their end.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute)
+fn parse_srcset(input: &str) -> Vec<(String, Vec<String>)> {
+    let mut candidates = vec![];
+    let mut chars = input.chars().peekable();
+    loop {
+        // 1. Skip the whitespace and commas before a candidate.
+        while chars
+            .next_if(|char| char.is_ascii_whitespace() || *char == ',')
+            .is_some()
+        {}
+        if chars.peek().is_none() {
+            return candidates;
+        }
+        // 2. The URL runs until whitespace, and commas at its end separate it from the next candidate.
+        let mut url = String::new();
+        while let Some(char) = chars.next_if(|char| !char.is_ascii_whitespace()) {
+            url.push(char);
+        }
+        let mut descriptors = vec![];
+        if url.ends_with(',') {
+            url.truncate(url.trim_end_matches(',').len());
+        } else {
+            // 3. Descriptors are separated by whitespace, and end at a comma outside of parentheses.
+            let mut descriptor = String::new();
+            let mut in_parens = false;
+            for char in chars.by_ref() {
+                match char {
+                    ')' if in_parens => {
+                        in_parens = false;
+                        descriptor.push(char);
+                    }
+                    _ if in_parens => descriptor.push(char),
+                    '(' => {
+                        in_parens = true;
+                        descriptor.push(char);
+                    }
+                    ',' => break,
+                    _ if char.is_ascii_whitespace() => {
+                        if !descriptor.is_empty() {
+                            descriptors.push(std::mem::take(&mut descriptor));
+                        }
+                    }
+                    _ => descriptor.push(char),
+                }
+            }
+            if !descriptor.is_empty() {
+                descriptors.push(descriptor);
+            }
+        }
+        candidates.push((url, descriptors));
+    }
+}
+
 impl AsHTMLElement for HTMLImageElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,11 +244,11 @@ impl AsElement for HTMLImageElement {
 }
 impl InnerHtml for HTMLImageElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLImageElement {}
@@ -70,7 +288,7 @@ impl TryFrom<HTMLElement> for HTMLImageElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Img) {
             Ok(HTMLImageElement {
                 html_element: value,
             })
@@ -1,11 +1,341 @@
+use super::{
+    base::HTMLElementBase,
+    constraint_validation::{allowed_step, radio_button_group, range, step_base},
+    form_associated::{descendant_elements, element_of},
+    input_type::{InputType, ValueMode},
+};
 use crate::{
-    tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    domitem::DOMItem, tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode,
+    AsParentNode, ConstraintValidation, DOMException, Element, File, FileList, HTMLElement,
+    HTMLFormElement, InnerHtml, Node,
 };
+
+/// The state of an `input` element that is kept apart from its attributes.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#the-input-element)
+#[derive(Debug, Default)]
+pub(crate) struct InputState {
+    /// The value, which follows the `value` attribute until it is set otherwise.
+    value: String,
+    /// Whether the value was set otherwise, so that the `value` attribute no longer changes it.
+    dirty_value: bool,
+    checkedness: bool,
+    /// Whether the checkedness was set otherwise, so that the `checked` attribute no longer changes it.
+    dirty_checkedness: bool,
+    indeterminate: bool,
+    files: Vec<File>,
+}
+
 pub struct HTMLInputElement {
     html_element: HTMLElement,
 }
 
+impl HTMLInputElement {
+    /// Returns the form that the input is associated with, which is set by its `form` attribute or else is its nearest ancestor form.
+    ///
+    /// MDN Reference: [`HTMLInputElement.form`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/form)
+    pub fn form(&self) -> Option<HTMLFormElement> {
+        super::form_associated::form(AsElement::cast(self))
+    }
+    /// Returns the type of the input, which is `text` if its `type` attribute is missing or invalid.
+    ///
+    /// MDN Reference: [`HTMLInputElement.type`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/type)
+    pub fn r#type(&self) -> &str {
+        InputType::of(AsElement::cast(self)).as_str()
+    }
+    /// Sets the `type` attribute of the input.
+    ///
+    /// MDN Reference: [`HTMLInputElement.type`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/type)
+    pub fn set_type(&mut self, value: &str) {
+        self.set_attribute("type", value)
+    }
+    /// Returns the value of the input. Depending on its type, this is the value that was set or else its sanitized `value` attribute, the `value` attribute itself, the `value` attribute or `on` for checkboxes and radio buttons, or a fake path to the first selected file.
+    ///
+    /// MDN Reference: [`HTMLInputElement.value`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/value)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLElement, HTMLInputElement};
+    ///
+    /// let document = Document::new();
+    /// let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    /// input.set_type("color");
+    /// input.set_default_value("#FF8800");
+    /// assert_eq!(input.value(), "#ff8800");
+    ///
+    /// // Setting the value makes it dirty, so the attribute no longer changes it.
+    /// input.set_value("not a color").unwrap();
+    /// assert_eq!(input.value(), "#000000");
+    /// input.set_default_value("#0000ff");
+    /// assert_eq!(input.value(), "#000000");
+    /// ```
+    pub fn value(&self) -> String {
+        let input = AsElement::cast(self);
+        match InputType::of(input).value_mode() {
+            ValueMode::Value => value(input),
+            ValueMode::Default => input.get_attribute("value").unwrap_or_default().to_owned(),
+            ValueMode::DefaultOn => input.get_attribute("value").unwrap_or("on").to_owned(),
+            ValueMode::Filename => files(input)
+                .first()
+                .map(|file| format!("C:\\fakepath\\{}", file.name()))
+                .unwrap_or_default(),
+        }
+    }
+    /// Sets the value of the input, which is sanitized for its type. For types whose value is their `value` attribute, the attribute is set instead.
+    ///
+    /// MDN Reference: [`HTMLInputElement.value`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/value)
+    /// # Errors
+    /// Returns an `InvalidStateError` DOMException if the input is a file input and the value is not empty, since only the selection of files can be cleared.
+    pub fn set_value(&mut self, value: &str) -> Result<(), DOMException> {
+        let input = AsElement::cast(self);
+        match InputType::of(input).value_mode() {
+            ValueMode::Value => set_dirty_value(input, value),
+            ValueMode::Default | ValueMode::DefaultOn => self.set_attribute("value", value),
+            ValueMode::Filename if value.is_empty() => {
+                with_state(input, |state| state.files.clear());
+                crate::layout::invalidate(AsNode::cast(input));
+            }
+            ValueMode::Filename => {
+                return Err(DOMException::InvalidStateError(String::from(
+                    "The value of a file input can only be set to the empty string.",
+                )))
+            }
+        }
+        Ok(())
+    }
+    /// Returns the `value` attribute of the input, which is its value until the value is set otherwise.
+    ///
+    /// MDN Reference: [`HTMLInputElement.defaultValue`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/defaultValue)
+    pub fn default_value(&self) -> &str {
+        self.get_attribute("value").unwrap_or_default()
+    }
+    /// Sets the `value` attribute of the input.
+    ///
+    /// MDN Reference: [`HTMLInputElement.defaultValue`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/defaultValue)
+    pub fn set_default_value(&mut self, value: &str) {
+        self.set_attribute("value", value)
+    }
+    /// Returns the value of the input as a number, or NaN if its type has no numbers or its value is empty. Dates are in milliseconds since the start of 1970 in UTC, months are months since January 1970, and times are milliseconds since midnight.
+    ///
+    /// MDN Reference: [`HTMLInputElement.valueAsNumber`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/valueAsNumber)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLInputElement};
+    ///
+    /// let document = Document::new();
+    /// let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    /// input.set_type("time");
+    /// input.set_value_as_number(5_400_500.0).unwrap();
+    /// assert_eq!(input.value(), "01:30:00.500");
+    ///
+    /// input.set_type("month");
+    /// input.set_value("2024-03").unwrap();
+    /// assert_eq!(input.value_as_number(), 650.0);
+    /// ```
+    pub fn value_as_number(&self) -> f64 {
+        let input = AsElement::cast(self);
+        let input_type = InputType::of(input);
+        match input_type.is_numeric() {
+            true => input_type.to_number(&value(input)).unwrap_or(f64::NAN),
+            false => f64::NAN,
+        }
+    }
+    /// Sets the value of the input to a number, in the units of [`HTMLInputElement::value_as_number`]. NaN empties the value.
+    ///
+    /// MDN Reference: [`HTMLInputElement.valueAsNumber`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/valueAsNumber)
+    /// # Errors
+    /// - Returns a `TypeError` DOMException if the number is infinite.
+    /// - Returns an `InvalidStateError` DOMException if the type of the input has no numbers.
+    pub fn set_value_as_number(&mut self, value: f64) -> Result<(), DOMException> {
+        if value.is_infinite() {
+            return Err(DOMException::TypeError(String::from(
+                "The value cannot be set to an infinite number.",
+            )));
+        }
+        let input = AsElement::cast(self);
+        let input_type = InputType::of(input);
+        if !input_type.is_numeric() {
+            return Err(DOMException::InvalidStateError(format!(
+                "The value of a {} input cannot be set to a number.",
+                input_type.as_str()
+            )));
+        }
+        let value = match value.is_nan() {
+            true => String::new(),
+            false => input_type.value_from_number(value),
+        };
+        set_dirty_value(input, &value);
+        Ok(())
+    }
+    /// Returns the value of a `date`, `month`, `week` or `time` input as a date, in milliseconds since the start of 1970 in UTC, or `None` if the value is empty or the type has no dates.
+    ///
+    /// MDN Reference: [`HTMLInputElement.valueAsDate`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/valueAsDate)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLInputElement};
+    ///
+    /// let document = Document::new();
+    /// let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    /// input.set_type("week");
+    /// // Thursday, the first of January 2015.
+    /// input.set_value_as_date(Some(1_420_070_400_000.0)).unwrap();
+    /// assert_eq!(input.value(), "2015-W01");
+    /// // The Monday that starts the week.
+    /// assert_eq!(input.value_as_date(), Some(1_419_811_200_000.0));
+    /// ```
+    pub fn value_as_date(&self) -> Option<f64> {
+        let input = AsElement::cast(self);
+        InputType::of(input).to_date(&value(input))
+    }
+    /// Sets the value of a `date`, `month`, `week` or `time` input to a date, in milliseconds since the start of 1970 in UTC. `None` or NaN empties the value.
+    ///
+    /// MDN Reference: [`HTMLInputElement.valueAsDate`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/valueAsDate)
+    /// # Errors
+    /// Returns an `InvalidStateError` DOMException if the type of the input has no dates.
+    pub fn set_value_as_date(&mut self, value: Option<f64>) -> Result<(), DOMException> {
+        let input = AsElement::cast(self);
+        let input_type = InputType::of(input);
+        if !input_type.has_date_value() {
+            return Err(DOMException::InvalidStateError(format!(
+                "The value of a {} input cannot be set to a date.",
+                input_type.as_str()
+            )));
+        }
+        let value = match value.filter(|value| !value.is_nan()) {
+            Some(value) => input_type.value_from_date(value),
+            None => String::new(),
+        };
+        set_dirty_value(input, &value);
+        Ok(())
+    }
+    /// Increases the value of the input by a number of steps, as its `step` attribute sets them, and keeps it within its minimum and maximum. A value between steps goes to the next step first.
+    ///
+    /// MDN Reference: [`HTMLInputElement.stepUp()`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/stepUp)
+    /// # Errors
+    /// Returns an `InvalidStateError` DOMException if the type of the input has no numbers, or if its `step` attribute is `any`.
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLInputElement};
+    ///
+    /// let document = Document::new();
+    /// let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    /// input.set_type("number");
+    /// input.set_attribute("step", "0.1");
+    /// input.set_attribute("max", "1");
+    /// input.set_value("0.2").unwrap();
+    ///
+    /// input.step_up(1).unwrap();
+    /// assert_eq!(input.value(), "0.3");
+    /// input.step_up(10).unwrap();
+    /// assert_eq!(input.value(), "1");
+    /// ```
+    pub fn step_up(&mut self, n: i32) -> Result<(), DOMException> {
+        step(AsElement::cast(self), n, true)
+    }
+    /// Decreases the value of the input by a number of steps, as its `step` attribute sets them, and keeps it within its minimum and maximum. A value between steps goes to the previous step first.
+    ///
+    /// MDN Reference: [`HTMLInputElement.stepDown()`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/stepDown)
+    /// # Errors
+    /// Returns an `InvalidStateError` DOMException if the type of the input has no numbers, or if its `step` attribute is `any`.
+    pub fn step_down(&mut self, n: i32) -> Result<(), DOMException> {
+        step(AsElement::cast(self), n, false)
+    }
+    /// Returns true if the checkbox or radio button is checked. It follows the `checked` attribute until it is set otherwise.
+    ///
+    /// MDN Reference: [`HTMLInputElement.checked`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/checked)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLFormElement, HTMLInputElement};
+    ///
+    /// let document = Document::new();
+    /// let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    /// let mut radio = |value: &str| {
+    ///     let mut radio = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    ///     radio.set_type("radio");
+    ///     radio.set_attribute("name", "size");
+    ///     radio.set_default_value(value);
+    ///     form.append_child(&mut radio).unwrap();
+    ///     radio
+    /// };
+    /// let mut small = radio("small");
+    /// let mut large = radio("large");
+    /// small.set_default_checked(true);
+    /// assert!(small.checked());
+    ///
+    /// // Checking a radio button unchecks the others in its group.
+    /// large.set_checked(true);
+    /// assert!(!small.checked());
+    /// assert!(small.default_checked());
+    /// ```
+    pub fn checked(&self) -> bool {
+        checkedness(AsElement::cast(self))
+    }
+    /// Checks or unchecks the input. Checking a radio button unchecks the others in its group.
+    ///
+    /// MDN Reference: [`HTMLInputElement.checked`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/checked)
+    pub fn set_checked(&mut self, value: bool) {
+        set_checked(AsElement::cast(self), value)
+    }
+    /// Returns true if the input has the `checked` attribute, which it is checked by until it is checked otherwise.
+    ///
+    /// MDN Reference: [`HTMLInputElement.defaultChecked`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/defaultChecked)
+    pub fn default_checked(&self) -> bool {
+        self.has_attribute("checked")
+    }
+    /// Adds or removes the `checked` attribute of the input.
+    ///
+    /// MDN Reference: [`HTMLInputElement.defaultChecked`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/defaultChecked)
+    pub fn set_default_checked(&mut self, value: bool) {
+        match value {
+            true => self.set_attribute("checked", ""),
+            false => self.remove_attribute("checked"),
+        }
+    }
+    /// Returns true if the checkbox is shown as neither checked nor unchecked. This does not change whether it is checked.
+    ///
+    /// MDN Reference: [`HTMLInputElement.indeterminate`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/indeterminate)
+    pub fn indeterminate(&self) -> bool {
+        indeterminate(AsElement::cast(self))
+    }
+    /// Sets whether the checkbox is shown as neither checked nor unchecked.
+    ///
+    /// MDN Reference: [`HTMLInputElement.indeterminate`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/indeterminate)
+    pub fn set_indeterminate(&mut self, value: bool) {
+        with_state(AsElement::cast(self), |state| state.indeterminate = value);
+        crate::layout::invalidate(AsNode::cast(self));
+    }
+    /// Returns the files that are selected in a file input, or `None` if the input is of another type.
+    ///
+    /// MDN Reference: [`HTMLInputElement.files`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/files)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, File, HTMLInputElement};
+    ///
+    /// let document = Document::new();
+    /// let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    /// assert!(input.files().is_none());
+    ///
+    /// input.set_type("file");
+    /// input.set_files(vec![File::new("%PDF", "report.pdf", None)].into());
+    /// assert_eq!(input.files().unwrap().len(), 1);
+    /// assert_eq!(input.value(), "C:\\fakepath\\report.pdf");
+    /// ```
+    pub fn files(&self) -> Option<FileList> {
+        let input = AsElement::cast(self);
+        (InputType::of(input) == InputType::File).then(|| files(input).into())
+    }
+    /// Replaces the files that are selected in a file input. Inputs of other types are left alone.
+    ///
+    /// MDN Reference: [`HTMLInputElement.files`](https://developer.mozilla.org/docs/Web/API/HTMLInputElement/files)
+    pub fn set_files(&mut self, files: FileList) {
+        let input = AsElement::cast(self);
+        if InputType::of(input) == InputType::File {
+            with_state(input, |state| state.files = files.into());
+            crate::layout::invalidate(AsNode::cast(input));
+        }
+    }
+}
+
 impl AsHTMLElement for HTMLInputElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,15 +356,16 @@ impl AsElement for HTMLInputElement {
 }
 impl InnerHtml for HTMLInputElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLInputElement {}
 impl AsChildNode for HTMLInputElement {}
+impl ConstraintValidation for HTMLInputElement {}
 impl AsNode for HTMLInputElement {
     fn cast(&self) -> &crate::Node {
         AsNode::cast(&self.html_element)
@@ -70,7 +401,7 @@ impl TryFrom<HTMLElement> for HTMLInputElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Input) {
             Ok(HTMLInputElement {
                 html_element: value,
             })
@@ -81,3 +412,330 @@ impl TryFrom<HTMLElement> for HTMLInputElement {
         }
     }
 }
+
+/// Runs a closure with the state of an input, or returns `None` if the element is not an HTML `input` element.
+fn with_state<T>(input: &Element, closure: impl FnOnce(&mut InputState) -> T) -> Option<T> {
+    let base = input
+        .owner_document()?
+        .lookup_html_element_base(AsNode::cast(input).get_base_ptr())?;
+    let mut base = base.borrow_mut();
+    match &mut *base {
+        HTMLElementBase::Input { state, .. } => Some(closure(state)),
+        _ => None,
+    }
+}
+
+/// Returns the value of an input, which is its sanitized `value` attribute until it is set otherwise.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-fe-value)
+pub(crate) fn value(input: &Element) -> String {
+    with_state(input, |state| state.value.clone())
+        .unwrap_or_else(|| input.get_attribute("value").unwrap_or_default().to_owned())
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-fe-checked)
+pub(crate) fn checkedness(input: &Element) -> bool {
+    with_state(input, |state| state.checkedness).unwrap_or_else(|| input.has_attribute("checked"))
+}
+
+pub(crate) fn indeterminate(input: &Element) -> bool {
+    with_state(input, |state| state.indeterminate).unwrap_or_default()
+}
+
+/// Returns the files that are selected in a file input.
+pub(crate) fn files(input: &Element) -> Vec<File> {
+    with_state(input, |state| state.files.clone()).unwrap_or_default()
+}
+
+/// Sets the value of an input after sanitizing it, without changing whether it is dirty.
+fn set_sanitized_value(input: &Element, value: &str) {
+    let value = sanitize(input, value);
+    with_state(input, |state| state.value = value);
+    crate::layout::invalidate(AsNode::cast(input));
+}
+
+/// Sets the value of an input the way the `value` IDL attribute does, so that the `value` attribute no longer changes it.
+fn set_dirty_value(input: &Element, value: &str) {
+    set_sanitized_value(input, value);
+    with_state(input, |state| state.dirty_value = true);
+}
+
+/// Sets the checkedness of an input the way the `checked` IDL attribute does, so that the `checked` attribute no longer changes it.
+pub(crate) fn set_checked(input: &Element, checked: bool) {
+    with_state(input, |state| state.dirty_checkedness = true);
+    set_checkedness(input, checked);
+}
+
+/// Sets the checkedness of an input, and unchecks the other radio buttons in its group if it is a checked radio button.
+fn set_checkedness(input: &Element, checked: bool) {
+    with_state(input, |state| state.checkedness = checked);
+    crate::layout::invalidate(AsNode::cast(input));
+    if checked {
+        uncheck_radio_button_group(input);
+    }
+}
+
+/// Unchecks the radio buttons in the group of a checked radio button, other than itself.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#radio-button-state-(type=radio))
+fn uncheck_radio_button_group(radio: &Element) {
+    if InputType::of(radio) != InputType::Radio || !checkedness(radio) {
+        return;
+    }
+    for other in radio_button_group(radio) {
+        if !other.is_same_node(radio) {
+            with_state(&other, |state| state.checkedness = false);
+        }
+    }
+}
+
+/// Runs the value sanitization algorithm of the type of an input. Values of `range` inputs are also kept within their range and on their steps.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#value-sanitization-algorithm)
+fn sanitize(input: &Element, value: &str) -> String {
+    let input_type = InputType::of(input);
+    let value = input_type.sanitize(value, input.has_attribute("multiple"));
+    if input_type != InputType::Range {
+        return value;
+    }
+    let Some((Some(min), Some(max))) = range(input) else {
+        return value;
+    };
+    // A maximum below the minimum is the minimum, which is then also the default value.
+    let max = max.max(min);
+    let mut number = input_type
+        .to_number(&value)
+        .unwrap_or(min + (max - min) / 2.0)
+        .clamp(min, max);
+    if let Some(step) = allowed_step(input) {
+        let base = step_base(input);
+        // The nearest step, preferring the larger one when two are as near, unless it is above the maximum.
+        number = base + floor_steps((number - base) / step + 0.5) * step;
+        if number > max {
+            number = base + floor_steps((max - base) / step) * step;
+        }
+        number = round_to_step_precision(input, number, &value);
+    }
+    input_type.value_from_number(number)
+}
+
+/// Returns the number of steps rounded up, unless it is a whole number but for floating-point error.
+fn ceil_steps(steps: f64) -> f64 {
+    match is_whole(steps) {
+        true => steps.round(),
+        false => steps.ceil(),
+    }
+}
+
+/// Returns the number of steps rounded down, unless it is a whole number but for floating-point error.
+fn floor_steps(steps: f64) -> f64 {
+    match is_whole(steps) {
+        true => steps.round(),
+        false => steps.floor(),
+    }
+}
+
+fn is_whole(steps: f64) -> bool {
+    (steps - steps.round()).abs() <= 1e-9 * steps.abs().max(1.0)
+}
+
+/// Rounds away the floating-point error of stepping, to the most decimal places that the step, the step base and the value stepped from have, so that stepping from `0.2` by `0.1` gives `0.3`.
+fn round_to_step_precision(input: &Element, number: f64, value: &str) -> f64 {
+    if !matches!(InputType::of(input), InputType::Number | InputType::Range) {
+        // Other types step in whole milliseconds or months.
+        return number.round();
+    }
+    let places = ["step", "min", "value"]
+        .iter()
+        .filter_map(|name| input.get_attribute(name))
+        .chain([value])
+        .map(decimal_places)
+        .max()
+        .unwrap_or_default();
+    if places > 15 {
+        return number;
+    }
+    let factor = 10f64.powi(places);
+    (number * factor).round() / factor
+}
+
+/// Returns the number of decimal places of a floating-point number, like 3 for `1.5e-2`.
+fn decimal_places(number: &str) -> i32 {
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(index) => (&number[..index], number[index + 1..].parse().unwrap_or(0)),
+        None => (number, 0),
+    };
+    let fraction = mantissa
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len() as i32);
+    (fraction - exponent).max(0)
+}
+
+/// Runs the steps of the `stepUp()` and `stepDown()` methods.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#dom-input-stepup)
+fn step(input: &Element, n: i32, up: bool) -> Result<(), DOMException> {
+    let input_type = InputType::of(input);
+    // 1. If the stepDown() and stepUp() methods do not apply, as defined for the input element's type attribute's current state, then throw an "InvalidStateError" DOMException.
+    let Some((min, max)) = range(input) else {
+        return Err(DOMException::InvalidStateError(format!(
+            "A {} input cannot be stepped.",
+            input_type.as_str()
+        )));
+    };
+    // 2. If the element has no allowed value step, then throw an "InvalidStateError" DOMException.
+    let Some(step) = allowed_step(input) else {
+        return Err(DOMException::InvalidStateError(String::from(
+            "The input cannot be stepped, since its step is \"any\".",
+        )));
+    };
+    let base = step_base(input);
+    if let (Some(min), Some(max)) = (min, max) {
+        // 3. If the element has a minimum and a maximum and the minimum is greater than the maximum, then return.
+        // 4. If the element has a minimum and a maximum and there is no value between them that is on a step, then return.
+        if min > max || base + ceil_steps((min - base) / step) * step > max {
+            return Ok(());
+        }
+    }
+    // 5. Let value be the result of converting the value of the element to a number, or zero if it cannot be converted.
+    let current = value(input);
+    let mut number = input_type.to_number(&current).unwrap_or(0.0);
+    // 6. Let valueBeforeStepping be value.
+    let before = number;
+    // 7. If value is not on a step, go to the nearest step below it for stepDown() and above it for stepUp(). Otherwise add n steps for stepUp(), or subtract them for stepDown().
+    let steps = (number - base) / step;
+    let steps = match (is_whole(steps), up) {
+        (false, true) => steps.ceil(),
+        (false, false) => steps.floor(),
+        (true, true) => steps.round() + n as f64,
+        (true, false) => steps.round() - n as f64,
+    };
+    number = base + steps * step;
+    // 8. If the element has a minimum, and value is less than that minimum, then set value to the smallest value that, when subtracted from the step base, is an integral multiple of the allowed value step, and that is more than or equal to minimum.
+    if let Some(min) = min.filter(|min| number < *min) {
+        number = base + ceil_steps((min - base) / step) * step;
+    }
+    // 9. If the element has a maximum, and value is greater than that maximum, then set value to the largest value that, when subtracted from the step base, is an integral multiple of the allowed value step, and that is less than or equal to maximum.
+    if let Some(max) = max.filter(|max| number > *max) {
+        number = base + floor_steps((max - base) / step) * step;
+    }
+    // 10. If either the method invoked was the stepDown() method and value is greater than valueBeforeStepping, or the method invoked was the stepUp() method and value is less than valueBeforeStepping, then return.
+    if (up && number < before) || (!up && number > before) {
+        return Ok(());
+    }
+    // 11. Let value as string be the result of running the algorithm to convert a number to a string, as defined for the input element's type attribute's current state, on value.
+    // 12. Set the value of the element to value as string.
+    let number = round_to_step_precision(input, number, &current);
+    set_dirty_value(input, &input_type.value_from_number(number));
+    Ok(())
+}
+
+/// Runs the steps for changes of the attributes of an `input` element, which update its value and checkedness while they follow the `value` and `checked` attributes.
+pub(crate) fn run_attribute_change_steps(
+    element: &Element,
+    local_name: &str,
+    old_value: Option<&str>,
+    value: Option<&str>,
+) {
+    if element.base().tag != Tag::Input {
+        return;
+    }
+    match local_name {
+        // When the value content attribute is added, set, or removed, if the control's dirty value flag is false, the user agent must set the value of the element to the value of the value content attribute, if there is one, or the empty string otherwise, and then run the current value sanitization algorithm, if one is defined.
+        // The value attribute is also the step base of range inputs without a min attribute.
+        "value" => sanitize_again(element),
+        // When the checked content attribute is added or removed, if the control does not have dirty checkedness, the user agent must set the checkedness of the element to whether the attribute is there.
+        "checked"
+            if old_value.is_some() != value.is_some()
+                && with_state(element, |state| !state.dirty_checkedness) == Some(true) =>
+        {
+            set_checkedness(element, value.is_some())
+        }
+        "type" => {
+            let (previous, new) = (InputType::parse(old_value), InputType::parse(value));
+            if previous != new {
+                run_type_change_steps(element, previous, new);
+            }
+        }
+        // The name of a radio button decides its group.
+        "name" => uncheck_radio_button_group(element),
+        // The sanitization of email and range inputs depends on these attributes.
+        "min" | "max" | "step" | "multiple" => sanitize_again(element),
+        _ => {}
+    }
+}
+
+/// The insertion steps for `input` elements: a checked radio button that is inserted unchecks the others in the group it joins.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#radio-button-state-(type=radio))
+pub(crate) fn run_insertion_steps(node: &Node) {
+    for element in element_of(node)
+        .into_iter()
+        .chain(descendant_elements(node))
+        .filter(|element| element.base().tag == Tag::Input)
+    {
+        uncheck_radio_button_group(&element);
+    }
+}
+
+/// Sanitizes the value of an input again after an attribute that it depends on changes. A value that is not dirty is taken from the `value` attribute again, so that a value is not lost to a range that is changed back.
+fn sanitize_again(input: &Element) {
+    let value = match with_state(input, |state| state.dirty_value) {
+        Some(true) => value(input),
+        _ => input.get_attribute("value").unwrap_or_default().to_owned(),
+    };
+    set_sanitized_value(input, &value);
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#input-type-change)
+fn run_type_change_steps(input: &Element, previous: InputType, new: InputType) {
+    let value = value(input);
+    match (previous.value_mode(), new.value_mode()) {
+        // 1. If the previous state of the element's type attribute put the value IDL attribute in the value mode, and the element's value is not the empty string, and the new state of the element's type attribute puts the value IDL attribute in either the default mode or the default/on mode, then set the element's value content attribute to the element's value.
+        // The value only differs from the attribute if it is dirty.
+        (ValueMode::Value, ValueMode::Default | ValueMode::DefaultOn)
+            if !value.is_empty() && with_state(input, |state| state.dirty_value) == Some(true) =>
+        {
+            let mut input = input.clone_ref();
+            input.set_attribute("value", &value);
+        }
+        // 2. Otherwise, if the previous state of the element's type attribute put the value IDL attribute in any mode other than the value mode, and the new state of the element's type attribute puts the value IDL attribute in the value mode, then set the value of the element to the value of the value content attribute, if there is one, or the empty string otherwise, and then set the control's dirty value flag to false.
+        (previous, ValueMode::Value) if previous != ValueMode::Value => {
+            let attribute = input.get_attribute("value").unwrap_or_default().to_owned();
+            with_state(input, |state| {
+                state.value = attribute;
+                state.dirty_value = false;
+            });
+        }
+        // 3. Otherwise, if the previous state of the element's type attribute put the value IDL attribute in any mode other than the filename mode, and the new state of the element's type attribute puts the value IDL attribute in the filename mode, then set the value of the element to the empty string.
+        (previous, ValueMode::Filename) if previous != ValueMode::Filename => {
+            with_state(input, |state| state.value.clear());
+        }
+        _ => {}
+    }
+    // 5. Invoke the value sanitization algorithm, if one is defined for the type attribute's new state.
+    set_sanitized_value(input, &self::value(input));
+    uncheck_radio_button_group(input);
+}
+
+/// Runs the cloning steps for `input` elements, which copy the value, checkedness and whether they are dirty to the copy.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/input.html#the-input-element:concept-node-clone-ext)
+pub(crate) fn clone_state(input: &Element, copy: &Element) {
+    let Some((value, dirty_value, checkedness, dirty_checkedness)) = with_state(input, |state| {
+        (
+            state.value.clone(),
+            state.dirty_value,
+            state.checkedness,
+            state.dirty_checkedness,
+        )
+    }) else {
+        return;
+    };
+    with_state(copy, |state| {
+        state.value = value;
+        state.dirty_value = dirty_value;
+        state.checkedness = checkedness;
+        state.dirty_checkedness = dirty_checkedness;
+    });
+}
@@ -3,6 +3,8 @@ use crate::{
     DOMException, HTMLElement, HTMLFormElement, InnerHtml,
 };
 
+use super::form_associated;
+
 /// Gives access to properties specific to `<label>` elements. It inherits methods and properties from the base [`HTMLElement`] struct.
 ///
 ///  MDN Reference: [`HTMLLabelElement`](https://developer.mozilla.org/docs/Web/API/HTMLLabelElement)
@@ -11,17 +13,59 @@ pub struct HTMLLabelElement {
 }
 
 impl HTMLLabelElement {
+    /// Returns the control that the label labels: the element with the ID in its `for` attribute, or else its first labelable descendant.
+    ///
+    /// MDN Reference: [`HTMLLabelElement.control`](https://developer.mozilla.org/docs/Web/API/HTMLLabelElement/control)
     pub fn control(&self) -> Option<HTMLElement> {
-        todo!()
-    }
+        let control = form_associated::labeled_control(AsElement::cast(self))?;
+        let base = self
+            .owner_document()?
+            .lookup_html_element_base(AsNode::cast(&control).get_base_ptr())?;
+        Some(HTMLElement { base })
+    }
+    /// Returns the form that the labeled control is associated with.
+    ///
+    /// MDN Reference: [`HTMLLabelElement.form`](https://developer.mozilla.org/docs/Web/API/HTMLLabelElement/form)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLLabelElement};
+    ///
+    /// let mut document = Document::new();
+    /// let mut html = document.create_element("html");
+    /// document.append_child(&mut html).unwrap();
+    ///
+    /// let mut form = document.create_element("form");
+    /// form.set_id("order");
+    /// html.append_child(&mut form).unwrap();
+    ///
+    /// let mut label = HTMLLabelElement::try_from(document.create_element("label")).unwrap();
+    /// label.set_html_for("quantity");
+    /// html.append_child(&mut label).unwrap();
+    ///
+    /// // The input is outside of the form, but its `form` attribute associates it with the form.
+    /// let mut input = document.create_element("input");
+    /// input.set_id("quantity");
+    /// input.set_attribute("form", "order");
+    /// html.append_child(&mut input).unwrap();
+    ///
+    /// assert!(label.control().unwrap() == input);
+    /// assert!(label.form().unwrap() == form);
+    /// ```
     pub fn form(&self) -> Option<HTMLFormElement> {
-        todo!()
+        let control = form_associated::labeled_control(AsElement::cast(self))?;
+        form_associated::form(&control)
     }
+    /// Returns the ID of the control that the label labels, from its `for` attribute.
+    ///
+    /// MDN Reference: [`HTMLLabelElement.htmlFor`](https://developer.mozilla.org/docs/Web/API/HTMLLabelElement/htmlFor)
     pub fn html_for(&self) -> &str {
-        todo!()
+        self.get_attribute("for").unwrap_or("")
     }
+    /// Sets the ID of the control that the label labels.
+    ///
+    /// MDN Reference: [`HTMLLabelElement.htmlFor`](https://developer.mozilla.org/docs/Web/API/HTMLLabelElement/htmlFor)
     pub fn set_html_for(&mut self, value: &str) {
-        todo!()
+        self.set_attribute("for", value)
     }
 }
 
@@ -45,11 +89,11 @@ impl AsElement for HTMLLabelElement {
 }
 impl InnerHtml for HTMLLabelElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLLabelElement {}
@@ -1,11 +1,73 @@
 use crate::{
-    tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    css::ParseContext, domitem::DOMItem, loader::resolve_url, tag::Tag, AsChildNode, AsElement,
+    AsEventTarget, AsHTMLElement, AsNode, AsParentNode, CSSStyleSheet, DOMException, HTMLElement,
+    HTMLElementBase, InnerHtml,
 };
 pub struct HTMLLinkElement {
     html_element: HTMLElement,
 }
 
+// Properties.
+impl HTMLLinkElement {
+    /// Returns the URL of the linked resource, as written in the `href` attribute.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLLinkElement/href)
+    pub fn href(&self) -> &str {
+        self.get_attribute("href").unwrap_or_default()
+    }
+    pub fn set_href(&mut self, value: &str) {
+        self.set_attribute("href", value)
+    }
+    /// Returns the relationship of the linked resource to the document, like `stylesheet`.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLLinkElement/rel)
+    pub fn rel(&self) -> &str {
+        self.get_attribute("rel").unwrap_or_default()
+    }
+    pub fn set_rel(&mut self, value: &str) {
+        self.set_attribute("rel", value)
+    }
+    /// Returns the media queries that the linked resource applies to.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLLinkElement/media)
+    pub fn media(&self) -> &str {
+        self.get_attribute("media").unwrap_or_default()
+    }
+    pub fn set_media(&mut self, value: &str) {
+        self.set_attribute("media", value)
+    }
+    /// Returns the style sheet loaded from the link, or `None` if the link is not a connected `rel=stylesheet` link, or its resource could not be loaded.
+    ///
+    /// Style sheets are loaded through the document's resource loader, and loaded again whenever the `href` changes.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLLinkElement/sheet)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLLinkElement};
+    ///
+    /// let mut document = Document::new();
+    /// document.set_resource_loader(|url: &str| match url {
+    ///     "light.css" => Some(String::from("body { color: black }")),
+    ///     "dark.css" => Some(String::from("body { color: white } a { color: cyan }")),
+    ///     _ => None,
+    /// });
+    /// let mut link = HTMLLinkElement::try_from(document.create_element("link")).unwrap();
+    /// link.set_rel("stylesheet");
+    /// link.set_href("light.css");
+    /// document.append_child(&mut link).unwrap();
+    /// assert_eq!(link.sheet().unwrap().css_rules().len(), 1);
+    ///
+    /// link.set_href("dark.css");
+    /// assert_eq!(link.sheet().unwrap().css_rules().len(), 2);
+    ///
+    /// link.set_href("missing.css");
+    /// assert!(link.sheet().is_none());
+    /// ```
+    pub fn sheet(&self) -> Option<CSSStyleSheet> {
+        link_element_sheet(self)
+    }
+}
+
 impl AsHTMLElement for HTMLLinkElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,11 +88,11 @@ impl AsElement for HTMLLinkElement {
 }
 impl InnerHtml for HTMLLinkElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLLinkElement {}
@@ -70,7 +132,7 @@ impl TryFrom<HTMLElement> for HTMLLinkElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Link) {
             Ok(HTMLLinkElement {
                 html_element: value,
             })
@@ -81,3 +143,48 @@ impl TryFrom<HTMLElement> for HTMLLinkElement {
         }
     }
 }
+
+/// Returns the style sheet of a link element, loading it again if its URL has changed, or `None` if the node is not a connected `rel=stylesheet` link whose resource loads.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/links.html#link-type-stylesheet)
+pub(crate) fn link_element_sheet(node: &impl AsNode) -> Option<CSSStyleSheet> {
+    if !node.is_connected() {
+        return None;
+    }
+    let document = node.owner_document()?;
+    let html_element = document.lookup_html_element_base(AsNode::cast(node).get_base_ptr())?;
+    let mut html_element = html_element.borrow_mut();
+    let HTMLElementBase::Link { element, sheet } = &mut *html_element else {
+        return None;
+    };
+    let is_stylesheet = element.get_attribute("rel").is_some_and(|rel| {
+        rel.split_ascii_whitespace()
+            .any(|keyword| keyword.eq_ignore_ascii_case("stylesheet"))
+    });
+    let href = element.get_attribute("href").unwrap_or_default();
+    if !is_stylesheet || href.is_empty() {
+        *sheet = None;
+        return None;
+    }
+    let url = resolve_url(&document.state().url, href);
+    let is_stale = sheet
+        .as_ref()
+        .is_none_or(|sheet| sheet.inner.borrow().source != url);
+    if is_stale {
+        let loader = document.state().resource_loader.clone();
+        let text = loader.as_ref().and_then(|loader| loader.load(&url));
+        *sheet = text.map(|text| {
+            let context = ParseContext {
+                base_url: url.clone(),
+                loader,
+                depth: 0,
+                document: document.weak_ref(),
+            };
+            CSSStyleSheet::parse(&text, Some(url.clone()), url, context)
+        });
+    }
+    let sheet = sheet.clone()?;
+    let media = element.get_attribute("media").unwrap_or_default();
+    sheet.inner.borrow().media.set(media);
+    Some(sheet)
+}
@@ -1,11 +1,20 @@
 use crate::{
     tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    ConstraintValidation, DOMException, HTMLElement, HTMLFormElement, InnerHtml,
 };
 pub struct HTMLObjectElement {
     html_element: HTMLElement,
 }
 
+impl HTMLObjectElement {
+    /// Returns the form that the object is associated with, which is set by its `form` attribute or else is its nearest ancestor form.
+    ///
+    /// MDN Reference: [`HTMLObjectElement.form`](https://developer.mozilla.org/docs/Web/API/HTMLObjectElement/form)
+    pub fn form(&self) -> Option<HTMLFormElement> {
+        super::form_associated::form(AsElement::cast(self))
+    }
+}
+
 impl AsHTMLElement for HTMLObjectElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,15 +35,16 @@ impl AsElement for HTMLObjectElement {
 }
 impl InnerHtml for HTMLObjectElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLObjectElement {}
 impl AsChildNode for HTMLObjectElement {}
+impl ConstraintValidation for HTMLObjectElement {}
 impl AsNode for HTMLObjectElement {
     fn cast(&self) -> &crate::Node {
         AsNode::cast(&self.html_element)
@@ -70,7 +80,7 @@ impl TryFrom<HTMLElement> for HTMLObjectElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Object) {
             Ok(HTMLObjectElement {
                 html_element: value,
             })
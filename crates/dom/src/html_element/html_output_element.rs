@@ -1,11 +1,20 @@
 use crate::{
     tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    ConstraintValidation, DOMException, HTMLElement, HTMLFormElement, InnerHtml,
 };
 pub struct HTMLOutputElement {
     html_element: HTMLElement,
 }
 
+impl HTMLOutputElement {
+    /// Returns the form that the output is associated with, which is set by its `form` attribute or else is its nearest ancestor form.
+    ///
+    /// MDN Reference: [`HTMLOutputElement.form`](https://developer.mozilla.org/docs/Web/API/HTMLOutputElement/form)
+    pub fn form(&self) -> Option<HTMLFormElement> {
+        super::form_associated::form(AsElement::cast(self))
+    }
+}
+
 impl AsHTMLElement for HTMLOutputElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,15 +35,16 @@ impl AsElement for HTMLOutputElement {
 }
 impl InnerHtml for HTMLOutputElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLOutputElement {}
 impl AsChildNode for HTMLOutputElement {}
+impl ConstraintValidation for HTMLOutputElement {}
 impl AsNode for HTMLOutputElement {
     fn cast(&self) -> &crate::Node {
         AsNode::cast(&self.html_element)
@@ -70,7 +80,7 @@ impl TryFrom<HTMLElement> for HTMLOutputElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Output) {
             Ok(HTMLOutputElement {
                 html_element: value,
             })
@@ -26,11 +26,11 @@ impl AsElement for HTMLProgressElement {
 }
 impl InnerHtml for HTMLProgressElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLProgressElement {}
@@ -1,11 +1,20 @@
 use crate::{
     tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    ConstraintValidation, DOMException, HTMLElement, HTMLFormElement, InnerHtml,
 };
 pub struct HTMLSelectElement {
     html_element: HTMLElement,
 }
 
+impl HTMLSelectElement {
+    /// Returns the form that the select is associated with, which is set by its `form` attribute or else is its nearest ancestor form.
+    ///
+    /// MDN Reference: [`HTMLSelectElement.form`](https://developer.mozilla.org/docs/Web/API/HTMLSelectElement/form)
+    pub fn form(&self) -> Option<HTMLFormElement> {
+        super::form_associated::form(AsElement::cast(self))
+    }
+}
+
 impl AsHTMLElement for HTMLSelectElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,15 +35,16 @@ impl AsElement for HTMLSelectElement {
 }
 impl InnerHtml for HTMLSelectElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLSelectElement {}
 impl AsChildNode for HTMLSelectElement {}
+impl ConstraintValidation for HTMLSelectElement {}
 impl AsNode for HTMLSelectElement {
     fn cast(&self) -> &crate::Node {
         AsNode::cast(&self.html_element)
@@ -70,7 +80,7 @@ impl TryFrom<HTMLElement> for HTMLSelectElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Select) {
             Ok(HTMLSelectElement {
                 html_element: value,
             })
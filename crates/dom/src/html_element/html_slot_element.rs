@@ -1,11 +1,112 @@
 use crate::{
-    tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    domitem::DOMItem, tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode,
+    AsParentNode, AssignedNodesOptions, ChildNode, DOMException, Element, HTMLElement, InnerHtml,
 };
 pub struct HTMLSlotElement {
     html_element: HTMLElement,
 }
 
+// Properties.
+impl HTMLSlotElement {
+    /// Returns the name of the slot.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSlotElement/name)
+    pub fn name(&self) -> &str {
+        self.get_attribute("name").unwrap_or("")
+    }
+    /// Sets the name of the slot.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSlotElement/name)
+    pub fn set_name(&mut self, value: &str) {
+        self.set_attribute("name", value)
+    }
+}
+
+// Methods.
+impl HTMLSlotElement {
+    /// Returns the nodes assigned to the slot. If `flatten` is set, the assigned nodes of any slots nested in the slot are returned instead, and the slot's children are used if nothing is assigned.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSlotElement/assignedNodes)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, AssignedNodesOptions, Document, HTMLSlotElement, ShadowRootInit, ShadowRootMode};
+    ///
+    /// let document = Document::new();
+    /// let mut host = document.create_element("div");
+    /// let shadow_root = host.attach_shadow(ShadowRootInit::new(ShadowRootMode::Open)).unwrap();
+    ///
+    /// let mut slot = HTMLSlotElement::try_from(document.create_element("slot")).unwrap();
+    /// let mut fallback = document.create_element("span");
+    /// slot.append_child(&mut fallback).unwrap();
+    /// shadow_root.append_child(&mut slot).unwrap();
+    ///
+    /// // Nothing is assigned yet, so only the flattened nodes include the fallback content.
+    /// assert!(slot.assigned_nodes(None).is_empty());
+    /// let flattened = slot.assigned_nodes(Some(AssignedNodesOptions { flatten: true }));
+    /// assert_eq!(flattened[0], fallback);
+    ///
+    /// let mut paragraph = document.create_element("p");
+    /// host.append_child(&mut paragraph).unwrap();
+    /// assert_eq!(slot.assigned_nodes(None)[0], paragraph);
+    /// ```
+    pub fn assigned_nodes(&self, options: Option<AssignedNodesOptions>) -> Vec<ChildNode> {
+        // 1. If options["flatten"] is false, then return this's assigned nodes.
+        // 2. Return the result of finding flattened slottables with this.
+        if options.is_some_and(|options| options.flatten) {
+            crate::slot::find_flattened_slottables(AsNode::cast(self))
+        } else {
+            crate::slot::assigned_nodes(self)
+        }
+    }
+    /// Returns the elements assigned to the slot. If `flatten` is set, the assigned elements of any slots nested in the slot are returned instead, and the slot's children are used if nothing is assigned.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSlotElement/assignedElements)
+    pub fn assigned_elements(&self, options: Option<AssignedNodesOptions>) -> Vec<Element> {
+        let document = match self.owner_document() {
+            Some(document) => document,
+            None => return vec![],
+        };
+        self.assigned_nodes(options)
+            .iter()
+            .filter_map(|node| document.lookup_html_element(AsNode::cast(node).get_base_ptr()))
+            .collect()
+    }
+    /// Sets the slot's manually assigned nodes to the given nodes. This only takes effect in shadow roots with manual slot assignment.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSlotElement/assign)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLSlotElement, ShadowRootInit, ShadowRootMode, SlotAssignment};
+    ///
+    /// let document = Document::new();
+    /// let mut host = document.create_element("div");
+    /// let mut init = ShadowRootInit::new(ShadowRootMode::Open);
+    /// init.slot_assignment = SlotAssignment::Manual;
+    /// let shadow_root = host.attach_shadow(init).unwrap();
+    ///
+    /// let mut slot = HTMLSlotElement::try_from(document.create_element("slot")).unwrap();
+    /// shadow_root.append_child(&mut slot).unwrap();
+    ///
+    /// let mut first = document.create_element("span");
+    /// let mut second = document.create_element("span");
+    /// host.append_child(&mut first).unwrap();
+    /// host.append_child(&mut second).unwrap();
+    /// assert!(slot.assigned_nodes(None).is_empty());
+    ///
+    /// slot.assign(&[&second]);
+    /// assert_eq!(slot.assigned_nodes(None).len(), 1);
+    /// assert_eq!(slot.assigned_elements(None)[0], second);
+    /// ```
+    pub fn assign<T: AsNode>(&mut self, nodes: &[&T]) {
+        let nodes = nodes.iter().map(|node| ChildNode::from(*node)).collect();
+        crate::slot::assign(AsNode::cast(self), nodes);
+        crate::layout::invalidate(AsNode::cast(self));
+        if let Some(document) = self.owner_document() {
+            document.notify_mutation_observers();
+        }
+    }
+}
+
 impl AsHTMLElement for HTMLSlotElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,11 +127,11 @@ impl AsElement for HTMLSlotElement {
 }
 impl InnerHtml for HTMLSlotElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLSlotElement {}
@@ -70,7 +171,7 @@ impl TryFrom<HTMLElement> for HTMLSlotElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Slot) {
             Ok(HTMLSlotElement {
                 html_element: value,
             })
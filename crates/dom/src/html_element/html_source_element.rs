@@ -6,6 +6,46 @@ pub struct HTMLSourceElement {
     html_element: HTMLElement,
 }
 
+// Properties.
+impl HTMLSourceElement {
+    /// Returns the URL of the media resource, as written in the `src` attribute.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSourceElement/src)
+    pub fn src(&self) -> &str {
+        self.get_attribute("src").unwrap_or_default()
+    }
+    pub fn set_src(&mut self, value: &str) {
+        self.set_attribute("src", value)
+    }
+    /// Returns the candidate images of the source, as written in the `srcset` attribute.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSourceElement/srcset)
+    pub fn srcset(&self) -> &str {
+        self.get_attribute("srcset").unwrap_or_default()
+    }
+    pub fn set_srcset(&mut self, value: &str) {
+        self.set_attribute("srcset", value)
+    }
+    /// Returns the media queries that the viewport must match for the source to be selected.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSourceElement/media)
+    pub fn media(&self) -> &str {
+        self.get_attribute("media").unwrap_or_default()
+    }
+    pub fn set_media(&mut self, value: &str) {
+        self.set_attribute("media", value)
+    }
+    /// Returns the MIME type of the resource, like `image/webp`.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLSourceElement/type)
+    pub fn type_(&self) -> &str {
+        self.get_attribute("type").unwrap_or_default()
+    }
+    pub fn set_type(&mut self, value: &str) {
+        self.set_attribute("type", value)
+    }
+}
+
 impl AsHTMLElement for HTMLSourceElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,11 +66,11 @@ impl AsElement for HTMLSourceElement {
 }
 impl InnerHtml for HTMLSourceElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLSourceElement {}
@@ -70,7 +110,7 @@ impl TryFrom<HTMLElement> for HTMLSourceElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Source) {
             Ok(HTMLSourceElement {
                 html_element: value,
             })
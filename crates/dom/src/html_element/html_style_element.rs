@@ -1,11 +1,48 @@
 use crate::{
-    tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    css::ParseContext, domitem::DOMItem, tag::Tag, AsChildNode, AsElement, AsEventTarget,
+    AsHTMLElement, AsNode, AsParentNode, CSSStyleSheet, DOMException, HTMLElement, HTMLElementBase,
+    InnerHtml,
 };
 pub struct HTMLStyleElement {
     html_element: HTMLElement,
 }
 
+// Properties.
+impl HTMLStyleElement {
+    /// Returns the style sheet parsed from the element's text, or `None` if the element is not connected to a document.
+    ///
+    /// The sheet is parsed again whenever the text of the element changes.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLStyleElement/sheet)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLStyleElement};
+    ///
+    /// let mut document = Document::new();
+    /// let mut style = HTMLStyleElement::try_from(document.create_element("style")).unwrap();
+    /// style.set_text_content("p { color: red }");
+    /// assert!(style.sheet().is_none());
+    ///
+    /// document.append_child(&mut style).unwrap();
+    /// assert_eq!(style.sheet().unwrap().css_rules().len(), 1);
+    ///
+    /// style.set_text_content("p { color: red } a { color: blue }");
+    /// assert_eq!(style.sheet().unwrap().css_rules().len(), 2);
+    /// ```
+    pub fn sheet(&self) -> Option<CSSStyleSheet> {
+        style_element_sheet(self)
+    }
+    /// Returns the media queries that the style sheet applies to.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLStyleElement/media)
+    pub fn media(&self) -> &str {
+        self.get_attribute("media").unwrap_or_default()
+    }
+    pub fn set_media(&mut self, value: &str) {
+        self.set_attribute("media", value)
+    }
+}
+
 impl AsHTMLElement for HTMLStyleElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,11 +63,11 @@ impl AsElement for HTMLStyleElement {
 }
 impl InnerHtml for HTMLStyleElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLStyleElement {}
@@ -70,7 +107,7 @@ impl TryFrom<HTMLElement> for HTMLStyleElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Style) {
             Ok(HTMLStyleElement {
                 html_element: value,
             })
@@ -81,3 +118,47 @@ impl TryFrom<HTMLElement> for HTMLStyleElement {
         }
     }
 }
+
+/// Returns the style sheet of a style element, parsing it again if the element's text has changed, or `None` if the node is not a style element connected to a document.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/semantics.html#update-a-style-block)
+pub(crate) fn style_element_sheet(node: &impl AsNode) -> Option<CSSStyleSheet> {
+    // 3. If element is not connected, then return.
+    if !node.is_connected() {
+        return None;
+    }
+    let document = node.owner_document()?;
+    let html_element = document.lookup_html_element_base(AsNode::cast(node).get_base_ptr())?;
+    let mut html_element = html_element.borrow_mut();
+    let HTMLElementBase::Style { element, sheet } = &mut *html_element else {
+        return None;
+    };
+    // 4. If element's type attribute is present and its value is neither the empty string nor an ASCII case-insensitive match for "text/css", then return.
+    if let Some(kind) = element.get_attribute("type") {
+        if !kind.is_empty() && !kind.eq_ignore_ascii_case("text/css") {
+            return None;
+        }
+    }
+    let text: String = node
+        .child_nodes()
+        .into_iter()
+        .filter(|child| child.node_type() == crate::Node::TEXT_NODE)
+        .map(|child| AsNode::cast(child).base().data.clone())
+        .collect();
+    let is_stale = sheet
+        .as_ref()
+        .is_none_or(|sheet| sheet.inner.borrow().source != text);
+    if is_stale {
+        let context = ParseContext {
+            base_url: document.state().url.clone(),
+            loader: document.state().resource_loader.clone(),
+            depth: 0,
+            document: document.weak_ref(),
+        };
+        *sheet = Some(CSSStyleSheet::parse(&text, None, text.clone(), context));
+    }
+    let sheet = sheet.clone()?;
+    let media = element.get_attribute("media").unwrap_or_default();
+    sheet.inner.borrow().media.set(media);
+    Some(sheet)
+}
@@ -26,11 +26,11 @@ impl AsElement for HTMLTablecaptionElement {
 }
 impl InnerHtml for HTMLTablecaptionElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLTablecaptionElement {}
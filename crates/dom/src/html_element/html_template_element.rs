@@ -1,11 +1,42 @@
 use crate::{
-    tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    domitem::DOMItem, tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode,
+    AsParentNode, DOMException, DocumentFragment, HTMLElement, HTMLElementBase, InnerHtml, Node,
 };
 pub struct HTMLTemplateElement {
     html_element: HTMLElement,
 }
 
+// Properties.
+impl HTMLTemplateElement {
+    /// Returns the template's contents. The contents are kept in a fragment owned by an inert document, so they are not rendered, and custom elements inside them are not upgraded, until they are cloned or adopted into a live document.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLTemplateElement/content)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, HTMLTemplateElement, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut template = HTMLTemplateElement::try_from(document.create_element("template")).unwrap();
+    /// template.set_inner_html("<li class=row>Row</li>").unwrap();
+    ///
+    /// // The template itself has no children; its markup is parsed into its contents.
+    /// assert!(!template.has_child_nodes());
+    /// assert_eq!(template.content().child_nodes().len(), 1);
+    /// assert!(template.content().owner_document().unwrap() != document);
+    ///
+    /// // Stamp out a row.
+    /// let mut list = document.create_element("ul");
+    /// let mut row = template.content().clone_node(true);
+    /// list.append_child(&mut row).unwrap();
+    /// assert_eq!(list.get_elements_by_class_name("row").len(), 1);
+    /// ```
+    pub fn content(&self) -> DocumentFragment {
+        DocumentFragment {
+            node: template_contents(self).expect("template elements always have contents"),
+        }
+    }
+}
+
 impl AsHTMLElement for HTMLTemplateElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,11 +57,11 @@ impl AsElement for HTMLTemplateElement {
 }
 impl InnerHtml for HTMLTemplateElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLTemplateElement {}
@@ -70,7 +101,7 @@ impl TryFrom<HTMLElement> for HTMLTemplateElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Template) {
             Ok(HTMLTemplateElement {
                 html_element: value,
             })
@@ -81,3 +112,17 @@ impl TryFrom<HTMLElement> for HTMLTemplateElement {
         }
     }
 }
+
+/// Returns the node of a template element's contents, or `None` if the node is not a template.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/scripting.html#template-contents)
+pub(crate) fn template_contents(node: &impl AsNode) -> Option<Node> {
+    let html_element = node
+        .owner_document()?
+        .lookup_html_element_base(AsNode::cast(node).get_base_ptr())?;
+    let html_element = html_element.borrow();
+    match &*html_element {
+        HTMLElementBase::Template { content, .. } => Some(content.node.clone_ref()),
+        _ => None,
+    }
+}
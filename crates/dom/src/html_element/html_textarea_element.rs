@@ -1,11 +1,20 @@
 use crate::{
     tag::Tag, AsChildNode, AsElement, AsEventTarget, AsHTMLElement, AsNode, AsParentNode,
-    DOMException, HTMLElement, InnerHtml,
+    ConstraintValidation, DOMException, HTMLElement, HTMLFormElement, InnerHtml,
 };
 pub struct HTMLTextareaElement {
     html_element: HTMLElement,
 }
 
+impl HTMLTextareaElement {
+    /// Returns the form that the text area is associated with, which is set by its `form` attribute or else is its nearest ancestor form.
+    ///
+    /// MDN Reference: [`HTMLTextAreaElement.form`](https://developer.mozilla.org/docs/Web/API/HTMLTextAreaElement/form)
+    pub fn form(&self) -> Option<HTMLFormElement> {
+        super::form_associated::form(AsElement::cast(self))
+    }
+}
+
 impl AsHTMLElement for HTMLTextareaElement {
     fn cast(&self) -> &HTMLElement {
         &self.html_element
@@ -26,15 +35,16 @@ impl AsElement for HTMLTextareaElement {
 }
 impl InnerHtml for HTMLTextareaElement {
     fn inner_html(&self) -> String {
-        todo!()
+        self.html_element.inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
-        todo!()
+        self.html_element.set_inner_html(value)
     }
 }
 impl AsParentNode for HTMLTextareaElement {}
 impl AsChildNode for HTMLTextareaElement {}
+impl ConstraintValidation for HTMLTextareaElement {}
 impl AsNode for HTMLTextareaElement {
     fn cast(&self) -> &crate::Node {
         AsNode::cast(&self.html_element)
@@ -70,7 +80,7 @@ impl TryFrom<HTMLElement> for HTMLTextareaElement {
 
     fn try_from(value: HTMLElement) -> Result<Self, Self::Error> {
         let tag = value.tag();
-        if matches!(value.element().base.borrow().tag, Tag::A) {
+        if matches!(value.element().base.borrow().tag, Tag::Textarea) {
             Ok(HTMLTextareaElement {
                 html_element: value,
             })
@@ -0,0 +1,576 @@
+//! The states of the `type` attribute of `input` elements, and the conversions between their values and numbers.
+//!
+//! [Reference](https://html.spec.whatwg.org/multipage/input.html#attr-input-type)
+use crate::{AsElement, Element};
+
+/// The state of the `type` attribute of an `input` element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InputType {
+    Hidden,
+    Text,
+    Search,
+    Tel,
+    Url,
+    Email,
+    Password,
+    Date,
+    Month,
+    Week,
+    Time,
+    DatetimeLocal,
+    Number,
+    Range,
+    Color,
+    Checkbox,
+    Radio,
+    File,
+    Submit,
+    Image,
+    Reset,
+    Button,
+}
+
+/// The modes of the `value` IDL attribute of an `input` element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ValueMode {
+    /// The attribute returns and sets the value of the element.
+    Value,
+    /// The attribute returns and sets the `value` content attribute, or the empty string.
+    Default,
+    /// The attribute returns and sets the `value` content attribute, or `on`.
+    DefaultOn,
+    /// The attribute returns the name of the first selected file, and can only be set to the empty string.
+    Filename,
+}
+
+const MILLISECONDS_PER_DAY: f64 = 86_400_000.0;
+
+impl InputType {
+    /// Returns the state of the `type` attribute of an `input` element. A missing or invalid value is the text state.
+    pub(crate) fn of(element: &Element) -> InputType {
+        InputType::parse(element.get_attribute("type"))
+    }
+
+    /// Returns the state of a value of the `type` attribute.
+    pub(crate) fn parse(value: Option<&str>) -> InputType {
+        match value.unwrap_or_default().to_ascii_lowercase().as_str() {
+            "hidden" => InputType::Hidden,
+            "search" => InputType::Search,
+            "tel" => InputType::Tel,
+            "url" => InputType::Url,
+            "email" => InputType::Email,
+            "password" => InputType::Password,
+            "date" => InputType::Date,
+            "month" => InputType::Month,
+            "week" => InputType::Week,
+            "time" => InputType::Time,
+            "datetime-local" => InputType::DatetimeLocal,
+            "number" => InputType::Number,
+            "range" => InputType::Range,
+            "color" => InputType::Color,
+            "checkbox" => InputType::Checkbox,
+            "radio" => InputType::Radio,
+            "file" => InputType::File,
+            "submit" => InputType::Submit,
+            "image" => InputType::Image,
+            "reset" => InputType::Reset,
+            "button" => InputType::Button,
+            _ => InputType::Text,
+        }
+    }
+
+    /// Returns the keyword of the state, which the `type` IDL attribute returns.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            InputType::Hidden => "hidden",
+            InputType::Text => "text",
+            InputType::Search => "search",
+            InputType::Tel => "tel",
+            InputType::Url => "url",
+            InputType::Email => "email",
+            InputType::Password => "password",
+            InputType::Date => "date",
+            InputType::Month => "month",
+            InputType::Week => "week",
+            InputType::Time => "time",
+            InputType::DatetimeLocal => "datetime-local",
+            InputType::Number => "number",
+            InputType::Range => "range",
+            InputType::Color => "color",
+            InputType::Checkbox => "checkbox",
+            InputType::Radio => "radio",
+            InputType::File => "file",
+            InputType::Submit => "submit",
+            InputType::Image => "image",
+            InputType::Reset => "reset",
+            InputType::Button => "button",
+        }
+    }
+
+    /// Returns the mode of the `value` IDL attribute for the type.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#dom-input-value)
+    pub(crate) fn value_mode(self) -> ValueMode {
+        match self {
+            InputType::Hidden
+            | InputType::Submit
+            | InputType::Image
+            | InputType::Reset
+            | InputType::Button => ValueMode::Default,
+            InputType::Checkbox | InputType::Radio => ValueMode::DefaultOn,
+            InputType::File => ValueMode::Filename,
+            _ => ValueMode::Value,
+        }
+    }
+
+    /// Returns true if the values of the type are free text, which the `maxlength`, `minlength` and `pattern` attributes apply to.
+    pub(crate) fn is_textual(self) -> bool {
+        matches!(
+            self,
+            InputType::Text
+                | InputType::Search
+                | InputType::Tel
+                | InputType::Url
+                | InputType::Email
+                | InputType::Password
+        )
+    }
+
+    /// Returns true if the values of the type convert to numbers, which the `min`, `max` and `step` attributes apply to.
+    pub(crate) fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            InputType::Date
+                | InputType::Month
+                | InputType::Week
+                | InputType::Time
+                | InputType::DatetimeLocal
+                | InputType::Number
+                | InputType::Range
+        )
+    }
+
+    /// Converts a value of the type to a number, if it is valid.
+    ///
+    /// Dates and times are milliseconds since the start of 1970 in UTC, months are months since January 1970, and times are milliseconds since midnight.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-value-string-number)
+    pub(crate) fn to_number(self, value: &str) -> Option<f64> {
+        match self {
+            InputType::Number | InputType::Range => parse_floating_point_number(value),
+            InputType::Date => {
+                let (year, month, day) = parse_date(value)?;
+                Some(days_from_civil(year, month, day) as f64 * MILLISECONDS_PER_DAY)
+            }
+            InputType::Month => {
+                let (year, month) = parse_month(value)?;
+                Some(((year - 1970) * 12 + month as i64 - 1) as f64)
+            }
+            InputType::Week => {
+                let (year, week) = parse_week(value)?;
+                let monday = first_monday_of_week_year(year) + (week as i64 - 1) * 7;
+                Some(monday as f64 * MILLISECONDS_PER_DAY)
+            }
+            InputType::Time => parse_time(value),
+            InputType::DatetimeLocal => {
+                let separator = value.find(['T', ' '])?;
+                let (year, month, day) = parse_date(&value[..separator])?;
+                let time = parse_time(&value[separator + 1..])?;
+                Some(days_from_civil(year, month, day) as f64 * MILLISECONDS_PER_DAY + time)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the default step, which the `step` attribute is a multiple of.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-step-default)
+    pub(crate) fn default_step(self) -> f64 {
+        match self {
+            InputType::Time | InputType::DatetimeLocal => 60.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Returns the factor that steps are multiplied with to be in the units of the numbers that values convert to.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-step-scale)
+    pub(crate) fn step_scale_factor(self) -> f64 {
+        match self {
+            InputType::Date => MILLISECONDS_PER_DAY,
+            InputType::Week => 7.0 * MILLISECONDS_PER_DAY,
+            InputType::Time | InputType::DatetimeLocal => 1000.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Returns the number that steps count from when the element has neither a `min` nor a `value` attribute.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-step-default-base)
+    pub(crate) fn default_step_base(self) -> f64 {
+        match self {
+            // The Monday of the first week of 1970.
+            InputType::Week => -259_200_000.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns true if the `valueAsDate` IDL attribute applies to the type.
+    pub(crate) fn has_date_value(self) -> bool {
+        matches!(
+            self,
+            InputType::Date | InputType::Month | InputType::Week | InputType::Time
+        )
+    }
+
+    /// Runs the value sanitization algorithm of the type. Values of `range` inputs are only checked to be numbers here, since their clamping depends on the attributes of the element.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#value-sanitization-algorithm)
+    pub(crate) fn sanitize(self, value: &str, multiple: bool) -> String {
+        let strip_newlines = |value: &str| value.replace(['\n', '\r'], "");
+        let is_valid = |valid: bool| match valid {
+            true => value.to_owned(),
+            false => String::new(),
+        };
+        match self {
+            InputType::Text | InputType::Search | InputType::Tel | InputType::Password => {
+                strip_newlines(value)
+            }
+            InputType::Url => strip_newlines(value)
+                .trim_matches(|char: char| char.is_ascii_whitespace())
+                .to_owned(),
+            InputType::Email if multiple => value
+                .split(',')
+                .map(|address| address.trim_matches(|char: char| char.is_ascii_whitespace()))
+                .collect::<Vec<_>>()
+                .join(","),
+            InputType::Email => strip_newlines(value)
+                .trim_matches(|char: char| char.is_ascii_whitespace())
+                .to_owned(),
+            InputType::Number | InputType::Range => {
+                is_valid(parse_floating_point_number(value).is_some())
+            }
+            InputType::Date => is_valid(parse_date(value).is_some()),
+            InputType::Month => is_valid(parse_month(value).is_some()),
+            InputType::Week => is_valid(parse_week(value).is_some()),
+            InputType::Time => is_valid(parse_time(value).is_some()),
+            // Valid values are normalized, with a `T` separator and the shortest time.
+            InputType::DatetimeLocal => match self.to_number(value) {
+                Some(number) => self.value_from_number(number),
+                None => String::new(),
+            },
+            InputType::Color => {
+                let is_simple_color = value.len() == 7
+                    && value.starts_with('#')
+                    && value[1..].bytes().all(|byte| byte.is_ascii_hexdigit());
+                match is_simple_color {
+                    true => value.to_ascii_lowercase(),
+                    false => String::from("#000000"),
+                }
+            }
+            _ => value.to_owned(),
+        }
+    }
+
+    /// Converts a number to a value of the type. The number is in the units of [`InputType::to_number`].
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-value-number-string)
+    pub(crate) fn value_from_number(self, number: f64) -> String {
+        match self {
+            InputType::Number | InputType::Range => format_number(number),
+            InputType::Date => format_date((number / MILLISECONDS_PER_DAY).floor() as i64),
+            InputType::Month => {
+                let months = number.floor() as i64;
+                format_month(
+                    1970 + months.div_euclid(12),
+                    months.rem_euclid(12) as u32 + 1,
+                )
+            }
+            InputType::Week => {
+                let days = (number / MILLISECONDS_PER_DAY).floor() as i64;
+                // A week belongs to the year of its Thursday.
+                let thursday = days - weekday(days) + 3;
+                let (year, _, _) = civil_from_days(thursday);
+                match year > 0 {
+                    true => format!(
+                        "{year:04}-W{:02}",
+                        (thursday - first_monday_of_week_year(year)) / 7 + 1
+                    ),
+                    false => String::new(),
+                }
+            }
+            InputType::Time => format_time(number.rem_euclid(MILLISECONDS_PER_DAY)),
+            InputType::DatetimeLocal => {
+                let days = (number / MILLISECONDS_PER_DAY).floor() as i64;
+                let date = format_date(days);
+                match date.is_empty() {
+                    true => date,
+                    false => format!(
+                        "{date}T{}",
+                        format_time(number.rem_euclid(MILLISECONDS_PER_DAY))
+                    ),
+                }
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Converts a value of the type to a date, in milliseconds since the start of 1970 in UTC. Months are the first day of the month, and times are on the first of January 1970.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-value-string-date)
+    pub(crate) fn to_date(self, value: &str) -> Option<f64> {
+        match self {
+            InputType::Month => {
+                let (year, month) = parse_month(value)?;
+                Some(days_from_civil(year, month, 1) as f64 * MILLISECONDS_PER_DAY)
+            }
+            InputType::Date | InputType::Week | InputType::Time => self.to_number(value),
+            _ => None,
+        }
+    }
+
+    /// Converts a date, in milliseconds since the start of 1970 in UTC, to a value of the type.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/input.html#concept-input-value-date-string)
+    pub(crate) fn value_from_date(self, date: f64) -> String {
+        match self {
+            InputType::Month => {
+                let (year, month, _) =
+                    civil_from_days((date / MILLISECONDS_PER_DAY).floor() as i64);
+                format_month(year, month)
+            }
+            InputType::Date | InputType::Week | InputType::Time => self.value_from_number(date),
+            _ => String::new(),
+        }
+    }
+
+    /// Returns the minimum and maximum that a type has without the `min` and `max` attributes.
+    pub(crate) fn default_range(self) -> (Option<f64>, Option<f64>) {
+        match self {
+            InputType::Range => (Some(0.0), Some(100.0)),
+            _ => (None, None),
+        }
+    }
+}
+
+/// Parses a valid floating-point number, which has no leading `+` and no trailing `.`.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-floating-point-number)
+pub(crate) fn parse_floating_point_number(value: &str) -> Option<f64> {
+    let digits = |text: &str| !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_digit());
+    let unsigned = value.strip_prefix('-').unwrap_or(value);
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(index) => (&unsigned[..index], Some(&unsigned[index + 1..])),
+        None => (unsigned, None),
+    };
+    let valid_mantissa = match mantissa.split_once('.') {
+        Some((integer, fraction)) => (integer.is_empty() || digits(integer)) && digits(fraction),
+        None => digits(mantissa),
+    };
+    let valid_exponent = exponent
+        .is_none_or(|exponent| digits(exponent.strip_prefix(['-', '+']).unwrap_or(exponent)));
+    if !valid_mantissa || !valid_exponent {
+        return None;
+    }
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
+/// Converts a number to the shortest string that converts back to it, like JavaScript does, which is the best representation of the number as a floating-point number.
+///
+/// [Reference](https://tc39.es/ecma262/#sec-numeric-types-number-tostring)
+pub(crate) fn format_number(number: f64) -> String {
+    if number == 0.0 {
+        return String::from("0");
+    }
+    let sign = if number < 0.0 { "-" } else { "" };
+    // The shortest digits, and the exponent of their first digit.
+    let scientific = format!("{:e}", number.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("Scientific notation has an exponent.");
+    let digits = mantissa.replace('.', "");
+    let length = digits.len() as i32;
+    let point = exponent.parse::<i32>().unwrap_or_default() + 1;
+    let formatted = if length <= point && point <= 21 {
+        format!("{digits}{}", "0".repeat((point - length) as usize))
+    } else if 0 < point && point <= 21 {
+        format!(
+            "{}.{}",
+            &digits[..point as usize],
+            &digits[point as usize..]
+        )
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{digits}", "0".repeat(-point as usize))
+    } else {
+        let exponent_sign = if point > 0 { '+' } else { '-' };
+        let fraction = match length {
+            1 => String::new(),
+            _ => format!(".{}", &digits[1..]),
+        };
+        format!(
+            "{}{fraction}e{exponent_sign}{}",
+            &digits[..1],
+            (point - 1).abs()
+        )
+    };
+    format!("{sign}{formatted}")
+}
+
+/// Parses a string of exactly `length` ASCII digits, or at least `length` digits if `at_least` is set.
+fn parse_digits(text: &str, length: usize, at_least: bool) -> Option<u32> {
+    let valid_length = text.len() == length || (at_least && text.len() > length);
+    (valid_length && text.bytes().all(|byte| byte.is_ascii_digit()))
+        .then(|| text.parse().ok())
+        .flatten()
+}
+
+fn parse_month(value: &str) -> Option<(i64, u32)> {
+    let (year, month) = value.split_once('-')?;
+    let year = parse_digits(year, 4, true).filter(|year| *year > 0)?;
+    let month = parse_digits(month, 2, false).filter(|month| (1..=12).contains(month))?;
+    Some((year as i64, month))
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-date-string)
+pub(crate) fn parse_date(value: &str) -> Option<(i64, u32, u32)> {
+    let (month, day) = value.rsplit_once('-')?;
+    let (year, month) = parse_month(month)?;
+    let day =
+        parse_digits(day, 2, false).filter(|day| (1..=days_in_month(year, month)).contains(day))?;
+    Some((year, month, day))
+}
+
+/// [Reference](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-week-string)
+fn parse_week(value: &str) -> Option<(i64, u32)> {
+    let (year, week) = value.split_once("-W")?;
+    let year = parse_digits(year, 4, true).filter(|year| *year > 0)? as i64;
+    let week =
+        parse_digits(week, 2, false).filter(|week| (1..=weeks_in_year(year)).contains(week))?;
+    Some((year, week))
+}
+
+/// Parses a valid time string to milliseconds since midnight.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-time-string)
+pub(crate) fn parse_time(value: &str) -> Option<f64> {
+    let mut parts = value.splitn(3, ':');
+    let hour = parse_digits(parts.next()?, 2, false).filter(|hour| *hour < 24)?;
+    let minute = parse_digits(parts.next()?, 2, false).filter(|minute| *minute < 60)?;
+    let second = match parts.next() {
+        Some(second) => {
+            let (whole, fraction) = match second.split_once('.') {
+                Some((whole, fraction)) => (whole, Some(fraction)),
+                None => (second, None),
+            };
+            let whole = parse_digits(whole, 2, false).filter(|second| *second < 60)?;
+            let fraction = match fraction {
+                Some(fraction) if (1..=3).contains(&fraction.len()) => {
+                    parse_digits(fraction, fraction.len(), false)? as f64
+                        / 10f64.powi(fraction.len() as i32)
+                }
+                Some(_) => return None,
+                None => 0.0,
+            };
+            whole as f64 + fraction
+        }
+        None => 0.0,
+    };
+    Some(((hour * 60 + minute) as f64 * 60.0 + second) * 1000.0)
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days between the first of January 1970 and a date.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the date that is a number of days after the first of January 1970.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats a valid date string, or returns the empty string for dates before the year 1.
+fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    match year > 0 {
+        true => format!("{year:04}-{month:02}-{day:02}"),
+        false => String::new(),
+    }
+}
+
+fn format_month(year: i64, month: u32) -> String {
+    match year > 0 {
+        true => format!("{year:04}-{month:02}"),
+        false => String::new(),
+    }
+}
+
+/// Formats milliseconds since midnight as the shortest valid time string, leaving out seconds and milliseconds that are zero.
+fn format_time(milliseconds: f64) -> String {
+    let milliseconds = milliseconds.round() as u64 % 86_400_000;
+    let (hour, minute) = (milliseconds / 3_600_000, milliseconds / 60_000 % 60);
+    let (second, millisecond) = (milliseconds / 1000 % 60, milliseconds % 1000);
+    match (second, millisecond) {
+        (0, 0) => format!("{hour:02}:{minute:02}"),
+        (_, 0) => format!("{hour:02}:{minute:02}:{second:02}"),
+        _ => format!("{hour:02}:{minute:02}:{second:02}.{millisecond:03}"),
+    }
+}
+
+/// Returns the day of the week of a number of days since 1970, where Monday is 0.
+fn weekday(days: i64) -> i64 {
+    // The first of January 1970 was a Thursday.
+    (days + 3).rem_euclid(7)
+}
+
+/// Returns the days since 1970 of the Monday of the first week of a year, which is the week with the year's first Thursday.
+fn first_monday_of_week_year(year: i64) -> i64 {
+    let january_fourth = days_from_civil(year, 1, 4);
+    january_fourth - weekday(january_fourth)
+}
+
+/// Returns 53 if the year starts on a Thursday, or on a Wednesday in a leap year, and 52 otherwise.
+fn weeks_in_year(year: i64) -> u32 {
+    let first_day = weekday(days_from_civil(year, 1, 1));
+    if first_day == 3 || (first_day == 2 && is_leap_year(year)) {
+        53
+    } else {
+        52
+    }
+}
@@ -1,5 +1,9 @@
 // #[warn(unused)]
 mod base;
+pub(crate) mod constraint_validation;
+mod directionality;
+pub(crate) mod form_associated;
+pub(crate) mod form_submission;
 mod html_anchor_element;
 mod html_area_element;
 mod html_audio_element;
@@ -27,7 +31,7 @@ mod html_hr_element;
 mod html_html_element;
 mod html_iframe_element;
 mod html_image_element;
-mod html_input_element;
+pub(crate) mod html_input_element;
 mod html_label_element;
 mod html_legend_element;
 mod html_li_element;
@@ -69,19 +73,23 @@ mod html_track_element;
 mod html_ulist_element;
 mod html_unknown_element;
 mod html_video_element;
+mod input_type;
 
 use crate::{
-    document::WeakDocumentRef, domitem::DOMItem, tag::Tag, AsChildNode, AsElement, AsEventTarget,
-    AsNode, AsParentNode, Element, Event, InnerHtml, Node, TimeRanges,
+    document::WeakDocumentRef, domitem::DOMItem, tag::Tag, AsChildNode, AsDocument, AsElement,
+    AsEventTarget, AsNode, AsParentNode, Document, Element, Event, InnerHtml, Node, ShadowRootInit,
+    TimeRanges,
 };
+pub use constraint_validation::{ConstraintValidation, ValidityState};
+pub use form_submission::NavigationRequest;
 pub use html_anchor_element::HTMLAnchorElement;
 pub use html_area_element::HTMLAreaElement;
 pub use html_audio_element::HTMLAudioElement;
 pub use html_base_element::HTMLBaseElement;
 pub use html_body_element::HTMLBodyElement;
 pub use html_br_element::HTMLBRElement;
-pub use html_button_element::{HTMLButtonElement, ValidityState};
-pub use html_canvas_element::HTMLCanvasElement;
+pub use html_button_element::HTMLButtonElement;
+pub use html_canvas_element::{CanvasRenderingContext2D, HTMLCanvasElement, TextMetrics};
 pub use html_data_element::HTMLDataElement;
 pub use html_datalist_element::HTMLDatalistElement;
 pub use html_details_element::HTMLDetailsElement;
@@ -92,7 +100,9 @@ pub use html_dlist_element::HTMLDListElement;
 pub use html_embed_element::HTMLEmbedElement;
 pub use html_fieldset_element::HTMLFieldsetElement;
 pub use html_font_element::HTMLFontElement;
-pub use html_form_element::HTMLFormElement;
+pub use html_form_element::{
+    HTMLFormControlsCollection, HTMLFormElement, RadioNodeList, RadioNodeListOrElement,
+};
 pub use html_frame_element::HTMLFrameElement;
 pub use html_frameset_element::HTMLFramesetElement;
 pub use html_head_element::HTMLHeadElement;
@@ -105,6 +115,7 @@ pub use html_input_element::HTMLInputElement;
 pub use html_label_element::HTMLLabelElement;
 pub use html_legend_element::HTMLLegendElement;
 pub use html_li_element::HTMLLiElement;
+pub(crate) use html_link_element::link_element_sheet;
 pub use html_link_element::HTMLLinkElement;
 pub use html_map_element::HTMLMapElement;
 pub use html_marquee_element::HTMLMarqueeElement;
@@ -123,6 +134,7 @@ pub use html_select_element::HTMLSelectElement;
 pub use html_slot_element::HTMLSlotElement;
 pub use html_source_element::HTMLSourceElement;
 pub use html_span_element::HTMLSpanElement;
+pub(crate) use html_style_element::style_element_sheet;
 pub use html_style_element::HTMLStyleElement;
 pub use html_table_element::HTMLTableElement;
 pub use html_tablecaption_element::HTMLTablecaptionElement;
@@ -130,6 +142,7 @@ pub use html_tablecell_element::HTMLTablecellElement;
 pub use html_tablecol_element::HTMLTablecolElement;
 pub use html_tablerow_element::HTMLTablerowElement;
 pub use html_tablesection_element::HTMLTablesectionElement;
+pub(crate) use html_template_element::template_contents;
 pub use html_template_element::HTMLTemplateElement;
 pub use html_textarea_element::HTMLTextareaElement;
 pub use html_time_element::HTMLTimeElement;
@@ -139,9 +152,9 @@ pub use html_unknown_element::HTMLUnknownElement;
 pub use html_video_element::HTMLVideoElement;
 
 pub(crate) use base::HTMLElementBase;
+pub(crate) use directionality::{dir_state, directionality, Direction};
 
 use std::{any::Any, cell::RefCell, rc::Rc};
-use unicode_bidi::{bidi_class, BidiClass};
 
 /// Any HTML element. Some elements directly implement this interface, while others implement it via an interface that inherits it.
 ///
@@ -155,7 +168,10 @@ impl Drop for HTMLElement {
     fn drop(&mut self) {
         // Disconnect node from document.
         if self.parent_node().is_none() && Rc::strong_count(&self.base) == 2 {
-            let mut document = self.owner_document().unwrap();
+            let mut document = match self.owner_document() {
+                Some(document) => document,
+                None => return,
+            };
 
             document.drop_node(AsNode::cast(self).get_base_ptr());
             debug_assert!(document
@@ -204,6 +220,42 @@ impl HTMLElement {
         self.base().element_mut()
     }
 
+    /// Creates a copy of an element in a document, with its attributes and clonable shadow root, and its descendants if `deep` is true.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#concept-node-clone)
+    pub(crate) fn clone_element(element: &Element, document: &Document, deep: bool) -> Self {
+        let mut copy = document.create_element(&element.base().tag.to_string());
+        for attr in element.attributes().iter() {
+            copy.set_attribute(attr.name(), attr.value());
+        }
+        html_input_element::clone_state(element, AsElement::cast(&copy));
+        if let Some(shadow_root) = element.base().shadow_root.as_ref() {
+            if shadow_root.clonable() {
+                let mut init = ShadowRootInit::new(shadow_root.mode());
+                init.delegates_focus = shadow_root.delegates_focus();
+                init.slot_assignment = shadow_root.slot_assignment();
+                init.clonable = true;
+                init.serializable = shadow_root.serializable();
+                let shadow_root_copy = copy
+                    .attach_shadow(init)
+                    .expect("The copy of a shadow host can host a shadow root.");
+                AsNode::cast(shadow_root)
+                    .clone_children_into(AsNode::cast_mut(shadow_root_copy), document);
+            }
+        }
+        if deep {
+            AsNode::cast(element).clone_children_into(AsNode::cast_mut(&mut copy), document);
+            // The cloning steps for templates copy their contents into the contents of the copy.
+            if let (Some(content), Some(mut content_copy)) =
+                (template_contents(element), template_contents(&copy))
+            {
+                let content_document = document.template_contents_owner();
+                content.clone_children_into(&mut content_copy, &content_document);
+            }
+        }
+        copy
+    }
+
     pub(crate) fn tag(&self) -> &Tag {
         &(unsafe { &*self.element().base.as_ptr() }).tag
     }
@@ -224,11 +276,11 @@ impl AsChildNode for HTMLElement {}
 
 impl InnerHtml for HTMLElement {
     fn inner_html(&self) -> String {
-        todo!()
+        AsElement::cast(self).inner_html()
     }
 
     fn set_inner_html(&mut self, value: &str) -> Result<(), crate::DOMException> {
-        todo!()
+        AsElement::cast_mut(self).set_inner_html(value)
     }
 }
 
@@ -241,8 +293,30 @@ impl AsNode for HTMLElement {
         AsNode::cast_mut(AsElement::cast_mut(self))
     }
 
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut host = document.create_element("div");
+    /// host.set_html_unsafe(
+    ///     "<section title=Card><template shadowrootmode=open shadowrootclonable><slot></slot></template>Content</section>",
+    /// );
+    /// let section = host.first_element_child().unwrap();
+    ///
+    /// let copy = host.clone_node(true);
+    /// assert_eq!(copy.inner_html(), r#"<section title="Card">Content</section>"#);
+    ///
+    /// // Clonable shadow roots are cloned with their host.
+    /// let section_copy = copy.first_element_child().unwrap();
+    /// assert!(section_copy != section);
+    /// assert_eq!(section_copy.shadow_root().unwrap().inner_html(), "<slot></slot>");
+    /// ```
     fn clone_node(&self, deep: bool) -> Self {
-        todo!()
+        let document = self
+            .owner_document()
+            .expect("Elements always belong to a document.");
+        HTMLElement::clone_element(AsElement::cast(self), &document, deep)
     }
 }
 
@@ -298,11 +372,12 @@ pub trait AsHTMLElement: AsElement {
     ///
     /// MDN Reference: [autocapitalize](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/autocapitalize)
     fn autocapitalize(&self) -> &str {
-        let state = helpers::get_own_capitalization_hint(self);
-        match state {
-            "default" => "",
-            "none" | "sentences" => state,
-            _ => todo!(),
+        match helpers::get_own_capitalization_hint(self).as_str() {
+            "none" => "none",
+            "sentences" => "sentences",
+            "words" => "words",
+            "characters" => "characters",
+            _ => "",
         }
     }
     /// Sets the value that controls whether and how text input is automatically capitalized as it is entered by the user.
@@ -312,14 +387,149 @@ pub trait AsHTMLElement: AsElement {
         self.set_attribute("autocapitalize", value);
         todo!()
     }
-    /// Returns a value indicating the writing direction of the content in this element.
+    /// Returns the writing direction that the `dir` attribute sets on the element: `"ltr"`, `"rtl"` or `"auto"`, or an empty string when it is missing or invalid.
+    ///
+    /// The direction that the element actually has, which can be inherited or detected from its text, is what the `:dir()` pseudo-class matches.
     ///
     /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLElement/dir)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::parse_html_unsafe("<body></body>");
+    /// let html = document.first_element_child().unwrap();
+    /// let mut body = html.children().item(1).unwrap();
+    /// let mut article = document.create_element("article");
+    /// article.set_inner_html("<p>שלום</p><p dir=auto>Hello</p>").unwrap();
+    /// article.set_dir("RTL");
+    /// body.append_child(&mut article).unwrap();
+    ///
+    /// assert_eq!(article.dir(), "rtl");
+    /// let paragraphs = article.children();
+    /// assert!(paragraphs.item(0).unwrap().matches(":dir(rtl)"));
+    /// assert!(paragraphs.item(1).unwrap().matches(":dir(ltr)"));
+    /// ```
     fn dir(&self) -> &str {
-        todo!()
+        dir_state(AsElement::cast(self)).unwrap_or_default()
     }
+    /// Sets the writing direction of the element.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/HTMLElement/dir)
     fn set_dir(&mut self, value: &str) {
-        todo!()
+        self.set_attribute("dir", value);
+    }
+    /// Returns the text of the element as it is rendered: with white space collapsed, with line breaks between blocks and at `<br>` elements, and without the text that is hidden.
+    ///
+    /// Elements that are not being rendered, like those with `display: none` or outside a document, return their [`text_content`] instead.
+    ///
+    /// MDN Reference: [`HTMLElement.innerText`](https://developer.mozilla.org/docs/Web/API/HTMLElement/innerText)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::parse_html_unsafe("<body></body>");
+    /// let html = document.first_element_child().unwrap();
+    /// let mut body = html.children().item(1).unwrap();
+    /// let mut article = document.create_element("article");
+    /// article
+    ///     .set_inner_html(
+    ///         "<h1>Title</h1>
+    ///         <p>First   line<br>second line</p>
+    ///         <p style='display: none'>Hidden</p>",
+    ///     )
+    ///     .unwrap();
+    /// body.append_child(&mut article).unwrap();
+    ///
+    /// assert_eq!(article.inner_text(), "Title\n\nFirst line\nsecond line");
+    /// ```
+    ///
+    /// [`text_content`]: crate::AsNode::text_content
+    fn inner_text(&self) -> String {
+        let element = AsElement::cast(self);
+        crate::layout::rendered_text(element)
+            .unwrap_or_else(|| element.text_content().unwrap_or_default())
+    }
+    /// Replaces the children of the element with the text, with `<br>` elements for its line breaks.
+    ///
+    /// MDN Reference: [`HTMLElement.innerText`](https://developer.mozilla.org/docs/Web/API/HTMLElement/innerText)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut element = document.create_element("div");
+    /// element.set_inner_text("Hello,\nthere!");
+    /// assert_eq!(element.inner_html(), "Hello,<br>there!");
+    /// ```
+    fn set_inner_text(&mut self, value: &str) {
+        let element = AsElement::cast_mut(self);
+        let Some(document) = element.owner_document() else {
+            return;
+        };
+        let mut fragment = helpers::rendered_text_fragment(&document, value);
+        AsNode::cast_mut(element).replace_all(&mut fragment);
+    }
+    /// Returns the text of the element as it is rendered, like [`inner_text`](AsHTMLElement::inner_text).
+    ///
+    /// MDN Reference: [`HTMLElement.outerText`](https://developer.mozilla.org/docs/Web/API/HTMLElement/outerText)
+    fn outer_text(&self) -> String {
+        self.inner_text()
+    }
+    /// Replaces the element itself with the text, with `<br>` elements for its line breaks. Text next to the element is merged with the text that replaces it.
+    ///
+    /// # Errors
+    /// - Returns a [`NoModificationAllowedError`] DOMException if the element has no parent.
+    ///
+    /// MDN Reference: [`HTMLElement.outerText`](https://developer.mozilla.org/docs/Web/API/HTMLElement/outerText)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml};
+    ///
+    /// let document = Document::new();
+    /// let mut paragraph = document.create_element("p");
+    /// paragraph.set_inner_html("Hello, ").unwrap();
+    /// let mut bold = document.create_element("b");
+    /// bold.set_inner_html("world").unwrap();
+    /// paragraph.append_child(&mut bold).unwrap();
+    /// paragraph.append_child(&mut document.create_text_node("!")).unwrap();
+    ///
+    /// bold.set_outer_text("there").unwrap();
+    /// assert_eq!(paragraph.inner_html(), "Hello, there!");
+    /// assert_eq!(paragraph.child_nodes().len(), 1);
+    /// ```
+    ///
+    /// [`NoModificationAllowedError`]: crate::DOMException::NoModificationAllowedError
+    fn set_outer_text(&mut self, value: &str) -> Result<(), crate::DOMException> {
+        let element = AsElement::cast_mut(self);
+        let Some(mut parent) = element.parent_node() else {
+            return Err(crate::DOMException::NoModificationAllowedError(
+                String::from("The element has no parent."),
+            ));
+        };
+        let document = element
+            .owner_document()
+            .expect("Elements always belong to a document.");
+        let previous = element
+            .previous_sibling()
+            .map(|node| AsNode::cast(node).clone_ref());
+        let next = element
+            .next_sibling()
+            .map(|node| AsNode::cast(node).clone_ref());
+        let mut fragment = helpers::rendered_text_fragment(&document, value);
+        if value.is_empty() {
+            fragment.append_child(&mut document.create_text_node(""))?;
+        }
+        parent.replace_child(&mut fragment, AsNode::cast_mut(element))?;
+        // The text is merged with the text that was next to the element.
+        if let Some(next) = next {
+            if let Some(node) = next.previous_sibling() {
+                helpers::merge_with_next_text(node);
+            }
+        }
+        if let Some(previous) = previous {
+            helpers::merge_with_next_text(&previous);
+        }
+        Ok(())
     }
 }
 
@@ -337,30 +547,90 @@ pub trait AsHTMLElement: AsElement {
 // }
 
 mod helpers {
-    use crate::{element, AsHTMLElement, HTMLElement, HTMLFormElement};
+    use crate::{
+        domitem::DOMItem, element, tag::Tag, AsChildNode, AsDocument, AsElement, AsHTMLElement,
+        AsNode, Document, DocumentFragment, HTMLElement, HTMLFormElement, Node,
+    };
 
-    pub fn get_own_capitalization_hint<'a>(element: &'a impl AsHTMLElement) -> &'a str {
-        match element.get_attribute("autocapitalize") {
-            Some(value) if value != "" => value,
+    /// Returns a fragment with the lines of a string as text nodes, separated by `<br>` elements.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/dom.html#rendered-text-fragment)
+    pub fn rendered_text_fragment(document: &Document, value: &str) -> DocumentFragment {
+        let mut fragment = document.create_document_fragment();
+        let value = value.replace("\r\n", "\n").replace('\r', "\n");
+        for (index, line) in value.split('\n').enumerate() {
+            if index > 0 {
+                fragment
+                    .append_child(&mut document.create_element("br"))
+                    .expect("Elements are always valid children of fragments.");
+            }
+            if !line.is_empty() {
+                fragment
+                    .append_child(&mut document.create_text_node(line))
+                    .expect("Text nodes are always valid children of fragments.");
+            }
+        }
+        fragment
+    }
+
+    /// Merges a text node with the text node after it, if both are text nodes.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/dom.html#merge-with-the-next-text-node)
+    pub fn merge_with_next_text(node: &impl AsNode) {
+        if node.node_type() != Node::TEXT_NODE {
+            return;
+        }
+        let Some(mut next) = node
+            .next_sibling()
+            .map(|next| AsNode::cast(next).clone_ref())
+        else {
+            return;
+        };
+        if next.node_type() != Node::TEXT_NODE {
+            return;
+        }
+        let data = next.base().data.clone();
+        AsNode::cast(node).base().data.push_str(&data);
+        if let Some(mut parent) = next.parent_node() {
+            let _ = parent.remove_child(&mut next);
+        }
+    }
+
+    pub fn get_own_capitalization_hint(element: &impl AsHTMLElement) -> String {
+        // The keywords map to states, and a missing or invalid value is the default state.
+        let state = element.get_attribute("autocapitalize").map(|value| {
+            match value.to_ascii_lowercase().as_str() {
+                "off" | "none" => "none",
+                "on" | "sentences" => "sentences",
+                "words" => "words",
+                "characters" => "characters",
+                _ => "default",
+            }
+        });
+        match state {
+            Some(state) if state != "default" => state.to_owned(),
             _ => {
                 if is_autocapitalize_inheriting_element(element) {
                     match form_owner(element) {
-                        Some(form) => get_own_capitalization_hint(form),
-                        _ => "default",
+                        Some(form) => get_own_capitalization_hint(&form),
+                        _ => String::from("default"),
                     }
                 } else {
-                    "default"
+                    String::from("default")
                 }
             }
         }
     }
 
     pub fn is_autocapitalize_inheriting_element(element: &impl AsHTMLElement) -> bool {
-        todo!()
+        matches!(
+            AsElement::cast(element).base().tag,
+            Tag::Button | Tag::Fieldset | Tag::Input | Tag::Output | Tag::Select | Tag::Textarea
+        )
     }
 
-    pub fn form_owner<'a>(element: &'a impl AsHTMLElement) -> Option<&'a HTMLElement> {
-        todo!()
+    pub fn form_owner(element: &impl AsHTMLElement) -> Option<HTMLFormElement> {
+        super::form_associated::form(AsElement::cast(element))
     }
 }
 
@@ -0,0 +1,11 @@
+//! The HTML parser and serializer behind [`InnerHtml`], [`AsElement::get_html`] and [`Document::parse_html_unsafe`].
+//!
+//! [`InnerHtml`]: crate::InnerHtml
+//! [`AsElement::get_html`]: crate::AsElement::get_html
+//! [`Document::parse_html_unsafe`]: crate::Document::parse_html_unsafe
+mod serializer;
+mod tokenizer;
+mod tree_builder;
+
+pub(crate) use serializer::{serialize_children, serialize_node, SerializeOptions};
+pub(crate) use tree_builder::{parse_document, parse_fragment};
@@ -0,0 +1,158 @@
+use crate::{
+    domitem::DOMItem, tag::Tag, AsElement, AsNode, Element, Node, ShadowRoot, ShadowRootMode,
+};
+
+/// The options used to serialize shadow roots, as given to [`AsElement::get_html`].
+pub(crate) struct SerializeOptions<'a> {
+    pub serializable_shadow_roots: bool,
+    pub shadow_roots: &'a [&'a ShadowRoot],
+}
+
+impl SerializeOptions<'_> {
+    /// Options that leave out all shadow roots, as used by `inner_html`.
+    pub(crate) const NONE: SerializeOptions<'static> = SerializeOptions {
+        serializable_shadow_roots: false,
+        shadow_roots: &[],
+    };
+
+    fn includes(&self, shadow_root: &ShadowRoot) -> bool {
+        (self.serializable_shadow_roots && shadow_root.serializable())
+            || self.shadow_roots.contains(&shadow_root)
+    }
+}
+
+/// Serializes the children of a node, and the shadow root of the node if the options ask for it.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#html-fragment-serialisation-algorithm)
+pub(crate) fn serialize_children(node: &impl AsNode, options: &SerializeOptions) -> String {
+    let mut output = String::new();
+    write_children(AsNode::cast(node), options, &mut output);
+    output
+}
+
+/// Serializes a node and its descendants.
+pub(crate) fn serialize_node(node: &impl AsNode, options: &SerializeOptions) -> String {
+    let mut output = String::new();
+    write_node(AsNode::cast(node), None, options, &mut output);
+    output
+}
+
+fn element_of(node: &Node) -> Option<Element> {
+    node.owner_document()?
+        .lookup_html_element(node.get_base_ptr())
+}
+
+fn write_children(node: &Node, options: &SerializeOptions, output: &mut String) {
+    let element = element_of(node);
+    if let Some(element) = &element {
+        if let Some(shadow_root) = element.base().shadow_root.as_ref() {
+            if options.includes(shadow_root) {
+                write_shadow_root(shadow_root, options, output);
+            }
+        }
+    }
+    let tag = element.as_ref().map(|element| &element.base().tag);
+    // Templates are serialized with their contents in place of their children.
+    if let Some(content) = crate::html_element::template_contents(node) {
+        for child in content.child_nodes() {
+            write_node(AsNode::cast(child), tag, options, output);
+        }
+        return;
+    }
+    for child in node.child_nodes() {
+        write_node(AsNode::cast(child), tag, options, output);
+    }
+}
+
+fn write_shadow_root(shadow_root: &ShadowRoot, options: &SerializeOptions, output: &mut String) {
+    output.push_str("<template shadowrootmode=\"");
+    output.push_str(match shadow_root.mode() {
+        ShadowRootMode::Open => "open",
+        ShadowRootMode::Closed => "closed",
+    });
+    output.push('"');
+    if shadow_root.delegates_focus() {
+        output.push_str(" shadowrootdelegatesfocus=\"\"");
+    }
+    if shadow_root.serializable() {
+        output.push_str(" shadowrootserializable=\"\"");
+    }
+    if shadow_root.clonable() {
+        output.push_str(" shadowrootclonable=\"\"");
+    }
+    output.push('>');
+    write_children(AsNode::cast(shadow_root), options, output);
+    output.push_str("</template>");
+}
+
+fn write_node(
+    node: &Node,
+    parent_tag: Option<&Tag>,
+    options: &SerializeOptions,
+    output: &mut String,
+) {
+    match node.node_type() {
+        Node::ELEMENT_NODE => {
+            let element = match element_of(node) {
+                Some(element) => element,
+                None => return,
+            };
+            let tag = &element.base().tag;
+            output.push('<');
+            output.push_str(&tag.to_string());
+            for attribute in element.attributes().iter() {
+                output.push(' ');
+                output.push_str(attribute.name());
+                output.push_str("=\"");
+                escape(attribute.value(), true, output);
+                output.push('"');
+            }
+            output.push('>');
+            if tag.is_void() {
+                return;
+            }
+            write_children(node, options, output);
+            output.push_str("</");
+            output.push_str(&tag.to_string());
+            output.push('>');
+        }
+        Node::TEXT_NODE => {
+            let data = &node.base().data;
+            match parent_tag {
+                Some(
+                    Tag::Style
+                    | Tag::Script
+                    | Tag::Xmp
+                    | Tag::Iframe
+                    | Tag::Noembed
+                    | Tag::Noframes
+                    | Tag::Plaintext,
+                ) => output.push_str(data),
+                _ => escape(data, false, output),
+            }
+        }
+        Node::COMMENT_NODE => {
+            output.push_str("<!--");
+            output.push_str(&node.base().data);
+            output.push_str("-->");
+        }
+        Node::DOCUMENT_FRAGMENT_NODE | Node::DOCUMENT_NODE => write_children(node, options, output),
+        _ => {}
+    }
+}
+
+/// Escapes a string for use in text or an attribute value.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#escapingString)
+fn escape(input: &str, in_attribute: bool, output: &mut String) {
+    for char in input.chars() {
+        match char {
+            '&' => output.push_str("&amp;"),
+            '\u{a0}' => output.push_str("&nbsp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' if in_attribute => output.push_str("&quot;"),
+            char => output.push(char),
+        }
+    }
+}
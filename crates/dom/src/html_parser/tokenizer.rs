@@ -0,0 +1,441 @@
+use std::{iter::Peekable, str::CharIndices};
+
+/// A token emitted by the [`Tokenizer`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Doctype(String),
+    StartTag {
+        name: String,
+        attributes: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    EndTag {
+        name: String,
+    },
+    Comment(String),
+    Text(String),
+}
+
+/// The content model of the text that follows a start tag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TextState {
+    /// Ordinary markup.
+    Data,
+    /// Text in which character references are decoded, but no tags are recognized, e.g. `<textarea>`.
+    RcData,
+    /// Text in which neither character references nor tags are recognized, e.g. `<script>`.
+    RawText,
+}
+
+impl TextState {
+    /// Returns the state the tokenizer switches to after the start tag of an element.
+    pub(crate) fn after_start_tag(name: &str) -> Self {
+        match name {
+            "title" | "textarea" => Self::RcData,
+            "script" | "style" | "xmp" | "iframe" | "noembed" | "noframes" | "plaintext" => {
+                Self::RawText
+            }
+            _ => Self::Data,
+        }
+    }
+}
+
+/// Splits an HTML string into tokens.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#tokenization)
+pub(crate) struct Tokenizer<'a> {
+    input: &'a str,
+    position: usize,
+    state: TextState,
+    /// The name of the element whose raw text or RCDATA content is being consumed.
+    appropriate_end_tag: String,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            position: 0,
+            state: TextState::Data,
+            appropriate_end_tag: String::new(),
+        }
+    }
+
+    /// Creates a tokenizer that starts in the text state of a context element, as in fragment parsing.
+    pub(crate) fn for_context(input: &'a str, context: &str) -> Self {
+        let mut tokenizer = Self::new(input);
+        tokenizer.state = TextState::after_start_tag(context);
+        tokenizer.appropriate_end_tag = context.to_owned();
+        tokenizer
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    /// Consumes text in the raw text or RCDATA states, up to the appropriate end tag.
+    fn consume_raw_text(&mut self) -> Option<Token> {
+        let rest = self.rest();
+        let end = match self.appropriate_end_tag.as_str() {
+            "plaintext" => rest.len(),
+            name => find_end_tag(rest, name).unwrap_or(rest.len()),
+        };
+        let state = std::mem::replace(&mut self.state, TextState::Data);
+        self.position += end;
+        let text = &rest[..end];
+        if text.is_empty() {
+            return self.next();
+        }
+        Some(Token::Text(match state {
+            TextState::RcData => decode_character_references(text, false),
+            _ => text.to_owned(),
+        }))
+    }
+
+    /// Consumes a run of text up to the next markup.
+    fn consume_text(&mut self) -> Token {
+        let rest = self.rest();
+        let end = rest
+            .match_indices('<')
+            .map(|(index, _)| index)
+            .find(|index| *index > 0 && starts_markup(&rest[*index..]))
+            .unwrap_or(rest.len());
+        self.position += end;
+        Token::Text(decode_character_references(&rest[..end], false))
+    }
+
+    /// Consumes a comment, after the opening `<!--`.
+    fn consume_comment(&mut self) -> Token {
+        let rest = self.rest();
+        // "<!-->" and "<!--->" are empty comments.
+        for abrupt in ["-->", "->"] {
+            if rest.starts_with(abrupt) {
+                self.position += abrupt.len();
+                return Token::Comment(String::new());
+            }
+        }
+        match rest.find("-->") {
+            Some(end) => {
+                self.position += end + 3;
+                Token::Comment(rest[..end].to_owned())
+            }
+            None => {
+                self.position = self.input.len();
+                Token::Comment(rest.to_owned())
+            }
+        }
+    }
+
+    /// Consumes a bogus comment or a doctype, i.e. anything from `<!` or `<?` to the next `>`.
+    fn consume_declaration(&mut self) -> Token {
+        let rest = self.rest();
+        let end = rest.find('>').unwrap_or(rest.len());
+        self.position = (self.position + end + 1).min(self.input.len());
+        let content = &rest[..end];
+        if content.len() >= 8 && content[..8].eq_ignore_ascii_case("!doctype") {
+            let name = content[8..].split_whitespace().next().unwrap_or("");
+            return Token::Doctype(name.to_ascii_lowercase());
+        }
+        let content = content.strip_prefix('!').unwrap_or(content);
+        Token::Comment(content.to_owned())
+    }
+
+    /// Consumes a start or end tag, after the opening `<` or `</`.
+    fn consume_tag(&mut self, is_end_tag: bool) -> Token {
+        let input = self.rest();
+        let mut chars = input.char_indices().peekable();
+        let mut name = String::new();
+        while let Some((_, char)) = chars.next_if(|(_, char)| !is_tag_delimiter(*char)) {
+            name.push(char.to_ascii_lowercase());
+        }
+        let mut attributes: Vec<(String, String)> = vec![];
+        let mut self_closing = false;
+        loop {
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                None => {
+                    self.position = self.input.len();
+                    break;
+                }
+                Some((index, '>')) => {
+                    self.position += index + 1;
+                    break;
+                }
+                Some((_, '/')) => {
+                    self_closing = chars.peek().is_some_and(|(_, char)| *char == '>');
+                }
+                Some((_, first)) => {
+                    let mut attribute_name = String::from(first.to_ascii_lowercase());
+                    while let Some((_, char)) =
+                        chars.next_if(|(_, char)| !is_tag_delimiter(*char) && *char != '=')
+                    {
+                        attribute_name.push(char.to_ascii_lowercase());
+                    }
+                    skip_whitespace(&mut chars);
+                    let value = match chars.next_if(|(_, char)| *char == '=') {
+                        Some(_) => {
+                            skip_whitespace(&mut chars);
+                            consume_attribute_value(&mut chars)
+                        }
+                        None => String::new(),
+                    };
+                    // Duplicate attributes are dropped.
+                    if !attributes.iter().any(|(name, _)| *name == attribute_name) {
+                        attributes.push((attribute_name, value));
+                    }
+                }
+            }
+            if chars.peek().is_none() {
+                self.position = self.input.len();
+                break;
+            }
+        }
+        if is_end_tag {
+            Token::EndTag { name }
+        } else {
+            self.state = TextState::after_start_tag(&name);
+            self.appropriate_end_tag = name.clone();
+            Token::StartTag {
+                name,
+                attributes,
+                self_closing,
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state != TextState::Data {
+            return self.consume_raw_text();
+        }
+        let rest = self.rest();
+        if rest.is_empty() {
+            return None;
+        }
+        if !starts_markup(rest) {
+            return Some(self.consume_text());
+        }
+        if rest.starts_with("<!--") {
+            self.position += 4;
+            Some(self.consume_comment())
+        } else if rest.starts_with("</") {
+            self.position += 2;
+            if rest.as_bytes().get(2) == Some(&b'>') {
+                // "</>" is ignored entirely.
+                self.position += 1;
+                return self.next();
+            }
+            match rest.as_bytes().get(2) {
+                Some(char) if char.is_ascii_alphabetic() => Some(self.consume_tag(true)),
+                _ => Some(self.consume_declaration()),
+            }
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            self.position += 1;
+            Some(self.consume_declaration())
+        } else {
+            self.position += 1;
+            Some(self.consume_tag(false))
+        }
+    }
+}
+
+/// Returns true if the string starts with a tag, comment or declaration.
+fn starts_markup(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    if bytes.first() != Some(&b'<') {
+        return false;
+    }
+    match bytes.get(1) {
+        Some(b'!') | Some(b'?') => true,
+        Some(b'/') => bytes
+            .get(2)
+            .is_some_and(|char| char.is_ascii_alphabetic() || *char == b'>'),
+        Some(char) => char.is_ascii_alphabetic(),
+        None => false,
+    }
+}
+
+fn is_tag_delimiter(char: char) -> bool {
+    char.is_ascii_whitespace() || char == '/' || char == '>'
+}
+
+fn skip_whitespace(chars: &mut Peekable<CharIndices>) {
+    while chars
+        .next_if(|(_, char)| char.is_ascii_whitespace())
+        .is_some()
+    {}
+}
+
+fn consume_attribute_value(chars: &mut Peekable<CharIndices>) -> String {
+    let mut value = String::new();
+    match chars.next_if(|(_, char)| *char == '"' || *char == '\'') {
+        Some((_, quote)) => {
+            for (_, char) in chars.by_ref() {
+                if char == quote {
+                    break;
+                }
+                value.push(char);
+            }
+        }
+        None => {
+            while let Some((_, char)) =
+                chars.next_if(|(_, char)| !char.is_ascii_whitespace() && *char != '>')
+            {
+                value.push(char);
+            }
+        }
+    }
+    decode_character_references(&value, true)
+}
+
+/// Returns the index of the first end tag with the given name, matched case-insensitively.
+fn find_end_tag(input: &str, name: &str) -> Option<usize> {
+    input
+        .match_indices("</")
+        .map(|(index, _)| index)
+        .find(|index| {
+            let after = &input[index + 2..];
+            after.len() >= name.len()
+                && after[..name.len()].eq_ignore_ascii_case(name)
+                && after[name.len()..]
+                    .chars()
+                    .next()
+                    .is_none_or(is_tag_delimiter)
+        })
+}
+
+/// Named character references that are recognized without a trailing semicolon.
+const LEGACY_REFERENCES: [&str; 7] = ["amp", "lt", "gt", "quot", "nbsp", "copy", "reg"];
+
+/// Returns the character for a named character reference.
+fn named_reference(name: &str) -> Option<char> {
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "bull" => '•',
+        "middot" => '·',
+        "times" => '×',
+        "divide" => '÷',
+        "minus" => '−',
+        "plusmn" => '±',
+        "deg" => '°',
+        "sect" => '§',
+        "para" => '¶',
+        "shy" => '\u{ad}',
+        "euro" => '€',
+        "pound" => '£',
+        "yen" => '¥',
+        "cent" => '¢',
+        "larr" => '←',
+        "rarr" => '→',
+        "uarr" => '↑',
+        "darr" => '↓',
+        "harr" => '↔',
+        "hearts" => '♥',
+        "star" => '☆',
+        "check" => '✓',
+        "zwj" => '\u{200d}',
+        "zwnj" => '\u{200c}',
+        "ensp" => '\u{2002}',
+        "emsp" => '\u{2003}',
+        "thinsp" => '\u{2009}',
+        _ => return None,
+    })
+}
+
+/// Replaces the character references in a string with the characters they represent.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#character-reference-state)
+pub(crate) fn decode_character_references(input: &str, in_attribute: bool) -> String {
+    if !input.contains('&') {
+        return input.to_owned();
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(index) = rest.find('&') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let (char, consumed) = match rest.strip_prefix('#') {
+            Some(numeric) => decode_numeric_reference(numeric),
+            None => decode_named_reference(rest, in_attribute),
+        };
+        match char {
+            Some(char) => {
+                output.push(char);
+                rest = &rest[consumed..];
+            }
+            None => output.push('&'),
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Decodes the part of a numeric character reference after the `#`. Returns the character and the number of bytes consumed, including the `#`.
+fn decode_numeric_reference(input: &str) -> (Option<char>, usize) {
+    let (radix, digits_start) = match input.as_bytes().first() {
+        Some(b'x') | Some(b'X') => (16, 1),
+        _ => (10, 0),
+    };
+    let digits = input[digits_start..]
+        .chars()
+        .take_while(|char| char.is_digit(radix))
+        .count();
+    if digits == 0 {
+        return (None, 0);
+    }
+    let code =
+        u32::from_str_radix(&input[digits_start..digits_start + digits], radix).unwrap_or(u32::MAX);
+    let mut consumed = 1 + digits_start + digits;
+    if input[digits_start + digits..].starts_with(';') {
+        consumed += 1;
+    }
+    let char = match code {
+        0 => '\u{fffd}',
+        _ => char::from_u32(code).unwrap_or('\u{fffd}'),
+    };
+    (Some(char), consumed)
+}
+
+/// Decodes the part of a named character reference after the `&`. Returns the character and the number of bytes consumed.
+fn decode_named_reference(input: &str, in_attribute: bool) -> (Option<char>, usize) {
+    let name_length = input
+        .chars()
+        .take_while(|char| char.is_ascii_alphanumeric())
+        .count();
+    let name = &input[..name_length];
+    if input[name_length..].starts_with(';') {
+        return (named_reference(name), name_length + 1);
+    }
+    // Legacy references without a semicolon, which are left alone in attributes when followed by an alphanumeric character or "=".
+    for legacy in LEGACY_REFERENCES {
+        if let Some(after) = input.strip_prefix(legacy) {
+            let next = after.chars().next();
+            if in_attribute && next.is_some_and(|char| char.is_ascii_alphanumeric() || char == '=')
+            {
+                return (None, 0);
+            }
+            return (named_reference(legacy), legacy.len());
+        }
+    }
+    (None, 0)
+}
@@ -0,0 +1,696 @@
+use super::tokenizer::{Token, Tokenizer};
+use crate::{
+    domitem::DOMItem, node::NodeType, tag::Tag, AsDocument, AsElement, AsNode, Document,
+    DocumentFragment, HTMLElement, Node, ShadowRootInit, ShadowRootMode,
+};
+
+/// The insertion modes of the tree builder. Only the modes needed to place the `<html>`, `<head>` and `<body>` elements are modelled, everything else is parsed as if in the "in body" mode.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#the-insertion-mode)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InsertionMode {
+    Initial,
+    BeforeHead,
+    InHead,
+    AfterHead,
+    InBody,
+}
+
+/// An entry in the stack of open elements.
+struct OpenElement {
+    element: HTMLElement,
+    tag: Tag,
+    /// The node that children of the element are inserted into. This is the element itself, or the shadow root of a declarative shadow root template.
+    container: Node,
+}
+
+/// Builds a DOM tree from the tokens of an HTML string.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#tree-construction)
+pub(crate) struct TreeBuilder {
+    document: Document,
+    /// The node that content is inserted into when the stack of open elements is empty.
+    root: Node,
+    stack: Vec<OpenElement>,
+    mode: InsertionMode,
+    /// Whether `<template shadowrootmode>` elements attach shadow roots.
+    allow_declarative_shadow_roots: bool,
+    /// Whether a newline at the start of the next text should be dropped, as after `<pre>`.
+    skip_newline: bool,
+}
+
+/// Parses an HTML string into a document.
+pub(crate) fn parse_document(
+    document: &Document,
+    input: &str,
+    allow_declarative_shadow_roots: bool,
+) {
+    let mut builder = TreeBuilder {
+        document: document.clone(),
+        root: AsNode::cast(document).clone_ref(),
+        stack: vec![],
+        mode: InsertionMode::Initial,
+        allow_declarative_shadow_roots,
+        skip_newline: false,
+    };
+    builder.run(Tokenizer::new(&normalize_newlines(input)));
+}
+
+/// Parses an HTML string in the context of an element, returning the parsed nodes in a fragment.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#html-fragment-parsing-algorithm)
+pub(crate) fn parse_fragment(
+    context: &impl AsNode,
+    context_name: &str,
+    input: &str,
+    allow_declarative_shadow_roots: bool,
+) -> DocumentFragment {
+    let document = context
+        .owner_document()
+        .expect("Fragments can only be parsed in the context of a node in a document.");
+    let fragment = document.create_document_fragment();
+    let mut builder = TreeBuilder {
+        document,
+        root: AsNode::cast(&fragment).clone_ref(),
+        stack: vec![],
+        mode: InsertionMode::InBody,
+        allow_declarative_shadow_roots,
+        skip_newline: false,
+    };
+    let input = normalize_newlines(input);
+    builder.run(Tokenizer::for_context(&input, context_name));
+    fragment
+}
+
+fn normalize_newlines(input: &str) -> String {
+    input.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+impl TreeBuilder {
+    fn run(&mut self, tokenizer: Tokenizer) {
+        for token in tokenizer {
+            self.process(token);
+        }
+        self.finish();
+    }
+
+    /// Returns the node that new nodes are inserted into.
+    fn container(&self) -> &Node {
+        self.stack
+            .last()
+            .map_or(&self.root, |open_element| &open_element.container)
+    }
+
+    /// Returns the document that owns the current container, which is the inert template contents owner inside templates.
+    fn container_document(&self) -> Document {
+        self.container()
+            .owner_document()
+            .unwrap_or_else(|| self.document.clone())
+    }
+
+    fn current_tag(&self) -> Option<&Tag> {
+        self.stack.last().map(|open_element| &open_element.tag)
+    }
+
+    fn process(&mut self, token: Token) {
+        match self.mode {
+            InsertionMode::Initial => match token {
+                Token::Doctype(_) => {}
+                Token::Comment(data) => self.insert_comment(&data),
+                Token::Text(text) => {
+                    match text.trim_start_matches(|char: char| char.is_ascii_whitespace()) {
+                        "" => {}
+                        rest => {
+                            self.insert_html_element(vec![]);
+                            self.process(Token::Text(rest.to_owned()));
+                        }
+                    }
+                }
+                Token::StartTag {
+                    ref name,
+                    ref attributes,
+                    ..
+                } if name == "html" => self.insert_html_element(attributes.clone()),
+                Token::EndTag { ref name } if !matches!(name.as_str(), "head" | "body" | "br") => {}
+                token => {
+                    self.insert_html_element(vec![]);
+                    self.process(token);
+                }
+            },
+            InsertionMode::BeforeHead => match token {
+                Token::Text(text) => {
+                    match text.trim_start_matches(|char: char| char.is_ascii_whitespace()) {
+                        "" => {}
+                        rest => {
+                            self.insert_head_element(vec![]);
+                            self.process(Token::Text(rest.to_owned()));
+                        }
+                    }
+                }
+                Token::StartTag {
+                    ref name,
+                    ref attributes,
+                    ..
+                } if name == "head" => self.insert_head_element(attributes.clone()),
+                Token::EndTag { ref name } if !matches!(name.as_str(), "head" | "body" | "br") => {}
+                token => {
+                    self.process_before_body(token, |builder| builder.insert_head_element(vec![]))
+                }
+            },
+            InsertionMode::InHead => match token {
+                // The content of elements in the head, like <template>, is parsed as usual.
+                token if self.current_tag() != Some(&Tag::Head) => self.process_in_body(token),
+                Token::Text(text) => {
+                    let whitespace = text.len()
+                        - text
+                            .trim_start_matches(|char: char| char.is_ascii_whitespace())
+                            .len();
+                    self.insert_text(&text[..whitespace]);
+                    if whitespace < text.len() {
+                        self.close_head();
+                        self.process(Token::Text(text[whitespace..].to_owned()));
+                    }
+                }
+                Token::StartTag { ref name, .. } if is_head_content(name) => {
+                    self.process_in_body(token)
+                }
+                Token::EndTag { ref name } if name == "head" => self.close_head(),
+                Token::EndTag { ref name } if !matches!(name.as_str(), "body" | "html" | "br") => {}
+                token => self.process_before_body(token, Self::close_head),
+            },
+            InsertionMode::AfterHead => match token {
+                Token::Text(text) => {
+                    let whitespace = text.len()
+                        - text
+                            .trim_start_matches(|char: char| char.is_ascii_whitespace())
+                            .len();
+                    self.insert_text(&text[..whitespace]);
+                    if whitespace < text.len() {
+                        self.insert_body_element(vec![]);
+                        self.process(Token::Text(text[whitespace..].to_owned()));
+                    }
+                }
+                Token::StartTag {
+                    ref name,
+                    ref attributes,
+                    ..
+                } if name == "body" => self.insert_body_element(attributes.clone()),
+                Token::EndTag { ref name } if !matches!(name.as_str(), "body" | "html" | "br") => {}
+                token => {
+                    self.process_before_body(token, |builder| builder.insert_body_element(vec![]))
+                }
+            },
+            InsertionMode::InBody => self.process_in_body(token),
+        }
+    }
+
+    /// Handles the tokens that are treated alike in every mode before the body, i.e. comments and stray `<html>` tags, and otherwise runs the step that moves on to the next mode before reprocessing the token.
+    fn process_before_body(&mut self, token: Token, next_mode: impl FnOnce(&mut Self)) {
+        match token {
+            Token::Comment(data) => self.insert_comment(&data),
+            Token::Doctype(_) => {}
+            Token::StartTag {
+                ref name,
+                ref attributes,
+                ..
+            } if name == "html" => self.merge_attributes(0, attributes),
+            token => {
+                next_mode(self);
+                self.process(token);
+            }
+        }
+    }
+
+    fn insert_html_element(&mut self, attributes: Vec<(String, String)>) {
+        self.insert_element("html", &attributes);
+        self.mode = InsertionMode::BeforeHead;
+    }
+
+    fn insert_head_element(&mut self, attributes: Vec<(String, String)>) {
+        self.insert_element("head", &attributes);
+        self.mode = InsertionMode::InHead;
+    }
+
+    fn close_head(&mut self) {
+        if let Some(index) = self.position_of(|tag| *tag == Tag::Head) {
+            self.stack.truncate(index);
+        }
+        self.mode = InsertionMode::AfterHead;
+    }
+
+    fn insert_body_element(&mut self, attributes: Vec<(String, String)>) {
+        self.insert_element("body", &attributes);
+        self.mode = InsertionMode::InBody;
+    }
+
+    /// Adds attributes from a stray tag to an element on the stack, if it does not already have them.
+    fn merge_attributes(&mut self, index: usize, attributes: &[(String, String)]) {
+        if let Some(open_element) = self.stack.get_mut(index) {
+            for (name, value) in attributes {
+                if open_element.element.get_attribute(name).is_none() {
+                    open_element.element.set_attribute(name, value);
+                }
+            }
+        }
+    }
+
+    fn process_in_body(&mut self, token: Token) {
+        match token {
+            Token::Doctype(_) => {}
+            Token::Comment(data) => self.insert_comment(&data),
+            Token::Text(mut text) => {
+                if std::mem::take(&mut self.skip_newline) && text.starts_with('\n') {
+                    text.remove(0);
+                }
+                self.insert_text(&text);
+            }
+            Token::StartTag {
+                name, attributes, ..
+            } => self.process_start_tag(&name, attributes),
+            Token::EndTag { name } => self.process_end_tag(&name),
+        }
+    }
+
+    fn process_start_tag(&mut self, name: &str, attributes: Vec<(String, String)>) {
+        let tag = Tag::from(name);
+        match tag {
+            Tag::Html => return self.merge_attributes(0, &attributes),
+            Tag::Body => {
+                if self.position_of(|tag| *tag == Tag::Body) == Some(1) {
+                    self.merge_attributes(1, &attributes);
+                }
+                return;
+            }
+            Tag::Head => return,
+            Tag::Li => self.close_list_item(|tag| *tag == Tag::Li),
+            Tag::Dd | Tag::Dt => self.close_list_item(|tag| matches!(tag, Tag::Dd | Tag::Dt)),
+            Tag::Option | Tag::Optgroup => self.close_option(&tag),
+            Tag::Caption | Tag::Colgroup | Tag::Tbody | Tag::Thead | Tag::Tfoot => {
+                self.clear_to_table_context(&[Tag::Table]);
+            }
+            Tag::Tr | Tag::Td | Tag::Th => self.prepare_table_row(&tag),
+            Tag::Template
+                if self.allow_declarative_shadow_roots
+                    && self.attach_declarative_shadow_root(&attributes) =>
+            {
+                return
+            }
+            _ => {}
+        }
+        if closes_paragraph(&tag) {
+            self.close_paragraph();
+        }
+        if is_heading(&tag) && self.current_tag().is_some_and(is_heading) {
+            self.stack.pop();
+        }
+        if matches!(tag, Tag::Pre | Tag::Textarea) {
+            self.skip_newline = true;
+        }
+        self.insert_element(name, &attributes);
+    }
+
+    fn process_end_tag(&mut self, name: &str) {
+        let tag = Tag::from(name);
+        match tag {
+            Tag::Html | Tag::Body => {}
+            Tag::Br => self.insert_element("br", &[]),
+            Tag::P => {
+                if self.paragraph_in_button_scope().is_none() {
+                    self.insert_element("p", &[]);
+                }
+                self.close_paragraph();
+            }
+            _ if is_special(&tag) => {
+                let matches_tag = |open: &Tag| match is_heading(&tag) {
+                    true => is_heading(open),
+                    false => *open == tag,
+                };
+                let scope = match tag {
+                    Tag::Li => Scope::ListItem,
+                    Tag::Table
+                    | Tag::Tbody
+                    | Tag::Thead
+                    | Tag::Tfoot
+                    | Tag::Tr
+                    | Tag::Td
+                    | Tag::Th
+                    | Tag::Caption
+                    | Tag::Colgroup => Scope::Table,
+                    _ => Scope::Default,
+                };
+                if let Some(index) = self.in_scope(matches_tag, scope) {
+                    self.stack.truncate(index);
+                }
+            }
+            _ => {
+                // Any other end tag closes the nearest matching element, unless a special element is in the way.
+                for index in (0..self.stack.len()).rev() {
+                    let open = &self.stack[index].tag;
+                    if *open == tag {
+                        self.stack.truncate(index);
+                        return;
+                    }
+                    if is_special(open) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Attaches a shadow root to the current node for a `<template shadowrootmode>` start tag. Returns false if the template should be inserted as a normal element instead.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inhead)
+    fn attach_declarative_shadow_root(&mut self, attributes: &[(String, String)]) -> bool {
+        let attribute = |name: &str| {
+            attributes
+                .iter()
+                .find(|(attribute_name, _)| attribute_name == name)
+                .map(|(_, value)| value.as_str())
+        };
+        let mode = match attribute("shadowrootmode").map(str::to_ascii_lowercase) {
+            Some(mode) if mode == "open" => ShadowRootMode::Open,
+            Some(mode) if mode == "closed" => ShadowRootMode::Closed,
+            _ => return false,
+        };
+        // The root element of a document cannot be a declarative shadow host.
+        let topmost = match self.root.node_type() {
+            Node::DOCUMENT_NODE => 1,
+            _ => 0,
+        };
+        if self.stack.len() <= topmost {
+            return false;
+        }
+        let mut init = ShadowRootInit::new(mode);
+        init.delegates_focus = attribute("shadowrootdelegatesfocus").is_some();
+        init.clonable = attribute("shadowrootclonable").is_some();
+        init.serializable = attribute("shadowrootserializable").is_some();
+
+        let host = &mut self.stack.last_mut().unwrap().element;
+        let container = match host.attach_shadow(init) {
+            Ok(shadow_root) => AsNode::cast(shadow_root).clone_ref(),
+            Err(_) => return false,
+        };
+        let mut template = self.container_document().create_element("template");
+        for (name, value) in attributes {
+            template.set_attribute(name, value);
+        }
+        self.stack.push(OpenElement {
+            element: template,
+            tag: Tag::Template,
+            container,
+        });
+        true
+    }
+
+    /// Creates an element, inserts it into the current container, and pushes it onto the stack unless it is void.
+    fn insert_element(&mut self, name: &str, attributes: &[(String, String)]) {
+        let mut element = self.container_document().create_element(name);
+        for (name, value) in attributes {
+            element.set_attribute(name, value);
+        }
+        let mut container = self.container().clone_ref();
+        container
+            .append_child(&mut element)
+            .expect("Parsed elements are always valid children.");
+        let tag = Tag::from(name);
+        if !tag.is_void() {
+            // The children of a template are parsed into its contents.
+            let container = crate::html_element::template_contents(&element)
+                .unwrap_or_else(|| AsNode::cast(&element).clone_ref());
+            self.stack.push(OpenElement {
+                element,
+                tag,
+                container,
+            });
+        }
+    }
+
+    /// Inserts text into the current container, appending it to a preceding text node if there is one.
+    fn insert_text(&mut self, text: &str) {
+        if text.is_empty() || self.container().node_type() == Node::DOCUMENT_NODE {
+            return;
+        }
+        let mut container = self.container().clone_ref();
+        if let Some(last_child) = container.base().children.last() {
+            if last_child.node_type() == Node::TEXT_NODE {
+                AsNode::cast(last_child).base().data.push_str(text);
+                return;
+            }
+        }
+        let mut text = self.container_document().create_text_node(text);
+        container
+            .append_child(&mut text)
+            .expect("Text can be inserted in any container except the document.");
+    }
+
+    fn insert_comment(&mut self, data: &str) {
+        let mut comment = self.container_document().create_comment(data);
+        let mut container = self.container().clone_ref();
+        container
+            .append_child(&mut comment)
+            .expect("Comments can be inserted in any container.");
+    }
+
+    /// Completes the tree at the end of the input, creating the `<html>`, `<head>` and `<body>` elements if they were never seen.
+    fn finish(&mut self) {
+        while self.mode != InsertionMode::InBody {
+            match self.mode {
+                InsertionMode::Initial => self.insert_html_element(vec![]),
+                InsertionMode::BeforeHead => self.insert_head_element(vec![]),
+                InsertionMode::InHead => self.close_head(),
+                InsertionMode::AfterHead => self.insert_body_element(vec![]),
+                InsertionMode::InBody => {}
+            }
+        }
+        self.stack.clear();
+    }
+
+    /// Returns the index of the topmost element on the stack that matches a predicate.
+    fn position_of(&self, predicate: impl Fn(&Tag) -> bool) -> Option<usize> {
+        self.stack
+            .iter()
+            .rposition(|open_element| predicate(&open_element.tag))
+    }
+
+    /// Returns the index of the topmost matching element on the stack, if no scope boundary comes before it.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#has-an-element-in-scope)
+    fn in_scope(&self, predicate: impl Fn(&Tag) -> bool, scope: Scope) -> Option<usize> {
+        for (index, open_element) in self.stack.iter().enumerate().rev() {
+            if predicate(&open_element.tag) {
+                return Some(index);
+            }
+            if scope.is_boundary(&open_element.tag) {
+                return None;
+            }
+        }
+        None
+    }
+
+    fn paragraph_in_button_scope(&self) -> Option<usize> {
+        self.in_scope(|tag| *tag == Tag::P, Scope::Button)
+    }
+
+    fn close_paragraph(&mut self) {
+        if let Some(index) = self.paragraph_in_button_scope() {
+            self.stack.truncate(index);
+        }
+    }
+
+    /// Closes an open `<li>`, `<dd>` or `<dt>` element before a new one is opened.
+    fn close_list_item(&mut self, predicate: impl Fn(&Tag) -> bool) {
+        for index in (0..self.stack.len()).rev() {
+            let tag = &self.stack[index].tag;
+            if predicate(tag) {
+                self.stack.truncate(index);
+                return;
+            }
+            if is_special(tag) && !matches!(tag, Tag::Address | Tag::Div | Tag::P) {
+                return;
+            }
+        }
+    }
+
+    /// Closes an open `<option>`, and for `<optgroup>` an open `<optgroup>`, before a new one is opened.
+    fn close_option(&mut self, tag: &Tag) {
+        if self.current_tag() == Some(&Tag::Option) {
+            self.stack.pop();
+        }
+        if *tag == Tag::Optgroup && self.current_tag() == Some(&Tag::Optgroup) {
+            self.stack.pop();
+        }
+    }
+
+    /// Closes open rows or cells before a `<tr>`, `<td>` or `<th>` start tag, inserting the `<tbody>` and `<tr>` elements it implies.
+    fn prepare_table_row(&mut self, tag: &Tag) {
+        let context: &[Tag] = match tag {
+            Tag::Tr => &[Tag::Table, Tag::Tbody, Tag::Thead, Tag::Tfoot],
+            _ => &[Tag::Table, Tag::Tbody, Tag::Thead, Tag::Tfoot, Tag::Tr],
+        };
+        if !self.clear_to_table_context(context) {
+            return;
+        }
+        if self.current_tag() == Some(&Tag::Table) {
+            self.insert_element("tbody", &[]);
+        }
+        if *tag != Tag::Tr && self.current_tag() != Some(&Tag::Tr) {
+            self.insert_element("tr", &[]);
+        }
+    }
+
+    /// Closes any open table cell, then pops elements until one of the given table elements is the current node. Returns false, leaving the stack untouched, if there is no table in scope.
+    fn clear_to_table_context(&mut self, context: &[Tag]) -> bool {
+        if self
+            .in_scope(|tag| *tag == Tag::Table, Scope::Table)
+            .is_none()
+        {
+            return false;
+        }
+        if let Some(cell) = self.in_scope(|tag| matches!(tag, Tag::Td | Tag::Th), Scope::Table) {
+            self.stack.truncate(cell);
+        }
+        match self.in_scope(|tag| context.contains(tag), Scope::Table) {
+            Some(index) => {
+                self.stack.truncate(index + 1);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn is_head_content(name: &str) -> bool {
+    matches!(
+        name,
+        "base"
+            | "basefont"
+            | "bgsound"
+            | "link"
+            | "meta"
+            | "noframes"
+            | "noscript"
+            | "script"
+            | "style"
+            | "template"
+            | "title"
+    )
+}
+
+fn is_heading(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::H1 | Tag::H2 | Tag::H3 | Tag::H4 | Tag::H5 | Tag::H6
+    )
+}
+
+/// Returns true if the start tag of an element closes an open `<p>` element.
+fn closes_paragraph(tag: &Tag) -> bool {
+    is_heading(tag)
+        || matches!(
+            tag,
+            Tag::Address
+                | Tag::Article
+                | Tag::Aside
+                | Tag::Blockquote
+                | Tag::Center
+                | Tag::Dd
+                | Tag::Details
+                | Tag::Dialog
+                | Tag::Dir
+                | Tag::Div
+                | Tag::Dl
+                | Tag::Dt
+                | Tag::Fieldset
+                | Tag::Figcaption
+                | Tag::Figure
+                | Tag::Footer
+                | Tag::Form
+                | Tag::Header
+                | Tag::Hgroup
+                | Tag::Hr
+                | Tag::Li
+                | Tag::Main
+                | Tag::Menu
+                | Tag::Nav
+                | Tag::Ol
+                | Tag::P
+                | Tag::Plaintext
+                | Tag::Pre
+                | Tag::Section
+                | Tag::Summary
+                | Tag::Table
+                | Tag::Ul
+                | Tag::Xmp
+        )
+}
+
+/// The kinds of scope an element can be looked up in, which differ in the elements that end the search.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#has-an-element-in-scope)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scope {
+    Default,
+    ListItem,
+    Button,
+    Table,
+}
+
+impl Scope {
+    fn is_boundary(self, tag: &Tag) -> bool {
+        match self {
+            Scope::Default => is_scope_boundary(tag),
+            Scope::ListItem => is_scope_boundary(tag) || matches!(tag, Tag::Ol | Tag::Ul),
+            Scope::Button => is_scope_boundary(tag) || *tag == Tag::Button,
+            Scope::Table => matches!(tag, Tag::Html | Tag::Table | Tag::Template),
+        }
+    }
+}
+
+fn is_scope_boundary(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Applet
+            | Tag::Caption
+            | Tag::Html
+            | Tag::Table
+            | Tag::Td
+            | Tag::Th
+            | Tag::Marquee
+            | Tag::Object
+            | Tag::Template
+    )
+}
+
+/// Returns true if the element is in the special category of the parser.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/parsing.html#special)
+fn is_special(tag: &Tag) -> bool {
+    closes_paragraph(tag)
+        || is_scope_boundary(tag)
+        || tag.is_void()
+        || matches!(
+            tag,
+            Tag::Basefont
+                | Tag::Bgsound
+                | Tag::Body
+                | Tag::Button
+                | Tag::Colgroup
+                | Tag::Frame
+                | Tag::Frameset
+                | Tag::Head
+                | Tag::Iframe
+                | Tag::Noembed
+                | Tag::Noframes
+                | Tag::Noscript
+                | Tag::Script
+                | Tag::Select
+                | Tag::Style
+                | Tag::Tbody
+                | Tag::Textarea
+                | Tag::Tfoot
+                | Tag::Thead
+                | Tag::Title
+                | Tag::Tr
+        )
+}
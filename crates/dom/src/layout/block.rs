@@ -0,0 +1,748 @@
+//! Block layout: the sizing of block containers and replaced boxes, the stacking of block-level boxes with margin collapsing, floats, and positioning.
+//!
+//! [Reference](https://www.w3.org/TR/CSS2/visudet.html)
+use super::{
+    box_tree::{BoxKind, LayoutBox},
+    flex, grid, inline, Rect, Sides, Size,
+};
+use crate::{css::ComputedStyle, Viewport};
+
+/// The size of the content box that a box is laid out in.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ContainingBlock {
+    pub width: f64,
+    /// The height, if it is definite. Percentage heights are `auto` in containing blocks without a definite height.
+    pub height: Option<f64>,
+}
+
+/// A set of adjoining margins, which collapse into one.
+///
+/// [Reference](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
+#[derive(Debug, Clone, Copy, Default)]
+struct Strut {
+    positive: f64,
+    negative: f64,
+}
+
+impl Strut {
+    fn new(margin: f64) -> Self {
+        let mut strut = Strut::default();
+        strut.add(margin);
+        strut
+    }
+
+    fn add(&mut self, margin: f64) {
+        self.positive = self.positive.max(margin);
+        self.negative = self.negative.min(margin);
+    }
+
+    fn join(&mut self, other: Strut) {
+        self.positive = self.positive.max(other.positive);
+        self.negative = self.negative.min(other.negative);
+    }
+
+    /// The width of the collapsed margin: the largest positive margin plus the most negative one.
+    fn resolve(&self) -> f64 {
+        self.positive + self.negative
+    }
+}
+
+/// The margins of a laid out box that can collapse with the margins around it.
+struct Margins {
+    top: Strut,
+    bottom: Strut,
+    /// Whether the top and bottom margins of the box adjoin, because it is empty.
+    collapsed_through: bool,
+}
+
+/// The result of laying out the contents of a block container.
+struct Contents {
+    height: f64,
+    /// The margins of the first children that collapse with the top margin of the container.
+    top: Option<Strut>,
+    /// The margins of the last children that collapse with the bottom margin of the container.
+    bottom: Option<Strut>,
+    /// Whether all the contents collapsed through, leaving nothing between the top and the bottom of the container.
+    collapsed_through: bool,
+    /// The baseline of the last line, relative to the top of the content box.
+    baseline: Option<f64>,
+}
+
+/// Floats placed in a block container, as margin boxes relative to its content box.
+#[derive(Debug, Default)]
+pub(crate) struct Floats {
+    left: Vec<Rect>,
+    right: Vec<Rect>,
+}
+
+impl Floats {
+    /// Places a laid out float as high as possible, but no higher than a line and the floats before it.
+    ///
+    /// [Reference](https://www.w3.org/TR/CSS2/visuren.html#float-position)
+    pub(crate) fn place(&mut self, float: &mut LayoutBox, container_width: f64, line: f64) {
+        let width = float.rect.width + float.margin.horizontal();
+        let height = float.rect.height + float.margin.vertical();
+        let mut top = self
+            .left
+            .iter()
+            .chain(&self.right)
+            .map(|rect| rect.y)
+            .fold(line, f64::max);
+        let is_right = float.style.get("float") == "right";
+        let x = loop {
+            let overlaps =
+                |rect: &&Rect| rect.y < top + height.max(f64::EPSILON) && rect.bottom() > top;
+            let left = self
+                .left
+                .iter()
+                .filter(overlaps)
+                .map(Rect::right)
+                .fold(0.0, f64::max);
+            let right = self
+                .right
+                .iter()
+                .filter(overlaps)
+                .map(|rect| rect.x)
+                .fold(container_width, f64::min);
+            let next = self
+                .left
+                .iter()
+                .chain(&self.right)
+                .filter(overlaps)
+                .map(Rect::bottom)
+                .fold(f64::INFINITY, f64::min);
+            if right - left >= width || next.is_infinite() {
+                break match is_right {
+                    true => right - width,
+                    false => left,
+                };
+            }
+            top = next;
+        };
+        let rect = Rect::new(x, top, width, height);
+        match is_right {
+            true => self.right.push(rect),
+            false => self.left.push(rect),
+        }
+        float.rect.x = x + float.margin.left;
+        float.rect.y = top + float.margin.top;
+    }
+
+    /// Returns the position below the floats that a box with a `clear` value has to be moved past.
+    fn clearance(&self, clear: &str) -> f64 {
+        let bottom = |rects: &Vec<Rect>| rects.iter().map(Rect::bottom).fold(0.0, f64::max);
+        match clear {
+            "left" | "inline-start" => bottom(&self.left),
+            "right" | "inline-end" => bottom(&self.right),
+            "both" => bottom(&self.left).max(bottom(&self.right)),
+            _ => 0.0,
+        }
+    }
+
+    pub(crate) fn bottom(&self) -> f64 {
+        self.clearance("both")
+    }
+}
+
+/// How the width of a box is decided.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Sizing {
+    /// Block-level boxes in normal flow fill their containing block.
+    Fill,
+    /// Floats and atomic inlines shrink to fit their contents.
+    ShrinkToFit,
+    /// Flex and grid items are given their content-box width, and maybe their content-box height, by their container.
+    Given(f64, Option<f64>),
+}
+
+/// Lays out the box of the root element in the initial containing block, which has the size of the viewport, and moves every box to document coordinates.
+pub(crate) fn layout_root(root: &mut LayoutBox, viewport: &Viewport) {
+    let initial = ContainingBlock {
+        width: viewport.width,
+        height: Some(viewport.height),
+    };
+    layout_block_box(root, initial, Sizing::Fill, true);
+    root.rect.x = root.margin.left;
+    root.rect.y = root.margin.top;
+    let initial = Rect::new(0.0, 0.0, viewport.width, viewport.height);
+    place(root, (0.0, 0.0), initial, initial);
+}
+
+/// Lays out an atomic inline or a float, which are sized to fit their contents.
+pub(crate) fn layout_atomic(layout_box: &mut LayoutBox, containing_block: ContainingBlock) {
+    layout_block_box(layout_box, containing_block, Sizing::ShrinkToFit, true);
+}
+
+/// Lays out a flex or grid item with the size its container gives it. Its margins, borders and padding must already be resolved.
+pub(crate) fn layout_item(
+    layout_box: &mut LayoutBox,
+    containing_block: ContainingBlock,
+    width: f64,
+    height: Option<f64>,
+) {
+    layout_block_box(
+        layout_box,
+        containing_block,
+        Sizing::Given(width, height),
+        true,
+    );
+}
+
+/// Lays out a block-level box, an atomic inline or a float, and returns its margins that can collapse.
+///
+/// The size of the box is set, and its descendants are positioned relative to its content box. Its own position is up to its parent.
+fn layout_block_box(
+    layout_box: &mut LayoutBox,
+    containing_block: ContainingBlock,
+    sizing: Sizing,
+    independent: bool,
+) -> Margins {
+    let style = layout_box.style.clone();
+    if !matches!(sizing, Sizing::Given(..)) {
+        resolve_edges(layout_box, containing_block.width);
+    }
+    let edges = layout_box.padding.horizontal() + layout_box.border.horizontal();
+    let vertical_edges = layout_box.padding.vertical() + layout_box.border.vertical();
+    let available = (containing_block.width - layout_box.margin.horizontal() - edges).max(0.0);
+    let (width, replaced_height) = match (layout_box.kind.clone(), sizing) {
+        (
+            BoxKind::Replaced {
+                width: natural_width,
+                height: natural_height,
+            },
+            Sizing::Given(width, None),
+        ) => {
+            // Without a given height, a replaced item keeps its aspect ratio.
+            let height = content_size(&style, "height", containing_block.height, vertical_edges)
+                .unwrap_or(match natural_width > 0.0 {
+                    true => width * natural_height / natural_width,
+                    false => natural_height,
+                });
+            (
+                width,
+                Some(clamp(
+                    &style,
+                    "height",
+                    height,
+                    containing_block.height,
+                    vertical_edges,
+                )),
+            )
+        }
+        (_, Sizing::Given(width, height)) => (width, height),
+        (BoxKind::Replaced { width, height }, _) => {
+            let (width, height) = replaced_size(
+                &style,
+                containing_block,
+                (width, height),
+                (edges, vertical_edges),
+            );
+            (width, Some(height))
+        }
+        _ => {
+            let width = match content_size(&style, "width", Some(containing_block.width), edges) {
+                Some(width) => width,
+                None if sizing == Sizing::ShrinkToFit => {
+                    let (min, max) = intrinsic_widths(layout_box, containing_block);
+                    max.min(available).max(min)
+                }
+                None => available,
+            };
+            let width = clamp(&style, "width", width, Some(containing_block.width), edges);
+            (width, None)
+        }
+    };
+    if sizing == Sizing::Fill {
+        // Auto margins take up the space left in the containing block, which centers boxes with both margins auto.
+        let remaining = containing_block.width - width - edges - layout_box.margin.horizontal();
+        match (
+            style.get("margin-left") == "auto",
+            style.get("margin-right") == "auto",
+        ) {
+            (true, true) => {
+                let half = (remaining / 2.0).max(0.0);
+                layout_box.margin.left += half;
+                layout_box.margin.right += remaining - half;
+            }
+            (true, false) => layout_box.margin.left += remaining,
+            _ => layout_box.margin.right += remaining,
+        }
+    }
+    layout_box.rect.width = width + edges;
+
+    let specified_height = replaced_height
+        .or_else(|| content_size(&style, "height", containing_block.height, vertical_edges));
+    let min_height = content_size(
+        &style,
+        "min-height",
+        containing_block.height,
+        vertical_edges,
+    );
+    let collapse_top =
+        !independent && layout_box.border.top == 0.0 && layout_box.padding.top == 0.0;
+    let collapse_bottom = !independent
+        && layout_box.border.bottom == 0.0
+        && layout_box.padding.bottom == 0.0
+        && specified_height.is_none()
+        && min_height.unwrap_or(0.0) == 0.0;
+    let inner = ContainingBlock {
+        width,
+        height: specified_height,
+    };
+    let contents = match layout_box.kind {
+        BoxKind::Replaced { .. } => Contents {
+            height: 0.0,
+            top: None,
+            bottom: None,
+            collapsed_through: false,
+            baseline: None,
+        },
+        _ => layout_contents(
+            layout_box,
+            inner,
+            collapse_top,
+            collapse_bottom,
+            independent,
+        ),
+    };
+    let height = match sizing {
+        Sizing::Given(_, Some(height)) => height,
+        _ => clamp(
+            &style,
+            "height",
+            specified_height.unwrap_or(contents.height),
+            containing_block.height,
+            vertical_edges,
+        ),
+    };
+    layout_box.rect.height = height + vertical_edges;
+    layout_box.baseline = match style.get("overflow-y") {
+        "visible" => contents
+            .baseline
+            .map(|baseline| baseline + layout_box.border.top + layout_box.padding.top),
+        _ => None,
+    };
+
+    let mut top = Strut::new(layout_box.margin.top);
+    let mut bottom = Strut::new(layout_box.margin.bottom);
+    if let Some(strut) = contents.top {
+        top.join(strut);
+    }
+    if let Some(strut) = contents.bottom {
+        bottom.join(strut);
+    }
+    Margins {
+        top,
+        bottom,
+        collapsed_through: contents.collapsed_through
+            && collapse_top
+            && collapse_bottom
+            && height == 0.0,
+    }
+}
+
+/// Lays out the children of a block container, in lines if they are inline-level, or stacked vertically if they are block-level.
+fn layout_contents(
+    layout_box: &mut LayoutBox,
+    containing_block: ContainingBlock,
+    collapse_top: bool,
+    collapse_bottom: bool,
+    independent: bool,
+) -> Contents {
+    let laid_out = match () {
+        _ if layout_box.is_flex_container() => Some(flex::layout(layout_box, containing_block)),
+        _ if layout_box.is_grid_container() => Some(grid::layout(layout_box, containing_block)),
+        _ => None,
+    };
+    if let Some((height, baseline)) = laid_out {
+        return Contents {
+            height,
+            top: None,
+            bottom: None,
+            collapsed_through: false,
+            baseline,
+        };
+    }
+    if layout_box.has_inline_content() {
+        let lines = inline::layout_lines(layout_box, containing_block);
+        return Contents {
+            height: lines.height,
+            top: None,
+            bottom: None,
+            collapsed_through: lines.height == 0.0,
+            baseline: lines.baseline,
+        };
+    }
+    let mut floats = Floats::default();
+    let mut y = 0.0;
+    let mut strut = Strut::default();
+    // Whether the margins met so far adjoin the top margin of the container.
+    let mut adjoining_top = collapse_top;
+    let mut top = Strut::default();
+    let mut baseline = None;
+    for child in layout_box.children.iter_mut() {
+        if child.is_absolutely_positioned() {
+            child.static_position = (0.0, y + strut.resolve());
+            continue;
+        }
+        if child.is_floating() {
+            layout_atomic(child, containing_block);
+            floats.place(child, containing_block.width, y + strut.resolve());
+            continue;
+        }
+        let independent = child.establishes_formatting_context();
+        let margins = layout_block_box(child, containing_block, Sizing::Fill, independent);
+        child.rect.x = child.margin.left;
+        if adjoining_top {
+            top.join(margins.top);
+            if margins.collapsed_through {
+                top.join(margins.bottom);
+                child.rect.y = 0.0;
+                continue;
+            }
+            adjoining_top = false;
+            child.rect.y = 0.0;
+        } else {
+            strut.join(margins.top);
+            if margins.collapsed_through {
+                strut.join(margins.bottom);
+                child.rect.y = y + strut.resolve();
+                continue;
+            }
+            child.rect.y = y + strut.resolve();
+        }
+        let clearance = floats.clearance(child.style.get("clear"));
+        if child.rect.y < clearance {
+            child.rect.y = clearance;
+        }
+        y = child.rect.bottom();
+        strut = margins.bottom;
+        if let Some(child_baseline) = child.baseline {
+            baseline = Some(child.rect.y + child_baseline);
+        }
+    }
+    let (mut height, bottom) = match collapse_bottom && !adjoining_top {
+        true => (y, Some(strut)),
+        false => (y + strut.resolve(), None),
+    };
+    // A box that establishes a formatting context grows to contain its floats.
+    if independent {
+        height = height.max(floats.bottom());
+    }
+    Contents {
+        height,
+        top: collapse_top.then_some(top),
+        bottom,
+        collapsed_through: adjoining_top,
+        baseline,
+    }
+}
+
+/// Lays out an absolutely positioned box in its containing block, with both in document coordinates.
+///
+/// [Reference](https://www.w3.org/TR/CSS2/visudet.html#abs-non-replaced-width)
+fn layout_absolute(layout_box: &mut LayoutBox, containing: Rect, static_position: (f64, f64)) {
+    let style = layout_box.style.clone();
+    resolve_edges(layout_box, containing.width);
+    let offset = |name: &str, basis: f64| Size::parse(style.get(name)).resolve(Some(basis));
+    let left = offset("left", containing.width);
+    let right = offset("right", containing.width);
+    let top = offset("top", containing.height);
+    let bottom = offset("bottom", containing.height);
+    let edges = layout_box.padding.horizontal() + layout_box.border.horizontal();
+    let vertical_edges = layout_box.padding.vertical() + layout_box.border.vertical();
+    let containing_block = ContainingBlock {
+        width: containing.width,
+        height: Some(containing.height),
+    };
+    let margins = layout_box.margin.horizontal();
+    let (width, replaced_height) = match layout_box.kind {
+        BoxKind::Replaced { width, height } => {
+            let (width, height) = replaced_size(
+                &style,
+                containing_block,
+                (width, height),
+                (edges, vertical_edges),
+            );
+            (width, Some(height))
+        }
+        _ => {
+            let width = match content_size(&style, "width", Some(containing.width), edges) {
+                Some(width) => width,
+                None => match (left, right) {
+                    (Some(left), Some(right)) => containing.width - left - right - margins - edges,
+                    _ => {
+                        let available = containing.width
+                            - left.unwrap_or(0.0)
+                            - right.unwrap_or(0.0)
+                            - margins
+                            - edges;
+                        let (min, max) = intrinsic_widths(layout_box, containing_block);
+                        max.min(available).max(min)
+                    }
+                },
+            };
+            let width = clamp(
+                &style,
+                "width",
+                width.max(0.0),
+                Some(containing.width),
+                edges,
+            );
+            (width, None)
+        }
+    };
+    layout_box.rect.width = width + edges;
+    if let (Some(left), Some(right)) = (left, right) {
+        if style.get("margin-left") == "auto" && style.get("margin-right") == "auto" {
+            let remaining = containing.width - left - right - layout_box.rect.width;
+            let half = (remaining / 2.0).max(0.0);
+            layout_box.margin.left = half;
+            layout_box.margin.right = remaining - half;
+        }
+    }
+    layout_box.rect.x = match (left, right) {
+        (Some(left), _) => containing.x + left + layout_box.margin.left,
+        (None, Some(right)) => {
+            containing.right() - right - layout_box.margin.right - layout_box.rect.width
+        }
+        (None, None) => static_position.0 + layout_box.margin.left,
+    };
+
+    let vertical_margins = layout_box.margin.vertical();
+    let specified_height = replaced_height
+        .or_else(|| content_size(&style, "height", Some(containing.height), vertical_edges))
+        .or_else(|| match (top, bottom) {
+            (Some(top), Some(bottom)) => Some(
+                (containing.height - top - bottom - vertical_margins - vertical_edges).max(0.0),
+            ),
+            _ => None,
+        });
+    let inner = ContainingBlock {
+        width,
+        height: specified_height,
+    };
+    let contents_height = match layout_box.kind {
+        BoxKind::Replaced { .. } => 0.0,
+        _ => layout_contents(layout_box, inner, false, false, true).height,
+    };
+    let height = specified_height.unwrap_or(contents_height);
+    let height = clamp(
+        &style,
+        "height",
+        height,
+        Some(containing.height),
+        vertical_edges,
+    );
+    layout_box.rect.height = height + vertical_edges;
+    layout_box.rect.y = match (top, bottom) {
+        (Some(top), _) => containing.y + top + layout_box.margin.top,
+        (None, Some(bottom)) => {
+            containing.bottom() - bottom - layout_box.margin.bottom - layout_box.rect.height
+        }
+        (None, None) => static_position.1 + layout_box.margin.top,
+    };
+}
+
+/// Moves a laid out box and its descendants from coordinates relative to the content box of their parent to document coordinates, applying relative offsets, and lays out the absolutely positioned boxes in it.
+fn place(layout_box: &mut LayoutBox, origin: (f64, f64), containing: Rect, viewport: Rect) {
+    let (dx, dy) = relative_offset(layout_box);
+    let (x, y) = (origin.0 + dx, origin.1 + dy);
+    match layout_box.kind {
+        BoxKind::Text(_) => {
+            for run in layout_box.text_runs.iter_mut() {
+                run.rect.translate(x, y);
+            }
+        }
+        BoxKind::LineBreak => {}
+        BoxKind::Inline => {
+            for fragment in layout_box.fragments.iter_mut() {
+                fragment.translate(x, y);
+            }
+            let containing = match layout_box.is_positioned() {
+                true => layout_box
+                    .fragments
+                    .first()
+                    .map(|fragment| fragment.shrink(&layout_box.border))
+                    .unwrap_or(containing),
+                false => containing,
+            };
+            place_children(layout_box, (x, y), containing, viewport);
+        }
+        BoxKind::Flow | BoxKind::Replaced { .. } => {
+            layout_box.rect.translate(x, y);
+            let content = layout_box
+                .rect
+                .shrink(&layout_box.border)
+                .shrink(&layout_box.padding);
+            let containing = match layout_box.is_positioned() {
+                true => layout_box.rect.shrink(&layout_box.border),
+                false => containing,
+            };
+            place_children(layout_box, (content.x, content.y), containing, viewport);
+        }
+    }
+}
+
+fn place_children(
+    layout_box: &mut LayoutBox,
+    origin: (f64, f64),
+    containing: Rect,
+    viewport: Rect,
+) {
+    for child in layout_box.children.iter_mut() {
+        if !child.is_absolutely_positioned() {
+            place(child, origin, containing, viewport);
+            continue;
+        }
+        let containing = match child.style.get("position") {
+            "fixed" => viewport,
+            _ => containing,
+        };
+        let static_position = (
+            origin.0 + child.static_position.0,
+            origin.1 + child.static_position.1,
+        );
+        layout_absolute(child, containing, static_position);
+        let padding_box = child.rect.shrink(&child.border);
+        let content = padding_box.shrink(&child.padding);
+        place_children(child, (content.x, content.y), padding_box, viewport);
+    }
+}
+
+/// Returns the offset of a relatively positioned box from its position in flow.
+///
+/// [Reference](https://www.w3.org/TR/CSS2/visuren.html#relative-positioning)
+fn relative_offset(layout_box: &LayoutBox) -> (f64, f64) {
+    let style = &layout_box.style;
+    if style.get("position") != "relative" || matches!(layout_box.kind, BoxKind::Text(_)) {
+        return (0.0, 0.0);
+    }
+    let offset = |name: &str| Size::parse(style.get(name)).resolve(None);
+    let x = match (offset("left"), offset("right")) {
+        (Some(left), _) => left,
+        (None, Some(right)) => -right,
+        (None, None) => 0.0,
+    };
+    let y = match (offset("top"), offset("bottom")) {
+        (Some(top), _) => top,
+        (None, Some(bottom)) => -bottom,
+        (None, None) => 0.0,
+    };
+    (x, y)
+}
+
+/// Resolves the margins, borders and padding of a box. Auto margins are zero until they are resolved against the space left over.
+pub(crate) fn resolve_edges(layout_box: &mut LayoutBox, basis: f64) {
+    layout_box.margin = Sides::margin(&layout_box.style, basis);
+    layout_box.border = Sides::border(&layout_box.style);
+    layout_box.padding = Sides::padding(&layout_box.style, basis);
+}
+
+/// Returns the content-box size given by a property like `width`, or `None` if it is `auto` or a percentage of an indefinite size.
+pub(crate) fn content_size(
+    style: &ComputedStyle,
+    property: &str,
+    basis: Option<f64>,
+    edges: f64,
+) -> Option<f64> {
+    let size = Size::parse(style.get(property)).resolve(basis)?;
+    Some(match style.get("box-sizing") {
+        "border-box" => (size - edges).max(0.0),
+        _ => size,
+    })
+}
+
+/// Clamps a content-box width or height between its minimum and maximum, like `min-width` and `max-width`.
+pub(crate) fn clamp(
+    style: &ComputedStyle,
+    property: &str,
+    size: f64,
+    basis: Option<f64>,
+    edges: f64,
+) -> f64 {
+    let maximum = content_size(style, &format!("max-{property}"), basis, edges);
+    let minimum = content_size(style, &format!("min-{property}"), basis, edges);
+    size.min(maximum.unwrap_or(f64::INFINITY))
+        .max(minimum.unwrap_or(0.0))
+}
+
+/// Returns the content-box size of a replaced element, keeping the aspect ratio of its natural size when only one dimension is given.
+///
+/// [Reference](https://www.w3.org/TR/CSS2/visudet.html#inline-replaced-width)
+fn replaced_size(
+    style: &ComputedStyle,
+    containing_block: ContainingBlock,
+    natural: (f64, f64),
+    edges: (f64, f64),
+) -> (f64, f64) {
+    let ratio = (natural.0 > 0.0 && natural.1 > 0.0).then(|| natural.0 / natural.1);
+    let width = content_size(style, "width", Some(containing_block.width), edges.0);
+    let height = content_size(style, "height", containing_block.height, edges.1);
+    let (width, height) = match (width, height) {
+        (Some(width), Some(height)) => (width, height),
+        (Some(width), None) => (width, ratio.map_or(natural.1, |ratio| width / ratio)),
+        (None, Some(height)) => (ratio.map_or(natural.0, |ratio| height * ratio), height),
+        (None, None) => natural,
+    };
+    (
+        clamp(style, "width", width, Some(containing_block.width), edges.0),
+        clamp(style, "height", height, containing_block.height, edges.1),
+    )
+}
+
+/// Returns the min-content and max-content widths of the content box of a box.
+///
+/// [Reference](https://drafts.csswg.org/css-sizing/#intrinsic-sizes)
+pub(crate) fn intrinsic_widths(
+    layout_box: &LayoutBox,
+    containing_block: ContainingBlock,
+) -> (f64, f64) {
+    if let BoxKind::Replaced { width, height } = layout_box.kind {
+        let (width, _) = replaced_size(
+            &layout_box.style,
+            containing_block,
+            (width, height),
+            (0.0, 0.0),
+        );
+        return (width, width);
+    }
+    if layout_box.is_flex_container() {
+        return flex::intrinsic_widths(layout_box, containing_block);
+    }
+    if layout_box.is_grid_container() {
+        return grid::intrinsic_widths(layout_box, containing_block);
+    }
+    if layout_box.has_inline_content() {
+        return inline::intrinsic_widths(layout_box, containing_block);
+    }
+    layout_box
+        .children
+        .iter()
+        .filter(|child| !child.is_absolutely_positioned())
+        .map(|child| contribution(child, containing_block))
+        .fold((0.0, 0.0), |(min, max), (child_min, child_max)| {
+            (f64::max(min, child_min), f64::max(max, child_max))
+        })
+}
+
+/// Returns the min-content and max-content contributions of a box: the widths of its margin box when it is sized to its min-content and max-content widths.
+pub(crate) fn contribution(
+    layout_box: &LayoutBox,
+    containing_block: ContainingBlock,
+) -> (f64, f64) {
+    let style = &layout_box.style;
+    let padding = Sides::padding(style, 0.0);
+    let border = Sides::border(style);
+    let inner_edges = padding.horizontal() + border.horizontal();
+    let edges = inner_edges + Sides::margin(style, 0.0).horizontal();
+    if let Some(width) = content_size(style, "width", None, inner_edges) {
+        let width = clamp(style, "width", width, None, inner_edges);
+        return (width + edges, width + edges);
+    }
+    let (min, max) = intrinsic_widths(layout_box, containing_block);
+    (
+        clamp(style, "width", min, None, inner_edges) + edges,
+        clamp(style, "width", max, None, inner_edges) + edges,
+    )
+}
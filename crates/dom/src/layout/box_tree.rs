@@ -0,0 +1,524 @@
+//! The generation of boxes from the flat tree.
+//!
+//! [Reference](https://drafts.csswg.org/css-display/#box-generation)
+use std::rc::Rc;
+
+use super::{text::Font, Rect, Sides};
+use crate::{
+    css::{ComputedStyle, PseudoElement, StyleResolver},
+    domitem::DOMItem,
+    node::ChildNode,
+    slot::{assigned_nodes, is_slot, shadow_root_of},
+    tag::Tag,
+    AsElement, AsNode, Element, FontProvider, Node,
+};
+
+/// A box in the box tree.
+#[derive(Debug)]
+pub(crate) struct LayoutBox {
+    /// The element that generated the box. `None` for anonymous boxes, text and pseudo-elements.
+    pub element: Option<Element>,
+    pub style: Rc<ComputedStyle>,
+    /// The font that the text of the box is measured with.
+    pub font: Font,
+    pub kind: BoxKind,
+    /// Whether the box takes part in an inline formatting context, rather than a block formatting context.
+    pub inline_level: bool,
+    /// Whether the box is a flex item or a grid item, which is laid out by its container and never floats.
+    pub is_item: bool,
+    /// Whether the box, or the box it is in, is generated by a `::before` or `::after` pseudo-element.
+    pub is_generated: bool,
+    pub children: Vec<LayoutBox>,
+    /// The border box. Relative to the content box of the parent until layout is finished, then in document coordinates.
+    pub rect: Rect,
+    pub margin: Sides,
+    pub border: Sides,
+    pub padding: Sides,
+    /// The border boxes of an inline box, one for each line it is on.
+    pub fragments: Vec<Rect>,
+    /// The runs of a text box, one for each line it is on.
+    pub text_runs: Vec<TextRun>,
+    /// Where an absolutely positioned box would have been if it were in flow, relative to the content box of the parent.
+    pub static_position: (f64, f64),
+    /// The distance from the top of the border box to the baseline of the last line in the box, if it has any.
+    pub baseline: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum BoxKind {
+    /// A block container, like a block or an inline-block.
+    Flow,
+    /// A non-replaced inline box.
+    Inline,
+    /// A replaced element with its natural size, like an image.
+    Replaced { width: f64, height: f64 },
+    /// A run of text, before white space is processed.
+    Text(String),
+    /// A forced line break, generated by a `br` element.
+    LineBreak,
+}
+
+/// A part of a text box on one line.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TextRun {
+    pub text: String,
+    /// The content area of the run.
+    pub rect: Rect,
+}
+
+impl LayoutBox {
+    pub(crate) fn new(
+        element: Option<Element>,
+        style: Rc<ComputedStyle>,
+        kind: BoxKind,
+        fonts: &Rc<dyn FontProvider>,
+    ) -> Self {
+        LayoutBox {
+            element,
+            font: Font::new(fonts, &style),
+            style,
+            kind,
+            inline_level: false,
+            is_item: false,
+            is_generated: false,
+            children: vec![],
+            rect: Rect::default(),
+            margin: Sides::default(),
+            border: Sides::default(),
+            padding: Sides::default(),
+            fragments: vec![],
+            text_runs: vec![],
+            static_position: (0.0, 0.0),
+            baseline: None,
+        }
+    }
+
+    /// Returns true for absolutely positioned boxes, which are taken out of flow.
+    pub(crate) fn is_absolutely_positioned(&self) -> bool {
+        matches!(self.style.get("position"), "absolute" | "fixed")
+            && !matches!(self.kind, BoxKind::Text(_))
+    }
+
+    pub(crate) fn is_floating(&self) -> bool {
+        self.style.get("float") != "none" && !self.is_item && !matches!(self.kind, BoxKind::Text(_))
+    }
+
+    pub(crate) fn is_out_of_flow(&self) -> bool {
+        self.is_absolutely_positioned() || self.is_floating()
+    }
+
+    /// Returns true for inline-level boxes that are laid out as a single unit, like inline-blocks and images.
+    pub(crate) fn is_atomic_inline(&self) -> bool {
+        self.inline_level && matches!(self.kind, BoxKind::Flow | BoxKind::Replaced { .. })
+    }
+
+    /// Returns true if the box is positioned, and so is the containing block of its absolutely positioned descendants.
+    pub(crate) fn is_positioned(&self) -> bool {
+        self.style.get("position") != "static"
+    }
+
+    /// Returns true if the box establishes a new block formatting context for its contents.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-display/#establish-an-independent-formatting-context)
+    pub(crate) fn establishes_formatting_context(&self) -> bool {
+        self.inline_level
+            || self.is_out_of_flow()
+            || self.style.get("overflow-x") != "visible"
+            || self.style.get("overflow-y") != "visible"
+            || matches!(
+                self.style.get("display"),
+                "flow-root" | "flex" | "grid" | "table" | "table-cell" | "table-caption"
+            )
+    }
+
+    /// Returns true if the box clips its contents and can be scrolled, which is when its `overflow` is `hidden`, `scroll` or `auto`.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-overflow/#scroll-container)
+    pub(crate) fn is_scroll_container(&self) -> bool {
+        matches!(self.kind, BoxKind::Flow | BoxKind::Replaced { .. })
+            && ["overflow-x", "overflow-y"]
+                .iter()
+                .any(|property| matches!(self.style.get(property), "hidden" | "scroll" | "auto"))
+    }
+
+    /// Returns the `z-index` of the box, or `None` for `auto`.
+    pub(crate) fn z_index(&self) -> Option<i32> {
+        self.style.get("z-index").parse().ok()
+    }
+
+    /// Returns the `opacity` of the box, between zero and one.
+    pub(crate) fn opacity(&self) -> f64 {
+        self.style
+            .get("opacity")
+            .parse::<f64>()
+            .map_or(1.0, |opacity| opacity.clamp(0.0, 1.0))
+    }
+
+    /// Returns true if the box creates a stacking context, which paints its contents together, above or below its siblings by its `z-index`.
+    ///
+    /// [Reference](https://drafts.csswg.org/css2/#stacking-context)
+    pub(crate) fn is_stacking_context(&self) -> bool {
+        if matches!(self.kind, BoxKind::Text(_) | BoxKind::LineBreak) {
+            return false;
+        }
+        let style = &self.style;
+        (self.z_index().is_some() && (self.is_positioned() || self.is_item))
+            || matches!(style.get("position"), "fixed" | "sticky")
+            || self.opacity() < 1.0
+            || (style.get("transform") != "none"
+                && matches!(self.kind, BoxKind::Flow | BoxKind::Replaced { .. }))
+    }
+
+    /// Returns true if the box clips its contents to its padding box, which is when its `overflow` is not `visible`.
+    pub(crate) fn clips_contents(&self) -> bool {
+        matches!(self.kind, BoxKind::Flow | BoxKind::Replaced { .. })
+            && (self.style.get("overflow-x") != "visible"
+                || self.style.get("overflow-y") != "visible")
+    }
+
+    /// Moves the box, but not its children.
+    pub(crate) fn translate(&mut self, x: f64, y: f64) {
+        match self.kind {
+            BoxKind::Text(_) => {
+                for run in self.text_runs.iter_mut() {
+                    run.rect.translate(x, y);
+                }
+            }
+            BoxKind::Inline => {
+                for fragment in self.fragments.iter_mut() {
+                    fragment.translate(x, y);
+                }
+            }
+            BoxKind::LineBreak => {}
+            BoxKind::Flow | BoxKind::Replaced { .. } => self.rect.translate(x, y),
+        }
+    }
+
+    pub(crate) fn is_flex_container(&self) -> bool {
+        self.kind == BoxKind::Flow && matches!(self.style.get("display"), "flex" | "inline-flex")
+    }
+
+    pub(crate) fn is_grid_container(&self) -> bool {
+        self.kind == BoxKind::Flow && matches!(self.style.get("display"), "grid" | "inline-grid")
+    }
+
+    /// Returns true if the box only contains inline-level content, which is laid out in lines.
+    pub(crate) fn has_inline_content(&self) -> bool {
+        self.children
+            .iter()
+            .all(|child| child.inline_level || child.is_out_of_flow())
+    }
+}
+
+/// Generates the box tree of a root element. Returns `None` if the element does not generate boxes.
+pub(crate) fn build(
+    root: &Element,
+    resolver: &StyleResolver,
+    fonts: &Rc<dyn FontProvider>,
+) -> Option<LayoutBox> {
+    let mut boxes = vec![];
+    build_element(root, resolver, fonts, &mut boxes);
+    let mut root = boxes.pop()?;
+    root.inline_level = false;
+    Some(root)
+}
+
+/// Generates the boxes of an element, and appends them to a list of boxes.
+fn build_element(
+    element: &Element,
+    resolver: &StyleResolver,
+    fonts: &Rc<dyn FontProvider>,
+    boxes: &mut Vec<LayoutBox>,
+) {
+    let style = resolver.compute(element, None);
+    let display = style.get("display");
+    if display == "none" {
+        return;
+    }
+    if display == "contents" {
+        build_children(element, &style, resolver, fonts, boxes);
+        return;
+    }
+    let is_line_break = matches!(element.base().tag, Tag::Br);
+    let kind = match is_line_break {
+        true => BoxKind::LineBreak,
+        false if input_is_hidden(element) => return,
+        _ => match natural_size(element, &style, fonts) {
+            Some((width, height)) => BoxKind::Replaced { width, height },
+            None if display == "inline" || display.starts_with("ruby") => BoxKind::Inline,
+            None => BoxKind::Flow,
+        },
+    };
+    let mut layout_box = LayoutBox::new(Some(element.clone_ref()), style.clone(), kind, fonts);
+    layout_box.inline_level = display.starts_with("inline")
+        || display.starts_with("ruby")
+        || display == "table-cell"
+        || matches!(layout_box.kind, BoxKind::LineBreak);
+    // Elements with `content-visibility: hidden` skip their contents, like `display: none`.
+    let skips_contents = style.get("content-visibility") == "hidden";
+    if matches!(layout_box.kind, BoxKind::Flow | BoxKind::Inline) && !skips_contents {
+        let mut children = vec![];
+        build_pseudo_element(element, "before", resolver, fonts, &mut children);
+        build_children(element, &style, resolver, fonts, &mut children);
+        build_pseudo_element(element, "after", resolver, fonts, &mut children);
+        if is_table_part(display) {
+            children.retain(|child| !is_collapsible_white_space(child));
+        }
+        layout_box.children = children;
+        fix_up_children(&mut layout_box, resolver, fonts);
+    }
+    boxes.push(layout_box);
+}
+
+/// Generates the boxes of the children of an element in the flat tree.
+fn build_children(
+    element: &Element,
+    style: &Rc<ComputedStyle>,
+    resolver: &StyleResolver,
+    fonts: &Rc<dyn FontProvider>,
+    boxes: &mut Vec<LayoutBox>,
+) {
+    let Some(document) = element.owner_document() else {
+        return;
+    };
+    let children: Vec<ChildNode> = match shadow_root_of(element) {
+        Some(shadow_root) => child_nodes(&shadow_root),
+        None => {
+            let assigned = match is_slot(element) {
+                true => assigned_nodes(element),
+                false => vec![],
+            };
+            match assigned.is_empty() {
+                true => child_nodes(element),
+                false => assigned,
+            }
+        }
+    };
+    for child in children {
+        match child.node_type() {
+            Node::TEXT_NODE | Node::CDATA_SECTION_NODE => {
+                let data = AsNode::cast(&child).base().data.clone();
+                if data.is_empty() {
+                    continue;
+                }
+                let mut text = LayoutBox::new(None, style.clone(), BoxKind::Text(data), fonts);
+                text.inline_level = true;
+                boxes.push(text);
+            }
+            Node::ELEMENT_NODE => {
+                if let Some(element) =
+                    document.lookup_html_element(AsNode::cast(&child).get_base_ptr())
+                {
+                    build_element(&element, resolver, fonts, boxes);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn child_nodes(node: &impl AsNode) -> Vec<ChildNode> {
+    node.child_nodes().iter().map(ChildNode::from).collect()
+}
+
+/// Generates the box of a `::before` or `::after` pseudo-element, if it has content.
+fn build_pseudo_element(
+    element: &Element,
+    name: &'static str,
+    resolver: &StyleResolver,
+    fonts: &Rc<dyn FontProvider>,
+    boxes: &mut Vec<LayoutBox>,
+) {
+    let style = resolver.compute(element, Some(&PseudoElement::Named(name)));
+    let display = style.get("display");
+    if display == "none" {
+        return;
+    }
+    let Some(content) = generated_content(&style) else {
+        return;
+    };
+    let kind = match display {
+        "inline" => BoxKind::Inline,
+        _ => BoxKind::Flow,
+    };
+    let mut layout_box = LayoutBox::new(None, style.clone(), kind, fonts);
+    layout_box.inline_level = display.starts_with("inline");
+    layout_box.is_generated = true;
+    if !content.is_empty() {
+        let mut text = LayoutBox::new(None, style, BoxKind::Text(content), fonts);
+        text.inline_level = true;
+        text.is_generated = true;
+        layout_box.children.push(text);
+    }
+    boxes.push(layout_box);
+}
+
+/// Returns the text generated by the `content` property of a pseudo-element, or `None` if it generates no box.
+///
+/// [Reference](https://drafts.csswg.org/css-content/#content-property)
+fn generated_content(style: &ComputedStyle) -> Option<String> {
+    let value = style.get("content");
+    if matches!(value, "" | "normal" | "none") {
+        return None;
+    }
+    let (open, close) = match style.get("quotes") {
+        "none" => ("", ""),
+        _ => ("\u{201C}", "\u{201D}"),
+    };
+    let mut content = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '"' | '\'' => {
+                while let Some(next) = chars.next() {
+                    match next {
+                        next if next == char => break,
+                        '\\' => content.extend(chars.next()),
+                        next => content.push(next),
+                    }
+                }
+            }
+            char if char.is_whitespace() => {}
+            _ => {
+                let mut word = String::from(char);
+                while let Some(next) = chars.next_if(|next| !next.is_whitespace()) {
+                    word.push(next);
+                }
+                match word.as_str() {
+                    "open-quote" => content.push_str(open),
+                    "close-quote" => content.push_str(close),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Some(content)
+}
+
+/// Returns the natural size of a replaced element, or `None` if the element is not replaced.
+///
+/// There are no resources to load, so the size comes from the element's attributes, or from the defaults for its kind of element.
+fn natural_size(
+    element: &Element,
+    style: &ComputedStyle,
+    fonts: &Rc<dyn FontProvider>,
+) -> Option<(f64, f64)> {
+    let dimension = |name: &str, default: f64| {
+        element
+            .get_attribute(name)
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .filter(|value| *value >= 0.0)
+            .unwrap_or(default)
+    };
+    let font = Font::new(fonts, style);
+    let char_width = font.text_width("0");
+    let line_height = font.line_height(style);
+    let size = match &element.base().tag {
+        Tag::Img => (dimension("width", 0.0), dimension("height", 0.0)),
+        Tag::Canvas | Tag::Video | Tag::Iframe | Tag::Embed | Tag::Object => {
+            (dimension("width", 300.0), dimension("height", 150.0))
+        }
+        Tag::Input => match element.get_attribute("type").map(str::to_ascii_lowercase) {
+            Some(kind) if kind == "checkbox" || kind == "radio" => (13.0, 13.0),
+            _ => (dimension("size", 20.0) * char_width, line_height),
+        },
+        Tag::Textarea => (
+            dimension("cols", 20.0) * char_width,
+            dimension("rows", 2.0) * line_height,
+        ),
+        Tag::Select => {
+            let options = element.get_elements_by_tag_name("option");
+            let longest = (0..options.len())
+                .filter_map(|index| options.item(index))
+                .map(|option| {
+                    let text = option.text_content().unwrap_or_default();
+                    font.text_width(text.trim())
+                })
+                .fold(0.0, f64::max);
+            (longest + 20.0, line_height)
+        }
+        _ => return None,
+    };
+    Some(size)
+}
+
+fn input_is_hidden(element: &Element) -> bool {
+    matches!(element.base().tag, Tag::Input)
+        && element
+            .get_attribute("type")
+            .is_some_and(|kind| kind.eq_ignore_ascii_case("hidden"))
+}
+
+fn is_table_part(display: &str) -> bool {
+    display.contains("table")
+}
+
+fn is_collapsible_white_space(layout_box: &LayoutBox) -> bool {
+    match &layout_box.kind {
+        BoxKind::Text(text) => {
+            !layout_box.style.get("white-space").starts_with("pre")
+                && text
+                    .chars()
+                    .all(|char| matches!(char, ' ' | '\t' | '\n' | '\r'))
+        }
+        _ => false,
+    }
+}
+
+/// Makes the children of a box either all block-level or all inline-level, by wrapping runs of inline-level boxes among block-level ones in anonymous block boxes.
+///
+/// An inline box that contains block-level boxes is turned into a block container. In flex and grid containers, every child is block-level, and runs of text are wrapped so they become items too.
+///
+/// [Reference](https://drafts.csswg.org/css-display/#anonymous-block-level)
+fn fix_up_children(
+    layout_box: &mut LayoutBox,
+    resolver: &StyleResolver,
+    fonts: &Rc<dyn FontProvider>,
+) {
+    let is_container = layout_box.is_flex_container() || layout_box.is_grid_container();
+    let is_block_level = |child: &LayoutBox| match is_container {
+        true => !matches!(child.kind, BoxKind::Text(_)),
+        false => !child.inline_level && !child.is_out_of_flow(),
+    };
+    if !is_container && !layout_box.children.iter().any(is_block_level) {
+        return;
+    }
+    if layout_box.kind == BoxKind::Inline {
+        layout_box.kind = BoxKind::Flow;
+        layout_box.inline_level = false;
+    }
+    let mut children = vec![];
+    let mut run: Vec<LayoutBox> = vec![];
+    let flush = |run: &mut Vec<LayoutBox>, children: &mut Vec<LayoutBox>| {
+        if run.iter().all(|child| {
+            is_collapsible_white_space(child) || (child.is_out_of_flow() && !child.inline_level)
+        }) {
+            children.extend(
+                run.drain(..)
+                    .filter(|child| !is_collapsible_white_space(child)),
+            );
+            return;
+        }
+        let style = resolver.anonymous(&layout_box.style);
+        let mut anonymous = LayoutBox::new(None, style, BoxKind::Flow, fonts);
+        anonymous.children = std::mem::take(run);
+        children.push(anonymous);
+    };
+    for child in std::mem::take(&mut layout_box.children) {
+        match is_block_level(&child) {
+            true => {
+                flush(&mut run, &mut children);
+                children.push(child);
+            }
+            false => run.push(child),
+        }
+    }
+    flush(&mut run, &mut children);
+    if is_container {
+        for child in children.iter_mut() {
+            child.is_item = !child.is_absolutely_positioned();
+            child.inline_level = false;
+        }
+    }
+    layout_box.children = children;
+}
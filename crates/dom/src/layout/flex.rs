@@ -0,0 +1,621 @@
+//! Flex layout: the sizing of flex items along the main axis of their container, and their alignment in flex lines.
+//!
+//! [Reference](https://drafts.csswg.org/css-flexbox/#layout-algorithm)
+use super::{
+    alignment,
+    block::{self, ContainingBlock},
+    box_tree::LayoutBox,
+    distribute, gap,
+};
+use crate::css::ComputedStyle;
+
+/// The directions of a flex container.
+#[derive(Debug, Clone, Copy)]
+struct Axes {
+    /// Whether the main axis is horizontal.
+    is_row: bool,
+    /// Whether items are placed from the end of the main axis.
+    is_reverse: bool,
+    /// Whether lines are stacked from the end of the cross axis.
+    is_wrap_reverse: bool,
+}
+
+/// A flex item being laid out. Sizes are of the content box unless they are called outer, which means the margin box.
+#[derive(Debug)]
+struct Item {
+    /// The index of the item among the children of the container.
+    index: usize,
+    grow: f64,
+    shrink: f64,
+    base: f64,
+    hypothetical: f64,
+    min: f64,
+    max: f64,
+    /// The main size, once flexible lengths are resolved.
+    target: f64,
+    frozen: bool,
+    /// The margins, borders and padding along the main axis, with auto margins as zero.
+    main_edges: f64,
+    /// The width of an item in a column, which is known before its main size.
+    width: Option<f64>,
+    outer_cross: f64,
+    /// The baseline, relative to the top of the margin box.
+    baseline: Option<f64>,
+}
+
+impl Item {
+    fn outer_hypothetical(&self) -> f64 {
+        self.hypothetical + self.main_edges
+    }
+
+    fn outer_target(&self) -> f64 {
+        self.target + self.main_edges
+    }
+}
+
+/// A flex line: a range of items and its cross size.
+#[derive(Debug)]
+struct Line {
+    start: usize,
+    end: usize,
+    cross: f64,
+    position: f64,
+}
+
+/// Lays out the items of a flex container in its content box, and returns the height of its contents and its baseline.
+pub(crate) fn layout(
+    container: &mut LayoutBox,
+    containing_block: ContainingBlock,
+) -> (f64, Option<f64>) {
+    let style = container.style.clone();
+    let direction = style.get("flex-direction");
+    let axes = Axes {
+        is_row: direction.starts_with("row"),
+        is_reverse: direction.ends_with("-reverse"),
+        is_wrap_reverse: style.get("flex-wrap") == "wrap-reverse",
+    };
+    let is_single_line = style.get("flex-wrap") == "nowrap";
+    let row_gap = gap(&style, "row-gap", containing_block.height);
+    let column_gap = gap(&style, "column-gap", Some(containing_block.width));
+    let (main_gap, cross_gap) = match axes.is_row {
+        true => (column_gap, row_gap),
+        false => (row_gap, column_gap),
+    };
+    let (inner_main, inner_cross) = match axes.is_row {
+        true => (Some(containing_block.width), containing_block.height),
+        false => (containing_block.height, Some(containing_block.width)),
+    };
+
+    // Absolutely positioned children are placed as if they were the only item.
+    for child in container.children.iter_mut() {
+        if !child.is_item {
+            child.static_position = (0.0, 0.0);
+        }
+    }
+    let mut order: Vec<usize> = (0..container.children.len())
+        .filter(|index| container.children[*index].is_item)
+        .collect();
+    order.sort_by_key(|index| {
+        let order = container.children[*index].style.get("order");
+        order.parse::<i32>().unwrap_or(0)
+    });
+    let stretch_width = (is_single_line && !axes.is_row).then_some(containing_block.width);
+    let mut items: Vec<Item> = order
+        .into_iter()
+        .map(|index| {
+            let child = &mut container.children[index];
+            measure(child, index, axes, containing_block, &style, stretch_width)
+        })
+        .collect();
+
+    // Items are collected into lines, which break where the next item would overflow the container.
+    let mut lines = vec![];
+    let mut start = 0;
+    let mut size = 0.0;
+    for (index, item) in items.iter().enumerate() {
+        let outer = item.outer_hypothetical();
+        let overflows = size + main_gap + outer > inner_main.unwrap_or(f64::INFINITY);
+        if !is_single_line && index > start && overflows {
+            lines.push(Line::new(start, index));
+            start = index;
+            size = outer;
+        } else {
+            size += outer + if index > start { main_gap } else { 0.0 };
+        }
+    }
+    if !items.is_empty() {
+        lines.push(Line::new(start, items.len()));
+    }
+
+    // A column without a definite height is as tall as its longest line.
+    let content_main = lines
+        .iter()
+        .map(|line| {
+            let items = &items[line.start..line.end];
+            let gaps = main_gap * (items.len() - 1) as f64;
+            items.iter().map(Item::outer_hypothetical).sum::<f64>() + gaps
+        })
+        .fold(0.0, f64::max);
+    let main_size = inner_main.unwrap_or(content_main);
+    for line in &lines {
+        resolve_flexible_lengths(&mut items[line.start..line.end], main_size, main_gap);
+    }
+
+    // With their main sizes known, items are laid out to find their cross sizes.
+    for item in items.iter_mut() {
+        let child = &mut container.children[item.index];
+        layout_item(child, item, axes, containing_block, None);
+    }
+    for line in lines.iter_mut() {
+        let items = &items[line.start..line.end];
+        line.cross = match (is_single_line, inner_cross) {
+            (true, Some(cross)) => cross,
+            _ => {
+                let (above, below) = items
+                    .iter()
+                    .filter_map(|item| Some((item.baseline?, item.outer_cross)))
+                    .fold((0.0, 0.0), |(above, below), (baseline, cross)| {
+                        (f64::max(above, baseline), f64::max(below, cross - baseline))
+                    });
+                items
+                    .iter()
+                    .map(|item| item.outer_cross)
+                    .fold(above + below, f64::max)
+            }
+        };
+    }
+
+    // Lines are packed along the cross axis, and stretched to fill it by default.
+    let total_cross = lines.iter().map(|line| line.cross).sum::<f64>()
+        + cross_gap * lines.len().saturating_sub(1) as f64;
+    let free = inner_cross.map_or(0.0, |cross| cross - total_cross);
+    let align_content = style.get("align-content");
+    let (mut position, between) = match alignment(align_content) {
+        "normal" | "stretch" if !is_single_line && free > 0.0 => {
+            let extra = free / lines.len() as f64;
+            for line in lines.iter_mut() {
+                line.cross += extra;
+            }
+            (0.0, 0.0)
+        }
+        _ if is_single_line => (0.0, 0.0),
+        value => distribute(value, free, lines.len()),
+    };
+    for line in lines.iter_mut() {
+        line.position = position;
+        position += line.cross + cross_gap + between;
+    }
+    let cross_size = inner_cross.unwrap_or(total_cross);
+
+    let justify_content = style.get("justify-content");
+    for line in &lines {
+        for item in items[line.start..line.end].iter_mut() {
+            let child = &mut container.children[item.index];
+            if stretches(child, &style, axes) {
+                let edges = item.outer_cross - cross_size_of(child, axes);
+                layout_item(
+                    child,
+                    item,
+                    axes,
+                    containing_block,
+                    Some(line.cross - edges),
+                );
+            }
+        }
+        let items = &mut items[line.start..line.end];
+
+        // Free space on the main axis goes to auto margins first, then is distributed by `justify-content`.
+        let used =
+            items.iter().map(Item::outer_target).sum::<f64>() + main_gap * (items.len() - 1) as f64;
+        let free = main_size - used;
+        let auto_margin_count = items
+            .iter()
+            .map(|item| {
+                let (start, end) = auto_margins(&container.children[item.index], axes.is_row);
+                start as usize + end as usize
+            })
+            .sum::<usize>();
+        let (mut main, between) = match auto_margin_count {
+            0 => distribute(justify_content, free, items.len()),
+            _ => (0.0, 0.0),
+        };
+        let share = (free / auto_margin_count.max(1) as f64).max(0.0);
+        let baseline = items
+            .iter()
+            .filter(|item| {
+                axes.is_row && is_baseline_aligned(&container.children[item.index], &style)
+            })
+            .filter_map(|item| item.baseline)
+            .fold(0.0, f64::max);
+        for item in items.iter_mut() {
+            let child = &mut container.children[item.index];
+            let (start, end) = auto_margins(child, axes.is_row);
+            let (margin_start, margin_end) = match axes.is_row {
+                true => (&mut child.margin.left, &mut child.margin.right),
+                false => (&mut child.margin.top, &mut child.margin.bottom),
+            };
+            if start {
+                *margin_start += share;
+            }
+            if end {
+                *margin_end += share;
+            }
+            let outer = item.outer_target() + share * (start as usize + end as usize) as f64;
+            let offset = match axes.is_reverse {
+                true => main_size - main - outer,
+                false => main,
+            };
+            main += outer + main_gap + between;
+            let main_position = offset + *margin_start_of(child, axes.is_row);
+
+            // Items are aligned in their line by auto margins, or by `align-self`.
+            let free = line.cross - item.outer_cross;
+            let (start, end) = auto_margins(child, !axes.is_row);
+            let cross_offset = match (start, end) {
+                (false, false) => match align_self(child, &style) {
+                    "end" | "flex-end" | "self-end" => free,
+                    "center" => free / 2.0,
+                    "baseline" if axes.is_row => baseline - item.baseline.unwrap_or(0.0),
+                    _ => 0.0,
+                },
+                _ => {
+                    let share = free.max(0.0) / (start as usize + end as usize) as f64;
+                    let (margin_start, margin_end) = match axes.is_row {
+                        true => (&mut child.margin.top, &mut child.margin.bottom),
+                        false => (&mut child.margin.left, &mut child.margin.right),
+                    };
+                    if start {
+                        *margin_start += share;
+                    }
+                    if end {
+                        *margin_end += share;
+                    }
+                    item.outer_cross += free.max(0.0);
+                    0.0
+                }
+            };
+            let (cross_offset, line_position) = match axes.is_wrap_reverse {
+                true => (
+                    line.cross - cross_offset - item.outer_cross,
+                    cross_size - line.position - line.cross,
+                ),
+                false => (cross_offset, line.position),
+            };
+            let cross_position = line_position + cross_offset;
+            match axes.is_row {
+                true => {
+                    child.rect.x = main_position;
+                    child.rect.y = cross_position + child.margin.top;
+                }
+                false => {
+                    child.rect.y = main_position;
+                    child.rect.x = cross_position + child.margin.left;
+                }
+            }
+        }
+    }
+
+    let height = match axes.is_row {
+        true => total_cross,
+        false => content_main,
+    };
+    let baseline = items.first().and_then(|item| {
+        let child = &container.children[item.index];
+        child.baseline.map(|baseline| child.rect.y + baseline)
+    });
+    (height, baseline)
+}
+
+impl Line {
+    fn new(start: usize, end: usize) -> Self {
+        Line {
+            start,
+            end,
+            cross: 0.0,
+            position: 0.0,
+        }
+    }
+}
+
+/// Finds the flex base size and the hypothetical main size of an item.
+///
+/// [Reference](https://drafts.csswg.org/css-flexbox/#algo-main-item)
+fn measure(
+    child: &mut LayoutBox,
+    index: usize,
+    axes: Axes,
+    containing_block: ContainingBlock,
+    container: &ComputedStyle,
+    stretch_width: Option<f64>,
+) -> Item {
+    block::resolve_edges(child, containing_block.width);
+    let style = child.style.clone();
+    let horizontal = child.padding.horizontal() + child.border.horizontal();
+    let vertical = child.padding.vertical() + child.border.vertical();
+    let (main_property, main_basis, inner_edges, main_edges) = match axes.is_row {
+        true => (
+            "width",
+            Some(containing_block.width),
+            horizontal,
+            horizontal + child.margin.horizontal(),
+        ),
+        false => (
+            "height",
+            containing_block.height,
+            vertical,
+            vertical + child.margin.vertical(),
+        ),
+    };
+    // In a column, the width of an item is known before its height, which depends on it.
+    let width = match axes.is_row {
+        true => None,
+        false => {
+            let stretch = stretch_width.filter(|_| stretches(child, container, axes));
+            Some(column_width(child, containing_block, stretch))
+        }
+    };
+    let mut content = None;
+    let mut measure_content = |child: &mut LayoutBox| {
+        *content.get_or_insert_with(|| match width {
+            Some(width) => {
+                block::layout_item(child, containing_block, width, None);
+                child.rect.height - vertical
+            }
+            None => block::intrinsic_widths(child, containing_block).1,
+        })
+    };
+    let property = match style.get("flex-basis") {
+        "auto" => main_property,
+        _ => "flex-basis",
+    };
+    let base = block::content_size(&style, property, main_basis, inner_edges)
+        .unwrap_or_else(|| measure_content(child));
+    let max = block::content_size(
+        &style,
+        &format!("max-{main_property}"),
+        main_basis,
+        inner_edges,
+    )
+    .unwrap_or(f64::INFINITY);
+    let min_property = format!("min-{main_property}");
+    let is_scroll_container =
+        style.get("overflow-x") != "visible" || style.get("overflow-y") != "visible";
+    let min = match style.get(&min_property) {
+        // The automatic minimum size keeps items from shrinking below their contents.
+        "auto" if !is_scroll_container => {
+            let content = match axes.is_row {
+                true => block::intrinsic_widths(child, containing_block).0,
+                false => measure_content(child),
+            };
+            let specified = block::content_size(&style, main_property, main_basis, inner_edges);
+            specified
+                .map_or(content, |specified| specified.min(content))
+                .min(max)
+        }
+        _ => block::content_size(&style, &min_property, main_basis, inner_edges).unwrap_or(0.0),
+    };
+    let factor = |name: &str| style.get(name).parse::<f64>().unwrap_or(0.0).max(0.0);
+    Item {
+        index,
+        grow: factor("flex-grow"),
+        shrink: factor("flex-shrink"),
+        base,
+        hypothetical: base.min(max).max(min),
+        min,
+        max,
+        target: 0.0,
+        frozen: false,
+        main_edges,
+        width,
+        outer_cross: 0.0,
+        baseline: None,
+    }
+}
+
+/// Returns the width of an item in a column: its specified width, the width of its line if it stretches, or else the width that fits its contents.
+fn column_width(
+    child: &mut LayoutBox,
+    containing_block: ContainingBlock,
+    stretch: Option<f64>,
+) -> f64 {
+    let style = child.style.clone();
+    let edges = child.padding.horizontal() + child.border.horizontal();
+    let available = (containing_block.width - child.margin.horizontal() - edges).max(0.0);
+    let width = match block::content_size(&style, "width", Some(containing_block.width), edges) {
+        Some(width) => width,
+        None => match stretch {
+            Some(line) => (line - child.margin.horizontal() - edges).max(0.0),
+            None => {
+                let (min, max) = block::intrinsic_widths(child, containing_block);
+                max.min(available).max(min)
+            }
+        },
+    };
+    block::clamp(&style, "width", width, Some(containing_block.width), edges)
+}
+
+/// Grows or shrinks the items in a line to fill its main size, keeping each within its minimum and maximum.
+///
+/// [Reference](https://drafts.csswg.org/css-flexbox/#resolve-flexible-lengths)
+fn resolve_flexible_lengths(items: &mut [Item], main_size: f64, main_gap: f64) {
+    let gaps = main_gap * items.len().saturating_sub(1) as f64;
+    let used = items.iter().map(Item::outer_hypothetical).sum::<f64>() + gaps;
+    let grows = used < main_size;
+    for item in items.iter_mut() {
+        item.target = item.base;
+        let factor = match grows {
+            true => item.grow,
+            false => item.shrink,
+        };
+        item.frozen = factor == 0.0
+            || (grows && item.base > item.hypothetical)
+            || (!grows && item.base < item.hypothetical);
+        if item.frozen {
+            item.target = item.hypothetical;
+        }
+    }
+    let free_space = |items: &[Item]| {
+        main_size
+            - gaps
+            - items
+                .iter()
+                .map(|item| match item.frozen {
+                    true => item.outer_target(),
+                    false => item.base + item.main_edges,
+                })
+                .sum::<f64>()
+    };
+    let initial = free_space(items);
+    while items.iter().any(|item| !item.frozen) {
+        let mut free = free_space(items);
+        let factors: f64 = items
+            .iter()
+            .filter(|item| !item.frozen)
+            .map(|item| match grows {
+                true => item.grow,
+                false => item.shrink,
+            })
+            .sum();
+        // Factors that add up to less than one only take that fraction of the free space.
+        if factors < 1.0 && (initial * factors).abs() < free.abs() {
+            free = initial * factors;
+        }
+        let scaled: f64 = items
+            .iter()
+            .filter(|item| !item.frozen)
+            .map(|item| item.shrink * item.base)
+            .sum();
+        for item in items.iter_mut().filter(|item| !item.frozen) {
+            item.target = match grows {
+                true => item.base + free * item.grow / factors,
+                false if scaled > 0.0 => item.base + free * item.shrink * item.base / scaled,
+                false => item.base,
+            };
+        }
+        let mut violation = 0.0;
+        for item in items.iter_mut().filter(|item| !item.frozen) {
+            let clamped = item.target.min(item.max).max(item.min).max(0.0);
+            violation += clamped - item.target;
+        }
+        for item in items.iter_mut().filter(|item| !item.frozen) {
+            let clamped = item.target.min(item.max).max(item.min).max(0.0);
+            item.frozen = violation == 0.0
+                || (violation > 0.0 && clamped > item.target)
+                || (violation < 0.0 && clamped < item.target);
+            item.target = clamped;
+        }
+    }
+}
+
+/// Lays out an item with its resolved main size, and its cross size if it is stretched, and records its outer cross size and baseline.
+fn layout_item(
+    child: &mut LayoutBox,
+    item: &mut Item,
+    axes: Axes,
+    containing_block: ContainingBlock,
+    cross: Option<f64>,
+) {
+    match axes.is_row {
+        true => {
+            let edges = child.padding.vertical() + child.border.vertical();
+            let height = cross.map(|height| {
+                let basis = containing_block.height;
+                block::clamp(&child.style.clone(), "height", height, basis, edges)
+            });
+            block::layout_item(child, containing_block, item.target, height);
+            item.outer_cross = child.rect.height + child.margin.vertical();
+        }
+        false => {
+            let width = match cross {
+                Some(width) => {
+                    let edges = child.padding.horizontal() + child.border.horizontal();
+                    let basis = Some(containing_block.width);
+                    block::clamp(&child.style.clone(), "width", width, basis, edges)
+                }
+                None => item.width.unwrap_or(0.0),
+            };
+            block::layout_item(child, containing_block, width, Some(item.target));
+            item.outer_cross = child.rect.width + child.margin.horizontal();
+        }
+    }
+    item.baseline = Some(child.margin.top + child.baseline.unwrap_or(child.rect.height));
+}
+
+/// Returns the size of the content box of an item along the cross axis.
+fn cross_size_of(child: &LayoutBox, axes: Axes) -> f64 {
+    match axes.is_row {
+        true => child.rect.height - child.padding.vertical() - child.border.vertical(),
+        false => child.rect.width - child.padding.horizontal() - child.border.horizontal(),
+    }
+}
+
+/// Returns which of the margins of an item along an axis are auto.
+fn auto_margins(child: &LayoutBox, horizontal: bool) -> (bool, bool) {
+    let (start, end) = match horizontal {
+        true => ("margin-left", "margin-right"),
+        false => ("margin-top", "margin-bottom"),
+    };
+    (
+        child.style.get(start) == "auto",
+        child.style.get(end) == "auto",
+    )
+}
+
+fn margin_start_of(child: &LayoutBox, horizontal: bool) -> &f64 {
+    match horizontal {
+        true => &child.margin.left,
+        false => &child.margin.top,
+    }
+}
+
+/// Returns the alignment of an item in the cross axis, from `align-self` or the `align-items` of its container.
+fn align_self<'a>(child: &'a LayoutBox, container: &'a ComputedStyle) -> &'a str {
+    match child.style.get("align-self") {
+        "auto" => alignment(container.get("align-items")),
+        value => alignment(value),
+    }
+}
+
+fn is_baseline_aligned(child: &LayoutBox, container: &ComputedStyle) -> bool {
+    align_self(child, container) == "baseline" && auto_margins(child, false) == (false, false)
+}
+
+/// Returns true if an item is stretched to the cross size of its line: it is aligned with `stretch`, and has neither a cross size nor auto margins in the cross axis.
+fn stretches(child: &LayoutBox, container: &ComputedStyle, axes: Axes) -> bool {
+    let property = match axes.is_row {
+        true => "height",
+        false => "width",
+    };
+    matches!(align_self(child, container), "normal" | "stretch")
+        && child.style.get(property) == "auto"
+        && auto_margins(child, !axes.is_row) == (false, false)
+}
+
+/// Returns the min-content and max-content widths of the content box of a flex container.
+///
+/// [Reference](https://drafts.csswg.org/css-flexbox/#intrinsic-sizes)
+pub(crate) fn intrinsic_widths(
+    container: &LayoutBox,
+    containing_block: ContainingBlock,
+) -> (f64, f64) {
+    let style = &container.style;
+    let contributions: Vec<_> = container
+        .children
+        .iter()
+        .filter(|child| child.is_item)
+        .map(|child| block::contribution(child, containing_block))
+        .collect();
+    let largest = |(min, max): (f64, f64), (child_min, child_max): &(f64, f64)| {
+        (f64::max(min, *child_min), f64::max(max, *child_max))
+    };
+    if !style.get("flex-direction").starts_with("row") {
+        return contributions.iter().fold((0.0, 0.0), largest);
+    }
+    let gaps = gap(style, "column-gap", None) * contributions.len().saturating_sub(1) as f64;
+    let max = contributions.iter().map(|(_, max)| max).sum::<f64>() + gaps;
+    let min = match style.get("flex-wrap") {
+        "nowrap" => contributions.iter().map(|(min, _)| min).sum::<f64>() + gaps,
+        _ => contributions.iter().fold((0.0, 0.0), largest).0,
+    };
+    (min, max)
+}
@@ -0,0 +1,1315 @@
+//! Grid layout: the explicit grid from the template properties, the placement of items in it, and the sizing of its tracks.
+//!
+//! [Reference](https://drafts.csswg.org/css-grid/#layout-algorithm)
+use std::collections::HashMap;
+
+use super::{
+    alignment,
+    block::{self, ContainingBlock},
+    box_tree::{BoxKind, LayoutBox},
+    distribute, gap, Size,
+};
+use crate::css::ComputedStyle;
+
+/// One end of a track sizing function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Breadth {
+    Length(Size),
+    Flex(f64),
+    MinContent,
+    MaxContent,
+    Auto,
+    /// The maximum of `fit-content()`, which is the max-content size no larger than a limit.
+    FitContent(Size),
+}
+
+/// A track sizing function, like `minmax(100px, 1fr)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TrackSize {
+    min: Breadth,
+    max: Breadth,
+}
+
+impl TrackSize {
+    const AUTO: TrackSize = TrackSize {
+        min: Breadth::Auto,
+        max: Breadth::Auto,
+    };
+
+    fn parse(value: &str) -> Option<Self> {
+        if let Some(arguments) = function_arguments(value, "minmax") {
+            let [min, max] = arguments.as_slice() else {
+                return None;
+            };
+            let min = match Breadth::parse(min)? {
+                Breadth::Flex(_) | Breadth::FitContent(_) => return None,
+                min => min,
+            };
+            return Some(TrackSize {
+                min,
+                max: Breadth::parse(max)?,
+            });
+        }
+        Some(match Breadth::parse(value)? {
+            max @ (Breadth::Flex(_) | Breadth::FitContent(_)) => TrackSize {
+                min: Breadth::Auto,
+                max,
+            },
+            breadth => TrackSize {
+                min: breadth,
+                max: breadth,
+            },
+        })
+    }
+
+    /// Resolves percentages against the size of the grid container, or treats them as `auto` if it is indefinite.
+    fn resolve(self, basis: Option<f64>) -> Self {
+        let resolve = |breadth| match breadth {
+            Breadth::Length(size) => match size.resolve(basis) {
+                Some(pixels) => Breadth::Length(Size::Px(pixels)),
+                None => Breadth::Auto,
+            },
+            Breadth::FitContent(size) => match size.resolve(basis) {
+                Some(pixels) => Breadth::FitContent(Size::Px(pixels)),
+                None => Breadth::MaxContent,
+            },
+            breadth => breadth,
+        };
+        TrackSize {
+            min: resolve(self.min),
+            max: resolve(self.max),
+        }
+    }
+
+    /// Returns the size of the track if it does not depend on its contents or the free space.
+    fn fixed(&self) -> Option<f64> {
+        let pixels = |breadth| match breadth {
+            Breadth::Length(Size::Px(pixels)) => Some(pixels),
+            _ => None,
+        };
+        pixels(self.max).or(pixels(self.min))
+    }
+}
+
+impl Breadth {
+    fn parse(value: &str) -> Option<Self> {
+        if let Some(arguments) = function_arguments(value, "fit-content") {
+            let [limit] = arguments.as_slice() else {
+                return None;
+            };
+            return match Size::parse(limit) {
+                Size::Auto => None,
+                limit => Some(Breadth::FitContent(limit)),
+            };
+        }
+        Some(match value {
+            "auto" => Breadth::Auto,
+            "min-content" => Breadth::MinContent,
+            "max-content" => Breadth::MaxContent,
+            value => match value.strip_suffix("fr") {
+                Some(factor) => Breadth::Flex(factor.parse::<f64>().ok()?.max(0.0)),
+                None => match Size::parse(value) {
+                    Size::Auto => return None,
+                    size => Breadth::Length(size),
+                },
+            },
+        })
+    }
+}
+
+/// Returns the comma-separated arguments of a function like `minmax(10px, 1fr)`.
+fn function_arguments<'a>(value: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let arguments = value
+        .strip_prefix(name)?
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, character) in arguments.char_indices() {
+        match character {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(arguments[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(arguments[start..].trim());
+    Some(parts)
+}
+
+/// Splits a value like `[a] repeat(2, 1fr) [b c]` at the white space between its components.
+fn components(value: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = None;
+    for (index, character) in value.char_indices() {
+        match character {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            character if character.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    parts.push(&value[start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(start) = start {
+        parts.push(&value[start..]);
+    }
+    parts
+}
+
+/// The tracks of the explicit grid along one axis, and the names of the lines between them.
+#[derive(Debug, Default)]
+struct TrackList {
+    sizes: Vec<TrackSize>,
+    /// The names of each line, one more than there are tracks.
+    names: Vec<Vec<String>>,
+    /// Whether each track was repeated by `repeat(auto-fit, ...)`, so it collapses if it is empty.
+    auto_fit: Vec<bool>,
+}
+
+impl TrackList {
+    /// Parses a value of `grid-template-columns` or `grid-template-rows`. Tracks repeated with `auto-fill` or `auto-fit` are repeated as many times as fit in the available space.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-grid/#track-sizing)
+    fn parse(value: &str, available: Option<f64>, gap: f64) -> Self {
+        let components = components(value);
+        let fixed = |list: &TrackList| {
+            list.sizes
+                .iter()
+                .map(|size| size.resolve(available).fixed().unwrap_or(0.0))
+                .sum::<f64>()
+                + gap * list.sizes.len().saturating_sub(1) as f64
+        };
+        let Some(mut list) = TrackList::expand(&components, 1) else {
+            return TrackList::default();
+        };
+        if let Some(available) = available {
+            let has_auto_repeat = components.iter().any(|component| {
+                function_arguments(component, "repeat").is_some_and(|arguments| {
+                    matches!(arguments.first(), Some(&"auto-fill" | &"auto-fit"))
+                })
+            });
+            if has_auto_repeat {
+                let mut count = 1;
+                while count < 10_000 {
+                    let Some(next) = TrackList::expand(&components, count + 1) else {
+                        break;
+                    };
+                    if fixed(&next) > available || fixed(&next) <= fixed(&list) {
+                        break;
+                    }
+                    list = next;
+                    count += 1;
+                }
+            }
+        }
+        list
+    }
+
+    /// Expands the components of a track list, with automatic repetitions repeated a number of times.
+    fn expand(components: &[&str], repetitions: usize) -> Option<Self> {
+        let mut list = TrackList {
+            names: vec![vec![]],
+            ..Default::default()
+        };
+        for component in components {
+            if let Some(arguments) = function_arguments(component, "repeat") {
+                let [count, tracks] = arguments.as_slice() else {
+                    return None;
+                };
+                let (count, auto_fit) = match *count {
+                    "auto-fill" => (repetitions, false),
+                    "auto-fit" => (repetitions, true),
+                    count => (
+                        count.parse::<usize>().ok().filter(|count| *count > 0)?,
+                        false,
+                    ),
+                };
+                let tracks = self::components(tracks);
+                for _ in 0..count {
+                    for track in &tracks {
+                        list.push(track, auto_fit)?;
+                    }
+                }
+            } else {
+                list.push(component, false)?;
+            }
+        }
+        Some(list)
+    }
+
+    fn push(&mut self, component: &str, auto_fit: bool) -> Option<()> {
+        match component.strip_prefix('[') {
+            Some(names) => {
+                let names = names.strip_suffix(']')?.split_whitespace();
+                self.names.last_mut()?.extend(names.map(str::to_owned));
+            }
+            None => {
+                self.sizes.push(TrackSize::parse(component)?);
+                self.names.push(vec![]);
+                self.auto_fit.push(auto_fit);
+            }
+        }
+        Some(())
+    }
+}
+
+/// The named areas of `grid-template-areas`, as the lines that bound them.
+#[derive(Debug, Default)]
+struct Areas {
+    rows: usize,
+    columns: usize,
+    /// The start and end lines of each area: its rows, then its columns.
+    areas: HashMap<String, [(usize, usize); 2]>,
+}
+
+impl Areas {
+    /// Parses a value of `grid-template-areas`, where each string is a row and `.` marks a cell without an area.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-grid/#grid-template-areas-property)
+    fn parse(value: &str) -> Self {
+        let mut areas = Areas::default();
+        let mut rest = value;
+        let mut rows = vec![];
+        while let Some(start) = rest.find(['"', '\'']) {
+            let quote = &rest[start..start + 1];
+            let Some(end) = rest[start + 1..].find(quote) else {
+                break;
+            };
+            rows.push(&rest[start + 1..start + 1 + end]);
+            rest = &rest[start + end + 2..];
+        }
+        for (row, cells) in rows.iter().enumerate() {
+            let cells: Vec<_> = cells.split_whitespace().collect();
+            areas.columns = areas.columns.max(cells.len());
+            for (column, cell) in cells.into_iter().enumerate() {
+                if cell.chars().all(|character| character == '.') {
+                    continue;
+                }
+                let area = areas
+                    .areas
+                    .entry(cell.to_owned())
+                    .or_insert([(row, row + 1), (column, column + 1)]);
+                area[0] = (area[0].0.min(row), area[0].1.max(row + 1));
+                area[1] = (area[1].0.min(column), area[1].1.max(column + 1));
+            }
+        }
+        areas.rows = rows.len();
+        areas
+    }
+}
+
+/// The placement of an item on one axis: its start and end lines, or the number of tracks it spans if it is placed automatically.
+#[derive(Debug, Clone, Copy)]
+enum Placement {
+    Definite(i32, i32),
+    Auto(i32),
+}
+
+/// The lines of the explicit grid along one axis, and the tracks of the implicit grid around it.
+#[derive(Debug)]
+struct Axis {
+    explicit: Vec<TrackSize>,
+    names: Vec<Vec<String>>,
+    auto_fit: Vec<bool>,
+    implicit: Vec<TrackSize>,
+}
+
+impl Axis {
+    /// Collects the tracks of an axis from `grid-template-columns` or `grid-template-rows`, `grid-template-areas` and `grid-auto-columns` or `grid-auto-rows`.
+    fn new(
+        style: &ComputedStyle,
+        is_row: bool,
+        areas: &Areas,
+        available: Option<f64>,
+        gap: f64,
+    ) -> Self {
+        let (template, auto, area_count) = match is_row {
+            true => ("grid-template-rows", "grid-auto-rows", areas.rows),
+            false => ("grid-template-columns", "grid-auto-columns", areas.columns),
+        };
+        let list = TrackList::parse(style.get(template), available, gap);
+        let implicit: Vec<_> = components(style.get(auto))
+            .into_iter()
+            .filter_map(TrackSize::parse)
+            .collect();
+        let implicit = match implicit.is_empty() {
+            true => vec![TrackSize::AUTO],
+            false => implicit,
+        };
+        let mut axis = Axis {
+            explicit: list.sizes,
+            names: list.names,
+            auto_fit: list.auto_fit,
+            implicit,
+        };
+        // Template areas extend the explicit grid with tracks sized like implicit ones.
+        while axis.explicit.len() < area_count {
+            let size = axis.implicit[(axis.explicit.len()) % axis.implicit.len()];
+            axis.explicit.push(size);
+            axis.auto_fit.push(false);
+        }
+        axis.names.resize(axis.explicit.len() + 1, vec![]);
+        for (name, lines) in &areas.areas {
+            let (start, end) = lines[if is_row { 0 } else { 1 }];
+            axis.names[start].push(format!("{name}-start"));
+            axis.names[end].push(format!("{name}-end"));
+        }
+        axis
+    }
+
+    /// Returns the lines of the explicit grid with a name, in order.
+    fn lines_named(&self, name: &str) -> Vec<i32> {
+        (0..self.names.len())
+            .filter(|line| self.names[*line].iter().any(|line_name| line_name == name))
+            .map(|line| line as i32)
+            .collect()
+    }
+
+    /// Returns the track size of a track, counted from the start of the explicit grid.
+    fn size(&self, track: i32) -> TrackSize {
+        let count = self.explicit.len() as i32;
+        let implicit = self.implicit.len() as i32;
+        match track {
+            track if track < 0 => self.implicit[(implicit - 1 - (-track - 1) % implicit) as usize],
+            track if track < count => self.explicit[track as usize],
+            track => self.implicit[((track - count) % implicit) as usize],
+        }
+    }
+
+    /// Resolves the placement of an item from the values of properties like `grid-column-start` and `grid-column-end`.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-grid/#line-placement)
+    fn resolve(&self, start: &str, end: &str) -> Placement {
+        let start_line = self.line(start, "start");
+        let end_line = self.line(end, "end");
+        match (start_line, end_line) {
+            (Some(start), Some(end)) if start == end => Placement::Definite(start, start + 1),
+            (Some(start), Some(end)) => Placement::Definite(start.min(end), start.max(end)),
+            (Some(start), None) => Placement::Definite(start, self.span_from(start, end, true)),
+            (None, Some(end)) => Placement::Definite(self.span_from(end, start, false), end),
+            (None, None) => match span(start) {
+                Some((count, None)) => Placement::Auto(count),
+                _ => Placement::Auto(1),
+            },
+        }
+    }
+
+    /// Resolves a value like `3`, `-1`, `header` or `2 header` to a line. Returns `None` for `auto` and spans.
+    fn line(&self, value: &str, side: &str) -> Option<i32> {
+        let parts: Vec<_> = value.split_whitespace().collect();
+        if parts.contains(&"span") || value == "auto" {
+            return None;
+        }
+        let number = parts.iter().find_map(|part| part.parse::<i32>().ok());
+        let name = parts.iter().find(|part| part.parse::<i32>().is_err());
+        let count = self.explicit.len() as i32;
+        match (number, name) {
+            (Some(0), _) | (None, None) => None,
+            (Some(number), None) if number > 0 => Some(number - 1),
+            (Some(number), None) => Some(count + 1 + number),
+            (number, Some(name)) => {
+                // A name alone is first looked up as the line at the side of an area.
+                if number.is_none() {
+                    if let Some(line) = self.lines_named(&format!("{name}-{side}")).first() {
+                        return Some(*line);
+                    }
+                }
+                let lines = self.lines_named(name);
+                let length = lines.len() as i32;
+                // Every implicit line is taken to have every name, so missing lines are found outside the explicit grid.
+                Some(match number.unwrap_or(1) {
+                    number if number > 0 && number <= length => lines[number as usize - 1],
+                    number if number > 0 => count + number - length,
+                    number if -number <= length => lines[(length + number) as usize],
+                    number => number + length,
+                })
+            }
+        }
+    }
+
+    /// Resolves a line from a span like `span 2` or `span header` that counts from another line, forwards or backwards.
+    fn span_from(&self, line: i32, value: &str, forwards: bool) -> i32 {
+        let direction = if forwards { 1 } else { -1 };
+        match span(value) {
+            Some((count, Some(name))) => {
+                let lines = self.lines_named(name);
+                let candidates: Vec<_> = match forwards {
+                    true => lines.into_iter().filter(|other| *other > line).collect(),
+                    false => lines
+                        .into_iter()
+                        .rev()
+                        .filter(|other| *other < line)
+                        .collect(),
+                };
+                match candidates.get(count as usize - 1) {
+                    Some(other) => *other,
+                    None if forwards => {
+                        let count_past = count - candidates.len() as i32;
+                        line.max(self.explicit.len() as i32) + count_past
+                    }
+                    None => line.min(0) - (count - candidates.len() as i32),
+                }
+            }
+            Some((count, None)) => line + direction * count,
+            None => line + direction,
+        }
+    }
+}
+
+/// Parses a span like `span 2` or `span 2 header`, or returns `None` if the value is not a span.
+fn span(value: &str) -> Option<(i32, Option<&str>)> {
+    let parts: Vec<_> = value.split_whitespace().collect();
+    if !parts.contains(&"span") {
+        return None;
+    }
+    let count = parts
+        .iter()
+        .find_map(|part| part.parse::<i32>().ok())
+        .unwrap_or(1)
+        .max(1);
+    let name = parts
+        .iter()
+        .find(|part| **part != "span" && part.parse::<i32>().is_err())
+        .copied();
+    Some((count, name))
+}
+
+/// A grid item: the index of its box among the children of the container, and the lines of its area, counted from the start of the implicit grid. The first pair is its rows.
+#[derive(Debug, Clone, Copy)]
+struct GridItem {
+    index: usize,
+    area: [(usize, usize); 2],
+}
+
+/// The grid of a container, after items are placed.
+#[derive(Debug)]
+struct Grid {
+    items: Vec<GridItem>,
+    /// The track sizes of the rows and the columns of the implicit grid.
+    tracks: [Vec<TrackSize>; 2],
+    /// Whether each track collapses because it was repeated by `auto-fit` and has no items.
+    collapsed: [Vec<bool>; 2],
+}
+
+impl Grid {
+    /// Places the items of a grid container, first those with definite positions, then the others in order along the direction of `grid-auto-flow`.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-grid/#auto-placement-algo)
+    fn place(container: &LayoutBox, axes: &[Axis; 2]) -> Self {
+        let style = &container.style;
+        let flow = style.get("grid-auto-flow");
+        let is_dense = flow.contains("dense");
+        // The primary axis is the one the cursor moves along: columns when rows are filled, rows when columns are.
+        let primary = if flow.contains("column") { 0 } else { 1 };
+        let secondary = 1 - primary;
+        let placements: Vec<(usize, [Placement; 2])> = container
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| child.is_item)
+            .map(|(index, child)| {
+                let child_style = &child.style;
+                let placement = |axis: &Axis, name: &str| {
+                    let start = child_style.get(&format!("grid-{name}-start"));
+                    let end = child_style.get(&format!("grid-{name}-end"));
+                    axis.resolve(start, end)
+                };
+                (
+                    index,
+                    [placement(&axes[0], "row"), placement(&axes[1], "column")],
+                )
+            })
+            .collect();
+
+        // Lines before the explicit grid move it, so that every line is counted from the start of the implicit grid.
+        let mut offsets = [0, 0];
+        for (_, placement) in &placements {
+            for axis in 0..2 {
+                if let Placement::Definite(start, _) = placement[axis] {
+                    offsets[axis] = offsets[axis].max(-start);
+                }
+            }
+        }
+        let to_area = |placement: Placement, axis: usize| match placement {
+            Placement::Definite(start, end) => Some((
+                (start + offsets[axis]) as usize,
+                (end + offsets[axis]) as usize,
+            )),
+            Placement::Auto(_) => None,
+        };
+        let span_of = |placement: Placement| match placement {
+            Placement::Definite(start, end) => (end - start) as usize,
+            Placement::Auto(span) => span as usize,
+        };
+        let mut counts = [0, 1].map(|axis| axes[axis].explicit.len() + offsets[axis] as usize);
+        // The primary axis has enough tracks for every item positioned or spanning along it.
+        for (_, placement) in &placements {
+            let end = match to_area(placement[primary], primary) {
+                Some((_, end)) => end,
+                None => span_of(placement[primary]),
+            };
+            counts[primary] = counts[primary].max(end);
+        }
+
+        let mut placed: Vec<GridItem> = vec![];
+        let overlaps = |placed: &[GridItem], area: [(usize, usize); 2]| {
+            placed.iter().any(|item| {
+                (0..2).all(|axis| {
+                    item.area[axis].0 < area[axis].1 && area[axis].0 < item.area[axis].1
+                })
+            })
+        };
+        let make_area = |primary_range: (usize, usize), secondary_range: (usize, usize)| {
+            let mut area = [(0, 0); 2];
+            area[primary] = primary_range;
+            area[secondary] = secondary_range;
+            area
+        };
+
+        // Items with definite positions on both axes are placed first.
+        for (index, placement) in &placements {
+            if let (Some(rows), Some(columns)) =
+                (to_area(placement[0], 0), to_area(placement[1], 1))
+            {
+                placed.push(GridItem {
+                    index: *index,
+                    area: [rows, columns],
+                });
+            }
+        }
+        // Items locked to a track of the secondary axis are placed along it.
+        let mut cursors: HashMap<usize, usize> = HashMap::new();
+        for (index, placement) in &placements {
+            let (Some(secondary_range), None) = (
+                to_area(placement[secondary], secondary),
+                to_area(placement[primary], primary),
+            ) else {
+                continue;
+            };
+            let span = span_of(placement[primary]);
+            let mut start = match is_dense {
+                true => 0,
+                false => cursors.get(&secondary_range.0).copied().unwrap_or(0),
+            };
+            while overlaps(&placed, make_area((start, start + span), secondary_range)) {
+                start += 1;
+            }
+            cursors.insert(secondary_range.0, start + span);
+            counts[primary] = counts[primary].max(start + span);
+            placed.push(GridItem {
+                index: *index,
+                area: make_area((start, start + span), secondary_range),
+            });
+        }
+        // The other items are placed at the auto-placement cursor.
+        let mut cursor = (0, 0);
+        for (index, placement) in &placements {
+            if to_area(placement[secondary], secondary).is_some() {
+                continue;
+            }
+            let secondary_span = span_of(placement[secondary]);
+            if is_dense {
+                cursor = (0, 0);
+            }
+            let area = match to_area(placement[primary], primary) {
+                Some(primary_range) => {
+                    if !is_dense && primary_range.0 < cursor.1 {
+                        cursor.0 += 1;
+                    }
+                    cursor.1 = primary_range.0;
+                    while overlaps(
+                        &placed,
+                        make_area(primary_range, (cursor.0, cursor.0 + secondary_span)),
+                    ) {
+                        cursor.0 += 1;
+                    }
+                    make_area(primary_range, (cursor.0, cursor.0 + secondary_span))
+                }
+                None => {
+                    let span = span_of(placement[primary]).min(counts[primary]);
+                    loop {
+                        if cursor.1 + span > counts[primary] {
+                            cursor = (cursor.0 + 1, 0);
+                            continue;
+                        }
+                        let area = make_area(
+                            (cursor.1, cursor.1 + span),
+                            (cursor.0, cursor.0 + secondary_span),
+                        );
+                        if !overlaps(&placed, area) {
+                            cursor.1 += span;
+                            break area;
+                        }
+                        cursor.1 += 1;
+                    }
+                }
+            };
+            placed.push(GridItem {
+                index: *index,
+                area,
+            });
+        }
+        placed.sort_by_key(|item| item.index);
+
+        for item in &placed {
+            for (count, range) in counts.iter_mut().zip(item.area) {
+                *count = (*count).max(range.1);
+            }
+        }
+        let tracks = [0, 1].map(|axis| {
+            (0..counts[axis])
+                .map(|track| axes[axis].size(track as i32 - offsets[axis]))
+                .collect::<Vec<_>>()
+        });
+        let collapsed = [0, 1].map(|axis| {
+            (0..counts[axis])
+                .map(|track| {
+                    let explicit = track as i32 - offsets[axis];
+                    let auto_fit = usize::try_from(explicit)
+                        .ok()
+                        .and_then(|explicit| axes[axis].auto_fit.get(explicit))
+                        .copied()
+                        .unwrap_or(false);
+                    auto_fit
+                        && !placed
+                            .iter()
+                            .any(|item| item.area[axis].0 <= track && track < item.area[axis].1)
+                })
+                .collect::<Vec<_>>()
+        });
+        Grid {
+            items: placed,
+            tracks,
+            collapsed,
+        }
+    }
+}
+
+/// How much space the tracks of an axis are sized in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Available {
+    Definite(f64),
+    MinContent,
+    MaxContent,
+}
+
+/// A track being sized, with its base size and its growth limit.
+#[derive(Debug, Clone, Copy)]
+struct Track {
+    size: TrackSize,
+    base: f64,
+    limit: f64,
+    collapsed: bool,
+}
+
+/// The min-content and max-content contributions of an item to the tracks it spans.
+#[derive(Debug, Clone, Copy)]
+struct Contribution {
+    start: usize,
+    end: usize,
+    min: f64,
+    max: f64,
+}
+
+/// Sizes the tracks of an axis to fit the contributions of the items in them.
+///
+/// [Reference](https://drafts.csswg.org/css-grid/#algo-track-sizing)
+fn size_tracks(
+    sizes: &[TrackSize],
+    collapsed: &[bool],
+    contributions: &[Contribution],
+    available: Available,
+    gap: f64,
+    stretch: bool,
+) -> Vec<Track> {
+    let basis = match available {
+        Available::Definite(size) => Some(size),
+        _ => None,
+    };
+    let mut tracks: Vec<Track> = sizes
+        .iter()
+        .zip(collapsed)
+        .map(|(size, collapsed)| {
+            let size = size.resolve(basis);
+            let base = match size.min {
+                Breadth::Length(Size::Px(pixels)) => pixels,
+                _ => 0.0,
+            };
+            let limit = match size.max {
+                Breadth::Length(Size::Px(pixels)) => pixels.max(base),
+                _ => f64::INFINITY,
+            };
+            match collapsed {
+                true => Track {
+                    size,
+                    base: 0.0,
+                    limit: 0.0,
+                    collapsed: true,
+                },
+                false => Track {
+                    size,
+                    base,
+                    limit,
+                    collapsed: false,
+                },
+            }
+        })
+        .collect();
+    let gaps = |tracks: &[Track]| {
+        gap * tracks
+            .iter()
+            .filter(|track| !track.collapsed)
+            .count()
+            .saturating_sub(1) as f64
+    };
+    let is_flexible = |track: &Track| matches!(track.size.max, Breadth::Flex(_));
+
+    // Items spanning one track size it directly. Items spanning several share their size out among the intrinsic tracks, unless they span a flexible track.
+    let mut sorted = contributions.to_vec();
+    sorted.sort_by_key(|contribution| contribution.end - contribution.start);
+    for contribution in &sorted {
+        let spanned = &mut tracks[contribution.start..contribution.end];
+        if spanned.len() == 1 {
+            let track = &mut spanned[0];
+            if track.collapsed {
+                continue;
+            }
+            match track.size.min {
+                Breadth::MinContent | Breadth::Auto => {
+                    track.base = track.base.max(contribution.min)
+                }
+                Breadth::MaxContent => track.base = track.base.max(contribution.max),
+                _ => {}
+            }
+            let limit = match track.size.max {
+                Breadth::MinContent => Some(contribution.min),
+                Breadth::MaxContent | Breadth::Auto => Some(contribution.max),
+                Breadth::FitContent(Size::Px(limit)) => Some(contribution.max.min(limit)),
+                _ => None,
+            };
+            if let Some(limit) = limit {
+                track.limit = match track.limit.is_infinite() {
+                    true => limit,
+                    false => track.limit.max(limit),
+                };
+            }
+            continue;
+        }
+        if spanned.iter().any(is_flexible) {
+            continue;
+        }
+        let spanned_gaps = gaps(spanned);
+        let intrinsic_min = |track: &Track| {
+            !track.collapsed
+                && matches!(
+                    track.size.min,
+                    Breadth::MinContent | Breadth::MaxContent | Breadth::Auto
+                )
+        };
+        let extra =
+            contribution.min - spanned_gaps - spanned.iter().map(|track| track.base).sum::<f64>();
+        let count = spanned.iter().filter(|track| intrinsic_min(track)).count();
+        if extra > 0.0 && count > 0 {
+            for track in spanned.iter_mut().filter(|track| intrinsic_min(track)) {
+                track.base += extra / count as f64;
+            }
+        }
+        let intrinsic_max = |track: &Track| {
+            !track.collapsed
+                && matches!(
+                    track.size.max,
+                    Breadth::MinContent
+                        | Breadth::MaxContent
+                        | Breadth::Auto
+                        | Breadth::FitContent(_)
+                )
+        };
+        let limits = spanned
+            .iter()
+            .map(|track| match track.limit.is_infinite() {
+                true => track.base,
+                false => track.limit,
+            })
+            .sum::<f64>();
+        let extra = contribution.max - spanned_gaps - limits;
+        let count = spanned.iter().filter(|track| intrinsic_max(track)).count();
+        for track in spanned.iter_mut().filter(|track| intrinsic_max(track)) {
+            let limit = match track.limit.is_infinite() {
+                true => track.base,
+                false => track.limit,
+            };
+            track.limit = limit + extra.max(0.0) / count as f64;
+        }
+    }
+    for track in tracks.iter_mut() {
+        if track.limit.is_infinite() {
+            track.limit = track.base;
+        }
+        track.limit = track.limit.max(track.base);
+    }
+
+    // Free space grows the tracks up to their growth limits.
+    match available {
+        Available::Definite(size) => {
+            let mut free =
+                size - gaps(&tracks) - tracks.iter().map(|track| track.base).sum::<f64>();
+            loop {
+                let growable = tracks
+                    .iter()
+                    .filter(|track| !is_flexible(track) && track.base < track.limit)
+                    .count();
+                if free <= 1e-9 || growable == 0 {
+                    break;
+                }
+                let share = free / growable as f64;
+                for track in tracks
+                    .iter_mut()
+                    .filter(|track| !is_flexible(track) && track.base < track.limit)
+                {
+                    let grown = share.min(track.limit - track.base);
+                    track.base += grown;
+                    free -= grown;
+                }
+            }
+        }
+        Available::MaxContent => {
+            for track in tracks.iter_mut().filter(|track| !is_flexible(track)) {
+                track.base = track.limit;
+            }
+        }
+        Available::MinContent => {}
+    }
+
+    // Flexible tracks share out the space left, each taking its flex factor of the size of an `fr`.
+    let factor = |track: &Track| match track.size.max {
+        Breadth::Flex(factor) if !track.collapsed => factor,
+        _ => 0.0,
+    };
+    if tracks.iter().any(|track| factor(track) > 0.0) {
+        let fraction = match available {
+            Available::Definite(size) => {
+                let mut inflexible = vec![false; tracks.len()];
+                loop {
+                    let leftover = size
+                        - gaps(&tracks)
+                        - tracks
+                            .iter()
+                            .zip(&inflexible)
+                            .filter(|(track, inflexible)| factor(track) == 0.0 || **inflexible)
+                            .map(|(track, _)| track.base)
+                            .sum::<f64>();
+                    let factors = tracks
+                        .iter()
+                        .zip(&inflexible)
+                        .filter(|(_, inflexible)| !**inflexible)
+                        .map(|(track, _)| factor(track))
+                        .sum::<f64>();
+                    let fraction = leftover.max(0.0) / factors.max(1.0);
+                    let mut changed = false;
+                    for (track, inflexible) in tracks.iter().zip(inflexible.iter_mut()) {
+                        if factor(track) > 0.0
+                            && !*inflexible
+                            && fraction * factor(track) < track.base
+                        {
+                            *inflexible = true;
+                            changed = true;
+                        }
+                    }
+                    if !changed {
+                        break fraction;
+                    }
+                }
+            }
+            _ => {
+                let from_tracks = tracks
+                    .iter()
+                    .filter(|track| factor(track) > 0.0)
+                    .map(|track| match factor(track) > 1.0 {
+                        true => track.base / factor(track),
+                        false => track.base,
+                    })
+                    .fold(0.0, f64::max);
+                contributions
+                    .iter()
+                    .filter(|contribution| {
+                        tracks[contribution.start..contribution.end]
+                            .iter()
+                            .any(|track| factor(track) > 0.0)
+                    })
+                    .map(|contribution| {
+                        let spanned = &tracks[contribution.start..contribution.end];
+                        let size = match available {
+                            Available::MinContent => contribution.min,
+                            _ => contribution.max,
+                        };
+                        let fixed = spanned
+                            .iter()
+                            .filter(|track| factor(track) == 0.0)
+                            .map(|track| track.base)
+                            .sum::<f64>();
+                        let factors = spanned.iter().map(factor).sum::<f64>();
+                        (size - fixed - gaps(spanned)) / factors.max(1.0)
+                    })
+                    .fold(from_tracks, f64::max)
+            }
+        };
+        for track in tracks.iter_mut() {
+            track.base = track.base.max(fraction * factor(track));
+        }
+    }
+
+    // Tracks with an automatic maximum stretch to fill what is left.
+    if let (true, Available::Definite(size)) = (stretch, available) {
+        let free = size - gaps(&tracks) - tracks.iter().map(|track| track.base).sum::<f64>();
+        let count = tracks
+            .iter()
+            .filter(|track| !track.collapsed && track.size.max == Breadth::Auto)
+            .count();
+        if free > 0.0 && count > 0 {
+            for track in tracks
+                .iter_mut()
+                .filter(|track| !track.collapsed && track.size.max == Breadth::Auto)
+            {
+                track.base += free / count as f64;
+            }
+        }
+    }
+    tracks
+}
+
+/// Returns the start of each track and the end of the last, after the free space is distributed by `justify-content` or `align-content`.
+fn positions(tracks: &[Track], gap: f64, available: Option<f64>, value: &str) -> Vec<f64> {
+    let visible = tracks.iter().filter(|track| !track.collapsed).count();
+    let used =
+        tracks.iter().map(|track| track.base).sum::<f64>() + gap * visible.saturating_sub(1) as f64;
+    let (mut position, between) = match (alignment(value), available) {
+        ("normal" | "stretch", _) | (_, None) => (0.0, 0.0),
+        (value, Some(available)) => distribute(value, available - used, visible),
+    };
+    let mut positions = vec![];
+    let mut first = true;
+    for track in tracks {
+        if !track.collapsed && !first {
+            position += gap + between;
+        }
+        positions.push(position);
+        position += track.base;
+        first &= track.collapsed;
+    }
+    positions.push(position);
+    positions
+}
+
+/// Returns the size of the area between two lines, including the gaps inside it.
+fn area_size(tracks: &[Track], positions: &[f64], range: (usize, usize)) -> f64 {
+    match range.1 > range.0 {
+        true => positions[range.1 - 1] + tracks[range.1 - 1].base - positions[range.0],
+        false => 0.0,
+    }
+}
+
+/// Lays out the items of a grid container in its content box, and returns the height of its contents and its baseline.
+pub(crate) fn layout(
+    container: &mut LayoutBox,
+    containing_block: ContainingBlock,
+) -> (f64, Option<f64>) {
+    let style = container.style.clone();
+    let row_gap = gap(&style, "row-gap", containing_block.height);
+    let column_gap = gap(&style, "column-gap", Some(containing_block.width));
+    let areas = Areas::parse(style.get("grid-template-areas"));
+    let axes = [
+        Axis::new(&style, true, &areas, containing_block.height, row_gap),
+        Axis::new(
+            &style,
+            false,
+            &areas,
+            Some(containing_block.width),
+            column_gap,
+        ),
+    ];
+    for child in container.children.iter_mut() {
+        if !child.is_item {
+            child.static_position = (0.0, 0.0);
+        }
+    }
+    let grid = Grid::place(container, &axes);
+
+    let stretches_content =
+        |property: &str| matches!(alignment(style.get(property)), "normal" | "stretch");
+    let contributions: Vec<_> = grid
+        .items
+        .iter()
+        .map(|item| {
+            let child = &container.children[item.index];
+            let (min, max) = block::contribution(child, containing_block);
+            Contribution {
+                start: item.area[1].0,
+                end: item.area[1].1,
+                min,
+                max,
+            }
+        })
+        .collect();
+    let columns = size_tracks(
+        &grid.tracks[1],
+        &grid.collapsed[1],
+        &contributions,
+        Available::Definite(containing_block.width),
+        column_gap,
+        stretches_content("justify-content"),
+    );
+    let column_positions = positions(
+        &columns,
+        column_gap,
+        Some(containing_block.width),
+        style.get("justify-content"),
+    );
+
+    // With the widths of their areas known, items are laid out to find their heights.
+    let mut widths = vec![];
+    let contributions: Vec<_> = grid
+        .items
+        .iter()
+        .map(|item| {
+            let child = &mut container.children[item.index];
+            let area_width = area_size(&columns, &column_positions, item.area[1]);
+            let area = ContainingBlock {
+                width: area_width,
+                height: None,
+            };
+            block::resolve_edges(child, area_width);
+            let width = item_width(child, area, &style);
+            widths.push(width);
+            block::layout_item(child, area, width, None);
+            let height = child.rect.height + child.margin.vertical();
+            Contribution {
+                start: item.area[0].0,
+                end: item.area[0].1,
+                min: height,
+                max: height,
+            }
+        })
+        .collect();
+    let available_height = match containing_block.height {
+        Some(height) => Available::Definite(height),
+        None => Available::MaxContent,
+    };
+    let rows = size_tracks(
+        &grid.tracks[0],
+        &grid.collapsed[0],
+        &contributions,
+        available_height,
+        row_gap,
+        stretches_content("align-content"),
+    );
+    let row_positions = positions(
+        &rows,
+        row_gap,
+        containing_block.height,
+        style.get("align-content"),
+    );
+
+    for (item, width) in grid.items.iter().zip(widths) {
+        let child = &mut container.children[item.index];
+        let area = ContainingBlock {
+            width: area_size(&columns, &column_positions, item.area[1]),
+            height: Some(area_size(&rows, &row_positions, item.area[0])),
+        };
+        block::resolve_edges(child, area.width);
+        let area_height = area.height.unwrap_or(0.0);
+        let height = match self_alignment(child, &style, false) {
+            "stretch" if child.style.get("height") == "auto" && !has_auto_margin(child, false) => {
+                let edges = child.padding.vertical() + child.border.vertical();
+                let height = (area_height - child.margin.vertical() - edges).max(0.0);
+                Some(block::clamp(
+                    &child.style.clone(),
+                    "height",
+                    height,
+                    area.height,
+                    edges,
+                ))
+            }
+            _ => None,
+        };
+        block::layout_item(child, area, width, height);
+        let x = align_in_area(child, &style, true, area.width);
+        let y = align_in_area(child, &style, false, area_height);
+        child.rect.x = column_positions[item.area[1].0] + x;
+        child.rect.y = row_positions[item.area[0].0] + y;
+    }
+
+    let height = rows.iter().map(|track| track.base).sum::<f64>()
+        + row_gap
+            * rows
+                .iter()
+                .filter(|track| !track.collapsed)
+                .count()
+                .saturating_sub(1) as f64;
+    let baseline = grid
+        .items
+        .iter()
+        .filter(|item| item.area[0].0 == 0)
+        .find_map(|item| {
+            let child = &container.children[item.index];
+            child.baseline.map(|baseline| child.rect.y + baseline)
+        });
+    (height, baseline)
+}
+
+/// Returns the content-box width of an item in its area: the width of the area if it stretches, or else its specified width or the width that fits its contents.
+fn item_width(child: &LayoutBox, area: ContainingBlock, container: &ComputedStyle) -> f64 {
+    let style = &child.style;
+    let edges = child.padding.horizontal() + child.border.horizontal();
+    let available = (area.width - child.margin.horizontal() - edges).max(0.0);
+    let width = match block::content_size(style, "width", Some(area.width), edges) {
+        Some(width) => width,
+        None => match self_alignment(child, container, true) {
+            "stretch" if !has_auto_margin(child, true) => available,
+            _ => match child.kind {
+                BoxKind::Replaced { .. } => block::intrinsic_widths(child, area).1,
+                _ => {
+                    let (min, max) = block::intrinsic_widths(child, area);
+                    max.min(available).max(min)
+                }
+            },
+        },
+    };
+    block::clamp(style, "width", width, Some(area.width), edges)
+}
+
+/// Returns the alignment of an item in its area along an axis, from `justify-self` or `align-self`, or the `justify-items` or `align-items` of its container.
+///
+/// `normal` stretches items, except replaced ones, which are aligned to the start.
+fn self_alignment<'a>(
+    child: &'a LayoutBox,
+    container: &'a ComputedStyle,
+    horizontal: bool,
+) -> &'a str {
+    let (own, inherited) = match horizontal {
+        true => ("justify-self", "justify-items"),
+        false => ("align-self", "align-items"),
+    };
+    let value = match child.style.get(own) {
+        "auto" => alignment(container.get(inherited)),
+        value => alignment(value),
+    };
+    match value {
+        "normal" | "legacy" => match child.kind {
+            BoxKind::Replaced { .. } => "start",
+            _ => "stretch",
+        },
+        value => value,
+    }
+}
+
+fn has_auto_margin(child: &LayoutBox, horizontal: bool) -> bool {
+    let (start, end) = match horizontal {
+        true => ("margin-left", "margin-right"),
+        false => ("margin-top", "margin-bottom"),
+    };
+    child.style.get(start) == "auto" || child.style.get(end) == "auto"
+}
+
+/// Aligns a laid out item in its area along an axis, and returns the offset of its border box from the start of the area. Auto margins take up the free space first.
+fn align_in_area(
+    child: &mut LayoutBox,
+    container: &ComputedStyle,
+    horizontal: bool,
+    area: f64,
+) -> f64 {
+    let (size, start, end, start_name, end_name) = match horizontal {
+        true => (
+            child.rect.width,
+            child.margin.left,
+            child.margin.right,
+            "margin-left",
+            "margin-right",
+        ),
+        false => (
+            child.rect.height,
+            child.margin.top,
+            child.margin.bottom,
+            "margin-top",
+            "margin-bottom",
+        ),
+    };
+    let free = area - size - start - end;
+    let auto_start = child.style.get(start_name) == "auto";
+    let auto_end = child.style.get(end_name) == "auto";
+    let offset = match (auto_start, auto_end) {
+        (true, true) => free.max(0.0) / 2.0,
+        (true, false) => free.max(0.0),
+        (false, true) => 0.0,
+        (false, false) => match self_alignment(child, container, horizontal) {
+            "end" | "flex-end" | "self-end" => free,
+            "right" if horizontal => free,
+            "center" => free / 2.0,
+            _ => 0.0,
+        },
+    };
+    let (margin_start, margin_end) = match horizontal {
+        true => (&mut child.margin.left, &mut child.margin.right),
+        false => (&mut child.margin.top, &mut child.margin.bottom),
+    };
+    if auto_start {
+        *margin_start += offset;
+    }
+    if auto_end {
+        *margin_end += free.max(0.0) - offset;
+    }
+    start + offset
+}
+
+/// Returns the min-content and max-content widths of the content box of a grid container.
+///
+/// [Reference](https://drafts.csswg.org/css-grid/#intrinsic-sizes)
+pub(crate) fn intrinsic_widths(
+    container: &LayoutBox,
+    containing_block: ContainingBlock,
+) -> (f64, f64) {
+    let style = &container.style;
+    let column_gap = gap(style, "column-gap", None);
+    let areas = Areas::parse(style.get("grid-template-areas"));
+    let axes = [
+        Axis::new(style, true, &areas, None, 0.0),
+        Axis::new(style, false, &areas, None, column_gap),
+    ];
+    let grid = Grid::place(container, &axes);
+    let contributions: Vec<_> = grid
+        .items
+        .iter()
+        .map(|item| {
+            let (min, max) = block::contribution(&container.children[item.index], containing_block);
+            Contribution {
+                start: item.area[1].0,
+                end: item.area[1].1,
+                min,
+                max,
+            }
+        })
+        .collect();
+    let width = |available| {
+        let tracks = size_tracks(
+            &grid.tracks[1],
+            &grid.collapsed[1],
+            &contributions,
+            available,
+            column_gap,
+            false,
+        );
+        let visible = tracks.iter().filter(|track| !track.collapsed).count();
+        tracks.iter().map(|track| track.base).sum::<f64>()
+            + column_gap * visible.saturating_sub(1) as f64
+    };
+    (width(Available::MinContent), width(Available::MaxContent))
+}
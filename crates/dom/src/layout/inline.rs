@@ -0,0 +1,996 @@
+//! Inline layout: the processing of white space, the breaking of inline content into lines, and the alignment of boxes on each line.
+//!
+//! [Reference](https://drafts.csswg.org/css-inline/)
+use super::{
+    block::{self, ContainingBlock, Floats},
+    box_tree::{BoxKind, LayoutBox, TextRun},
+    Rect, Sides, Size,
+};
+use crate::{css::ComputedStyle, font::is_wide};
+use std::ops::Range;
+use unicode_bidi::{BidiInfo, Level};
+
+/// The result of laying out lines.
+pub(crate) struct Lines {
+    pub height: f64,
+    /// The baseline of the last line, relative to the top of the content box.
+    pub baseline: Option<f64>,
+}
+
+/// A piece of inline content that is not broken across lines.
+#[derive(Debug, Clone)]
+struct Piece {
+    /// The path of child indices from the block container to the box of the piece.
+    path: Vec<usize>,
+    kind: PieceKind,
+    /// The advance of the piece, including any white space at its end.
+    width: f64,
+    /// The width of the white space at the end of the piece, which hangs past the end of a line.
+    hang: f64,
+    /// Whether a line can break after the piece.
+    break_after: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PieceKind {
+    /// The start of an inline box, with the width of its left margin, border and padding.
+    Start,
+    /// The end of an inline box, with the width of its right margin, border and padding.
+    End,
+    Text(String),
+    Atomic,
+    LineBreak,
+    OutOfFlow,
+}
+
+impl Piece {
+    /// Returns true if the piece makes the line it is on take up space.
+    fn has_content(&self, layout_box: &LayoutBox) -> bool {
+        match &self.kind {
+            PieceKind::Text(text) => self.width > self.hang || text.chars().any(|char| char != ' '),
+            PieceKind::Start | PieceKind::End => {
+                self.width != 0.0
+                    || layout_box.border.vertical() != 0.0
+                    || layout_box.padding.vertical() != 0.0
+            }
+            PieceKind::Atomic | PieceKind::LineBreak => true,
+            PieceKind::OutOfFlow => false,
+        }
+    }
+}
+
+/// A line box, covering a range of pieces.
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    start: usize,
+    end: usize,
+}
+
+/// Returns the box at a path of child indices.
+fn box_at<'a>(children: &'a [LayoutBox], path: &[usize]) -> &'a LayoutBox {
+    let (first, rest) = path.split_first().expect("paths are never empty");
+    match rest.is_empty() {
+        true => &children[*first],
+        false => box_at(&children[*first].children, rest),
+    }
+}
+
+fn box_at_mut<'a>(children: &'a mut [LayoutBox], path: &[usize]) -> &'a mut LayoutBox {
+    let (first, rest) = path.split_first().expect("paths are never empty");
+    match rest.is_empty() {
+        true => &mut children[*first],
+        false => box_at_mut(&mut children[*first].children, rest),
+    }
+}
+
+/// Calls a closure with each box in the inline content of a block container and its path, in tree order.
+fn for_each_box(
+    children: &mut [LayoutBox],
+    path: &mut Vec<usize>,
+    callback: &mut impl FnMut(&mut LayoutBox, &[usize]),
+) {
+    for (index, child) in children.iter_mut().enumerate() {
+        path.push(index);
+        callback(child, path);
+        if child.kind == BoxKind::Inline {
+            for_each_box(&mut child.children, path, callback);
+        }
+        path.pop();
+    }
+}
+
+/// Breaks the inline content of a block container into pieces, processing its white space.
+///
+/// The widths of atomic inlines are given by a closure, since they differ between layout and the computation of intrinsic sizes.
+fn pieces(
+    layout_box: &LayoutBox,
+    basis: f64,
+    atomic_width: &impl Fn(&LayoutBox) -> f64,
+) -> Vec<Piece> {
+    let mut pieces = vec![];
+    // Whether the last character was a collapsible space, which makes the following ones collapse. Spaces at the start of the content are removed.
+    let mut after_space = true;
+    let mut path = vec![];
+    collect_pieces(
+        &layout_box.children,
+        basis,
+        atomic_width,
+        &mut path,
+        &mut after_space,
+        &mut pieces,
+    );
+    pieces
+}
+
+fn collect_pieces(
+    children: &[LayoutBox],
+    basis: f64,
+    atomic_width: &impl Fn(&LayoutBox) -> f64,
+    path: &mut Vec<usize>,
+    after_space: &mut bool,
+    pieces: &mut Vec<Piece>,
+) {
+    for (index, child) in children.iter().enumerate() {
+        path.push(index);
+        let piece = |path: &[usize], kind: PieceKind, width: f64, break_after: bool| Piece {
+            path: path.to_vec(),
+            kind,
+            width,
+            hang: 0.0,
+            break_after,
+        };
+        match &child.kind {
+            _ if child.is_out_of_flow() => {
+                pieces.push(piece(path, PieceKind::OutOfFlow, 0.0, false))
+            }
+            BoxKind::Inline => {
+                let margin = Sides::margin(&child.style, basis);
+                let border = Sides::border(&child.style);
+                let padding = Sides::padding(&child.style, basis);
+                let start = margin.left + border.left + padding.left;
+                let end = margin.right + border.right + padding.right;
+                pieces.push(piece(path, PieceKind::Start, start, false));
+                collect_pieces(
+                    &child.children,
+                    basis,
+                    atomic_width,
+                    path,
+                    after_space,
+                    pieces,
+                );
+                pieces.push(piece(path, PieceKind::End, end, false));
+            }
+            BoxKind::Text(text) => {
+                let white_space = child.style.get("white-space");
+                let processed = process_white_space(text, &child.style, after_space);
+                text_pieces(&processed, child, white_space, path, pieces);
+            }
+            BoxKind::LineBreak => {
+                *after_space = true;
+                pieces.push(piece(path, PieceKind::LineBreak, 0.0, true));
+            }
+            BoxKind::Flow | BoxKind::Replaced { .. } => {
+                *after_space = false;
+                let wraps = !matches!(child.style.get("white-space"), "nowrap" | "pre");
+                pieces.push(piece(path, PieceKind::Atomic, atomic_width(child), wraps));
+            }
+        }
+        path.pop();
+    }
+}
+
+/// Applies the white space processing rules to text: collapsible spaces collapse into one, and newlines either become spaces or are kept as forced line breaks.
+///
+/// [Reference](https://drafts.csswg.org/css-text/#white-space-phase-1)
+pub(crate) fn process_white_space(
+    text: &str,
+    style: &ComputedStyle,
+    after_space: &mut bool,
+) -> String {
+    let white_space = style.get("white-space");
+    let collapses_spaces = matches!(white_space, "normal" | "nowrap" | "pre-line");
+    let keeps_newlines = !matches!(white_space, "normal" | "nowrap");
+    let tab_size = style.get("tab-size").parse::<usize>().unwrap_or(8);
+    let mut processed = String::new();
+    for char in text.chars() {
+        match char {
+            '\n' | '\r' if keeps_newlines => {
+                if collapses_spaces {
+                    processed.truncate(processed.trim_end_matches(' ').len());
+                }
+                processed.push('\n');
+                *after_space = collapses_spaces;
+            }
+            ' ' | '\t' | '\n' | '\r' if collapses_spaces => {
+                if !*after_space {
+                    processed.push(' ');
+                }
+                *after_space = true;
+            }
+            '\t' => processed.push_str(&" ".repeat(tab_size)),
+            '\n' | '\r' => processed.push(' '),
+            char => {
+                processed.push(char);
+                *after_space = false;
+            }
+        }
+    }
+    processed
+}
+
+/// Splits processed text into pieces at its soft wrap opportunities, which are after spaces and around wide characters, and at its forced line breaks.
+fn text_pieces(
+    text: &str,
+    layout_box: &LayoutBox,
+    white_space: &str,
+    path: &[usize],
+    pieces: &mut Vec<Piece>,
+) {
+    let wraps = !matches!(white_space, "nowrap" | "pre");
+    let hangs = !matches!(white_space, "pre" | "break-spaces");
+    let push = |pieces: &mut Vec<Piece>, word: &str, break_after: bool| {
+        if word.is_empty() {
+            return;
+        }
+        let spaces = word.len() - word.trim_end_matches(' ').len();
+        let hang = match hangs {
+            true => layout_box.font.text_width(&word[word.len() - spaces..]),
+            false => 0.0,
+        };
+        pieces.push(Piece {
+            path: path.to_vec(),
+            kind: PieceKind::Text(word.to_owned()),
+            width: layout_box.font.text_width(word),
+            hang,
+            break_after,
+        });
+    };
+    for (index, segment) in text.split('\n').enumerate() {
+        if index > 0 {
+            pieces.push(Piece {
+                path: path.to_vec(),
+                kind: PieceKind::LineBreak,
+                width: 0.0,
+                hang: 0.0,
+                break_after: true,
+            });
+        }
+        if !wraps {
+            push(pieces, segment, false);
+            continue;
+        }
+        let mut start = 0;
+        let mut chars = segment.char_indices().peekable();
+        while let Some((offset, char)) = chars.next() {
+            let next = chars.peek().map(|(_, next)| *next);
+            let end = offset + char.len_utf8();
+            let is_opportunity = match next {
+                Some(next) => {
+                    (char == ' ' && next != ' ')
+                        || (char != ' ' && is_wide(char))
+                        || (next != ' ' && is_wide(next))
+                }
+                None => false,
+            };
+            if is_opportunity {
+                push(pieces, &segment[start..end], true);
+                start = end;
+            }
+        }
+        let ends_with_space = segment.ends_with(' ');
+        push(pieces, &segment[start..], ends_with_space);
+    }
+}
+
+/// Breaks pieces into lines that fit in a width, at the last soft wrap opportunity before the content overflows.
+///
+/// [Reference](https://drafts.csswg.org/css-text/#line-breaking)
+fn break_lines(
+    pieces: &mut Vec<Piece>,
+    layout_box: &LayoutBox,
+    width: f64,
+    indent: f64,
+) -> Vec<Line> {
+    let mut lines = vec![];
+    let mut start = 0;
+    let mut advance = indent;
+    let mut opportunity: Option<usize> = None;
+    let mut index = 0;
+    while index < pieces.len() {
+        let piece = &pieces[index];
+        if piece.kind == PieceKind::LineBreak {
+            lines.push(Line {
+                start,
+                end: index + 1,
+            });
+            start = index + 1;
+            (advance, opportunity) = (0.0, None);
+            index += 1;
+            continue;
+        }
+        let overflows = advance + piece.width - piece.hang > width + 0.001;
+        if overflows && matches!(piece.kind, PieceKind::Text(_) | PieceKind::Atomic) {
+            if let Some(opportunity) = opportunity.filter(|opportunity| *opportunity > start) {
+                lines.push(Line {
+                    start,
+                    end: opportunity,
+                });
+                start = opportunity;
+                index = opportunity;
+                advance = 0.0;
+                continue;
+            }
+            // A word that does not fit on a line of its own can be broken between any two characters, if the style allows it.
+            if let PieceKind::Text(word) = &piece.kind {
+                let text_box = box_at(&layout_box.children, &piece.path);
+                let style = &text_box.style;
+                let breaks_anywhere =
+                    matches!(style.get("overflow-wrap"), "anywhere" | "break-word")
+                        || style.get("word-break") == "break-all";
+                if breaks_anywhere && word.trim_end().chars().count() > 1 {
+                    let characters = split_characters(piece, word, text_box);
+                    pieces.splice(index..=index, characters);
+                    continue;
+                }
+            }
+        }
+        advance += piece.width;
+        if piece.break_after {
+            opportunity = Some(index + 1);
+        }
+        index += 1;
+    }
+    if start < pieces.len() {
+        lines.push(Line {
+            start,
+            end: pieces.len(),
+        });
+    }
+    lines
+}
+
+/// Splits a text piece into one piece for each character, with soft wrap opportunities between them.
+fn split_characters(piece: &Piece, word: &str, text_box: &LayoutBox) -> Vec<Piece> {
+    let trimmed = word.trim_end_matches(' ');
+    let count = trimmed.chars().count();
+    trimmed
+        .chars()
+        .enumerate()
+        .map(|(index, char)| {
+            let is_last = index + 1 == count;
+            let text = match is_last {
+                true => format!("{char}{}", &word[trimmed.len()..]),
+                false => char.to_string(),
+            };
+            Piece {
+                path: piece.path.clone(),
+                width: text_box.font.text_width(&text),
+                hang: if is_last { piece.hang } else { 0.0 },
+                break_after: !is_last || piece.break_after,
+                kind: PieceKind::Text(text),
+            }
+        })
+        .collect()
+}
+
+/// How far a box is raised above the baseline of its parent by `vertical-align`, given the font size of the parent and the ascent and descent of the box.
+///
+/// [Reference](https://drafts.csswg.org/css-inline/#propdef-vertical-align)
+fn baseline_shift(layout_box: &LayoutBox, parent_font_size: f64, ascent: f64, descent: f64) -> f64 {
+    let style = &layout_box.style;
+    let value = style.get("vertical-align");
+    match value {
+        "sub" => -parent_font_size * 0.2,
+        "super" => parent_font_size * 0.34,
+        // The middle of the box is aligned with the middle of the lowercase letters of the parent, which are half an em tall.
+        "middle" => parent_font_size * 0.25 - (ascent - descent) / 2.0,
+        _ => match Size::parse(value) {
+            Size::Px(pixels) => pixels,
+            Size::Percent(percentage) => layout_box.font.line_height(style) * percentage / 100.0,
+            Size::Auto => 0.0,
+        },
+    }
+}
+
+/// The ascent and descent of the strut of an inline box: its font's, plus half the leading given by its line height.
+fn strut(layout_box: &LayoutBox) -> (f64, f64) {
+    let metrics = layout_box.font.metrics();
+    let half_leading = (layout_box.font.line_height(&layout_box.style) - metrics.height()) / 2.0;
+    (
+        metrics.ascent + half_leading,
+        metrics.descent + half_leading,
+    )
+}
+
+/// The ascent and descent of the margin box of an atomic inline, which is aligned by its last baseline or by its bottom margin edge.
+fn atomic_extent(layout_box: &LayoutBox) -> (f64, f64) {
+    let height = layout_box.rect.height + layout_box.margin.vertical();
+    match layout_box.baseline {
+        Some(baseline) => {
+            let ascent = layout_box.margin.top + baseline;
+            (ascent, height - ascent)
+        }
+        None => (height, 0.0),
+    }
+}
+
+/// An inline box that is open on a line.
+struct OpenBox {
+    path: Vec<usize>,
+    /// How far the baseline of the box is above the baseline of the line.
+    shift: f64,
+    font_size: f64,
+}
+
+/// The part of an inline box on a line, with where its pieces start and end on the line.
+struct LineBox {
+    path: Vec<usize>,
+    shift: f64,
+    /// The leftmost edge of its pieces, and the margin that the piece there starts with.
+    left: Option<(f64, f64)>,
+    /// The rightmost edge of its pieces, and the margin that the piece there ends with.
+    right: Option<(f64, f64)>,
+}
+
+/// A part of a piece on a line with a single embedding level, which is what bidirectional reordering moves around.
+struct Segment {
+    /// The index of the piece on the line.
+    index: usize,
+    /// The byte range of the part in the text of the piece.
+    range: Range<usize>,
+    level: Level,
+}
+
+/// Returns the text of the inline content of a block container for the Unicode Bidirectional Algorithm, with the byte range of each piece in it.
+///
+/// Atomic inlines are object replacement characters, and the inline boxes that open embeddings, isolates or overrides with `unicode-bidi` add the formatting characters that do the same.
+///
+/// [Reference](https://drafts.csswg.org/css-writing-modes/#unicode-bidi)
+fn bidi_text(layout_box: &LayoutBox, pieces: &[Piece]) -> (String, Vec<Range<usize>>) {
+    let mut text = String::new();
+    let ranges = pieces
+        .iter()
+        .map(|piece| {
+            let start = text.len();
+            let style = &box_at(&layout_box.children, &piece.path).style;
+            match &piece.kind {
+                PieceKind::Text(word) => text.push_str(word),
+                PieceKind::Atomic => text.push('\u{FFFC}'),
+                PieceKind::LineBreak => text.push('\n'),
+                PieceKind::Start => text.push_str(formatting_characters(style, true)),
+                PieceKind::End => text.push_str(formatting_characters(style, false)),
+                PieceKind::OutOfFlow => {}
+            }
+            start..text.len()
+        })
+        .collect();
+    (text, ranges)
+}
+
+/// Returns the bidirectional formatting characters that the start or the end of an inline box stands for.
+fn formatting_characters(style: &ComputedStyle, is_start: bool) -> &'static str {
+    let is_rtl = style.get("direction") == "rtl";
+    match (style.get("unicode-bidi"), is_start, is_rtl) {
+        ("embed", true, false) => "\u{202A}",
+        ("embed", true, true) => "\u{202B}",
+        ("bidi-override", true, false) => "\u{202D}",
+        ("bidi-override", true, true) => "\u{202E}",
+        ("embed" | "bidi-override", false, _) => "\u{202C}",
+        ("isolate", true, false) => "\u{2066}",
+        ("isolate", true, true) => "\u{2067}",
+        ("plaintext", true, _) => "\u{2068}",
+        ("isolate" | "plaintext", false, _) => "\u{2069}",
+        ("isolate-override", true, false) => "\u{2066}\u{202D}",
+        ("isolate-override", true, true) => "\u{2067}\u{202E}",
+        ("isolate-override", false, _) => "\u{202C}\u{2069}",
+        _ => "",
+    }
+}
+
+/// Splits the pieces of a line into parts with a single embedding level, and returns them in visual order.
+///
+/// [Reference](https://www.unicode.org/reports/tr9/#Reordering_Resolved_Levels)
+fn visual_segments(
+    bidi: &BidiInfo,
+    ranges: &[Range<usize>],
+    line_pieces: &[Piece],
+    line: Line,
+) -> Vec<Segment> {
+    let whole = |index: usize, piece: &Piece| Segment {
+        index,
+        range: match &piece.kind {
+            PieceKind::Text(word) => 0..word.len(),
+            _ => 0..0,
+        },
+        level: Level::ltr(),
+    };
+    let line_range = ranges[line.start].start..ranges[line.end - 1].end;
+    if !bidi.has_rtl() {
+        return line_pieces
+            .iter()
+            .enumerate()
+            .map(|(index, piece)| whole(index, piece))
+            .collect();
+    }
+    let Some(paragraph) = bidi
+        .paragraphs
+        .iter()
+        .rfind(|paragraph| paragraph.range.start <= line_range.start)
+    else {
+        return line_pieces
+            .iter()
+            .enumerate()
+            .map(|(index, piece)| whole(index, piece))
+            .collect();
+    };
+    let levels = bidi.reordered_levels(paragraph, line_range.clone());
+    // Pieces without text take the level of the text they are next to.
+    let level_after = |offset: usize| match offset < line_range.end {
+        true => levels[offset],
+        false => paragraph.level,
+    };
+    let level_before = |offset: usize| match offset > line_range.start {
+        true => levels[offset - 1],
+        false => paragraph.level,
+    };
+    let mut segments = vec![];
+    for (index, piece) in line_pieces.iter().enumerate() {
+        let range = &ranges[line.start + index];
+        match &piece.kind {
+            PieceKind::Text(word) => {
+                for (range, level) in level_runs(word, &levels[range.clone()]) {
+                    segments.push(Segment {
+                        index,
+                        range,
+                        level,
+                    });
+                }
+            }
+            _ if !range.is_empty() => segments.push(Segment {
+                level: levels[range.start],
+                ..whole(index, piece)
+            }),
+            PieceKind::End => segments.push(Segment {
+                level: level_before(range.start),
+                ..whole(index, piece)
+            }),
+            _ => segments.push(Segment {
+                level: level_after(range.start),
+                ..whole(index, piece)
+            }),
+        }
+    }
+    let levels: Vec<Level> = segments.iter().map(|segment| segment.level).collect();
+    let mut segments: Vec<Option<Segment>> = segments.into_iter().map(Some).collect();
+    BidiInfo::reorder_visual(&levels)
+        .into_iter()
+        .filter_map(|index| segments[index].take())
+        .collect()
+}
+
+/// Splits text into runs of characters with the same embedding level, given the level of each of its bytes.
+fn level_runs(text: &str, levels: &[Level]) -> Vec<(Range<usize>, Level)> {
+    let mut runs: Vec<(Range<usize>, Level)> = vec![];
+    for (offset, char) in text.char_indices() {
+        let end = offset + char.len_utf8();
+        match runs.last_mut() {
+            Some((range, level)) if *level == levels[offset] => range.end = end,
+            _ => runs.push((offset..end, levels[offset])),
+        }
+    }
+    runs
+}
+
+/// Returns the parts of a line of text in visual order, with the index of the part that each one comes from, by the Unicode Bidirectional Algorithm in a paragraph of a direction.
+///
+/// Parts are split where the direction of their text changes, and the text that reads right to left is reversed.
+pub(crate) fn reorder_text(parts: &[&str], is_rtl: bool) -> Vec<(usize, String)> {
+    let text = parts.concat();
+    let base_level = match is_rtl {
+        true => Level::rtl(),
+        false => Level::ltr(),
+    };
+    let bidi = BidiInfo::new(&text, Some(base_level));
+    let levels = match bidi.paragraphs.first() {
+        Some(paragraph) if bidi.has_rtl() => bidi.reordered_levels(paragraph, 0..text.len()),
+        _ => {
+            return parts
+                .iter()
+                .enumerate()
+                .map(|(index, part)| (index, (*part).to_owned()))
+                .collect()
+        }
+    };
+    let mut runs = vec![];
+    let mut offset = 0;
+    for (index, part) in parts.iter().enumerate() {
+        for (range, level) in level_runs(part, &levels[offset..offset + part.len()]) {
+            runs.push((index, range, level));
+        }
+        offset += part.len();
+    }
+    let run_levels: Vec<Level> = runs.iter().map(|(_, _, level)| *level).collect();
+    BidiInfo::reorder_visual(&run_levels)
+        .into_iter()
+        .map(|run| {
+            let (index, range, level) = &runs[run];
+            let text = &parts[*index][range.clone()];
+            match level.is_rtl() {
+                true => (*index, reverse_text(text)),
+                false => (*index, text.to_owned()),
+            }
+        })
+        .collect()
+}
+
+/// Returns text that reads right to left in the order it is displayed, with the characters that come in mirrored pairs, like brackets, mirrored.
+///
+/// [Reference](https://www.unicode.org/reports/tr9/#L4)
+fn reverse_text(text: &str) -> String {
+    text.chars()
+        .rev()
+        .map(|char| match char {
+            '(' => ')',
+            ')' => '(',
+            '[' => ']',
+            ']' => '[',
+            '{' => '}',
+            '}' => '{',
+            '<' => '>',
+            '>' => '<',
+            '«' => '»',
+            '»' => '«',
+            char => char,
+        })
+        .collect()
+}
+
+/// Lays out the inline content of a block container in lines. Boxes are positioned relative to the content box of the container.
+///
+/// [Reference](https://drafts.csswg.org/css-inline/#line-box)
+pub(crate) fn layout_lines(layout_box: &mut LayoutBox, containing_block: ContainingBlock) -> Lines {
+    let width = containing_block.width;
+    // Atomic inlines and floats are laid out first, since their sizes are needed to break lines.
+    for_each_box(
+        &mut layout_box.children,
+        &mut vec![],
+        &mut |child, _| match child.kind {
+            BoxKind::Inline => block::resolve_edges(child, width),
+            BoxKind::Flow | BoxKind::Replaced { .. }
+                if child.is_floating()
+                    || (child.inline_level && !child.is_absolutely_positioned()) =>
+            {
+                block::layout_atomic(child, containing_block)
+            }
+            _ => {}
+        },
+    );
+    let mut pieces = pieces(layout_box, width, &|child| {
+        child.rect.width + child.margin.horizontal()
+    });
+    let style = layout_box.style.clone();
+    let indent = Size::parse(style.get("text-indent"))
+        .resolve(Some(width))
+        .unwrap_or(0.0);
+    let lines = break_lines(&mut pieces, layout_box, width, indent);
+    let (bidi_text, ranges) = bidi_text(layout_box, &pieces);
+    let base_level = match (style.get("unicode-bidi"), style.get("direction")) {
+        ("plaintext", _) => None,
+        (_, "rtl") => Some(Level::rtl()),
+        _ => Some(Level::ltr()),
+    };
+    let bidi = BidiInfo::new(&bidi_text, base_level);
+
+    let root_strut = strut(layout_box);
+    let root_font_size = style.font_size();
+    let mut top = 0.0;
+    let mut baseline = None;
+    let mut floats = Floats::default();
+    let mut open: Vec<OpenBox> = vec![];
+    let mut fragments: Vec<(Vec<usize>, Rect)> = vec![];
+    let mut runs: Vec<(Vec<usize>, usize, TextRun)> = vec![];
+    let mut positions: Vec<(Vec<usize>, f64, f64)> = vec![];
+    let mut out_of_flow: Vec<(Vec<usize>, f64, f64)> = vec![];
+    for (line_index, line) in lines.iter().enumerate() {
+        let line_pieces = &pieces[line.start..line.end];
+        let children = &layout_box.children;
+
+        // The height of the line covers the struts of the container and of its open inline boxes, and every atomic inline on it.
+        let has_content = line_pieces
+            .iter()
+            .any(|piece| piece.has_content(box_at(children, &piece.path)));
+        let (mut above, mut below) = root_strut;
+        let on_line = |open: &OpenBox| LineBox {
+            path: open.path.clone(),
+            shift: open.shift,
+            left: None,
+            right: None,
+        };
+        let mut line_boxes: Vec<LineBox> = open.iter().map(on_line).collect();
+        // The shift and font size of the inline box that each piece is in.
+        let mut contexts = vec![];
+        let mut extend = |(ascent, descent): (f64, f64), shift: f64| {
+            above = f64::max(above, ascent + shift);
+            below = f64::max(below, descent - shift);
+        };
+        for open in &open {
+            let child = box_at(children, &open.path);
+            extend(strut(child), open.shift);
+        }
+        let mut edge_aligned = vec![];
+        for piece in line_pieces {
+            let child = box_at(children, &piece.path);
+            let parent_shift = open.last().map_or(0.0, |open| open.shift);
+            let parent_font_size = open.last().map_or(root_font_size, |open| open.font_size);
+            contexts.push((parent_shift, parent_font_size));
+            match piece.kind {
+                PieceKind::Start => {
+                    let (ascent, descent) = strut(child);
+                    let shift =
+                        parent_shift + baseline_shift(child, parent_font_size, ascent, descent);
+                    extend((ascent, descent), shift);
+                    open.push(OpenBox {
+                        path: piece.path.clone(),
+                        shift,
+                        font_size: child.style.font_size(),
+                    });
+                    line_boxes.push(on_line(open.last().unwrap()));
+                }
+                PieceKind::End => {
+                    open.pop();
+                }
+                PieceKind::Atomic => {
+                    let extent = atomic_extent(child);
+                    match child.style.get("vertical-align") {
+                        "top" | "bottom" => edge_aligned.push(extent.0 + extent.1),
+                        _ => {
+                            let shift = parent_shift
+                                + baseline_shift(child, parent_font_size, extent.0, extent.1);
+                            extend(extent, shift);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut height = match has_content {
+            true => above + below,
+            false => 0.0,
+        };
+        for edge_aligned in edge_aligned {
+            height = height.max(edge_aligned);
+        }
+        let line_baseline = top + above.min(height);
+        if has_content {
+            baseline = Some(line_baseline);
+        }
+
+        // Pieces are placed from the start of the line, which is shifted by the alignment of the text.
+        let start = match line_index {
+            0 => indent,
+            _ => 0.0,
+        };
+        let last_content = line_pieces
+            .iter()
+            .rposition(|piece| matches!(piece.kind, PieceKind::Text(_) | PieceKind::Atomic));
+        let content_width: f64 = line_pieces
+            .iter()
+            .enumerate()
+            .map(|(index, piece)| match Some(index) == last_content {
+                true => piece.width - piece.hang,
+                false => piece.width,
+            })
+            .sum();
+        let free = width - start - content_width;
+        let is_last_line = line_index + 1 == lines.len()
+            || line_pieces
+                .last()
+                .is_some_and(|piece| piece.kind == PieceKind::LineBreak);
+        let alignment = match style.get("text-align") {
+            "justify" if is_last_line => style.get("text-align-last"),
+            align => align,
+        };
+        let offset = match (alignment, style.get("direction")) {
+            ("right", _) | ("end", "ltr") | ("start", "rtl") => free,
+            ("center", _) => free / 2.0,
+            ("left", _) | ("start", _) | ("end", _) | ("justify", _) => 0.0,
+            (_, "rtl") => free,
+            _ => 0.0,
+        }
+        .max(0.0);
+        // Pieces are placed in visual order, which differs from their order in the content where text reads right to left.
+        let mut x = start + offset;
+        for segment in visual_segments(&bidi, &ranges, line_pieces, *line) {
+            let index = segment.index;
+            let piece = &line_pieces[index];
+            let child = box_at(children, &piece.path);
+            let (parent_shift, parent_font_size) = contexts[index];
+            let is_last_content = Some(index) == last_content;
+            let mut advance = match is_last_content {
+                true => piece.width - piece.hang,
+                false => piece.width,
+            };
+            match &piece.kind {
+                PieceKind::Start | PieceKind::End | PieceKind::LineBreak => {}
+                PieceKind::Text(text) => {
+                    let ends_piece = segment.range.end == text.len();
+                    if segment.range.len() != text.len() {
+                        advance = child.font.text_width(&text[segment.range.clone()]);
+                        if is_last_content && ends_piece {
+                            advance -= piece.hang;
+                        }
+                    }
+                    let mut text = &text[segment.range.clone()];
+                    if is_last_content && ends_piece {
+                        text = text.trim_end_matches(' ');
+                    }
+                    let text = match segment.level.is_rtl() {
+                        true => reverse_text(text),
+                        false => text.to_owned(),
+                    };
+                    let metrics = child.font.metrics();
+                    let rect = Rect::new(
+                        x,
+                        line_baseline - parent_shift - metrics.ascent,
+                        advance,
+                        metrics.height(),
+                    );
+                    match runs.last_mut() {
+                        Some((path, run_line, run))
+                            if *path == piece.path
+                                && *run_line == line_index
+                                && (run.rect.right() - x).abs() < 0.001 =>
+                        {
+                            run.text.push_str(&text);
+                            run.rect = run.rect.union(&rect);
+                        }
+                        _ => runs.push((piece.path.clone(), line_index, TextRun { text, rect })),
+                    }
+                }
+                PieceKind::Atomic => {
+                    let (ascent, descent) = atomic_extent(child);
+                    let margin_top = match child.style.get("vertical-align") {
+                        "top" => top,
+                        "bottom" => top + height - ascent - descent,
+                        _ => {
+                            line_baseline
+                                - parent_shift
+                                - baseline_shift(child, parent_font_size, ascent, descent)
+                                - ascent
+                        }
+                    };
+                    positions.push((
+                        piece.path.clone(),
+                        x + child.margin.left,
+                        margin_top + child.margin.top,
+                    ));
+                }
+                PieceKind::OutOfFlow => out_of_flow.push((piece.path.clone(), x, top)),
+            }
+            // The margins of an inline box are left out of its fragment where the pieces at its edges are its start or its end.
+            let margins = match piece.kind {
+                PieceKind::Start => (child.margin.left, child.margin.left),
+                PieceKind::End => (child.margin.right, child.margin.right),
+                _ => (0.0, 0.0),
+            };
+            for line_box in &mut line_boxes {
+                if !piece.path.starts_with(&line_box.path) {
+                    continue;
+                }
+                let is_own = piece.path == line_box.path;
+                let (left, right) = match is_own {
+                    true => margins,
+                    false => (0.0, 0.0),
+                };
+                if line_box.left.is_none_or(|(edge, _)| x < edge) {
+                    line_box.left = Some((x, left));
+                }
+                if line_box.right.is_none_or(|(edge, _)| x + advance >= edge) {
+                    line_box.right = Some((x + advance, right));
+                }
+            }
+            x += advance;
+        }
+        // Each inline box has a fragment on the line that covers its pieces, so the ones that continue on the next line are split at the end of this one.
+        for line_box in line_boxes {
+            let (Some((left, left_margin)), Some((right, right_margin))) =
+                (line_box.left, line_box.right)
+            else {
+                continue;
+            };
+            let child = box_at(children, &line_box.path);
+            fragments.push((
+                line_box.path,
+                inline_fragment(
+                    child,
+                    line_baseline - line_box.shift,
+                    left + left_margin,
+                    right - right_margin,
+                ),
+            ));
+        }
+        top += height;
+    }
+    for (path, x, top) in out_of_flow {
+        let child = box_at_mut(&mut layout_box.children, &path);
+        match child.is_floating() {
+            true => floats.place(child, width, top),
+            false => child.static_position = (x, top),
+        }
+    }
+    for (path, rect) in fragments {
+        box_at_mut(&mut layout_box.children, &path)
+            .fragments
+            .push(rect);
+    }
+    for (path, _, run) in runs {
+        box_at_mut(&mut layout_box.children, &path)
+            .text_runs
+            .push(run);
+    }
+    for (path, x, y) in positions {
+        let child = box_at_mut(&mut layout_box.children, &path);
+        child.rect.x = x;
+        child.rect.y = y;
+    }
+    Lines {
+        height: top.max(floats.bottom()),
+        baseline,
+    }
+}
+
+/// Returns the border box of an inline box on a line, from the baseline of the box and where it starts and ends on the line.
+fn inline_fragment(layout_box: &LayoutBox, baseline: f64, start: f64, end: f64) -> Rect {
+    let metrics = layout_box.font.metrics();
+    let top = baseline - metrics.ascent - layout_box.padding.top - layout_box.border.top;
+    Rect::new(
+        start,
+        top,
+        (end - start).max(0.0),
+        metrics.height() + layout_box.padding.vertical() + layout_box.border.vertical(),
+    )
+}
+
+/// Returns the min-content and max-content widths of the inline content of a block container.
+///
+/// The min-content width is the widest piece that cannot be broken, and the max-content width is the widest line when lines only break where they are forced to.
+pub(crate) fn intrinsic_widths(
+    layout_box: &LayoutBox,
+    containing_block: ContainingBlock,
+) -> (f64, f64) {
+    let indent = Size::parse(layout_box.style.get("text-indent"))
+        .resolve(Some(0.0))
+        .unwrap_or(0.0);
+    let min_pieces = pieces(layout_box, 0.0, &|child| {
+        block::contribution(child, containing_block).0
+    });
+    let max_pieces = pieces(layout_box, 0.0, &|child| {
+        block::contribution(child, containing_block).1
+    });
+    let mut min: f64 = 0.0;
+    let mut word = indent;
+    for piece in &min_pieces {
+        word += piece.width;
+        if piece.break_after {
+            min = min.max(word - piece.hang);
+            word = 0.0;
+        }
+    }
+    min = min.max(word);
+    let mut max: f64 = 0.0;
+    let mut line = indent;
+    let mut hang = 0.0;
+    for piece in &max_pieces {
+        if piece.kind == PieceKind::LineBreak {
+            max = max.max(line - hang);
+            (line, hang) = (0.0, 0.0);
+            continue;
+        }
+        line += piece.width;
+        if matches!(piece.kind, PieceKind::Text(_) | PieceKind::Atomic) {
+            hang = piece.hang;
+        }
+    }
+    max = max.max(line - hang);
+    (min, max)
+}
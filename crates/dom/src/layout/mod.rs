@@ -0,0 +1,477 @@
+//! Layout: the box tree generated from the flat tree of a document, and the sizes and positions of its boxes.
+//!
+//! A layout is computed when the geometry of an element is first asked for, and kept on the document until the document tree, its styles, the viewport, a scroll position or an animation changes.
+//!
+//! [Reference](https://drafts.csswg.org/css-display/#box-generation)
+mod block;
+mod box_tree;
+mod flex;
+mod grid;
+mod inline;
+mod rendered_text;
+mod stacking;
+mod text;
+mod transform;
+
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    animation,
+    css::{px_value, ComputedStyle, StyleResolver},
+    document::WeakDocumentRef,
+    domitem::DOMItem,
+    node::NodeBase,
+    AsElement, AsNode, AsParentNode, Document, Element, Node, Viewport,
+};
+
+pub(crate) use box_tree::{BoxKind, LayoutBox};
+pub(crate) use inline::{process_white_space, reorder_text};
+pub(crate) use rendered_text::rendered_text;
+pub(crate) use stacking::{hit_test, paint_order, Clip, Scope};
+pub(crate) use text::Font;
+pub use transform::Transform;
+
+/// Marks the cached layout of the document of a node as out of date. This is called on every change that could affect layout, like a mutation of the tree or a change to the state of an element.
+pub(crate) fn invalidate(node: &Node) {
+    invalidate_document(&node.base().owner_document);
+}
+
+/// Marks the cached layout of a document as out of date, for changes that are not tied to a node, like a change to one of the document's style sheets. Nothing happens if the document is gone.
+pub(crate) fn invalidate_document(document: &WeakDocumentRef) {
+    if let Some(document) = document.inner.upgrade() {
+        document.borrow_mut().generation += 1;
+    }
+}
+
+/// A rectangle in CSS pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub(crate) fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub(crate) fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub(crate) fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub(crate) fn translate(&mut self, x: f64, y: f64) {
+        self.x += x;
+        self.y += y;
+    }
+
+    /// Returns true if the rectangles overlap.
+    pub(crate) fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+
+    /// Returns true if a point is inside the rectangle, counting its top and left edges but not its bottom and right edges.
+    pub(crate) fn contains(&self, (x, y): (f64, f64)) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Returns the smallest rectangle that contains both rectangles.
+    pub(crate) fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect::new(
+            x,
+            y,
+            self.right().max(other.right()) - x,
+            self.bottom().max(other.bottom()) - y,
+        )
+    }
+
+    /// Returns the area that the rectangles share, which is empty if they do not overlap.
+    pub(crate) fn intersection(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Rect::new(
+            x,
+            y,
+            (self.right().min(other.right()) - x).max(0.0),
+            (self.bottom().min(other.bottom()) - y).max(0.0),
+        )
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+
+    /// Returns the rectangle with its edges moved inwards.
+    pub(crate) fn shrink(&self, sides: &Sides) -> Rect {
+        Rect::new(
+            self.x + sides.left,
+            self.y + sides.top,
+            (self.width - sides.horizontal()).max(0.0),
+            (self.height - sides.vertical()).max(0.0),
+        )
+    }
+}
+
+/// The widths of the four sides of a margin, border or padding.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Sides {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Sides {
+    pub(crate) fn horizontal(&self) -> f64 {
+        self.left + self.right
+    }
+
+    pub(crate) fn vertical(&self) -> f64 {
+        self.top + self.bottom
+    }
+
+    /// Resolves the sides of a property like `padding`, with percentages of the containing block's width. `auto` is zero.
+    fn resolve(style: &ComputedStyle, property: &str, suffix: &str, basis: f64) -> Self {
+        let side = |side: &str| {
+            let value = style.get(&format!("{property}-{side}{suffix}"));
+            Size::parse(value).resolve(Some(basis)).unwrap_or(0.0)
+        };
+        Sides {
+            top: side("top"),
+            right: side("right"),
+            bottom: side("bottom"),
+            left: side("left"),
+        }
+    }
+
+    pub(crate) fn margin(style: &ComputedStyle, basis: f64) -> Self {
+        Sides::resolve(style, "margin", "", basis)
+    }
+
+    pub(crate) fn padding(style: &ComputedStyle, basis: f64) -> Self {
+        Sides::resolve(style, "padding", "", basis).max(0.0)
+    }
+
+    pub(crate) fn border(style: &ComputedStyle) -> Self {
+        Sides::resolve(style, "border", "-width", 0.0).max(0.0)
+    }
+
+    fn max(self, minimum: f64) -> Self {
+        Sides {
+            top: self.top.max(minimum),
+            right: self.right.max(minimum),
+            bottom: self.bottom.max(minimum),
+            left: self.left.max(minimum),
+        }
+    }
+}
+
+/// A computed size: a length, a percentage, or a keyword like `auto`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Size {
+    Auto,
+    Px(f64),
+    Percent(f64),
+}
+
+impl Size {
+    pub(crate) fn parse(value: &str) -> Self {
+        if let Some(pixels) = px_value(value) {
+            return Size::Px(pixels);
+        }
+        if let Some(percentage) = value.strip_suffix('%').and_then(|value| value.parse().ok()) {
+            return Size::Percent(percentage);
+        }
+        match value.parse::<f64>() {
+            Ok(0.0) => Size::Px(0.0),
+            _ => Size::Auto,
+        }
+    }
+
+    /// Resolves the size against a basis for percentages. Returns `None` for `auto`, and for percentages without a basis.
+    pub(crate) fn resolve(self, basis: Option<f64>) -> Option<f64> {
+        match self {
+            Size::Auto => None,
+            Size::Px(pixels) => Some(pixels),
+            Size::Percent(percentage) => basis.map(|basis| basis * percentage / 100.0),
+        }
+    }
+}
+
+/// Resolves a gap between items, like `row-gap`. `normal` is zero.
+pub(crate) fn gap(style: &ComputedStyle, property: &str, basis: Option<f64>) -> f64 {
+    Size::parse(style.get(property))
+        .resolve(basis)
+        .unwrap_or(0.0)
+        .max(0.0)
+}
+
+/// Returns the keyword of an alignment value like `safe center` or `first baseline`, without its modifier.
+pub(crate) fn alignment(value: &str) -> &str {
+    value.rsplit(' ').next().unwrap_or(value)
+}
+
+/// Distributes the free space around a number of boxes for a value of `justify-content` or `align-content`, and returns the space before the first box and the space between two boxes.
+///
+/// [Reference](https://drafts.csswg.org/css-align/#distribution-values)
+pub(crate) fn distribute(value: &str, free: f64, count: usize) -> (f64, f64) {
+    let count = count as f64;
+    match alignment(value) {
+        "end" | "flex-end" | "right" => (free, 0.0),
+        "center" => (free / 2.0, 0.0),
+        "space-between" if count > 1.0 && free > 0.0 => (0.0, free / (count - 1.0)),
+        "space-around" if free > 0.0 => (free / count / 2.0, free / count),
+        "space-evenly" if free > 0.0 => (free / (count + 1.0), free / (count + 1.0)),
+        // Space cannot be distributed when there is none, so the boxes overflow equally on both sides.
+        "space-around" | "space-evenly" => (free / 2.0, 0.0),
+        _ => (0.0, 0.0),
+    }
+}
+
+/// The geometry of an element after layout and scrolling, relative to the viewport.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ElementGeometry {
+    /// The border boxes of the element: one for a block-level or atomic box, or one for each line an inline box is on.
+    pub fragments: Vec<Rect>,
+    pub border: Sides,
+    pub padding: Sides,
+    /// The size of the padding box, or of the viewport for the root element. Zero for inline boxes, which have no client area.
+    pub client_width: f64,
+    pub client_height: f64,
+    /// The size of the area that can be scrolled to, which covers the client area and the content that overflows it.
+    pub scroll_width: f64,
+    pub scroll_height: f64,
+    /// Whether the element generates a non-replaced inline box.
+    pub is_inline: bool,
+    /// Whether the element can be scrolled: a scroll container, or the root element, which scrolls the viewport.
+    pub is_scroll_container: bool,
+}
+
+impl ElementGeometry {
+    /// Returns the furthest the element can be scrolled to the right and down.
+    pub(crate) fn scroll_range(&self) -> (f64, f64) {
+        match self.is_scroll_container {
+            true => (
+                (self.scroll_width - self.client_width).max(0.0),
+                (self.scroll_height - self.client_height).max(0.0),
+            ),
+            false => (0.0, 0.0),
+        }
+    }
+
+    /// Clamps a scroll position to the scroll range of the element.
+    pub(crate) fn clamp_scroll(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (width, height) = self.scroll_range();
+        (x.clamp(0.0, width), y.clamp(0.0, height))
+    }
+}
+
+/// The result of laying out a document.
+pub(crate) struct Layout {
+    pub(crate) root: Option<LayoutBox>,
+    pub(crate) viewport: Viewport,
+    elements: HashMap<*mut NodeBase, ElementGeometry>,
+}
+
+impl Layout {
+    /// Returns the layout of a document in its viewport, which is only computed again if something changed since the last time.
+    pub(crate) fn of(document: &Document) -> Rc<Self> {
+        let cached = document.state().layout.clone();
+        if let Some((generation, layout)) = cached {
+            if generation == document.state().generation {
+                return layout;
+            }
+        }
+        let layout = Rc::new(Layout::compute(document));
+        // Computing the styles can start transitions, so the layout is current as of after it was computed.
+        let mut state = document.state_mut();
+        state.layout = Some((state.generation, layout.clone()));
+        layout
+    }
+
+    /// Lays out a document in its viewport.
+    fn compute(document: &Document) -> Self {
+        let viewport = document.state().viewport.clone();
+        let resolver = animation::style_resolver(document);
+        let fonts = document.state().font_provider.clone();
+        let mut root = document
+            .first_element_child()
+            .and_then(|element| box_tree::build(&element, &resolver, &fonts));
+        if let Some(root) = root.as_mut() {
+            block::layout_root(root, &viewport);
+        }
+        let mut elements = HashMap::new();
+        if let Some(root) = root.as_mut() {
+            collect_geometry(root, &mut elements);
+            let mut scroll_position = (0.0, 0.0);
+            if let Some(element) = &root.element {
+                if let Some(geometry) = elements.get_mut(&AsNode::cast(element).get_base_ptr()) {
+                    geometry.client_width = viewport.width;
+                    geometry.client_height = viewport.height;
+                    geometry.scroll_width = geometry.scroll_width.max(viewport.width);
+                    geometry.scroll_height = geometry.scroll_height.max(viewport.height);
+                    geometry.is_scroll_container = true;
+                    scroll_position = geometry.clamp_scroll(element.base().scroll_position);
+                }
+            }
+            // Scrolling the viewport moves the root element along with everything else.
+            apply_scroll_positions(root, &mut elements, scroll_position, true);
+        }
+        Layout {
+            root,
+            viewport,
+            elements,
+        }
+    }
+
+    /// Returns the geometry of an element, or `None` if it does not generate any boxes.
+    pub(crate) fn geometry(&self, element: &Element) -> Option<&ElementGeometry> {
+        self.elements.get(&AsNode::cast(element).get_base_ptr())
+    }
+
+    /// Returns the boxes from the root box down to the first box of an element, or `None` if it does not generate any boxes.
+    pub(crate) fn path_to(&self, element: &Element) -> Option<Vec<&LayoutBox>> {
+        fn search<'a>(
+            layout_box: &'a LayoutBox,
+            element: &Element,
+            path: &mut Vec<&'a LayoutBox>,
+        ) -> bool {
+            path.push(layout_box);
+            if layout_box.element.as_ref() == Some(element)
+                || layout_box
+                    .children
+                    .iter()
+                    .any(|child| search(child, element, path))
+            {
+                return true;
+            }
+            path.pop();
+            false
+        }
+        let mut path = vec![];
+        search(self.root.as_ref()?, element, &mut path).then_some(path)
+    }
+}
+
+/// Lays out the document of an element, and returns the element's geometry.
+pub(crate) fn geometry_of(element: &impl AsElement) -> Option<ElementGeometry> {
+    let element = AsElement::cast(element);
+    if !element.is_connected() {
+        return None;
+    }
+    let document = element.owner_document()?;
+    Layout::of(&document).geometry(element).cloned()
+}
+
+/// Records the geometry of every element in a box tree, and returns the bounds of the box and the content that overflows it.
+fn collect_geometry(
+    layout_box: &LayoutBox,
+    elements: &mut HashMap<*mut NodeBase, ElementGeometry>,
+) -> Option<Rect> {
+    let own = match &layout_box.kind {
+        BoxKind::Inline => layout_box
+            .fragments
+            .iter()
+            .copied()
+            .reduce(|bounds, fragment| bounds.union(&fragment)),
+        BoxKind::Text(_) => layout_box
+            .text_runs
+            .iter()
+            .map(|run| run.rect)
+            .reduce(|bounds, rect| bounds.union(&rect)),
+        BoxKind::LineBreak => None,
+        _ => Some(layout_box.rect),
+    };
+    let descendants = layout_box
+        .children
+        .iter()
+        .filter_map(|child| collect_geometry(child, elements))
+        .reduce(|bounds, rect| bounds.union(&rect));
+    if let Some(element) = &layout_box.element {
+        let is_inline = matches!(layout_box.kind, BoxKind::Inline);
+        let fragments = match is_inline {
+            true => layout_box.fragments.clone(),
+            false => vec![layout_box.rect],
+        };
+        let padding_box = layout_box.rect.shrink(&layout_box.border);
+        let (client_width, client_height) = match is_inline {
+            true => (0.0, 0.0),
+            false => (padding_box.width, padding_box.height),
+        };
+        let scrollable = match descendants {
+            Some(descendants) if !is_inline => padding_box.union(&descendants),
+            _ => padding_box,
+        };
+        elements.insert(
+            AsNode::cast(element).get_base_ptr(),
+            ElementGeometry {
+                fragments,
+                border: layout_box.border,
+                padding: layout_box.padding,
+                client_width,
+                client_height,
+                scroll_width: (scrollable.right() - padding_box.x).max(client_width),
+                scroll_height: (scrollable.bottom() - padding_box.y).max(client_height),
+                is_inline,
+                is_scroll_container: layout_box.is_scroll_container(),
+            },
+        );
+    }
+    // Content that overflows a box that clips it does not make its ancestors overflow.
+    let clips = layout_box.style.get("overflow-x") != "visible"
+        || layout_box.style.get("overflow-y") != "visible";
+    match (own, descendants) {
+        (Some(own), _) if clips => Some(own),
+        (Some(own), Some(descendants)) => Some(own.union(&descendants)),
+        (own, descendants) => own.or(descendants),
+    }
+}
+
+/// Moves the boxes inside scroll containers by the scroll positions of the containers, so that they are where they are seen. `offset` is how far the box has been scrolled by its ancestors.
+///
+/// Fixed position boxes are positioned relative to the viewport, so they are not moved by any scrolling.
+fn apply_scroll_positions(
+    layout_box: &mut LayoutBox,
+    elements: &mut HashMap<*mut NodeBase, ElementGeometry>,
+    offset: (f64, f64),
+    is_root: bool,
+) {
+    let offset = match layout_box.style.get("position") {
+        "fixed" => (0.0, 0.0),
+        _ => offset,
+    };
+    layout_box.translate(-offset.0, -offset.1);
+    let mut inner = offset;
+    if let Some(geometry) = layout_box
+        .element
+        .as_ref()
+        .and_then(|element| elements.get_mut(&AsNode::cast(element).get_base_ptr()))
+    {
+        for fragment in geometry.fragments.iter_mut() {
+            fragment.translate(-offset.0, -offset.1);
+        }
+        if !is_root && geometry.is_scroll_container {
+            let element = layout_box.element.as_ref().unwrap();
+            let (x, y) = geometry.clamp_scroll(element.base().scroll_position);
+            inner = (offset.0 + x, offset.1 + y);
+        }
+    }
+    for child in layout_box.children.iter_mut() {
+        apply_scroll_positions(child, elements, inner, false);
+    }
+}
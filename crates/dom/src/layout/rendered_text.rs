@@ -0,0 +1,157 @@
+//! The rendered text of elements: their text as it is laid out, with collapsed white space, and with line breaks between blocks and at `<br>` elements.
+//!
+//! [Reference](https://html.spec.whatwg.org/multipage/dom.html#rendered-text-collection-steps)
+use super::{inline::process_white_space, BoxKind, Layout, LayoutBox};
+use crate::{domitem::DOMItem, tag::Tag, AsNode, Element};
+
+/// A part of the rendered text of an element.
+enum Item {
+    Text(String),
+    /// A number of line breaks that separate the text before from the text after, if there is text on both sides.
+    RequiredLineBreaks(usize),
+}
+
+/// Returns the rendered text of an element, or `None` if it is not being rendered.
+pub(crate) fn rendered_text(element: &Element) -> Option<String> {
+    let document = element
+        .owner_document()
+        .filter(|_| element.is_connected())?;
+    let layout = Layout::of(&document);
+    let path = layout.path_to(element)?;
+    let mut collector = Collector {
+        items: vec![],
+        after_space: true,
+        at_line_start: true,
+    };
+    collector.children(path[path.len() - 1]);
+    collector.end_line();
+    Some(collector.finish())
+}
+
+struct Collector {
+    items: Vec<Item>,
+    /// Whether the last character was a collapsible space, or the line has just started, so that the next spaces collapse.
+    after_space: bool,
+    /// Whether no text has been collected since the last line ended.
+    at_line_start: bool,
+}
+
+impl Collector {
+    fn children(&mut self, layout_box: &LayoutBox) {
+        for (index, child) in layout_box.children.iter().enumerate() {
+            if !child.is_generated {
+                self.collect(child, &layout_box.children[index + 1..]);
+            }
+        }
+    }
+
+    /// Collects the rendered text of a box, given the boxes that follow it in its parent.
+    fn collect(&mut self, layout_box: &LayoutBox, following: &[LayoutBox]) {
+        let is_visible = layout_box.style.get("visibility") == "visible";
+        match &layout_box.kind {
+            BoxKind::Text(text) => {
+                let text = process_white_space(text, &layout_box.style, &mut self.after_space);
+                if is_visible && !text.is_empty() {
+                    self.items.push(Item::Text(text));
+                    self.at_line_start = false;
+                }
+                return;
+            }
+            BoxKind::LineBreak => {
+                self.end_line();
+                if is_visible {
+                    self.items.push(Item::Text(String::from("\n")));
+                }
+                return;
+            }
+            _ => {}
+        }
+        let display = layout_box.style.get("display");
+        let is_paragraph = layout_box
+            .element
+            .as_ref()
+            .is_some_and(|element| matches!(element.base().tag, Tag::P));
+        let line_breaks = match is_visible {
+            true if is_paragraph => 2,
+            true if !layout_box.inline_level
+                && (!display.starts_with("table-") || display == "table-caption") =>
+            {
+                1
+            }
+            _ => 0,
+        };
+        // The contents of inline-blocks are on lines of their own, inside the line the inline-block is on.
+        let is_atomic = layout_box.is_atomic_inline();
+        if is_atomic {
+            self.after_space = true;
+            self.at_line_start = true;
+        }
+        if line_breaks > 0 {
+            self.end_line();
+            self.items.push(Item::RequiredLineBreaks(line_breaks));
+        }
+        self.children(layout_box);
+        if line_breaks > 0 || is_atomic {
+            self.end_line();
+        }
+        let is_followed_by = |display: &str| {
+            following
+                .iter()
+                .any(|layout_box| layout_box.style.get("display") == display)
+        };
+        match display {
+            "table-cell" if is_visible && is_followed_by("table-cell") => {
+                self.items.push(Item::Text(String::from("\t")));
+            }
+            "table-row" if is_visible && is_followed_by("table-row") => {
+                self.items.push(Item::Text(String::from("\n")));
+            }
+            _ => {}
+        }
+        if line_breaks > 0 {
+            self.items.push(Item::RequiredLineBreaks(line_breaks));
+        }
+        if is_atomic {
+            self.after_space = false;
+            self.at_line_start = false;
+        }
+    }
+
+    /// Ends a line, removing the collapsible space at its end.
+    fn end_line(&mut self) {
+        if self.after_space && !self.at_line_start {
+            let last = self
+                .items
+                .iter_mut()
+                .rev()
+                .find(|item| matches!(item, Item::Text(_)));
+            if let Some(Item::Text(text)) = last {
+                if text.ends_with(' ') {
+                    text.pop();
+                }
+            }
+        }
+        self.after_space = true;
+        self.at_line_start = true;
+    }
+
+    /// Joins the items, with each run of required line breaks between text replaced by the largest number of line breaks in it.
+    fn finish(self) -> String {
+        let mut text = String::new();
+        let mut line_breaks = 0;
+        for item in self.items {
+            match item {
+                Item::Text(item) if item.is_empty() => {}
+                Item::Text(item) => {
+                    if !text.is_empty() {
+                        text.push_str(&"\n".repeat(line_breaks));
+                    }
+                    line_breaks = 0;
+                    text.push_str(&item);
+                }
+                Item::RequiredLineBreaks(count) => line_breaks = line_breaks.max(count),
+            }
+        }
+        text
+    }
+}
@@ -0,0 +1,264 @@
+//! The painting order of boxes, and hit testing, which finds the boxes under a point from the one painted on top down.
+//!
+//! Boxes are painted by stacking context: each stacking context paints its negative `z-index` layers, then its in-flow blocks, floats and inline content, and then its positioned descendants and positive `z-index` layers.
+//!
+//! [Reference](https://drafts.csswg.org/css2/#elaborate-stacking-contexts)
+use super::{BoxKind, Layout, LayoutBox, Rect, Transform};
+use crate::Element;
+
+/// A clip of an ancestor that is painted with a box: the padding box of the ancestor, in the coordinates of the ancestor.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Clip {
+    pub rect: Rect,
+    /// The transform of the ancestor, which maps its coordinates to the viewport.
+    pub transform: Transform,
+}
+
+impl Clip {
+    pub(crate) fn contains(&self, point: (f64, f64)) -> bool {
+        self.transform
+            .inverse()
+            .is_some_and(|inverse| self.rect.contains(inverse.apply(point)))
+    }
+}
+
+/// Where a box is painted: how it is transformed, and what it is clipped by.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Scope<'a> {
+    /// The element that the box belongs to: the element that generated it, or the nearest ancestor element for text, anonymous boxes and pseudo-elements.
+    pub element: Option<&'a Element>,
+    /// Maps the coordinates of the box to the viewport.
+    pub transform: Transform,
+    pub clips: Vec<Clip>,
+    /// The stacking contexts with an `opacity` below one that the box is painted in, from the outermost in, including the box itself. Each of them is painted as a group, which is made transparent as a whole.
+    pub groups: Vec<&'a LayoutBox>,
+    /// The clips that apply to the absolutely positioned boxes inside the box, which are the clips of their containing block.
+    absolute_clips: Vec<Clip>,
+    /// The clips that apply to the fixed position boxes inside the box: none, unless a transformed ancestor is their containing block.
+    fixed_clips: Vec<Clip>,
+}
+
+impl<'a> Scope<'a> {
+    /// Returns the scope of a box, given the scope of the contents of its parent.
+    fn enter(&self, layout_box: &'a LayoutBox) -> Scope<'a> {
+        let clips = match layout_box.style.get("position") {
+            "absolute" => &self.absolute_clips,
+            "fixed" => &self.fixed_clips,
+            _ => &self.clips,
+        };
+        let transform = match Transform::of(layout_box) {
+            Some(transform) => self.transform.multiply(&transform),
+            None => self.transform,
+        };
+        let mut groups = self.groups.clone();
+        if layout_box.is_stacking_context() && layout_box.opacity() < 1.0 {
+            groups.push(layout_box);
+        }
+        Scope {
+            element: layout_box.element.as_ref().or(self.element),
+            transform,
+            clips: clips.clone(),
+            groups,
+            absolute_clips: self.absolute_clips.clone(),
+            fixed_clips: self.fixed_clips.clone(),
+        }
+    }
+
+    /// Returns the scope of the contents of a box, given the scope of the box.
+    fn contents(&self, layout_box: &LayoutBox, is_root: bool) -> Scope<'a> {
+        let mut scope = self.clone();
+        // The root element does not clip: its overflow applies to the viewport.
+        if layout_box.clips_contents() && !is_root {
+            scope.clips.push(Clip {
+                rect: layout_box.rect.shrink(&layout_box.border),
+                transform: self.transform,
+            });
+        }
+        let is_transformed = Transform::of(layout_box).is_some();
+        if layout_box.is_positioned() || is_transformed {
+            scope.absolute_clips = scope.clips.clone();
+        }
+        if is_transformed {
+            scope.fixed_clips = scope.clips.clone();
+        }
+        scope
+    }
+}
+
+/// A box that is painted as its own layer in a stacking context: a positioned box, or a box that creates a stacking context.
+struct Layer<'a> {
+    layout_box: &'a LayoutBox,
+    scope: Scope<'a>,
+    z_index: i32,
+}
+
+fn is_layer(layout_box: &LayoutBox) -> bool {
+    !matches!(layout_box.kind, BoxKind::Text(_) | BoxKind::LineBreak)
+        && (layout_box.is_positioned() || layout_box.is_stacking_context())
+}
+
+/// Returns the boxes of a layout in the order they are painted, from the bottom up, with where they are painted.
+pub(crate) fn paint_order(layout: &Layout) -> Vec<(&LayoutBox, Scope<'_>)> {
+    let mut painted = vec![];
+    if let Some(root) = &layout.root {
+        let scope = Scope::default().enter(root);
+        PaintOrder {
+            root,
+            painted: &mut painted,
+        }
+        .stacking_context(root, scope);
+    }
+    painted
+}
+
+struct PaintOrder<'a, 'b> {
+    root: &'a LayoutBox,
+    painted: &'b mut Vec<(&'a LayoutBox, Scope<'a>)>,
+}
+
+impl<'a> PaintOrder<'a, '_> {
+    /// Paints a stacking context, with the layers of its descendants stacked by `z-index`.
+    fn stacking_context(&mut self, layout_box: &'a LayoutBox, scope: Scope<'a>) {
+        let contents = scope.contents(layout_box, std::ptr::eq(layout_box, self.root));
+        let mut layers = vec![];
+        collect_layers(layout_box, &contents, &mut layers);
+        // The sort is stable, so layers with the same `z-index` stay in tree order.
+        layers.sort_by_key(|layer| layer.z_index);
+        self.painted.push((layout_box, scope));
+        let (negative, layers): (Vec<_>, Vec<_>) =
+            layers.into_iter().partition(|layer| layer.z_index < 0);
+        for layer in negative {
+            self.layer(layer);
+        }
+        self.contents(layout_box, contents);
+        for layer in layers {
+            self.layer(layer);
+        }
+    }
+
+    fn layer(&mut self, layer: Layer<'a>) {
+        match layer.layout_box.is_stacking_context() {
+            true => self.stacking_context(layer.layout_box, layer.scope),
+            // Positioned boxes with a `z-index` of `auto` are painted as if they were stacking contexts, except that their own layers belong to the parent stacking context.
+            false => self.flow(layer.layout_box, layer.scope),
+        }
+    }
+
+    /// Paints a box and its contents that are not layers.
+    fn flow(&mut self, layout_box: &'a LayoutBox, scope: Scope<'a>) {
+        let contents = scope.contents(layout_box, false);
+        self.painted.push((layout_box, scope));
+        self.contents(layout_box, contents);
+    }
+
+    /// Paints the contents of a box that are not layers: its in-flow blocks, then its floats, then its inline content.
+    fn contents(&mut self, layout_box: &'a LayoutBox, scope: Scope<'a>) {
+        let mut floats = vec![];
+        let mut inlines = vec![];
+        self.blocks(layout_box, &scope, &mut floats, &mut inlines);
+        // Floats and atomic inlines are painted as if they were stacking contexts.
+        for (layout_box, scope) in floats {
+            self.flow(layout_box, scope);
+        }
+        for (layout_box, scope) in inlines {
+            match layout_box.is_atomic_inline() {
+                true => self.flow(layout_box, scope),
+                false => self.painted.push((layout_box, scope)),
+            }
+        }
+    }
+
+    fn blocks(
+        &mut self,
+        layout_box: &'a LayoutBox,
+        scope: &Scope<'a>,
+        floats: &mut Vec<(&'a LayoutBox, Scope<'a>)>,
+        inlines: &mut Vec<(&'a LayoutBox, Scope<'a>)>,
+    ) {
+        for child in &layout_box.children {
+            if is_layer(child) {
+                continue;
+            }
+            let child_scope = scope.enter(child);
+            if child.is_floating() {
+                floats.push((child, child_scope));
+            } else if child.inline_level {
+                let is_atomic = child.is_atomic_inline();
+                let contents = child_scope.contents(child, false);
+                inlines.push((child, child_scope));
+                if !is_atomic {
+                    self.blocks(child, &contents, floats, inlines);
+                }
+            } else {
+                let contents = child_scope.contents(child, false);
+                self.painted.push((child, child_scope));
+                self.blocks(child, &contents, floats, inlines);
+            }
+        }
+    }
+}
+
+/// Collects the layers of a stacking context in tree order, including the layers inside positioned boxes that do not create stacking contexts.
+fn collect_layers<'a>(layout_box: &'a LayoutBox, scope: &Scope<'a>, layers: &mut Vec<Layer<'a>>) {
+    for child in &layout_box.children {
+        let child_scope = scope.enter(child);
+        let contents = child_scope.contents(child, false);
+        if is_layer(child) {
+            layers.push(Layer {
+                layout_box: child,
+                scope: child_scope,
+                z_index: child
+                    .z_index()
+                    .filter(|_| child.is_stacking_context())
+                    .unwrap_or(0),
+            });
+            if child.is_stacking_context() {
+                continue;
+            }
+        }
+        collect_layers(child, &contents, layers);
+    }
+}
+
+/// Returns true if a point in the viewport is on a box that can be the target of hit testing, inside the clips of its ancestors.
+fn hits(layout_box: &LayoutBox, scope: &Scope, point: (f64, f64)) -> bool {
+    let style = &layout_box.style;
+    if style.get("visibility") != "visible" || style.get("pointer-events") == "none" {
+        return false;
+    }
+    let Some(inverse) = scope.transform.inverse() else {
+        return false;
+    };
+    if !scope.clips.iter().all(|clip| clip.contains(point)) {
+        return false;
+    }
+    let local = inverse.apply(point);
+    match &layout_box.kind {
+        BoxKind::Flow | BoxKind::Replaced { .. } => layout_box.rect.contains(local),
+        BoxKind::Inline => layout_box
+            .fragments
+            .iter()
+            .any(|fragment| fragment.contains(local)),
+        BoxKind::Text(_) => layout_box
+            .text_runs
+            .iter()
+            .any(|run| run.rect.contains(local)),
+        BoxKind::LineBreak => false,
+    }
+}
+
+/// Returns the elements of the boxes under a point in the viewport, from the one painted on top down, without repeating elements.
+///
+/// [Reference](https://drafts.csswg.org/cssom-view/#dom-document-elementsfrompoint)
+pub(crate) fn hit_test(layout: &Layout, point: (f64, f64)) -> Vec<&Element> {
+    let mut elements: Vec<&Element> = vec![];
+    for (layout_box, scope) in paint_order(layout).into_iter().rev() {
+        let Some(element) = scope.element else {
+            continue;
+        };
+        if hits(layout_box, &scope, point) && !elements.contains(&element) {
+            elements.push(element);
+        }
+    }
+    elements
+}
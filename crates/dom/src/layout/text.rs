@@ -0,0 +1,85 @@
+//! The measurement of text.
+//!
+//! Text is measured with the document's [`FontProvider`], which by default gives every font the same fixed metrics.
+use std::{fmt, rc::Rc};
+
+use crate::{
+    css::{px_value, ComputedStyle},
+    FontDescription, FontMetrics, FontProvider,
+};
+
+/// The font of a box: the font it describes, measured by the document's provider, and the spacing added between its letters and words.
+#[derive(Clone)]
+pub(crate) struct Font {
+    provider: Rc<dyn FontProvider>,
+    description: FontDescription,
+    letter_spacing: f64,
+    word_spacing: f64,
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font")
+            .field("description", &self.description)
+            .field("letter_spacing", &self.letter_spacing)
+            .field("word_spacing", &self.word_spacing)
+            .finish()
+    }
+}
+
+impl Font {
+    pub(crate) fn new(provider: &Rc<dyn FontProvider>, style: &ComputedStyle) -> Self {
+        Font {
+            provider: provider.clone(),
+            description: FontDescription::of(style),
+            letter_spacing: px_value(style.get("letter-spacing")).unwrap_or(0.0),
+            word_spacing: px_value(style.get("word-spacing")).unwrap_or(0.0),
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> FontMetrics {
+        self.provider.metrics(&self.description)
+    }
+
+    /// Returns the width of a run of text, in pixels.
+    pub(crate) fn text_width(&self, text: &str) -> f64 {
+        let spacing: f64 = text
+            .chars()
+            .map(|char| match char {
+                ' ' => self.letter_spacing + self.word_spacing,
+                _ => self.letter_spacing,
+            })
+            .sum();
+        self.provider.text_width(&self.description, text) + spacing
+    }
+
+    /// Returns how far a character moves the pen, with the spacing added after it, in pixels.
+    pub(crate) fn advance(&self, character: char) -> f64 {
+        let spacing = match character {
+            ' ' => self.letter_spacing + self.word_spacing,
+            _ => self.letter_spacing,
+        };
+        self.provider.advance(&self.description, character) + spacing
+    }
+
+    /// Returns the outline that a character is painted with, if the provider has one.
+    pub(crate) fn outline(&self, character: char) -> Option<Vec<Vec<(f64, f64)>>> {
+        self.provider.outline(&self.description, character)
+    }
+
+    pub(crate) fn description(&self) -> &FontDescription {
+        &self.description
+    }
+
+    /// Returns the used line height of a style that uses the font, in pixels.
+    pub(crate) fn line_height(&self, style: &ComputedStyle) -> f64 {
+        let value = style.get("line-height");
+        if let Some(pixels) = px_value(value) {
+            return pixels;
+        }
+        match value.parse::<f64>() {
+            Ok(number) => number * style.font_size(),
+            Err(_) => self.metrics().normal_line_height(),
+        }
+    }
+}
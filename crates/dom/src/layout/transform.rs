@@ -0,0 +1,276 @@
+//! The transforms of boxes, which move, scale, rotate and skew a box and its contents where they are painted, without changing layout.
+//!
+//! Transforms are two-dimensional: the 3D transform functions keep only their effect on the x and y axes.
+//!
+//! [Reference](https://drafts.csswg.org/css-transforms/)
+use std::f64::consts::PI;
+
+use super::{BoxKind, LayoutBox, Rect, Size};
+use crate::css::split_dimension;
+
+/// A 2D affine transform, which maps a point `(x, y)` to `(a * x + c * y + e, b * x + d * y + f)`, like a CSS `matrix()`.
+///
+/// MDN Reference: [`matrix()`](https://developer.mozilla.org/docs/Web/CSS/transform-function/matrix)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    /// The transform that leaves points where they are.
+    pub const IDENTITY: Transform = Transform {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    pub(crate) fn translate(x: f64, y: f64) -> Self {
+        Transform {
+            e: x,
+            f: y,
+            ..Transform::IDENTITY
+        }
+    }
+
+    pub(crate) fn scale(x: f64, y: f64) -> Self {
+        Transform {
+            a: x,
+            d: y,
+            ..Transform::IDENTITY
+        }
+    }
+
+    /// Rotates clockwise by an angle in radians, as the y axis points down.
+    pub(crate) fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Transform {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Transform::IDENTITY
+        }
+    }
+
+    pub(crate) fn skew(x: f64, y: f64) -> Self {
+        Transform {
+            b: y.tan(),
+            c: x.tan(),
+            ..Transform::IDENTITY
+        }
+    }
+
+    /// Returns the transform that applies `other` first, and then this transform.
+    pub fn multiply(&self, other: &Transform) -> Transform {
+        Transform {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    /// Returns the transform that undoes this one, or `None` if it flattens the plane, like `scale(0)`.
+    pub fn inverse(&self) -> Option<Transform> {
+        let determinant = self.a * self.d - self.b * self.c;
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        Some(Transform {
+            a: self.d / determinant,
+            b: -self.b / determinant,
+            c: -self.c / determinant,
+            d: self.a / determinant,
+            e: (self.c * self.f - self.d * self.e) / determinant,
+            f: (self.b * self.e - self.a * self.f) / determinant,
+        })
+    }
+
+    /// Returns where the transform moves a point.
+    pub fn apply(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+
+    /// Returns the smallest rectangle that contains a rectangle after the transform.
+    pub(crate) fn bounds(&self, rect: &Rect) -> Rect {
+        let corners = [
+            (rect.x, rect.y),
+            (rect.right(), rect.y),
+            (rect.x, rect.bottom()),
+            (rect.right(), rect.bottom()),
+        ]
+        .map(|corner| self.apply(corner));
+        let (mut left, mut top, mut right, mut bottom) = (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        );
+        for (x, y) in corners {
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+        }
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Returns the transform of a box, given by its `transform` around its `transform-origin`, or `None` if it is not transformed.
+    ///
+    /// Only block containers and replaced elements can be transformed, and a `transform` that cannot be parsed is ignored.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-transforms/#transform-rendering)
+    pub(crate) fn of(layout_box: &LayoutBox) -> Option<Transform> {
+        if !matches!(layout_box.kind, BoxKind::Flow | BoxKind::Replaced { .. }) {
+            return None;
+        }
+        let value = layout_box.style.get("transform");
+        if value == "none" {
+            return None;
+        }
+        let reference = layout_box.rect;
+        let transform = parse(value, &reference)?;
+        let (x, y) = origin(layout_box.style.get("transform-origin"), &reference);
+        Some(
+            Transform::translate(x, y)
+                .multiply(&transform)
+                .multiply(&Transform::translate(-x, -y)),
+        )
+    }
+}
+
+/// Parses a list of transform functions, with percentages relative to a reference box.
+fn parse(value: &str, reference: &Rect) -> Option<Transform> {
+    let mut transform = Transform::IDENTITY;
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let (name, tail) = rest.split_once('(')?;
+        let (arguments, tail) = tail.split_once(')')?;
+        let arguments: Vec<&str> = arguments
+            .split([',', ' '])
+            .filter(|argument| !argument.is_empty())
+            .collect();
+        let function = function(&name.trim().to_ascii_lowercase(), &arguments, reference)?;
+        transform = transform.multiply(&function);
+        rest = tail.trim_start();
+    }
+    Some(transform)
+}
+
+/// Returns the transform of a single transform function, or `None` if it is not valid.
+///
+/// [Reference](https://drafts.csswg.org/css-transforms/#transform-functions)
+fn function(name: &str, arguments: &[&str], reference: &Rect) -> Option<Transform> {
+    let length = |index: usize, basis: f64| -> Option<f64> {
+        Size::parse(arguments.get(index)?).resolve(Some(basis))
+    };
+    let number = |index: usize| -> Option<f64> { arguments.get(index)?.parse().ok() };
+    let angle = |index: usize| -> Option<f64> { angle(arguments.get(index)?) };
+    let count = arguments.len();
+    let transform = match name {
+        "matrix" if count == 6 => Transform {
+            a: number(0)?,
+            b: number(1)?,
+            c: number(2)?,
+            d: number(3)?,
+            e: number(4)?,
+            f: number(5)?,
+        },
+        "translate" if count <= 2 => Transform::translate(
+            length(0, reference.width)?,
+            match count {
+                2 => length(1, reference.height)?,
+                _ => 0.0,
+            },
+        ),
+        "translate3d" if count == 3 => {
+            Transform::translate(length(0, reference.width)?, length(1, reference.height)?)
+        }
+        "translatex" if count == 1 => Transform::translate(length(0, reference.width)?, 0.0),
+        "translatey" if count == 1 => Transform::translate(0.0, length(0, reference.height)?),
+        "scale" if count <= 2 => {
+            let x = number(0)?;
+            Transform::scale(x, if count == 2 { number(1)? } else { x })
+        }
+        "scale3d" if count == 3 => Transform::scale(number(0)?, number(1)?),
+        "scalex" if count == 1 => Transform::scale(number(0)?, 1.0),
+        "scaley" if count == 1 => Transform::scale(1.0, number(0)?),
+        "rotate" | "rotatez" if count == 1 => Transform::rotate(angle(0)?),
+        "skew" if count <= 2 => Transform::skew(
+            angle(0)?,
+            match count {
+                2 => angle(1)?,
+                _ => 0.0,
+            },
+        ),
+        "skewx" if count == 1 => Transform::skew(angle(0)?, 0.0),
+        "skewy" if count == 1 => Transform::skew(0.0, angle(0)?),
+        // Functions that only act on the z axis do nothing in two dimensions.
+        "translatez" | "scalez" | "rotatex" | "rotatey" | "perspective" if count == 1 => {
+            Transform::IDENTITY
+        }
+        _ => return None,
+    };
+    Some(transform)
+}
+
+/// Parses an angle, like `45deg` or `0.25turn`, in radians.
+fn angle(value: &str) -> Option<f64> {
+    let (number, unit) = split_dimension(value)?;
+    let radians = match unit.to_ascii_lowercase().as_str() {
+        "deg" => number.to_radians(),
+        "rad" => number,
+        "grad" => number * PI / 200.0,
+        "turn" => number * 2.0 * PI,
+        "" if number == 0.0 => 0.0,
+        _ => return None,
+    };
+    Some(radians)
+}
+
+/// Resolves a `transform-origin` to a point, relative to the viewport.
+///
+/// [Reference](https://drafts.csswg.org/css-transforms/#transform-origin-property)
+fn origin(value: &str, reference: &Rect) -> (f64, f64) {
+    let mut components: Vec<&str> = value.split_whitespace().take(2).collect();
+    match components.as_slice() {
+        [] => return (reference.x, reference.y),
+        [single @ ("top" | "bottom")] => components = vec!["center", single],
+        [single] => components = vec![single, "center"],
+        [first, second]
+            if matches!(*first, "top" | "bottom") || matches!(*second, "left" | "right") =>
+        {
+            components = vec![second, first];
+        }
+        _ => {}
+    }
+    let resolve = |value: &str, basis: f64| match value {
+        "left" | "top" => 0.0,
+        "center" => basis / 2.0,
+        "right" | "bottom" => basis,
+        _ => Size::parse(value).resolve(Some(basis)).unwrap_or(0.0),
+    };
+    (
+        reference.x + resolve(components[0], reference.width),
+        reference.y + resolve(components[1], reference.height),
+    )
+}
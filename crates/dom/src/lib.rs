@@ -1,59 +1,109 @@
 #![allow(unused, deprecated)]
 mod animatable;
+mod animation;
 mod attr;
+mod character_data;
+mod comment;
+mod css;
+mod custom_elements;
 mod document;
+mod document_fragment;
 mod document_type;
+mod dom_rect;
 mod dom_token_list;
 mod domexception;
 mod domitem;
 mod element;
 mod event;
 mod event_target;
+mod file;
+mod font;
+mod form_data;
 mod html_collection;
 mod html_element;
+mod html_parser;
 mod inner_html;
+mod layout;
+mod loader;
 mod misc;
 mod named_node_map;
 mod node;
 mod nodelist;
+mod paint;
 mod range;
+mod scroll;
 mod slot;
 mod tag;
+mod terminal;
 #[cfg(test)]
 mod test;
+mod text;
 mod window;
 
 pub use animatable::*;
+pub use animation::{
+    Animation, AnimationPlayState, ComputedEffectTiming, EffectTiming, FillMode, KeyframeEffect,
+    PlaybackDirection,
+};
 pub use attr::Attr;
+pub use character_data::AsCharacterData;
+pub use comment::Comment;
+pub use css::{
+    CSSFontFaceRule, CSSImportRule, CSSKeyframeRule, CSSKeyframesRule, CSSLayerBlockRule,
+    CSSLayerStatementRule, CSSMediaRule, CSSPropertyRule, CSSRule, CSSRuleList,
+    CSSStyleDeclaration, CSSStyleRule, CSSStyleSheet, CSSSupportsRule, MediaList, MediaQueryList,
+    StyleSheetList,
+};
+pub use custom_elements::{CustomElement, CustomElementRegistry};
 pub use document::{AsDocument, Document};
+pub use document_fragment::DocumentFragment;
 pub use document_type::*;
+pub use dom_rect::{DOMRect, DOMRectList};
 pub use dom_token_list::{DOMTokenList, MutDOMTokenList};
 pub use domexception::*;
 pub use element::{
-    AsElement, CheckVisibilityOptions, DOMRect, DOMRectList, Element, FullscreenOptions,
-    InsertPosition, ScrollIntoView, ScrollToOptions,
+    AsElement, CheckVisibilityOptions, Element, FullscreenOptions, GetHTMLOptions, InsertPosition,
+};
+pub use event::{
+    AsEvent, DOMHighResTimeStamp, Event, EventDetail, EventInit, EventPhase, EventType,
 };
-pub use event::{AsEvent, DOMHighResTimeStamp, Event};
-pub use event_target::{AsEventTarget, EventTarget};
+pub use event_target::{
+    AsEventTarget, EventListener, EventListenerOrEventListenerObject, EventTarget,
+};
+pub use file::{File, FileList, FilePropertyBag};
+pub use font::{
+    FixedFontProvider, FontCollection, FontDescription, FontMetrics, FontProvider, TrueTypeFont,
+};
+pub use form_data::{FormData, FormDataEntryValue};
 pub use html_collection::{HTMLCollection, HTMLCollectionOf};
 pub use html_element::*;
 pub use inner_html::InnerHtml;
+pub use layout::Transform;
+pub use loader::{FileLoader, ResourceLoader};
 pub use misc::*;
 pub use named_node_map::NamedNodeMap;
 pub use node::{
     AsChildNode, AsNode, AsParentNode, ChildNode, GetRootNodeOptions, Node, ParentNode,
 };
 pub use nodelist::{MutNodeListOf, NodeListOf};
+pub use paint::{
+    Bitmap, Clip, Color, DisplayItem, DisplayList, Draw, Glyph, Painter, Radii, RenderBackend,
+    Shape,
+};
 pub use range::Range;
+pub use scroll::{ScrollBehavior, ScrollIntoView, ScrollLogicalPosition, ScrollToOptions};
 pub use slot::*;
-pub use window::WindowEventHandlers;
+pub use terminal::TerminalOptions;
+pub use text::Text;
+pub use window::{ColorScheme, MediaType, Viewport, Window, WindowEventHandlers};
 
 pub type DOMResult = Result<(), DOMException>;
 
 pub mod traits {
     pub use crate::{
-        AsChildNode, AsDocument, AsElement, AsEvent, AsEventTarget, AsHTMLElement, AsNode,
-        AsParentNode, HTMLHyperlinkElementUtils, HTMLMediaElement, WindowEventHandlers,
+        Animatable, AsCharacterData, AsChildNode, AsDocument, AsElement, AsEvent, AsEventTarget,
+        AsHTMLElement, AsNode, AsParentNode, ConstraintValidation, HTMLHyperlinkElementUtils,
+        HTMLMediaElement, WindowEventHandlers,
     };
 }
 
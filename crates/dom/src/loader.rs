@@ -0,0 +1,145 @@
+//! Loading of the external resources used by a document, such as the style sheets of `<link rel=stylesheet>` elements.
+use std::path::PathBuf;
+
+/// A source of external resources.
+///
+/// There is no network access, so resources are read from wherever the loader chooses to find them. Any closure that maps a resolved URL to the text of the resource is also a loader.
+///
+/// # Example
+/// ```
+/// use dom::{traits::*, Document, InnerHtml};
+///
+/// let mut document = Document::new();
+/// document.set_resource_loader(|url: &str| match url {
+///     "theme.css" => Some(String::from("body { color: navy }")),
+///     _ => None,
+/// });
+/// let mut html = document.create_element("html");
+/// html.set_inner_html("<head><link rel=stylesheet href=theme.css></head>").unwrap();
+/// document.append_child(&mut html).unwrap();
+///
+/// let sheet = document.style_sheets().item(0).unwrap();
+/// assert_eq!(sheet.href().as_deref(), Some("theme.css"));
+/// assert_eq!(sheet.css_rules().len(), 1);
+/// ```
+pub trait ResourceLoader {
+    /// Returns the text of the resource at a resolved URL, or `None` if it cannot be loaded.
+    fn load(&self, url: &str) -> Option<String>;
+    /// Returns the bytes of the resource at a resolved URL, like an image, or `None` if it cannot be loaded. By default, these are the bytes of the text that [`load`](ResourceLoader::load) returns.
+    fn load_bytes(&self, url: &str) -> Option<Vec<u8>> {
+        self.load(url).map(String::into_bytes)
+    }
+}
+
+impl<F: Fn(&str) -> Option<String>> ResourceLoader for F {
+    fn load(&self, url: &str) -> Option<String> {
+        self(url)
+    }
+}
+
+/// A loader that reads resources from the local file system.
+///
+/// `file:` URLs and relative URLs are read from inside the root directory; URLs with any other scheme fail to load.
+///
+/// # Example
+/// ```
+/// use dom::FileLoader;
+/// use dom::ResourceLoader;
+///
+/// let loader = FileLoader::new(env!("CARGO_MANIFEST_DIR"));
+/// assert!(loader.load("Cargo.toml").is_some());
+/// assert!(loader.load("https://example.com/Cargo.toml").is_none());
+/// ```
+pub struct FileLoader {
+    root: PathBuf,
+}
+
+impl FileLoader {
+    /// Creates a loader that reads resources from inside a root directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileLoader { root: root.into() }
+    }
+
+    /// Returns the path of the file for a URL, or `None` if the URL is not inside the root directory.
+    fn path_of(&self, url: &str) -> Option<PathBuf> {
+        let path = match url.strip_prefix("file://") {
+            Some(path) => path,
+            None if scheme_of(url).is_some() => return None,
+            None => url,
+        };
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        // Keep the resource inside the root directory.
+        if path.split('/').any(|segment| segment == "..") {
+            return None;
+        }
+        Some(self.root.join(path.trim_start_matches('/')))
+    }
+}
+
+impl ResourceLoader for FileLoader {
+    fn load(&self, url: &str) -> Option<String> {
+        std::fs::read_to_string(self.path_of(url)?).ok()
+    }
+
+    fn load_bytes(&self, url: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.path_of(url)?).ok()
+    }
+}
+
+/// Returns the scheme of a URL, like `https` for `https://example.com`.
+pub(crate) fn scheme_of(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once(':')?;
+    let mut chars = scheme.chars();
+    let is_scheme = chars.next().is_some_and(|char| char.is_ascii_alphabetic())
+        && chars.all(|char| char.is_ascii_alphanumeric() || matches!(char, '+' | '-' | '.'));
+    is_scheme.then_some(scheme)
+}
+
+/// Resolves a URL against a base URL, removing the `.` and `..` segments of its path.
+///
+/// [Reference](https://url.spec.whatwg.org/#concept-basic-url-parser)
+pub(crate) fn resolve_url(base: &str, url: &str) -> String {
+    let url = url.trim();
+    if scheme_of(url).is_some() || base.is_empty() || scheme_of(base) == Some("about") {
+        return url.to_owned();
+    }
+    // The part of the base URL that stays the same: its scheme and host, if it has any.
+    let origin_length = match base.find("://") {
+        Some(index) => {
+            let after = index + 3;
+            after + base[after..].find('/').unwrap_or(base.len() - after)
+        }
+        None => 0,
+    };
+    let (origin, base_path) = base.split_at(origin_length);
+    if let Some(rest) = url.strip_prefix("//") {
+        let scheme = scheme_of(base).unwrap_or("file");
+        return format!("{scheme}://{rest}");
+    }
+    let path = if url.starts_with('/') {
+        url.to_owned()
+    } else {
+        let base_path = base_path.split(['?', '#']).next().unwrap_or_default();
+        let directory = &base_path[..base_path.rfind('/').map_or(0, |index| index + 1)];
+        format!("{directory}{url}")
+    };
+    let mut segments: Vec<&str> = vec![];
+    let path_segments: Vec<&str> = path.split('/').collect();
+    for (index, segment) in path_segments.iter().enumerate() {
+        let is_last = index + 1 == path_segments.len();
+        match *segment {
+            "." if is_last => segments.push(""),
+            "." => {}
+            ".." => {
+                if segments.len() > 1 || segments.first().is_some_and(|first| !first.is_empty()) {
+                    segments.pop();
+                }
+                if is_last {
+                    segments.push("");
+                }
+            }
+            segment => segments.push(segment),
+        }
+    }
+    format!("{origin}{}", segments.join("/"))
+}
@@ -4,7 +4,7 @@ use std::{
     rc::Weak,
 };
 
-use crate::{element::ElementBase, Attr};
+use crate::{element::ElementBase, Attr, Element};
 
 /// A collection of Attr objects. Objects inside a NamedNodeMap are not in any particular order, unlike NodeList, although they may be accessed by an index as in an array.
 #[derive(Debug)]
@@ -63,8 +63,11 @@ impl NamedNodeMap {
         self.items.get_mut(index)
     }
     pub fn remove_named_item(&mut self, qualified_name: &str) -> Option<Attr> {
-        self.index_of(qualified_name)
-            .map(|index| self.items.remove(index))
+        let attr = self
+            .index_of(qualified_name)
+            .map(|index| self.items.remove(index))?;
+        self.handle_attribute_changes(&attr.__name, attr.__value.as_deref(), None);
+        Some(attr)
     }
     pub fn remove_named_item_ns(&mut self, namespace: Option<&str>, local_name: &str) -> Attr {
         todo!()
@@ -88,16 +91,19 @@ impl NamedNodeMap {
     /// ```
     pub fn set_named_item(&mut self, mut attr: Attr) -> Option<&mut Attr> {
         attr.set_owner_element(self.owner_element.clone());
-        let index = match self.index_of(&attr.__name) {
+        let name = attr.__name.clone();
+        let value = attr.__value.clone();
+        let (index, old_value) = match self.index_of(&attr.__name) {
             Some(index) => {
-                self.items[index] = attr;
-                index
+                let old_attr = std::mem::replace(&mut self.items[index], attr);
+                (index, old_attr.__value)
             }
             None => {
                 self.items.push(attr);
-                self.items.len() - 1
+                (self.items.len() - 1, None)
             }
         };
+        self.handle_attribute_changes(&name, old_value.as_deref(), value.as_deref());
         self.items.get_mut(index)
     }
     pub fn set_named_item_ns(&mut self, attr: Attr) -> Option<&mut Attr> {
@@ -105,6 +111,19 @@ impl NamedNodeMap {
     }
 }
 
+impl NamedNodeMap {
+    fn handle_attribute_changes(
+        &self,
+        local_name: &str,
+        old_value: Option<&str>,
+        value: Option<&str>,
+    ) {
+        if let Some(base) = self.owner_element.upgrade() {
+            Element::with_base(base).handle_attribute_changes(local_name, old_value, value);
+        }
+    }
+}
+
 impl Index<usize> for NamedNodeMap {
     type Output = Attr;
 
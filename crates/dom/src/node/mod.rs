@@ -1,13 +1,13 @@
 mod child_node;
 mod mutation_algorithms;
 mod parent_node;
-mod trees;
+pub(crate) mod trees;
 
 use crate::{
     document::{DocumentBase, WeakDocumentRef},
     domitem::DOMItem,
-    AsElement, AsEventTarget, DOMException, Document, Element, EventTarget, MutNodeListOf,
-    NodeListOf,
+    AsElement, AsEventTarget, DOMException, Document, Element, EventTarget, HTMLElement,
+    MutNodeListOf, NodeListOf,
 };
 pub use child_node::{AsChildNode, ChildNode};
 pub use parent_node::{AsParentNode, ParentNode};
@@ -64,6 +64,14 @@ pub(crate) struct NodeBase {
     pub parent: Option<(WeakNodeRef, usize)>,
     pub children: Vec<ChildNode>,
     pub observer_list: Vec<RegisteredObserver>,
+    /// The slot this node is assigned to, if it is a slottable.
+    pub assigned_slot: Option<WeakNodeRef>,
+    /// The slot this node was manually assigned to with [`HTMLSlotElement::assign`].
+    ///
+    /// [`HTMLSlotElement::assign`]: crate::HTMLSlotElement::assign
+    pub manual_slot_assignment: Option<WeakNodeRef>,
+    /// The data of a text or comment node.
+    pub data: String,
 }
 impl std::fmt::Debug for NodeBase {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -147,22 +155,104 @@ impl Node {
 
     /// Create a node inside a document.
     pub(crate) fn in_document(node_type: NodeType, weak_ref: WeakDocumentRef) -> Self {
-        Self {
-            base: Rc::new(RefCell::new(NodeBase {
-                node_type,
-                event_target: EventTarget::new(),
-                owner_document: weak_ref,
-                parent: None,
-                children: vec![],
-                observer_list: vec![],
-            })),
-        }
+        Self::with_base(NodeBase {
+            node_type,
+            event_target: EventTarget::new(),
+            owner_document: weak_ref,
+            parent: None,
+            children: vec![],
+            observer_list: vec![],
+            assigned_slot: None,
+            manual_slot_assignment: None,
+            data: String::new(),
+        })
+    }
+
+    /// Create a text or comment node inside a document.
+    pub(crate) fn character_data(
+        node_type: NodeType,
+        data: &str,
+        weak_ref: WeakDocumentRef,
+    ) -> Self {
+        let node = Self::in_document(node_type, weak_ref);
+        node.base().data = data.to_owned();
+        node
+    }
+
+    /// Wrap a node base, making the node the owner of its event target.
+    pub(crate) fn with_base(base: NodeBase) -> Self {
+        let node = Self {
+            base: Rc::new(RefCell::new(base)),
+        };
+        node.base().event_target.owner = Some(WeakNodeRef::from(&node));
+        node
     }
     /// Returns a raw pointer to the underlying node base.
     pub(crate) fn get_base_ptr(&self) -> *mut NodeBase {
         self.base.as_ptr()
     }
 
+    /// Removes all the children of the node.
+    pub(crate) fn remove_all_children(&mut self) {
+        while let Some(mut child) = self.base().children.last().map(ChildNode::clone_ref) {
+            self.__remove_child(&mut child).unwrap();
+        }
+    }
+
+    /// Replaces all the children of the node with a text node containing a string.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#string-replace-all)
+    fn replace_all_with_text(&mut self, value: &str) {
+        self.remove_all_children();
+        if !value.is_empty() {
+            let weak_ref = self.base().owner_document.clone();
+            let mut text = Node::character_data(NodeType::TextNode, value, weak_ref);
+            self.__append_child(&mut text).unwrap();
+        }
+        self.update_document();
+    }
+
+    /// Replaces all the children of the node with a node, or with the children of a fragment.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#concept-node-replace-all)
+    pub(crate) fn replace_all(&mut self, node: &mut impl AsNode) {
+        self.remove_all_children();
+        self.__append_child(node)
+            .expect("Replacement nodes are always valid children.");
+        self.update_document();
+    }
+
+    /// Appends copies of the children of the node to another node, as part of cloning the node.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#concept-node-clone)
+    pub(crate) fn clone_children_into(&self, copy: &mut Node, document: &Document) {
+        for child in self.child_nodes() {
+            let child = &child.inner;
+            match child.node_type() {
+                Self::ELEMENT_NODE => {
+                    let element = match child
+                        .owner_document()
+                        .and_then(|owner| owner.lookup_html_element(child.get_base_ptr()))
+                    {
+                        Some(element) => element,
+                        None => continue,
+                    };
+                    let mut child_copy = HTMLElement::clone_element(&element, document, true);
+                    copy.__append_child(&mut child_copy).unwrap();
+                }
+                Self::TEXT_NODE | Self::COMMENT_NODE => {
+                    let mut child_copy = Node::character_data(
+                        child.base().node_type,
+                        &child.base().data,
+                        document.weak_ref(),
+                    );
+                    copy.__append_child(&mut child_copy).unwrap();
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Inner implementation of `remove()`.
     fn __remove(&mut self) {
         if let Some(mut parent) = self.parent_node() {
@@ -190,12 +280,14 @@ impl Node {
         };
         helpers::validate_hierarchy(self, new_child)?;
         if new_child.node_type() == Self::DOCUMENT_FRAGMENT_NODE {
-            let children = new_child.child_nodes_mut();
-            while !children.items.is_empty() {
+            let fragment = AsNode::cast(new_child).clone_ref();
+            while let Some(mut subchild) =
+                fragment.base().children.first().map(ChildNode::clone_ref)
+            {
                 let reference_node = unsafe { &mut *(self.base.as_ptr()) }
                     .children
                     .get_mut(index);
-                self.__insert_before(&mut children.items.remove(0), reference_node)?;
+                self.__insert_before(&mut subchild, reference_node)?;
                 index += 1;
             }
             return Ok(new_child);
@@ -207,16 +299,14 @@ impl Node {
 
         AsNode::cast_mut(&mut child).set_parent(Some((WeakNodeRef::from(&*self), index)));
         let children = self.child_nodes_mut().items;
-        children.insert(index, child);
+        children.insert(index, child.clone_ref());
 
         // Shift all following indexes.
-        loop {
+        while index + 1 < children.len() {
             index += 1;
             AsNode::cast_mut(&mut children[index]).set_index(index);
-            if index + 1 == children.len() {
-                break;
-            }
         }
+        helpers::run_insertion_steps(self, &child.inner);
         Ok(new_child)
     }
 
@@ -241,6 +331,7 @@ impl Node {
         }
         // Remove parent pointer.
         node_ref.set_parent(None);
+        helpers::run_removing_steps(self, node_ref);
         Ok(node)
     }
 
@@ -260,14 +351,13 @@ impl Node {
         helpers::validate_hierarchy(self, new_child)?;
 
         if new_child.node_type() == Self::DOCUMENT_FRAGMENT_NODE {
-            let children = new_child.child_nodes_mut();
-            let mut i = 0;
-            while i < children.items.len() {
-                AsNode::cast_mut(self)
-                    .__insert_before(&mut children.items.remove(i), Some(old_child))?;
-                i += 1;
+            let fragment = AsNode::cast(new_child).clone_ref();
+            while let Some(mut subchild) =
+                fragment.base().children.first().map(ChildNode::clone_ref)
+            {
+                self.__insert_before(&mut subchild, Some(old_child))?;
             }
-            self.remove_child(old_child).unwrap();
+            self.__remove_child(old_child)?;
             return Ok(old_child);
         }
 
@@ -277,20 +367,21 @@ impl Node {
         AsNode::cast_mut(&mut new_child).__remove();
 
         AsNode::cast_mut(&mut new_child).set_parent(old_child_as_node.base().parent.take());
-        self.child_nodes_mut().items[index] = new_child;
+        self.child_nodes_mut().items[index] = new_child.clone_ref();
+        helpers::run_removing_steps(self, AsNode::cast(old_child));
+        helpers::run_insertion_steps(self, &new_child.inner);
 
         Ok(old_child)
     }
 
     /// Refresh the DOM.
     fn update_document(&self) {
+        crate::layout::invalidate(AsNode::cast(self));
         // Refresh DOM.
         if let Some(document) = self.owner_document() {
-            document.inner.borrow_mut().refresh(
-                &document
-                    .lookup_html_element(self.get_base_ptr())
-                    .expect("Tried to retrieve a node that does not exist / is not an element."),
-            )
+            document.refresh();
+            document.invoke_custom_element_reactions();
+            document.notify_mutation_observers();
         }
     }
 
@@ -299,10 +390,12 @@ impl Node {
         helpers::validate_hierarchy(self, child)?;
 
         if child.node_type() == Self::DOCUMENT_FRAGMENT_NODE {
-            for subchild in child.child_nodes_mut() {
-                self.__append_child(subchild)?;
+            let fragment = AsNode::cast(child);
+            while let Some(mut subchild) =
+                fragment.base().children.first().map(ChildNode::clone_ref)
+            {
+                self.__append_child(&mut subchild)?;
             }
-            child.child_nodes_mut().items.clear();
         } else {
             let mut childnode = ChildNode::from(&*child);
             // Disconnect from former parent.
@@ -310,7 +403,8 @@ impl Node {
             let weak_reference = WeakNodeRef::from(&*self);
             let index = helpers::get_children_length(self);
             childnode.inner.set_parent(Some((weak_reference, index)));
-            self.child_nodes_mut().items.push(childnode);
+            self.child_nodes_mut().items.push(childnode.clone_ref());
+            helpers::run_insertion_steps(self, &childnode.inner);
         }
         Ok(child)
     }
@@ -416,8 +510,27 @@ pub trait AsNode: AsEventTarget {
     /// Returns a boolean indicating whether or not the Node is connected (directly or indirectly) to the context object, e.g. the [`Document`] object in the case of the normal DOM, or the ShadowRoot in the case of a shadow DOM.
     ///
     /// MDN Reference: [`Node.isConnected`](https://developer.mozilla.org/en-US/docs/Web/API/Node/isConnected)
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let mut document = Document::new();
+    /// let mut html = document.create_element("html");
+    /// let mut body = document.create_element("body");
+    /// html.append_child(&mut body).unwrap();
+    /// assert!(!body.is_connected());
+    ///
+    /// document.append_child(&mut html).unwrap();
+    /// assert!(body.is_connected());
+    /// ```
     fn is_connected(&self) -> bool {
-        todo!()
+        // An element is connected if its shadow-including root is a document.
+        let root = trees::root_of(self);
+        match crate::slot::host_of_shadow_root(&root) {
+            Some(host) => host.is_connected(),
+            None => root.node_type() == Self::DOCUMENT_NODE,
+        }
     }
     /// Returns the node document. Returns [`None`] for documents.
     ///
@@ -431,7 +544,7 @@ pub trait AsNode: AsEventTarget {
             .owner_document
             .inner
             .upgrade()
-            .map(|inner| Document { inner })
+            .map(Document::with_base)
     }
     /// Returns node's root.
     fn get_root_node(&self, options: Option<GetRootNodeOptions>) -> Option<&Node> {
@@ -552,6 +665,7 @@ pub trait AsNode: AsEventTarget {
     fn node_value(&self) -> Option<&str> {
         match AsNode::cast(self).base().node_type {
             NodeType::AttributeNode => todo!(),
+            NodeType::TextNode | NodeType::CommentNode => Some(&AsNode::cast(self).base().data),
             _ => None,
         }
     }
@@ -562,15 +676,68 @@ pub trait AsNode: AsEventTarget {
         let value = value.unwrap_or("");
         match AsNode::cast(self).base().node_type {
             NodeType::AttributeNode => todo!(),
+            NodeType::TextNode | NodeType::CommentNode => {
+                AsNode::cast(self).base().data = value.to_owned();
+                crate::layout::invalidate(AsNode::cast(self));
+            }
             _ => {}
         }
     }
-
-    fn text_content(&self) -> Option<&str> {
-        unimplemented!()
+    /// Returns the text content of the node and its descendants. Returns [`None`] for documents and doctypes.
+    ///
+    /// MDN Reference: [`Node.textContent`](https://developer.mozilla.org/en-US/docs/Web/API/Node/textContent)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut paragraph = document.create_element("p");
+    /// paragraph.append_child(&mut document.create_text_node("Hello, ")).unwrap();
+    /// let mut strong = document.create_element("strong");
+    /// strong.append_child(&mut document.create_text_node("world")).unwrap();
+    /// paragraph.append_child(&mut strong).unwrap();
+    ///
+    /// assert_eq!(paragraph.text_content(), Some(String::from("Hello, world")));
+    /// ```
+    fn text_content(&self) -> Option<String> {
+        let node = AsNode::cast(self);
+        match node.base().node_type {
+            NodeType::DocumentNode | NodeType::DocumentTypeNode => None,
+            NodeType::TextNode | NodeType::CommentNode => Some(node.base().data.clone()),
+            _ => Some(
+                trees::descendant_nodes(node)
+                    .into_iter()
+                    .filter(|descendant| descendant.node_type() == Self::TEXT_NODE)
+                    .map(|descendant| descendant.base().data.as_str())
+                    .collect(),
+            ),
+        }
     }
+    /// Replaces the children of the node with a single text node containing the value. For text and comment nodes, the data is set instead.
+    ///
+    /// MDN Reference: [`Node.textContent`](https://developer.mozilla.org/en-US/docs/Web/API/Node/textContent)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document};
+    ///
+    /// let document = Document::new();
+    /// let mut div = document.create_element("div");
+    /// div.append_child(&mut document.create_element("span")).unwrap();
+    ///
+    /// div.set_text_content("Plain text");
+    /// assert_eq!(div.child_nodes().len(), 1);
+    /// assert_eq!(div.text_content(), Some(String::from("Plain text")));
+    /// ```
     fn set_text_content(&mut self, value: &str) {
-        unimplemented!()
+        let node = AsNode::cast_mut(self);
+        match node.base().node_type {
+            NodeType::DocumentNode | NodeType::DocumentTypeNode => {}
+            NodeType::TextNode | NodeType::CommentNode => {
+                node.base().data = value.to_owned();
+                crate::layout::invalidate(node);
+            }
+            _ => node.replace_all_with_text(value),
+        }
     }
     /// Adds a node to the end of the list of the child nodes of this node.
     /// It returns a reference to the node appended, or an error if the appending was unsuccessful.
@@ -815,9 +982,28 @@ mod helpers {
     use std::{cell::RefCell, rc::Rc};
 
     use crate::{
-        domitem::DOMItem, node::NodeBase, node::WeakNodeRef, AsNode, ChildNode, DOMException, Node,
+        domitem::DOMItem, node::NodeBase, node::WeakNodeRef, AsNode, ChildNode, DOMException,
+        Document, Node,
     };
 
+    /// Runs the steps that follow the insertion of a node into a parent.
+    pub fn run_insertion_steps(parent: &Node, node: &Node) {
+        if let Some(inner) = parent.base().owner_document.inner.upgrade() {
+            Document::with_base(inner).adopt(node);
+        }
+        crate::slot::run_insertion_steps(parent, node);
+        crate::custom_elements::run_insertion_steps(node);
+        crate::html_element::form_associated::run_insertion_steps(parent, node);
+        crate::html_element::html_input_element::run_insertion_steps(node);
+    }
+
+    /// Runs the steps that follow the removal of a node from a parent.
+    pub fn run_removing_steps(parent: &Node, node: &Node) {
+        crate::slot::run_removing_steps(parent, node);
+        crate::custom_elements::run_removing_steps(parent, node);
+        crate::html_element::form_associated::run_removing_steps(parent, node);
+    }
+
     pub fn validate_hierarchy<T: AsNode, U: AsNode>(
         parent: &T,
         child: &U,
@@ -887,43 +1073,45 @@ mod helpers {
     pub fn clone_node<T: AsNode>(noderef: &T, deep: bool) -> Node {
         let inner_node = AsNode::cast(noderef).base();
         if deep {
-            Node {
-                base: Rc::new(RefCell::new(NodeBase {
-                    node_type: inner_node.node_type,
-                    event_target: crate::EventTarget::new(),
-                    owner_document: inner_node.owner_document.clone(),
-                    parent: inner_node.parent.clone(),
-                    children: inner_node
-                        .children
-                        .as_slice()
-                        .iter()
-                        .map(|noderef| ChildNode {
-                            inner: clone_node(noderef, deep),
-                        })
-                        .collect(),
-                    observer_list: vec![],
-                })),
-            }
+            Node::with_base(NodeBase {
+                node_type: inner_node.node_type,
+                event_target: crate::EventTarget::new(),
+                owner_document: inner_node.owner_document.clone(),
+                parent: inner_node.parent.clone(),
+                children: inner_node
+                    .children
+                    .as_slice()
+                    .iter()
+                    .map(|noderef| ChildNode {
+                        inner: clone_node(noderef, deep),
+                    })
+                    .collect(),
+                observer_list: vec![],
+                assigned_slot: None,
+                manual_slot_assignment: None,
+                data: inner_node.data.clone(),
+            })
         } else {
-            Node {
-                base: Rc::new(RefCell::new(NodeBase {
-                    node_type: inner_node.node_type,
-                    event_target: crate::EventTarget::new(),
-                    owner_document: inner_node.owner_document.clone(),
-                    parent: inner_node.parent.clone(),
-                    children: inner_node
-                        .children
-                        .as_slice()
-                        .iter()
-                        .map(|noderef| ChildNode {
-                            inner: Node {
-                                base: noderef.inner.base.clone(),
-                            },
-                        })
-                        .collect(),
-                    observer_list: vec![],
-                })),
-            }
+            Node::with_base(NodeBase {
+                node_type: inner_node.node_type,
+                event_target: crate::EventTarget::new(),
+                owner_document: inner_node.owner_document.clone(),
+                parent: inner_node.parent.clone(),
+                children: inner_node
+                    .children
+                    .as_slice()
+                    .iter()
+                    .map(|noderef| ChildNode {
+                        inner: Node {
+                            base: noderef.inner.base.clone(),
+                        },
+                    })
+                    .collect(),
+                observer_list: vec![],
+                assigned_slot: None,
+                manual_slot_assignment: None,
+                data: inner_node.data.clone(),
+            })
         }
     }
 
@@ -1,9 +1,12 @@
 use super::trees::{
     following_nodes, is_host_including_inclusive_ancestor, preceeding_nodes, root_of,
+    shadow_including_inclusive_descendants,
 };
 use crate::{
-    assign_slot, domitem::DOMItem, is_shadow_host_and, is_slottable, AsChildNode, AsNode,
-    AsParentNode, ChildNode, DOMException, Node, ParentNode,
+    custom_elements::CustomElementCallback,
+    domitem::DOMItem,
+    slot::{assign_slot, is_shadow_host_and, is_shadow_root, is_slot, is_slottable},
+    AsChildNode, AsNode, AsParentNode, ChildNode, DOMException, Node, ParentNode,
 };
 
 mod append_utils {
@@ -219,17 +222,15 @@ pub fn insert<T: AsNode>(
     // - For each live range whose end node is parent and end offset is greater than child’s index, increase its end offset by count.
     if let Some(ref child) = child {
         let child_index = AsNode::cast(*child).index().unwrap();
-        let mut document = node.owner_document().unwrap();
-        let live_ranges = document.live_ranges_mut();
-        live_ranges
-            .filter(|range| {
-                range.start.node.is_same_node(parent) && range.start.offset > child_index
-            })
-            .for_each(|range| range.start.offset += count);
-        let live_ranges = document.live_ranges_mut();
-        live_ranges
-            .filter(|range| range.end.node.is_same_node(parent) && range.end.offset > child_index)
-            .for_each(|range| range.end.offset += count);
+        let document = node.owner_document().unwrap();
+        document.update_live_ranges(|range| {
+            if range.start.node.is_same_node(parent) && range.start.offset > child_index {
+                range.start.offset += count;
+            }
+            if range.end.node.is_same_node(parent) && range.end.offset > child_index {
+                range.end.offset += count;
+            }
+        });
     }
     // 6. Let previousSibling be child’s previous sibling or parent’s last child if child is null.
     let mut previous_sibling = match child {
@@ -259,14 +260,16 @@ pub fn insert<T: AsNode>(
             assign_slot(node)
         }
         //  5. If parent’s root is a shadow root, and parent is a slot whose assigned nodes is the empty list, then run signal a slot change for parent.
-        if is_shadow_root(root_of(parent)) && is_slot(parent) && assigned_nodes(parent).len() == 0 {
+        if is_shadow_root(&root_of(parent)) && is_slot(parent) && assigned_nodes(parent).is_empty()
+        {
             signal_slot_change(parent)
         }
         //  6. Run assign slottables for a tree with node’s root.
-        assign_slottables(&mut root_of(node));
+        assign_slottables_for_a_tree(&root_of(node));
 
         //  7. For each shadow-including inclusive descendant inclusiveDescendant of node, in shadow-including tree order:
-        for inclusive_descendant in shadow_including_inclusive_descendants(&mut node) {
+        for inclusive_descendant in shadow_including_inclusive_descendants(node) {
+            let inclusive_descendant = &inclusive_descendant;
             // - Run the insertion steps with inclusiveDescendant.
             insertion_steps(inclusive_descendant);
             //      2. If inclusiveDescendant is connected, then:
@@ -276,8 +279,7 @@ pub fn insert<T: AsNode>(
                 if is_custom(inclusive_descendant) {
                     enqueue_custom_element_callback_reaction(
                         inclusive_descendant,
-                        CustomElementCallBack::ConnectedCallBack,
-                        &[],
+                        CustomElementCallback::Connected,
                     );
                 } else {
                     try_upgrade(inclusive_descendant)
@@ -320,7 +322,8 @@ pub fn replace<'child, T: AsNode>(
     node: &mut impl AsNode,
     parent: &mut impl AsParentNode,
 ) -> Result<&'child mut T, DOMException> {
-    let hierarchy_request_error: fn(&str) -> Result<&mut T, DOMException> = hierarchy_request_error;
+    let hierarchy_request_error: fn(&str) -> Result<&'child mut T, DOMException> =
+        hierarchy_request_error;
     let parent_type = parent.node_type();
     let node_type = node.node_type();
     // 1. If parent is not a Document, DocumentFragment, or Element node, then throw a "HierarchyRequestError" DOMException.
@@ -490,52 +493,49 @@ fn children_changed_steps(parent: &impl AsParentNode) {
     todo!()
 }
 
-fn try_upgrade(inclusive_descendant: &mut ChildNode) {
-    todo!()
+fn element_of(node: &Node) -> Option<crate::Element> {
+    node.owner_document()?
+        .lookup_html_element(node.get_base_ptr())
 }
 
-fn is_custom(inclusive_descendant: &mut ChildNode) -> bool {
-    todo!()
+fn try_upgrade(inclusive_descendant: &Node) {
+    if let Some(element) = element_of(inclusive_descendant) {
+        crate::custom_elements::try_upgrade(&element)
+    }
 }
 
-fn enqueue_custom_element_callback_reaction(
-    inclusive_descendant: &mut ChildNode,
-    callback_name: CustomElementCallBack,
-    argument_list: &[()],
-) {
-    todo!()
+fn is_custom(inclusive_descendant: &Node) -> bool {
+    element_of(inclusive_descendant)
+        .is_some_and(|element| crate::custom_elements::is_custom(&element))
 }
 
-fn insertion_steps(inclusive_descendant: &mut ChildNode) {
-    todo!()
+fn enqueue_custom_element_callback_reaction(
+    inclusive_descendant: &Node,
+    callback_name: CustomElementCallback,
+) {
+    if let Some(element) = element_of(inclusive_descendant) {
+        crate::custom_elements::enqueue_callback_reaction(&element, callback_name)
+    }
 }
 
-fn shadow_including_inclusive_descendants<'a>(node: &'a mut ChildNode) -> Vec<&'a mut ChildNode> {
+fn insertion_steps(inclusive_descendant: &Node) {
     todo!()
 }
 
 fn signal_slot_change(parent: &mut impl AsParentNode) {
-    todo!()
-}
-
-fn assign_slottables(node: &mut impl AsParentNode) {
-    todo!()
-}
-
-fn assigned_nodes(parent: &impl AsParentNode) -> &Vec<ChildNode> {
-    todo!()
+    crate::slot::signal_slot_change(parent)
 }
 
-fn is_slot(parent: &impl AsNode) -> bool {
-    todo!()
+fn assign_slottables_for_a_tree(root: &impl AsNode) {
+    crate::slot::assign_slottables_for_a_tree(root)
 }
 
-fn is_shadow_root(parent: ParentNode) -> bool {
-    todo!()
+fn assigned_nodes(parent: &impl AsParentNode) -> Vec<ChildNode> {
+    crate::slot::assigned_nodes(parent)
 }
 
 fn adopt_into_document(document: crate::Document, node: &mut ChildNode) {
-    todo!()
+    document.adopt(AsNode::cast(node))
 }
 
 fn queue_tree_mutation_record(
@@ -552,6 +552,3 @@ fn queue_tree_mutation_record(
 fn remove(child: &mut impl AsNode, suppress_observers: Option<bool>) -> ChildNode {
     todo!()
 }
-pub enum CustomElementCallBack {
-    ConnectedCallBack,
-}
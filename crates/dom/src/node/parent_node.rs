@@ -15,12 +15,26 @@ pub trait AsParentNode: AsNode {
         }
     }
     /// Returns the first child that is an element.
-    fn first_element_child(&self) {
-        todo!()
+    ///
+    /// MDN Reference: [`Element.firstElementChild`](https://developer.mozilla.org/docs/Web/API/Element/firstElementChild)
+    fn first_element_child(&self) -> Option<Element> {
+        let child = self
+            .child_nodes()
+            .iter()
+            .find(|node| node.node_type() == Self::ELEMENT_NODE)
+            .map(|node| AsNode::cast(node).get_base_ptr())?;
+        node_document(self)?.lookup_html_element(child)
     }
     /// Returns the last child that is an element.
-    fn last_element_child(&self) {
-        todo!()
+    ///
+    /// MDN Reference: [`Element.lastElementChild`](https://developer.mozilla.org/docs/Web/API/Element/lastElementChild)
+    fn last_element_child(&self) -> Option<Element> {
+        let child = self
+            .child_nodes()
+            .iter()
+            .rfind(|node| node.node_type() == Self::ELEMENT_NODE)
+            .map(|node| AsNode::cast(node).get_base_ptr())?;
+        node_document(self)?.lookup_html_element(child)
     }
     /// Inserts nodes after the last child of node, while replacing strings in nodes with equivalent Text nodes.
     /// # Errors
@@ -77,8 +91,8 @@ pub trait AsParentNode: AsNode {
 }
 
 use crate::{
-    domitem::DOMItem, AsElement, AsEventTarget, AsNode, DOMException, EventTarget, HTMLCollection,
-    Node,
+    domitem::DOMItem, AsElement, AsEventTarget, AsNode, DOMException, Document, Element,
+    EventTarget, HTMLCollection, Node,
 };
 
 #[derive(Debug)]
@@ -116,3 +130,13 @@ impl AsNode for ParentNode {
     }
 }
 impl AsParentNode for ParentNode {}
+
+/// Returns the document of a node, which is the node itself for documents.
+fn node_document(node: &impl AsNode) -> Option<Document> {
+    AsNode::cast(node)
+        .base()
+        .owner_document
+        .inner
+        .upgrade()
+        .map(Document::with_base)
+}
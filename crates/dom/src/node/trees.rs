@@ -49,8 +49,23 @@ pub fn descendant_nodes(reference: &impl AsNode) -> Vec<&Node> {
     descendants
 }
 
+/// Returns a node, followed by the nodes of its shadow tree and its descendants, in [shadow-including tree order](https://dom.spec.whatwg.org/#concept-shadow-including-tree-order).
+pub fn shadow_including_inclusive_descendants(reference: &impl AsNode) -> Vec<Node> {
+    let mut descendants = vec![AsNode::cast(reference).clone_ref()];
+    if let Some(shadow_root) = crate::slot::shadow_root_of(reference) {
+        for child in shadow_root.child_nodes() {
+            descendants.append(&mut shadow_including_inclusive_descendants(child));
+        }
+    }
+    for child in reference.child_nodes() {
+        descendants.append(&mut shadow_including_inclusive_descendants(child));
+    }
+    descendants
+}
+
+/// Returns the host of a node, if the node is a shadow root.
 pub fn host_of(b: &impl AsNode) -> Option<Node> {
-    None
+    crate::slot::host_of_shadow_root(b).map(|host| AsNode::cast(&host).clone_ref())
 }
 
 /// The root of an object is itself, if its parent is null, or else it is the root of its parent. The root of a tree is any object participating in that tree whose parent is null.
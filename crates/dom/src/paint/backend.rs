@@ -0,0 +1,88 @@
+//! Rendering through the backends of embedders, and the tracking of what changes between the frames they draw.
+use super::{DisplayList, Draw};
+use crate::{layout::Rect, DOMRect, Document, Viewport};
+
+/// Draws display lists, for embedders that show documents with a renderer of their own, like the one of a GUI toolkit.
+///
+/// A frame starts with [`begin_frame`](RenderBackend::begin_frame), with the parts of the viewport that it repaints. Then the items that paint in them are given from the bottom up, with every group that is started also ended, and the frame ends with [`end_frame`](RenderBackend::end_frame).
+///
+/// # Example
+/// ```
+/// use dom::{DOMRect, Document, Draw, Painter, RenderBackend, Viewport};
+///
+/// #[derive(Default)]
+/// struct Recorder {
+///     damage: Vec<DOMRect>,
+///     draws: usize,
+/// }
+///
+/// impl RenderBackend for Recorder {
+///     fn begin_frame(&mut self, _: &Viewport, damage: &[DOMRect]) {
+///         self.damage = damage.to_vec();
+///         self.draws = 0;
+///     }
+///     fn push_opacity(&mut self, _: f64) {}
+///     fn pop_opacity(&mut self) {}
+///     fn draw(&mut self, _: &Draw) {
+///         self.draws += 1;
+///     }
+/// }
+///
+/// let document = Document::parse_html_unsafe("<p>Hello</p>");
+/// let mut painter = Painter::new();
+/// let mut recorder = Recorder::default();
+/// // The first frame repaints the whole viewport.
+/// painter.paint(&document, &mut recorder);
+/// assert_eq!(recorder.damage, [DOMRect::new(0.0, 0.0, 800.0, 600.0)]);
+/// // Nothing changed, so there is no frame.
+/// assert!(painter.paint(&document, &mut recorder).is_empty());
+/// ```
+pub trait RenderBackend {
+    /// Starts a frame of a viewport, which repaints the damaged rectangles, in CSS pixels. What is drawn in the frame should be clipped to them, and the rest of the viewport kept as it was.
+    fn begin_frame(&mut self, viewport: &Viewport, damage: &[DOMRect]);
+    /// Starts a group of items, which is made transparent as a whole with an opacity from zero to one when it ends.
+    fn push_opacity(&mut self, opacity: f64);
+    /// Ends the last group that was started.
+    fn pop_opacity(&mut self);
+    /// Draws a shape.
+    fn draw(&mut self, draw: &Draw);
+    /// Ends the frame.
+    fn end_frame(&mut self) {}
+}
+
+/// Paints a document with a [`RenderBackend`] frame after frame, and tracks what changes between frames, so that after the document changes only the damaged parts of the viewport are repainted.
+///
+/// See [`RenderBackend`] for an example.
+#[derive(Debug, Clone, Default)]
+pub struct Painter {
+    /// The display list of the previous frame.
+    previous: Option<DisplayList>,
+}
+
+impl Painter {
+    pub fn new() -> Self {
+        Painter::default()
+    }
+    /// Paints a frame of a document, if it looks different than in the previous frame, and returns the damaged rectangles that the frame repainted. The first frame repaints the whole viewport.
+    ///
+    /// Only the items that paint in the damaged rectangles are given to the backend.
+    pub fn paint(&mut self, document: &Document, backend: &mut impl RenderBackend) -> Vec<DOMRect> {
+        let list = DisplayList::of(document);
+        let damage = match &self.previous {
+            Some(previous) => list.damage(previous),
+            None => {
+                let viewport = list.viewport();
+                vec![Rect::new(0.0, 0.0, viewport.width, viewport.height)]
+            }
+        };
+        if !damage.is_empty() {
+            list.render_damage(backend, &damage);
+        }
+        self.previous = Some(list);
+        damage.into_iter().map(DOMRect::from).collect()
+    }
+    /// Forgets the previous frame, so that the next one repaints the whole viewport, like after the backend loses what it drew.
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+}
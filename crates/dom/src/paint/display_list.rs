@@ -0,0 +1,616 @@
+//! The display list of a document: the shapes its boxes paint, in the order they are painted.
+use std::{collections::HashMap, rc::Rc};
+
+use super::{Bitmap, Color, RenderBackend};
+use crate::{
+    css::ComputedStyle,
+    domitem::DOMItem,
+    layout::{paint_order, BoxKind, Layout, LayoutBox, Rect, Scope, Sides, Size, Transform},
+    loader::resolve_url,
+    tag::Tag,
+    AsElement, DOMRect, Document, Element, FontDescription, Viewport,
+};
+
+/// What a document paints in its viewport: typed items, from the bottom up, which a [`RenderBackend`] draws.
+///
+/// Coordinates are in CSS pixels, relative to the viewport, before the transform of each item.
+///
+/// # Example
+/// ```
+/// use dom::{DisplayItem, Document, Shape};
+///
+/// let document = Document::parse_html_unsafe("<div style='height: 10px; background: red'></div>");
+/// let list = document.display_list();
+/// let colors: Vec<_> = list
+///     .items()
+///     .iter()
+///     .filter_map(|item| match item {
+///         DisplayItem::Draw(draw) => match &draw.shape {
+///             Shape::Rect { color, .. } => Some((color.red, color.green, color.blue)),
+///             _ => None,
+///         },
+///         _ => None,
+///     })
+///     .collect();
+/// // The white canvas, and the background of the div.
+/// assert_eq!(colors, [(255, 255, 255), (255, 0, 0)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayList {
+    viewport: Viewport,
+    items: Vec<DisplayItem>,
+}
+
+/// An item of a display list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayItem {
+    /// Starts a group of items, which is made transparent as a whole with an opacity from zero to one when it ends.
+    PushOpacity(f64),
+    /// Ends the last group that was started.
+    PopOpacity,
+    Draw(Box<Draw>),
+}
+
+/// A shape, drawn where a box is painted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Draw {
+    pub shape: Shape,
+    /// Maps the coordinates of the shape to the viewport.
+    pub transform: Transform,
+    /// The clips that the shape is drawn inside of, from the outermost in. Nothing outside of any of them is drawn.
+    pub clips: Vec<Clip>,
+}
+
+/// A rectangle that clips what is drawn, like the padding box of an element whose overflow is hidden.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clip {
+    pub rect: DOMRect,
+    /// Maps the coordinates of the rectangle to the viewport.
+    pub transform: Transform,
+}
+
+/// What is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// A rectangle filled with a color, like a background.
+    Rect {
+        rect: DOMRect,
+        radii: Radii,
+        color: Color,
+    },
+    /// The border of a box: the area between its border edge and its padding edge, with the widths and the colors of its top, right, bottom and left sides.
+    Border {
+        rect: DOMRect,
+        widths: [f64; 4],
+        radii: Radii,
+        colors: [Color; 4],
+    },
+    /// A run of text in a font.
+    Text {
+        glyphs: Vec<Glyph>,
+        font: FontDescription,
+        color: Color,
+    },
+    /// An image, scaled to fill a rectangle, with the URL it was loaded from.
+    Image {
+        rect: DOMRect,
+        url: String,
+        bitmap: Rc<Bitmap>,
+    },
+}
+
+/// A character of a run of text, where it is painted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyph {
+    pub character: char,
+    /// The position of the pen on the baseline, where the glyph starts.
+    pub origin: (f64, f64),
+    pub advance: f64,
+    /// The outline of the glyph, relative to its origin, or `None` if the font provider has no outlines, in which case a box is drawn instead.
+    pub outline: Option<Vec<Vec<(f64, f64)>>>,
+}
+
+impl Glyph {
+    /// Returns the area that the glyph covers in a font size: the bounds of its outline, or of the box drawn in its place if it has none.
+    pub(crate) fn bounds(&self, size: f64) -> Rect {
+        let (x, y) = self.origin;
+        let Some(outline) = &self.outline else {
+            // Glyphs without outlines are boxes as tall as lowercase letters, with space on either side.
+            return Rect::new(
+                x + self.advance * 0.1,
+                y - size * 0.5,
+                self.advance * 0.8,
+                size * 0.5,
+            );
+        };
+        let (mut left, mut top, mut right, mut bottom) = (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        );
+        for (point_x, point_y) in outline.iter().flatten() {
+            left = left.min(*point_x);
+            top = top.min(*point_y);
+            right = right.max(*point_x);
+            bottom = bottom.max(*point_y);
+        }
+        match left <= right {
+            true => Rect::new(x + left, y + top, right - left, bottom - top),
+            false => Rect::default(),
+        }
+    }
+}
+
+/// The horizontal and vertical radii of the corners of a box, from the top left corner clockwise, scaled so that they fit in the box.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Radii(pub [(f64, f64); 4]);
+
+impl Radii {
+    /// Resolves the `border-*-radius` properties of a box against its border box, and scales the radii down so that the corners on each side do not overlap.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-backgrounds/#corner-overlap)
+    pub(crate) fn of(style: &ComputedStyle, rect: &Rect) -> Self {
+        let corner = |name: &str| {
+            let mut values = style.get(name).split(' ');
+            let horizontal = values.next().unwrap_or_default();
+            let vertical = values.next().unwrap_or(horizontal);
+            let resolve = |value: &str, basis: f64| {
+                Size::parse(value)
+                    .resolve(Some(basis))
+                    .unwrap_or(0.0)
+                    .max(0.0)
+            };
+            (
+                resolve(horizontal, rect.width),
+                resolve(vertical, rect.height),
+            )
+        };
+        let corners = [
+            corner("border-top-left-radius"),
+            corner("border-top-right-radius"),
+            corner("border-bottom-right-radius"),
+            corner("border-bottom-left-radius"),
+        ];
+        let [top_left, top_right, bottom_right, bottom_left] = corners;
+        let ratio = |length: f64, sum: f64| match sum > 0.0 {
+            true => length / sum,
+            false => f64::INFINITY,
+        };
+        let scale = [
+            ratio(rect.width, top_left.0 + top_right.0),
+            ratio(rect.height, top_right.1 + bottom_right.1),
+            ratio(rect.width, bottom_right.0 + bottom_left.0),
+            ratio(rect.height, bottom_left.1 + top_left.1),
+        ]
+        .into_iter()
+        .fold(1.0, f64::min);
+        Radii(corners.map(|(horizontal, vertical)| (horizontal * scale, vertical * scale)))
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.0
+            .iter()
+            .all(|(horizontal, vertical)| *horizontal <= 0.0 || *vertical <= 0.0)
+    }
+
+    /// Returns the radii of the inner edge of a border with the widths of its sides.
+    pub(crate) fn inner(&self, widths: &Sides) -> Radii {
+        let [top_left, top_right, bottom_right, bottom_left] = self.0;
+        let shrink = |(horizontal, vertical): (f64, f64), x: f64, y: f64| {
+            ((horizontal - x).max(0.0), (vertical - y).max(0.0))
+        };
+        Radii([
+            shrink(top_left, widths.left, widths.top),
+            shrink(top_right, widths.right, widths.top),
+            shrink(bottom_right, widths.right, widths.bottom),
+            shrink(bottom_left, widths.left, widths.bottom),
+        ])
+    }
+
+    /// Returns true if a point is inside a rectangle with these corners.
+    pub(crate) fn contains(&self, rect: &Rect, (x, y): (f64, f64)) -> bool {
+        if !rect.contains((x, y)) {
+            return false;
+        }
+        let [top_left, top_right, bottom_right, bottom_left] = self.0;
+        // The center of each corner's ellipse, and which side of it the corner is on.
+        let corners = [
+            (
+                top_left,
+                (rect.x + top_left.0, rect.y + top_left.1),
+                (-1.0, -1.0),
+            ),
+            (
+                top_right,
+                (rect.right() - top_right.0, rect.y + top_right.1),
+                (1.0, -1.0),
+            ),
+            (
+                bottom_right,
+                (
+                    rect.right() - bottom_right.0,
+                    rect.bottom() - bottom_right.1,
+                ),
+                (1.0, 1.0),
+            ),
+            (
+                bottom_left,
+                (rect.x + bottom_left.0, rect.bottom() - bottom_left.1),
+                (-1.0, 1.0),
+            ),
+        ];
+        corners.iter().all(
+            |((horizontal, vertical), (center_x, center_y), (side_x, side_y))| {
+                let dx = (x - center_x) * side_x;
+                let dy = (y - center_y) * side_y;
+                if *horizontal <= 0.0 || *vertical <= 0.0 || dx <= 0.0 || dy <= 0.0 {
+                    return true;
+                }
+                (dx / horizontal).powi(2) + (dy / vertical).powi(2) <= 1.0
+            },
+        )
+    }
+}
+
+impl DisplayList {
+    /// Lays out a document, and lists what its boxes paint.
+    pub(crate) fn of(document: &Document) -> Self {
+        let layout = Layout::of(document);
+        let viewport = DOMRect::new(0.0, 0.0, layout.viewport.width, layout.viewport.height);
+        let mut builder = Builder {
+            document,
+            items: vec![],
+            groups: vec![],
+            images: HashMap::new(),
+        };
+        // The canvas is white, like the default background of a browser, and the background of the root element, or of the body, covers all of it.
+        builder.draw(
+            Shape::Rect {
+                rect: viewport,
+                radii: Radii::default(),
+                color: Color::WHITE,
+            },
+            &Scope::default(),
+        );
+        let canvas = layout.root.as_ref().and_then(canvas_background);
+        let color = canvas.map_or(Color::TRANSPARENT, |canvas| {
+            Color::parse(canvas.style.get("background-color"))
+        });
+        if color.alpha > 0 {
+            builder.draw(
+                Shape::Rect {
+                    rect: viewport,
+                    radii: Radii::default(),
+                    color,
+                },
+                &Scope::default(),
+            );
+        }
+        for (layout_box, scope) in paint_order(&layout) {
+            builder.enter_groups(&scope.groups);
+            let paints_background = canvas.is_none_or(|canvas| !std::ptr::eq(canvas, layout_box));
+            builder.paint(layout_box, &scope, paints_background);
+        }
+        builder.enter_groups(&[]);
+        DisplayList {
+            viewport: layout.viewport.clone(),
+            items: builder.items,
+        }
+    }
+
+    /// Returns the viewport that the document was painted in.
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// Returns the items of the list, from the bottom up.
+    pub fn items(&self) -> &[DisplayItem] {
+        &self.items
+    }
+
+    /// Draws all of the list with a backend, as a frame that repaints the whole viewport.
+    pub fn render(&self, backend: &mut impl RenderBackend) {
+        let viewport = Rect::new(0.0, 0.0, self.viewport.width, self.viewport.height);
+        self.render_damage(backend, &[viewport]);
+    }
+
+    /// Draws a frame with a backend that repaints the damaged parts of the viewport, with the items that paint in them.
+    pub(crate) fn render_damage(&self, backend: &mut impl RenderBackend, damage: &[Rect]) {
+        let rects: Vec<DOMRect> = damage.iter().copied().map(DOMRect::from).collect();
+        backend.begin_frame(&self.viewport, &rects);
+        for item in &self.items {
+            match item {
+                DisplayItem::PushOpacity(opacity) => backend.push_opacity(*opacity),
+                DisplayItem::PopOpacity => backend.pop_opacity(),
+                DisplayItem::Draw(draw) => {
+                    let bounds = draw.area();
+                    if damage.iter().any(|rect| rect.intersects(&bounds)) {
+                        backend.draw(draw);
+                    }
+                }
+            }
+        }
+        backend.end_frame();
+    }
+
+    /// Returns the parts of the viewport that look different in this list than in a previous one, as rectangles that do not overlap.
+    ///
+    /// The draws of both lists are matched in order, with the groups they are in, and the areas of the ones that are not matched are damaged.
+    pub(crate) fn damage(&self, previous: &DisplayList) -> Vec<Rect> {
+        let viewport = Rect::new(0.0, 0.0, self.viewport.width, self.viewport.height);
+        if self.viewport != previous.viewport {
+            return vec![viewport];
+        }
+        let draws = self.draws();
+        let previous_draws = previous.draws();
+        let mut matched = vec![false; previous_draws.len()];
+        let mut damage = vec![];
+        let mut cursor = 0;
+        for draw in &draws {
+            match previous_draws[cursor..]
+                .iter()
+                .position(|previous| previous == draw)
+            {
+                Some(offset) => {
+                    matched[cursor + offset] = true;
+                    cursor += offset + 1;
+                }
+                None => damage.push(draw.1.area()),
+            }
+        }
+        for (draw, matched) in previous_draws.iter().zip(matched) {
+            if !matched {
+                damage.push(draw.1.area());
+            }
+        }
+        // Overlapping rectangles are merged, so each part of the viewport is repainted once.
+        let mut merged: Vec<Rect> = vec![];
+        for mut rect in damage {
+            rect = rect.intersection(&viewport);
+            if rect.is_empty() {
+                continue;
+            }
+            while let Some(index) = merged.iter().position(|other| other.intersects(&rect)) {
+                rect = rect.union(&merged.remove(index));
+            }
+            merged.push(rect);
+        }
+        merged
+    }
+
+    /// Returns the draws of the list, with the opacities of the groups they are in.
+    fn draws(&self) -> Vec<(Vec<f64>, &Draw)> {
+        let mut groups = vec![];
+        let mut draws = vec![];
+        for item in &self.items {
+            match item {
+                DisplayItem::PushOpacity(opacity) => groups.push(*opacity),
+                DisplayItem::PopOpacity => {
+                    groups.pop();
+                }
+                DisplayItem::Draw(draw) => draws.push((groups.clone(), &**draw)),
+            }
+        }
+        draws
+    }
+}
+
+impl Draw {
+    /// Returns the smallest rectangle in the viewport that contains what is drawn, in CSS pixels.
+    pub fn bounds(&self) -> DOMRect {
+        self.area().into()
+    }
+
+    pub(crate) fn area(&self) -> Rect {
+        let rect = match &self.shape {
+            Shape::Rect { rect, .. } | Shape::Border { rect, .. } | Shape::Image { rect, .. } => {
+                Rect::from(*rect)
+            }
+            Shape::Text { glyphs, font, .. } => glyphs
+                .iter()
+                .map(|glyph| glyph.bounds(font.size))
+                .reduce(|bounds, glyph| bounds.union(&glyph))
+                .unwrap_or_default(),
+        };
+        self.clips
+            .iter()
+            .fold(self.transform.bounds(&rect), |bounds, clip| {
+                bounds.intersection(&clip.transform.bounds(&Rect::from(clip.rect)))
+            })
+    }
+}
+
+/// Returns the box whose background is painted over the whole canvas: the root element's, or the body's if the root element's is transparent.
+///
+/// [Reference](https://drafts.csswg.org/css-backgrounds/#special-backgrounds)
+fn canvas_background(root: &LayoutBox) -> Option<&LayoutBox> {
+    let has_tag = |layout_box: &LayoutBox, tag: Tag| {
+        layout_box
+            .element
+            .as_ref()
+            .is_some_and(|element| element.base().tag == tag)
+    };
+    if Color::parse(root.style.get("background-color")).alpha > 0 || !has_tag(root, Tag::Html) {
+        return Some(root);
+    }
+    root.children
+        .iter()
+        .find(|child| has_tag(child, Tag::Body))
+        .or(Some(root))
+}
+
+struct Builder<'a> {
+    document: &'a Document,
+    items: Vec<DisplayItem>,
+    /// The groups that are open, from the outermost in.
+    groups: Vec<&'a LayoutBox>,
+    /// The images that have been loaded, by their URL.
+    images: HashMap<String, Option<Rc<Bitmap>>>,
+}
+
+impl<'a> Builder<'a> {
+    /// Closes the open groups that a box is not painted in, and opens the ones it is painted in.
+    fn enter_groups(&mut self, groups: &[&'a LayoutBox]) {
+        let common = self
+            .groups
+            .iter()
+            .zip(groups)
+            .take_while(|(open, group)| std::ptr::eq(**open, **group))
+            .count();
+        for _ in common..self.groups.len() {
+            self.items.push(DisplayItem::PopOpacity);
+        }
+        self.groups.truncate(common);
+        for group in &groups[common..] {
+            self.items.push(DisplayItem::PushOpacity(group.opacity()));
+            self.groups.push(group);
+        }
+    }
+
+    fn draw(&mut self, shape: Shape, scope: &Scope) {
+        let clips = scope
+            .clips
+            .iter()
+            .map(|clip| Clip {
+                rect: clip.rect.into(),
+                transform: clip.transform,
+            })
+            .collect();
+        self.items.push(DisplayItem::Draw(Box::new(Draw {
+            shape,
+            transform: scope.transform,
+            clips,
+        })));
+    }
+
+    fn paint(&mut self, layout_box: &LayoutBox, scope: &Scope, paints_background: bool) {
+        if layout_box.style.get("visibility") != "visible" {
+            return;
+        }
+        match &layout_box.kind {
+            BoxKind::Text(_) => self.text(layout_box, scope),
+            BoxKind::LineBreak => {}
+            // The left border of an inline box is on its first line, and the right border on its last line.
+            BoxKind::Inline => {
+                let count = layout_box.fragments.len();
+                for (index, fragment) in layout_box.fragments.iter().enumerate() {
+                    let mut widths = layout_box.border;
+                    if index > 0 {
+                        widths.left = 0.0;
+                    }
+                    if index + 1 < count {
+                        widths.right = 0.0;
+                    }
+                    self.decorations(layout_box, fragment, widths, scope, paints_background);
+                }
+            }
+            BoxKind::Flow | BoxKind::Replaced { .. } => {
+                let rect = layout_box.rect;
+                self.decorations(
+                    layout_box,
+                    &rect,
+                    layout_box.border,
+                    scope,
+                    paints_background,
+                );
+                let image = match (&layout_box.kind, &layout_box.element) {
+                    (BoxKind::Replaced { .. }, Some(element)) => self.image(element),
+                    _ => None,
+                };
+                if let Some((url, bitmap)) = image {
+                    let rect = rect.shrink(&layout_box.border).shrink(&layout_box.padding);
+                    let rect = rect.into();
+                    self.draw(Shape::Image { rect, url, bitmap }, scope);
+                }
+            }
+        }
+    }
+
+    /// Paints the background and the border of a box, or of a fragment of an inline box.
+    fn decorations(
+        &mut self,
+        layout_box: &LayoutBox,
+        rect: &Rect,
+        widths: Sides,
+        scope: &Scope,
+        paints_background: bool,
+    ) {
+        let style = &layout_box.style;
+        let radii = Radii::of(style, rect);
+        let color = Color::parse(style.get("background-color"));
+        if paints_background && color.alpha > 0 {
+            let rect = (*rect).into();
+            self.draw(Shape::Rect { rect, radii, color }, scope);
+        }
+        if widths.horizontal() + widths.vertical() > 0.0 {
+            let colors = ["top", "right", "bottom", "left"]
+                .map(|side| Color::parse(style.get(&format!("border-{side}-color"))));
+            let shape = Shape::Border {
+                rect: (*rect).into(),
+                widths: [widths.top, widths.right, widths.bottom, widths.left],
+                radii,
+                colors,
+            };
+            self.draw(shape, scope);
+        }
+    }
+
+    /// Paints the runs of a text box, glyph by glyph.
+    fn text(&mut self, layout_box: &LayoutBox, scope: &Scope) {
+        let color = Color::parse(layout_box.style.get("color"));
+        if color.alpha == 0 {
+            return;
+        }
+        let font = &layout_box.font;
+        let ascent = font.metrics().ascent;
+        for run in &layout_box.text_runs {
+            let baseline = run.rect.y + ascent;
+            let mut x = run.rect.x;
+            let mut glyphs = vec![];
+            for character in run.text.chars() {
+                let advance = font.advance(character);
+                if !character.is_whitespace() {
+                    glyphs.push(Glyph {
+                        character,
+                        origin: (x, baseline),
+                        advance,
+                        outline: font.outline(character),
+                    });
+                }
+                x += advance;
+            }
+            if !glyphs.is_empty() {
+                let font = font.description().clone();
+                self.draw(
+                    Shape::Text {
+                        glyphs,
+                        font,
+                        color,
+                    },
+                    scope,
+                );
+            }
+        }
+    }
+
+    /// Loads the image of an `img` element through the document's resource loader, and returns it with its URL. Images that cannot be loaded or decoded are not painted.
+    fn image(&mut self, element: &Element) -> Option<(String, Rc<Bitmap>)> {
+        if element.base().tag != Tag::Img {
+            return None;
+        }
+        let src = element.get_attribute("src")?;
+        let url = resolve_url(&self.document.state().url, src);
+        let document = self.document;
+        let bitmap = self
+            .images
+            .entry(url.clone())
+            .or_insert_with_key(|url| {
+                let loader = document.state().resource_loader.clone()?;
+                let data = loader.load_bytes(url)?;
+                Bitmap::from_png(&data).ok().map(Rc::new)
+            })
+            .clone()?;
+        Some((url, bitmap))
+    }
+}
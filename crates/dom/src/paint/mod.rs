@@ -0,0 +1,142 @@
+//! Painting: the display list of a laid out document, which embedders draw with a [`RenderBackend`] of their own, and its rasterization into a [`Bitmap`] on the CPU.
+//!
+//! Painting follows the painting order of layout, so what is on top in hit testing is also on top in the picture. Everything is drawn in CSS pixels, scaled by the device pixel ratio of the viewport, and antialiased.
+//!
+//! [Reference](https://drafts.csswg.org/css2/#painting-model)
+mod backend;
+mod display_list;
+mod png;
+mod raster;
+
+use std::path::Path;
+
+use crate::DOMException;
+
+pub use backend::{Painter, RenderBackend};
+pub use display_list::{Clip, DisplayItem, DisplayList, Draw, Glyph, Radii, Shape};
+pub(crate) use raster::rasterize;
+
+/// An image made of RGBA pixels, with 8 bits a channel and alpha that is not premultiplied, like a picture of a rendered document.
+///
+/// # Example
+/// ```
+/// use dom::Bitmap;
+///
+/// let bitmap = Bitmap::from_pixels(1, 1, vec![255, 0, 0, 255]).unwrap();
+/// let png = bitmap.to_png();
+/// assert_eq!(Bitmap::from_png(&png).unwrap(), bitmap);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Creates a transparent bitmap.
+    pub fn new(width: u32, height: u32) -> Self {
+        Bitmap {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+    /// Creates a bitmap from its pixels, row by row from the top left, with four bytes for each pixel.
+    ///
+    /// Returns an `IndexSizeError` if there are not four bytes for each pixel.
+    pub fn from_pixels(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self, DOMException> {
+        if pixels.len() != width as usize * height as usize * 4 {
+            return Err(DOMException::IndexSizeError(format!(
+                "A {width}×{height} bitmap has {} bytes of pixels, not {}",
+                width as usize * height as usize * 4,
+                pixels.len()
+            )));
+        }
+        Ok(Bitmap {
+            width,
+            height,
+            pixels,
+        })
+    }
+    /// Decodes a PNG image.
+    ///
+    /// Returns a `NotSupportedError` if the data is not a PNG image, or is interlaced, which is not supported.
+    pub fn from_png(data: &[u8]) -> Result<Self, DOMException> {
+        let (width, height, pixels) = png::decode(data)?;
+        Bitmap::from_pixels(width, height, pixels)
+    }
+    /// Reads a PNG image from a file.
+    ///
+    /// Returns a `NotFoundError` if the file cannot be read, and a `NotSupportedError` if it is not a PNG image.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DOMException> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|error| {
+            DOMException::NotFoundError(format!("Cannot read {}: {error}", path.display()))
+        })?;
+        Bitmap::from_png(&data)
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    /// Returns the pixels of the bitmap, row by row from the top left, with four bytes for each pixel.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+    /// Returns the red, green, blue and alpha of a pixel, or `None` if it is outside the bitmap.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        Some([
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+            self.pixels[index + 3],
+        ])
+    }
+    /// Encodes the bitmap as a PNG image.
+    pub fn to_png(&self) -> Vec<u8> {
+        png::encode(self.width, self.height, &self.pixels)
+    }
+}
+
+/// A color in sRGB, with alpha that is not premultiplied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Color {
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+    pub const WHITE: Color = Color::rgba(255, 255, 255, 255);
+
+    pub const fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Parses a computed color, like `rgb(255, 0, 0)`. Colors that cannot be parsed are transparent.
+    pub(crate) fn parse(value: &str) -> Self {
+        match crate::css::parse_color(value) {
+            Some(([red, green, blue], alpha)) => Color::rgba(
+                red,
+                green,
+                blue,
+                (alpha.clamp(0.0, 1.0) * 255.0).round() as u8,
+            ),
+            None => Color::TRANSPARENT,
+        }
+    }
+}
@@ -0,0 +1,531 @@
+//! PNG files: the encoding of bitmaps, and the decoding of the images that documents show.
+//!
+//! Pixels are RGBA, with 8 bits a channel and alpha that is not premultiplied, which is how PNG stores them.
+//!
+//! [Reference](https://www.w3.org/TR/png-3/)
+use crate::DOMException;
+
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// Encodes RGBA pixels as a PNG file.
+pub(crate) fn encode(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    let mut png = SIGNATURE.to_vec();
+    let mut header = vec![];
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // 8 bits a channel, RGBA, and no interlacing.
+    header.extend([8, 6, 0, 0, 0]);
+    write_chunk(&mut png, b"IHDR", &header);
+    // Each row is filtered by subtracting the row above, so that flat areas become runs of zeros.
+    let stride = width as usize * 4;
+    let mut filtered = Vec::with_capacity((stride + 1) * height as usize);
+    let mut above = vec![0; stride];
+    for row in pixels.chunks_exact(stride.max(1)).take(height as usize) {
+        filtered.push(2);
+        filtered.extend(
+            row.iter()
+                .zip(&above)
+                .map(|(byte, up)| byte.wrapping_sub(*up)),
+        );
+        above.copy_from_slice(row);
+    }
+    write_chunk(&mut png, b"IDAT", &zlib::compress(&filtered));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    png.extend(crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+/// Computes the CRC-32 checksum of the type and data of a chunk.
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => 0xEDB8_8320 ^ (crc >> 1),
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+/// Decodes a PNG file into its width, height and RGBA pixels.
+///
+/// Every color type and bit depth is supported, but not interlaced images. Returns a `NotSupportedError` if the data is not a PNG file that can be decoded.
+pub(crate) fn decode(data: &[u8]) -> Result<(u32, u32, Vec<u8>), DOMException> {
+    let invalid =
+        || DOMException::NotSupportedError(String::from("The data is not a valid PNG image"));
+    if !data.starts_with(&SIGNATURE) {
+        return Err(invalid());
+    }
+    let mut position = SIGNATURE.len();
+    let mut header = None;
+    let mut palette: Vec<[u8; 4]> = vec![];
+    let mut transparency: Vec<u8> = vec![];
+    let mut compressed = vec![];
+    while position + 8 <= data.len() {
+        let length = u32::from_be_bytes(data[position..position + 4].try_into().unwrap()) as usize;
+        let kind = &data[position + 4..position + 8];
+        let contents = data
+            .get(position + 8..position + 8 + length)
+            .ok_or_else(invalid)?;
+        position += length + 12;
+        match kind {
+            b"IHDR" if contents.len() >= 13 => header = Some(contents.to_vec()),
+            b"PLTE" => {
+                palette = contents
+                    .chunks_exact(3)
+                    .map(|color| [color[0], color[1], color[2], 255])
+                    .collect();
+            }
+            b"tRNS" => transparency = contents.to_vec(),
+            b"IDAT" => compressed.extend_from_slice(contents),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+    let header = header.ok_or_else(invalid)?;
+    let width = u32::from_be_bytes(header[0..4].try_into().unwrap());
+    let height = u32::from_be_bytes(header[4..8].try_into().unwrap());
+    let (depth, color_type, interlace) = (header[8] as usize, header[9], header[12]);
+    if interlace != 0 {
+        return Err(DOMException::NotSupportedError(String::from(
+            "Interlaced PNG images are not supported",
+        )));
+    }
+    let channels = match color_type {
+        0 => 1,
+        2 => 3,
+        3 => 1,
+        4 => 2,
+        6 => 4,
+        _ => return Err(invalid()),
+    };
+    if !matches!(depth, 1 | 2 | 4 | 8 | 16) || width == 0 || height == 0 {
+        return Err(invalid());
+    }
+    for (color, alpha) in palette.iter_mut().zip(&transparency) {
+        color[3] = *alpha;
+    }
+    let raw = zlib::decompress(compressed.get(2..).ok_or_else(invalid)?).ok_or_else(invalid)?;
+    let bits_per_pixel = channels * depth;
+    let stride = (width as usize * bits_per_pixel).div_ceil(8);
+    let step = bits_per_pixel.div_ceil(8);
+    let rows = unfilter(&raw, stride, step, height as usize).ok_or_else(invalid)?;
+
+    // Samples are scaled to 8 bits: 16-bit samples keep their high byte, and samples with fewer bits are repeated.
+    let sample = |row: &[u8], index: usize| -> usize {
+        match depth {
+            16 => row[index * 2] as usize,
+            8 => row[index] as usize,
+            _ => {
+                let per_byte = 8 / depth;
+                let shift = 8 - depth * (index % per_byte + 1);
+                (row[index / per_byte] as usize >> shift) & ((1 << depth) - 1)
+            }
+        }
+    };
+    let scale = |value: usize| match depth {
+        16 | 8 => value as u8,
+        _ => (value * 255 / ((1 << depth) - 1)) as u8,
+    };
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    for row in rows.chunks_exact(stride) {
+        for x in 0..width as usize {
+            let pixel = match color_type {
+                0 => {
+                    let gray = scale(sample(row, x));
+                    [gray, gray, gray, 255]
+                }
+                2 => [
+                    scale(sample(row, x * 3)),
+                    scale(sample(row, x * 3 + 1)),
+                    scale(sample(row, x * 3 + 2)),
+                    255,
+                ],
+                3 => *palette.get(sample(row, x)).ok_or_else(invalid)?,
+                4 => {
+                    let gray = scale(sample(row, x * 2));
+                    [gray, gray, gray, scale(sample(row, x * 2 + 1))]
+                }
+                _ => [
+                    scale(sample(row, x * 4)),
+                    scale(sample(row, x * 4 + 1)),
+                    scale(sample(row, x * 4 + 2)),
+                    scale(sample(row, x * 4 + 3)),
+                ],
+            };
+            pixels.extend(pixel);
+        }
+    }
+    Ok((width, height, pixels))
+}
+
+/// Reverses the filters of the rows of an image, given the length of a row in bytes and the number of bytes in a pixel, rounded up.
+///
+/// [Reference](https://www.w3.org/TR/png-3/#9Filter-types)
+fn unfilter(raw: &[u8], stride: usize, step: usize, height: usize) -> Option<Vec<u8>> {
+    let mut rows = vec![0u8; stride * height];
+    for row in 0..height {
+        let start = row * (stride + 1);
+        let filter = *raw.get(start)?;
+        let line = raw.get(start + 1..start + 1 + stride)?;
+        let (before, current) = rows.split_at_mut(row * stride);
+        let above = before
+            .get(before.len().saturating_sub(stride)..)
+            .filter(|_| row > 0);
+        let current = &mut current[..stride];
+        for index in 0..stride {
+            let left = match index >= step {
+                true => current[index - step] as i16,
+                false => 0,
+            };
+            let up = above.map_or(0, |above| above[index] as i16);
+            let up_left = match index >= step {
+                true => above.map_or(0, |above| above[index - step] as i16),
+                false => 0,
+            };
+            let predictor = match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => (left + up) / 2,
+                4 => {
+                    let estimate = left + up - up_left;
+                    let (a, b, c) = (
+                        (estimate - left).abs(),
+                        (estimate - up).abs(),
+                        (estimate - up_left).abs(),
+                    );
+                    match (a <= b && a <= c, b <= c) {
+                        (true, _) => left,
+                        (false, true) => up,
+                        (false, false) => up_left,
+                    }
+                }
+                _ => return None,
+            };
+            current[index] = line[index].wrapping_add(predictor as u8);
+        }
+    }
+    Some(rows)
+}
+
+/// The zlib format, with DEFLATE compression.
+///
+/// [Reference](https://www.rfc-editor.org/rfc/rfc1951)
+mod zlib {
+    const LENGTH_BASES: [u16; 29] = [
+        3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+        131, 163, 195, 227, 258,
+    ];
+    const LENGTH_EXTRA_BITS: [u8; 29] = [
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+    ];
+    const DISTANCE_BASES: [u16; 30] = [
+        1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+        2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+    ];
+    const DISTANCE_EXTRA_BITS: [u8; 30] = [
+        0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12,
+        13, 13,
+    ];
+    /// The order that the lengths of the code length codes are stored in.
+    const CODE_LENGTH_ORDER: [usize; 19] = [
+        16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+    ];
+    const WINDOW: usize = 32768;
+
+    /// Compresses data into a zlib stream, with a single block of fixed Huffman codes, and repeated strings found with a hash of their first three bytes.
+    pub(super) fn compress(data: &[u8]) -> Vec<u8> {
+        let mut writer = BitWriter {
+            bytes: vec![0x78, 0x01],
+            buffer: 0,
+            count: 0,
+        };
+        // The final block, with fixed codes.
+        writer.bits(1, 1);
+        writer.bits(1, 2);
+        let mut heads = vec![usize::MAX; 1 << 15];
+        let hash = |index: usize| {
+            ((data[index] as usize) << 10
+                ^ (data[index + 1] as usize) << 5
+                ^ data[index + 2] as usize)
+                & ((1 << 15) - 1)
+        };
+        let mut index = 0;
+        while index < data.len() {
+            let mut length = 0;
+            let mut distance = 0;
+            if index + 3 <= data.len() {
+                let key = hash(index);
+                let candidate = heads[key];
+                heads[key] = index;
+                if candidate != usize::MAX && index - candidate <= WINDOW {
+                    let limit = (data.len() - index).min(258);
+                    while length < limit && data[candidate + length] == data[index + length] {
+                        length += 1;
+                    }
+                    distance = index - candidate;
+                }
+            }
+            if length < 3 {
+                writer.literal(data[index] as u16);
+                index += 1;
+                continue;
+            }
+            let code = LENGTH_BASES.partition_point(|base| *base as usize <= length) - 1;
+            writer.literal(257 + code as u16);
+            writer.bits(
+                (length - LENGTH_BASES[code] as usize) as u32,
+                LENGTH_EXTRA_BITS[code],
+            );
+            let code = DISTANCE_BASES.partition_point(|base| *base as usize <= distance) - 1;
+            writer.code(code as u32, 5);
+            writer.bits(
+                (distance - DISTANCE_BASES[code] as usize) as u32,
+                DISTANCE_EXTRA_BITS[code],
+            );
+            // The strings inside the match can be found by later matches too.
+            for inner in index + 1..(index + length).min(data.len().saturating_sub(2)) {
+                heads[hash(inner)] = inner;
+            }
+            index += length;
+        }
+        writer.literal(256);
+        let mut bytes = writer.finish();
+        bytes.extend(adler32(data).to_be_bytes());
+        bytes
+    }
+
+    fn adler32(data: &[u8]) -> u32 {
+        let (mut a, mut b) = (1u32, 0u32);
+        for chunk in data.chunks(5552) {
+            for byte in chunk {
+                a += *byte as u32;
+                b += a;
+            }
+            a %= 65521;
+            b %= 65521;
+        }
+        b << 16 | a
+    }
+
+    /// Writes bits from the least significant bit of each byte up.
+    struct BitWriter {
+        bytes: Vec<u8>,
+        buffer: u32,
+        count: u8,
+    }
+
+    impl BitWriter {
+        fn bits(&mut self, value: u32, count: u8) {
+            for bit in 0..count {
+                self.buffer |= ((value >> bit) & 1) << self.count;
+                self.count += 1;
+                if self.count == 8 {
+                    self.bytes.push(self.buffer as u8);
+                    self.buffer = 0;
+                    self.count = 0;
+                }
+            }
+        }
+
+        /// Writes a Huffman code, which starts from its most significant bit.
+        fn code(&mut self, code: u32, length: u8) {
+            for bit in (0..length).rev() {
+                self.bits((code >> bit) & 1, 1);
+            }
+        }
+
+        /// Writes a literal or length symbol with the fixed Huffman codes.
+        fn literal(&mut self, symbol: u16) {
+            let symbol = symbol as u32;
+            match symbol {
+                0..=143 => self.code(0x30 + symbol, 8),
+                144..=255 => self.code(0x190 + symbol - 144, 9),
+                256..=279 => self.code(symbol - 256, 7),
+                _ => self.code(0xC0 + symbol - 280, 8),
+            }
+        }
+
+        fn finish(mut self) -> Vec<u8> {
+            if self.count > 0 {
+                self.bytes.push(self.buffer as u8);
+            }
+            self.bytes
+        }
+    }
+
+    /// Decompresses the DEFLATE data of a zlib stream, after its two header bytes. Returns `None` if the data is not valid.
+    pub(super) fn decompress(data: &[u8]) -> Option<Vec<u8>> {
+        let mut reader = BitReader {
+            data,
+            position: 0,
+            bit: 0,
+        };
+        let mut output = vec![];
+        loop {
+            let is_final = reader.bits(1)? == 1;
+            match reader.bits(2)? {
+                0 => {
+                    reader.align();
+                    let length = reader.bits(16)? as usize;
+                    reader.bits(16)?;
+                    let start = reader.position;
+                    output.extend_from_slice(data.get(start..start + length)?);
+                    reader.position += length;
+                }
+                1 => {
+                    let mut lengths = [0u8; 288];
+                    lengths[..144].fill(8);
+                    lengths[144..256].fill(9);
+                    lengths[256..280].fill(7);
+                    lengths[280..].fill(8);
+                    let literals = Huffman::new(&lengths);
+                    let distances = Huffman::new(&[5; 30]);
+                    reader.block(&literals, &distances, &mut output)?;
+                }
+                2 => {
+                    let (literals, distances) = reader.dynamic_codes()?;
+                    reader.block(&literals, &distances, &mut output)?;
+                }
+                _ => return None,
+            }
+            if is_final {
+                return Some(output);
+            }
+        }
+    }
+
+    /// A canonical Huffman code, given by the number of codes of each length and the symbols in order of their codes.
+    struct Huffman {
+        counts: [u16; 16],
+        symbols: Vec<u16>,
+    }
+
+    impl Huffman {
+        fn new(lengths: &[u8]) -> Self {
+            let mut counts = [0; 16];
+            for length in lengths {
+                counts[*length as usize] += 1;
+            }
+            counts[0] = 0;
+            let mut symbols: Vec<u16> = (0..lengths.len() as u16)
+                .filter(|symbol| lengths[*symbol as usize] != 0)
+                .collect();
+            symbols.sort_by_key(|symbol| lengths[*symbol as usize]);
+            Huffman { counts, symbols }
+        }
+    }
+
+    struct BitReader<'a> {
+        data: &'a [u8],
+        position: usize,
+        bit: u8,
+    }
+
+    impl BitReader<'_> {
+        fn bits(&mut self, count: u8) -> Option<u32> {
+            let mut value = 0;
+            for index in 0..count {
+                let byte = *self.data.get(self.position)?;
+                value |= (((byte >> self.bit) & 1) as u32) << index;
+                self.bit += 1;
+                if self.bit == 8 {
+                    self.bit = 0;
+                    self.position += 1;
+                }
+            }
+            Some(value)
+        }
+
+        fn align(&mut self) {
+            if self.bit > 0 {
+                self.bit = 0;
+                self.position += 1;
+            }
+        }
+
+        /// Reads a symbol, one bit of its code at a time.
+        fn decode(&mut self, huffman: &Huffman) -> Option<u16> {
+            let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+            for length in 1..16 {
+                code |= self.bits(1)? as i32;
+                let count = huffman.counts[length] as i32;
+                if code - count < first {
+                    return huffman
+                        .symbols
+                        .get((index + code - first) as usize)
+                        .copied();
+                }
+                index += count;
+                first = (first + count) << 1;
+                code <<= 1;
+            }
+            None
+        }
+
+        fn dynamic_codes(&mut self) -> Option<(Huffman, Huffman)> {
+            let literal_count = self.bits(5)? as usize + 257;
+            let distance_count = self.bits(5)? as usize + 1;
+            let code_length_count = self.bits(4)? as usize + 4;
+            let mut code_lengths = [0u8; 19];
+            for index in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+                code_lengths[*index] = self.bits(3)? as u8;
+            }
+            let code_lengths = Huffman::new(&code_lengths);
+            let mut lengths = vec![];
+            while lengths.len() < literal_count + distance_count {
+                let (length, repeat) = match self.decode(&code_lengths)? {
+                    symbol @ 0..=15 => (symbol as u8, 1),
+                    16 => (*lengths.last()?, 3 + self.bits(2)?),
+                    17 => (0, 3 + self.bits(3)?),
+                    _ => (0, 11 + self.bits(7)?),
+                };
+                lengths.extend(std::iter::repeat_n(length, repeat as usize));
+            }
+            if lengths.len() > literal_count + distance_count {
+                return None;
+            }
+            Some((
+                Huffman::new(&lengths[..literal_count]),
+                Huffman::new(&lengths[literal_count..]),
+            ))
+        }
+
+        fn block(
+            &mut self,
+            literals: &Huffman,
+            distances: &Huffman,
+            output: &mut Vec<u8>,
+        ) -> Option<()> {
+            loop {
+                let symbol = self.decode(literals)? as usize;
+                match symbol {
+                    0..=255 => output.push(symbol as u8),
+                    256 => return Some(()),
+                    _ => {
+                        let code = symbol - 257;
+                        let length = *LENGTH_BASES.get(code)? as usize
+                            + self.bits(LENGTH_EXTRA_BITS[code])? as usize;
+                        let code = self.decode(distances)? as usize;
+                        let distance = *DISTANCE_BASES.get(code)? as usize
+                            + self.bits(DISTANCE_EXTRA_BITS[code])? as usize;
+                        let start = output.len().checked_sub(distance)?;
+                        for index in 0..length {
+                            output.push(output[start + index]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
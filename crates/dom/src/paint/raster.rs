@@ -0,0 +1,360 @@
+//! The rasterization of a display list into a bitmap, on the CPU.
+//!
+//! Shapes are antialiased by sampling each pixel on a 4×4 grid, and blended in premultiplied alpha. Groups are drawn into a layer of their own, which is blended with its opacity when the group ends.
+use super::{Bitmap, Color, DisplayList, Draw, Glyph, RenderBackend, Shape};
+use crate::{
+    layout::{Rect, Sides, Transform},
+    DOMRect, Viewport,
+};
+
+/// The number of samples along each side of a pixel.
+const SAMPLES: usize = 4;
+
+/// A pixel, with premultiplied alpha, each channel from zero to one.
+type Pixel = [f32; 4];
+
+/// Draws a display list into a bitmap of the size of its viewport in device pixels.
+pub(crate) fn rasterize(list: &DisplayList) -> Bitmap {
+    let mut canvas = Canvas {
+        width: 0,
+        height: 0,
+        scale: 1.0,
+        layers: vec![],
+    };
+    list.render(&mut canvas);
+    while canvas.layers.len() > 1 {
+        canvas.pop_layer();
+    }
+    let pixels = canvas.layers[0]
+        .0
+        .iter()
+        .flat_map(|[red, green, blue, alpha]| {
+            let unpremultiply = |channel: f32| match *alpha > 0.0 {
+                true => to_byte(channel / alpha),
+                false => 0,
+            };
+            [
+                unpremultiply(*red),
+                unpremultiply(*green),
+                unpremultiply(*blue),
+                to_byte(*alpha),
+            ]
+        })
+        .collect();
+    Bitmap::from_pixels(canvas.width as u32, canvas.height as u32, pixels)
+        .expect("The canvas has four bytes for each pixel.")
+}
+
+fn to_byte(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn premultiply(color: Color) -> Pixel {
+    let alpha = color.alpha as f32 / 255.0;
+    [
+        color.red as f32 / 255.0 * alpha,
+        color.green as f32 / 255.0 * alpha,
+        color.blue as f32 / 255.0 * alpha,
+        alpha,
+    ]
+}
+
+/// Blends a pixel over another.
+fn blend(destination: &mut Pixel, source: Pixel) {
+    let remaining = 1.0 - source[3];
+    for (destination, source) in destination.iter_mut().zip(source) {
+        *destination = source + *destination * remaining;
+    }
+}
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    scale: f64,
+    /// The layer of the canvas, and a layer for each open group, with its opacity.
+    layers: Vec<(Vec<Pixel>, f32)>,
+}
+
+/// A clip, with the transform from device pixels to its coordinates.
+struct DeviceClip {
+    rect: Rect,
+    inverse: Transform,
+}
+
+/// The canvas draws whole frames, on layers that it creates when a frame begins.
+impl RenderBackend for Canvas {
+    fn begin_frame(&mut self, viewport: &Viewport, _: &[DOMRect]) {
+        self.scale = viewport.device_pixel_ratio;
+        self.width = (viewport.width * self.scale).ceil().max(0.0) as usize;
+        self.height = (viewport.height * self.scale).ceil().max(0.0) as usize;
+        self.layers = vec![(vec![[0.0; 4]; self.width * self.height], 1.0)];
+    }
+
+    fn push_opacity(&mut self, opacity: f64) {
+        self.layers
+            .push((vec![[0.0; 4]; self.width * self.height], opacity as f32));
+    }
+
+    fn pop_opacity(&mut self) {
+        if self.layers.len() > 1 {
+            self.pop_layer();
+        }
+    }
+
+    fn draw(&mut self, draw: &Draw) {
+        self.draw_shape(draw);
+    }
+}
+
+impl Canvas {
+    fn pop_layer(&mut self) {
+        let Some((layer, opacity)) = self.layers.pop() else {
+            return;
+        };
+        let below = &mut self
+            .layers
+            .last_mut()
+            .expect("The canvas is never popped.")
+            .0;
+        for (destination, source) in below.iter_mut().zip(layer) {
+            blend(destination, source.map(|channel| channel * opacity));
+        }
+    }
+
+    /// Returns the bounds of a rectangle in device pixels after a transform, as the range of columns and rows it covers inside the canvas.
+    fn device_bounds(&self, rect: &Rect, transform: &Transform) -> (usize, usize, usize, usize) {
+        let bounds = transform.bounds(rect);
+        let clamp = |value: f64, limit: usize| value.clamp(0.0, limit as f64) as usize;
+        (
+            clamp(bounds.x.floor(), self.width),
+            clamp(bounds.y.floor(), self.height),
+            clamp(bounds.right().ceil(), self.width),
+            clamp(bounds.bottom().ceil(), self.height),
+        )
+    }
+
+    fn draw_shape(&mut self, draw: &Draw) {
+        let device = Transform::scale(self.scale, self.scale);
+        let transform = device.multiply(&draw.transform);
+        let Some(inverse) = transform.inverse() else {
+            return;
+        };
+        let mut bounds = (0, 0, self.width, self.height);
+        let mut clips = vec![];
+        for clip in &draw.clips {
+            let clip_transform = device.multiply(&clip.transform);
+            let Some(clip_inverse) = clip_transform.inverse() else {
+                return;
+            };
+            let rect = Rect::from(clip.rect);
+            bounds = intersect(bounds, self.device_bounds(&rect, &clip_transform));
+            clips.push(DeviceClip {
+                rect,
+                inverse: clip_inverse,
+            });
+        }
+        let sample = |point: (f64, f64)| {
+            clips
+                .iter()
+                .all(|clip| clip.rect.contains(clip.inverse.apply(point)))
+                .then(|| inverse.apply(point))
+        };
+        match &draw.shape {
+            Shape::Rect { rect, radii, color } => {
+                let rect = Rect::from(*rect);
+                let color = premultiply(*color);
+                let bounds = intersect(bounds, self.device_bounds(&rect, &transform));
+                self.fill(bounds, |point| {
+                    let local = sample(point)?;
+                    radii.contains(&rect, local).then_some(color)
+                });
+            }
+            Shape::Border {
+                rect,
+                widths,
+                radii,
+                colors,
+            } => {
+                let rect = Rect::from(*rect);
+                let [top, right, bottom, left] = *widths;
+                let widths = Sides {
+                    top,
+                    right,
+                    bottom,
+                    left,
+                };
+                let colors = colors.map(premultiply);
+                let inner = rect.shrink(&widths);
+                let inner_radii = radii.inner(&widths);
+                let bounds = intersect(bounds, self.device_bounds(&rect, &transform));
+                self.fill(bounds, |point| {
+                    let local = sample(point)?;
+                    let is_border =
+                        radii.contains(&rect, local) && !inner_radii.contains(&inner, local);
+                    is_border.then(|| colors[border_side(&rect, &widths, local)])
+                });
+            }
+            Shape::Text {
+                glyphs,
+                font,
+                color,
+            } => {
+                let color = premultiply(*color);
+                for glyph in glyphs {
+                    let shape = GlyphShape::of(glyph, font.size);
+                    let bounds = intersect(bounds, self.device_bounds(&shape.bounds, &transform));
+                    self.fill(bounds, |point| {
+                        let local = sample(point)?;
+                        shape.contains(local).then_some(color)
+                    });
+                }
+            }
+            Shape::Image { rect, bitmap, .. } => {
+                if bitmap.width() == 0 || bitmap.height() == 0 {
+                    return;
+                }
+                let rect = Rect::from(*rect);
+                let bounds = intersect(bounds, self.device_bounds(&rect, &transform));
+                self.fill(bounds, |point| {
+                    let local = sample(point)?;
+                    rect.contains(local)
+                        .then(|| sample_bitmap(bitmap, &rect, local))
+                });
+            }
+        }
+    }
+
+    /// Blends the samples of a shape into the pixels in bounds, where `paint` returns the color of the shape at a point in device pixels, or `None` outside of it.
+    fn fill(
+        &mut self,
+        (left, top, right, bottom): (usize, usize, usize, usize),
+        paint: impl Fn((f64, f64)) -> Option<Pixel>,
+    ) {
+        let width = self.width;
+        let layer = &mut self
+            .layers
+            .last_mut()
+            .expect("The canvas is never popped.")
+            .0;
+        let weight = 1.0 / (SAMPLES * SAMPLES) as f32;
+        for y in top..bottom {
+            for x in left..right {
+                let mut total = [0.0; 4];
+                for row in 0..SAMPLES {
+                    for column in 0..SAMPLES {
+                        let point = (
+                            x as f64 + (column as f64 + 0.5) / SAMPLES as f64,
+                            y as f64 + (row as f64 + 0.5) / SAMPLES as f64,
+                        );
+                        if let Some(color) = paint(point) {
+                            for (total, channel) in total.iter_mut().zip(color) {
+                                *total += channel * weight;
+                            }
+                        }
+                    }
+                }
+                if total[3] > 0.0 {
+                    blend(&mut layer[y * width + x], total);
+                }
+            }
+        }
+    }
+}
+
+fn intersect(
+    (left, top, right, bottom): (usize, usize, usize, usize),
+    other: (usize, usize, usize, usize),
+) -> (usize, usize, usize, usize) {
+    (
+        left.max(other.0),
+        top.max(other.1),
+        right.min(other.2).max(left.max(other.0)),
+        bottom.min(other.3).max(top.max(other.1)),
+    )
+}
+
+/// Returns the side of a border that a point in it belongs to, as an index from the top clockwise. The corners are split between the sides they join, in proportion to their widths.
+fn border_side(rect: &Rect, widths: &Sides, (x, y): (f64, f64)) -> usize {
+    let distances = [
+        (y - rect.y, widths.top),
+        (rect.right() - x, widths.right),
+        (rect.bottom() - y, widths.bottom),
+        (x - rect.x, widths.left),
+    ];
+    let mut side = 0;
+    let mut closest = f64::INFINITY;
+    for (index, (distance, width)) in distances.into_iter().enumerate() {
+        if width > 0.0 && distance / width < closest {
+            closest = distance / width;
+            side = index;
+        }
+    }
+    side
+}
+
+/// Samples a bitmap that is scaled to fill a rectangle at a point, interpolating between its pixels.
+fn sample_bitmap(bitmap: &Bitmap, rect: &Rect, (x, y): (f64, f64)) -> Pixel {
+    let (width, height) = (bitmap.width() as f64, bitmap.height() as f64);
+    let u = ((x - rect.x) / rect.width * width - 0.5).clamp(0.0, width - 1.0);
+    let v = ((y - rect.y) / rect.height * height - 0.5).clamp(0.0, height - 1.0);
+    let pixel = |column: f64, row: f64| {
+        let [red, green, blue, alpha] = bitmap
+            .pixel(column as u32, row as u32)
+            .expect("The pixel is clamped to the bitmap.");
+        premultiply(Color::rgba(red, green, blue, alpha))
+    };
+    let (left, top) = (u.floor(), v.floor());
+    let (right, bottom) = ((left + 1.0).min(width - 1.0), (top + 1.0).min(height - 1.0));
+    let (fx, fy) = ((u - left) as f32, (v - top) as f32);
+    let corners = [
+        (pixel(left, top), (1.0 - fx) * (1.0 - fy)),
+        (pixel(right, top), fx * (1.0 - fy)),
+        (pixel(left, bottom), (1.0 - fx) * fy),
+        (pixel(right, bottom), fx * fy),
+    ];
+    let mut color = [0.0; 4];
+    for (corner, weight) in corners {
+        for (channel, value) in color.iter_mut().zip(corner) {
+            *channel += value * weight;
+        }
+    }
+    color
+}
+
+/// The area that a glyph covers: its outline, or a box in its place if it has none.
+struct GlyphShape<'a> {
+    origin: (f64, f64),
+    outline: Option<&'a Vec<Vec<(f64, f64)>>>,
+    bounds: Rect,
+}
+
+impl<'a> GlyphShape<'a> {
+    fn of(glyph: &'a Glyph, size: f64) -> Self {
+        GlyphShape {
+            origin: glyph.origin,
+            outline: glyph.outline.as_ref(),
+            bounds: glyph.bounds(size),
+        }
+    }
+
+    /// Returns true if a point is inside the glyph, where its contours wind around the point a nonzero number of times.
+    fn contains(&self, (x, y): (f64, f64)) -> bool {
+        let Some(outline) = self.outline else {
+            return self.bounds.contains((x, y));
+        };
+        let (x, y) = (x - self.origin.0, y - self.origin.1);
+        let mut winding = 0;
+        for contour in outline {
+            for (index, start) in contour.iter().enumerate() {
+                let end = contour[(index + 1) % contour.len()];
+                let side = (end.0 - start.0) * (y - start.1) - (x - start.0) * (end.1 - start.1);
+                if start.1 <= y && end.1 > y && side > 0.0 {
+                    winding += 1;
+                } else if start.1 > y && end.1 <= y && side < 0.0 {
+                    winding -= 1;
+                }
+            }
+        }
+        winding != 0
+    }
+}
@@ -0,0 +1,518 @@
+//! Scrolling: the scroll positions of scroll containers and of the viewport, smooth scrolling on the virtual clock, scroll snapping, and the scrolling of elements into view.
+//!
+//! The viewport is scrolled through the root element, as in standards mode.
+//!
+//! [Reference](https://drafts.csswg.org/cssom-view/#scrolling)
+use crate::{
+    css::ComputedStyle,
+    domitem::DOMItem,
+    layout::{Layout, LayoutBox, Rect, Sides, Size},
+    AsEventTarget, AsNode, AsParentNode, Document, Element, Event, EventInit, EventType,
+};
+
+/// How long a smooth scroll takes, in milliseconds of the virtual clock.
+const SMOOTH_SCROLL_DURATION: f64 = 300.0;
+
+/// How close a scroll position has to be to a snap position to snap to it, when snapping is `proximity`, as a fraction of the size of the snapport.
+const SNAP_PROXIMITY: f64 = 1.0 / 3.0;
+
+/// How a scroll gets to its position.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/CSS/scroll-behavior)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScrollBehavior {
+    /// Smoothly if the `scroll-behavior` of the scrolled element is `smooth`, and instantly otherwise.
+    #[default]
+    Auto,
+    /// In a single jump.
+    Instant,
+    /// Smoothly, over time on the virtual clock. See [`Window::advance_time`](crate::Window::advance_time).
+    Smooth,
+}
+
+/// The options used when scrolling an element with [`AsElement::scroll_to`] or [`AsElement::scroll_by`].
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Element/scrollTo#options)
+///
+/// [`AsElement::scroll_to`]: crate::AsElement::scroll_to
+/// [`AsElement::scroll_by`]: crate::AsElement::scroll_by
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScrollToOptions {
+    /// The horizontal position to scroll to, or to scroll by. `None` leaves the horizontal position as it is.
+    pub left: Option<f64>,
+    /// The vertical position to scroll to, or to scroll by. `None` leaves the vertical position as it is.
+    pub top: Option<f64>,
+    pub behavior: ScrollBehavior,
+}
+
+/// Where an element is aligned in a scroll container on one axis when it is scrolled into view.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Element/scrollIntoView#block)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScrollLogicalPosition {
+    /// With its start edge at the start edge of the scroll container.
+    #[default]
+    Start,
+    /// In the middle of the scroll container.
+    Center,
+    /// With its end edge at the end edge of the scroll container.
+    End,
+    /// At whichever edge is closer, or where it is if it is already in view.
+    Nearest,
+}
+
+/// The options used when scrolling an element into view with [`AsElement::scroll_into_view`].
+///
+/// `true` and `false` convert to the options of `scrollIntoView(true)` and `scrollIntoView(false)`.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/Element/scrollIntoView)
+///
+/// [`AsElement::scroll_into_view`]: crate::AsElement::scroll_into_view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollIntoView {
+    pub behavior: ScrollBehavior,
+    /// The alignment on the vertical axis.
+    pub block: ScrollLogicalPosition,
+    /// The alignment on the horizontal axis.
+    pub inline: ScrollLogicalPosition,
+}
+
+impl Default for ScrollIntoView {
+    fn default() -> Self {
+        ScrollIntoView {
+            behavior: ScrollBehavior::Auto,
+            block: ScrollLogicalPosition::Start,
+            inline: ScrollLogicalPosition::Nearest,
+        }
+    }
+}
+
+impl From<bool> for ScrollIntoView {
+    fn from(align_to_top: bool) -> Self {
+        ScrollIntoView {
+            block: match align_to_top {
+                true => ScrollLogicalPosition::Start,
+                false => ScrollLogicalPosition::End,
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// A smooth scroll of an element that is in progress.
+pub(crate) struct SmoothScroll {
+    element: Element,
+    from: (f64, f64),
+    to: (f64, f64),
+    /// The time on the virtual clock when the scroll started.
+    start: f64,
+}
+
+/// Returns the scroll position of an element, or `(0, 0)` if it is not a scroll container.
+pub(crate) fn scroll_position(element: &Element) -> (f64, f64) {
+    let Some(document) = element.owner_document().filter(|_| element.is_connected()) else {
+        return (0.0, 0.0);
+    };
+    match Layout::of(&document).geometry(element) {
+        Some(geometry) => geometry.clamp_scroll(element.base().scroll_position),
+        None => (0.0, 0.0),
+    }
+}
+
+/// Scrolls an element to a position, which is snapped to the snap positions of the element and clamped to its scroll range. `None` leaves the position on that axis as it is.
+///
+/// [Reference](https://drafts.csswg.org/cssom-view/#dom-element-scroll)
+pub(crate) fn scroll_to(
+    element: &Element,
+    left: Option<f64>,
+    top: Option<f64>,
+    behavior: ScrollBehavior,
+) {
+    let Some(document) = element.owner_document().filter(|_| element.is_connected()) else {
+        return;
+    };
+    let layout = Layout::of(&document);
+    let Some(scroller) = Scroller::of(&layout, element) else {
+        return;
+    };
+    // Values that are not finite are taken to be zero.
+    let finite = |value: f64| if value.is_finite() { value } else { 0.0 };
+    let target = (
+        left.map_or(scroller.position.0, finite),
+        top.map_or(scroller.position.1, finite),
+    );
+    scroller.scroll(&document, target, behavior);
+}
+
+/// Scrolls an element by an amount, from its current position.
+pub(crate) fn scroll_by(element: &Element, left: f64, top: f64, behavior: ScrollBehavior) {
+    let (x, y) = scroll_position(element);
+    scroll_to(element, Some(x + left), Some(y + top), behavior);
+}
+
+/// Scrolls the scroll containers that an element is in, from the innermost out, so that the element is in view in each of them.
+///
+/// [Reference](https://drafts.csswg.org/cssom-view/#scroll-an-element-into-view)
+pub(crate) fn scroll_into_view(element: &Element, options: ScrollIntoView) {
+    let Some(document) = element.owner_document().filter(|_| element.is_connected()) else {
+        return;
+    };
+    let layout = Layout::of(&document);
+    let (Some(path), Some(geometry)) = (layout.path_to(element), layout.geometry(element)) else {
+        return;
+    };
+    let Some(border_box) = geometry
+        .fragments
+        .iter()
+        .copied()
+        .reduce(|bounds, fragment| bounds.union(&fragment))
+    else {
+        return;
+    };
+    let target_box = path[path.len() - 1];
+    let mut target = expand(border_box, &scroll_margin(&target_box.style));
+    // Fixed position boxes do not move when their ancestors are scrolled.
+    let first = path
+        .iter()
+        .rposition(|layout_box| layout_box.style.get("position") == "fixed")
+        .map_or(0, |fixed| fixed + 1);
+    let scrollers: Vec<Scroller> = path[..path.len() - 1]
+        .iter()
+        .enumerate()
+        .skip(first)
+        .filter(|(index, layout_box)| *index == 0 || layout_box.is_scroll_container())
+        .filter_map(|(_, layout_box)| Scroller::of(&layout, layout_box.element.as_ref()?))
+        .collect();
+    for scroller in scrollers.into_iter().rev() {
+        let snapport = scroller.snapport();
+        let delta = (
+            align(
+                (target.x, target.right()),
+                (snapport.x, snapport.right()),
+                options.inline,
+            ),
+            align(
+                (target.y, target.bottom()),
+                (snapport.y, snapport.bottom()),
+                options.block,
+            ),
+        );
+        let position = (scroller.position.0 + delta.0, scroller.position.1 + delta.1);
+        let position = scroller.scroll(&document, position, options.behavior);
+        // The element moves in the outer scroll containers as the inner ones are scrolled.
+        target.translate(
+            scroller.position.0 - position.0,
+            scroller.position.1 - position.1,
+        );
+    }
+}
+
+/// Returns how far a scroll container has to be scrolled on one axis to align the start and end edges of an element with the start and end edges of its snapport.
+///
+/// [Reference](https://drafts.csswg.org/cssom-view/#determine-the-scroll-into-view-position)
+fn align(
+    (start, end): (f64, f64),
+    (port_start, port_end): (f64, f64),
+    position: ScrollLogicalPosition,
+) -> f64 {
+    let is_larger = end - start > port_end - port_start;
+    match position {
+        ScrollLogicalPosition::Start => start - port_start,
+        ScrollLogicalPosition::End => end - port_end,
+        ScrollLogicalPosition::Center => (start + end) / 2.0 - (port_start + port_end) / 2.0,
+        // An element that is in view, or that covers the whole snapport, stays where it is.
+        ScrollLogicalPosition::Nearest => {
+            if (start >= port_start && end <= port_end) || (start < port_start && end > port_end) {
+                0.0
+            } else if (start < port_start) != is_larger {
+                start - port_start
+            } else {
+                end - port_end
+            }
+        }
+    }
+}
+
+/// Advances the smooth scrolls of a document to the time on its virtual clock, and fires their `scroll` and `scrollend` events.
+pub(crate) fn update_smooth_scrolls(document: &Document) {
+    let time = document.state().time;
+    let scrolls = std::mem::take(&mut document.state_mut().smooth_scrolls);
+    let mut steps = vec![];
+    for scroll in scrolls {
+        let progress = ((time - scroll.start) / SMOOTH_SCROLL_DURATION).clamp(0.0, 1.0);
+        let eased = ease_in_out(progress);
+        let position = (
+            scroll.from.0 + (scroll.to.0 - scroll.from.0) * eased,
+            scroll.from.1 + (scroll.to.1 - scroll.from.1) * eased,
+        );
+        steps.push((scroll.element.clone_ref(), position, progress == 1.0));
+        if progress < 1.0 {
+            document.state_mut().smooth_scrolls.push(scroll);
+        }
+    }
+    for (element, position, is_finished) in steps {
+        set_scroll_position(document, &element, position);
+        if is_finished {
+            fire(document, &element, EventType::ScrollEnd);
+        }
+    }
+}
+
+fn ease_in_out(progress: f64) -> f64 {
+    match progress < 0.5 {
+        true => 4.0 * progress.powi(3),
+        false => 1.0 - (2.0 - 2.0 * progress).powi(3) / 2.0,
+    }
+}
+
+/// Sets the scroll position of an element, and fires a `scroll` event if it changed.
+fn set_scroll_position(document: &Document, element: &Element, position: (f64, f64)) -> bool {
+    let changed = element.base().scroll_position != position;
+    if changed {
+        element.base().scroll_position = position;
+        crate::layout::invalidate(AsNode::cast(element));
+        fire(document, element, EventType::Scroll);
+    }
+    changed
+}
+
+/// Fires a scroll event at an element, or at the document for the root element, which scrolls the viewport.
+///
+/// [Reference](https://drafts.csswg.org/cssom-view/#scrolling-events)
+fn fire(document: &Document, element: &Element, event_type: EventType) {
+    let is_root = document
+        .first_element_child()
+        .is_some_and(|root| root == *element);
+    match is_root {
+        true => {
+            let event_init = EventInit {
+                bubbles: true,
+                ..Default::default()
+            };
+            document
+                .clone()
+                .dispatch_event(&mut Event::trusted(event_type, event_init));
+        }
+        false => {
+            element
+                .clone_ref()
+                .dispatch_event(&mut Event::trusted(event_type, EventInit::default()));
+        }
+    }
+}
+
+/// An element that can be scrolled, and what is needed to scroll it.
+struct Scroller<'a> {
+    element: Element,
+    layout_box: &'a LayoutBox,
+    /// The current scroll position.
+    position: (f64, f64),
+    range: (f64, f64),
+    /// The padding box of the element, or the viewport for the root element, relative to the viewport.
+    port: Rect,
+}
+
+impl<'a> Scroller<'a> {
+    fn of(layout: &'a Layout, element: &Element) -> Option<Self> {
+        let geometry = layout.geometry(element)?;
+        if !geometry.is_scroll_container {
+            return None;
+        }
+        let path = layout.path_to(element)?;
+        let is_root = path.len() == 1;
+        let port = match is_root {
+            true => Rect::new(0.0, 0.0, layout.viewport.width, layout.viewport.height),
+            false => geometry.fragments.first()?.shrink(&geometry.border),
+        };
+        Some(Scroller {
+            element: element.clone_ref(),
+            layout_box: path[path.len() - 1],
+            position: geometry.clamp_scroll(element.base().scroll_position),
+            range: geometry.scroll_range(),
+            port,
+        })
+    }
+
+    /// Returns the part of the scrollport that content is aligned in, which is the scrollport without its `scroll-padding`.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-scroll-snap/#scroll-padding)
+    fn snapport(&self) -> Rect {
+        let style = &self.layout_box.style;
+        let side = |side: &str, basis: f64| {
+            Size::parse(style.get(&format!("scroll-padding-{side}")))
+                .resolve(Some(basis))
+                .unwrap_or(0.0)
+        };
+        self.port.shrink(&Sides {
+            top: side("top", self.port.height),
+            right: side("right", self.port.width),
+            bottom: side("bottom", self.port.height),
+            left: side("left", self.port.width),
+        })
+    }
+
+    fn clamp(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (x.clamp(0.0, self.range.0), y.clamp(0.0, self.range.1))
+    }
+
+    /// Scrolls to a position, after snapping and clamping it, and returns the position it scrolls to.
+    ///
+    /// [Reference](https://drafts.csswg.org/cssom-view/#perform-a-scroll)
+    fn scroll(
+        &self,
+        document: &Document,
+        target: (f64, f64),
+        behavior: ScrollBehavior,
+    ) -> (f64, f64) {
+        let target = self.clamp(self.snap(target));
+        let is_smooth = match behavior {
+            ScrollBehavior::Auto => self.layout_box.style.get("scroll-behavior") == "smooth",
+            ScrollBehavior::Instant => false,
+            ScrollBehavior::Smooth => true,
+        };
+        // A new scroll takes over from a smooth scroll that is in progress.
+        document
+            .state_mut()
+            .smooth_scrolls
+            .retain(|scroll| scroll.element != self.element);
+        if is_smooth && target != self.position {
+            let start = document.state().time;
+            document.state_mut().smooth_scrolls.push(SmoothScroll {
+                element: self.element.clone_ref(),
+                from: self.position,
+                to: target,
+                start,
+            });
+        } else if set_scroll_position(document, &self.element, target) {
+            fire(document, &self.element, EventType::ScrollEnd);
+        }
+        target
+    }
+
+    /// Moves a scroll position to the closest snap position on each axis that the element snaps on, given by its `scroll-snap-type`.
+    ///
+    /// With `mandatory` snapping, there is always a snap position to move to if any of its descendants has a `scroll-snap-align`. With `proximity` snapping, the position only moves when the snap position is within a third of the snapport.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-scroll-snap/#snap-position)
+    fn snap(&self, target: (f64, f64)) -> (f64, f64) {
+        let mut snap_type = self
+            .layout_box
+            .style
+            .get("scroll-snap-type")
+            .split_whitespace();
+        let (snaps_x, snaps_y) = match snap_type.next() {
+            Some("x" | "inline") => (true, false),
+            Some("y" | "block") => (false, true),
+            Some("both") => (true, true),
+            _ => return target,
+        };
+        let is_mandatory = snap_type.next() == Some("mandatory");
+        let snapport = self.snapport();
+        let mut areas = vec![];
+        for child in &self.layout_box.children {
+            collect_snap_areas(child, &mut areas);
+        }
+        // Positions are compared in the coordinates of the scrolled content, where the snapport is at the scroll position.
+        let (offset_x, offset_y) = (self.position.0 - snapport.x, self.position.1 - snapport.y);
+        let snap_axis = |target: f64, positions: Vec<f64>, size: f64| {
+            positions
+                .into_iter()
+                .min_by(|a, b| (a - target).abs().total_cmp(&(b - target).abs()))
+                .filter(|position| {
+                    is_mandatory || (position - target).abs() <= size * SNAP_PROXIMITY
+                })
+                .unwrap_or(target)
+        };
+        let x = match snaps_x {
+            true => snap_axis(
+                target.0,
+                areas
+                    .iter()
+                    .filter_map(|(area, (_, inline))| {
+                        let start = area.x + offset_x;
+                        snap_position(start, start + area.width, snapport.width, inline)
+                    })
+                    .collect(),
+                snapport.width,
+            ),
+            false => target.0,
+        };
+        let y = match snaps_y {
+            true => snap_axis(
+                target.1,
+                areas
+                    .iter()
+                    .filter_map(|(area, (block, _))| {
+                        let start = area.y + offset_y;
+                        snap_position(start, start + area.height, snapport.height, block)
+                    })
+                    .collect(),
+                snapport.height,
+            ),
+            false => target.1,
+        };
+        (x, y)
+    }
+}
+
+/// Returns the scroll position that aligns a snap area with the snapport on one axis, given the start and end of the area in the scrolled content, or `None` if the area does not snap on the axis.
+fn snap_position(start: f64, end: f64, size: f64, align: &str) -> Option<f64> {
+    match align {
+        "start" => Some(start),
+        "end" => Some(end - size),
+        "center" => Some((start + end) / 2.0 - size / 2.0),
+        _ => None,
+    }
+}
+
+/// Collects the snap areas of the boxes in a scroll container, with their `scroll-snap-align` on the vertical and horizontal axes. Boxes inside nested scroll containers belong to those containers.
+///
+/// [Reference](https://drafts.csswg.org/css-scroll-snap/#scroll-snap-area)
+fn collect_snap_areas<'a>(layout_box: &'a LayoutBox, areas: &mut Vec<(Rect, (&'a str, &'a str))>) {
+    let style = &layout_box.style;
+    let align = style.get("scroll-snap-align");
+    if layout_box.element.is_some() && !matches!(align, "" | "none") {
+        let mut values = align.split_whitespace();
+        let block = values.next().unwrap_or("none");
+        let inline = values.next().unwrap_or(block);
+        let border_box = match layout_box.fragments.is_empty() {
+            true => layout_box.rect,
+            false => layout_box
+                .fragments
+                .iter()
+                .fold(layout_box.fragments[0], |bounds, fragment| {
+                    bounds.union(fragment)
+                }),
+        };
+        areas.push((expand(border_box, &scroll_margin(style)), (block, inline)));
+    }
+    if !layout_box.is_scroll_container() {
+        for child in &layout_box.children {
+            collect_snap_areas(child, areas);
+        }
+    }
+}
+
+/// Resolves the `scroll-margin` of a style, which grows the area that is aligned when the box is scrolled to.
+fn scroll_margin(style: &ComputedStyle) -> Sides {
+    let side = |side: &str| {
+        Size::parse(style.get(&format!("scroll-margin-{side}")))
+            .resolve(None)
+            .unwrap_or(0.0)
+    };
+    Sides {
+        top: side("top"),
+        right: side("right"),
+        bottom: side("bottom"),
+        left: side("left"),
+    }
+}
+
+fn expand(rect: Rect, sides: &Sides) -> Rect {
+    Rect::new(
+        rect.x - sides.left,
+        rect.y - sides.top,
+        rect.width + sides.horizontal(),
+        rect.height + sides.vertical(),
+    )
+}
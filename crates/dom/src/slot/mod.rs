@@ -1,5 +1,20 @@
-use crate::{AsNode, Node};
+use std::{cell::RefCell, rc::Weak};
 
+use crate::{
+    domitem::DOMItem,
+    element::ElementBase,
+    html_element::HTMLElementBase,
+    html_parser::{self, SerializeOptions},
+    node::{
+        trees::{descendant_nodes, root_of},
+        NodeType, WeakNodeRef,
+    },
+    tag::Tag,
+    AsElement, AsEventTarget, AsNode, AsParentNode, ChildNode, DOMException, Element, EventTarget,
+    GetHTMLOptions, InnerHtml, Node,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SlotAssignment {
     Named,
     Manual,
@@ -15,22 +30,623 @@ impl SlotAssignment {
     }
 }
 
+/// The encapsulation mode of a shadow root.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/mode)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShadowRootMode {
+    Open,
+    Closed,
+}
+
+/// The root node of a DOM subtree that is rendered separately from a document's main DOM tree.
+///
+/// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot)
 pub struct ShadowRoot {
+    node: Node,
+    host: Weak<RefCell<ElementBase>>,
+    mode: ShadowRootMode,
+    delegates_focus: bool,
+    clonable: bool,
+    serializable: bool,
     pub(crate) slot_assignment: SlotAssignment,
 }
-pub struct ShadowRootInit;
+
+impl std::fmt::Debug for ShadowRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShadowRoot")
+            .field("mode", &self.mode)
+            .field("slot_assignment", &self.slot_assignment)
+            .field("node", &self.node)
+            .finish()
+    }
+}
+
+/// The options used when attaching a shadow root with [`AsElement::attach_shadow`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowRootInit {
+    pub mode: ShadowRootMode,
+    pub delegates_focus: bool,
+    pub slot_assignment: SlotAssignment,
+    pub clonable: bool,
+    pub serializable: bool,
+}
+
+impl ShadowRootInit {
+    /// Creates the options for a shadow root with the given mode, using named slot assignment.
+    pub fn new(mode: ShadowRootMode) -> Self {
+        Self {
+            mode,
+            delegates_focus: false,
+            slot_assignment: SlotAssignment::Named,
+            clonable: false,
+            serializable: false,
+        }
+    }
+}
+
+impl ShadowRoot {
+    /// Create a shadow root for a host element.
+    pub(crate) fn for_host(host: &Element, init: ShadowRootInit) -> Self {
+        let owner_document = AsNode::cast(host).base().owner_document.clone();
+        let shadow_root = Self {
+            node: Node::in_document(NodeType::DocumentFragmentNode, owner_document),
+            host: host.as_weak_ref(),
+            mode: init.mode,
+            delegates_focus: init.delegates_focus,
+            clonable: init.clonable,
+            serializable: init.serializable,
+            slot_assignment: init.slot_assignment,
+        };
+        if let Some(document) = host.owner_document() {
+            document.register_shadow_root(shadow_root.node.get_base_ptr(), host.as_weak_ref());
+        }
+        shadow_root
+    }
+    /// Returns the mode of the shadow root, either open or closed.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/mode)
+    pub fn mode(&self) -> ShadowRootMode {
+        self.mode
+    }
+    /// Returns the element the shadow root is attached to.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/host)
+    pub fn host(&self) -> Element {
+        Element::with_base(
+            self.host
+                .upgrade()
+                .expect("Shadow root outlived its host element."),
+        )
+    }
+    /// Returns whether the shadow root delegates focus to its first focusable element.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/delegatesFocus)
+    pub fn delegates_focus(&self) -> bool {
+        self.delegates_focus
+    }
+    /// Returns whether the shadow root is cloned together with its host.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/clonable)
+    pub fn clonable(&self) -> bool {
+        self.clonable
+    }
+    /// Returns whether the shadow root is serialized by [`AsElement::get_html`] when `serializable_shadow_roots` is set.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/serializable)
+    pub fn serializable(&self) -> bool {
+        self.serializable
+    }
+    /// Returns an HTML serialization of the shadow root's contents, optionally including nested shadow roots.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/getHTML)
+    pub fn get_html(&self, options: Option<GetHTMLOptions>) -> String {
+        let options = options.unwrap_or_default();
+        html_parser::serialize_children(
+            self,
+            &SerializeOptions {
+                serializable_shadow_roots: options.serializable_shadow_roots,
+                shadow_roots: &options.shadow_roots,
+            },
+        )
+    }
+    /// Replaces the contents of the shadow root with parsed HTML. Unlike [`InnerHtml::set_inner_html`], declarative shadow roots in the markup are attached.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/setHTMLUnsafe)
+    pub fn set_html_unsafe(&mut self, html: &str) {
+        self.replace_with_html(html, true);
+    }
+    fn replace_with_html(&mut self, html: &str, allow_declarative_shadow_roots: bool) {
+        let host = self.host();
+        let mut fragment = html_parser::parse_fragment(
+            &host,
+            &host.base().tag.to_string(),
+            html,
+            allow_declarative_shadow_roots,
+        );
+        self.node.replace_all(&mut fragment);
+    }
+    /// Returns the slot assignment mode of the shadow root, either named or manual.
+    ///
+    /// [MDN Reference](https://developer.mozilla.org/docs/Web/API/ShadowRoot/slotAssignment)
+    pub fn slot_assignment(&self) -> SlotAssignment {
+        self.slot_assignment
+    }
+}
+
+impl<T: AsNode> PartialEq<T> for ShadowRoot {
+    fn eq(&self, other: &T) -> bool {
+        &self.node == AsNode::cast(other)
+    }
+}
+impl AsEventTarget for ShadowRoot {
+    fn cast(&self) -> &EventTarget {
+        AsEventTarget::cast(&self.node)
+    }
+
+    fn cast_mut(&mut self) -> &mut EventTarget {
+        AsEventTarget::cast_mut(&mut self.node)
+    }
+}
+impl AsNode for ShadowRoot {
+    fn cast(&self) -> &Node {
+        &self.node
+    }
+
+    fn cast_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    /// # Panics
+    /// Shadow roots cannot be cloned on their own, only together with their host.
+    fn clone_node(&self, _deep: bool) -> Self {
+        panic!("ShadowRoot nodes cannot be cloned.")
+    }
+}
+impl AsParentNode for ShadowRoot {}
+impl InnerHtml for ShadowRoot {
+    fn inner_html(&self) -> String {
+        html_parser::serialize_children(self, &SerializeOptions::NONE)
+    }
+
+    fn set_inner_html(&mut self, value: &str) -> Result<(), DOMException> {
+        self.replace_with_html(value, false);
+        Ok(())
+    }
+}
+
+/// The options for [`HTMLSlotElement::assigned_nodes`] and [`HTMLSlotElement::assigned_elements`].
+///
+/// [`HTMLSlotElement::assigned_nodes`]: crate::HTMLSlotElement::assigned_nodes
+/// [`HTMLSlotElement::assigned_elements`]: crate::HTMLSlotElement::assigned_elements
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AssignedNodesOptions {
+    pub flatten: bool,
+}
 
 pub(crate) fn is_slottable(node: &impl AsNode) -> bool {
     node.node_type() == Node::ELEMENT_NODE || node.node_type() == Node::TEXT_NODE
 }
 
+/// Returns the element a node represents, if it is an element.
+fn element_of(node: &impl AsNode) -> Option<Element> {
+    if node.node_type() != Node::ELEMENT_NODE {
+        return None;
+    }
+    node.owner_document()?
+        .lookup_html_element(AsNode::cast(node).get_base_ptr())
+}
+
+/// Returns true if the document of a node has any shadow roots. No slot can have assigned nodes otherwise.
+fn has_shadow_roots(node: &impl AsNode) -> bool {
+    node.owner_document()
+        .is_some_and(|document| document.has_shadow_roots())
+}
+
 pub(crate) fn is_shadow_host_and(
     node: &impl AsNode,
-    root_predicate: fn(ShadowRoot) -> bool,
+    root_predicate: fn(&ShadowRoot) -> bool,
 ) -> bool {
-    todo!()
+    element_of(node).is_some_and(|element| {
+        element
+            .base()
+            .shadow_root
+            .as_ref()
+            .is_some_and(root_predicate)
+    })
+}
+
+/// Returns the node of the shadow root hosted by a node, if any.
+pub(crate) fn shadow_root_of(node: &impl AsNode) -> Option<Node> {
+    let element = element_of(node)?;
+    let shadow_root = element.base().shadow_root.as_ref()?;
+    Some(shadow_root.node.clone_ref())
+}
+
+/// Returns the host of a node, if the node is a shadow root.
+pub(crate) fn host_of_shadow_root(node: &impl AsNode) -> Option<Element> {
+    if node.node_type() != Node::DOCUMENT_FRAGMENT_NODE {
+        return None;
+    }
+    node.owner_document()?
+        .lookup_shadow_host(AsNode::cast(node).get_base_ptr())
+}
+
+pub(crate) fn is_shadow_root(node: &impl AsNode) -> bool {
+    host_of_shadow_root(node).is_some()
 }
 
+pub(crate) fn is_slot(node: &impl AsNode) -> bool {
+    element_of(node).is_some_and(|element| matches!(element.base().tag, Tag::Slot))
+}
+
+/// Returns the slot a slottable is currently assigned to.
+pub(crate) fn assigned_slot_of(node: &impl AsNode) -> Option<Node> {
+    AsNode::cast(node)
+        .base()
+        .assigned_slot
+        .as_ref()?
+        .inner
+        .upgrade()
+        .map(|base| Node { base })
+}
+
+/// Run a closure with the assigned and manually assigned nodes of a slot.
+fn with_slot<R>(
+    slot: &impl AsNode,
+    callback: impl FnOnce(&mut Vec<ChildNode>, &mut Vec<ChildNode>) -> R,
+) -> Option<R> {
+    let html_element = slot
+        .owner_document()?
+        .lookup_html_element_base(AsNode::cast(slot).get_base_ptr())?;
+    let mut html_element = html_element.borrow_mut();
+    match &mut *html_element {
+        HTMLElementBase::Slot {
+            assigned_nodes,
+            manually_assigned_nodes,
+            ..
+        } => Some(callback(assigned_nodes, manually_assigned_nodes)),
+        _ => None,
+    }
+}
+
+pub(crate) fn assigned_nodes(slot: &impl AsNode) -> Vec<ChildNode> {
+    with_slot(slot, |assigned_nodes, _| {
+        assigned_nodes.iter().map(ChildNode::from).collect()
+    })
+    .unwrap_or_default()
+}
+
+/// The name of a slottable, i.e. the value of its `slot` attribute.
+fn slottable_name(slottable: &Node) -> String {
+    element_of(slottable)
+        .and_then(|element| element.get_attribute("slot").map(str::to_owned))
+        .unwrap_or_default()
+}
+
+/// The name of a slot, i.e. the value of its `name` attribute.
+fn slot_name(slot: &Node) -> String {
+    element_of(slot)
+        .and_then(|element| element.get_attribute("name").map(str::to_owned))
+        .unwrap_or_default()
+}
+
+/// Returns all the slots that are inclusive descendants of a node, in tree order.
+fn inclusive_descendant_slots(node: &Node) -> Vec<Node> {
+    let mut slots = vec![];
+    if is_slot(node) {
+        slots.push(node.clone_ref());
+    }
+    for descendant in descendant_nodes(node) {
+        if is_slot(descendant) {
+            slots.push(descendant.clone_ref())
+        }
+    }
+    slots
+}
+
+/// [Reference](https://dom.spec.whatwg.org/#find-a-slot)
+pub(crate) fn find_a_slot(slottable: &Node, open: bool) -> Option<Node> {
+    // 1. If slottable’s parent is null, then return null.
+    let parent = slottable.parent_node()?;
+    // 2. Let shadow be slottable’s parent’s shadow root.
+    // 3. If shadow is null, then return null.
+    let host = element_of(&parent)?;
+    let shadow = host.base().shadow_root.as_ref()?;
+    // 4. If the open flag is set and shadow’s mode is not "open", then return null.
+    if open && shadow.mode != ShadowRootMode::Open {
+        return None;
+    }
+    let mut slots = inclusive_descendant_slots(&shadow.node).into_iter();
+    match shadow.slot_assignment {
+        // 5. If shadow’s slot assignment is "manual", then return the slot in shadow’s descendants whose manually assigned nodes contains slottable, if any; otherwise null.
+        SlotAssignment::Manual => slots.find(|slot| {
+            with_slot(slot, |_, manually_assigned_nodes| {
+                manually_assigned_nodes
+                    .iter()
+                    .any(|node| node.is_same_node(slottable))
+            })
+            .unwrap_or(false)
+        }),
+        // 6. Return the first slot in tree order in shadow’s descendants whose name is slottable’s name, if any; otherwise null.
+        SlotAssignment::Named => {
+            let name = slottable_name(slottable);
+            slots.find(|slot| slot_name(slot) == name)
+        }
+    }
+}
+
+/// [Reference](https://dom.spec.whatwg.org/#find-slotables)
+pub(crate) fn find_slottables(slot: &Node) -> Vec<ChildNode> {
+    // 1. Let result be an empty list.
+    let mut result = vec![];
+    // 2. Let root be slot’s root.
+    // 3. If root is not a shadow root, then return result.
+    let root = root_of(slot);
+    let host = match host_of_shadow_root(&root) {
+        Some(host) => host,
+        None => return result,
+    };
+    // 4. Let host be root’s host.
+    let slot_assignment = match host.base().shadow_root.as_ref() {
+        Some(shadow) => shadow.slot_assignment,
+        None => return result,
+    };
+    match slot_assignment {
+        // 5. If root’s slot assignment is "manual", then:
+        //  1. Let result be « ».
+        //  2. For each slottable slottable of slot’s manually assigned nodes, if slottable’s parent is host, append slottable to result.
+        SlotAssignment::Manual => {
+            let manually_assigned_nodes = with_slot(slot, |_, manually_assigned_nodes| {
+                manually_assigned_nodes
+                    .iter()
+                    .map(ChildNode::from)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+            for slottable in manually_assigned_nodes {
+                if slottable
+                    .parent_node()
+                    .is_some_and(|parent| parent.is_same_node(&host))
+                {
+                    result.push(slottable);
+                }
+            }
+        }
+        // 6. Otherwise, for each slottable child slottable of host, in tree order:
+        //  1. Let foundSlot be the result of finding a slot given slottable.
+        //  2. If foundSlot is slot, then append slottable to result.
+        SlotAssignment::Named => {
+            for child in host.child_nodes() {
+                if is_slottable(child)
+                    && find_a_slot(AsNode::cast(child), false)
+                        .is_some_and(|found_slot| found_slot.is_same_node(slot))
+                {
+                    result.push(ChildNode::from(child));
+                }
+            }
+        }
+    }
+    // 7. Return result.
+    result
+}
+
+/// [Reference](https://dom.spec.whatwg.org/#find-flattened-slotables)
+pub(crate) fn find_flattened_slottables(slot: &Node) -> Vec<ChildNode> {
+    // 1. Let result be « ».
+    let mut result = vec![];
+    // 2. If slot’s root is not a shadow root, then return result.
+    if !is_shadow_root(&root_of(slot)) {
+        return result;
+    }
+    // 3. Let slottables be the result of finding slottables given slot.
+    let mut slottables = find_slottables(slot);
+    // 4. If slottables is the empty list, then append each slottable child of slot, in tree order, to slottables.
+    if slottables.is_empty() {
+        slottables = slot
+            .child_nodes()
+            .iter()
+            .filter(|child| is_slottable(*child))
+            .map(ChildNode::from)
+            .collect();
+    }
+    // 5. For each node in slottables:
+    for node in slottables {
+        // 1. If node is a slot whose root is a shadow root, then:
+        //  1. Let temporaryResult be the result of finding flattened slottables given node.
+        //  2. Append each slottable in temporaryResult, in order, to result.
+        // 2. Otherwise, append node to result.
+        if is_slot(&node) && is_shadow_root(&root_of(&node)) {
+            result.append(&mut find_flattened_slottables(AsNode::cast(&node)));
+        } else {
+            result.push(node);
+        }
+    }
+    // 6. Return result.
+    result
+}
+
+/// [Reference](https://dom.spec.whatwg.org/#assign-slotables)
+pub(crate) fn assign_slottables(slot: &Node) {
+    // 1. Let slottables be the result of finding slottables for slot.
+    let slottables = find_slottables(slot);
+    // 2. If slottables and slot’s assigned nodes are not identical, then run signal a slot change for slot.
+    let previous = assigned_nodes(slot);
+    let identical = previous.len() == slottables.len()
+        && previous
+            .iter()
+            .zip(slottables.iter())
+            .all(|(a, b)| a.is_same_node(b));
+    if !identical {
+        signal_slot_change(slot);
+    }
+    // Nodes that are no longer assigned to the slot are unassigned.
+    for node in previous {
+        let was_assigned_here = assigned_slot_of(&node).is_some_and(|other| other == *slot);
+        if was_assigned_here && !slottables.iter().any(|other| other.is_same_node(&node)) {
+            AsNode::cast(&node).base().assigned_slot = None;
+        }
+    }
+    // 4. For each slottable of slottables, set slottable’s assigned slot to slot.
+    for slottable in slottables.iter() {
+        AsNode::cast(slottable).base().assigned_slot = Some(WeakNodeRef::from(slot));
+    }
+    // 3. Set slot’s assigned nodes to slottables.
+    with_slot(slot, |assigned_nodes, _| *assigned_nodes = slottables);
+}
+
+/// [Reference](https://dom.spec.whatwg.org/#assign-slotables-for-a-tree)
+pub(crate) fn assign_slottables_for_a_tree(root: &impl AsNode) {
+    if !has_shadow_roots(root) {
+        return;
+    }
+    for slot in inclusive_descendant_slots(AsNode::cast(root)) {
+        assign_slottables(&slot);
+    }
+}
+
+/// [Reference](https://dom.spec.whatwg.org/#assign-a-slot)
 pub(crate) fn assign_slot(node: &mut impl AsNode) {
-    todo!()
+    // 1. Let slot be the result of finding a slot with slottable.
+    // 2. If slot is non-null, then run assign slottables for slot.
+    if let Some(slot) = find_a_slot(AsNode::cast(node), false) {
+        assign_slottables(&slot);
+    }
+}
+
+/// Queues a `slotchange` event for a slot. The event is fired once the mutation observers are notified.
+///
+/// [Reference](https://dom.spec.whatwg.org/#signal-a-slot-change)
+pub(crate) fn signal_slot_change(slot: &impl AsNode) {
+    if let Some(document) = slot.owner_document() {
+        document.append_signal_slot(AsNode::cast(slot));
+    }
+}
+
+/// The slot-related parts of the insertion algorithm, run for each node inserted into a parent.
+///
+/// [Reference](https://dom.spec.whatwg.org/#concept-node-insert)
+pub(crate) fn run_insertion_steps(parent: &Node, node: &Node) {
+    if !has_shadow_roots(parent) {
+        return;
+    }
+    // 7.4. If parent is a shadow host whose shadow root’s slot assignment is "named" and node is a slottable, then assign a slot for node.
+    if is_shadow_host_and(parent, |shadow_root| shadow_root.slot_assignment.is_named())
+        && is_slottable(node)
+    {
+        assign_slot(&mut node.clone_ref())
+    }
+    // 7.5. If parent’s root is a shadow root, and parent is a slot whose assigned nodes is the empty list, then run signal a slot change for parent.
+    if is_shadow_root(&root_of(parent)) && is_slot(parent) && assigned_nodes(parent).is_empty() {
+        signal_slot_change(parent)
+    }
+    // 7.6. Run assign slottables for a tree with node’s root.
+    assign_slottables_for_a_tree(&root_of(node));
+}
+
+/// The slot-related parts of the removal algorithm, run after a node is removed from its parent.
+///
+/// [Reference](https://dom.spec.whatwg.org/#concept-node-remove)
+pub(crate) fn run_removing_steps(parent: &Node, node: &Node) {
+    if !has_shadow_roots(parent) {
+        return;
+    }
+    // 9. If node is assigned, then run assign slottables for node’s assigned slot.
+    if let Some(slot) = assigned_slot_of(node) {
+        assign_slottables(&slot);
+    }
+    // 10. If parent’s root is a shadow root, and parent is a slot whose assigned nodes is the empty list, then run signal a slot change for parent.
+    if is_shadow_root(&root_of(parent)) && is_slot(parent) && assigned_nodes(parent).is_empty() {
+        signal_slot_change(parent);
+    }
+    // 11. If node has an inclusive descendant that is a slot, then:
+    //  1. Run assign slottables for a tree with parent’s root.
+    //  2. Run assign slottables for a tree with node.
+    if !inclusive_descendant_slots(node).is_empty() {
+        assign_slottables_for_a_tree(&root_of(parent));
+        assign_slottables_for_a_tree(node);
+    }
+}
+
+/// The slot-related attribute change steps.
+///
+/// [Reference](https://dom.spec.whatwg.org/#slot-name) and [Reference](https://dom.spec.whatwg.org/#slotable-name)
+pub(crate) fn run_attribute_change_steps(
+    element: &Element,
+    local_name: &str,
+    old_value: Option<&str>,
+    value: Option<&str>,
+) {
+    if !has_shadow_roots(element) {
+        return;
+    }
+    // 1. If value is oldValue, then return.
+    // 2. If value is null and oldValue is the empty string, then return.
+    // 3. If value is the empty string and oldValue is null, then return.
+    if value.unwrap_or("") == old_value.unwrap_or("") {
+        return;
+    }
+    let node = AsNode::cast(element);
+    match local_name {
+        "name" if is_slot(element) => {
+            // If element’s root is a shadow root, then run assign slottables for a tree with element’s root.
+            let root = root_of(element);
+            if is_shadow_root(&root) {
+                assign_slottables_for_a_tree(&root);
+            }
+        }
+        "slot" => {
+            // If element is assigned, then run assign slottables for element’s assigned slot.
+            if let Some(slot) = assigned_slot_of(element) {
+                assign_slottables(&slot);
+            }
+            // Run assign a slot for element.
+            assign_slot(&mut node.clone_ref());
+        }
+        _ => {}
+    }
+}
+
+/// Sets the manually assigned nodes of a slot.
+///
+/// [Reference](https://html.spec.whatwg.org/multipage/scripting.html#dom-slot-assign)
+pub(crate) fn assign(slot: &Node, nodes: Vec<ChildNode>) {
+    // 1. For each slottable of this's manually assigned nodes, set slottable's manual slot assignment to null.
+    let previous = with_slot(slot, |_, manually_assigned_nodes| {
+        std::mem::take(manually_assigned_nodes)
+    })
+    .unwrap_or_default();
+    for slottable in previous {
+        AsNode::cast(&slottable).base().manual_slot_assignment = None;
+    }
+    // 2. Let nodesSet be a new ordered set.
+    let mut nodes_set: Vec<ChildNode> = vec![];
+    // 3. For each node of nodes:
+    for node in nodes {
+        if nodes_set.iter().any(|other| other.is_same_node(&node)) {
+            continue;
+        }
+        // 1. If node's manual slot assignment refers to a slot, then remove node from that slot's manually assigned nodes.
+        let previous_slot = AsNode::cast(&node)
+            .base()
+            .manual_slot_assignment
+            .as_ref()
+            .and_then(|weak_ref| weak_ref.inner.upgrade())
+            .map(|base| Node { base });
+        if let Some(previous_slot) = previous_slot {
+            with_slot(&previous_slot, |_, manually_assigned_nodes| {
+                manually_assigned_nodes.retain(|other| !other.is_same_node(&node))
+            });
+        }
+        // 2. Set node's manual slot assignment to this.
+        AsNode::cast(&node).base().manual_slot_assignment = Some(WeakNodeRef::from(slot));
+        // 3. Append node to nodesSet.
+        nodes_set.push(node);
+    }
+    // 4. Set this's manually assigned nodes to nodesSet.
+    with_slot(slot, |_, manually_assigned_nodes| {
+        *manually_assigned_nodes = nodes_set
+    });
+    // 5. Run assign slottables for a tree for this's root.
+    assign_slottables_for_a_tree(&root_of(slot));
 }
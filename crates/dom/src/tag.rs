@@ -368,6 +368,55 @@ impl Tag {
     pub fn is_unknown(&self) -> bool {
         matches!(self, Tag::Unknown(_))
     }
+    /// Check if the tag is a valid custom element name, i.e. a lowercase name that starts with an ASCII letter, contains a hyphen, and is not reserved.
+    ///
+    /// [Reference](https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name)
+    pub fn is_valid_custom_element_name(&self) -> bool {
+        let name = match self {
+            Tag::Unknown(name) => name,
+            _ => return false,
+        };
+        name.starts_with(|char: char| char.is_ascii_lowercase())
+            && name.contains('-')
+            && !name.chars().any(|char| char.is_ascii_uppercase())
+            && !matches!(
+                name.as_str(),
+                "annotation-xml"
+                    | "color-profile"
+                    | "font-face"
+                    | "font-face-src"
+                    | "font-face-uri"
+                    | "font-face-format"
+                    | "font-face-name"
+                    | "missing-glyph"
+            )
+    }
+    /// Check if an element with the tag can host a shadow root.
+    ///
+    /// [Reference](https://dom.spec.whatwg.org/#dom-element-attachshadow)
+    pub fn is_valid_shadow_host(&self) -> bool {
+        matches!(
+            self,
+            Self::Article
+                | Self::Aside
+                | Self::Blockquote
+                | Self::Body
+                | Self::Div
+                | Self::Footer
+                | Self::H1
+                | Self::H2
+                | Self::H3
+                | Self::H4
+                | Self::H5
+                | Self::H6
+                | Self::Header
+                | Self::Main
+                | Self::Nav
+                | Self::P
+                | Self::Section
+                | Self::Span
+        ) || self.is_valid_custom_element_name()
+    }
 }
 
 impl From<&str> for Tag {
@@ -0,0 +1,864 @@
+//! The rendering of documents as text for terminals, the way text-mode browsers like Lynx and w3m show them.
+//!
+//! Documents are laid out first, so the text follows the computed styles of their boxes: `display` decides what is a block, a list item or a table, `white-space` how spaces and newlines are kept and where lines wrap, and the font and text decoration how text is styled. The left margins, borders and paddings of blocks indent them by a column for every ten pixels.
+use crate::{
+    domitem::DOMItem,
+    font::is_wide,
+    layout::{process_white_space, reorder_text, BoxKind, Layout, LayoutBox},
+    loader::resolve_url,
+    tag::Tag,
+    AsElement, AsNode, Document, Element, FontDescription,
+};
+
+/// The number of CSS pixels of indentation that make a column.
+const PIXELS_PER_COLUMN: f64 = 10.0;
+
+/// The width of cells that tables are measured with, before their columns are fitted to the line.
+const UNBOUNDED: usize = 10_000;
+
+/// The options used when rendering a document as text with [`Document::render_text`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalOptions {
+    /// The number of columns that lines are wrapped at. Text that does not wrap, like preformatted text, can be longer.
+    pub width: usize,
+    /// Whether text is styled with ANSI escape codes: bold, italic, underlined and struck through. Without them, the text is plain.
+    pub ansi: bool,
+}
+
+impl Default for TerminalOptions {
+    /// Lines of 80 columns, styled with ANSI escape codes.
+    fn default() -> Self {
+        TerminalOptions {
+            width: 80,
+            ansi: true,
+        }
+    }
+}
+
+/// Renders a document as lines of text, followed by the URLs of its links.
+pub(crate) fn render_text(document: &Document, options: &TerminalOptions) -> String {
+    let layout = Layout::of(document);
+    let mut renderer = Renderer::new(document, options.width.max(1), options.ansi, vec![]);
+    if let Some(root) = &layout.root {
+        renderer.block(root, &Context::default());
+    }
+    let mut text = renderer.finish();
+    if !renderer.links.is_empty() {
+        text.push_str("\n\nReferences\n");
+        let width = renderer.links.len().to_string().len();
+        let references: Vec<String> = renderer
+            .links
+            .iter()
+            .enumerate()
+            .map(|(index, url)| format!("{:>width$}. {url}", index + 1))
+            .collect();
+        text.push_str(&references.join("\n"));
+    }
+    text
+}
+
+/// How text is styled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+}
+
+impl Style {
+    /// Returns the style of the text of a box, with the decorations that its ancestors propagate to it.
+    ///
+    /// [Reference](https://drafts.csswg.org/css-text-decor/#line-decoration)
+    fn of(layout_box: &LayoutBox, decorations: Style) -> Self {
+        let font = FontDescription::of(&layout_box.style);
+        Style {
+            bold: font.weight >= 600,
+            italic: font.italic,
+            ..decorations
+        }
+    }
+
+    /// Adds the decorations of a box to the ones propagated to it.
+    fn decorate(self, layout_box: &LayoutBox) -> Self {
+        let line = layout_box.style.get("text-decoration-line");
+        Style {
+            underline: self.underline || line.contains("underline"),
+            strike: self.strike || line.contains("line-through"),
+            ..self
+        }
+    }
+
+    /// Returns the text wrapped in the ANSI escape codes of the style.
+    fn apply(&self, text: &str) -> String {
+        let codes: Vec<&str> = [
+            (self.bold, "1"),
+            (self.italic, "3"),
+            (self.underline, "4"),
+            (self.strike, "9"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, code)| enabled.then_some(code))
+        .collect();
+        match codes.is_empty() {
+            true => text.to_owned(),
+            false => format!("\x1b[{}m{text}\x1b[0m", codes.join(";")),
+        }
+    }
+}
+
+/// Returns the number of columns that text takes up, with wide characters, like CJK ideographs, taking up two.
+fn columns(text: &str) -> usize {
+    text.chars()
+        .map(|char| if is_wide(char) { 2 } else { 1 })
+        .sum()
+}
+
+/// A line of output, with the number of columns its text takes up without the escape codes.
+#[derive(Debug, Clone, Default)]
+struct Line {
+    text: String,
+    width: usize,
+}
+
+/// What a block is rendered in.
+#[derive(Debug, Clone, Copy, Default)]
+struct Context {
+    /// The indentation of the block, in CSS pixels.
+    indent: f64,
+    /// The text decorations that the ancestors of the block propagate to it.
+    decorations: Style,
+}
+
+/// A part of the inline content of a block.
+enum Piece {
+    Text {
+        text: String,
+        style: Style,
+        /// Whether spaces collapse, and so disappear at the start and end of lines.
+        collapsible: bool,
+        /// Whether lines can wrap at the spaces in the text.
+        wraps: bool,
+    },
+    /// Text that is never split, like the placeholder of a form control or the number of a link.
+    Atomic { text: String, style: Style },
+    /// A forced line break.
+    Break,
+}
+
+struct Renderer<'a> {
+    document: &'a Document,
+    width: usize,
+    ansi: bool,
+    lines: Vec<Line>,
+    /// Whether a blank line separates the next line from the lines before.
+    blank: bool,
+    /// The marker of a list item, which starts the next line.
+    marker: Option<String>,
+    /// The URLs of the links, in the order they are numbered.
+    links: Vec<String>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(document: &'a Document, width: usize, ansi: bool, links: Vec<String>) -> Self {
+        Renderer {
+            document,
+            width,
+            ansi,
+            lines: vec![],
+            blank: false,
+            marker: None,
+            links,
+        }
+    }
+
+    fn finish(&self) -> String {
+        let lines: Vec<&str> = self.lines.iter().map(|line| line.text.as_str()).collect();
+        lines.join("\n")
+    }
+
+    /// Adds a line, indented by a number of columns, after the list marker that starts it, if there is one.
+    fn push_line(&mut self, line: Line, indent: usize) {
+        if std::mem::take(&mut self.blank) && !self.lines.is_empty() {
+            self.lines.push(Line::default());
+        }
+        // Markers hang in the indentation before the first line of their list item.
+        let prefix = match self.marker.take() {
+            Some(marker) => {
+                let marker = format!("{marker} ");
+                let width = columns(&marker);
+                format!("{}{marker}", " ".repeat(indent.saturating_sub(width)))
+            }
+            None => " ".repeat(indent),
+        };
+        let prefix = match line.text.is_empty() {
+            true => prefix.trim_end().to_owned(),
+            false => prefix,
+        };
+        let width = columns(&prefix) + line.width;
+        self.lines.push(Line {
+            text: format!("{prefix}{}", line.text),
+            width,
+        });
+    }
+
+    /// Renders a block-level box.
+    fn block(&mut self, layout_box: &LayoutBox, context: &Context) {
+        if layout_box.style.get("display") == "table" {
+            return self.table(layout_box, context);
+        }
+        if layout_box.margin.top > 0.0 {
+            self.blank = true;
+        }
+        let context = Context {
+            indent: context.indent
+                + layout_box.margin.left
+                + layout_box.border.left
+                + layout_box.padding.left,
+            decorations: context.decorations.decorate(layout_box),
+        };
+        let indent = (context.indent / PIXELS_PER_COLUMN).floor() as usize;
+        let is_rule = layout_box
+            .element
+            .as_ref()
+            .is_some_and(|element| element.base().tag == Tag::Hr);
+        if is_rule {
+            let width = self.width.saturating_sub(indent).max(1);
+            self.push_line(
+                Line {
+                    text: "─".repeat(width),
+                    width,
+                },
+                indent,
+            );
+        } else if matches!(layout_box.kind, BoxKind::Replaced { .. })
+            || layout_box.children.iter().any(|child| child.inline_level)
+        {
+            let mut pieces = vec![];
+            match layout_box.kind {
+                BoxKind::Replaced { .. } => self.replaced(layout_box, &context, &mut pieces),
+                _ => self.inline_children(layout_box, &context, &mut pieces, &mut true),
+            }
+            let width = self.width.saturating_sub(indent).max(1);
+            let is_rtl = layout_box.style.get("direction") == "rtl";
+            for line in self.wrap(&pieces, width, is_rtl) {
+                self.push_line(line, indent);
+            }
+        } else {
+            self.block_children(layout_box, &context);
+        }
+        // A list item without text still shows its marker.
+        if self.marker.is_some() {
+            self.push_line(Line::default(), indent);
+        }
+        if layout_box.margin.bottom > 0.0 {
+            self.blank = true;
+        }
+    }
+
+    /// Renders the block-level children of a box, numbering the list items among them.
+    fn block_children(&mut self, layout_box: &LayoutBox, context: &Context) {
+        let list = layout_box.element.as_ref();
+        let is_item = |child: &&LayoutBox| child.style.get("display") == "list-item";
+        let reversed = list.is_some_and(|list| {
+            list.base().tag == Tag::Ol && list.get_attribute("reversed").is_some()
+        });
+        let start = list
+            .filter(|list| list.base().tag == Tag::Ol)
+            .and_then(|list| list.get_attribute("start"))
+            .and_then(|start| start.trim().parse::<i64>().ok());
+        let mut ordinal = match reversed {
+            true => start.unwrap_or(layout_box.children.iter().filter(is_item).count() as i64),
+            false => start.unwrap_or(1),
+        };
+        for child in &layout_box.children {
+            if is_item(&child) {
+                let value = child
+                    .element
+                    .as_ref()
+                    .filter(|element| element.base().tag == Tag::Li)
+                    .and_then(|element| element.get_attribute("value"))
+                    .and_then(|value| value.trim().parse::<i64>().ok());
+                ordinal = value.unwrap_or(ordinal);
+                self.marker = marker(child.style.get("list-style-type"), ordinal);
+                ordinal += if reversed { -1 } else { 1 };
+            }
+            self.block(child, context);
+        }
+    }
+
+    /// Renders a table, with its cells in columns, fitted to the width of the line.
+    fn table(&mut self, table: &LayoutBox, context: &Context) {
+        if table.margin.top > 0.0 {
+            self.blank = true;
+        }
+        let context = Context {
+            indent: context.indent + table.margin.left + table.border.left + table.padding.left,
+            decorations: context.decorations.decorate(table),
+        };
+        let indent = (context.indent / PIXELS_PER_COLUMN).floor() as usize;
+        let mut captions = vec![];
+        let mut rows = vec![];
+        table_parts(table, &mut captions, &mut rows);
+        for caption in captions {
+            self.block(caption, &context);
+        }
+        let rows: Vec<(Vec<&LayoutBox>, bool)> = rows
+            .into_iter()
+            .map(|row| {
+                let mut cells = vec![];
+                row_cells(row, &mut cells);
+                let is_header = is_header_row(row, &cells);
+                (cells, is_header)
+            })
+            .collect();
+        let count = rows.iter().map(|(cells, _)| cells.len()).max().unwrap_or(0);
+        if count == 0 {
+            return;
+        }
+        // Columns are as wide as their widest cell, and shrink in proportion if the table does not fit.
+        let cell_context = Context {
+            indent: 0.0,
+            decorations: context.decorations,
+        };
+        let mut widths = vec![0; count];
+        for (cells, _) in &rows {
+            for (column, cell) in cells.iter().enumerate() {
+                let lines = self.cell(cell, &cell_context, UNBOUNDED);
+                let width = lines.iter().map(|line| line.width).max().unwrap_or(0);
+                widths[column] = widths[column].max(width);
+            }
+        }
+        let available = self
+            .width
+            .saturating_sub(indent + 2 * (count - 1))
+            .max(count);
+        let total: usize = widths.iter().sum();
+        if total > available {
+            for width in &mut widths {
+                *width = (*width * available / total).max(1);
+            }
+        }
+        for (index, (cells, is_header)) in rows.iter().enumerate() {
+            let cells: Vec<Vec<Line>> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| self.cell(cell, &cell_context, *width))
+                .collect();
+            let height = cells.iter().map(Vec::len).max().unwrap_or(0);
+            for row in 0..height {
+                let mut line = Line::default();
+                for (column, lines) in cells.iter().enumerate() {
+                    if column > 0 {
+                        line.text.push_str("  ");
+                        line.width += 2;
+                    }
+                    let cell = lines.get(row).cloned().unwrap_or_default();
+                    let padding = widths[column].saturating_sub(cell.width);
+                    line.text.push_str(&cell.text);
+                    line.text.push_str(&" ".repeat(padding));
+                    line.width += cell.width + padding;
+                }
+                let trimmed = line.text.trim_end().len();
+                line.width -= line.text.len() - trimmed;
+                line.text.truncate(trimmed);
+                self.push_line(line, indent);
+            }
+            // A rule separates the header rows from the rows of data.
+            let is_last_header = *is_header && rows.get(index + 1).is_some_and(|(_, next)| !next);
+            if is_last_header {
+                let width = widths.iter().sum::<usize>() + 2 * (count - 1);
+                self.push_line(
+                    Line {
+                        text: "─".repeat(width),
+                        width,
+                    },
+                    indent,
+                );
+            }
+        }
+        if table.margin.bottom > 0.0 {
+            self.blank = true;
+        }
+    }
+
+    /// Renders the contents of a table cell into lines of a width, on their own.
+    fn cell(&mut self, cell: &LayoutBox, context: &Context, width: usize) -> Vec<Line> {
+        let links = std::mem::take(&mut self.links);
+        let mut renderer = Renderer::new(self.document, width, self.ansi, links);
+        let context = Context {
+            indent: context.indent - cell.margin.left - cell.border.left - cell.padding.left,
+            ..*context
+        };
+        renderer.block(cell, &context);
+        self.links = renderer.links;
+        renderer.lines
+    }
+
+    /// Collects the inline content of the children of a box.
+    fn inline_children(
+        &mut self,
+        layout_box: &LayoutBox,
+        context: &Context,
+        pieces: &mut Vec<Piece>,
+        after_space: &mut bool,
+    ) {
+        for child in &layout_box.children {
+            self.inline(child, context, pieces, after_space);
+        }
+    }
+
+    /// Collects the inline content of a box.
+    fn inline(
+        &mut self,
+        layout_box: &LayoutBox,
+        context: &Context,
+        pieces: &mut Vec<Piece>,
+        after_space: &mut bool,
+    ) {
+        let is_visible = layout_box.style.get("visibility") == "visible";
+        let style = Style::of(layout_box, context.decorations);
+        match &layout_box.kind {
+            BoxKind::Text(text) => {
+                let text = process_white_space(text, &layout_box.style, after_space);
+                if !is_visible {
+                    return;
+                }
+                let white_space = layout_box.style.get("white-space");
+                for (index, segment) in text.split('\n').enumerate() {
+                    if index > 0 {
+                        pieces.push(Piece::Break);
+                    }
+                    pieces.push(Piece::Text {
+                        text: segment.to_owned(),
+                        style,
+                        collapsible: matches!(white_space, "normal" | "nowrap" | "pre-line"),
+                        wraps: !matches!(white_space, "nowrap" | "pre"),
+                    });
+                }
+            }
+            BoxKind::LineBreak => {
+                pieces.push(Piece::Break);
+                *after_space = true;
+            }
+            BoxKind::Replaced { .. } => {
+                if is_visible {
+                    self.replaced(layout_box, context, pieces);
+                }
+                *after_space = false;
+            }
+            BoxKind::Inline | BoxKind::Flow => {
+                let context = Context {
+                    decorations: context.decorations.decorate(layout_box),
+                    ..*context
+                };
+                let element = layout_box.element.as_ref();
+                if element.is_some_and(|element| element.base().tag == Tag::Button) {
+                    // Buttons show their text in brackets, in one piece.
+                    let mut contents = vec![];
+                    self.inline_children(layout_box, &context, &mut contents, &mut true);
+                    let text = plain_text(&contents);
+                    let text = format!("[ {} ]", text.trim());
+                    pieces.push(Piece::Atomic { text, style });
+                    *after_space = false;
+                    return;
+                }
+                self.inline_children(layout_box, &context, pieces, after_space);
+                // Links are numbered after their text, and listed with their URLs at the end.
+                let href = element
+                    .filter(|element| matches!(element.base().tag, Tag::A | Tag::Area))
+                    .and_then(|element| element.get_attribute("href"));
+                if let (Some(href), true) = (href, is_visible) {
+                    let url = resolve_url(&self.document.state().url, href);
+                    let number = match self.links.iter().position(|link| *link == url) {
+                        Some(index) => index + 1,
+                        None => {
+                            self.links.push(url);
+                            self.links.len()
+                        }
+                    };
+                    pieces.push(Piece::Atomic {
+                        text: format!("[{number}]"),
+                        style: Style::default(),
+                    });
+                    *after_space = false;
+                }
+            }
+        }
+    }
+
+    /// Collects the placeholder of a replaced element, like an image or a form control.
+    fn replaced(&mut self, layout_box: &LayoutBox, context: &Context, pieces: &mut Vec<Piece>) {
+        let Some(element) = &layout_box.element else {
+            return;
+        };
+        let style = Style::of(layout_box, context.decorations.decorate(layout_box));
+        if let Some(text) = placeholder(element) {
+            pieces.push(Piece::Atomic { text, style });
+        }
+    }
+
+    /// Breaks inline content into lines that fit in a width, where it can wrap, and puts the text of each line in the order it reads in a direction.
+    fn wrap(&self, pieces: &[Piece], width: usize, is_rtl: bool) -> Vec<Line> {
+        let mut wrapper = Wrapper {
+            width,
+            ansi: self.ansi,
+            is_rtl,
+            lines: vec![],
+            tokens: vec![],
+            line_width: 0,
+            space: None,
+            can_break: false,
+            break_at: None,
+        };
+        for piece in pieces {
+            match piece {
+                Piece::Text {
+                    text,
+                    style,
+                    collapsible,
+                    wraps,
+                } => {
+                    let mut rest = text.as_str();
+                    while !rest.is_empty() {
+                        let is_space = rest.starts_with(' ');
+                        let length = rest
+                            .find(|char| (char == ' ') != is_space)
+                            .unwrap_or(rest.len());
+                        let (token, next) = rest.split_at(length);
+                        rest = next;
+                        match is_space {
+                            true => wrapper.space(token, *style, *collapsible, *wraps),
+                            false => wrapper.word(token, *style, *wraps),
+                        }
+                    }
+                }
+                Piece::Atomic { text, style } => wrapper.word(text, *style, false),
+                Piece::Break => wrapper.end_line(true),
+            }
+        }
+        wrapper.end_line(false);
+        wrapper.lines
+    }
+}
+
+/// Fills lines with words and spaces, starting a new line where a word does not fit.
+struct Wrapper {
+    width: usize,
+    ansi: bool,
+    /// Whether the paragraph reads right to left, which decides how text of both directions is ordered on its lines.
+    is_rtl: bool,
+    lines: Vec<Line>,
+    /// The words and spaces of the current line, with their styles.
+    tokens: Vec<(String, Style)>,
+    line_width: usize,
+    /// A collapsible space after the last word, which is dropped if the line ends after it.
+    space: Option<(String, Style)>,
+    /// Whether the line can wrap before the next word.
+    can_break: bool,
+    /// The index of the token after the last place where the line can wrap.
+    break_at: Option<usize>,
+}
+
+impl Wrapper {
+    fn push(&mut self, text: &str, style: Style) {
+        self.line_width += columns(text);
+        self.tokens.push((text.to_owned(), style));
+    }
+
+    fn space(&mut self, text: &str, style: Style, collapsible: bool, wraps: bool) {
+        if collapsible {
+            if !self.tokens.is_empty() {
+                self.space = Some((String::from(" "), style));
+            }
+        } else {
+            if let Some((space, style)) = self.space.take() {
+                self.push(&space, style);
+            }
+            self.push(text, style);
+        }
+        self.can_break = wraps;
+    }
+
+    fn word(&mut self, text: &str, style: Style, wraps: bool) {
+        let space = self.space.as_ref().map_or(0, |(space, _)| columns(space));
+        let width = columns(text);
+        let fits = self.line_width + space + width <= self.width;
+        if !fits && self.can_break && !self.tokens.is_empty() {
+            self.end_line(false);
+        }
+        if let Some((space, style)) = self.space.take() {
+            self.push(&space, style);
+        }
+        if self.can_break {
+            self.break_at = Some(self.tokens.len());
+        } else if !fits {
+            // A word that cannot be wrapped before, like the number after a link, takes the words it is attached to onto the next line.
+            if let Some(index) = self.break_at.filter(|index| *index > 0) {
+                let carried = self.tokens.split_off(index);
+                self.end_line(false);
+                for (text, style) in carried {
+                    self.push(&text, style);
+                }
+            }
+        }
+        // Words that are longer than a whole line are broken where they reach its end.
+        let mut rest = text;
+        while wraps
+            && columns(rest) > self.width.saturating_sub(self.line_width)
+            && columns(rest) > self.width
+        {
+            let mut fits = 0;
+            let mut length = 0;
+            for char in rest.chars() {
+                let char_width = if is_wide(char) { 2 } else { 1 };
+                if self.line_width + fits + char_width > self.width {
+                    break;
+                }
+                fits += char_width;
+                length += char.len_utf8();
+            }
+            if length == 0 && self.tokens.is_empty() {
+                length = rest.chars().next().map_or(0, char::len_utf8);
+            }
+            let (start, end) = rest.split_at(length);
+            self.push(start, style);
+            self.end_line(false);
+            rest = end;
+        }
+        self.push(rest, style);
+        self.can_break = false;
+    }
+
+    /// Ends the current line. A forced line break ends it even if it is empty.
+    fn end_line(&mut self, forced: bool) {
+        self.space = None;
+        self.break_at = None;
+        self.can_break = false;
+        // Spaces before the place where the line wraps are dropped.
+        while self
+            .tokens
+            .last()
+            .is_some_and(|(text, _)| !text.is_empty() && text.trim_start_matches(' ').is_empty())
+        {
+            let (text, _) = self.tokens.pop().expect("The line has a last token.");
+            self.line_width -= columns(&text);
+        }
+        if self.tokens.is_empty() && !forced {
+            return;
+        }
+        // Text that reads right to left is reordered, and runs of text with the same style are styled together.
+        let tokens = std::mem::take(&mut self.tokens);
+        let parts: Vec<&str> = tokens.iter().map(|(text, _)| text.as_str()).collect();
+        let mut spans: Vec<(String, Style)> = vec![];
+        for (index, text) in reorder_text(&parts, self.is_rtl) {
+            let style = tokens[index].1;
+            match spans.last_mut() {
+                Some((last, last_style)) if *last_style == style => last.push_str(&text),
+                _ => spans.push((text, style)),
+            }
+        }
+        let text = spans
+            .into_iter()
+            .map(|(text, style)| match self.ansi {
+                true => style.apply(&text),
+                false => text,
+            })
+            .collect();
+        self.lines.push(Line {
+            text,
+            width: self.line_width,
+        });
+        self.line_width = 0;
+    }
+}
+
+/// Returns the text of inline content, without styles.
+fn plain_text(pieces: &[Piece]) -> String {
+    pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Text { text, .. } | Piece::Atomic { text, .. } => text.as_str(),
+            Piece::Break => " ",
+        })
+        .collect()
+}
+
+/// Returns the marker of a list item with a `list-style-type` and an ordinal, or `None` if it has none.
+///
+/// [Reference](https://drafts.csswg.org/css-counter-styles/#predefined-counters)
+fn marker(list_style_type: &str, ordinal: i64) -> Option<String> {
+    let alphabetic = |letters: &str| {
+        let letters: Vec<char> = letters.chars().collect();
+        let mut value = ordinal;
+        let mut text = vec![];
+        while value > 0 {
+            value -= 1;
+            text.push(letters[(value % 26) as usize]);
+            value /= 26;
+        }
+        text.into_iter().rev().collect::<String>()
+    };
+    let roman = || {
+        let numerals = [
+            (1000, "m"),
+            (900, "cm"),
+            (500, "d"),
+            (400, "cd"),
+            (100, "c"),
+            (90, "xc"),
+            (50, "l"),
+            (40, "xl"),
+            (10, "x"),
+            (9, "ix"),
+            (5, "v"),
+            (4, "iv"),
+            (1, "i"),
+        ];
+        let mut value = ordinal;
+        let mut text = String::new();
+        for (amount, numeral) in numerals {
+            while value >= amount {
+                text.push_str(numeral);
+                value -= amount;
+            }
+        }
+        text
+    };
+    let marker = match list_style_type {
+        "none" => return None,
+        "disc" => String::from("•"),
+        "circle" => String::from("◦"),
+        "square" => String::from("▪"),
+        "decimal-leading-zero" => format!("{ordinal:02}."),
+        "lower-alpha" | "lower-latin" if ordinal > 0 => {
+            format!("{}.", alphabetic("abcdefghijklmnopqrstuvwxyz"))
+        }
+        "upper-alpha" | "upper-latin" if ordinal > 0 => {
+            format!("{}.", alphabetic("ABCDEFGHIJKLMNOPQRSTUVWXYZ"))
+        }
+        "lower-roman" if (1..4000).contains(&ordinal) => format!("{}.", roman()),
+        "upper-roman" if (1..4000).contains(&ordinal) => format!("{}.", roman().to_uppercase()),
+        // Strings are used as they are.
+        value if value.len() >= 2 && (value.starts_with('"') || value.starts_with('\'')) => {
+            value[1..value.len() - 1].to_owned()
+        }
+        _ => format!("{ordinal}."),
+    };
+    Some(marker)
+}
+
+/// Returns the text that stands in for a replaced element: the alternative text of an image, or the state of a form control. Images with empty alternative text are decorative, and have none.
+fn placeholder(element: &Element) -> Option<String> {
+    let attribute = |name: &str| element.get_attribute(name);
+    let size = |name: &str, default: usize| {
+        attribute(name)
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|size| *size > 0)
+            .unwrap_or(default)
+    };
+    // Text fields show their value, or their placeholder, on a line of their size.
+    let field = |text: &str, size: usize| {
+        let padding = size.saturating_sub(columns(text));
+        format!("[{text}{}]", "_".repeat(padding))
+    };
+    let text = match element.base().tag {
+        Tag::Img => match attribute("alt") {
+            Some(alt) if alt.trim().is_empty() => return None,
+            Some(alt) => format!("[{}]", alt.trim()),
+            None => String::from("[image]"),
+        },
+        Tag::Input => {
+            let kind = attribute("type").unwrap_or_default().to_ascii_lowercase();
+            let checked = attribute("checked").is_some();
+            let value = attribute("value");
+            match kind.as_str() {
+                "checkbox" => String::from(if checked { "[x]" } else { "[ ]" }),
+                "radio" => String::from(if checked { "(*)" } else { "( )" }),
+                "submit" => format!("[ {} ]", value.unwrap_or("Submit")),
+                "reset" => format!("[ {} ]", value.unwrap_or("Reset")),
+                "button" => format!("[ {} ]", value.unwrap_or_default()),
+                "image" => format!("[ {} ]", attribute("alt").or(value).unwrap_or("Submit")),
+                "file" => String::from("[ Choose file ]"),
+                _ => {
+                    let text = match (value.filter(|value| !value.is_empty()), kind.as_str()) {
+                        (Some(value), "password") => "*".repeat(value.chars().count()),
+                        (Some(value), _) => value.to_owned(),
+                        (None, _) => attribute("placeholder").unwrap_or_default().to_owned(),
+                    };
+                    field(&text, size("size", 20))
+                }
+            }
+        }
+        Tag::Textarea => {
+            let text = element.text_content().unwrap_or_default();
+            let text = match text.trim().is_empty() {
+                true => attribute("placeholder").unwrap_or_default().to_owned(),
+                false => text.trim().lines().next().unwrap_or_default().to_owned(),
+            };
+            field(&text, size("cols", 20))
+        }
+        Tag::Select => {
+            let options = element.get_elements_by_tag_name("option");
+            let options: Vec<Element> = (0..options.len())
+                .filter_map(|index| options.item(index).map(|option| option.clone_ref()))
+                .collect();
+            let selected = options
+                .iter()
+                .find(|option| option.get_attribute("selected").is_some())
+                .or(options.first());
+            let text = selected
+                .and_then(|option| option.text_content())
+                .unwrap_or_default();
+            format!(
+                "[{} ▾]",
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            )
+        }
+        _ => format!("[{}]", element.local_name()),
+    };
+    Some(text)
+}
+
+/// Collects the captions and the rows of a table, looking through row groups, but not into nested tables.
+fn table_parts<'a>(
+    layout_box: &'a LayoutBox,
+    captions: &mut Vec<&'a LayoutBox>,
+    rows: &mut Vec<&'a LayoutBox>,
+) {
+    for child in &layout_box.children {
+        match child.style.get("display") {
+            "table-caption" => captions.push(child),
+            "table-row" => rows.push(child),
+            "table" | "inline-table" => {}
+            _ => table_parts(child, captions, rows),
+        }
+    }
+}
+
+/// Collects the cells of a table row.
+fn row_cells<'a>(layout_box: &'a LayoutBox, cells: &mut Vec<&'a LayoutBox>) {
+    for child in &layout_box.children {
+        match child.style.get("display") {
+            "table-cell" => cells.push(child),
+            "table" | "inline-table" => {}
+            _ => row_cells(child, cells),
+        }
+    }
+}
+
+/// Returns true if a table row is a header: in a `thead`, or made only of `th` cells.
+fn is_header_row(row: &LayoutBox, cells: &[&LayoutBox]) -> bool {
+    let has_tag = |layout_box: &LayoutBox, tag: Tag| {
+        layout_box
+            .element
+            .as_ref()
+            .is_some_and(|element| element.base().tag == tag)
+    };
+    let in_head = row
+        .element
+        .as_ref()
+        .and_then(|row| row.parent_element())
+        .is_some_and(|parent| parent.base().tag == Tag::Thead);
+    in_head || (!cells.is_empty() && cells.iter().all(|cell| has_tag(cell, Tag::Th)))
+}
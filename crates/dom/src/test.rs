@@ -15,3 +15,3395 @@ fn test() {
     println!("{}", child.parent_node().is_some());
     // println!("{:?}", time.elapsed());
 }
+
+#[test]
+fn slot_assignment() {
+    use crate::{Event, EventType, HTMLSlotElement, ShadowRootInit, ShadowRootMode};
+    use std::{cell::Cell, rc::Rc};
+
+    let document = Document::new();
+    let mut host = document.create_element("div");
+    let shadow_root = host
+        .attach_shadow(ShadowRootInit::new(ShadowRootMode::Open))
+        .unwrap();
+
+    let mut default_slot: HTMLSlotElement = document.create_element("slot").try_into().unwrap();
+    let mut named_slot: HTMLSlotElement = document.create_element("slot").try_into().unwrap();
+    named_slot.set_name("title");
+    shadow_root.append_child(&mut default_slot).unwrap();
+    shadow_root.append_child(&mut named_slot).unwrap();
+
+    let slot_changes = Rc::new(Cell::new(0));
+    let counter = slot_changes.clone();
+    named_slot.add_event_listener(
+        EventType::SlotChange,
+        Some((move |_: &mut Event| counter.set(counter.get() + 1)).into()),
+        None,
+    );
+
+    let mut span = document.create_element("span");
+    host.append_child(&mut span).unwrap();
+    assert!(default_slot.assigned_nodes(None)[0] == span);
+    assert!(span.assigned_slot().unwrap() == default_slot);
+    assert_eq!(slot_changes.get(), 0);
+
+    // Changing the slot attribute moves the element to the named slot.
+    span.set_slot("title");
+    assert!(default_slot.assigned_nodes(None).is_empty());
+    assert!(named_slot.assigned_nodes(None)[0] == span);
+    assert_eq!(slot_changes.get(), 1);
+
+    // Removing the element unassigns it.
+    host.remove_child(&mut span).unwrap();
+    assert!(named_slot.assigned_nodes(None).is_empty());
+    assert!(span.assigned_slot().is_none());
+    assert_eq!(slot_changes.get(), 2);
+
+    // Renaming a slot reassigns the slottables of the host.
+    let mut heading = document.create_element("h1");
+    heading.set_slot("heading");
+    host.append_child(&mut heading).unwrap();
+    assert!(heading.assigned_slot().is_none());
+    named_slot.set_name("heading");
+    assert!(heading.assigned_slot().unwrap() == named_slot);
+    assert_eq!(slot_changes.get(), 3);
+}
+
+#[test]
+fn shadow_event_dispatch() {
+    use crate::{AsEvent, Event, EventInit, EventType, ShadowRootInit, ShadowRootMode};
+    use std::{cell::RefCell, rc::Rc};
+
+    let document = Document::new();
+    let mut main = document.create_element("main");
+    let mut host = document.create_element("div");
+    main.append_child(&mut host).unwrap();
+    let shadow_root = host
+        .attach_shadow(ShadowRootInit::new(ShadowRootMode::Closed))
+        .unwrap();
+    let mut button = document.create_element("button");
+    shadow_root.append_child(&mut button).unwrap();
+
+    let log = Rc::new(RefCell::new(vec![]));
+    let listener = |name: &'static str| {
+        let log = log.clone();
+        move |event: &mut Event| {
+            let path: Vec<String> = event
+                .composed_path()
+                .iter()
+                .map(|node| node.node_name())
+                .collect();
+            log.borrow_mut().push(format!(
+                "{name}: {:?} at {}, path {}",
+                event.event_phase(),
+                event.target().unwrap().node_name(),
+                path.join(" ")
+            ));
+        }
+    };
+    button.add_event_listener(EventType::Click, Some(listener("button").into()), None);
+    host.add_event_listener(EventType::Click, Some(listener("host").into()), None);
+    main.add_event_listener(EventType::Click, Some(listener("main").into()), None);
+
+    // Outside of the closed shadow tree, the event is targeted at its host, and the nodes in the tree are left out of the path.
+    let init = EventInit {
+        bubbles: true,
+        composed: true,
+        ..Default::default()
+    };
+    let mut event = Event::new(EventType::Click, Some(init));
+    button.dispatch_event(&mut event);
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "button: AtTarget at BUTTON, path BUTTON #document-fragment DIV MAIN",
+            "host: AtTarget at DIV, path DIV MAIN",
+            "main: BubblingPhase at DIV, path DIV MAIN",
+        ]
+    );
+    // The target in the shadow tree is not exposed after the dispatch either.
+    assert!(*event.target().unwrap() == host);
+    assert!(event.composed_path().is_empty());
+
+    // An event that is not composed does not leave the shadow tree.
+    log.borrow_mut().clear();
+    let init = EventInit {
+        bubbles: true,
+        ..Default::default()
+    };
+    let mut event = Event::new(EventType::Click, Some(init));
+    button.dispatch_event(&mut event);
+    assert_eq!(
+        *log.borrow(),
+        vec!["button: AtTarget at BUTTON, path BUTTON #document-fragment"]
+    );
+    assert!(event.target().is_none());
+}
+
+#[test]
+fn removed_event_listeners() {
+    use crate::{domitem::DOMItem, Event, EventListenerOrEventListenerObject, EventType};
+    use std::{cell::RefCell, rc::Rc};
+
+    let document = Document::new();
+    let mut button = document.create_element("button");
+    let log = Rc::new(RefCell::new(vec![]));
+    let record = |name: &'static str| -> EventListenerOrEventListenerObject {
+        let log = log.clone();
+        (move |_: &mut Event| log.borrow_mut().push(name)).into()
+    };
+    let second = record("second");
+    let target = button.clone_ref();
+    let remover = second.clone();
+    button.add_event_listener(
+        EventType::Click,
+        Some(
+            (move |_: &mut Event| {
+                let mut target = target.clone_ref();
+                target.remove_event_listener(EventType::Click, Some(remover.clone()), None);
+            })
+            .into(),
+        ),
+        None,
+    );
+    button.add_event_listener(EventType::Click, Some(second), None);
+    button.add_event_listener(EventType::Click, Some(record("third")), None);
+
+    // A listener that an earlier listener of the same dispatch removes is not called.
+    button.dispatch_event(&mut Event::new(EventType::Click, None));
+    assert_eq!(*log.borrow(), vec!["third"]);
+    button.dispatch_event(&mut Event::new(EventType::Click, None));
+    assert_eq!(*log.borrow(), vec!["third", "third"]);
+}
+
+#[test]
+fn custom_element_reactions() {
+    use crate::{CustomElement, HTMLElement};
+    use std::{cell::RefCell, rc::Rc};
+
+    struct Logger(Rc<RefCell<Vec<String>>>);
+    impl CustomElement for Logger {
+        fn observed_attributes(&self) -> Vec<&str> {
+            vec!["state"]
+        }
+        fn connected_callback(&self, _: &mut HTMLElement) {
+            self.0.borrow_mut().push("connected".to_owned());
+        }
+        fn disconnected_callback(&self, _: &mut HTMLElement) {
+            self.0.borrow_mut().push("disconnected".to_owned());
+        }
+        fn adopted_callback(&self, _: &mut HTMLElement, _: &Document, _: &Document) {
+            self.0.borrow_mut().push("adopted".to_owned());
+        }
+        fn attribute_changed_callback(
+            &self,
+            _: &mut HTMLElement,
+            name: &str,
+            old_value: Option<&str>,
+            new_value: Option<&str>,
+        ) {
+            self.0
+                .borrow_mut()
+                .push(format!("{name}: {old_value:?} -> {new_value:?}"));
+        }
+    }
+
+    let log = Rc::new(RefCell::new(vec![]));
+    let mut document = Document::new();
+    let mut root = document.create_element("html");
+    document.append_child(&mut root).unwrap();
+
+    // Elements created before the definition are upgraded.
+    let mut element = document.create_element("x-logger");
+    element.set_attribute("state", "initial");
+    element.set_attribute("ignored", "");
+    root.append_child(&mut element).unwrap();
+    assert!(log.borrow().is_empty());
+
+    let registry = document.custom_elements();
+    registry.define("x-logger", Logger(log.clone())).unwrap();
+    assert_eq!(
+        *log.borrow(),
+        vec!["state: None -> Some(\"initial\")", "connected"]
+    );
+    assert!(registry.define("x-logger", Logger(log.clone())).is_err());
+    assert!(registry.define("logger", Logger(log.clone())).is_err());
+
+    log.borrow_mut().clear();
+    element.set_attribute("state", "changed");
+    element.remove_attribute("state");
+    root.remove_child(&mut element).unwrap();
+    Document::new().adopt_node(&mut element).unwrap();
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "state: Some(\"initial\") -> Some(\"changed\")",
+            "state: Some(\"changed\") -> None",
+            "disconnected",
+            "adopted"
+        ]
+    );
+}
+
+#[test]
+fn declarative_shadow_roots() {
+    use crate::{domitem::DOMItem, GetHTMLOptions, InnerHtml, ShadowRootMode};
+
+    let markup = concat!(
+        "<x-panel id=panel>",
+        "<template shadowrootmode=\"closed\" shadowrootdelegatesfocus shadowrootclonable>",
+        "<style>p > b { color: red }</style><slot name=title></slot>",
+        "<nested-part><template shadowrootmode=open shadowrootserializable>deep</template></nested-part>",
+        "</template>",
+        "<h2 slot=title>Title &amp; more</h2>",
+        "</x-panel>"
+    );
+    let document = Document::parse_html_unsafe(markup);
+    let body = document
+        .first_element_child()
+        .unwrap()
+        .last_element_child()
+        .unwrap();
+    let panel = body.first_element_child().unwrap();
+
+    // The closed shadow root is attached, but hidden from `shadow_root`.
+    assert!(panel.shadow_root().is_none());
+    let shadow_root = panel.base().shadow_root.as_ref().unwrap();
+    assert_eq!(shadow_root.mode(), ShadowRootMode::Closed);
+    assert!(shadow_root.delegates_focus());
+    assert!(shadow_root.clonable());
+    assert!(!shadow_root.serializable());
+
+    // The heading is assigned to the named slot, which a closed shadow root keeps hidden.
+    let heading = panel.first_element_child().unwrap();
+    assert!(AsNode::cast(&heading).base().assigned_slot.is_some());
+    assert!(heading.assigned_slot().is_none());
+
+    // Only shadow roots that are serializable or explicitly listed are emitted.
+    assert_eq!(
+        body.get_html(Some(GetHTMLOptions {
+            serializable_shadow_roots: true,
+            ..Default::default()
+        })),
+        r#"<x-panel id="panel"><h2 slot="title">Title &amp; more</h2></x-panel>"#
+    );
+    let html = body.get_html(Some(GetHTMLOptions {
+        serializable_shadow_roots: true,
+        shadow_roots: vec![shadow_root],
+    }));
+    assert_eq!(html, markup_with_quoted_attributes());
+
+    // Serialized markup parses back into the same tree.
+    let mut copy = document.create_element("div");
+    copy.set_html_unsafe(&html);
+    let copy_panel = copy.first_element_child().unwrap();
+    let copy_shadow_root = copy_panel.base().shadow_root.as_ref().unwrap();
+    assert_eq!(copy_shadow_root.inner_html(), shadow_root.inner_html());
+
+    // `inner_html` leaves declarative templates in the tree.
+    copy.set_inner_html(markup).unwrap();
+    let copy_panel = copy.first_element_child().unwrap();
+    assert!(copy_panel.base().shadow_root.is_none());
+    assert_eq!(copy_panel.child_element_count(), 2);
+}
+
+fn markup_with_quoted_attributes() -> &'static str {
+    concat!(
+        "<x-panel id=\"panel\">",
+        "<template shadowrootmode=\"closed\" shadowrootdelegatesfocus=\"\" shadowrootclonable=\"\">",
+        "<style>p > b { color: red }</style><slot name=\"title\"></slot>",
+        "<nested-part><template shadowrootmode=\"open\" shadowrootserializable=\"\">deep</template></nested-part>",
+        "</template>",
+        "<h2 slot=\"title\">Title &amp; more</h2>",
+        "</x-panel>"
+    )
+}
+
+#[test]
+fn template_contents() {
+    use crate::{
+        domitem::DOMItem, html_element::template_contents, CustomElement, HTMLElement, InnerHtml,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    struct Counter(Rc<Cell<usize>>);
+    impl CustomElement for Counter {
+        fn connected_callback(&self, _: &mut HTMLElement) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let markup = "<ul><template id=row><li class=row><x-counter>Row</x-counter><!--end--></li></template></ul>";
+    let mut document = Document::parse_html_unsafe(markup);
+    let connected = Rc::new(Cell::new(0));
+    document
+        .custom_elements()
+        .define("x-counter", Counter(connected.clone()))
+        .unwrap();
+    let body = document
+        .first_element_child()
+        .unwrap()
+        .last_element_child()
+        .unwrap();
+    let mut list = body.first_element_child().unwrap();
+    let template = list.first_element_child().unwrap();
+
+    // The children of the template are parsed into its contents, which belong to an inert document.
+    assert!(!template.has_child_nodes());
+    let content = template_contents(&template).unwrap();
+    assert_eq!(content.child_nodes().len(), 1);
+    let inert_document = content.owner_document().unwrap();
+    assert!(inert_document != document);
+    assert!(document.template_contents_owner() == inert_document);
+    assert!(inert_document.template_contents_owner() == inert_document);
+    assert_eq!(connected.get(), 0);
+    assert_eq!(
+        body.inner_html(),
+        "<ul><template id=\"row\"><li class=\"row\"><x-counter>Row</x-counter><!--end--></li></template></ul>"
+    );
+
+    // Clones carry the contents, and stamped out copies are upgraded once they are connected.
+    let copy = HTMLElement::clone_element(&template, &document, true);
+    let copy_content = template_contents(&copy).unwrap();
+    assert!(!copy_content.is_same_node(&content));
+    assert_eq!(copy.inner_html(), template.inner_html());
+    for _ in 0..2 {
+        let mut row = crate::DocumentFragment {
+            node: copy_content.clone_ref(),
+        }
+        .clone_node(true);
+        list.append_child(&mut row).unwrap();
+    }
+    assert_eq!(list.get_elements_by_class_name("row").len(), 2);
+    assert_eq!(connected.get(), 2);
+    assert_eq!(content.child_nodes().len(), 1);
+
+    // Adopting a template moves its contents into the new document's inert document.
+    let mut template = template;
+    let other_document = Document::new();
+    other_document.adopt_node(&mut template).unwrap();
+    let content = template_contents(&template).unwrap();
+    assert!(content.owner_document().unwrap() == other_document.template_contents_owner());
+    assert!(
+        AsNode::cast(&content.child_nodes()[0])
+            .owner_document()
+            .unwrap()
+            == other_document.template_contents_owner()
+    );
+    assert_eq!(
+        template.inner_html(),
+        "<li class=\"row\"><x-counter>Row</x-counter><!--end--></li>"
+    );
+
+    // Setting the markup of a template replaces its contents.
+    template.set_inner_html("<p>Replaced</p>").unwrap();
+    assert!(!template.has_child_nodes());
+    assert_eq!(content.child_nodes().len(), 1);
+    assert_eq!(template.inner_html(), "<p>Replaced</p>");
+}
+
+#[test]
+fn inline_style() {
+    let document = Document::new();
+    let mut element = document.create_element("div");
+    let mut style = element.style_mut();
+
+    // Shorthands expand into longhands, and serialize back when all of them are present.
+    style
+        .set_font("italic bold 16px / 1.5 \"Helvetica Neue\", sans-serif")
+        .unwrap();
+    assert_eq!(style.font_weight(), "bold");
+    assert_eq!(style.line_height(), "1.5");
+    assert_eq!(style.font_family(), "\"Helvetica Neue\", sans-serif");
+    assert_eq!(
+        style.font(),
+        "italic bold 16px/1.5 \"Helvetica Neue\", sans-serif"
+    );
+    style.set_flex("2").unwrap();
+    assert_eq!(style.flex(), "2 1 0%");
+    style.set_flex("none").unwrap();
+    assert_eq!(style.get_property_value("flex-basis"), "auto");
+    style
+        .set_background("url(\"a;b.png\") no-repeat center / cover #fff")
+        .unwrap();
+    assert_eq!(style.background_image(), "url(\"a;b.png\")");
+    assert_eq!(style.get_property_value("background-size"), "cover");
+    assert_eq!(style.background_color(), "#fff");
+    style.set_property("list-style", "none", None).unwrap();
+    assert_eq!(style.get_property_value("list-style-type"), "none");
+    assert_eq!(style.get_property_value("list-style-image"), "none");
+    style.set_text_decoration("underline dotted red").unwrap();
+    assert_eq!(
+        style.get_property_value("text-decoration-line"),
+        "underline"
+    );
+
+    // Invalid values leave the declaration as it was.
+    style.set_flex("1 2 3 4").unwrap();
+    style.set_border("solid solid").unwrap();
+    style.set_margin("1px 2px 3px 4px 5px").unwrap();
+    assert_eq!(style.flex(), "0 0 auto");
+    assert_eq!(style.border(), "");
+    assert_eq!(style.margin(), "");
+    style.set_color("green").unwrap();
+    style.set_width("10px").unwrap();
+    style.set_display("block").unwrap();
+    let attribute = element.get_attribute("style").unwrap();
+    style.set_color("banana").unwrap();
+    style.set_width("10").unwrap();
+    style.set_display("sideways").unwrap();
+    style.set_property("padding-left", "-1px", None).unwrap();
+    style.set_property("z-index", "1.5", None).unwrap();
+    assert_eq!(style.color(), "green");
+    assert_eq!(style.width(), "10px");
+    assert_eq!(style.display(), "block");
+    assert_eq!(style.padding_left(), "");
+    assert_eq!(style.z_index(), "");
+    assert_eq!(element.get_attribute("style").unwrap(), attribute);
+    // Invalid longhands in the attribute are dropped, and the valid ones around them kept.
+    element.set_attribute(
+        "style",
+        "color: banana; width: 10; height: 5px; display: sideways",
+    );
+    assert_eq!(style.css_text(), "height: 5px;");
+    style.set_css_text("display: inline flex").unwrap();
+    assert_eq!(style.display(), "inline flex");
+
+    // CSS-wide keywords apply to every longhand of a shorthand.
+    style.set_padding("inherit").unwrap();
+    assert_eq!(style.padding_top(), "inherit");
+    assert_eq!(style.padding(), "inherit");
+    style.set_padding_top("1px").unwrap();
+    assert_eq!(style.padding(), "");
+
+    // Changes to the attribute are seen by existing declarations, and the other way round.
+    element.set_attribute(
+        "style",
+        "/* theme */ --Accent: #08f; color: var(--Accent) !important; border-top: 1px solid; border-right: 1px solid; border-bottom: 1px solid; border-left: 1px solid",
+    );
+    assert_eq!(style.get_property_value("--Accent"), "#08f");
+    assert_eq!(style.get_property_value("--accent"), "");
+    assert_eq!(style.get_property_priority("color"), "important");
+    assert_eq!(style.border(), "1px solid");
+    assert_eq!(
+        style.css_text(),
+        "--Accent: #08f; color: var(--Accent) !important; border: 1px solid;"
+    );
+    style.remove_property("border-left-width").unwrap();
+    assert_eq!(
+        element.get_attribute("style").unwrap(),
+        "--Accent: #08f; color: var(--Accent) !important; border-top: 1px solid; border-right: 1px solid; border-bottom: 1px solid; border-left-style: solid; border-left-color: currentcolor;"
+    );
+    style.set_css_text("").unwrap();
+    assert!(style.is_empty());
+    assert_eq!(element.get_attribute("style").unwrap(), "");
+}
+
+#[test]
+fn style_sheets() {
+    use crate::{domitem::DOMItem, CSSRule, InnerHtml};
+
+    let mut document = Document::new();
+    document.set_resource_loader(|url: &str| match url {
+        "css/main.css" => Some(String::from(
+            "@import url(base.css) layer(base); @import 'print.css' print; h1 { color: red }",
+        )),
+        "css/base.css" => Some(String::from("* { margin: 0 } @import 'late.css';")),
+        "css/print.css" => Some(String::from("body { color: black }")),
+        // Import cycles end after a limited depth.
+        "loop.css" => Some(String::from("@import 'loop.css';")),
+        _ => None,
+    });
+    let mut html = document.create_element("html");
+    html.set_inner_html(
+        r#"
+            <link rel="preload stylesheet" href="css/main.css">
+            <style>
+                /* comments are ignored */
+                <!-- a, b > c:not(.x) + d ~ e::before, [data-ID = 'a' i]:nth-child(2N + 1 of li) {}
+                p:unknown, .valid { color: blue }
+                @charset "utf-8";
+                @layer reset, base;
+                @media screen AND (min-width: 100px) { .wide { gap: 1px } @media print { x { top: 0 } } }
+                @supports (display: grid) and (not (selector(:has(> img)))) { .grid { display: grid } }
+                @layer { p { margin: 0 } }
+                @font-face { font-family: "Inter"; src: url(inter.woff2) }
+                @keyframes pulse { from, 50% { opacity: 0 !important; opacity: .5 } to { opacity: 1 } }
+                @import "too-late.css";
+                @unknown { p { color: red } }
+            </style>
+            <link rel=stylesheet href=loop.css>
+            <link rel=icon href=css/main.css>
+        "#,
+    )
+    .unwrap();
+    document.append_child(&mut html).unwrap();
+
+    let sheets = document.style_sheets();
+    assert_eq!(sheets.len(), 3);
+
+    // Linked sheets and their imports are loaded relative to their own URLs.
+    let linked = sheets.item(0).unwrap();
+    assert_eq!(linked.href().as_deref(), Some("css/main.css"));
+    let rules = linked.css_rules();
+    assert_eq!(rules.len(), 3);
+    let CSSRule::Import(base) = rules.item(0).unwrap() else {
+        panic!("expected an import rule");
+    };
+    assert_eq!(base.layer_name(), Some("base"));
+    let base_sheet = base.style_sheet().unwrap();
+    assert_eq!(base_sheet.href().as_deref(), Some("css/base.css"));
+    assert_eq!(base_sheet.css_rules().len(), 1);
+    assert_eq!(
+        rules.item(1).unwrap().css_text(),
+        "@import url(\"print.css\") print;"
+    );
+
+    let text: Vec<String> = sheets
+        .item(1)
+        .unwrap()
+        .css_rules()
+        .into_iter()
+        .map(|rule| rule.css_text())
+        .collect();
+    assert_eq!(
+        text,
+        [
+            "a, b > c:not(.x) + d ~ e::before, [data-id=\"a\" i]:nth-child(2n+1 of li) { }",
+            "@layer reset, base;",
+            "@media screen and (min-width: 100px) {\n  .wide { gap: 1px; }\n  @media print {\n    x { top: 0; }\n  }\n}",
+            "@supports (display: grid) and (not (selector(:has(> img)))) {\n  .grid { display: grid; }\n}",
+            "@layer {\n  p { margin: 0; }\n}",
+            "@font-face { font-family: \"Inter\"; src: url(inter.woff2); }",
+            "@keyframes pulse {\n  0%, 50% { opacity: .5; }\n  100% { opacity: 1; }\n}",
+        ]
+    );
+    let CSSRule::Supports(supports) = sheets.item(1).unwrap().css_rules().item(3).unwrap() else {
+        panic!("expected a supports rule");
+    };
+    assert!(!supports.matches());
+
+    // Grouping rules reject rules that are only allowed at the top level.
+    let CSSRule::Media(mut media) = sheets.item(1).unwrap().css_rules().item(2).unwrap() else {
+        panic!("expected a media rule");
+    };
+    assert!(media
+        .insert_rule("@import 'a.css';", 0)
+        .unwrap_err()
+        .is_hierarchy_request_error());
+    media.insert_rule(".narrow { gap: 0 }", 2).unwrap();
+    assert_eq!(media.css_rules().len(), 3);
+
+    // The import cycle still produces a sheet.
+    let mut depth = 0;
+    let mut sheet = sheets.item(2).unwrap();
+    while let Some(CSSRule::Import(import)) = sheet.css_rules().item(0) {
+        match import.style_sheet() {
+            Some(imported) => sheet = imported,
+            None => break,
+        }
+        depth += 1;
+    }
+    assert_eq!(depth, 16);
+
+    // Removing the element removes its sheet.
+    let mut style = html.children().item(1).unwrap().clone_ref();
+    assert_eq!(style.tag_name(), "STYLE");
+    style.remove();
+    assert_eq!(document.style_sheets().len(), 2);
+}
+
+#[test]
+fn css_syntax() {
+    use crate::InnerHtml;
+
+    let mut document = Document::new();
+    let mut html = document.create_element("html");
+    html.set_inner_html(
+        "<style>
+            p { color: green; color: banana; display: block; display: sideways; color: blue !important junk }
+            a { content: \"unterminated
+            ; color: red; background-image: url(a b.png) }
+            b { background-image: url(x/*y*/z.png); color:/**/red; content: \"}\" }
+            i\" { color: red }
+            {} u { color: red }
+        </style>",
+    )
+    .unwrap();
+    document.append_child(&mut html).unwrap();
+
+    // Invalid declarations are dropped while parsing, and a string cut off by a newline only spoils its own declaration.
+    let text: Vec<String> = document
+        .style_sheets()
+        .item(0)
+        .unwrap()
+        .css_rules()
+        .into_iter()
+        .map(|rule| rule.css_text())
+        .collect();
+    assert_eq!(
+        text,
+        [
+            "p { color: green; display: block; }",
+            "a { color: red; }",
+            "b { background-image: url(x/*y*/z.png); color: red; content: \"}\"; }",
+            "u { color: red; }",
+        ]
+    );
+
+    // Inline styles are parsed the same way.
+    let mut element = document.create_element("div");
+    element.set_attribute(
+        "style",
+        "color: blue !important junk; width: 10; content: 'a\n; height: 1px; background: url(a(b).png)",
+    );
+    assert_eq!(element.style().css_text(), "height: 1px;");
+}
+
+#[test]
+fn computed_style() {
+    use crate::{InnerHtml, ShadowRootInit, ShadowRootMode, Window};
+
+    let mut document = Document::new();
+    let mut html = document.create_element("html");
+    html.set_inner_html(
+        r#"
+            <style>
+                @layer base, theme;
+                @layer theme { p { color: blue; padding: 1px !important } }
+                @layer base { p { color: red; padding: 2px !important } }
+                p { color: green; font-size: 20px }
+                span { font-size: 1.5em; border-left: thick solid }
+                #revert { display: flex; margin: 3pt }
+                #revert.reverted { display: revert }
+                div { color: black }
+                q::before { color: rgb(0 0 255 / 50%) }
+                @media print { p { color: black } }
+            </style>
+            <p>Text <span>nested</span> <q>quote</q></p>
+            <p id=revert class=reverted></p>
+            <div>Host <span>slotted</span></div>
+        "#,
+    )
+    .unwrap();
+    document.append_child(&mut html).unwrap();
+    let window = Window::new(document.clone());
+    let children = html.children();
+    let paragraph = children.item(1).unwrap();
+    let span = paragraph.children().item(0).unwrap();
+    let quote = paragraph.children().item(1).unwrap();
+
+    // Unlayered rules win over layered ones, but important declarations in earlier layers win.
+    let style = window.get_computed_style(&paragraph, None);
+    assert_eq!(style.color(), "rgb(0, 128, 0)");
+    assert_eq!(style.padding_left(), "2px");
+    assert_eq!(style.margin_top(), "20px");
+    assert_eq!(style.font_weight(), "400");
+    assert_eq!(style.item(0).as_deref(), Some("align-content"));
+
+    // Inherited properties come from the parent, and relative lengths are resolved.
+    let style = window.get_computed_style(&span, None);
+    assert_eq!(style.color(), "rgb(0, 128, 0)");
+    assert_eq!(style.font_size(), "30px");
+    assert_eq!(style.get_property_value("border-left-width"), "5px");
+    assert_eq!(
+        style.get_property_value("border-left-color"),
+        "rgb(0, 128, 0)"
+    );
+    assert_eq!(style.get_property_value("border-top-width"), "0px");
+    assert_eq!(style.display(), "inline");
+
+    // `revert` goes back to the default style of the element.
+    let reverted = children.item(2).unwrap();
+    let style = window.get_computed_style(&reverted, None);
+    assert_eq!(style.display(), "block");
+    assert_eq!(style.margin_left(), "4px");
+
+    // Pseudo-elements inherit from their element.
+    let before = window.get_computed_style(&quote, Some("::before"));
+    assert_eq!(before.color(), "rgba(0, 0, 255, 0.5)");
+    assert_eq!(before.font_size(), "20px");
+    assert_eq!(before.get_property_value("content"), "open-quote");
+    assert_eq!(
+        window.get_computed_style(&quote, Some(":before")).color(),
+        before.color()
+    );
+    assert!(window
+        .get_computed_style(&quote, Some("::nonsense"))
+        .is_empty());
+    assert!(window
+        .get_computed_style(&quote, Some("::before"))
+        .set_property("color", "red", None)
+        .is_err());
+
+    // The styles of a shadow tree apply to its host and to the elements slotted into it.
+    let mut host = children.item(3).unwrap();
+    let shadow_root = host
+        .attach_shadow(ShadowRootInit::new(ShadowRootMode::Open))
+        .unwrap();
+    shadow_root.set_html_unsafe(
+        "<style>:host { color: red; padding: 5px } ::slotted(span) { font-weight: bold } span { color: red }</style><slot></slot>",
+    );
+    let style = window.get_computed_style(&host, None);
+    // Normal declarations from the outer tree win over the shadow tree's.
+    assert_eq!(style.color(), "rgb(0, 0, 0)");
+    assert_eq!(style.padding_top(), "5px");
+    let slotted = host.children().item(0).unwrap();
+    let style = window.get_computed_style(&slotted, None);
+    assert_eq!(style.font_weight(), "700");
+    assert_eq!(style.color(), "rgb(0, 0, 0)");
+
+    // The computed style is live.
+    let mut paragraph = paragraph;
+    paragraph.set_attribute("style", "color: purple");
+    let style = window.get_computed_style(&span, None);
+    assert_eq!(style.color(), "rgb(128, 0, 128)");
+    assert!(span.matches("p > span:first-child"));
+    assert!(!span.matches("p >"));
+
+    // Invalid declarations never win the cascade over earlier valid ones.
+    let mut sheet_style = document.create_element("style");
+    sheet_style.set_text_content("p { color: banana; width: 10; font-size: huge !important }");
+    html.append_child(&mut sheet_style).unwrap();
+    paragraph.set_attribute(
+        "style",
+        "color: purple; color: rgb(nope); padding-left: -1px",
+    );
+    let style = window.get_computed_style(&paragraph, None);
+    assert_eq!(style.color(), "rgb(128, 0, 128)");
+    assert_eq!(style.width(), "auto");
+    assert_eq!(style.font_size(), "20px");
+    assert_eq!(style.padding_left(), "2px");
+    // A value that only turns out to be invalid once its `var()` functions are substituted acts like `unset`, and is not passed through.
+    paragraph.set_attribute(
+        "style",
+        "--size: banana; color: purple; font-size: var(--size)",
+    );
+    let style = window.get_computed_style(&paragraph, None);
+    assert_eq!(style.color(), "rgb(128, 0, 128)");
+    assert_eq!(style.font_size(), "16px");
+}
+
+#[test]
+fn custom_properties() {
+    use crate::{InnerHtml, Window};
+
+    let mut document = Document::new();
+    let mut html = document.create_element("html");
+    html.set_inner_html(
+        r#"
+            <style>
+                @property --gap { syntax: "<length>"; inherits: false; initial-value: 4px; }
+                @property --accent { syntax: "<color>"; inherits: true; initial-value: black; }
+                html { --brand: #ff0000; --size: 2em; font-size: 10px; --gap: 1em; }
+                p {
+                    color: var(--brand);
+                    margin: var(--missing, 3px) var(--size);
+                    padding-left: var(--gap);
+                    --a: var(--b);
+                    --b: var(--a);
+                    --c: var(--a, fallback);
+                    --accent: 12px;
+                    font-size: var(--undefined);
+                }
+                .blue { --brand: blue; --gap: 2em; --accent: rgb(0 128 0) }
+            </style>
+            <p>Text</p>
+            <p class=blue>Text</p>
+        "#,
+    )
+    .unwrap();
+    document.append_child(&mut html).unwrap();
+    let window = Window::new(document.clone());
+    let children = html.children();
+    let paragraph = children.item(1).unwrap();
+    let blue = children.item(2).unwrap();
+
+    let style = window.get_computed_style(&html, None);
+    assert_eq!(style.get_property_value("--brand"), "#ff0000");
+    assert_eq!(style.get_property_value("--gap"), "10px");
+
+    // Unregistered properties inherit, and `var()` uses fallbacks for missing properties.
+    let style = window.get_computed_style(&paragraph, None);
+    assert_eq!(style.get_property_value("--brand"), "#ff0000");
+    assert_eq!(style.color(), "rgb(255, 0, 0)");
+    assert_eq!(style.margin_top(), "3px");
+    assert_eq!(style.margin_left(), "20px");
+    // `--gap` is registered not to inherit, so the paragraph has its initial value.
+    assert_eq!(style.get_property_value("--gap"), "4px");
+    assert_eq!(style.padding_left(), "4px");
+    // Properties in a cycle are invalid, and so are properties that use them without a fallback.
+    assert_eq!(style.get_property_value("--a"), "");
+    assert_eq!(style.get_property_value("--b"), "");
+    assert_eq!(style.get_property_value("--c"), "fallback");
+    // A value that does not match the registered syntax acts like `unset`.
+    assert_eq!(style.get_property_value("--accent"), "rgb(0, 0, 0)");
+    // A `var()` with no value makes the declaration act like `unset`.
+    assert_eq!(style.font_size(), "10px");
+
+    let style = window.get_computed_style(&blue, None);
+    assert_eq!(style.color(), "rgb(0, 0, 255)");
+    assert_eq!(style.get_property_value("--gap"), "20px");
+    assert_eq!(style.padding_left(), "20px");
+    assert_eq!(style.get_property_value("--accent"), "rgb(0, 128, 0)");
+
+    // Inline styles can use custom properties too.
+    let mut paragraph = paragraph;
+    paragraph.set_attribute(
+        "style",
+        "--brand: green; border-top: 1px solid var(--brand)",
+    );
+    let style = window.get_computed_style(&paragraph, None);
+    assert_eq!(
+        style.get_property_value("border-top-color"),
+        "rgb(0, 128, 0)"
+    );
+    assert_eq!(style.get_property_value("border-top-width"), "1px");
+}
+
+#[test]
+fn media_queries() {
+    use crate::{css::evaluate_list, ColorScheme, InnerHtml, MediaType, Viewport, Window};
+
+    let viewport = Viewport {
+        width: 1024.0,
+        height: 768.0,
+        device_pixel_ratio: 2.0,
+        color_scheme: ColorScheme::Dark,
+        reduced_motion: true,
+        media_type: MediaType::Screen,
+    };
+    let cases = [
+        ("", true),
+        ("all", true),
+        ("screen", true),
+        ("print", false),
+        ("not print", true),
+        ("only screen and (min-width: 1024px)", true),
+        ("screen and (max-width: 1023.5px)", false),
+        ("(width >= 64em) and (height < 800px)", true),
+        ("(400px <= width <= 1000px)", false),
+        ("(1000px < width < 1100px)", true),
+        ("(orientation: landscape)", true),
+        ("(aspect-ratio: 4/3)", true),
+        ("(min-aspect-ratio: 16/9)", false),
+        ("(min-resolution: 192dpi)", true),
+        ("(-webkit-min-device-pixel-ratio: 2)", true),
+        ("(prefers-color-scheme: dark)", true),
+        ("(prefers-reduced-motion)", true),
+        ("(hover: hover) and (pointer: fine)", true),
+        ("not (color)", false),
+        ("(monochrome)", false),
+        (
+            "(max-width: 500px), (prefers-color-scheme: light), print",
+            false,
+        ),
+        ("print, (min-width: 50vw)", true),
+        ("((min-width: 2000px) or (color)) and (grid: 0)", true),
+        // Unknown features and invalid queries never match, even when negated.
+        ("(unknown-feature)", false),
+        ("not (unknown-feature)", false),
+        ("not screen and (unknown-feature)", false),
+        ("screen and (color) or (grid)", false),
+        ("(min-width: 10)", false),
+        ("tv", false),
+        ("and", false),
+    ];
+    for (query, expected) in cases {
+        assert_eq!(evaluate_list(query, &viewport), expected, "{query}");
+    }
+
+    let mut document = Document::new();
+    document.set_resource_loader(|url: &str| match url {
+        "print.css" => Some(String::from("p { margin-top: 7px }")),
+        _ => None,
+    });
+    let mut html = document.create_element("html");
+    html.set_inner_html(
+        r#"
+            <link rel=stylesheet href=print.css media=print>
+            <style>
+                p { width: 50vw; height: 10vmin }
+                @media (max-width: 600px) { p { color: red } }
+                @media (prefers-reduced-motion: reduce) { p { font-size: 20px } }
+            </style>
+            <p>Text</p>
+        "#,
+    )
+    .unwrap();
+    document.append_child(&mut html).unwrap();
+    let mut window = Window::new(document.clone());
+    let paragraph = html.children().item(2).unwrap();
+
+    let style = window.get_computed_style(&paragraph, None);
+    assert_eq!(style.color(), "rgb(0, 0, 0)");
+    assert_eq!(style.font_size(), "16px");
+    assert_eq!(style.margin_top(), "16px");
+    assert_eq!(style.width(), "400px");
+    assert_eq!(style.height(), "60px");
+
+    window.set_viewport(Viewport {
+        width: 500.0,
+        reduced_motion: true,
+        ..Default::default()
+    });
+    assert_eq!(style.color(), "rgb(255, 0, 0)");
+    assert_eq!(style.font_size(), "20px");
+    assert_eq!(style.width(), "250px");
+    assert_eq!(style.height(), "50px");
+
+    window.set_viewport(Viewport {
+        media_type: MediaType::Print,
+        ..Default::default()
+    });
+    assert_eq!(style.margin_top(), "7px");
+
+    // Media query lists report changes only when their result changes.
+    let list = window.match_media("print");
+    assert_eq!(list.media(), "print");
+    assert!(list.matches());
+    let mut list_ref = list.clone();
+    let changes = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let recorded = changes.clone();
+    let observed = list.clone();
+    list_ref.add_event_listener(
+        crate::EventType::Change,
+        Some((move |_: &mut crate::Event| recorded.borrow_mut().push(observed.matches())).into()),
+        None,
+    );
+    window.set_viewport(Viewport::default());
+    window.set_viewport(Viewport {
+        width: 300.0,
+        ..Default::default()
+    });
+    assert_eq!(*changes.borrow(), vec![false]);
+    assert_eq!(
+        window.match_media("SCREEN,  (Min-Width: 100px)").media(),
+        "screen, (min-width: 100px)"
+    );
+}
+
+#[test]
+fn layout() {
+    use crate::domitem::DOMItem;
+
+    let document = Document::parse_html_unsafe(
+        "<style>
+            body { margin: 10px; }
+            .a { margin: 20px 0; height: 10px; }
+            .b { margin-top: 30px; }
+            .b > p { margin: 15px 0 5px; }
+            .wrap { width: 80px; }
+            .abs { position: absolute; right: 10px; bottom: 20px; width: 50px; height: 50px; }
+            .rel { position: relative; left: 5px; top: -3px; height: 10px; }
+            .ib { display: inline-block; width: 20px; height: 30px; }
+            .center { text-align: center; width: 200px; }
+            .scroll { overflow: hidden; height: 20px; width: 50px; }
+            .sized { box-sizing: border-box; width: 50%; padding: 10px; border: 5px solid; max-width: 300px; }
+            .container { position: relative; margin-left: 100px; height: 40px; padding: 5px; }
+            .fill { position: absolute; inset: 0; }
+            .negative { margin-bottom: -10px; height: 10px; }
+            .empty { margin: 30px 0; }
+            .shrink { position: absolute; top: 500px; padding: 1px; }
+            .pre { white-space: pre; }
+        </style>
+        <div class=a></div><div class=a></div>
+        <div class=b><p>Text</p></div>
+        <div class=wrap>aaaa bbbb <span class=span>cccc dddd eeee</span></div>
+        <div class=abs></div>
+        <div class=rel></div>
+        <div>x<span class=ib></span>y</div>
+        <div class=center><span class=centered>abc</span></div>
+        <div class=scroll><div style='height: 100px; width: 300px'></div></div>
+        <div class=sized></div>
+        <div class=container><div class=fill></div></div>
+        <div class=negative></div><div class=empty></div><div class=after>x</div>
+        <div class=shrink>hello world</div>
+        <div class=pre>ab  c
+de<br>f</div>
+        <div style='display: none' class=none></div>",
+    );
+    let html = document.first_element_child().unwrap();
+    let element = |class: &str| {
+        html.get_elements_by_class_name(class)
+            .item(0)
+            .unwrap()
+            .clone_ref()
+    };
+    let round = |value: f64| (value * 10.0).round() / 10.0;
+    let rects = |class: &str| -> Vec<(f64, f64, f64, f64)> {
+        element(class)
+            .get_client_rects()
+            .iter()
+            .map(|rect| {
+                (
+                    round(rect.x()),
+                    round(rect.y()),
+                    round(rect.width()),
+                    round(rect.height()),
+                )
+            })
+            .collect()
+    };
+
+    // The top margin of the first box collapses with the body's, and adjoining sibling margins collapse.
+    assert_eq!(rects("a"), vec![(10.0, 20.0, 780.0, 10.0)]);
+    let second = html
+        .get_elements_by_class_name("a")
+        .item(1)
+        .unwrap()
+        .get_bounding_client_rect();
+    assert_eq!((second.y(), second.height()), (50.0, 10.0));
+    // Margins of a parent and its first child collapse, and the bottom margin of the last child collapses through its parent.
+    assert_eq!(rects("b"), vec![(10.0, 90.0, 780.0, 19.2)]);
+    assert_eq!(rects("wrap"), vec![(10.0, 114.2, 80.0, 57.6)]);
+
+    // Text wraps at spaces, and inline boxes have a fragment on each line.
+    assert_eq!(
+        rects("span"),
+        vec![(10.0, 135.0, 72.0, 16.0), (10.0, 154.2, 32.0, 16.0)]
+    );
+    assert_eq!(
+        round(element("span").get_bounding_client_rect().height()),
+        35.2
+    );
+    assert_eq!(element("span").client_width(), 0);
+
+    // Absolutely positioned boxes are placed in the viewport, and relatively positioned boxes are offset from their place in flow.
+    assert_eq!(rects("abs"), vec![(740.0, 530.0, 50.0, 50.0)]);
+    assert_eq!(rects("rel"), vec![(15.0, 168.8, 780.0, 10.0)]);
+
+    // Inline-blocks sit on the baseline, and text is aligned in its line.
+    assert_eq!(rects("ib"), vec![(18.0, 181.8, 20.0, 30.0)]);
+    assert_eq!(rects("centered"), vec![(98.0, 218.2, 24.0, 16.0)]);
+
+    // Overflow is clipped, but can be scrolled to.
+    let scroll = element("scroll");
+    assert_eq!((scroll.client_width(), scroll.client_height()), (50, 20));
+    assert_eq!((scroll.scroll_width(), scroll.scroll_height()), (300, 100));
+
+    // Percentages and maximums apply to the border box with `box-sizing: border-box`.
+    let sized = element("sized");
+    assert_eq!(rects("sized"), vec![(10.0, 255.8, 300.0, 30.0)]);
+    assert_eq!((sized.client_width(), sized.client_height()), (290, 20));
+    assert_eq!((sized.client_left(), sized.client_top()), (5, 5));
+
+    // A positioned ancestor is the containing block of absolutely positioned boxes.
+    assert_eq!(rects("container"), vec![(110.0, 285.8, 680.0, 50.0)]);
+    assert_eq!(rects("fill"), rects("container"));
+
+    // Positive and negative margins that adjoin, including those of empty boxes, collapse together.
+    assert_eq!(rects("after"), vec![(10.0, 365.8, 780.0, 19.2)]);
+
+    // Absolutely positioned boxes without a width shrink to fit their contents.
+    assert_eq!(rects("shrink"), vec![(10.0, 500.0, 90.0, 21.2)]);
+
+    // Preserved newlines and `<br>` break lines.
+    assert_eq!(
+        round(element("pre").get_bounding_client_rect().height()),
+        57.6
+    );
+
+    // Elements that are not rendered have no boxes.
+    assert!(rects("none").is_empty());
+    assert_eq!(element("none").client_width(), 0);
+    assert_eq!(html.client_width(), 800);
+    assert_eq!(html.client_height(), 600);
+
+    // The layout is computed again after the document, its style sheets or the text in it change.
+    let mut first = element("a");
+    first.set_attribute("style", "height: 40px");
+    assert_eq!(rects("a")[0].3, 40.0);
+    let mut sheet = document.style_sheets().item(0).unwrap();
+    sheet.insert_rule(".a { width: 100px }", 1).unwrap();
+    assert_eq!(rects("a")[0].2, 100.0);
+    let mut text = document.create_text_node("def");
+    element("centered").append_child(&mut text).unwrap();
+    assert_eq!(rects("centered")[0].2, 48.0);
+    text.append_data("ghi");
+    assert_eq!(rects("centered")[0].2, 72.0);
+}
+
+#[test]
+fn layout_cache_per_document() {
+    use crate::{domitem::DOMItem, layout::Layout, FixedFontProvider};
+    use std::rc::Rc;
+
+    let mut first = Document::parse_html_unsafe("<style>p { margin: 0 }</style><p>first</p>");
+    let second = Document::parse_html_unsafe("<p>second</p>");
+    let cached = Layout::of(&second);
+
+    // Changes to the tree, the style sheets or the settings of one document leave the layout of another alone.
+    let html = first.first_element_child().unwrap();
+    let mut paragraph = html
+        .get_elements_by_tag_name("p")
+        .item(0)
+        .unwrap()
+        .clone_ref();
+    paragraph.set_attribute("style", "width: 10px");
+    let mut text = first.create_text_node("more");
+    paragraph.append_child(&mut text).unwrap();
+    let mut sheet = first.style_sheets().item(0).unwrap();
+    sheet.insert_rule("p { height: 10px }", 1).unwrap();
+    first.set_font_provider(FixedFontProvider);
+    assert!(Rc::ptr_eq(&cached, &Layout::of(&second)));
+
+    // A change to the document itself still lays it out again.
+    let html = second.first_element_child().unwrap();
+    let mut paragraph = html
+        .get_elements_by_tag_name("p")
+        .item(0)
+        .unwrap()
+        .clone_ref();
+    paragraph.set_attribute("style", "width: 10px");
+    assert!(!Rc::ptr_eq(&cached, &Layout::of(&second)));
+}
+
+#[test]
+fn flex_and_grid() {
+    use crate::domitem::DOMItem;
+
+    let document = Document::parse_html_unsafe(
+        "<style>
+            body { margin: 0; }
+            .row { display: flex; width: 420px; gap: 10px; }
+            .grow-1 { flex: 1; }
+            .grow-2 { flex: 2; }
+            .fixed { width: 100px; height: 50px; }
+            .centering { display: flex; justify-content: center; align-items: center; height: 100px; }
+            .box { width: 20px; height: 20px; }
+            .wrapping { display: flex; flex-wrap: wrap; width: 100px; }
+            .wrapping > div { width: 40px; height: 10px; }
+            .column { display: flex; flex-direction: column-reverse; height: 100px; }
+            .column > div { height: 20px; }
+            .shrinking { display: flex; width: 100px; height: 10px; }
+            .shrinking > div { width: 80px; }
+            .pushing { display: flex; width: 100px; height: 10px; }
+            .pushed { margin-left: auto; width: 10px; }
+            .text { display: flex; justify-content: space-between; width: 200px; }
+            .grid { display: grid; grid-template-columns: 100px 1fr 2fr; width: 390px; gap: 10px; grid-auto-rows: 30px; }
+            .areas { display: grid; grid-template-areas: 'head head' 'side main'; grid-template-columns: 50px 1fr; grid-template-rows: 20px 40px; width: 200px; }
+            .head { grid-area: head; }
+            .main { grid-area: main; }
+            .placed { display: grid; grid-template-columns: repeat(auto-fill, 50px); grid-auto-rows: 1em; width: 220px; }
+            .spanned { grid-column: 2 / span 2; grid-row: 2; }
+            .last { grid-column: -2; }
+            .dense { display: grid; grid-template-columns: repeat(3, 20px); grid-auto-flow: row dense; justify-content: end; width: 100px; }
+            .wide { grid-column: span 2; }
+        </style>
+        <div class=row><div class=grow-1></div><div class=grow-2></div><div class=fixed></div></div>
+        <div class=centering><div class='box centered'></div></div>
+        <div class=wrapping><div></div><div></div><div class=wrapped></div></div>
+        <div class=column><div class=first></div><div></div></div>
+        <div class=shrinking><div class=shrunk></div><div></div><div></div></div>
+        <div class=pushing><div class=pushed></div></div>
+        <div class=text>ab<span class=end>cd</span></div>
+        <div class=grid><div></div><div class=fr></div><div></div><div class=next-row>x</div></div>
+        <div class=areas><div class=main></div><div class=head></div></div>
+        <div class=placed><div class=spanned></div><div class=auto></div><div class=last></div></div>
+        <div class=dense><div class=wide></div><div class=wide></div><div class=filler></div></div>",
+    );
+    let html = document.first_element_child().unwrap();
+    let element = |class: &str| {
+        html.get_elements_by_class_name(class)
+            .item(0)
+            .unwrap()
+            .clone_ref()
+    };
+    let round = |value: f64| (value * 10.0).round() / 10.0;
+    let rect = |class: &str| {
+        let rect = element(class).get_bounding_client_rect();
+        (
+            round(rect.x()),
+            round(rect.y()),
+            round(rect.width()),
+            round(rect.height()),
+        )
+    };
+
+    // Free space is shared by flex factors after gaps and fixed items, and items stretch to the height of their line.
+    assert_eq!(rect("grow-1"), (0.0, 0.0, 100.0, 50.0));
+    assert_eq!(rect("grow-2"), (110.0, 0.0, 200.0, 50.0));
+    assert_eq!(rect("fixed"), (320.0, 0.0, 100.0, 50.0));
+    assert_eq!(rect("centered"), (390.0, 90.0, 20.0, 20.0));
+    // Items that overflow a wrapping container move to the next line.
+    assert_eq!(rect("wrapping"), (0.0, 150.0, 100.0, 20.0));
+    assert_eq!(rect("wrapped"), (0.0, 160.0, 40.0, 10.0));
+    // Reversed columns start at the bottom, and their items stretch to the width of the container.
+    assert_eq!(rect("first"), (0.0, 250.0, 800.0, 20.0));
+    // Items shrink to fit, and auto margins take up free space.
+    assert_eq!(rect("shrunk"), (0.0, 270.0, 33.3, 10.0));
+    assert_eq!(rect("pushed"), (90.0, 280.0, 10.0, 10.0));
+    // Text in a flex container is wrapped in an anonymous item.
+    assert_eq!(rect("end"), (184.0, 290.0, 16.0, 19.2));
+
+    // Fixed tracks are sized first, and `fr` tracks share what is left.
+    assert_eq!(rect("grid"), (0.0, 309.2, 390.0, 70.0));
+    assert_eq!(rect("fr"), (110.0, 309.2, 90.0, 30.0));
+    assert_eq!(rect("next-row"), (0.0, 349.2, 100.0, 30.0));
+    // Items are placed in named areas.
+    assert_eq!(rect("head"), (0.0, 379.2, 200.0, 20.0));
+    assert_eq!(rect("main"), (50.0, 399.2, 150.0, 40.0));
+    // Tracks repeat to fill the container, and items are placed by lines, spans and the auto-placement cursor.
+    assert_eq!(rect("placed"), (0.0, 439.2, 220.0, 32.0));
+    assert_eq!(rect("spanned"), (50.0, 455.2, 100.0, 16.0));
+    assert_eq!(rect("auto"), (0.0, 439.2, 50.0, 16.0));
+    assert_eq!(rect("last"), (150.0, 439.2, 50.0, 16.0));
+    // Dense packing fills holes left earlier in the grid.
+    assert_eq!(rect("filler"), (80.0, 471.2, 20.0, 0.0));
+
+    let mut style = element("head").style_mut();
+    style.set_grid_area("1 / 2 / span 2").unwrap();
+    assert_eq!(style.grid_column(), "2");
+    assert_eq!(style.grid_area(), "1 / 2 / span 2");
+    style.set_grid_area("side").unwrap();
+    assert_eq!(style.get_property_value("grid-column-end"), "side");
+    assert_eq!(style.grid_area(), "side");
+}
+
+/// Builds a font file with glyphs for `A` and `B`, whose advances are given in thousandths of an em, and whose outlines are boxes half an em wide.
+fn test_font(family: &str, weight: u16, advances: [u16; 3]) -> Vec<u8> {
+    let mut head = vec![0; 54];
+    head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+    let mut hhea = vec![0; 36];
+    hhea[4..6].copy_from_slice(&800i16.to_be_bytes());
+    hhea[6..8].copy_from_slice(&(-200i16).to_be_bytes());
+    hhea[8..10].copy_from_slice(&100i16.to_be_bytes());
+    hhea[34..36].copy_from_slice(&3u16.to_be_bytes());
+    let hmtx: Vec<u8> = advances
+        .iter()
+        .flat_map(|advance| [advance.to_be_bytes(), [0, 0]].concat())
+        .collect();
+    let mut os2 = vec![0; 78];
+    os2[4..6].copy_from_slice(&weight.to_be_bytes());
+    // One format 4 subtable, mapping `A` and `B` to glyphs 1 and 2.
+    let mut cmap = vec![];
+    for value in [0u16, 1, 3, 1, 0, 12, 4, 32, 0, 4, 0, 0, 0] {
+        cmap.extend(value.to_be_bytes());
+    }
+    for value in [
+        0x42u16,
+        0xFFFF,
+        0,
+        0x41,
+        0xFFFF,
+        1u16.wrapping_sub(0x41),
+        1,
+        0,
+        0,
+    ] {
+        cmap.extend(value.to_be_bytes());
+    }
+    // Glyph 1 is a square, and glyph 2 is glyph 1 moved to the right as a component.
+    let mut glyf = vec![];
+    for value in [1i16, 0, 0, 500, 700, 3, 0] {
+        glyf.extend(value.to_be_bytes());
+    }
+    glyf.extend([1, 1, 1, 1]);
+    for value in [0i16, 500, 0, -500, 0, 0, 700, 0] {
+        glyf.extend(value.to_be_bytes());
+    }
+    glyf.extend([0, 0]);
+    for value in [-1i16, 100, 0, 600, 700, 3, 1, 100, 0] {
+        glyf.extend(value.to_be_bytes());
+    }
+    let mut loca = vec![];
+    for value in [0u16, 0, 18, 27] {
+        loca.extend(value.to_be_bytes());
+    }
+    let family: Vec<u8> = family.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let mut name = vec![];
+    for value in [0u16, 1, 18, 3, 1, 0x409, 1, family.len() as u16, 0] {
+        name.extend(value.to_be_bytes());
+    }
+    name.extend(family);
+
+    let tables: [(&[u8; 4], Vec<u8>); 8] = [
+        (b"OS/2", os2),
+        (b"cmap", cmap),
+        (b"glyf", glyf),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"name", name),
+    ];
+    let mut font = vec![0, 1, 0, 0, 0, tables.len() as u8, 0, 0, 0, 0, 0, 0];
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        font.extend(*tag);
+        font.extend([0; 4]);
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        offset += data.len();
+    }
+    for (_, data) in tables {
+        font.extend(data);
+    }
+    font
+}
+
+#[test]
+fn font_providers() {
+    use crate::{
+        DOMException, FontCollection, FontDescription, FontProvider, HTMLCanvasElement,
+        TrueTypeFont,
+    };
+
+    let regular = TrueTypeFont::from_bytes(test_font("Test Sans", 400, [500, 600, 700])).unwrap();
+    assert_eq!(regular.family(), "Test Sans");
+    assert_eq!(regular.weight(), 400);
+    assert_eq!(regular.glyph('B'), Some(2));
+    assert_eq!(regular.glyph('C'), None);
+    assert!(matches!(
+        TrueTypeFont::from_bytes(vec![0; 12]),
+        Err(DOMException::NotSupportedError(_))
+    ));
+    assert!(matches!(
+        TrueTypeFont::open("does-not-exist.ttf"),
+        Err(DOMException::NotFoundError(_))
+    ));
+
+    let mut fonts = FontCollection::new();
+    fonts.add(regular);
+    fonts.add(TrueTypeFont::from_bytes(test_font("Test Sans", 700, [1000; 3])).unwrap());
+    fonts.set_generic_family("sans-serif", "Test Sans");
+    let font = FontDescription {
+        families: vec![String::from("sans-serif")],
+        size: 10.0,
+        ..Default::default()
+    };
+    // Characters without glyphs fall back to the fixed metrics.
+    assert_eq!(fonts.text_width(&font, "ABC"), 18.0);
+    assert_eq!(fonts.metrics(&font).normal_line_height(), 11.0);
+    let bold = FontDescription {
+        weight: 600,
+        ..font.clone()
+    };
+    assert_eq!(fonts.text_width(&bold, "AB"), 20.0);
+
+    let mut document = Document::parse_html_unsafe(
+        "<style>
+            body { margin: 0; font: 10px sans-serif; }
+        </style>
+        <p class=regular><span class=regular-text>ABC</span></p>
+        <p class=bold style='font-weight: bold; letter-spacing: 1px'><span class=bold-text>AB</span></p>",
+    );
+    document.set_font_provider(fonts);
+    let html = document.first_element_child().unwrap();
+    let rect = |class: &str| {
+        let rect = html
+            .get_elements_by_class_name(class)
+            .item(0)
+            .unwrap()
+            .get_bounding_client_rect();
+        (rect.x(), rect.y(), rect.width(), rect.height())
+    };
+    // Glyphs come from the loaded fonts, and lines are as tall as their ascent, descent and line gap.
+    assert_eq!(rect("regular"), (0.0, 10.0, 800.0, 11.0));
+    assert_eq!(rect("regular-text"), (0.0, 10.5, 18.0, 10.0));
+    // Bold text uses the bold font, with letter spacing added to its advances.
+    assert_eq!(rect("bold-text").2, 22.0);
+
+    let canvas = HTMLCanvasElement::try_from(document.create_element("canvas")).unwrap();
+    assert_eq!((canvas.width(), canvas.height()), (300, 150));
+    let mut context = canvas.get_2d_context(None).unwrap();
+    assert_eq!(context.measure_text("ABC").width(), 18.0);
+    // Relative font sizes are relative to 10px.
+    context.set_font("bold 200% sans-serif");
+    assert_eq!(context.font(), "bold 200% sans-serif");
+    let metrics = context.measure_text("AB");
+    assert_eq!(metrics.width(), 40.0);
+    assert_eq!(metrics.font_bounding_box_ascent(), 16.0);
+    context.set_font("not a font");
+    assert_eq!(context.font(), "bold 200% sans-serif");
+}
+
+#[test]
+fn truncated_character_map() {
+    use crate::TrueTypeFont;
+
+    // Point the `cmap` table at a format 12 subtable at the end of the file, which claims far more groups than there are, and whose last group is cut short.
+    let mut font = test_font("Test Sans", 400, [500, 600, 700]);
+    let cmap = font.len() as u32;
+    font[36..40].copy_from_slice(&cmap.to_be_bytes());
+    for value in [0u16, 1, 3, 10] {
+        font.extend(value.to_be_bytes());
+    }
+    font.extend(12u32.to_be_bytes());
+    font.extend([0, 12, 0, 0]);
+    for value in [u32::MAX, 0, u32::MAX] {
+        font.extend(value.to_be_bytes());
+    }
+    for value in [
+        0x41u32,
+        0x41,
+        1,
+        0x42,
+        0x42,
+        u32::MAX,
+        0x43,
+        0x43,
+        0x10000,
+        0x44,
+    ] {
+        font.extend(value.to_be_bytes());
+    }
+    font.extend([0, 0]);
+
+    let font = TrueTypeFont::from_bytes(font).unwrap();
+    assert_eq!(font.glyph('A'), Some(1));
+    // Glyphs that overflow are not mapped.
+    assert_eq!(font.glyph('B'), None);
+    assert_eq!(font.glyph('C'), None);
+    // Reading stops at the group that is cut short.
+    assert_eq!(font.glyph('D'), None);
+    assert_eq!(font.glyph('\u{10FFFF}'), None);
+}
+
+#[test]
+fn scrolling() {
+    use crate::{
+        domitem::DOMItem, Event, EventType, ScrollBehavior, ScrollIntoView, ScrollLogicalPosition,
+        ScrollToOptions, Window,
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    let mut document = Document::parse_html_unsafe(
+        "<style>
+            body { margin: 0 }
+            .list { height: 100px; overflow: auto }
+            .row { height: 40px }
+            .snap { height: 100px; overflow-y: scroll; scroll-snap-type: y mandatory; scroll-padding-top: 10px }
+            .area { height: 100px; scroll-snap-align: start }
+        </style>
+        <div class=fixed style='position: fixed; top: 0; height: 10px'></div>
+        <div class=list>
+            <div class=row></div><div class=row></div><div class=row></div><div class=row></div><div class=row></div>
+        </div>
+        <div class=snap>
+            <div class=area></div><div class=area></div><div class=area></div><div class=area></div>
+        </div>
+        <div style='height: 2000px'></div>
+        <p class=target style='height: 20px; margin: 0; scroll-margin-top: 5px'></p>
+        <div style='height: 2000px'></div>",
+    );
+    let mut window = Window::new(document.clone());
+    let html = document.first_element_child().unwrap();
+    let element = |class: &str, index: usize| {
+        html.get_elements_by_class_name(class)
+            .item(index)
+            .unwrap()
+            .clone_ref()
+    };
+    let y = |class: &str, index: usize| element(class, index).get_bounding_client_rect().y();
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let record = |name: &'static str| {
+        let events = events.clone();
+        Some(
+            (move |event: &mut Event| events.borrow_mut().push((name, event.type_().clone())))
+                .into(),
+        )
+    };
+    let mut list = element("list", 0);
+    list.add_event_listener(EventType::Scroll, record("list"), None);
+    list.add_event_listener(EventType::ScrollEnd, record("list"), None);
+    document.add_event_listener(EventType::Scroll, record("document"), None);
+    document.add_event_listener(EventType::ScrollEnd, record("document"), None);
+
+    // Scroll positions are clamped to the scroll range, and move the content of the scroll container.
+    assert_eq!(y("row", 2), 80.0);
+    list.set_scroll_top(1000);
+    assert_eq!(list.scroll_top(), 100);
+    assert_eq!(list.scroll_height(), 200);
+    assert_eq!(y("row", 2), -20.0);
+    assert_eq!(
+        *events.borrow(),
+        vec![("list", EventType::Scroll), ("list", EventType::ScrollEnd)]
+    );
+    // Scrolling to the current position does nothing.
+    list.set_scroll_top(1000);
+    assert_eq!(events.borrow().len(), 2);
+
+    // Smooth scrolls move over time on the virtual clock, and end with a `scrollend` event.
+    list.scroll_to(Some(ScrollToOptions {
+        top: Some(0.0),
+        behavior: ScrollBehavior::Smooth,
+        ..Default::default()
+    }));
+    assert_eq!(list.scroll_top(), 100);
+    window.advance_time(150.0);
+    assert_eq!(list.scroll_top(), 50);
+    window.advance_time(150.0);
+    assert_eq!(list.scroll_top(), 0);
+    assert_eq!(events.borrow().len(), 5);
+    assert_eq!(events.borrow()[4], ("list", EventType::ScrollEnd));
+    events.borrow_mut().clear();
+
+    // Mandatory snapping always moves to the closest snap position, inside the scroll padding.
+    let mut snap = element("snap", 0);
+    snap.set_scroll_top(45);
+    assert_eq!(snap.scroll_top(), 90);
+    assert_eq!(y("area", 1), 110.0);
+    snap.set_scroll_top(20);
+    assert_eq!(snap.scroll_top(), 0);
+    // Proximity snapping only moves to snap positions that are close enough.
+    snap.set_attribute("style", "scroll-snap-type: y proximity");
+    snap.set_scroll_top(45);
+    assert_eq!(snap.scroll_top(), 45);
+    snap.set_scroll_top(80);
+    assert_eq!(snap.scroll_top(), 90);
+
+    // The viewport is scrolled through the root element, and its scroll events fire at the document.
+    let mut target = element("target", 0);
+    target.scroll_into_view(true.into());
+    assert_eq!(html.scroll_top(), 2195);
+    assert_eq!(y("target", 0), 5.0);
+    assert_eq!(y("fixed", 0), 0.0);
+    assert_eq!(
+        *events.borrow(),
+        vec![
+            ("document", EventType::Scroll),
+            ("document", EventType::ScrollEnd)
+        ]
+    );
+
+    // Nested scroll containers are scrolled from the innermost out.
+    element("row", 4).scroll_into_view(ScrollIntoView {
+        block: ScrollLogicalPosition::Nearest,
+        ..Default::default()
+    });
+    assert_eq!(list.scroll_top(), 100);
+    assert_eq!(html.scroll_top(), 60);
+    assert_eq!(y("row", 4), 0.0);
+    assert_eq!(y("fixed", 0), 0.0);
+}
+
+#[test]
+fn hit_testing() {
+    use crate::{domitem::DOMItem, AsDocument};
+
+    let document = Document::parse_html_unsafe(
+        "<style>
+            body { margin: 0 }
+            div { position: absolute; width: 100px; height: 100px }
+        </style>
+        <div class=under style='z-index: -1; left: 0; top: 0'></div>
+        <div class=ghost style='left: 0; top: 0; pointer-events: none'></div>
+        <div class=hidden style='left: 100px; top: 0; visibility: hidden'>
+            <div class=shown style='visibility: visible; left: 50px; top: 0; width: 50px; height: 50px'></div>
+        </div>
+        <div class=clip style='left: 200px; top: 0; overflow: hidden'>
+            <div class=clipped style='position: static; width: 300px; height: 50px'></div>
+        </div>
+        <div class=rotated style='left: 0; top: 200px; width: 200px; height: 20px; transform: rotate(90deg)'></div>
+        <div class=scroller style='left: 300px; top: 200px; overflow: auto'>
+            <div class=content style='position: static; height: 300px'></div>
+            <div class=end style='position: static; height: 20px'></div>
+        </div>
+        <div class=stack style='left: 0; top: 400px; z-index: 1'>
+            <div class=inner style='z-index: 100; width: 50px; height: 50px'></div>
+        </div>
+        <div class=top style='left: 25px; top: 400px; z-index: 2; width: 50px'></div>
+        <p class=text style='position: absolute; left: 500px; top: 0; margin: 0'>Text <span class=word>word</span></p>
+        <x-host class=host style='position: absolute; left: 500px; top: 100px'>
+            <template shadowrootmode=open><div style='width: 50px; height: 50px'></div></template>
+        </x-host>",
+    );
+    let names = |x: f64, y: f64| -> Vec<String> {
+        document
+            .elements_from_point(x, y)
+            .iter()
+            .map(|element| match element.class_name() {
+                "" => element.tag_name().to_lowercase(),
+                class => class.to_owned(),
+            })
+            .collect()
+    };
+    let html = document.first_element_child().unwrap();
+
+    // Boxes with a negative `z-index` are hit, but not boxes with `pointer-events: none`.
+    assert_eq!(names(10.0, 10.0), ["under", "html"]);
+    // Hidden boxes are not hit, but their visible descendants are.
+    assert!(document.element_from_point(110.0, 10.0).unwrap() == html);
+    assert_eq!(names(160.0, 10.0), ["shown", "html"]);
+    // Content that overflows a box that clips it is only hit inside the box.
+    assert_eq!(names(250.0, 10.0), ["clipped", "clip", "html"]);
+    assert_eq!(names(350.0, 10.0), ["html"]);
+    // Transforms move boxes where they are hit.
+    assert_eq!(names(100.0, 150.0), ["rotated", "html"]);
+    assert_eq!(names(10.0, 210.0), ["html"]);
+    // Scrolled content is hit where it is scrolled to, inside the scroll container.
+    assert_eq!(names(350.0, 290.0), ["content", "scroller", "html"]);
+    let mut scroller = html
+        .get_elements_by_class_name("scroller")
+        .item(0)
+        .unwrap()
+        .clone_ref();
+    scroller.set_scroll_top(220);
+    assert_eq!(names(350.0, 290.0), ["end", "scroller", "html"]);
+    assert_eq!(names(350.0, 350.0), ["html"]);
+    // A `z-index` only orders boxes inside their stacking context.
+    assert_eq!(names(30.0, 410.0), ["top", "inner", "stack", "html"]);
+    // Text is hit as the element it is in.
+    assert_eq!(names(502.0, 5.0), ["text", "html"]);
+    assert_eq!(names(545.0, 5.0), ["word", "text", "html"]);
+    // Elements in shadow trees are retargeted to their hosts.
+    assert_eq!(names(510.0, 110.0), ["host", "html"]);
+    // Points outside the viewport hit nothing.
+    assert!(document.element_from_point(801.0, 10.0).is_none());
+    assert!(document.elements_from_point(10.0, -1.0).is_empty());
+}
+
+#[test]
+fn rendered_text() {
+    use crate::{CheckVisibilityOptions, DOMException, Element, InnerHtml};
+
+    let document = Document::parse_html_unsafe(
+        "<style>
+            .cell { display: inline-block }
+            span::before { content: 'Before ' }
+        </style>",
+    );
+    let html = document.first_element_child().unwrap();
+    let mut body = html.children().item(1).unwrap();
+    let append = |body: &mut Element, html: &str| {
+        let mut element = document.create_element("div");
+        element.set_inner_html(html).unwrap();
+        body.append_child(&mut element).unwrap();
+        element
+    };
+
+    // Blocks are on lines of their own, and paragraphs are separated by blank lines.
+    let blocks = append(
+        &mut body,
+        "  Some   <b>bold</b>\n text <div>Block</div><p>One</p><p>Two<br>lines</p>",
+    );
+    assert_eq!(
+        blocks.inner_text(),
+        "Some bold text\nBlock\n\nOne\n\nTwo\nlines"
+    );
+    assert_eq!(blocks.outer_text(), blocks.inner_text());
+    // Hidden content is not rendered, and pseudo-elements are not part of the text.
+    let hidden = append(
+        &mut body,
+        "<span>Shown</span><em style='display: none'>None</em><em style='visibility: hidden'>Hidden</em>",
+    );
+    assert_eq!(hidden.inner_text(), "Shown");
+    // Preserved white space stays as it is.
+    let pre = append(&mut body, "<pre>a  b\n  c</pre>");
+    assert_eq!(pre.inner_text(), "a  b\n  c");
+    // Table cells are separated by tabs, and rows by line breaks.
+    let table = append(
+        &mut body,
+        "<table><tr><td>1</td><td>2</td></tr><tr><td>3</td><td>4</td></tr></table>",
+    );
+    assert_eq!(table.inner_text(), "1\t2\n3\t4");
+    // The contents of inline-blocks are trimmed like lines.
+    let inline_blocks = append(&mut body, "<b class=cell> a </b> <b class=cell> b </b>");
+    assert_eq!(inline_blocks.inner_text(), "a b");
+    // Elements that are not rendered return their text content.
+    let mut detached = document.create_element("div");
+    detached
+        .set_inner_html("<p>Not</p>  <p>rendered</p>")
+        .unwrap();
+    assert_eq!(detached.inner_text(), "Not  rendered");
+
+    // Setting the inner text replaces the children, with line breaks as `<br>` elements.
+    detached.set_inner_text("One\r\nTwo\n\nThree\r");
+    assert_eq!(detached.inner_html(), "One<br>Two<br><br>Three<br>");
+    detached.set_inner_text("");
+    assert_eq!(detached.child_nodes().len(), 0);
+    // Setting the outer text replaces the element, merging the text with the text around it.
+    detached.set_inner_html("a<i>b</i>c<i>d</i>").unwrap();
+    let mut italic = document.create_element("i");
+    detached.append_child(&mut italic).unwrap();
+    italic.set_outer_text("e\nf").unwrap();
+    assert_eq!(detached.inner_html(), "a<i>b</i>c<i>d</i>e<br>f");
+    let mut empty = document.create_element("i");
+    detached.append_child(&mut empty).unwrap();
+    empty.set_outer_text("").unwrap();
+    assert_eq!(detached.inner_html(), "a<i>b</i>c<i>d</i>e<br>f");
+    assert!(matches!(
+        empty.set_outer_text("orphan"),
+        Err(DOMException::NoModificationAllowedError(_))
+    ));
+
+    // Visibility is checked by the options that are given.
+    let visibility = append(
+        &mut body,
+        "<div style='opacity: 0'><span>Transparent</span></div>
+        <div style='visibility: hidden'>Hidden</div>
+        <div style='content-visibility: hidden'><span>Skipped</span></div>
+        <div style='content-visibility: auto; margin-top: 2000px'><span>Offscreen</span></div>",
+    );
+    let children: Vec<Element> = (0..visibility.children().len())
+        .map(|index| visibility.children().item(index).unwrap())
+        .collect();
+    let transparent = children[0].first_element_child().unwrap();
+    assert!(transparent.check_visibility(None));
+    assert!(!transparent.check_visibility(Some(CheckVisibilityOptions {
+        opacity_property: true,
+        ..Default::default()
+    })));
+    assert!(children[1].check_visibility(None));
+    assert!(!children[1].check_visibility(Some(CheckVisibilityOptions {
+        visibility_property: true,
+        ..Default::default()
+    })));
+    assert!(children[2].check_visibility(None));
+    assert!(!children[2]
+        .first_element_child()
+        .unwrap()
+        .check_visibility(None));
+    let offscreen = children[3].first_element_child().unwrap();
+    assert!(offscreen.check_visibility(None));
+    assert!(!offscreen.check_visibility(Some(CheckVisibilityOptions {
+        content_visibility_auto: true,
+        ..Default::default()
+    })));
+    assert!(!detached.check_visibility(None));
+}
+
+#[test]
+fn painting() {
+    use crate::{
+        Bitmap, DOMException, FontCollection, ResourceLoader, TrueTypeFont, Viewport, Window,
+    };
+
+    struct Images(Vec<u8>);
+
+    impl ResourceLoader for Images {
+        fn load(&self, _: &str) -> Option<String> {
+            None
+        }
+
+        fn load_bytes(&self, url: &str) -> Option<Vec<u8>> {
+            (url == "image.png").then(|| self.0.clone())
+        }
+    }
+
+    let mut document = Document::parse_html_unsafe(
+        "<style>
+            body { margin: 0 }
+            div, img, p { position: absolute; width: 10px; height: 10px; margin: 0 }
+        </style>
+        <div style='left: 0; top: 0; background: red'></div>
+        <div style='left: 10px; top: 0; width: 6px; height: 6px; border: 2px solid blue; border-left-color: lime'></div>
+        <div style='left: 20px; top: 0; opacity: 0.5'>
+            <div style='background: black'></div>
+            <div style='left: 5px; background: black'></div>
+        </div>
+        <div style='left: 0; top: 10px; overflow: hidden'>
+            <div style='position: static; width: 20px; background: green'></div>
+        </div>
+        <div style='left: 20px; top: 14px; height: 2px; background: black; transform: rotate(90deg)'></div>
+        <p style='left: 0; top: 20px; width: 20px; height: auto; font: 10px serif; color: blue'>ab</p>
+        <img src=image.png style='left: 0; top: 30px'>
+        <div style='left: 20px; top: 30px; background: red; border-radius: 5px'></div>",
+    );
+    let image = Bitmap::from_pixels(
+        2,
+        2,
+        vec![
+            255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 255,
+        ],
+    )
+    .unwrap();
+    document.set_resource_loader(Images(image.to_png()));
+    let mut window = Window::new(document.clone());
+    window.set_viewport(Viewport {
+        width: 40.0,
+        height: 40.0,
+        ..Default::default()
+    });
+    let bitmap = document.render();
+    let pixel = |x: u32, y: u32| bitmap.pixel(x, y).unwrap();
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    assert_eq!((bitmap.width(), bitmap.height()), (40, 40));
+
+    // Backgrounds, and borders with a color for each side.
+    assert_eq!(pixel(5, 5), RED);
+    assert_eq!(pixel(15, 0), BLUE);
+    assert_eq!(pixel(10, 5), [0, 255, 0, 255]);
+    assert_eq!(pixel(15, 5), WHITE);
+    // A group is made transparent as a whole, so its overlapping boxes do not show through each other.
+    assert_eq!(pixel(22, 5), [128, 128, 128, 255]);
+    assert_eq!(pixel(27, 5), [128, 128, 128, 255]);
+    assert_eq!(pixel(36, 5), WHITE);
+    // Overflow is clipped.
+    assert_eq!(pixel(5, 15), [0, 128, 0, 255]);
+    assert_eq!(pixel(15, 15), WHITE);
+    // Transforms move what is painted.
+    assert_eq!(pixel(24, 12), BLACK);
+    assert_eq!(pixel(21, 15), WHITE);
+    // Without outlines, glyphs are painted as boxes on the baseline.
+    assert_eq!(pixel(2, 26), BLUE);
+    assert_eq!(pixel(7, 26), BLUE);
+    assert_eq!(pixel(2, 22), WHITE);
+    // Images are scaled to their boxes.
+    assert_eq!(pixel(1, 31), RED);
+    assert_eq!(pixel(8, 31), [0, 255, 0, 255]);
+    assert_eq!(pixel(1, 38), BLUE);
+    // Rounded corners are not painted outside their curves.
+    assert_eq!(pixel(20, 30), WHITE);
+    assert_eq!(pixel(25, 35), RED);
+    let [red, green, blue, _] = pixel(21, 31);
+    assert!(red == 255 && green < 255 && green == blue);
+
+    // Bitmaps have the size of the viewport in device pixels.
+    window.set_viewport(Viewport {
+        width: 40.0,
+        height: 40.0,
+        device_pixel_ratio: 2.0,
+        ..Default::default()
+    });
+    let scaled = document.render();
+    assert_eq!((scaled.width(), scaled.height()), (80, 80));
+    assert_eq!(scaled.pixel(10, 10), Some(RED));
+    assert_eq!(scaled.pixel(45, 10), Some([128, 128, 128, 255]));
+
+    // Glyphs are painted with the outlines of the font provider.
+    let regular = TrueTypeFont::from_bytes(test_font("Test Sans", 400, [500, 600, 700])).unwrap();
+    assert_eq!(
+        regular.outline(1, 10.0),
+        Some(vec![vec![
+            (0.0, 0.0),
+            (5.0, 0.0),
+            (5.0, -7.0),
+            (0.0, -7.0),
+            (0.0, 0.0)
+        ]])
+    );
+    assert_eq!(regular.outline(0, 10.0), Some(vec![]));
+    let mut fonts = FontCollection::new();
+    fonts.add(regular);
+    let mut text = Document::parse_html_unsafe(
+        "<body style='margin: 0; font: 10px \"Test Sans\"'><p style='margin: 0'>AB",
+    );
+    text.set_font_provider(fonts);
+    Window::new(text.clone()).set_viewport(Viewport {
+        width: 20.0,
+        height: 20.0,
+        ..Default::default()
+    });
+    let text = text.render();
+    assert_eq!(text.pixel(2, 4), Some(BLACK));
+    assert_eq!(text.pixel(2, 0), Some(WHITE));
+    // The second glyph is the first one moved one pixel to the right, after its advance of six pixels.
+    assert_eq!(text.pixel(6, 4), Some(WHITE));
+    assert_eq!(text.pixel(7, 4), Some(BLACK));
+    assert_eq!(text.pixel(12, 4), Some(WHITE));
+
+    // PNG images can be encoded and decoded.
+    assert_eq!(Bitmap::from_png(&bitmap.to_png()).unwrap(), bitmap);
+    assert!(bitmap.to_png().len() < 40 * 40 * 4);
+    // A grayscale image, compressed with dynamic Huffman codes.
+    let gray = Bitmap::from_png(&[
+        137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 12, 0, 0, 0, 12, 8,
+        0, 0, 0, 0, 115, 30, 3, 59, 0, 0, 0, 58, 73, 68, 65, 84, 120, 218, 77, 141, 201, 17, 0, 48,
+        8, 2, 41, 125, 59, 39, 234, 120, 132, 7, 35, 8, 42, 5, 0, 7, 75, 118, 114, 195, 74, 179,
+        52, 37, 53, 76, 198, 236, 219, 80, 229, 237, 141, 170, 107, 70, 155, 28, 252, 115, 27, 244,
+        143, 123, 227, 7, 105, 120, 31, 108, 57, 69, 17, 29, 0, 0, 0, 0, 73, 69, 78, 68, 174, 66,
+        96, 130,
+    ])
+    .unwrap();
+    assert_eq!((gray.width(), gray.height()), (12, 12));
+    assert_eq!(gray.pixel(0, 0), Some(BLACK));
+    assert_eq!(gray.pixel(11, 11), Some(WHITE));
+    let total: u32 = gray.pixels().chunks(4).map(|pixel| pixel[0] as u32).sum();
+    assert_eq!(total, 8043);
+    assert!(matches!(
+        Bitmap::from_png(b"not a png"),
+        Err(DOMException::NotSupportedError(_))
+    ));
+    assert!(matches!(
+        Bitmap::from_pixels(2, 2, vec![0; 4]),
+        Err(DOMException::IndexSizeError(_))
+    ));
+}
+
+#[test]
+fn display_lists() {
+    use crate::{
+        Bitmap, Clip, DOMRect, DisplayItem, Draw, Painter, RenderBackend, ResourceLoader, Shape,
+        Transform, Viewport, Window,
+    };
+
+    struct Images(Vec<u8>);
+
+    impl ResourceLoader for Images {
+        fn load(&self, _: &str) -> Option<String> {
+            None
+        }
+
+        fn load_bytes(&self, url: &str) -> Option<Vec<u8>> {
+            (url == "image.png").then(|| self.0.clone())
+        }
+    }
+
+    /// Records the frames it is given, with their damage and their items.
+    #[derive(Default)]
+    struct Recorder {
+        frames: Vec<(Vec<DOMRect>, Vec<DisplayItem>)>,
+    }
+
+    impl Recorder {
+        fn items(&mut self) -> &mut Vec<DisplayItem> {
+            &mut self.frames.last_mut().unwrap().1
+        }
+    }
+
+    impl RenderBackend for Recorder {
+        fn begin_frame(&mut self, _: &Viewport, damage: &[DOMRect]) {
+            self.frames.push((damage.to_vec(), vec![]));
+        }
+        fn push_opacity(&mut self, opacity: f64) {
+            self.items().push(DisplayItem::PushOpacity(opacity));
+        }
+        fn pop_opacity(&mut self) {
+            self.items().push(DisplayItem::PopOpacity);
+        }
+        fn draw(&mut self, draw: &Draw) {
+            self.items().push(DisplayItem::Draw(Box::new(draw.clone())));
+        }
+    }
+
+    fn draws(items: &[DisplayItem]) -> Vec<&Draw> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                DisplayItem::Draw(draw) => Some(&**draw),
+                _ => None,
+            })
+            .collect()
+    }
+
+    let mut document = Document::parse_html_unsafe(
+        "<style>
+            body { margin: 0 }
+            div { position: absolute; width: 10px; height: 10px }
+        </style>
+        <div style='left: 0; top: 0; background: red'></div>
+        <div style='left: 100px; top: 0; border: 1px solid blue; border-right-color: lime'></div>
+        <div style='left: 200px; top: 0; opacity: 0.5'><div style='background: black'></div></div>
+        <div style='left: 300px; top: 0; overflow: hidden; transform: translate(5px, 5px)'>
+            <div style='width: 20px; background: green'></div>
+        </div>
+        <p style='position: absolute; left: 0; top: 100px; margin: 0; font: 10px serif'>Hi</p>
+        <img src=image.png style='position: absolute; left: 0; top: 200px; width: 10px; height: 10px'>",
+    );
+    let image = Bitmap::from_pixels(1, 1, vec![255, 0, 0, 255]).unwrap();
+    document.set_resource_loader(Images(image.to_png()));
+
+    // The display list has typed items, in painting order.
+    let list = document.display_list();
+    assert_eq!(list.viewport(), &Viewport::default());
+    let items = list.items();
+    let shapes: Vec<&Shape> = draws(items).into_iter().map(|draw| &draw.shape).collect();
+    assert_eq!(shapes.len(), 7);
+    assert!(matches!(
+        shapes[0],
+        Shape::Rect { rect, color, .. } if *rect == DOMRect::new(0.0, 0.0, 800.0, 600.0) && color.red == 255 && color.blue == 255
+    ));
+    assert!(matches!(
+        shapes[1],
+        Shape::Rect { rect, color, .. } if *rect == DOMRect::new(0.0, 0.0, 10.0, 10.0) && (color.red, color.green) == (255, 0)
+    ));
+    let Shape::Border {
+        rect,
+        widths,
+        colors,
+        ..
+    } = shapes[2]
+    else {
+        panic!("The second div has a border.");
+    };
+    assert_eq!(*rect, DOMRect::new(100.0, 0.0, 12.0, 12.0));
+    assert_eq!(*widths, [1.0; 4]);
+    assert_eq!(
+        colors.map(|color| (color.red, color.green, color.blue)),
+        [(0, 0, 255), (0, 255, 0), (0, 0, 255), (0, 0, 255)]
+    );
+    // Groups are around the items they make transparent.
+    let group = items
+        .iter()
+        .position(|item| *item == DisplayItem::PushOpacity(0.5))
+        .unwrap();
+    assert!(
+        matches!(&items[group + 1], DisplayItem::Draw(draw) if matches!(draw.shape, Shape::Rect { color, .. } if color.alpha == 255 && color.red == 0))
+    );
+    assert_eq!(items[group + 2], DisplayItem::PopOpacity);
+    // Draws have the transforms and clips they are painted with, and are bounded by their clips.
+    let translate = Transform {
+        e: 5.0,
+        f: 5.0,
+        ..Transform::IDENTITY
+    };
+    let clipped = draws(items)[4];
+    assert_eq!(clipped.transform, translate);
+    assert_eq!(
+        clipped.clips,
+        [Clip {
+            rect: DOMRect::new(300.0, 0.0, 10.0, 10.0),
+            transform: translate
+        }]
+    );
+    assert_eq!(clipped.bounds(), DOMRect::new(305.0, 5.0, 10.0, 10.0));
+    let Shape::Text { glyphs, font, .. } = shapes[5] else {
+        panic!("The paragraph has text.");
+    };
+    assert_eq!(
+        glyphs
+            .iter()
+            .map(|glyph| glyph.character)
+            .collect::<String>(),
+        "Hi"
+    );
+    // The baseline is below the half-leading and the ascent.
+    assert_eq!((glyphs[0].origin, glyphs[0].advance), ((0.0, 109.0), 5.0));
+    assert_eq!(glyphs[1].origin.0, 5.0);
+    assert_eq!(
+        (font.families.as_slice(), font.size),
+        (&[String::from("serif")][..], 10.0)
+    );
+    let Shape::Image { rect, url, bitmap } = shapes[6] else {
+        panic!("The image is loaded.");
+    };
+    assert_eq!(
+        (*rect, url.as_str(), &**bitmap),
+        (DOMRect::new(0.0, 200.0, 10.0, 10.0), "image.png", &image)
+    );
+
+    // The first frame repaints the whole viewport, and then frames only repaint what changed.
+    let mut painter = Painter::new();
+    let mut recorder = Recorder::default();
+    let viewport = DOMRect::new(0.0, 0.0, 800.0, 600.0);
+    assert_eq!(painter.paint(&document, &mut recorder), [viewport]);
+    assert_eq!(recorder.frames[0].1, items);
+    assert!(painter.paint(&document, &mut recorder).is_empty());
+    assert_eq!(recorder.frames.len(), 1);
+
+    let mut first = document.element_from_point(5.0, 5.0).unwrap();
+    first.set_attribute("style", "left: 0; top: 0; background: lime");
+    assert_eq!(
+        painter.paint(&document, &mut recorder),
+        [DOMRect::new(0.0, 0.0, 10.0, 10.0)]
+    );
+    // Only the items that paint in the damage are given, with the groups balanced.
+    let (damage, frame) = recorder.frames.last().unwrap();
+    assert_eq!(damage, &[DOMRect::new(0.0, 0.0, 10.0, 10.0)]);
+    assert_eq!(draws(frame).len(), 2);
+    assert_eq!(
+        frame
+            .iter()
+            .filter(|item| **item == DisplayItem::PopOpacity)
+            .count(),
+        1
+    );
+
+    // Moving a box damages where it was and where it is.
+    first.set_attribute("style", "left: 50px; top: 0; background: lime");
+    let damage = painter.paint(&document, &mut recorder);
+    assert_eq!(damage.len(), 2);
+    assert!(damage.contains(&DOMRect::new(0.0, 0.0, 10.0, 10.0)));
+    assert!(damage.contains(&DOMRect::new(50.0, 0.0, 10.0, 10.0)));
+    // Overlapping damage is merged.
+    first.set_attribute("style", "left: 55px; top: 0; background: lime");
+    assert_eq!(
+        painter.paint(&document, &mut recorder),
+        [DOMRect::new(50.0, 0.0, 15.0, 10.0)]
+    );
+
+    // Changing the opacity of a group damages what is in it.
+    let mut group = document
+        .element_from_point(205.0, 5.0)
+        .unwrap()
+        .parent_element()
+        .unwrap();
+    group.set_attribute("style", "left: 200px; top: 0; opacity: 0.25");
+    assert_eq!(
+        painter.paint(&document, &mut recorder),
+        [DOMRect::new(200.0, 0.0, 10.0, 10.0)]
+    );
+
+    // Resizing the viewport, or invalidating the painter, repaints everything.
+    Window::new(document.clone()).set_viewport(Viewport {
+        width: 400.0,
+        height: 300.0,
+        ..Default::default()
+    });
+    assert_eq!(
+        painter.paint(&document, &mut recorder),
+        [DOMRect::new(0.0, 0.0, 400.0, 300.0)]
+    );
+    assert!(painter.paint(&document, &mut recorder).is_empty());
+    painter.invalidate();
+    assert_eq!(
+        painter.paint(&document, &mut recorder),
+        [DOMRect::new(0.0, 0.0, 400.0, 300.0)]
+    );
+}
+
+#[test]
+fn terminal_rendering() {
+    use crate::TerminalOptions;
+
+    let plain = |width: usize| TerminalOptions { width, ansi: false };
+    let document = Document::parse_html_unsafe(
+        "<style>body { margin: 0 }</style>
+        <h1>Heading</h1>
+        <p>Some <b>bold</b>, <em>italic</em> and <a href='/docs'>linked</a> text that wraps across lines.</p>
+        <ol start=3><li>Three</li><li value=10>Ten<ul><li>Nested</li></ul></li></ol>
+        <ul style='list-style-type: upper-roman'><li>First<li>Second</ul>
+        <pre>  keep   spaces\nand lines</pre>
+        <table>
+            <thead><tr><th>Name</th><th>Qty</th></tr></thead>
+            <tbody><tr><td>Apples</td><td>3</td></tr><tr><td>Pears</td><td>12</td></tr></tbody>
+        </table>
+        <form>
+            <input value=Ada size=5> <input type=checkbox checked> <input type=radio>
+            <input type=password value=abc size=4> <select><option>One<option selected>Two</select>
+            <button>Send <b>now</b></button> <img alt=Logo><img alt=''>
+        </form>
+        <p style='display: none'>Hidden</p>
+        <p>Again <a href='/docs'>docs</a>, <span style='visibility: hidden'>unseen</span><a href='https://example.com/'>elsewhere</a></p>
+        <hr>
+        <p style='white-space: nowrap'>This line of text does not wrap at all</p>
+        <p>Words_longer_than_lines_are_broken_up</p>",
+    );
+    assert_eq!(
+        document.render_text(&plain(30)),
+        [
+            "Heading",
+            "",
+            "Some bold, italic and",
+            "linked[1] text that wraps",
+            "across lines.",
+            "",
+            " 3. Three",
+            "10. Ten",
+            "      ◦ Nested",
+            "",
+            " I. First",
+            "II. Second",
+            "",
+            "  keep   spaces",
+            "and lines",
+            "",
+            "Name    Qty",
+            "───────────",
+            "Apples  3",
+            "Pears   12",
+            "[Ada__] [x] ( ) [***_] [Two ▾]",
+            "[ Send now ] [Logo]",
+            "",
+            "Again docs[1], elsewhere[2]",
+            "",
+            "──────────────────────────────",
+            "",
+            "This line of text does not wrap at all",
+            "",
+            "Words_longer_than_lines_are_br",
+            "oken_up",
+            "",
+            "References",
+            "1. /docs",
+            "2. https://example.com/",
+        ]
+        .join("\n")
+    );
+
+    // Text is styled with ANSI escape codes, from the computed font and text decorations.
+    let styled = Document::parse_html_unsafe(
+        "<h2>Title</h2><p><b>bold</b> <i>italic</i> <u>under<b>line</b></u> <s>struck</s> plain",
+    );
+    assert_eq!(
+        styled.render_text(&TerminalOptions::default()),
+        "\x1b[1mTitle\x1b[0m\n\n\x1b[1mbold\x1b[0m \x1b[3mitalic\x1b[0m \x1b[4munder\x1b[0m\x1b[1;4mline\x1b[0m \x1b[9mstruck\x1b[0m plain"
+    );
+
+    // Columns of tables shrink to fit the line, and their cells wrap.
+    let table = Document::parse_html_unsafe(
+        "<table><tr><td>A cell with a lot of text in it</td><td>Another long cell</td></tr></table>",
+    );
+    assert_eq!(
+        table.render_text(&plain(30)),
+        "A cell with a lot   Another\nof text in it       long cell"
+    );
+    // Documents without anything to show render as nothing.
+    assert_eq!(Document::new().render_text(&plain(30)), "");
+}
+
+#[test]
+fn web_animations() {
+    use crate::{
+        Animatable, AnimationOptions, AnimationPlayState, DOMException, EffectTiming, Event,
+        EventType, FillMode, GetAnimationsOptions, Keyframe, KeyframeAnimationOptions, Window,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    let document = Document::parse_html_unsafe(
+        "<style>#important { opacity: 0.5 !important }</style>
+        <div id=outer style='width: 100px'><p id=inner>Text</p></div>
+        <span id=important></span>",
+    );
+    let body = document
+        .first_element_child()
+        .unwrap()
+        .last_element_child()
+        .unwrap();
+    let mut outer = body.first_element_child().unwrap();
+    let mut inner = outer.first_element_child().unwrap();
+    let mut important = body.last_element_child().unwrap();
+    let mut window = Window::new(document.clone());
+    let timing = |timing: EffectTiming| {
+        Some(AnimationOptions::from(KeyframeAnimationOptions {
+            id: None,
+            timing,
+        }))
+    };
+    let style = |window: &Window, element: &crate::Element, name: &str| {
+        window
+            .get_computed_style(element, None)
+            .get_property_value(name)
+    };
+
+    // Keyframes that leave out the start animate from the underlying value, and offsets space the others out.
+    let mut animation = outer
+        .animate(
+            vec![
+                Keyframe {
+                    offset: Some(0.5),
+                    ..Keyframe::new([("width", "300px")])
+                },
+                Keyframe::new([("width", "200px")]),
+            ],
+            Some(AnimationOptions::from(1000.0)),
+        )
+        .unwrap();
+    assert_eq!(animation.play_state(), AnimationPlayState::Running);
+    assert_eq!(style(&window, &outer, "width"), "100px");
+    window.advance_time(250.0);
+    assert_eq!(style(&window, &outer, "width"), "200px");
+    window.advance_time(500.0);
+    assert_eq!(style(&window, &outer, "width"), "250px");
+    let keyframes = animation.effect().get_keyframes();
+    assert_eq!(
+        keyframes
+            .iter()
+            .map(|keyframe| keyframe.offset)
+            .collect::<Vec<_>>(),
+        [Some(0.5), Some(1.0)]
+    );
+
+    // Finishing fires a `finish` event once, and the effect stops applying without a fill.
+    let finishes = Rc::new(Cell::new(0));
+    let counter = finishes.clone();
+    animation.add_event_listener(
+        EventType::Finish,
+        Some((move |_: &mut Event| counter.set(counter.get() + 1)).into()),
+        None,
+    );
+    animation.finish().unwrap();
+    assert_eq!(animation.play_state(), AnimationPlayState::Finished);
+    assert_eq!(animation.current_time(), Some(1000.0));
+    assert_eq!(finishes.get(), 1);
+    window.advance_time(100.0);
+    assert_eq!(finishes.get(), 1);
+    assert_eq!(style(&window, &outer, "width"), "100px");
+    assert!(outer.get_animations(None).is_empty());
+
+    // Playing a finished animation restarts it, and playing it backwards starts from its end.
+    animation.play().unwrap();
+    assert_eq!(animation.current_time(), Some(0.0));
+    animation.set_current_time(Some(500.0)).unwrap();
+    animation.set_playback_rate(-2.0);
+    window.advance_time(100.0);
+    assert_eq!(animation.current_time(), Some(300.0));
+    assert_eq!(style(&window, &outer, "width"), "220px");
+    window.advance_time(150.0);
+    assert_eq!(animation.play_state(), AnimationPlayState::Finished);
+    assert_eq!(animation.current_time(), Some(0.0));
+    assert_eq!(finishes.get(), 2);
+    animation.reverse().unwrap();
+    assert_eq!(animation.playback_rate(), 2.0);
+    assert_eq!(animation.current_time(), Some(0.0));
+    assert!(animation.set_current_time(None).is_err());
+
+    // Cancelling removes the effect and fires a `cancel` event.
+    let cancels = Rc::new(Cell::new(0));
+    let counter = cancels.clone();
+    animation.add_event_listener(
+        EventType::Cancel,
+        Some((move |_: &mut Event| counter.set(counter.get() + 1)).into()),
+        None,
+    );
+    window.advance_time(375.0);
+    assert_eq!(style(&window, &outer, "width"), "250px");
+    animation.cancel();
+    animation.cancel();
+    assert_eq!(cancels.get(), 1);
+    assert_eq!(animation.play_state(), AnimationPlayState::Idle);
+    assert_eq!(animation.current_time(), None);
+    assert_eq!(style(&window, &outer, "width"), "100px");
+
+    // Delays, fills, iterations and easing.
+    let mut fade = inner
+        .animate(
+            vec![
+                Keyframe::new([("opacity", "0")]),
+                Keyframe::new([("opacity", "1")]),
+            ],
+            timing(EffectTiming {
+                delay: 100.0,
+                duration: 100.0,
+                iterations: 3.0,
+                iteration_start: 0.5,
+                fill: FillMode::Both,
+                easing: String::from("steps(4)"),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+    // Before the effect starts, its steps have not jumped yet.
+    assert_eq!(style(&window, &inner, "opacity"), "0.25");
+    window.advance_time(130.0);
+    assert_eq!(style(&window, &inner, "opacity"), "0.75");
+    let computed = fade.effect().get_computed_timing();
+    assert_eq!(computed.current_iteration, Some(0.0));
+    assert_eq!(computed.end_time, 400.0);
+    window.advance_time(30.0);
+    assert_eq!(style(&window, &inner, "opacity"), "0");
+    assert_eq!(
+        fade.effect().get_computed_timing().current_iteration,
+        Some(1.0)
+    );
+    window.advance_time(1000.0);
+    assert_eq!(style(&window, &inner, "opacity"), "0.5");
+    assert_eq!(
+        outer.get_animations(Some(GetAnimationsOptions { subtree: true })),
+        [fade.clone()]
+    );
+    assert_eq!(document.get_animations(), [fade.clone()]);
+
+    // Pausing holds the current time.
+    fade.set_current_time(Some(250.0)).unwrap();
+    fade.pause().unwrap();
+    window.advance_time(500.0);
+    assert_eq!(fade.current_time(), Some(250.0));
+    assert_eq!(fade.play_state(), AnimationPlayState::Paused);
+    assert_eq!(style(&window, &inner, "opacity"), "0");
+
+    // Animations that apply later override earlier ones, and `!important` declarations override both.
+    let mut keyframes = vec![
+        Keyframe::new([("opacity", "0.2"), ("transform", "none")]),
+        Keyframe::new([
+            ("opacity", "0.4"),
+            ("transform", "translate(100px, 10px) scale(3)"),
+        ]),
+    ];
+    let slide = inner
+        .animate(keyframes.clone(), Some(AnimationOptions::from(100.0)))
+        .unwrap();
+    window.advance_time(50.0);
+    assert_eq!(style(&window, &inner, "opacity"), "0.3");
+    assert_eq!(
+        style(&window, &inner, "transform"),
+        "translate(50px, 5px) scale(2)"
+    );
+    important
+        .animate(keyframes.clone(), Some(AnimationOptions::from(100.0)))
+        .unwrap();
+    assert_eq!(style(&window, &important, "opacity"), "0.5");
+    assert_eq!(
+        style(&window, &important, "transform"),
+        "translate(0px, 0px) scale(1)"
+    );
+    assert_eq!(inner.get_animations(None), [fade, slide]);
+
+    // Keyframes and timings that are not valid are errors.
+    keyframes[0].offset = Some(0.8);
+    keyframes[1].offset = Some(0.2);
+    assert!(matches!(
+        inner.animate(keyframes, None),
+        Err(DOMException::TypeError(_))
+    ));
+    let bad_easing = Keyframe {
+        easing: Some(String::from("bounce")),
+        ..Keyframe::new([("opacity", "0")])
+    };
+    assert!(inner.animate(vec![bad_easing], None).is_err());
+    let mut forever = inner
+        .animate(
+            vec![Keyframe::new([("opacity", "0")])],
+            timing(EffectTiming {
+                duration: 100.0,
+                iterations: f64::INFINITY,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+    assert!(matches!(
+        forever.finish(),
+        Err(DOMException::InvalidStateError(_))
+    ));
+    assert!(forever
+        .effect()
+        .update_timing(EffectTiming {
+            duration: -1.0,
+            ..Default::default()
+        })
+        .is_err());
+}
+
+#[test]
+fn css_transitions_and_animations() {
+    use crate::{
+        domitem::DOMItem, Animatable, AnimationPlayState, Event, EventDetail, EventType,
+        GetAnimationsOptions, Window,
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    let document = Document::parse_html_unsafe(
+        "<style>
+            .fade { opacity: 1; transition: opacity 1s linear, margin-left 1s linear 0.5s }
+            .fade.out { opacity: 0; margin-left: 100px }
+            .slide { margin-left: 10px; transition: margin-left 1s linear }
+            .slide.open { margin-left: 110px }
+            @keyframes move { from { margin-left: 0 } to { margin-left: 100px } }
+            @keyframes move { 50% { margin-left: 80px } }
+            .moving { animation: move 1s linear 2 alternate }
+        </style>
+        <div class=fade></div><div class=slide></div><div></div>",
+    );
+    let body = document
+        .first_element_child()
+        .unwrap()
+        .last_element_child()
+        .unwrap();
+    let mut fade = body.children().item(0).unwrap().clone_ref();
+    let mut slide = body.children().item(1).unwrap().clone_ref();
+    let mut moving = body.children().item(2).unwrap().clone_ref();
+    let mut window = Window::new(document.clone());
+    let style = |window: &Window, element: &crate::Element, name: &str| {
+        window
+            .get_computed_style(element, None)
+            .get_property_value(name)
+    };
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let mut body_ref = AsNode::cast(&body).clone_ref();
+    for event_type in [
+        EventType::TransitionRun,
+        EventType::TransitionStart,
+        EventType::TransitionEnd,
+        EventType::TransitionCancel,
+        EventType::AnimationStart,
+        EventType::AnimationIteration,
+        EventType::AnimationEnd,
+        EventType::AnimationCancel,
+    ] {
+        let events = events.clone();
+        let listener = move |event: &mut Event| {
+            let (name, elapsed_time) = match event.detail() {
+                EventDetail::Transition {
+                    property_name,
+                    elapsed_time,
+                    ..
+                } => (property_name.clone(), *elapsed_time),
+                EventDetail::Animation {
+                    animation_name,
+                    elapsed_time,
+                    ..
+                } => (animation_name.clone(), *elapsed_time),
+                _ => unreachable!(),
+            };
+            events
+                .borrow_mut()
+                .push((event.type_().clone(), name, elapsed_time));
+        };
+        body_ref.add_event_listener(event_type, Some(listener.into()), None);
+    }
+    let take = || std::mem::take(&mut *events.borrow_mut());
+
+    // The shorthand sets lists of longhands, and serializes back from them.
+    let computed = window.get_computed_style(&fade, None);
+    assert_eq!(
+        computed.get_property_value("transition-property"),
+        "opacity, margin-left"
+    );
+    assert_eq!(computed.get_property_value("transition-delay"), "0s, 0.5s");
+    assert_eq!(
+        computed.get_property_value("transition"),
+        "opacity 1s linear, margin-left 1s linear 0.5s"
+    );
+
+    // A change of computed value starts a transition from the old value, and its events are fired as the clock moves.
+    fade.class_list_mut().add("out").unwrap();
+    assert_eq!(style(&window, &fade, "opacity"), "1");
+    window.advance_time(250.0);
+    assert_eq!(style(&window, &fade, "opacity"), "0.75");
+    assert_eq!(style(&window, &fade, "margin-left"), "0px");
+    assert_eq!(
+        take(),
+        [
+            (EventType::TransitionRun, String::from("opacity"), 0.0),
+            (EventType::TransitionStart, String::from("opacity"), 0.0),
+            (EventType::TransitionRun, String::from("margin-left"), 0.0),
+        ]
+    );
+    let transitions = fade.get_animations(None);
+    assert_eq!(
+        transitions
+            .iter()
+            .map(|animation| animation.transition_property().unwrap())
+            .collect::<Vec<_>>(),
+        ["margin-left", "opacity"]
+    );
+    window.advance_time(1000.0);
+    assert_eq!(style(&window, &fade, "margin-left"), "75px");
+    window.advance_time(1000.0);
+    assert_eq!(style(&window, &fade, "opacity"), "0");
+    assert_eq!(style(&window, &fade, "margin-left"), "100px");
+    assert_eq!(
+        take(),
+        [
+            (EventType::TransitionStart, String::from("margin-left"), 0.0),
+            (EventType::TransitionEnd, String::from("opacity"), 1.0),
+            (EventType::TransitionEnd, String::from("margin-left"), 1.0),
+        ]
+    );
+    assert!(fade.get_animations(None).is_empty());
+
+    // Going back before a transition is done reverses it, in the time it took to get there.
+    slide.class_list_mut().add("open").unwrap();
+    window.advance_time(250.0);
+    assert_eq!(style(&window, &slide, "margin-left"), "35px");
+    slide.class_list_mut().remove("open").unwrap();
+    window.advance_time(125.0);
+    assert_eq!(style(&window, &slide, "margin-left"), "22.5px");
+    window.advance_time(125.0);
+    assert_eq!(style(&window, &slide, "margin-left"), "10px");
+    let types: Vec<_> = take()
+        .into_iter()
+        .map(|(event_type, _, _)| event_type)
+        .collect();
+    assert_eq!(
+        types,
+        [
+            EventType::TransitionRun,
+            EventType::TransitionStart,
+            EventType::TransitionCancel,
+            EventType::TransitionRun,
+            EventType::TransitionStart,
+            EventType::TransitionEnd,
+        ]
+    );
+
+    // Elements that stop being rendered lose their transitions.
+    slide.class_list_mut().add("open").unwrap();
+    window.advance_time(100.0);
+    slide.set_attribute("style", "display: none");
+    window.advance_time(100.0);
+    assert_eq!(
+        take().last().unwrap(),
+        &(
+            EventType::TransitionCancel,
+            String::from("margin-left"),
+            0.1
+        )
+    );
+
+    // Animations play the last `@keyframes` rule with their name, with the underlying value for missing keyframes.
+    moving.set_attribute("class", "moving");
+    assert_eq!(style(&window, &moving, "margin-left"), "0px");
+    window.advance_time(250.0);
+    assert_eq!(style(&window, &moving, "margin-left"), "40px");
+    window.advance_time(1100.0);
+    assert_eq!(style(&window, &moving, "margin-left"), "56px");
+    let animations = body.get_animations(Some(GetAnimationsOptions { subtree: true }));
+    assert_eq!(animations.len(), 1);
+    assert_eq!(animations[0].animation_name().as_deref(), Some("move"));
+
+    // Pausing with `animation-play-state` holds the animation, and removing its name cancels it.
+    moving.set_attribute("style", "animation-play-state: paused");
+    window.advance_time(500.0);
+    assert_eq!(style(&window, &moving, "margin-left"), "56px");
+    assert_eq!(animations[0].play_state(), AnimationPlayState::Paused);
+    moving.remove_attribute("style");
+    window.advance_time(250.0);
+    assert_eq!(style(&window, &moving, "margin-left"), "64px");
+    moving.set_attribute("class", "");
+    window.advance_time(0.0);
+    assert_eq!(style(&window, &moving, "margin-left"), "0px");
+    assert_eq!(
+        take(),
+        [
+            (EventType::AnimationStart, String::from("move"), 0.0),
+            (EventType::AnimationIteration, String::from("move"), 1.0),
+            (EventType::AnimationCancel, String::from("move"), 1.6),
+        ]
+    );
+    assert!(body
+        .get_animations(Some(GetAnimationsOptions { subtree: true }))
+        .is_empty());
+}
+
+#[test]
+fn directionality() {
+    use crate::{Element, InnerHtml, TerminalOptions, Window};
+
+    let document = Document::parse_html_unsafe("<body></body>");
+    let html = document.first_element_child().unwrap();
+    let mut body = html.children().item(1).unwrap();
+    let mut section = document.create_element("section");
+    section
+        .set_inner_html(
+            "<p>text</p>
+            <p dir=auto><bdi>ignored</bdi> <span dir=ltr>set</span> 123 שלום</p>
+            <p dir=AUTO>123</p>
+            <div dir=ltr><bdi>مرحبا</bdi><input dir=auto value='שלום'><input type=tel></div>
+            <textarea dir=auto>hello</textarea>",
+        )
+        .unwrap();
+    section.set_dir("rtl");
+    body.append_child(&mut section).unwrap();
+    assert_eq!(section.dir(), "rtl");
+    let children = |element: &Element| {
+        let children = element.children();
+        (0..children.len())
+            .map(|index| children.item(index).unwrap())
+            .collect::<Vec<_>>()
+    };
+    let elements = children(AsElement::cast(&section));
+    let direction = |index: usize| match elements[index].matches(":dir(rtl)") {
+        true => "rtl",
+        false => "ltr",
+    };
+    // Elements without `dir` inherit the direction, and `auto` takes the first strong character of text that is not isolated.
+    assert_eq!(direction(0), "rtl");
+    assert_eq!(direction(1), "rtl");
+    // Without strong characters, `auto` is left to right rather than inherited.
+    assert_eq!(direction(2), "ltr");
+    let div_children = children(&elements[3]);
+    assert_eq!(direction(3), "ltr");
+    assert!(div_children[0].matches(":dir(rtl)"));
+    assert!(div_children[1].matches(":dir(rtl)"));
+    assert!(div_children[2].matches(":dir(ltr)"));
+    assert_eq!(direction(4), "ltr");
+
+    let window = Window::new(document.clone());
+    let direction_of = |element: &Element| {
+        window
+            .get_computed_style(element, None)
+            .get_property_value("direction")
+    };
+    assert_eq!(direction_of(&elements[0]), "rtl");
+    assert_eq!(direction_of(&elements[1]), "rtl");
+    assert_eq!(direction_of(&elements[2]), "ltr");
+    assert_eq!(direction_of(&div_children[0]), "rtl");
+
+    // Invalid values are dropped, and the direction is inherited again.
+    section.set_dir("up");
+    assert_eq!(section.dir(), "");
+    assert!(!section.matches(":dir(rtl)"));
+    assert_eq!(direction(0), "ltr");
+
+    // Text that reads right to left is reordered on its line, around the text that reads left to right.
+    section
+        .set_inner_html(
+            "<style>body, p { margin: 0 } p { width: 200px }</style>
+            <p dir=rtl><span class=latin>abc</span> <span class=hebrew>אבג</span></p>
+            <p><span class=first>x</span><bdo dir=rtl><span class=second>y</span><span class=third>z</span></bdo></p>",
+        )
+        .unwrap();
+    let rect = |class: &str| {
+        html.get_elements_by_class_name(class)
+            .item(0)
+            .unwrap()
+            .get_bounding_client_rect()
+    };
+    // Right-to-left paragraphs start at the right.
+    assert!(rect("hebrew").x() < rect("latin").x());
+    assert_eq!(rect("latin").right(), 200.0);
+    // Overrides order everything in them in their direction.
+    assert_eq!(rect("first").x(), 0.0);
+    assert!(rect("first").x() < rect("third").x());
+    assert!(rect("third").x() < rect("second").x());
+
+    // Lines of text rendered for terminals are reordered the same way, and mirror their brackets.
+    section
+        .set_inner_html(
+            "<style>p { margin: 0 }</style>
+            <p>abc אבג (דה) def</p>
+            <p dir=rtl>abc אבג 12 def</p>",
+        )
+        .unwrap();
+    let plain = TerminalOptions {
+        width: 40,
+        ansi: false,
+    };
+    assert_eq!(
+        document.render_text(&plain).lines().collect::<Vec<_>>(),
+        ["abc (הד) גבא def", "def 12 גבא abc"]
+    );
+}
+
+#[test]
+fn form_owner() {
+    use crate::{
+        HTMLFormElement, HTMLInputElement, HTMLLabelElement, InnerHtml, RadioNodeListOrElement,
+    };
+
+    let document = Document::parse_html_unsafe("<body></body>");
+    let html = document.first_element_child().unwrap();
+    let mut body = html.children().item(1).unwrap();
+    let tag_names = |form: &HTMLFormElement| {
+        form.elements()
+            .iter()
+            .map(|element| element.tag_name().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let mut first = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    first.set_id("first");
+    first
+        .set_inner_html(
+            "<input name=a><fieldset><select name=b></select></fieldset>\
+            <input type=image name=c><button form=second></button>",
+        )
+        .unwrap();
+    body.append_child(&mut first).unwrap();
+    // The form that the button refers to is not in the document yet.
+    assert_eq!(tag_names(&first), ["INPUT", "FIELDSET", "SELECT"]);
+
+    let mut second = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    second.set_id("second");
+    body.append_child(&mut second).unwrap();
+    assert_eq!(tag_names(&second), ["BUTTON"]);
+    assert_eq!(first.len(), 3);
+
+    // Controls are reassociated when they move.
+    let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+    assert!(input.form().is_none());
+    first.append_child(&mut input).unwrap();
+    assert!(input.form().unwrap() == first);
+    let elements = second.elements();
+    second.append_child(&mut input).unwrap();
+    assert!(input.form().unwrap() == second);
+    assert_eq!(elements.len(), 2);
+    body.append_child(&mut input).unwrap();
+    assert!(input.form().is_none());
+
+    // The form attribute overrides the ancestor form.
+    second.append_child(&mut input).unwrap();
+    input.set_attribute("form", "first");
+    assert!(input.form().unwrap() == first);
+    assert_eq!(tag_names(&first), ["INPUT", "FIELDSET", "SELECT", "INPUT"]);
+    input.set_attribute("form", "missing");
+    assert!(input.form().is_none());
+    input.remove_attribute("form");
+    assert!(input.form().unwrap() == second);
+
+    // Changing the ID of a form changes the controls that refer to it.
+    second.set_id("renamed");
+    assert_eq!(tag_names(&second), ["INPUT"]);
+    first.set_id("second");
+    assert_eq!(tag_names(&first), ["INPUT", "FIELDSET", "SELECT", "BUTTON"]);
+
+    // Controls that share a name are returned as a list.
+    let mut radio = document.create_element("input");
+    radio.set_attribute("name", "a");
+    radio.set_attribute("type", "radio");
+    first.append_child(&mut radio).unwrap();
+    let elements = first.elements();
+    match elements.named_item("a") {
+        Some(RadioNodeListOrElement::RadioNodeList(list)) => {
+            assert_eq!(list.len(), 2);
+            assert!(list.item(1).unwrap() == radio);
+            radio.remove_attribute("name");
+            assert_eq!(list.len(), 1);
+        }
+        _ => panic!("expected a list of controls named 'a'"),
+    }
+    match elements.named_item("b") {
+        Some(RadioNodeListOrElement::Element(element)) => assert_eq!(element.tag_name(), "SELECT"),
+        _ => panic!("expected the select element"),
+    }
+    assert!(elements.named_item("c").is_none());
+    assert!(elements.named_item("").is_none());
+
+    // Labels belong to the form of their control.
+    let mut label = HTMLLabelElement::try_from(document.create_element("label")).unwrap();
+    label.set_inner_html("Name <textarea></textarea>").unwrap();
+    assert!(label.form().is_none());
+    first.append_child(&mut label).unwrap();
+    assert_eq!(label.control().unwrap().tag_name(), "TEXTAREA");
+    assert!(label.form().unwrap() == first);
+
+    // Controls keep their form when they are removed along with it, and the ones with a form attribute fall back to their ancestor form.
+    body.remove_child(&mut first).unwrap();
+    assert_eq!(
+        tag_names(&first),
+        ["INPUT", "FIELDSET", "SELECT", "BUTTON", "INPUT", "TEXTAREA"]
+    );
+    assert!(label.form().unwrap() == first);
+}
+
+/// Returns the HTML element of an element in a document, which the interfaces of specific elements are converted from.
+fn html_element(element: &crate::Element) -> crate::HTMLElement {
+    let document = element.owner_document().unwrap();
+    let base = document
+        .lookup_html_element_base(AsNode::cast(element).get_base_ptr())
+        .unwrap();
+    crate::HTMLElement { base }
+}
+
+#[test]
+fn constraint_validation() {
+    use crate::{
+        Element, Event, EventType, HTMLFormElement, HTMLInputElement, HTMLSelectElement, InnerHtml,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    let document = Document::parse_html_unsafe("<body></body>");
+    let html = document.first_element_child().unwrap();
+    let mut body = html.children().item(1).unwrap();
+    let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    form.set_inner_html(
+        "<input id=email type=email value=a@b multiple>\
+        <input id=code pattern=[a-z]{3} value=abc>\
+        <input id=count type=number min=0 max=10 step=5 value=5>\
+        <input id=when type=time min=22:00 max=02:00 value=23:30>\
+        <input id=short minlength=3 value=ab>\
+        <input id=hidden type=hidden required>\
+        <input name=choice type=radio required><input name=choice type=radio>\
+        <select id=pick required><option value=''>Pick one</option><option>A</option></select>\
+        <fieldset disabled><legend><input id=legend required></legend><input id=barred required></fieldset>",
+    )
+    .unwrap();
+    body.append_child(&mut form).unwrap();
+    let controls = form.children();
+    let to_input = |element: &Element| HTMLInputElement::try_from(html_element(element)).unwrap();
+    let input = |index: usize| to_input(&controls.item(index).unwrap());
+    let fieldset = controls.item(9).unwrap();
+    let mut legend = to_input(
+        &fieldset
+            .first_element_child()
+            .unwrap()
+            .first_element_child()
+            .unwrap(),
+    );
+    let barred = to_input(&fieldset.last_element_child().unwrap());
+
+    // Types and patterns.
+    let mut email = input(0);
+    assert!(email.validity().valid());
+    email.set_attribute("value", "a@b, c");
+    assert!(email.validity().type_mismatch());
+    email.set_attribute("value", "a@b,c@d");
+    let mut code = input(1);
+    code.set_attribute("value", "abcd");
+    assert!(code.validity().pattern_mismatch());
+    code.set_attribute("pattern", "[[a-z]--[x]]+");
+    assert!(code.validity().valid());
+    code.set_attribute("pattern", "(");
+    assert!(code.validity().valid());
+
+    // Ranges and steps.
+    let mut count = input(2);
+    assert!(count.matches(":in-range"));
+    count.set_attribute("value", "12");
+    assert!(count.validity().range_overflow());
+    assert!(count.validity().step_mismatch());
+    assert!(count.matches(":out-of-range"));
+    count.set_attribute("value", "10");
+    let mut when = input(3);
+    assert!(when.validity().valid());
+    when.set_attribute("value", "12:00");
+    assert!(when.validity().range_underflow() && when.validity().range_overflow());
+    when.set_attribute("value", "01:00");
+    assert_eq!(
+        input(4).validation_message(),
+        "Please lengthen this text to 3 characters or more (you are currently using 2 characters)."
+    );
+    input(4).remove_attribute("minlength");
+
+    // Required controls.
+    assert!(!input(5).will_validate());
+    assert!(legend.will_validate());
+    assert!(!barred.will_validate());
+    let mut radio = input(6);
+    assert!(radio.validity().value_missing());
+    input(7).set_attribute("checked", "");
+    assert!(!radio.validity().value_missing());
+    let select = HTMLSelectElement::try_from(html_element(&controls.item(8).unwrap())).unwrap();
+    assert!(select.validity().value_missing());
+    assert!(form.matches(":invalid"));
+
+    // Invalid events are fired at the invalid controls, and the ones that are not canceled are reported.
+    let invalid = Rc::new(Cell::new(0));
+    let count_invalid = invalid.clone();
+    let listener = move |event: &mut Event| {
+        count_invalid.set(count_invalid.get() + 1);
+        event.prevent_default();
+    };
+    legend.add_event_listener(EventType::Invalid, Some(listener.into()), None);
+    assert!(!form.check_validity());
+    assert_eq!(invalid.get(), 1);
+    assert!(!form.report_validity());
+    assert!(select.matches(":user-invalid"));
+    assert!(email.matches(":user-valid"));
+    assert_eq!(invalid.get(), 2);
+
+    // Custom errors take precedence.
+    let mut option = select.first_element_child().unwrap();
+    option.set_attribute("value", "none");
+    legend.set_attribute("value", "filled");
+    assert!(form.check_validity());
+    assert!(form.matches(":valid"));
+    radio.set_attribute("checked", "");
+    email.set_custom_validity("Taken");
+    assert!(email.validity().custom_error());
+    assert_eq!(email.validation_message(), "Taken");
+    assert!(!email.check_validity());
+    email.set_custom_validity("");
+    assert!(email.check_validity());
+}
+
+#[test]
+fn form_submission() {
+    use crate::{
+        Event, EventDetail, EventType, FormData, FormDataEntryValue, HTMLFormElement, InnerHtml,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    let mut document = Document::parse_html_unsafe("<body></body>");
+    document.set_url("https://example.com/forms/page.html");
+    let html = document.first_element_child().unwrap();
+    let mut body = html.children().item(1).unwrap();
+    let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    form.set_inner_html(
+        "<input name=text value='a+b c' dirname=text.dir>\
+        <textarea name=notes>one\ntwo</textarea>\
+        <select name=pick multiple><option selected>A</option><option>B</option>\
+        <option selected disabled>C</option><option value=d selected>D</option></select>\
+        <input type=checkbox name=box checked><input type=radio name=radio value=r>\
+        <input type=hidden name=_charset_>\
+        <input type=file name=upload>\
+        <input name=off disabled value=x>\
+        <datalist><input name=listed value=x></datalist>\
+        <input type=image name=map>\
+        <button name=go value=1>Go</button>",
+    )
+    .unwrap();
+    assert_eq!(form.action(), "https://example.com/forms/page.html");
+    assert_eq!(form.method(), "get");
+    assert_eq!(form.enctype(), "application/x-www-form-urlencoded");
+
+    // Forms that are not in a document are not submitted.
+    assert!(form.submit().is_none());
+    body.append_child(&mut form).unwrap();
+
+    let image = html_element(&form.children().item(9).unwrap());
+    let button = html_element(&form.children().item(10).unwrap());
+    let strings = |form_data: &FormData| {
+        form_data
+            .entries()
+            .into_iter()
+            .map(|(name, value)| match value {
+                FormDataEntryValue::String(value) => format!("{name}={value}"),
+                FormDataEntryValue::File(file) => {
+                    format!("{name}=<{}:{}>", file.name(), file.r#type())
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        strings(&FormData::from_form(&form, Some(&image)).unwrap()),
+        [
+            "text=a+b c",
+            "text.dir=ltr",
+            "notes=one\ntwo",
+            "pick=A",
+            "pick=d",
+            "box=on",
+            "_charset_=UTF-8",
+            "upload=<:application/octet-stream>",
+            "map.x=0",
+            "map.y=0",
+        ]
+    );
+    let mut radio = form.children().item(4).unwrap();
+    assert!(matches!(
+        FormData::from_form(&form, Some(&html_element(&radio))),
+        Err(crate::DOMException::TypeError(_))
+    ));
+
+    // Submitting with the GET method puts the entries in the query of the URL.
+    let request = form.request_submit(Some(button)).unwrap().unwrap();
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.target, "_self");
+    assert_eq!(
+        request.url,
+        "https://example.com/forms/page.html?text=a%2Bb+c&text.dir=ltr&notes=one%0D%0Atwo\
+        &pick=A&pick=d&box=on&_charset_=UTF-8&upload=&go=1"
+    );
+
+    // The formdata event can change the entries, and the submit event can cancel the submission.
+    radio.set_attribute("checked", "");
+    let cancel = Rc::new(Cell::new(true));
+    let should_cancel = cancel.clone();
+    let listener = move |event: &mut Event| match event.detail().clone() {
+        EventDetail::FormData { mut form_data } => {
+            form_data.delete("pick");
+            form_data.set("text", "changed");
+        }
+        EventDetail::Submit { submitter } => {
+            assert!(submitter.is_none());
+            if should_cancel.get() {
+                event.prevent_default();
+            }
+        }
+        _ => {}
+    };
+    let listener: crate::EventListenerOrEventListenerObject = listener.into();
+    form.add_event_listener(EventType::Submit, Some(listener.clone()), None);
+    form.add_event_listener(EventType::FormData, Some(listener), None);
+    form.set_method("POST");
+    form.set_action("../submit");
+    form.set_enctype("text/plain");
+    assert!(form.request_submit(None).unwrap().is_none());
+    cancel.set(false);
+    let request = form.request_submit(None).unwrap().unwrap();
+    assert_eq!(request.url, "https://example.com/submit");
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.content_type.as_deref(), Some("text/plain"));
+    assert_eq!(
+        String::from_utf8(request.body.unwrap()).unwrap(),
+        "text=changed\r\ntext.dir=ltr\r\nnotes=one\r\ntwo\r\nbox=on\r\nradio=r\r\n\
+        _charset_=UTF-8\r\nupload=\r\n"
+    );
+
+    // Files are sent as parts of multipart bodies.
+    form.set_enctype("multipart/form-data");
+    let request = form.submit().unwrap();
+    let content_type = request.content_type.unwrap();
+    let boundary = content_type
+        .strip_prefix("multipart/form-data; boundary=")
+        .unwrap();
+    let body = String::from_utf8(request.body.unwrap()).unwrap();
+    assert!(body.starts_with(&format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"text\"\r\n\r\nchanged\r\n"
+    )));
+    assert!(body.contains(&format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"\"\r\n\
+        Content-Type: application/octet-stream\r\n\r\n\r\n"
+    )));
+    assert!(body.ends_with(&format!("--{boundary}--\r\n")));
+
+    // Invalid controls stop interactive submission, unless the form is not validated.
+    let mut required = document.create_element("input");
+    required.set_attribute("required", "");
+    form.append_child(&mut required).unwrap();
+    assert!(form.request_submit(None).unwrap().is_none());
+    form.set_no_validate(true);
+    assert!(form.request_submit(None).unwrap().is_some());
+
+    // Mail forms put the entries in the headers or the body of the message.
+    form.set_action("mailto:someone@example.com?subject=Hi");
+    form.set_inner_html("<input name=a value='b c'>").unwrap();
+    form.set_enctype("");
+    assert_eq!(
+        form.submit().unwrap().url,
+        "mailto:someone@example.com?subject=Hi&body=a=b%20c&text=changed"
+    );
+    form.set_method("get");
+    assert_eq!(
+        form.submit().unwrap().url,
+        "mailto:someone@example.com?a=b%20c&text=changed"
+    );
+}
+
+#[test]
+fn input_values() {
+    use crate::{
+        ConstraintValidation, DOMException, File, FormData, FormDataEntryValue, HTMLFormElement,
+        HTMLInputElement, InnerHtml,
+    };
+
+    let document = Document::new();
+    let input = |input_type: &str, value: &str| {
+        let mut input = HTMLInputElement::try_from(document.create_element("input")).unwrap();
+        input.set_type(input_type);
+        input.set_default_value(value);
+        input
+    };
+
+    // Values are sanitized for their type.
+    assert_eq!(input("text", "a\r\nb").value(), "ab");
+    assert_eq!(input("url", " https://a.b/ \n").value(), "https://a.b/");
+    assert_eq!(input("email", " a@b.c ").value(), "a@b.c");
+    let mut emails = input("email", " a@b.c , d@e.f ");
+    emails.set_attribute("multiple", "");
+    assert_eq!(emails.value(), "a@b.c,d@e.f");
+    assert_eq!(input("number", "1.").value(), "");
+    assert_eq!(input("number", "-1.5e3").value(), "-1.5e3");
+    assert_eq!(input("date", "2023-02-29").value(), "");
+    assert_eq!(input("date", "2024-02-29").value(), "2024-02-29");
+    assert_eq!(input("week", "2020-W53").value(), "2020-W53");
+    assert_eq!(input("week", "2021-W53").value(), "");
+    assert_eq!(input("time", "24:00").value(), "");
+    assert_eq!(
+        input("datetime-local", "2024-01-02 03:04:00.000").value(),
+        "2024-01-02T03:04"
+    );
+    assert_eq!(input("color", "#ABCDEF").value(), "#abcdef");
+    assert_eq!(input("color", "red").value(), "#000000");
+    assert_eq!(input("hidden", " a\n").value(), " a\n");
+    assert_eq!(input("checkbox", "").value(), "");
+    assert_eq!(input("checkbox", "x").value(), "x");
+    assert_eq!(
+        HTMLInputElement::try_from(document.create_element("input"))
+            .unwrap()
+            .r#type(),
+        "text"
+    );
+    assert_eq!(
+        HTMLInputElement::try_from(document.create_element("input"))
+            .unwrap()
+            .value(),
+        ""
+    );
+
+    // Range values are kept within their range and on their steps.
+    assert_eq!(input("range", "").value(), "50");
+    assert_eq!(input("range", "150").value(), "100");
+    assert_eq!(input("range", "-5").value(), "0");
+    // Without a min attribute, the value attribute is the step base.
+    let mut range = input("range", "7");
+    range.set_attribute("step", "5");
+    assert_eq!(range.value(), "7");
+    range.set_attribute("min", "0");
+    assert_eq!(range.value(), "5");
+    range.set_value("8").unwrap();
+    assert_eq!(range.value(), "10");
+    range.set_attribute("max", "8");
+    assert_eq!(range.value(), "5");
+    range.set_attribute("min", "10");
+    assert_eq!(range.value(), "10");
+    let mut range = input("range", "0.35");
+    range.set_attribute("min", "0");
+    range.set_attribute("max", "1");
+    range.set_attribute("step", "0.1");
+    assert_eq!(range.value(), "0.4");
+
+    // Setting the value makes it dirty, so the value attribute no longer changes it.
+    let mut text = input("text", "default");
+    text.set_default_value("changed");
+    assert_eq!(text.value(), "changed");
+    text.set_value("typed\n").unwrap();
+    text.set_default_value("ignored");
+    assert_eq!(text.value(), "typed");
+    assert_eq!(text.default_value(), "ignored");
+
+    // Types whose value is their value attribute keep it when the type changes.
+    text.set_type("hidden");
+    assert_eq!(text.default_value(), "typed");
+    text.set_value("hidden").unwrap();
+    assert_eq!(text.default_value(), "hidden");
+    text.set_type("number");
+    assert_eq!(text.value(), "");
+    text.set_default_value("12");
+    assert_eq!(text.value(), "12");
+
+    // Numbers are formatted like JavaScript formats them.
+    let mut number = input("number", "");
+    for (value, expected) in [
+        (1e21, "1e+21"),
+        (123456789012345680000.0, "123456789012345680000"),
+        (0.000001, "0.000001"),
+        (1e-7, "1e-7"),
+        (-2.5, "-2.5"),
+        (-0.0, "0"),
+    ] {
+        number.set_value_as_number(value).unwrap();
+        assert_eq!(number.value(), expected);
+    }
+    number.set_value_as_number(f64::NAN).unwrap();
+    assert_eq!(number.value(), "");
+    assert!(number.value_as_number().is_nan());
+    assert!(matches!(
+        number.set_value_as_number(f64::INFINITY),
+        Err(DOMException::TypeError(_))
+    ));
+    assert!(matches!(
+        input("text", "").set_value_as_number(1.0),
+        Err(DOMException::InvalidStateError(_))
+    ));
+    assert!(input("text", "1").value_as_number().is_nan());
+
+    // Dates and times convert to milliseconds and back.
+    let mut date = input("date", "1970-01-02");
+    assert_eq!(date.value_as_number(), 86_400_000.0);
+    assert_eq!(date.value_as_date(), Some(86_400_000.0));
+    date.set_value_as_number(951_782_400_000.0).unwrap();
+    assert_eq!(date.value(), "2000-02-29");
+    date.set_value_as_date(None).unwrap();
+    assert_eq!(date.value(), "");
+    let mut month = input("month", "1970-02");
+    assert_eq!(month.value_as_number(), 1.0);
+    assert_eq!(month.value_as_date(), Some(31.0 * 86_400_000.0));
+    month.set_value_as_date(Some(951_782_400_000.0)).unwrap();
+    assert_eq!(month.value(), "2000-02");
+    let mut week = input("week", "");
+    // Sunday, the third of January 2021, is in the last week of 2020.
+    week.set_value_as_date(Some(1_609_632_000_000.0)).unwrap();
+    assert_eq!(week.value(), "2020-W53");
+    let mut local = input("datetime-local", "");
+    local.set_value_as_number(1_000.0).unwrap();
+    assert_eq!(local.value(), "1970-01-01T00:00:01");
+    assert_eq!(local.value_as_date(), None);
+    assert!(matches!(
+        local.set_value_as_date(Some(0.0)),
+        Err(DOMException::InvalidStateError(_))
+    ));
+
+    // Stepping stays on steps and within the range.
+    let mut number = input("number", "");
+    number.set_attribute("min", "2");
+    number.set_attribute("max", "11");
+    number.set_attribute("step", "3");
+    number.step_up(1).unwrap();
+    assert_eq!(number.value(), "2");
+    number.step_up(2).unwrap();
+    assert_eq!(number.value(), "8");
+    number.step_up(5).unwrap();
+    assert_eq!(number.value(), "11");
+    number.set_value("6").unwrap();
+    number.step_down(1).unwrap();
+    assert_eq!(number.value(), "5");
+    number.set_value("6").unwrap();
+    number.step_up(1).unwrap();
+    assert_eq!(number.value(), "8");
+    number.set_value("1").unwrap();
+    number.step_down(1).unwrap();
+    assert_eq!(number.value(), "1");
+    let mut time = input("time", "10:00");
+    time.set_attribute("step", "900");
+    time.step_down(2).unwrap();
+    assert_eq!(time.value(), "09:30");
+    time.set_attribute("step", "any");
+    assert!(matches!(
+        time.step_up(1),
+        Err(DOMException::InvalidStateError(_))
+    ));
+    assert!(matches!(
+        input("text", "").step_up(1),
+        Err(DOMException::InvalidStateError(_))
+    ));
+
+    // Checkedness follows the checked attribute until it is set.
+    let mut checkbox = input("checkbox", "");
+    checkbox.set_default_checked(true);
+    assert!(checkbox.checked());
+    assert!(checkbox.matches(":checked"));
+    checkbox.set_checked(false);
+    checkbox.set_default_checked(true);
+    assert!(!checkbox.checked());
+    assert!(checkbox.matches(":default"));
+    assert!(!checkbox.matches(":indeterminate"));
+    checkbox.set_indeterminate(true);
+    assert!(checkbox.indeterminate());
+    assert!(checkbox.matches(":indeterminate"));
+    assert!(!checkbox.checked());
+
+    // Clones keep the value and checkedness.
+    let copy = checkbox.clone_node(false);
+    assert!(!copy.checked());
+    assert!(!copy.indeterminate());
+    let copy = text.clone_node(false);
+    assert_eq!(copy.value(), "12");
+
+    // The last checked radio button of a group that is parsed stays checked.
+    let mut form = HTMLFormElement::try_from(document.create_element("form")).unwrap();
+    form.set_inner_html(
+        "<input type=radio name=r value=a checked><input type=radio name=r value=b checked>\
+        <input type=radio name=other checked>\
+        <input type=file name=upload required>",
+    )
+    .unwrap();
+    let controls = form.children();
+    let control = |index: usize| {
+        HTMLInputElement::try_from(html_element(&controls.item(index).unwrap())).unwrap()
+    };
+    let (mut a, b, other) = (control(0), control(1), control(2));
+    assert!(!a.checked());
+    assert!(b.checked());
+    assert!(other.checked());
+    a.set_checked(true);
+    assert!(!b.checked());
+    assert!(other.checked());
+    assert!(!b.matches(":indeterminate"));
+    a.set_checked(false);
+    assert!(b.matches(":indeterminate"));
+
+    // File inputs have files, and their value is a fake path.
+    let mut upload = control(3);
+    assert!(upload.files().unwrap().is_empty());
+    assert!(!upload.check_validity());
+    upload.set_files(
+        vec![
+            File::new("one", "one.txt", None),
+            File::new("two", "two.txt", None),
+        ]
+        .into(),
+    );
+    assert_eq!(upload.value(), "C:\\fakepath\\one.txt");
+    assert!(upload.check_validity());
+    let entries = FormData::from_form(&form, None).unwrap().get_all("upload");
+    assert!(
+        matches!(entries.as_slice(), [FormDataEntryValue::File(one), FormDataEntryValue::File(two)]
+            if one.name() == "one.txt" && two.text() == "two")
+    );
+    assert!(matches!(
+        upload.set_value("C:\\fakepath\\other.txt"),
+        Err(DOMException::InvalidStateError(_))
+    ));
+    upload.set_value("").unwrap();
+    assert!(upload.files().unwrap().is_empty());
+    assert_eq!(upload.value(), "");
+}
+
+#[test]
+fn image_sources() {
+    use crate::{HTMLImageElement, Viewport, Window};
+
+    let document = Document::new();
+    let mut image = HTMLImageElement::try_from(document.create_element("img")).unwrap();
+    let mut document_ref = document.clone();
+    document_ref.append_child(&mut image).unwrap();
+    let mut window = Window::new(document);
+
+    // URLs can contain commas, like `data:` URLs, and a comma at the end of a URL separates it from the next candidate.
+    image.set_srcset("data:image/png;base64,AAA,BBB 1x, data:image/png;base64,CCC= 2x");
+    assert_eq!(image.current_src(), "data:image/png;base64,AAA,BBB");
+    window.set_viewport(Viewport {
+        device_pixel_ratio: 2.0,
+        ..Default::default()
+    });
+    assert_eq!(image.current_src(), "data:image/png;base64,CCC=");
+    image.set_srcset("a.png, b.png 2x,,c.png 3x");
+    assert_eq!(image.current_src(), "b.png");
+
+    // Candidates with invalid or conflicting descriptors are dropped, but a height can follow a width.
+    image.set_srcset("a.png 2x 3x, b.png 0w, c.png 1600w 900h, d.png 4x");
+    assert_eq!(image.current_src(), "c.png");
+    image.set_srcset("a.png 900h, b.png 4x");
+    assert_eq!(image.current_src(), "b.png");
+}
@@ -0,0 +1,71 @@
+use crate::{
+    character_data::AsCharacterData, domitem::DOMItem, AsChildNode, AsEventTarget, AsNode,
+    EventTarget, Node,
+};
+
+/// A node that contains the textual content of an element or attribute.
+///
+/// MDN Reference: [`Text`](https://developer.mozilla.org/docs/Web/API/Text)
+#[derive(Debug)]
+pub struct Text {
+    pub(crate) node: Node,
+}
+
+impl Text {
+    /// Returns the text of the node and of all its logically adjacent text nodes.
+    ///
+    /// MDN Reference: [`Text.wholeText`](https://developer.mozilla.org/docs/Web/API/Text/wholeText)
+    pub fn whole_text(&self) -> String {
+        let mut start = AsNode::cast(self).clone_ref();
+        while let Some(previous) = start.previous_sibling() {
+            if previous.node_type() != Node::TEXT_NODE {
+                break;
+            }
+            let previous = AsNode::cast(previous).clone_ref();
+            start = previous;
+        }
+        let mut whole_text = start.base().data.clone();
+        let mut current = start;
+        while let Some(next) = current.next_sibling() {
+            if next.node_type() != Node::TEXT_NODE {
+                break;
+            }
+            let next = AsNode::cast(next).clone_ref();
+            whole_text.push_str(&next.base().data);
+            current = next;
+        }
+        whole_text
+    }
+}
+
+impl<T: AsNode> PartialEq<T> for Text {
+    fn eq(&self, other: &T) -> bool {
+        &self.node == AsNode::cast(other)
+    }
+}
+impl AsEventTarget for Text {
+    fn cast(&self) -> &EventTarget {
+        AsEventTarget::cast(&self.node)
+    }
+
+    fn cast_mut(&mut self) -> &mut EventTarget {
+        AsEventTarget::cast_mut(&mut self.node)
+    }
+}
+impl AsNode for Text {
+    fn cast(&self) -> &Node {
+        &self.node
+    }
+
+    fn cast_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn clone_node(&self, deep: bool) -> Self {
+        Text {
+            node: self.node.clone_node(deep),
+        }
+    }
+}
+impl AsChildNode for Text {}
+impl AsCharacterData for Text {}
@@ -1,3 +1,231 @@
+use std::rc::Rc;
+
+use crate::{
+    animation, css::MediaQueryList, document::WeakDocumentRef, scroll, AsElement, AsNode,
+    CSSStyleDeclaration, Document,
+};
+
 pub trait WindowEventHandlers {
     //
 }
+
+/// The media that a document is shown on, which the `screen` and `print` media types match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MediaType {
+    #[default]
+    Screen,
+    Print,
+}
+
+/// The color scheme that the user prefers, which the `prefers-color-scheme` media feature reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// The size and capabilities of the area a document is shown in, which media queries are evaluated against.
+///
+/// The default viewport is an 800×600 screen with a device pixel ratio of 1, a light color scheme and no preference for reduced motion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+    /// The width of the viewport, in CSS pixels.
+    pub width: f64,
+    /// The height of the viewport, in CSS pixels.
+    pub height: f64,
+    /// The number of device pixels in a CSS pixel, which the `resolution` media feature reports.
+    pub device_pixel_ratio: f64,
+    pub color_scheme: ColorScheme,
+    /// Whether the user prefers reduced motion, which the `prefers-reduced-motion` media feature reports.
+    pub reduced_motion: bool,
+    pub media_type: MediaType,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            width: 800.0,
+            height: 600.0,
+            device_pixel_ratio: 1.0,
+            color_scheme: ColorScheme::Light,
+            reduced_motion: false,
+            media_type: MediaType::Screen,
+        }
+    }
+}
+
+/// A window containing a DOM document.
+///
+/// MDN Reference: [`Window`](https://developer.mozilla.org/docs/Web/API/Window)
+#[derive(Debug, Clone)]
+pub struct Window {
+    document: Document,
+}
+
+impl Window {
+    /// Creates a window that shows a document.
+    pub fn new(document: Document) -> Self {
+        // The first styles of the document are the ones that CSS transitions start from.
+        let _ = animation::update_style(&document);
+        Window { document }
+    }
+    /// Returns the document shown in the window.
+    ///
+    /// MDN Reference: [`Window.document`](https://developer.mozilla.org/docs/Web/API/Window/document)
+    pub fn document(&self) -> Document {
+        self.document.clone()
+    }
+    /// Returns the viewport that the window shows its document in.
+    pub fn viewport(&self) -> Viewport {
+        self.document.state().viewport.clone()
+    }
+    /// Changes the viewport of the window, like resizing it or switching to print media.
+    ///
+    /// Style and media queries are evaluated against the new viewport, and the media query lists from [`Window::match_media`] whose results change fire a `change` event.
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        let lists: Vec<_> = {
+            let mut document = self.document.state_mut();
+            document.viewport = viewport.clone();
+            document
+                .media_query_lists
+                .retain(|list| list.strong_count() > 0);
+            document
+                .media_query_lists
+                .iter()
+                .filter_map(|list| list.upgrade())
+                .collect()
+        };
+        crate::layout::invalidate(AsNode::cast(&self.document));
+        for inner in lists {
+            MediaQueryList { inner }.report_change(&viewport);
+        }
+    }
+    /// Returns a list that evaluates a media query list, like `(min-width: 600px)`, against the viewport of the window.
+    ///
+    /// The list stays up to date as the viewport changes, and fires a `change` event whenever its result changes.
+    ///
+    /// MDN Reference: [`Window.matchMedia()`](https://developer.mozilla.org/docs/Web/API/Window/matchMedia)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, ColorScheme, Document, Event, EventType, Viewport, Window};
+    /// use std::{cell::Cell, rc::Rc};
+    ///
+    /// let mut window = Window::new(Document::new());
+    /// let mut wide = window.match_media("screen and (min-width: 600px)");
+    /// let dark = window.match_media("(prefers-color-scheme: dark)");
+    /// assert!(wide.matches());
+    /// assert!(!dark.matches());
+    ///
+    /// let changes = Rc::new(Cell::new(0));
+    /// let counter = changes.clone();
+    /// wide.add_event_listener(
+    ///     EventType::Change,
+    ///     Some((move |_: &mut Event| counter.set(counter.get() + 1)).into()),
+    ///     None,
+    /// );
+    ///
+    /// window.set_viewport(Viewport { width: 375.0, height: 667.0, ..Default::default() });
+    /// assert!(!wide.matches());
+    /// assert_eq!(changes.get(), 1);
+    ///
+    /// window.set_viewport(Viewport {
+    ///     width: 375.0,
+    ///     height: 667.0,
+    ///     color_scheme: ColorScheme::Dark,
+    ///     ..Default::default()
+    /// });
+    /// assert!(dark.matches());
+    /// assert_eq!(changes.get(), 1);
+    /// ```
+    pub fn match_media(&self, query: &str) -> MediaQueryList {
+        let weak_ref = WeakDocumentRef {
+            inner: Rc::downgrade(&self.document.inner),
+        };
+        let viewport = self.document.state().viewport.clone();
+        let list = MediaQueryList::new(query, weak_ref, &viewport);
+        self.document
+            .state_mut()
+            .media_query_lists
+            .push(list.downgrade());
+        list
+    }
+    /// Returns the computed values of all the CSS properties of an element, or of one of its pseudo-elements, like `::before`.
+    ///
+    /// The declaration is read-only, and reflects the current state of the document whenever it is read. Its values come from the cascade of the default styles of HTML elements, the document's style sheets and the element's `style` attribute. An invalid pseudo-element gives an empty declaration.
+    ///
+    /// MDN Reference: [`Window.getComputedStyle()`](https://developer.mozilla.org/docs/Web/API/Window/getComputedStyle)
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, InnerHtml, Window};
+    ///
+    /// let mut document = Document::new();
+    /// let mut html = document.create_element("html");
+    /// html.set_inner_html(
+    ///     "<style>
+    ///         p { color: green; margin: 0 !important; }
+    ///         .note { color: red; font-size: 2em; }
+    ///     </style>
+    ///     <p class=note style='margin: 4px'>Hello</p>",
+    /// )
+    /// .unwrap();
+    /// document.append_child(&mut html).unwrap();
+    ///
+    /// let window = Window::new(document);
+    /// let paragraph = html.children().item(1).unwrap();
+    /// let style = window.get_computed_style(&paragraph, None);
+    ///
+    /// assert_eq!(style.display(), "block");
+    /// assert_eq!(style.color(), "rgb(255, 0, 0)");
+    /// assert_eq!(style.font_size(), "32px");
+    /// assert_eq!(style.margin_top(), "0px");
+    /// assert_eq!(style.margin_bottom(), "0px");
+    /// assert!(style.css_text().is_empty());
+    /// ```
+    pub fn get_computed_style(
+        &self,
+        element: &impl AsElement,
+        pseudo_element: Option<&str>,
+    ) -> CSSStyleDeclaration {
+        CSSStyleDeclaration::computed(AsElement::cast(element), pseudo_element)
+    }
+    /// Returns the time of the window's virtual clock, in milliseconds. The clock starts at zero, and only moves when [`Window::advance_time`] is called, so that everything driven by it is deterministic.
+    ///
+    /// MDN Reference: [`Performance.now()`](https://developer.mozilla.org/docs/Web/API/Performance/now)
+    pub fn now(&self) -> f64 {
+        self.document.state().time
+    }
+    /// Moves the virtual clock forward, and updates everything that happens over time, like smooth scrolls and animations, to the new time.
+    ///
+    /// # Example
+    /// ```
+    /// use dom::{traits::*, Document, ScrollBehavior, ScrollToOptions, Window};
+    ///
+    /// let document = Document::parse_html_unsafe(
+    ///     "<div style='height: 100px; overflow: auto'><div style='height: 500px'></div></div>",
+    /// );
+    /// let html = document.first_element_child().unwrap();
+    /// let body = html.children().item(1).unwrap();
+    /// let mut list = body.first_element_child().unwrap();
+    /// let mut window = Window::new(document.clone());
+    ///
+    /// list.scroll_to(Some(ScrollToOptions {
+    ///     top: Some(200.0),
+    ///     behavior: ScrollBehavior::Smooth,
+    ///     ..Default::default()
+    /// }));
+    /// assert_eq!(list.scroll_top(), 0);
+    /// window.advance_time(150.0);
+    /// assert_eq!(list.scroll_top(), 100);
+    /// window.advance_time(150.0);
+    /// assert_eq!(list.scroll_top(), 200);
+    /// assert_eq!(window.now(), 300.0);
+    /// ```
+    pub fn advance_time(&mut self, milliseconds: f64) {
+        let _ = animation::update_style(&self.document);
+        self.document.state_mut().time += milliseconds.max(0.0);
+        crate::layout::invalidate(AsNode::cast(&self.document));
+        scroll::update_smooth_scrolls(&self.document);
+        animation::update_animations(&self.document);
+    }
+}